@@ -0,0 +1,131 @@
+//! Structured access log
+//!
+//! An [`AccessLog`] writes one JSON object per line for every call a transport
+//! handles, for log pipelines to ingest:
+//!
+//! ```json
+//! {"timestamp_ms":1760000000000,"transport":"mcp","method":"echo.echo","status":"ok","duration_us":812,"client":"10.0.0.7:51234","session_id":"6f1c..."}
+//! ```
+//!
+//! `status` is `ok`, `denied` or `error` (with the error `code`; for REST the
+//! HTTP status). `client` is the peer address and `session_id` the MCP session
+//! or WebSocket connection id, both `null` where the transport has none.
+//!
+//! Unlike the [audit log](crate::audit) the access log is not tamper-evident
+//! and carries no caller identity or params; it is meant for operations, not
+//! compliance.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::AuditStatus;
+
+/// One access log line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessRecord {
+    /// When the call completed (milliseconds since the Unix epoch)
+    pub timestamp_ms: u64,
+    /// Transport the call arrived on (`websocket`, `stdio`, `mcp`, `rest`)
+    pub transport: String,
+    /// Full method or tool name
+    pub method: String,
+    #[serde(flatten)]
+    pub status: AuditStatus,
+    /// Time taken to handle the call, in microseconds
+    pub duration_us: u64,
+    /// Peer address of the connection the call arrived on
+    pub client: Option<String>,
+    /// MCP session id or WebSocket connection id
+    pub session_id: Option<String>,
+}
+
+/// Writes an [`AccessRecord`] per handled call as a JSON line
+///
+/// Each line is written with a single `write` call and flushed. Share one
+/// `Arc<AccessLog>` across transports to get a single stream.
+pub struct AccessLog {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl AccessLog {
+    /// Log to `writer`
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Log to standard error
+    ///
+    /// Use this rather than standard output when serving stdio, where stdout
+    /// carries the protocol.
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr())
+    }
+
+    /// Append to the file at `path`, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open access log {}", path.display()))?;
+        Ok(Self::new(file))
+    }
+
+    /// Write a record
+    pub fn write(&self, record: &AccessRecord) {
+        let mut line = match serde_json::to_vec(record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize access record: {}", e);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writer.write_all(&line).and_then(|_| writer.flush()) {
+            tracing::error!("Failed to write access log: {}", e);
+        }
+    }
+
+    /// Record a handled call
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn record(
+        &self,
+        transport: &str,
+        method: &str,
+        status: AuditStatus,
+        duration: Duration,
+        client: Option<String>,
+        session_id: Option<String>,
+    ) {
+        self.write(&AccessRecord {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            transport: transport.to_string(),
+            method: method.to_string(),
+            status,
+            duration_us: duration.as_micros() as u64,
+            client,
+            session_id,
+        });
+    }
+}
+
+impl std::fmt::Debug for AccessLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessLog").finish_non_exhaustive()
+    }
+}
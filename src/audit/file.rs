@@ -0,0 +1,52 @@
+//! JSON-lines audit file
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{AuditRecord, AuditSink};
+use crate::sink::JsonLinesFile;
+
+/// Appends one JSON [`AuditRecord`] per line to a file
+///
+/// Each record is written with a single `write` call and reaches the OS before
+/// the call returns. Reopening an existing file continues its hash chain.
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: JsonLinesFile,
+    last_hash: Option<String>,
+}
+
+impl FileAuditSink {
+    /// Open (or create) the audit file at `path` for appending
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let last_hash = if path.exists() {
+            JsonLinesFile::read::<AuditRecord>("audit log", path)?
+                .last()
+                .map(|r| r.hash.clone())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            file: JsonLinesFile::open("audit log", path)?,
+            last_hash,
+        })
+    }
+
+    /// Read back every record in the file, e.g. to pass to [`super::verify_chain`]
+    pub fn records(&self) -> Result<Vec<AuditRecord>> {
+        JsonLinesFile::read("audit log", self.file.path())
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn write(&self, record: &AuditRecord) {
+        self.file.append(record);
+    }
+
+    fn last_hash(&self) -> Option<String> {
+        self.last_hash.clone()
+    }
+}
@@ -0,0 +1,259 @@
+//! Audit log of inbound calls
+//!
+//! Every transport can report the calls it dispatches to an [`Auditor`], which
+//! turns them into [`AuditRecord`]s (transport, caller identity, method, params
+//! digest, result status, latency) and hands them to a pluggable [`AuditSink`]:
+//! a JSON-lines file ([`FileAuditSink`]), SQLite (`SqliteAuditSink`, feature
+//! `sqlite-audit`), or any closure.
+//!
+//! Records are tamper-evident: each carries an HMAC-SHA256 of its own contents
+//! and the previous record's hash, keyed with a secret given to the
+//! [`Auditor`]. Without the key, editing, dropping, or reordering records
+//! breaks [`verify_chain`], and so does cutting records off the head of the
+//! log. Records cut off its tail leave a valid chain: compare the last hash
+//! with [`Auditor::last_hash`], or a copy kept outside the log, to catch that.
+//! Share one `Arc<Auditor>` across transports to keep a single chain.
+//!
+//! Requests never wait on the sink: records are queued to a writer thread,
+//! which hands them to the sink in chain order. When the queue is full, new
+//! records are dropped with an error log, which shows up as a gap in the chain.
+
+pub mod file;
+#[cfg(feature = "sqlite-audit")]
+pub mod sqlite;
+
+pub use file::FileAuditSink;
+#[cfg(feature = "sqlite-audit")]
+pub use sqlite::SqliteAuditSink;
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::auth::policy::FORBIDDEN_ERROR_CODE;
+use crate::auth::CallerIdentity;
+use crate::sink::Writer;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Records waiting for the writer thread before new ones are dropped
+pub const AUDIT_QUEUE_CAPACITY: usize = 4096;
+
+/// Outcome of an audited call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum AuditStatus {
+    /// The call succeeded
+    Ok,
+    /// The authorization policy denied the call
+    Denied,
+    /// The call failed with a JSON-RPC / MCP error code
+    Error { code: i32 },
+}
+
+impl AuditStatus {
+    /// Status for a JSON-RPC error code; `FORBIDDEN_ERROR_CODE` maps to `Denied`
+    pub fn from_error_code(code: i32) -> Self {
+        if code == FORBIDDEN_ERROR_CODE {
+            Self::Denied
+        } else {
+            Self::Error { code }
+        }
+    }
+
+    /// Status for a raw JSON-RPC response object
+    pub fn from_response(response: &serde_json::Value) -> Self {
+        match response.get("error") {
+            Some(error) => Self::from_error_code(
+                error.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32,
+            ),
+            None => Self::Ok,
+        }
+    }
+}
+
+/// One audited call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the call completed (milliseconds since the Unix epoch)
+    pub timestamp_ms: u64,
+    /// Transport the call arrived on (`websocket`, `stdio`, `mcp`, `rest`)
+    pub transport: String,
+    /// Caller, e.g. `api_key:reader`, `jwt:alice`, `local`, `anonymous`
+    pub identity: String,
+    /// Full method or tool name
+    pub method: String,
+    /// SHA-256 of the raw params JSON (hex); params themselves are not stored
+    pub params_digest: String,
+    #[serde(flatten)]
+    pub status: AuditStatus,
+    /// Time taken to handle the call, in microseconds
+    pub latency_us: u64,
+    /// Hash of the previous record in the chain (empty for the first record)
+    pub prev_hash: String,
+    /// HMAC-SHA256 over `prev_hash` and this record's other fields (hex)
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Recompute this record's hash from its contents and the chain's key
+    pub fn compute_hash(&self, key: &[u8]) -> String {
+        let status = serde_json::to_string(&self.status).unwrap_or_default();
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        for field in [
+            self.prev_hash.as_str(),
+            &self.timestamp_ms.to_string(),
+            &self.transport,
+            &self.identity,
+            &self.method,
+            &self.params_digest,
+            &status,
+            &self.latency_us.to_string(),
+        ] {
+            // Length-prefix each field so boundaries cannot be shifted
+            mac.update(&(field.len() as u64).to_be_bytes());
+            mac.update(field.as_bytes());
+        }
+        hex(&mac.finalize().into_bytes())
+    }
+}
+
+/// Check that `records` form an unbroken chain from the start of the log
+///
+/// The first record must start the chain (an empty `prev_hash`), every
+/// record's hash must match its contents under `key`, and every `prev_hash`
+/// must equal the hash of the record before it.
+pub fn verify_chain<'a>(records: impl IntoIterator<Item = &'a AuditRecord>, key: &[u8]) -> bool {
+    verify_chain_from(records, key, "")
+}
+
+/// Check that `records` continue the chain after the record hashed `prev_hash`
+///
+/// Verifies part of a log, e.g. the records written since a hash kept
+/// elsewhere.
+pub fn verify_chain_from<'a>(
+    records: impl IntoIterator<Item = &'a AuditRecord>,
+    key: &[u8],
+    prev_hash: &'a str,
+) -> bool {
+    let mut prev = prev_hash;
+    for record in records {
+        if record.prev_hash != prev || record.hash != record.compute_hash(key) {
+            return false;
+        }
+        prev = &record.hash;
+    }
+    true
+}
+
+/// Destination for audit records
+///
+/// Implemented for any `Fn(&AuditRecord)`, so custom sinks (log shipping,
+/// message queues) can be written as closures. Sinks are called one record
+/// at a time on the auditor's writer thread, so they may block, but a slow
+/// sink fills the queue and loses records.
+pub trait AuditSink: Send + Sync {
+    /// Persist a record; failures should be logged, not propagated
+    fn write(&self, record: &AuditRecord);
+
+    /// Hash of the last record already stored, so a restarted process
+    /// continues the existing chain
+    fn last_hash(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&AuditRecord) + Send + Sync,
+{
+    fn write(&self, record: &AuditRecord) {
+        self(record)
+    }
+}
+
+/// Builds chained [`AuditRecord`]s and queues them for a sink
+pub struct Auditor {
+    /// Secret the record hashes are keyed with
+    key: Vec<u8>,
+    /// Hash of the most recent record; the lock also serialises queueing so
+    /// the sink sees records in chain order
+    last_hash: Mutex<String>,
+    writer: Writer<AuditRecord>,
+}
+
+impl Auditor {
+    /// Audit to `sink`, keying the hash chain with `key`
+    ///
+    /// Keep the key away from whoever can write the log (e.g. load it from a
+    /// secret store): anyone holding it can forge a consistent chain.
+    pub fn new(sink: impl AuditSink + 'static, key: impl Into<Vec<u8>>) -> Self {
+        let last_hash = sink.last_hash().unwrap_or_default();
+        Self {
+            key: key.into(),
+            last_hash: Mutex::new(last_hash),
+            writer: Writer::spawn("plexus-audit", AUDIT_QUEUE_CAPACITY, move |record| sink.write(&record)),
+        }
+    }
+
+    /// Hash of the most recent record, empty before the first
+    pub fn last_hash(&self) -> String {
+        self.last_hash.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Record a completed call
+    ///
+    /// `params` is the raw params JSON, if any; only its digest is kept.
+    pub fn record(
+        &self,
+        transport: &str,
+        identity: &CallerIdentity,
+        method: &str,
+        params: Option<&str>,
+        status: AuditStatus,
+        latency: Duration,
+    ) {
+        let mut last_hash = self.last_hash.lock().unwrap_or_else(|e| e.into_inner());
+        let mut record = AuditRecord {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            transport: transport.to_string(),
+            identity: identity.label(),
+            method: method.to_string(),
+            params_digest: hex(&Sha256::digest(params.unwrap_or("").as_bytes())),
+            status,
+            latency_us: latency.as_micros() as u64,
+            prev_hash: last_hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash(&self.key);
+        *last_hash = record.hash.clone();
+
+        if let Err(reason) = self.writer.send(record) {
+            tracing::error!("Audit {}; record for {} dropped", reason, method);
+        }
+    }
+
+    /// Wait until every record queued so far has been handed to the sink
+    ///
+    /// Blocks the calling thread; in async code, call it from
+    /// `tokio::task::spawn_blocking`.
+    pub fn flush(&self) {
+        self.writer.flush();
+    }
+}
+
+impl std::fmt::Debug for Auditor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Auditor").finish_non_exhaustive()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
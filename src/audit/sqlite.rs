@@ -0,0 +1,98 @@
+//! SQLite audit table
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::SqlitePool;
+
+use super::{AuditRecord, AuditSink, AUDIT_QUEUE_CAPACITY};
+use crate::sink::sqlite::{connect, SqliteWriter};
+
+/// Stores [`AuditRecord`]s in an `audit_log` SQLite table
+///
+/// Records are inserted in order by a background task. The auditor's writer
+/// thread waits while the task is [`AUDIT_QUEUE_CAPACITY`] records behind, so
+/// a slow database fills the auditor's own queue instead of memory.
+#[derive(Debug)]
+pub struct SqliteAuditSink {
+    writer: SqliteWriter<AuditRecord>,
+    last_hash: Option<String>,
+}
+
+impl SqliteAuditSink {
+    /// Open (or create) the database at `path` and start the writer task
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let pool = connect(
+            "audit",
+            path.as_ref(),
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp_ms INTEGER NOT NULL,
+                transport TEXT NOT NULL,
+                identity TEXT NOT NULL,
+                method TEXT NOT NULL,
+                params_digest TEXT NOT NULL,
+                status TEXT NOT NULL,
+                error_code INTEGER,
+                latency_us INTEGER NOT NULL,
+                prev_hash TEXT NOT NULL,
+                hash TEXT NOT NULL
+            );
+            "#,
+        )
+        .await?;
+
+        let last_hash = sqlx::query_scalar::<_, String>(
+            "SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&pool)
+        .await
+        .context("Failed to read last audit record")?;
+
+        let writer = SqliteWriter::spawn("plexus.audit.writer", AUDIT_QUEUE_CAPACITY, move |record| {
+            let pool = pool.clone();
+            async move { insert(&pool, &record).await }
+        });
+
+        Ok(Self { writer, last_hash })
+    }
+}
+
+async fn insert(pool: &SqlitePool, record: &AuditRecord) -> Result<(), sqlx::Error> {
+    let (status, error_code) = match record.status {
+        super::AuditStatus::Ok => ("ok", None),
+        super::AuditStatus::Denied => ("denied", None),
+        super::AuditStatus::Error { code } => ("error", Some(code)),
+    };
+    sqlx::query(
+        "INSERT INTO audit_log (timestamp_ms, transport, identity, method, params_digest, \
+         status, error_code, latency_us, prev_hash, hash) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(record.timestamp_ms as i64)
+    .bind(&record.transport)
+    .bind(&record.identity)
+    .bind(&record.method)
+    .bind(&record.params_digest)
+    .bind(status)
+    .bind(error_code)
+    .bind(record.latency_us as i64)
+    .bind(&record.prev_hash)
+    .bind(&record.hash)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+impl AuditSink for SqliteAuditSink {
+    /// Called on the auditor's writer thread; must not be called from async code
+    fn write(&self, record: &AuditRecord) {
+        if !self.writer.send(record.clone()) {
+            tracing::error!("Audit writer task stopped; record for {} dropped", record.method);
+        }
+    }
+
+    fn last_hash(&self) -> Option<String> {
+        self.last_hash.clone()
+    }
+}
@@ -0,0 +1,254 @@
+//! JWT bearer token validation (feature `jwt-auth`)
+//!
+//! Tokens are verified either against a static HMAC secret or against the keys
+//! published at a remote JWKS URL. The JWKS document is cached and refetched
+//! when it goes stale or when a token references an unknown `kid` (key rotation),
+//! with a minimum interval between fetches so bogus tokens can't hammer the
+//! identity provider. Fetches are time-limited and one at a time: requests that
+//! miss the cache together wait for a single fetch.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
+
+/// Default JWKS cache lifetime
+pub const DEFAULT_JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default time limit for fetching the JWKS document
+pub const DEFAULT_JWKS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Minimum time between JWKS fetches triggered by unknown key IDs
+const JWKS_MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Time limit for connecting to the JWKS endpoint
+const JWKS_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Claims of a validated JWT, attached to the request extensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct JwtClaims(pub serde_json::Value);
+
+impl JwtClaims {
+    /// The `sub` (subject) claim, if present
+    pub fn subject(&self) -> Option<&str> {
+        self.0.get("sub").and_then(|v| v.as_str())
+    }
+
+    /// Look up an arbitrary claim by name
+    pub fn get(&self, claim: &str) -> Option<&serde_json::Value> {
+        self.0.get(claim)
+    }
+}
+
+/// Error types for JWT validation
+#[derive(Debug, Error)]
+pub enum JwtError {
+    #[error("Invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("Token has no key id (kid) and JWKS validation requires one")]
+    MissingKeyId,
+    #[error("Unknown signing key: {0}")]
+    UnknownKey(String),
+    #[error("Failed to fetch JWKS: {0}")]
+    JwksFetch(String),
+}
+
+/// Where verification keys come from
+enum KeySource {
+    Secret(DecodingKey),
+    Jwks {
+        url: String,
+        client: reqwest::Client,
+        cache: RwLock<Option<CachedJwks>>,
+        /// Held while fetching, so concurrent cache misses fetch once
+        refresh: Mutex<()>,
+    },
+}
+
+impl KeySource {
+    fn jwks(url: String, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(JWKS_CONNECT_TIMEOUT.min(timeout))
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build the JWKS HTTP client");
+        KeySource::Jwks {
+            url,
+            client,
+            cache: RwLock::new(None),
+            refresh: Mutex::new(()),
+        }
+    }
+}
+
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// JWT validator shared by the network transports
+#[derive(Clone)]
+pub struct JwtAuth {
+    source: Arc<KeySource>,
+    validation: Validation,
+    jwks_refresh_interval: Duration,
+}
+
+impl JwtAuth {
+    /// Validate HS256 tokens signed with a static shared secret
+    pub fn with_secret(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            source: Arc::new(KeySource::Secret(DecodingKey::from_secret(secret.as_ref()))),
+            validation: Validation::new(Algorithm::HS256),
+            jwks_refresh_interval: DEFAULT_JWKS_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Validate RS256 tokens against the keys published at a JWKS URL
+    pub fn with_jwks(url: impl Into<String>) -> Self {
+        Self {
+            source: Arc::new(KeySource::jwks(url.into(), DEFAULT_JWKS_TIMEOUT)),
+            validation: Validation::new(Algorithm::RS256),
+            jwks_refresh_interval: DEFAULT_JWKS_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Require the `iss` claim to match
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.validation.set_issuer(&[issuer.into()]);
+        self
+    }
+
+    /// Require the `aud` claim to contain the given audience
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.validation.set_audience(&[audience.into()]);
+        self
+    }
+
+    /// Override the accepted signing algorithms
+    pub fn with_algorithms(mut self, algorithms: Vec<Algorithm>) -> Self {
+        self.validation.algorithms = algorithms;
+        self
+    }
+
+    /// Override how long a fetched JWKS document is trusted before refetching
+    ///
+    /// Has no effect on secret-based validators.
+    pub fn with_jwks_refresh_interval(mut self, interval: Duration) -> Self {
+        self.jwks_refresh_interval = interval;
+        self
+    }
+
+    /// Override how long a JWKS fetch may take before the token is rejected
+    ///
+    /// Has no effect on secret-based validators.
+    pub fn with_jwks_timeout(mut self, timeout: Duration) -> Self {
+        if let KeySource::Jwks { url, .. } = self.source.as_ref() {
+            self.source = Arc::new(KeySource::jwks(url.clone(), timeout));
+        }
+        self
+    }
+
+    /// Validate a raw token (without the `Bearer ` prefix) and return its claims
+    pub async fn validate(&self, token: &str) -> Result<JwtClaims, JwtError> {
+        let key = match self.source.as_ref() {
+            KeySource::Secret(key) => key.clone(),
+            KeySource::Jwks { .. } => {
+                let header = jsonwebtoken::decode_header(token)?;
+                let kid = header.kid.ok_or(JwtError::MissingKeyId)?;
+                self.jwks_key(&kid).await?
+            }
+        };
+
+        let data = jsonwebtoken::decode::<serde_json::Value>(token, &key, &self.validation)?;
+        Ok(JwtClaims(data.claims))
+    }
+
+    /// Authenticate a request by its `Authorization: Bearer` header
+    pub async fn authenticate(&self, headers: &http::HeaderMap) -> Option<JwtClaims> {
+        let token = headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))?;
+
+        match self.validate(token).await {
+            Ok(claims) => Some(claims),
+            Err(e) => {
+                tracing::debug!("JWT rejected: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Resolve a decoding key by id, refreshing the JWKS cache when needed
+    async fn jwks_key(&self, kid: &str) -> Result<DecodingKey, JwtError> {
+        let KeySource::Jwks { url, client, cache, refresh } = self.source.as_ref()
+        else {
+            unreachable!("jwks_key is only called for JWKS sources");
+        };
+
+        // Fast path: fresh cache containing the key
+        if let Some(key) = self.cached_key(cache, kid).await {
+            return key;
+        }
+
+        // Slow path: refetch (stale cache or unknown kid after rotation), unless
+        // the fetch we waited for already brought the answer
+        let _refresh = refresh.lock().await;
+        if let Some(key) = self.cached_key(cache, kid).await {
+            return key;
+        }
+        let keys: JwkSet = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| JwtError::JwksFetch(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| JwtError::JwksFetch(e.to_string()))?;
+        tracing::debug!(url = %url, keys = keys.keys.len(), "Fetched JWKS");
+
+        let key = keys
+            .find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()?
+            .ok_or_else(|| JwtError::UnknownKey(kid.to_string()));
+
+        *cache.write().await = Some(CachedJwks {
+            keys,
+            fetched_at: Instant::now(),
+        });
+
+        key
+    }
+
+    /// The cached answer for `kid`, or `None` when the JWKS must be refetched
+    async fn cached_key(
+        &self,
+        cache: &RwLock<Option<CachedJwks>>,
+        kid: &str,
+    ) -> Option<Result<DecodingKey, JwtError>> {
+        let cached = cache.read().await;
+        let c = cached.as_ref()?;
+        let fresh = c.fetched_at.elapsed() < self.jwks_refresh_interval;
+        let rate_limited = c.fetched_at.elapsed() < JWKS_MIN_REFETCH_INTERVAL;
+        match c.keys.find(kid) {
+            Some(jwk) if fresh => Some(DecodingKey::from_jwk(jwk).map_err(JwtError::from)),
+            None if rate_limited => Some(Err(JwtError::UnknownKey(kid.to_string()))),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for JwtAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.source.as_ref() {
+            KeySource::Secret(_) => f.debug_struct("JwtAuth").field("source", &"secret").finish(),
+            KeySource::Jwks { url, .. } => f.debug_struct("JwtAuth").field("jwks", url).finish(),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Authentication shared by the network transports
+//!
+//! An [`ApiKeyAuth`] validates the key a client presents in either
+//! `Authorization: Bearer <key>` or `X-Api-Key: <key>` against a configured key
+//! set or a user-supplied async validator. On success the caller's
+//! [`ApiKeyIdentity`] is inserted into the HTTP request extensions so later
+//! layers (and RPC methods, via jsonrpsee/rmcp extensions) can see who called.
+//!
+//! With the `jwt-auth` feature, [`jwt::JwtAuth`] validates bearer JWTs and
+//! attaches the token's [`jwt::JwtClaims`] the same way.
+//!
+//! Once a caller is authenticated, an optional [`policy::AuthorizationPolicy`]
+//! decides which methods it may invoke.
+
+#[cfg(feature = "jwt-auth")]
+pub mod jwt;
+pub mod policy;
+
+pub use policy::{AuthorizationPolicy, CallerIdentity, MethodAllowList};
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+/// Header carrying an API key as an alternative to `Authorization: Bearer`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Async validator callback: receives the presented key and returns the caller's
+/// identity, or `None` to reject the request.
+pub type ApiKeyValidatorFn = Arc<
+    dyn Fn(String) -> Pin<Box<dyn Future<Output = Option<ApiKeyIdentity>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Identity of a caller authenticated by an API key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiKeyIdentity(pub String);
+
+/// API key authentication policy
+#[derive(Clone)]
+pub enum ApiKeyAuth {
+    /// Static set of accepted keys, mapped to the identity each one authenticates as
+    ///
+    /// Keys are stored as their SHA-256 digest (see [`ApiKeyAuth::digest`]) and a
+    /// presented key is hashed before the lookup, so how long the lookup takes
+    /// says nothing about how much of a configured key was guessed.
+    Keys(Arc<HashMap<[u8; 32], ApiKeyIdentity>>),
+    /// User-supplied async validator
+    Validator(ApiKeyValidatorFn),
+}
+
+impl ApiKeyAuth {
+    /// Accept any key in `keys`; every key authenticates as the identity `"api-key"`
+    pub fn keys<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        Self::named_keys(keys.into_iter().map(|k| ("api-key".to_string(), k.into())))
+    }
+
+    /// Accept any key in `keys`, given as `(identity, key)` pairs
+    pub fn named_keys<I, N, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = (N, K)>,
+        N: Into<String>,
+        K: Into<String>,
+    {
+        let map = keys
+            .into_iter()
+            .map(|(name, key)| (Self::digest(&Into::<String>::into(key)), ApiKeyIdentity(name.into())))
+            .collect();
+        Self::Keys(Arc::new(map))
+    }
+
+    /// SHA-256 digest of a key, as stored in [`ApiKeyAuth::Keys`]
+    pub fn digest(key: &str) -> [u8; 32] {
+        Sha256::digest(key.as_bytes()).into()
+    }
+
+    /// Validate keys with an async callback
+    pub fn validator<F, Fut>(validator: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<ApiKeyIdentity>> + Send + 'static,
+    {
+        Self::Validator(Arc::new(move |key| Box::pin(validator(key))))
+    }
+
+    /// Extract the presented key from `Authorization: Bearer` or `X-Api-Key`
+    pub fn extract_key(headers: &http::HeaderMap) -> Option<&str> {
+        let bearer = headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+
+        bearer.or_else(|| {
+            headers
+                .get(API_KEY_HEADER)
+                .and_then(|v| v.to_str().ok())
+        })
+    }
+
+    /// Authenticate a request by its headers, returning the caller's identity
+    pub async fn authenticate(&self, headers: &http::HeaderMap) -> Option<ApiKeyIdentity> {
+        let key = Self::extract_key(headers)?;
+        match self {
+            Self::Keys(keys) => keys.get(&Self::digest(key)).cloned(),
+            Self::Validator(validator) => validator(key.to_string()).await,
+        }
+    }
+}
+
+impl std::fmt::Debug for ApiKeyAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the keys themselves
+        match self {
+            Self::Keys(keys) => f.debug_tuple("Keys").field(&keys.len()).finish(),
+            Self::Validator(_) => f.debug_tuple("Validator").finish(),
+        }
+    }
+}
+
+/// Authentication methods enabled on a transport
+///
+/// A request is accepted if any configured method accepts it; when nothing is
+/// configured every request passes through.
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TransportAuth {
+    pub(crate) api_key: Option<ApiKeyAuth>,
+    #[cfg(feature = "jwt-auth")]
+    pub(crate) jwt: Option<jwt::JwtAuth>,
+}
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+impl TransportAuth {
+    /// Whether any authentication method is configured
+    pub(crate) fn is_enabled(&self) -> bool {
+        #[cfg(feature = "jwt-auth")]
+        if self.jwt.is_some() {
+            return true;
+        }
+        self.api_key.is_some()
+    }
+
+    /// Authenticate a request, attaching the caller's identity/claims to its
+    /// extensions. Returns `false` if authentication is enabled and no method
+    /// accepted the request.
+    pub(crate) async fn authenticate<B>(&self, request: &mut http::Request<B>) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        if let Some(ref api_key) = self.api_key {
+            if let Some(identity) = api_key.authenticate(request.headers()).await {
+                tracing::debug!("API key resolved for identity: {}", identity.0);
+                request.extensions_mut().insert(identity);
+                return true;
+            }
+        }
+
+        #[cfg(feature = "jwt-auth")]
+        if let Some(ref jwt) = self.jwt {
+            if let Some(claims) = jwt.authenticate(request.headers()).await {
+                tracing::debug!("JWT resolved for subject: {:?}", claims.subject());
+                request.extensions_mut().insert(claims);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// HTTP 401 response with a Bearer challenge, in the body type of the caller's choosing
+#[cfg(feature = "websocket")]
+pub(crate) fn unauthorized_response<B: From<&'static str>>() -> http::Response<B> {
+    http::Response::builder()
+        .status(http::StatusCode::UNAUTHORIZED)
+        .header(http::header::WWW_AUTHENTICATE, "Bearer realm=\"plexus\"")
+        .header(http::header::CONTENT_TYPE, "text/plain")
+        .body(B::from("Unauthorized"))
+        .expect("static response is valid")
+}
@@ -0,0 +1,223 @@
+//! Per-method authorization
+//!
+//! Authentication (API keys, JWTs, sessions, client certificates) decides *who*
+//! is calling; an [`AuthorizationPolicy`] decides *what* they may call. Every
+//! transport resolves the caller's [`CallerIdentity`] from the request
+//! extensions and consults the policy before dispatching a method, so e.g. a
+//! read-only API key can be limited to `query.*` while `admin.*` stays reserved
+//! for operators.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use plexus_core::plexus::AuthContext;
+
+use super::ApiKeyIdentity;
+
+#[cfg(feature = "jwt-auth")]
+use super::jwt::JwtClaims;
+
+/// JSON-RPC error code returned for calls denied by the authorization policy
+///
+/// WebSocket, stdio and MCP answer denied calls with it (an MCP protocol
+/// error rather than `invalid_request`, so clients can tell a denial from a
+/// malformed call); REST answers HTTP 403.
+pub const FORBIDDEN_ERROR_CODE: i32 = -32003;
+
+/// Who is making a call, as established by the transport's authentication layer
+#[derive(Debug, Clone)]
+pub enum CallerIdentity {
+    /// No credentials were presented (authentication disabled or optional)
+    Anonymous,
+    /// The local process on the other end of a stdio / IO pipe
+    Local,
+    /// Authenticated by API key
+    ApiKey(ApiKeyIdentity),
+    /// Authenticated by bearer JWT
+    #[cfg(feature = "jwt-auth")]
+    Jwt(JwtClaims),
+    /// Authenticated by a mutual-TLS client certificate (subject DN)
+    #[cfg(feature = "tls")]
+    Certificate(String),
+    /// Authenticated by a session cookie
+    Session(Arc<AuthContext>),
+}
+
+impl CallerIdentity {
+    /// Resolve the caller from request extensions populated by the auth layers
+    ///
+    /// Explicit credentials win over ambient ones: API key, then JWT, then
+    /// client certificate, then session cookie.
+    pub fn from_extensions(extensions: &http::Extensions) -> Self {
+        if let Some(identity) = extensions.get::<ApiKeyIdentity>() {
+            return Self::ApiKey(identity.clone());
+        }
+        #[cfg(feature = "jwt-auth")]
+        if let Some(claims) = extensions.get::<JwtClaims>() {
+            return Self::Jwt(claims.clone());
+        }
+        #[cfg(feature = "tls")]
+        if let Some(cert) = extensions.get::<crate::tls::PeerCertificate>() {
+            return Self::Certificate(cert.subject.clone());
+        }
+        if let Some(ctx) = extensions.get::<Arc<AuthContext>>() {
+            return Self::Session(ctx.clone());
+        }
+        Self::Anonymous
+    }
+
+    /// Stable name of the caller, if authenticated
+    ///
+    /// The API key identity, JWT `sub`, certificate subject, or session user id.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Anonymous | Self::Local => None,
+            Self::ApiKey(identity) => Some(identity.0.as_str()),
+            #[cfg(feature = "jwt-auth")]
+            Self::Jwt(claims) => claims.subject(),
+            #[cfg(feature = "tls")]
+            Self::Certificate(subject) => Some(subject.as_str()),
+            Self::Session(ctx) => Some(ctx.user_id.as_str()),
+        }
+    }
+
+    /// The caller as `kind:name`, e.g. `api_key:reader` or `jwt:alice`, or
+    /// `anonymous` / `local`
+    ///
+    /// Unlike [`name`](Self::name), labels of different kinds of identity
+    /// never collide: a JWT whose `sub` is `admin` is `jwt:admin`, not the
+    /// API key `api_key:admin`.
+    pub fn label(&self) -> String {
+        let kind = match self {
+            Self::Anonymous => return "anonymous".to_string(),
+            Self::Local => return "local".to_string(),
+            Self::ApiKey(_) => "api_key",
+            #[cfg(feature = "jwt-auth")]
+            Self::Jwt(_) => "jwt",
+            #[cfg(feature = "tls")]
+            Self::Certificate(_) => "cert",
+            Self::Session(_) => "session",
+        };
+        format!("{}:{}", kind, self.name().unwrap_or_default())
+    }
+}
+
+/// Kinds of identity a grant can name, as in [`CallerIdentity::label`]
+const IDENTITY_KINDS: &[&str] = &["api_key:", "jwt:", "cert:", "session:"];
+
+/// Decides whether a caller may invoke a method
+///
+/// Implemented for any `Fn(&CallerIdentity, &str, &str) -> bool`, so simple
+/// policies can be written as closures.
+pub trait AuthorizationPolicy: Send + Sync {
+    /// Return `true` to allow `identity` to call `namespace.method`
+    fn allow(&self, identity: &CallerIdentity, namespace: &str, method: &str) -> bool;
+
+    /// Check a full `namespace.method` name
+    fn allow_method(&self, identity: &CallerIdentity, full_method: &str) -> bool {
+        let (namespace, method) = split_method(full_method);
+        self.allow(identity, namespace, method)
+    }
+}
+
+impl<F> AuthorizationPolicy for F
+where
+    F: Fn(&CallerIdentity, &str, &str) -> bool + Send + Sync,
+{
+    fn allow(&self, identity: &CallerIdentity, namespace: &str, method: &str) -> bool {
+        self(identity, namespace, method)
+    }
+}
+
+impl std::fmt::Debug for dyn AuthorizationPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuthorizationPolicy")
+    }
+}
+
+/// Error message for a denied call
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) fn forbidden_message(full_method: &str) -> String {
+    format!("Not authorized to call {}", full_method)
+}
+
+/// Split `namespace.method` at the first dot; names without one have an empty namespace
+pub fn split_method(full_method: &str) -> (&str, &str) {
+    full_method.split_once('.').unwrap_or(("", full_method))
+}
+
+/// Allow-list policy mapping identities to method patterns
+///
+/// Identities are labelled by kind, as in [`CallerIdentity::label`]
+/// (`api_key:reader`, `jwt:alice`, `cert:CN=ops`, `session:42`), so a grant
+/// for one kind of identity never applies to another that happens to share
+/// its name. Patterns are `namespace.method`, `namespace.*`, or `*`. Callers
+/// without a name (anonymous and local) and identities without an explicit
+/// grant fall back to the patterns set with [`MethodAllowList::with_fallback`],
+/// which default to none.
+#[derive(Debug, Clone, Default)]
+pub struct MethodAllowList {
+    grants: HashMap<String, Vec<String>>,
+    fallback: Vec<String>,
+}
+
+impl MethodAllowList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow `identity` to call methods matching `patterns`
+    ///
+    /// `identity` is a label such as `jwt:alice`; a bare name without one of
+    /// the `api_key:`, `jwt:`, `cert:` or `session:` prefixes names an API key
+    /// identity.
+    pub fn grant<I, P>(mut self, identity: impl Into<String>, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        let identity = identity.into();
+        let label = if IDENTITY_KINDS.iter().any(|kind| identity.starts_with(kind)) {
+            identity
+        } else {
+            format!("api_key:{}", identity)
+        };
+        self.grants
+            .entry(label)
+            .or_default()
+            .extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Patterns allowed for callers without an explicit grant
+    pub fn with_fallback<I, P>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.fallback = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn matches(pattern: &str, namespace: &str, method: &str) -> bool {
+        if pattern == "*" {
+            return true;
+        }
+        match split_method(pattern) {
+            (ns, "*") => ns == namespace,
+            (ns, m) => ns == namespace && m == method,
+        }
+    }
+}
+
+impl AuthorizationPolicy for MethodAllowList {
+    fn allow(&self, identity: &CallerIdentity, namespace: &str, method: &str) -> bool {
+        let patterns = identity
+            .name()
+            .and_then(|_| self.grants.get(&identity.label()))
+            .unwrap_or(&self.fallback);
+        patterns
+            .iter()
+            .any(|pattern| Self::matches(pattern, namespace, method))
+    }
+}
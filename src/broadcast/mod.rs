@@ -0,0 +1,139 @@
+//! Server-wide notifications
+//!
+//! Clients normally hear from the server only about what they asked for:
+//! responses, and notifications of subscriptions they opened. A
+//! [`Broadcaster`] pushes a JSON-RPC notification to every connected
+//! WebSocket client, and to every initialized MCP session, without a
+//! subscription, e.g. to announce a shutdown or a plugin reload:
+//!
+//! ```rust,ignore
+//! let broadcaster = Broadcaster::new();
+//! let server = TransportServer::builder(activation, rpc_converter)
+//!     .with_websocket(4444)
+//!     .with_broadcaster(broadcaster.clone())
+//!     .build().await?;
+//!
+//! // Later:
+//! broadcaster.send("hub.reloading", json!({ "plugins": ["search"] }));
+//! ```
+//!
+//! WebSocket clients receive `{"jsonrpc":"2.0","method":...,"params":...}`,
+//! MCP clients (over HTTP or stdio) a notification with the same method and
+//! params. Plain HTTP callers and JSON-RPC stdio clients are not reached. A
+//! connection that falls more than [`BROADCAST_CAPACITY`] broadcasts behind
+//! loses the oldest ones.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+#[cfg(feature = "websocket")]
+pub(crate) mod websocket;
+
+/// Broadcasts a connection may fall behind by before it loses the oldest
+pub const BROADCAST_CAPACITY: usize = 64;
+
+/// One notification for every client
+#[derive(Debug)]
+pub(crate) struct Broadcast {
+    #[cfg(feature = "mcp-http")]
+    pub(crate) method: String,
+    #[cfg(feature = "mcp-http")]
+    pub(crate) params: Value,
+    /// The JSON-RPC notification, serialized once for every connection
+    #[cfg(feature = "websocket")]
+    pub(crate) json: String,
+}
+
+/// Pushes notifications to every connected client
+///
+/// Cloning is cheap; every clone reaches the same clients.
+#[derive(Clone)]
+pub struct Broadcaster(Arc<broadcast::Sender<Arc<Broadcast>>>);
+
+impl Default for Broadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Broadcaster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broadcaster")
+            .field("clients", &self.0.receiver_count())
+            .finish()
+    }
+}
+
+impl Broadcaster {
+    pub fn new() -> Self {
+        Self(Arc::new(broadcast::channel(BROADCAST_CAPACITY).0))
+    }
+
+    /// Send the notification `method` with `params` to every client
+    ///
+    /// Returns the number of connections and sessions it was queued for.
+    pub fn send(&self, method: impl Into<String>, params: Value) -> usize {
+        let method = method.into();
+        // Only the transports that are compiled in ever receive broadcasts
+        #[cfg(not(any(feature = "websocket", feature = "mcp-http")))]
+        let _ = (method, params);
+        let broadcast = Broadcast {
+            #[cfg(feature = "websocket")]
+            json: serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string(),
+            #[cfg(feature = "mcp-http")]
+            method,
+            #[cfg(feature = "mcp-http")]
+            params,
+        };
+        self.0.send(Arc::new(broadcast)).unwrap_or(0)
+    }
+
+    #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Arc<Broadcast>> {
+        self.0.subscribe()
+    }
+
+    /// Forward broadcasts to the MCP session of `peer` until it ends
+    ///
+    /// The session's logging state lives exactly as long as the session; the
+    /// task stops, and releases `peer`, as soon as it is dropped.
+    #[cfg(feature = "mcp-http")]
+    pub(crate) fn watch(
+        &self,
+        peer: rmcp::service::Peer<rmcp::service::RoleServer>,
+        session: &Arc<crate::mcp::logging::SessionLogging>,
+    ) {
+        use rmcp::model::{CustomNotification, ServerNotification};
+
+        let mut broadcasts = self.subscribe();
+        let closed = session.closed();
+        crate::task::spawn("plexus.mcp.broadcast", async move {
+            tokio::pin!(closed);
+            loop {
+                let received = tokio::select! {
+                    _ = &mut closed => break,
+                    received = broadcasts.recv() => received,
+                };
+                let broadcast = match received {
+                    Ok(broadcast) => broadcast,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::debug!("MCP session missed {} broadcasts", missed);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let notification = CustomNotification::new(broadcast.method.clone(), Some(broadcast.params.clone()));
+                if let Err(e) = peer
+                    .send_notification(ServerNotification::CustomNotification(notification))
+                    .await
+                {
+                    tracing::debug!("Failed to send MCP broadcast: {}", e);
+                    break;
+                }
+            }
+        });
+    }
+}
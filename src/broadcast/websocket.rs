@@ -0,0 +1,380 @@
+//! Broadcasts and shutdown on WebSocket connections
+//!
+//! jsonrpsee writes to a connection only in answer to its client, and offers
+//! no way to add messages of our own. Each connection's socket is therefore
+//! split: jsonrpsee writes into a pipe, and a relay task copies the pipe to
+//! the socket. Once the connection has been upgraded, the relay follows the
+//! WebSocket frames going through and writes each broadcast as a text frame
+//! of its own between two of jsonrpsee's messages.
+//!
+//! When the server stops, jsonrpsee stops reading calls and answers the ones
+//! in flight before closing the connection. The relay replaces that close
+//! frame with a "going away" one, so clients know to reconnect elsewhere, and
+//! cuts connections still busy when the drain timeout runs out.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use jsonrpsee::server::StopHandle;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf, ReadHalf, WriteHalf};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+use super::Broadcast;
+
+/// Bytes jsonrpsee may write ahead of the relay
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// Status line of the response accepting an upgrade
+const SWITCHING_PROTOCOLS: &[u8] = b"HTTP/1.1 101";
+
+/// End of a response head
+const HEAD_END: &[u8] = b"\r\n\r\n";
+
+/// Close code telling the client the server is going away
+const GOING_AWAY: u16 = 1001;
+
+/// Reason sent with [`GOING_AWAY`]
+const GOING_AWAY_REASON: &[u8] = b"server shutting down";
+
+/// Set once the client asked to upgrade its connection to WebSocket
+///
+/// Until then the connection carries plain HTTP, where frames must not be
+/// written.
+#[derive(Clone, Default)]
+pub(crate) struct UpgradeRequested(Arc<AtomicBool>);
+
+impl UpgradeRequested {
+    /// Note a request on the connection
+    pub(crate) fn observe<B>(&self, request: &http::Request<B>) {
+        let upgrade = request
+            .headers()
+            .get(http::header::UPGRADE)
+            .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"websocket"));
+        if upgrade {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn get(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A connection's socket, with jsonrpsee's writes going through the relay
+pub(crate) struct Relayed<S> {
+    reader: ReadHalf<S>,
+    pipe: DuplexStream,
+    /// Ends reading when the relay cuts the connection
+    cut: Pin<Box<WaitForCancellationFutureOwned>>,
+}
+
+/// How a connection is closed when the server stops
+#[derive(Clone)]
+pub(crate) struct Drain {
+    pub(crate) stop: StopHandle,
+    /// How long in-flight calls may take before the connection is cut
+    pub(crate) timeout: Duration,
+}
+
+/// Relay `socket`, adding `broadcasts` once the connection is upgraded and
+/// closing it gracefully on `drain`
+pub(crate) fn relay<S>(
+    socket: S,
+    broadcasts: Option<Receiver<Arc<Broadcast>>>,
+    upgrade: UpgradeRequested,
+    drain: Drain,
+) -> Relayed<S>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, writer) = tokio::io::split(socket);
+    let (pipe, relayed) = tokio::io::duplex(PIPE_CAPACITY);
+    let cut = CancellationToken::new();
+    let relay = Relay {
+        broadcasts,
+        upgrade,
+        drain,
+        cut: cut.clone(),
+    };
+    crate::task::spawn("plexus.websocket.relay", async move {
+        if let Err(e) = relay.copy_frames(relayed, writer).await {
+            tracing::debug!("WebSocket relay stopped: {}", e);
+        }
+    });
+    Relayed {
+        reader,
+        pipe,
+        cut: Box::pin(cut.cancelled_owned()),
+    }
+}
+
+/// What the relay task adds to a connection
+struct Relay {
+    broadcasts: Option<Receiver<Arc<Broadcast>>>,
+    upgrade: UpgradeRequested,
+    drain: Drain,
+    cut: CancellationToken,
+}
+
+impl Relay {
+    /// Copy what jsonrpsee writes to the socket, adding broadcasts between
+    /// messages, until jsonrpsee is done or the drain timeout runs out
+    async fn copy_frames<S>(mut self, mut pipe: DuplexStream, mut socket: WriteHalf<S>) -> io::Result<()>
+    where
+        S: AsyncWrite,
+    {
+        let mut frames = Frames::default();
+        let mut buf = vec![0u8; 8 * 1024];
+        let mut open = self.broadcasts.is_some();
+        let stopped = self.drain.stop.clone().shutdown();
+        tokio::pin!(stopped);
+        let deadline = tokio::time::sleep(self.drain.timeout);
+        tokio::pin!(deadline);
+        let mut draining = false;
+        // The going-away close frame was sent; jsonrpsee has nothing left to say
+        let mut closed = false;
+        loop {
+            tokio::select! {
+                n = pipe.read(&mut buf) => {
+                    let n = n?;
+                    if n == 0 {
+                        break;
+                    }
+                    if closed {
+                        continue;
+                    }
+                    let bytes = &buf[..n];
+                    // jsonrpsee closes a draining connection once its calls are answered
+                    let forward = match frames.advance(bytes, &self.upgrade) {
+                        Some(close) if draining => {
+                            closed = true;
+                            &bytes[..close]
+                        }
+                        _ => bytes,
+                    };
+                    socket.write_all(forward).await?;
+                    if closed {
+                        socket.write_all(&going_away_frame()).await?;
+                    }
+                    socket.flush().await?;
+                }
+                broadcast = next(&mut self.broadcasts), if open && !closed && frames.between_messages() => match broadcast {
+                    Ok(broadcast) => {
+                        socket.write_all(&text_frame(broadcast.json.as_bytes())).await?;
+                        socket.flush().await?;
+                    }
+                    Err(RecvError::Lagged(missed)) => tracing::debug!("WebSocket connection missed {} broadcasts", missed),
+                    Err(RecvError::Closed) => open = false,
+                },
+                _ = &mut stopped, if !draining => {
+                    draining = true;
+                    deadline.set(tokio::time::sleep(self.drain.timeout));
+                }
+                _ = &mut deadline, if draining => {
+                    tracing::debug!("WebSocket connection still open after {:?}; closing it", self.drain.timeout);
+                    if !closed && frames.between_messages() {
+                        socket.write_all(&going_away_frame()).await?;
+                    }
+                    self.cut.cancel();
+                    break;
+                }
+            }
+        }
+        socket.shutdown().await
+    }
+}
+
+/// The next broadcast, or never without a broadcaster
+async fn next(broadcasts: &mut Option<Receiver<Arc<Broadcast>>>) -> Result<Arc<Broadcast>, RecvError> {
+    match broadcasts {
+        Some(broadcasts) => broadcasts.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Where the bytes written so far leave off
+#[derive(Default)]
+struct Frames {
+    /// Bytes of the upgrade response matched, before the first frame
+    head: Option<Head>,
+    /// Bytes of the current frame header seen
+    header: Vec<u8>,
+    /// Payload bytes of the current frame still to come
+    payload: u64,
+    /// A fragmented message is under way; its frames must stay together
+    fragmented: bool,
+}
+
+/// Progress through the `101 Switching Protocols` response head
+#[derive(Clone, Copy)]
+enum Head {
+    Status(usize),
+    End(usize),
+    Done,
+}
+
+impl Frames {
+    /// A broadcast frame may be written now
+    fn between_messages(&self) -> bool {
+        matches!(self.head, Some(Head::Done)) && self.header.is_empty() && self.payload == 0 && !self.fragmented
+    }
+
+    /// Follow `bytes` written to the connection
+    ///
+    /// Returns the offset in `bytes` of the first close frame they start, if any.
+    fn advance(&mut self, mut bytes: &[u8], upgrade: &UpgradeRequested) -> Option<usize> {
+        let written = bytes.len();
+        let mut close = None;
+        while !bytes.is_empty() {
+            match self.head {
+                None if !upgrade.get() => return None,
+                None => self.head = Some(Head::Status(0)),
+                Some(Head::Status(matched)) => {
+                    let (matched, rest) = match_prefix(SWITCHING_PROTOCOLS, matched, bytes);
+                    self.head = Some(if matched == SWITCHING_PROTOCOLS.len() {
+                        Head::End(0)
+                    } else {
+                        Head::Status(matched)
+                    });
+                    bytes = rest;
+                }
+                Some(Head::End(matched)) => {
+                    let (matched, rest) = match_prefix(HEAD_END, matched, bytes);
+                    self.head = Some(if matched == HEAD_END.len() {
+                        Head::Done
+                    } else {
+                        Head::End(matched)
+                    });
+                    bytes = rest;
+                }
+                Some(Head::Done) if self.payload > 0 => {
+                    let n = bytes.len().min(usize::try_from(self.payload).unwrap_or(usize::MAX));
+                    self.payload -= n as u64;
+                    bytes = &bytes[n..];
+                }
+                Some(Head::Done) => {
+                    if self.header.is_empty() && bytes[0] & 0x0f == 0x8 {
+                        close = close.or(Some(written - bytes.len()));
+                    }
+                    self.header.push(bytes[0]);
+                    bytes = &bytes[1..];
+                    if let Some(payload) = self.frame_header() {
+                        self.payload = payload;
+                        self.header.clear();
+                    }
+                }
+            }
+        }
+        close
+    }
+
+    /// The payload length, once the frame header is complete
+    fn frame_header(&mut self) -> Option<u64> {
+        let header = &self.header;
+        if header.len() < 2 {
+            return None;
+        }
+        let extended = match header[1] & 0x7f {
+            126 => 2,
+            127 => 8,
+            _ => 0,
+        };
+        let mask = if header[1] & 0x80 != 0 { 4 } else { 0 };
+        if header.len() < 2 + extended + mask {
+            return None;
+        }
+        let payload = match extended {
+            0 => u64::from(header[1] & 0x7f),
+            n => header[2..2 + n].iter().fold(0, |len, &b| (len << 8) | u64::from(b)),
+        };
+
+        let fin = header[0] & 0x80 != 0;
+        match header[0] & 0x0f {
+            // Text or binary: starts a message, fragmented without FIN
+            0x1 | 0x2 => self.fragmented = !fin,
+            // Continuation: the message ends with FIN
+            0x0 => self.fragmented &= !fin,
+            // Control frames may come between fragments
+            _ => {}
+        }
+        Some(payload)
+    }
+}
+
+/// Match `bytes` against `pattern`, `matched` bytes of which were seen
+///
+/// Returns how much of `pattern` is matched and the bytes after the match,
+/// or after `bytes` when the match is incomplete.
+fn match_prefix<'a>(pattern: &[u8], mut matched: usize, bytes: &'a [u8]) -> (usize, &'a [u8]) {
+    for (i, &b) in bytes.iter().enumerate() {
+        matched = if b == pattern[matched] {
+            matched + 1
+        } else if b == pattern[0] {
+            1
+        } else {
+            0
+        };
+        if matched == pattern.len() {
+            return (matched, &bytes[i + 1..]);
+        }
+    }
+    (matched, &[])
+}
+
+/// An unmasked close frame saying the server is going away
+fn going_away_frame() -> Vec<u8> {
+    let mut frame = vec![0x88, (2 + GOING_AWAY_REASON.len()) as u8];
+    frame.extend_from_slice(&GOING_AWAY.to_be_bytes());
+    frame.extend_from_slice(GOING_AWAY_REASON);
+    frame
+}
+
+/// An unmasked, unfragmented WebSocket text frame carrying `payload`
+fn text_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x81);
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for Relayed<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.cut.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.reader).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for Relayed<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().pipe).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().pipe).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().pipe).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,42 @@
+//! JSON-lines capture file
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{CaptureRecord, CaptureSink};
+use crate::sink::JsonLinesFile;
+
+/// Appends one JSON [`CaptureRecord`] per line to a file
+///
+/// Each record is written with a single `write` call; reopening an existing
+/// file appends to it.
+#[derive(Debug)]
+pub struct FileCaptureSink {
+    file: JsonLinesFile,
+}
+
+impl FileCaptureSink {
+    /// Open (or create) the capture file at `path` for appending
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            file: JsonLinesFile::open("capture file", path)?,
+        })
+    }
+
+    /// Read every record in the capture file at `path`, e.g. to replay it
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<CaptureRecord>> {
+        JsonLinesFile::read("capture file", path)
+    }
+
+    /// Read back every record written so far
+    pub fn records(&self) -> Result<Vec<CaptureRecord>> {
+        Self::read(self.file.path())
+    }
+}
+
+impl CaptureSink for FileCaptureSink {
+    fn write(&self, record: &CaptureRecord) {
+        self.file.append(record);
+    }
+}
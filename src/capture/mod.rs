@@ -0,0 +1,190 @@
+//! Traffic capture and replay
+//!
+//! A [`Capture`] records the messages a transport exchanges with its clients
+//! as [`CaptureRecord`]s: every request, its response, and the notifications
+//! sent on its behalf, in full, with the transport and connection they
+//! belong to. Records go to a pluggable [`CaptureSink`]: a JSON-lines file
+//! ([`FileCaptureSink`]), SQLite (`SqliteCaptureSink`, feature
+//! `sqlite-capture`), or any closure.
+//!
+//! A [`Replayer`] drives an RPC module with the requests of a capture and
+//! returns what it answers next to what was recorded, so a misbehaving agent
+//! session can be reproduced from the capture alone.
+//!
+//! WebSocket, stdio and MCP HTTP calls are captured. WebSocket subscription
+//! notifications bypass the RPC middleware and are not; neither is REST HTTP
+//! traffic.
+//!
+//! Messages are masked with the capture's [`Redaction`] before they are
+//! queued (credential fields by default; see [`Capture::with_redaction`]);
+//! everything else is kept verbatim, so treat captures like the data they
+//! contain. Requests never wait on the sink: records are queued to a writer
+//! thread, and dropped with an error log when the queue is full.
+
+pub mod file;
+pub mod replay;
+#[cfg(feature = "sqlite-capture")]
+pub mod sqlite;
+
+pub use file::FileCaptureSink;
+pub use replay::{ReplayedCall, Replayer};
+#[cfg(feature = "sqlite-capture")]
+pub use sqlite::SqliteCaptureSink;
+
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::redact::Redaction;
+use crate::sink::Writer;
+
+/// Records waiting for the writer thread before new ones are dropped
+pub const CAPTURE_QUEUE_CAPACITY: usize = 4096;
+
+/// Which way a captured message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// A request or notification from the client
+    Request,
+    /// The server's response to a request
+    Response,
+    /// A notification the server sent while handling a request
+    Notification,
+}
+
+impl Direction {
+    /// The name stored for this direction
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Request => "request",
+            Self::Response => "response",
+            Self::Notification => "notification",
+        }
+    }
+}
+
+/// One captured message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    /// When the message was sent or received (milliseconds since the Unix epoch)
+    pub timestamp_ms: u64,
+    /// Transport the message went over (`websocket`, `stdio`, `mcp`)
+    pub transport: String,
+    /// WebSocket connection id or MCP session id; `None` on stdio
+    pub connection: Option<String>,
+    pub direction: Direction,
+    /// Id of the request the message belongs to; for notifications, the
+    /// request that caused them
+    pub request_id: Option<serde_json::Value>,
+    /// The JSON-RPC message, redacted; input that was not valid JSON is
+    /// replaced by its size, since it cannot be masked
+    pub message: serde_json::Value,
+}
+
+/// Destination for capture records
+///
+/// Implemented for any `Fn(&CaptureRecord)`. Sinks are called one record at
+/// a time on the capture's writer thread, so they may block, but a slow sink
+/// fills the queue and loses records.
+pub trait CaptureSink: Send + Sync {
+    /// Persist a record; failures should be logged, not propagated
+    fn write(&self, record: &CaptureRecord);
+}
+
+impl<F> CaptureSink for F
+where
+    F: Fn(&CaptureRecord) + Send + Sync,
+{
+    fn write(&self, record: &CaptureRecord) {
+        self(record)
+    }
+}
+
+/// Turns the messages of the transports into [`CaptureRecord`]s for a sink
+///
+/// Share one `Arc<Capture>` across transports to get a single capture.
+pub struct Capture {
+    redaction: Redaction,
+    writer: Writer<CaptureRecord>,
+}
+
+impl Capture {
+    /// Capture to `sink`, masking the default [`Redaction`] fields
+    pub fn new(sink: impl CaptureSink + 'static) -> Self {
+        Self {
+            redaction: Redaction::default(),
+            writer: Writer::spawn("plexus-capture", CAPTURE_QUEUE_CAPACITY, move |record| sink.write(&record)),
+        }
+    }
+
+    /// Mask these fields instead; `Redaction::none()` captures messages verbatim
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Wait until every record queued so far has been handed to the sink
+    ///
+    /// Blocks the calling thread; in async code, call it from
+    /// `tokio::task::spawn_blocking`.
+    pub fn flush(&self) {
+        self.writer.flush();
+    }
+
+    /// Capture a message
+    ///
+    /// The request id of requests and responses is taken from the message;
+    /// `request_id` is only used for notifications.
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http"))]
+    pub(crate) fn record(
+        &self,
+        transport: &str,
+        connection: Option<String>,
+        direction: Direction,
+        request_id: Option<serde_json::Value>,
+        mut message: serde_json::Value,
+    ) {
+        let request_id = match direction {
+            Direction::Notification => request_id,
+            Direction::Request | Direction::Response => message.get("id").cloned(),
+        };
+        self.redaction.redact_json(&mut message);
+        let record = CaptureRecord {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            transport: transport.to_string(),
+            connection,
+            direction,
+            request_id,
+            message,
+        };
+        if let Err(reason) = self.writer.send(record) {
+            tracing::error!("Capture {}; {} record dropped", reason, direction.as_str());
+        }
+    }
+
+    /// Capture a raw JSON message
+    #[cfg(any(feature = "websocket", feature = "stdio"))]
+    pub(crate) fn record_raw(
+        &self,
+        transport: &str,
+        connection: Option<String>,
+        direction: Direction,
+        request_id: Option<serde_json::Value>,
+        raw: &str,
+    ) {
+        let message = serde_json::from_str(raw)
+            .unwrap_or_else(|_| serde_json::Value::String(format!("<{} bytes>", raw.len())));
+        self.record(transport, connection, direction, request_id, message);
+    }
+}
+
+impl std::fmt::Debug for Capture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Capture").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,181 @@
+//! Replaying a capture against an RPC module
+
+use std::time::Duration;
+
+use jsonrpsee::Methods;
+use serde_json::{json, Value};
+
+use super::{CaptureRecord, Direction};
+
+/// Default time to wait for a further subscription notification
+pub const DEFAULT_NOTIFICATION_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Drives an RPC module with the requests of a capture
+///
+/// Requests are replayed one at a time, in capture order, on a single
+/// connection. MCP tool calls are replayed as calls of the method named like
+/// the tool, with the tool arguments as params. Client notifications (requests
+/// without an id) are skipped.
+///
+/// ```rust,ignore
+/// let records = FileCaptureSink::read("capture.jsonl")?;
+/// for call in Replayer::new(module).replay(&records).await {
+///     if call.matches() == Some(false) {
+///         println!("{} now answers {}", call.request, call.response);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Replayer {
+    methods: Methods,
+    notification_timeout: Duration,
+}
+
+/// A replayed request, with what was recorded for it and what the module answered
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedCall {
+    /// Transport the request was captured on
+    pub transport: String,
+    /// WebSocket connection id or MCP session id it was captured on
+    pub connection: Option<String>,
+    /// The request as sent to the module
+    pub request: Value,
+    /// The response in the capture, if it was captured
+    pub recorded_response: Option<Value>,
+    /// The notifications captured for the request
+    pub recorded_notifications: Vec<Value>,
+    /// The module's response
+    pub response: Value,
+    /// The subscription notifications the module sent
+    pub notifications: Vec<Value>,
+}
+
+impl ReplayedCall {
+    /// Whether the module answered as recorded
+    ///
+    /// Calls compare their `result` or `error`; subscriptions compare the
+    /// results of their notifications, since subscription ids differ between
+    /// runs. `None` when there is nothing comparable: the response was not
+    /// captured, or the call was an MCP tool call, whose results the bridge
+    /// reshapes.
+    pub fn matches(&self) -> Option<bool> {
+        if self.transport == "mcp" {
+            return None;
+        }
+        let recorded = self.recorded_response.as_ref()?;
+        if !self.recorded_notifications.is_empty() || !self.notifications.is_empty() {
+            let results = |notifications: &[Value]| -> Vec<Value> {
+                notifications.iter().map(|n| n["params"]["result"].clone()).collect()
+            };
+            return Some(results(&self.recorded_notifications) == results(&self.notifications));
+        }
+        Some(recorded.get("result") == self.response.get("result") && recorded.get("error") == self.response.get("error"))
+    }
+}
+
+impl Replayer {
+    pub fn new(methods: impl Into<Methods>) -> Self {
+        Self {
+            methods: methods.into(),
+            notification_timeout: DEFAULT_NOTIFICATION_TIMEOUT,
+        }
+    }
+
+    /// How long to wait for a further notification before moving on from a
+    /// subscription that is still open
+    pub fn with_notification_timeout(mut self, timeout: Duration) -> Self {
+        self.notification_timeout = timeout;
+        self
+    }
+
+    /// Replay every request in `records`
+    pub async fn replay(&self, records: &[CaptureRecord]) -> Vec<ReplayedCall> {
+        let mut calls = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            if record.direction != Direction::Request {
+                continue;
+            }
+            let Some(id) = record.request_id.clone() else {
+                continue;
+            };
+            let Some(request) = replayable(record, &id) else {
+                continue;
+            };
+
+            let (recorded_response, recorded_notifications) = recorded_answer(records, index, &id);
+            let (response, notifications) = self.call(&request, &id).await;
+            calls.push(ReplayedCall {
+                transport: record.transport.clone(),
+                connection: record.connection.clone(),
+                request,
+                recorded_response,
+                recorded_notifications,
+                response,
+                notifications,
+            });
+        }
+        calls
+    }
+
+    /// Send a request to the module and collect its response and notifications
+    async fn call(&self, request: &Value, id: &Value) -> (Value, Vec<Value>) {
+        let (response, mut receiver) = match self.methods.raw_json_request(&request.to_string(), 1024).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32700, "message": e.to_string() },
+                });
+                return (response, Vec::new());
+            }
+        };
+        let response = serde_json::from_str(response.get()).unwrap_or(Value::Null);
+
+        // Non-subscription calls close the channel right away
+        let mut notifications = Vec::new();
+        while let Ok(Some(notification)) = tokio::time::timeout(self.notification_timeout, receiver.recv()).await {
+            notifications.push(serde_json::from_str(notification.get()).unwrap_or(Value::Null));
+        }
+        (response, notifications)
+    }
+}
+
+/// The JSON-RPC request to replay for a captured request
+fn replayable(record: &CaptureRecord, id: &Value) -> Option<Value> {
+    let message = record.message.as_object()?;
+    let method = message.get("method")?.as_str()?;
+    if record.transport == "mcp" && method == "tools/call" {
+        let params = message.get("params")?;
+        return Some(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": params.get("name")?,
+            "params": params.get("arguments").cloned().unwrap_or_else(|| json!({})),
+        }));
+    }
+    Some(Value::Object(message.clone()))
+}
+
+/// The captured response and notifications of the request at `index`
+///
+/// Looks ahead until the same connection reuses the request id.
+fn recorded_answer(records: &[CaptureRecord], index: usize, id: &Value) -> (Option<Value>, Vec<Value>) {
+    let request = &records[index];
+    let mut response = None;
+    let mut notifications = Vec::new();
+    let same_call = |record: &CaptureRecord| {
+        record.transport == request.transport
+            && record.connection == request.connection
+            && record.request_id.as_ref() == Some(id)
+    };
+    for record in records[index + 1..].iter().filter(|record| same_call(record)) {
+        match record.direction {
+            Direction::Request => break,
+            Direction::Response if response.is_none() => response = Some(record.message.clone()),
+            Direction::Response => {}
+            Direction::Notification => notifications.push(record.message.clone()),
+        }
+    }
+    (response, notifications)
+}
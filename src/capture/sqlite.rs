@@ -0,0 +1,119 @@
+//! SQLite capture table
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use super::{CaptureRecord, CaptureSink, CAPTURE_QUEUE_CAPACITY};
+use crate::sink::sqlite::{self, SqliteWriter};
+
+/// Stores [`CaptureRecord`]s in a `capture_log` SQLite table
+///
+/// Records are inserted in order by a background task. The capture's writer
+/// thread waits while the task is [`CAPTURE_QUEUE_CAPACITY`] records behind,
+/// so a slow database fills the capture's own queue instead of memory.
+#[derive(Debug)]
+pub struct SqliteCaptureSink {
+    writer: SqliteWriter<CaptureRecord>,
+    pool: SqlitePool,
+}
+
+impl SqliteCaptureSink {
+    /// Open (or create) the database at `path` and start the writer task
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let pool = connect(path.as_ref()).await?;
+
+        let writer = {
+            let pool = pool.clone();
+            SqliteWriter::spawn("plexus.capture.writer", CAPTURE_QUEUE_CAPACITY, move |record| {
+                let pool = pool.clone();
+                async move { insert(&pool, &record).await }
+            })
+        };
+
+        Ok(Self { writer, pool })
+    }
+
+    /// Read every record in the capture database at `path`, e.g. to replay it
+    pub async fn read(path: impl AsRef<Path>) -> Result<Vec<CaptureRecord>> {
+        select_all(&connect(path.as_ref()).await?).await
+    }
+
+    /// Read back every record inserted so far
+    pub async fn records(&self) -> Result<Vec<CaptureRecord>> {
+        select_all(&self.pool).await
+    }
+}
+
+async fn connect(path: &Path) -> Result<SqlitePool> {
+    sqlite::connect(
+        "capture",
+        path,
+        r#"
+        CREATE TABLE IF NOT EXISTS capture_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp_ms INTEGER NOT NULL,
+            transport TEXT NOT NULL,
+            connection TEXT,
+            direction TEXT NOT NULL,
+            request_id TEXT,
+            message TEXT NOT NULL
+        );
+        "#,
+    )
+    .await
+}
+
+async fn insert(pool: &SqlitePool, record: &CaptureRecord) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO capture_log (timestamp_ms, transport, connection, direction, request_id, message) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(record.timestamp_ms as i64)
+    .bind(&record.transport)
+    .bind(&record.connection)
+    .bind(record.direction.as_str())
+    .bind(record.request_id.as_ref().map(|id| id.to_string()))
+    .bind(record.message.to_string())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn select_all(pool: &SqlitePool) -> Result<Vec<CaptureRecord>> {
+    let rows = sqlx::query(
+        "SELECT timestamp_ms, transport, connection, direction, request_id, message \
+         FROM capture_log ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to read capture records")?;
+
+    rows.iter()
+        .map(|row| {
+            let direction: String = row.try_get("direction")?;
+            let request_id: Option<String> = row.try_get("request_id")?;
+            let message: String = row.try_get("message")?;
+            Ok(CaptureRecord {
+                timestamp_ms: row.try_get::<i64, _>("timestamp_ms")? as u64,
+                transport: row.try_get("transport")?,
+                connection: row.try_get("connection")?,
+                direction: serde_json::from_value(serde_json::Value::String(direction))
+                    .context("Invalid capture direction")?,
+                request_id: request_id.map(|id| serde_json::from_str(&id)).transpose()?,
+                message: serde_json::from_str(&message)?,
+            })
+        })
+        .collect()
+}
+
+impl CaptureSink for SqliteCaptureSink {
+    /// Called on the capture's writer thread; must not be called from async code
+    fn write(&self, record: &CaptureRecord) {
+        if !self.writer.send(record.clone()) {
+            tracing::error!("Capture writer task stopped; {} record dropped", record.direction.as_str());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Command-line flags for binaries embedding the transports
+//!
+//! [`TransportArgs`] is a clap argument group meant to be flattened into the
+//! binary's own parser:
+//!
+//! ```rust,ignore
+//! use clap::Parser;
+//! use plexus_transport::{TransportArgs, TransportConfig};
+//!
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[command(flatten)]
+//!     transport: TransportArgs,
+//! }
+//!
+//! let config: TransportConfig = Cli::parse().transport.into();
+//! ```
+
+use std::net::{IpAddr, SocketAddr};
+
+use crate::config::{McpHttpConfig, SessionStorage, StdioConfig, TransportConfig, WebSocketConfig};
+
+/// Transport flags: `--ws-port`, `--mcp-port`, `--stdio`, `--sessions`
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct TransportArgs {
+    /// Serve JSON-RPC over WebSocket on this port
+    #[arg(long, value_name = "PORT")]
+    pub ws_port: Option<u16>,
+
+    /// Serve MCP over streamable HTTP on this port
+    #[arg(long, value_name = "PORT")]
+    pub mcp_port: Option<u16>,
+
+    /// Serve line-delimited JSON-RPC over stdin/stdout
+    #[arg(long)]
+    pub stdio: bool,
+
+    /// Address the network transports listen on [default: 127.0.0.1]
+    #[arg(long, value_name = "IP")]
+    pub host: Option<IpAddr>,
+
+    /// Bearer token required on every network transport
+    #[arg(long, value_name = "KEY")]
+    pub api_key: Option<String>,
+
+    /// MCP session storage: `memory`, `sqlite:<path>` or a `redis://` URL
+    #[arg(long, value_name = "STORAGE", value_parser = parse_sessions, requires = "mcp_port")]
+    pub sessions: Option<SessionStorage>,
+}
+
+impl TransportArgs {
+    /// Overlay `config` (e.g. one loaded from a file) with the flags that were given
+    ///
+    /// `--host` moves every configured network listener; the port flags
+    /// enable their transport or change the port of an existing one.
+    pub fn apply(self, mut config: TransportConfig) -> TransportConfig {
+        let listen = |current: Option<SocketAddr>, port: Option<u16>| {
+            let port = port.or(current.map(|addr| addr.port()))?;
+            let ip = self
+                .host
+                .or(current.map(|addr| addr.ip()))
+                .unwrap_or([127, 0, 0, 1].into());
+            Some(SocketAddr::new(ip, port))
+        };
+
+        if let Some(addr) = listen(config.websocket.as_ref().map(|c| c.addr), self.ws_port) {
+            config.websocket = Some(match config.websocket.take() {
+                Some(ws) => ws.with_addr(addr),
+                None => WebSocketConfig::from_addr(addr),
+            });
+        }
+        if let Some(addr) = listen(config.mcp_http.as_ref().map(|c| c.addr), self.mcp_port) {
+            config.mcp_http = Some(match config.mcp_http.take() {
+                Some(mcp) => mcp.with_addr(addr),
+                None => McpHttpConfig::from_addr(addr),
+            });
+        }
+        if self.stdio && config.stdio.is_none() {
+            config.stdio = Some(StdioConfig::default());
+        }
+        if let Some(key) = self.api_key {
+            config.api_key = Some(key);
+        }
+        if let (Some(storage), Some(mcp)) = (self.sessions, config.mcp_http.as_mut()) {
+            mcp.session_storage = storage;
+        }
+        config
+    }
+}
+
+impl From<TransportArgs> for TransportConfig {
+    fn from(args: TransportArgs) -> Self {
+        args.apply(TransportConfig::default())
+    }
+}
+
+/// Parse `--sessions`
+fn parse_sessions(value: &str) -> Result<SessionStorage, String> {
+    if value == "memory" {
+        return Ok(SessionStorage::InMemory);
+    }
+    if value.starts_with("redis://") || value.starts_with("rediss://") {
+        #[cfg(feature = "redis-sessions")]
+        return Ok(SessionStorage::redis(value));
+        #[cfg(not(feature = "redis-sessions"))]
+        return Err("redis storage requires the `redis-sessions` feature".to_string());
+    }
+    match value.strip_prefix("sqlite:") {
+        Some("") => Err("sqlite storage needs a path, e.g. `sqlite:sessions.db`".to_string()),
+        #[cfg(feature = "sqlite-sessions")]
+        Some(path) => Ok(SessionStorage::Sqlite { path: path.into() }),
+        #[cfg(not(feature = "sqlite-sessions"))]
+        Some(_) => Err("sqlite storage requires the `sqlite-sessions` feature".to_string()),
+        None => Err("expected `memory`, `sqlite:<path>` or `redis://<host>`".to_string()),
+    }
+}
@@ -164,7 +164,7 @@ where
     // Pre-compute the expected Authorization header value for WebSocket path checks.
     let ws_auth_header: Option<String> = api_key.map(|k| format!("Bearer {}", k));
 
-    tokio::spawn(async move {
+    crate::task::spawn("plexus.combined.accept", async move {
         loop {
             let (sock, _peer) = tokio::select! {
                 res = listener.accept() => match res {
@@ -180,7 +180,7 @@ where
             let mcp = mcp_router.clone();
             let ws_auth = ws_auth_header.clone();
 
-            tokio::spawn(async move {
+            crate::task::spawn("plexus.combined.connection", async move {
                 // Clone stop2 before moving into the closure, for the shutdown future.
                 let stop_for_serve = stop2.clone();
 
@@ -1,12 +1,62 @@
 //! Configuration types for transport servers
 
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "websocket")]
+use jsonrpsee::core::middleware::{RpcServiceBuilder, RpcServiceT};
+#[cfg(feature = "websocket")]
+use jsonrpsee::MethodResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::access_log::AccessLog;
+use crate::audit::Auditor;
+use crate::auth::{ApiKeyAuth, AuthorizationPolicy};
+use crate::broadcast::Broadcaster;
+use crate::capture::Capture;
+use crate::ip_filter::IpFilter;
+use crate::mcp::timeout::ToolTimeouts;
+#[cfg(feature = "metrics")]
+use crate::metrics::{MetricsConfig, TransportMetrics};
+use crate::middleware::TransportMiddleware;
+use crate::observer::TransportObserver;
+use crate::origin_check::OriginCheck;
+use crate::rate_limit::RateLimitConfig;
+use crate::redact::Redaction;
+use crate::resume::SubscriptionResume;
+#[cfg(feature = "websocket")]
+use crate::rpc_middleware::{BoxedRpcService, RpcMiddleware};
+use crate::supervisor::RestartPolicy;
+
+/// Default payload limit for every transport (10 MiB)
+pub const DEFAULT_MAX_PAYLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+/// Default time allowed for in-flight requests to finish on shutdown
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default path of the MCP endpoint
+pub const DEFAULT_MCP_PATH: &str = "/mcp";
+
+/// Default interval between keep-alive comments on MCP SSE streams
+pub const DEFAULT_SSE_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Default number of messages buffered per MCP session stream
+pub const DEFAULT_SESSION_CHANNEL_CAPACITY: usize = 16;
+
+#[cfg(feature = "jwt-auth")]
+use crate::auth::jwt::JwtAuth;
 
-#[cfg(feature = "sqlite-sessions")]
 use std::path::PathBuf;
 
 /// Complete transport configuration
-#[derive(Debug, Clone)]
+///
+/// Serializable so applications can keep it in their own config systems.
+/// Runtime-only settings (authorization policies, audit logs, validators,
+/// custom middleware) are skipped and must be set again after deserializing;
+/// durations are in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TransportConfig {
     pub websocket: Option<WebSocketConfig>,
     pub stdio: Option<StdioConfig>,
@@ -15,6 +65,49 @@ pub struct TransportConfig {
     /// Optional bearer token required on all WebSocket, MCP HTTP, and REST HTTP connections.
     /// When `None`, no authentication is required (current behaviour).
     pub api_key: Option<String>,
+    /// Optional per-method authorization policy, applied to every transport
+    /// that does not configure its own.
+    #[serde(skip)]
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every call, for every transport that does not
+    /// configure its own.
+    #[serde(skip)]
+    pub middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional audit log, shared by every transport that does not configure its own.
+    #[serde(skip)]
+    pub audit: Option<Arc<Auditor>>,
+    /// Optional access log, shared by every transport that does not configure its own.
+    #[serde(skip)]
+    pub access_log: Option<Arc<AccessLog>>,
+    /// Optional connection and session lifecycle hooks, shared by every
+    /// transport that does not configure its own.
+    #[serde(skip)]
+    pub observer: Option<Arc<dyn TransportObserver>>,
+    /// Optional server-wide notifications, sent to WebSocket clients and MCP
+    /// sessions of every transport that does not configure its own.
+    #[serde(skip)]
+    pub broadcaster: Option<Broadcaster>,
+    /// Optional traffic capture, shared by every transport that does not configure its own.
+    /// REST HTTP traffic is not captured.
+    #[serde(skip)]
+    pub capture: Option<Arc<Capture>>,
+    /// Optional Prometheus metrics, shared by every transport that does not configure its own.
+    #[cfg(feature = "metrics")]
+    #[serde(skip)]
+    pub metrics: Option<Arc<TransportMetrics>>,
+    /// Optional endpoint serving the metrics in the Prometheus text format.
+    #[cfg(feature = "metrics")]
+    pub metrics_endpoint: Option<MetricsConfig>,
+    /// How long shutdown waits for in-flight requests before aborting them.
+    #[serde(with = "serde_secs")]
+    pub shutdown_timeout: Duration,
+    /// Time limits of calls, by method, for every transport that does not
+    /// configure its own; none by default.
+    pub request_timeouts: ToolTimeouts,
+    /// Start the graceful shutdown on SIGTERM or SIGINT.
+    pub signal_shutdown: bool,
+    /// Restart crashed MCP/REST HTTP transports instead of shutting down.
+    pub restart_policy: Option<RestartPolicy>,
 }
 
 impl Default for TransportConfig {
@@ -25,16 +118,152 @@ impl Default for TransportConfig {
             mcp_http: None,
             rest_http: None,
             api_key: None,
+            authorization: None,
+            middleware: Vec::new(),
+            audit: None,
+            access_log: None,
+            observer: None,
+            broadcaster: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            metrics_endpoint: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            request_timeouts: ToolTimeouts::default(),
+            signal_shutdown: false,
+            restart_policy: None,
         }
     }
 }
 
+/// Resolve a bind host and port to a socket address
+///
+/// IPv6 literals may be bracketed (`[::]`); hostnames are resolved and the
+/// first address is used.
+pub(crate) fn resolve_host(host: &str, port: u16) -> std::io::Result<SocketAddr> {
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+        return Ok(SocketAddr::new(ip, port));
+    }
+    std::net::ToSocketAddrs::to_socket_addrs(&(host, port))?
+        .next()
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No address found for host {}", host),
+            )
+        })
+}
+
 /// WebSocket server configuration
-#[derive(Debug, Clone)]
+///
+/// Missing fields deserialize to the defaults of `WebSocketConfig::new(0)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default = "WebSocketConfig::unbound")]
 pub struct WebSocketConfig {
     pub addr: SocketAddr,
+    /// Optional Unix socket path the server accepts connections on too (Unix only).
+    pub unix_socket: Option<PathBuf>,
     /// Optional bearer token required on the HTTP upgrade request.
     pub api_key: Option<String>,
+    /// Optional API key authentication (key set or async validator).
+    /// Takes precedence over `api_key` when both are set.
+    #[serde(skip)]
+    pub auth: Option<ApiKeyAuth>,
+    /// Optional JWT bearer validation (static secret or JWKS).
+    #[cfg(feature = "jwt-auth")]
+    #[serde(skip)]
+    pub jwt: Option<JwtAuth>,
+    /// Optional TLS certificate/key; when set the server speaks `wss://`.
+    #[cfg(feature = "tls")]
+    pub tls: Option<TlsConfig>,
+    /// Optional per-method authorization policy, consulted on every call.
+    #[serde(skip)]
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every call the authorization policy and rate limit let through.
+    #[serde(skip)]
+    pub middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional audit log recording every call.
+    #[serde(skip)]
+    pub audit: Option<Arc<Auditor>>,
+    /// Optional access log with a JSON line per call.
+    #[serde(skip)]
+    pub access_log: Option<Arc<AccessLog>>,
+    /// Optional hooks called as connections open and close.
+    #[serde(skip)]
+    pub observer: Option<Arc<dyn TransportObserver>>,
+    /// Optional server-wide notifications, sent to every WebSocket connection.
+    #[serde(skip)]
+    pub broadcaster: Option<Broadcaster>,
+    /// Optional capture of every request and response; subscription
+    /// notifications are not captured.
+    #[serde(skip)]
+    pub capture: Option<Arc<Capture>>,
+    /// Optional Prometheus metrics recording every call.
+    #[cfg(feature = "metrics")]
+    #[serde(skip)]
+    pub metrics: Option<Arc<TransportMetrics>>,
+    /// Optional per-client rate limit, applied to every call.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Optional CIDR allow/deny lists, enforced when connections are accepted.
+    pub ip_filter: Option<IpFilter>,
+    /// `Origin` header validation on the upgrade request (DNS-rebinding
+    /// protection); by default only localhost origins are accepted on loopback
+    /// and wildcard binds.
+    pub origin_check: OriginCheck,
+    /// `Host` header values accepted on upgrade requests and HTTP calls, e.g.
+    /// `localhost:*` or `hub.example.com`; `None` (the default) accepts any host.
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Maximum size of a single message (WebSocket frame payload or HTTP body)
+    /// in bytes; larger requests get a JSON-RPC "Request is too big" error.
+    pub max_message_size: u32,
+    /// Maximum size of a single response (or batch of responses) in bytes;
+    /// larger ones are replaced by a JSON-RPC "Response is too big" error.
+    pub max_response_size: u32,
+    /// Maximum number of concurrent connections; further connections are
+    /// dropped as soon as they are accepted.
+    pub max_connections: u32,
+    /// Maximum number of active subscriptions per connection.
+    pub max_subscriptions_per_connection: u32,
+    /// Maximum number of calls in one JSON-RPC batch; larger batches are
+    /// rejected whole. `None` (the default) allows any size, `Some(0)` refuses batches.
+    pub max_request_batch_size: Option<u32>,
+    /// Maximum number of outgoing messages buffered per connection; when full,
+    /// the server stops reading from that connection until the client catches up.
+    pub message_buffer_capacity: u32,
+    /// Interval between WebSocket pings to every connection; `None` (the
+    /// default) sends none, and connections are kept until the client closes them.
+    #[serde(with = "serde_secs::option")]
+    pub ping_interval: Option<Duration>,
+    /// With pings on, a connection that has not answered one for this long
+    /// misses a ping at each interval.
+    #[serde(with = "serde_secs")]
+    pub inactive_limit: Duration,
+    /// With pings on, connections are closed after this many missed pings.
+    pub max_missed_pings: usize,
+    /// Optional resume window for subscriptions whose connection drops; `None`
+    /// (the default) ends subscriptions with their connection.
+    pub subscription_resume: Option<SubscriptionResume>,
+    /// When the server stops, how long connections may take to answer their
+    /// in-flight calls before they are closed anyway.
+    #[serde(with = "serde_secs")]
+    pub drain_timeout: Duration,
+    /// Calls running longer than this are logged as slow (and counted, with
+    /// feature `metrics`); `None` disables the check.
+    #[serde(with = "serde_secs::option")]
+    pub slow_request_threshold: Option<Duration>,
+    /// Time limits of calls, by method; calls running longer are answered
+    /// with a `TIMEOUT_ERROR_CODE` error. None by default.
+    pub request_timeouts: ToolTimeouts,
+    /// Optional jsonrpsee middleware run on every call after the authorization
+    /// policy and rate limit.
+    #[cfg(feature = "websocket")]
+    #[serde(skip)]
+    pub rpc_middleware: Option<RpcMiddleware>,
 }
 
 impl WebSocketConfig {
@@ -43,37 +272,765 @@ impl WebSocketConfig {
             addr: format!("127.0.0.1:{}", port)
                 .parse()
                 .expect("Valid socket address"),
+            unix_socket: None,
             api_key: None,
+            auth: None,
+            #[cfg(feature = "jwt-auth")]
+            jwt: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+            authorization: None,
+            middleware: Vec::new(),
+            audit: None,
+            access_log: None,
+            observer: None,
+            broadcaster: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            rate_limit: None,
+            ip_filter: None,
+            origin_check: OriginCheck::default(),
+            allowed_hosts: None,
+            max_message_size: DEFAULT_MAX_PAYLOAD_SIZE as u32,
+            max_response_size: DEFAULT_MAX_PAYLOAD_SIZE as u32,
+            max_connections: 100,
+            max_subscriptions_per_connection: 1024,
+            max_request_batch_size: None,
+            message_buffer_capacity: 1024,
+            ping_interval: None,
+            inactive_limit: Duration::from_secs(40),
+            max_missed_pings: 1,
+            subscription_resume: None,
+            drain_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            slow_request_threshold: None,
+            request_timeouts: ToolTimeouts::default(),
+            #[cfg(feature = "websocket")]
+            rpc_middleware: None,
+        }
+    }
+
+    /// Configuration listening on `addr`, e.g. `0.0.0.0:4444` or `[::]:4444`
+    pub fn from_addr(addr: SocketAddr) -> Self {
+        Self::new(addr.port()).with_addr(addr)
+    }
+
+    fn unbound() -> Self {
+        Self::new(0)
+    }
+
+    /// Listen on `addr` instead of `127.0.0.1:<port>`
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Listen on `host` (an IP such as `0.0.0.0`, `::` or `[::]`, or a hostname),
+    /// keeping the configured port
+    pub fn with_host(mut self, host: &str) -> std::io::Result<Self> {
+        self.addr = resolve_host(host, self.addr.port())?;
+        Ok(self)
+    }
+
+    /// Also accept connections on the Unix socket at `path` (Unix only)
+    ///
+    /// A socket file left behind by a server that is no longer running is
+    /// replaced; the file is removed when the server stops.
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Require API key authentication on the HTTP upgrade request
+    pub fn with_auth(mut self, auth: ApiKeyAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Require a valid JWT bearer token on the HTTP upgrade request
+    #[cfg(feature = "jwt-auth")]
+    pub fn with_jwt(mut self, jwt: JwtAuth) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    /// Check every call against an authorization policy; denied calls get a JSON-RPC error
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every call, after the ones added before it
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Record every call in an audit log
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per call to an access log
+    pub fn with_access_log(mut self, log: Arc<AccessLog>) -> Self {
+        self.access_log = Some(log);
+        self
+    }
+
+    /// Report connections opening and closing to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn TransportObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Send the notifications of `broadcaster` to every WebSocket connection
+    pub fn with_broadcaster(mut self, broadcaster: Broadcaster) -> Self {
+        self.broadcaster = Some(broadcaster);
+        self
+    }
+
+    /// Capture every request and response, e.g. to replay them later
+    pub fn with_capture(mut self, capture: Arc<Capture>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Record every call in Prometheus metrics
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<TransportMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log calls still running after `threshold` as slow
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Fail calls that run longer than their limit in `timeouts`
+    pub fn with_request_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.request_timeouts = timeouts;
+        self
+    }
+
+    /// Rate limit calls per client; calls over the limit get a JSON-RPC error
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Only accept connections from networks allowed by `filter`
+    pub fn with_ip_filter(mut self, filter: IpFilter) -> Self {
+        self.ip_filter = Some(filter);
+        self
+    }
+
+    /// Accept browser upgrade requests from these origins in addition to localhost
+    pub fn with_allowed_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.origin_check = OriginCheck::Allow(origins.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set how the `Origin` header of upgrade requests is validated
+    pub fn with_origin_check(mut self, check: OriginCheck) -> Self {
+        self.origin_check = check;
+        self
+    }
+
+    /// Accept only requests whose `Host` header matches one of `hosts`
+    /// (`host`, `host:port` or `host:*` for any port)
+    pub fn with_allowed_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_hosts = Some(hosts.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Reject messages larger than `bytes`
+    pub fn with_max_message_size(mut self, bytes: u32) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+
+    /// Answer responses larger than `bytes` with an error instead
+    pub fn with_max_response_size(mut self, bytes: u32) -> Self {
+        self.max_response_size = bytes;
+        self
+    }
+
+    /// Limit the number of concurrent connections
+    pub fn with_max_connections(mut self, max: u32) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Limit the number of active subscriptions per connection
+    pub fn with_max_subscriptions_per_connection(mut self, max: u32) -> Self {
+        self.max_subscriptions_per_connection = max;
+        self
+    }
+
+    /// Reject JSON-RPC batches of more than `max` calls; `0` refuses batches
+    pub fn with_max_request_batch_size(mut self, max: u32) -> Self {
+        self.max_request_batch_size = Some(max);
+        self
+    }
+
+    /// Limit the number of outgoing messages buffered per connection
+    pub fn with_message_buffer_capacity(mut self, capacity: u32) -> Self {
+        self.message_buffer_capacity = capacity;
+        self
+    }
+
+    /// Ping every connection each `interval`, and close connections whose
+    /// client stops answering (half-open connections behind NAT, vanished clients)
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Count a ping as missed once the client has not answered one for `limit`
+    pub fn with_inactive_limit(mut self, limit: Duration) -> Self {
+        self.inactive_limit = limit;
+        self
+    }
+
+    /// Close a connection after `max` missed pings (at least 1)
+    pub fn with_max_missed_pings(mut self, max: usize) -> Self {
+        self.max_missed_pings = max.max(1);
+        self
+    }
+
+    /// Keep subscriptions running when their connection drops, so a client
+    /// reconnecting within the window resumes them (see [`crate::resume`])
+    pub fn with_subscription_resume(mut self, resume: SubscriptionResume) -> Self {
+        self.subscription_resume = Some(resume);
+        self
+    }
+
+    /// On shutdown, give connections up to `timeout` to answer their in-flight
+    /// calls before closing them (default 30s)
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Run the layers of a jsonrpsee `RpcServiceBuilder` on every call (logging,
+    /// metrics, custom authorization)
+    #[cfg(feature = "websocket")]
+    pub fn with_rpc_middleware<L>(mut self, builder: RpcServiceBuilder<L>) -> Self
+    where
+        L: tower::Layer<BoxedRpcService> + Send + Sync + 'static,
+        L::Service: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + 'static,
+    {
+        self.rpc_middleware = Some(RpcMiddleware::new(builder));
+        self
+    }
+
+    /// Serve `wss://` using the PEM certificate chain and private key at the given paths
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        self.tls = Some(TlsConfig::new(cert_path, key_path));
+        self
+    }
+
+    /// Serve TLS with a fully specified configuration (e.g. mutual TLS)
+    #[cfg(feature = "tls")]
+    pub fn with_tls_config(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+}
+
+/// TLS certificate configuration (PEM-encoded files, loaded at startup)
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to the PEM certificate chain (leaf first)
+    pub cert_path: PathBuf,
+    /// Path to the PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: PathBuf,
+    /// Optional PEM bundle of CAs trusted to sign client certificates.
+    /// When set, clients must present a valid certificate (mutual TLS).
+    pub client_ca_path: Option<PathBuf>,
+}
+
+#[cfg(feature = "tls")]
+impl TlsConfig {
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
         }
     }
+
+    /// Require client certificates signed by a CA in the given PEM bundle (mutual TLS)
+    pub fn with_client_ca(mut self, ca_path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(ca_path.into());
+        self
+    }
 }
 
-/// Stdio (line-delimited JSON-RPC) configuration
-#[derive(Debug, Clone)]
+/// Stdio (line-delimited or `Content-Length` framed JSON-RPC) configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StdioConfig {
     /// Buffer size for subscription notifications
     pub subscription_buffer_size: usize,
+    /// What happens to a subscription's notifications when its buffer is full.
+    pub notification_overflow: NotificationOverflow,
+    /// How messages are delimited on the reader and writer.
+    pub framing: StdioFraming,
+    /// Optional per-method authorization policy; the caller is `CallerIdentity::Local`.
+    #[serde(skip)]
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every call the authorization policy lets through.
+    #[serde(skip)]
+    pub middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional audit log recording every call.
+    #[serde(skip)]
+    pub audit: Option<Arc<Auditor>>,
+    /// Optional access log with a JSON line per call.
+    #[serde(skip)]
+    pub access_log: Option<Arc<AccessLog>>,
+    /// Optional hooks called as the stdio connection opens and closes.
+    #[serde(skip)]
+    pub observer: Option<Arc<dyn TransportObserver>>,
+    /// Optional capture of every request, response and notification.
+    #[serde(skip)]
+    pub capture: Option<Arc<Capture>>,
+    /// Optional Prometheus metrics recording every call.
+    #[cfg(feature = "metrics")]
+    #[serde(skip)]
+    pub metrics: Option<Arc<TransportMetrics>>,
+    /// Maximum length of a request line (or framed message) in bytes; longer
+    /// requests are discarded without being buffered and answered with a
+    /// JSON-RPC error.
+    pub max_line_length: usize,
+    /// Calls running longer than this are logged as slow (and counted, with
+    /// feature `metrics`); `None` disables the check.
+    #[serde(with = "serde_secs::option")]
+    pub slow_request_threshold: Option<Duration>,
+    /// After the reader reaches EOF, keep forwarding notifications of open
+    /// subscriptions until they end or this long has passed; `None` returns
+    /// at once, abandoning them.
+    #[serde(with = "serde_secs::option")]
+    pub eof_grace: Option<Duration>,
+    /// Calls running longer than this are dropped and answered with a
+    /// `TIMEOUT_ERROR_CODE` error; `None` lets them run.
+    #[serde(with = "serde_secs::option")]
+    pub request_timeout: Option<Duration>,
+    /// Time limits of calls, by method, taking precedence over
+    /// `request_timeout`; none by default.
+    pub request_timeouts: ToolTimeouts,
+    /// Stop serving when the parent process exits, even if stdin stays open
+    /// (Unix only).
+    pub exit_on_parent_death: bool,
+    /// Speak MCP through the activation's MCP bridge instead of exposing its
+    /// RPC methods as plain JSON-RPC (feature `mcp-http`).
+    pub mcp: bool,
+    /// Point the process's stdout at stderr while serving, writing the
+    /// protocol to a duplicate of the original, so stray prints and stdout
+    /// loggers cannot corrupt it (Unix only).
+    pub stdout_guard: bool,
 }
 
 impl Default for StdioConfig {
     fn default() -> Self {
         Self {
             subscription_buffer_size: 1024,
+            notification_overflow: NotificationOverflow::default(),
+            framing: StdioFraming::default(),
+            authorization: None,
+            middleware: Vec::new(),
+            audit: None,
+            access_log: None,
+            observer: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            max_line_length: DEFAULT_MAX_PAYLOAD_SIZE,
+            slow_request_threshold: None,
+            eof_grace: None,
+            request_timeout: None,
+            request_timeouts: ToolTimeouts::default(),
+            exit_on_parent_death: false,
+            stdout_guard: false,
+            mcp: false,
         }
     }
 }
 
+impl StdioConfig {
+    /// Check every call against an authorization policy; denied calls get a JSON-RPC error
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every call, after the ones added before it
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Record every call in an audit log
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per call to an access log
+    pub fn with_access_log(mut self, log: Arc<AccessLog>) -> Self {
+        self.access_log = Some(log);
+        self
+    }
+
+    /// Report the connection opening and closing to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn TransportObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Capture every request, response and notification, e.g. to replay them later
+    pub fn with_capture(mut self, capture: Arc<Capture>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Record every call in Prometheus metrics
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<TransportMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log calls still running after `threshold` as slow
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Reject request lines longer than `bytes`
+    pub fn with_max_line_length(mut self, bytes: usize) -> Self {
+        self.max_line_length = bytes;
+        self
+    }
+
+    /// Delimit messages with `framing` instead of newlines
+    pub fn with_framing(mut self, framing: StdioFraming) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Handle notifications beyond `subscription_buffer_size` per subscription with `overflow`
+    pub fn with_notification_overflow(mut self, overflow: NotificationOverflow) -> Self {
+        self.notification_overflow = overflow;
+        self
+    }
+
+    /// At EOF, wait up to `grace` for open subscriptions to deliver their notifications
+    pub fn with_eof_grace(mut self, grace: Duration) -> Self {
+        self.eof_grace = Some(grace);
+        self
+    }
+
+    /// Drop calls running longer than `timeout`, answering them with an error
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Fail calls that run longer than their limit in `timeouts`
+    pub fn with_request_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.request_timeouts = timeouts;
+        self
+    }
+
+    /// Stop serving when the process that started this one exits
+    pub fn with_exit_on_parent_death(mut self) -> Self {
+        self.exit_on_parent_death = true;
+        self
+    }
+
+    /// Serve MCP (`initialize`, `tools/list`, `tools/call`, ...) instead of plain JSON-RPC
+    pub fn with_mcp(mut self) -> Self {
+        self.mcp = true;
+        self
+    }
+
+    /// Send everything else written to stdout to stderr, reserving it for the protocol
+    pub fn with_stdout_guard(mut self) -> Self {
+        self.stdout_guard = true;
+        self
+    }
+}
+
+/// What the stdio transport does with a notification while its subscription's
+/// buffer is full, because the client reads slower than the publisher sends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationOverflow {
+    /// Hold the publisher back until the client catches up
+    #[default]
+    Block,
+    /// Drop the oldest buffered notification to make room
+    DropOldest,
+    /// Drop the new notification
+    DropNewest,
+    /// Close the subscription and send the client an error notification
+    Close,
+}
+
+/// How the stdio transport delimits JSON-RPC messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StdioFraming {
+    /// One message per line (MCP stdio)
+    #[default]
+    Lines,
+    /// A `Content-Length: N` header, a blank line and `N` bytes of message,
+    /// as in LSP; messages may contain newlines
+    ContentLength,
+    /// A 4-byte big-endian length, then that many bytes of message; no
+    /// header parsing or scanning for delimiters
+    LengthPrefixed,
+}
+
+/// Request logging verbosity for HTTP transports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestLogging {
+    /// No per-request logging
+    Off,
+    /// One info line per request: method, URI, status, latency
+    #[default]
+    Summary,
+    /// The summary plus headers and body at debug level, masked per the
+    /// configured [`Redaction`]
+    Full,
+}
+
+/// A tower layer added to an HTTP transport's router
+///
+/// Built from any layer axum's [`Router::layer`](axum::Router::layer) accepts
+/// (tracing, timeouts, compression, custom authentication, ...) and type-erased
+/// so configurations stay `Clone`.
+#[cfg(feature = "mcp-http")]
+#[derive(Clone)]
+pub struct RouterLayer(Arc<dyn Fn(axum::Router) -> axum::Router + Send + Sync>);
+
+#[cfg(feature = "mcp-http")]
+impl std::fmt::Debug for RouterLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RouterLayer")
+    }
+}
+
+#[cfg(feature = "mcp-http")]
+impl RouterLayer {
+    /// Type-erase `layer`
+    pub fn new<L>(layer: L) -> Self
+    where
+        L: tower::Layer<axum::routing::Route> + Clone + Send + Sync + 'static,
+        L::Service: tower::Service<axum::extract::Request> + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Response: axum::response::IntoResponse + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Error: Into<std::convert::Infallible> + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Future: Send + 'static,
+    {
+        Self(Arc::new(move |router: axum::Router| router.layer(layer.clone())))
+    }
+
+    /// Wrap `router` in the layer
+    pub(crate) fn apply(&self, router: axum::Router) -> axum::Router {
+        (self.0)(router)
+    }
+}
+
 /// MCP HTTP server configuration
-#[derive(Debug, Clone)]
+///
+/// Missing fields deserialize to the defaults of `McpHttpConfig::new(0)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default = "McpHttpConfig::unbound")]
 pub struct McpHttpConfig {
     pub addr: SocketAddr,
+    /// Path the MCP endpoint is served at (default `/mcp`), e.g. `/v1/mcp`
+    pub path: String,
+    /// Extra routes served on the same listener, e.g. a status page. They
+    /// are not authenticated or rate limited; custom layers, origin
+    /// validation and CORS apply.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub routes: Option<axum::Router>,
     pub session_storage: SessionStorage,
+    /// Answer every POST on its own, without sessions, so any replica behind a
+    /// round-robin load balancer can serve any request. GET and DELETE get
+    /// 405, and session storage, TTL and administration are unused.
+    pub stateless: bool,
+    /// Also serve the HTTP+SSE transport of protocol version 2024-11-05
+    /// (`GET /sse` and `POST /messages`) for clients that predate Streamable
+    /// HTTP.
+    pub legacy_sse: bool,
+    /// Interval between keep-alive comments on SSE streams, which stop
+    /// proxies from closing quiet streams; `None` sends none.
+    #[serde(with = "serde_secs::option")]
+    pub sse_keep_alive: Option<Duration>,
+    /// Messages buffered per session stream before the session waits for the
+    /// client to read; also how many events a session keeps for resumption.
+    pub session_channel_capacity: usize,
+    /// Sessions without requests or open streams for this long are closed by a
+    /// background task; `None` keeps sessions until their clients delete them.
+    #[serde(with = "serde_secs::option")]
+    pub session_ttl: Option<Duration>,
+    /// Interval between `ping` requests to sessions with an open SSE stream;
+    /// sessions not answering within the interval are closed. `None` sends none.
+    #[serde(with = "serde_secs::option")]
+    pub ping_interval: Option<Duration>,
+    /// Optional handle listing and terminating the endpoint's sessions.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub session_admin: Option<Arc<crate::mcp::admin::SessionAdmin>>,
+    /// Connection and vacuum settings for SQLite session storage.
+    #[cfg(feature = "sqlite-sessions")]
+    pub sqlite_tuning: crate::mcp::session::SqliteTuning,
     /// Optional override for server name (defaults to activation namespace)
     pub server_name: Option<String>,
     /// Optional override for server version (defaults to activation version)
     pub server_version: Option<String>,
+    /// Optional usage instructions for the client's LLM (defaults to activation description)
+    pub instructions: Option<String>,
+    /// The MCP capabilities the server may declare; all by default.
+    pub capabilities: crate::mcp::capabilities::McpCapabilities,
     /// Optional bearer token required on all MCP HTTP requests.
     pub api_key: Option<String>,
+    /// Optional API key authentication (key set or async validator).
+    /// Takes precedence over `api_key` when both are set.
+    #[serde(skip)]
+    pub auth: Option<ApiKeyAuth>,
+    /// Optional JWT bearer validation (static secret or JWKS).
+    #[cfg(feature = "jwt-auth")]
+    #[serde(skip)]
+    pub jwt: Option<JwtAuth>,
+    /// Optional OAuth 2.1 protected-resource setup (MCP authorization spec).
+    /// Its token validator takes precedence over `jwt`.
+    #[cfg(all(feature = "mcp-http", feature = "jwt-auth"))]
+    #[serde(skip)]
+    pub oauth: Option<crate::mcp::oauth::OAuthResourceConfig>,
+    /// Optional TLS certificate/key; when set the MCP endpoint is served over HTTPS.
+    #[cfg(feature = "tls")]
+    pub tls: Option<TlsConfig>,
+    /// Optional per-method authorization policy, consulted on every tool call.
+    #[serde(skip)]
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every tool call the authorization policy lets through.
+    #[serde(skip)]
+    pub middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional audit log recording every tool call.
+    #[serde(skip)]
+    pub audit: Option<Arc<Auditor>>,
+    /// Optional access log with a JSON line per tool call.
+    #[serde(skip)]
+    pub access_log: Option<Arc<AccessLog>>,
+    /// Optional hooks called as MCP sessions are created and expire.
+    #[serde(skip)]
+    pub observer: Option<Arc<dyn TransportObserver>>,
+    /// Optional capture of every tool call, its result and notifications.
+    #[serde(skip)]
+    pub capture: Option<Arc<Capture>>,
+    /// Optional Prometheus metrics recording every tool call and open session.
+    #[cfg(feature = "metrics")]
+    #[serde(skip)]
+    pub metrics: Option<Arc<TransportMetrics>>,
+    /// Optional include/exclude patterns limiting which methods become MCP tools.
+    pub tool_filter: Option<crate::mcp::filter::ToolFilter>,
+    /// The names MCP clients see for `namespace.method` tools.
+    #[serde(skip)]
+    pub tool_naming: crate::mcp::naming::ToolNaming,
+    /// Time limits of tool calls, by tool; none by default.
+    pub tool_timeouts: crate::mcp::timeout::ToolTimeouts,
+    /// Stream each data item of a tool call to clients that passed a progress
+    /// token, as progress messages, before the final result.
+    pub partial_results: bool,
+    /// Keep only the last this many bytes of a tool call's output in its final
+    /// result; `None` returns all of it.
+    pub max_result_bytes: Option<usize>,
+    /// Tools per `tools/list` page; `None` lists every tool in one response.
+    pub tools_page_size: Option<usize>,
+    /// Optional notifier telling MCP sessions that the tool list changed.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub tool_list: Option<crate::mcp::tool_list::ToolListNotifier>,
+    /// Optional server-wide notifications, sent to every initialized session.
+    #[serde(skip)]
+    pub broadcaster: Option<Broadcaster>,
+    /// Optional tool input schemas, overriding those of the activation.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub schema_provider: Option<Arc<dyn crate::mcp::schema::SchemaProvider>>,
+    /// Optional tool titles, descriptions and annotations.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub tool_metadata: Option<Arc<dyn crate::mcp::metadata::ToolMetadataProvider>>,
+    /// Optional source of MCP resources, served by `resources/list` and `resources/read`.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub resources: Option<Arc<dyn crate::mcp::resources::ResourceProvider>>,
+    /// Optional source of MCP prompt templates, served by `prompts/list` and `prompts/get`.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub prompts: Option<Arc<dyn crate::mcp::prompts::PromptProvider>>,
+    /// Optional source of argument suggestions, served by `completion/complete`.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub completions: Option<Arc<dyn crate::mcp::completion::CompletionProvider>>,
+    /// Optional translation of activation errors into MCP errors and tool results.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub error_mapper: Option<Arc<dyn crate::mcp::error_map::ErrorMapper>>,
+    /// Optional forwarder of tracing events to sessions that send `logging/setLevel`.
+    #[cfg(feature = "mcp-logging")]
+    #[serde(skip)]
+    pub log_forwarder: Option<Arc<crate::mcp::logging::McpLogForwarder>>,
+    /// Optional per-client rate limit, applied to every HTTP request.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Optional CIDR allow/deny lists, enforced when connections are accepted.
+    pub ip_filter: Option<IpFilter>,
+    /// Optional CORS policy for browser clients (MCP inspectors, web frontends).
+    pub cors: Option<CorsConfig>,
+    /// `Origin` header validation (DNS-rebinding protection); by default only
+    /// localhost origins are accepted on loopback and wildcard binds. CORS
+    /// origins are always accepted.
+    pub origin_check: OriginCheck,
+    /// Maximum request body size in bytes; larger requests get HTTP 413.
+    pub max_body_size: usize,
+    /// Headers and JSON body fields masked in request logs.
+    pub redaction: Redaction,
+    /// How much of each request is logged (default: a one-line summary).
+    pub request_logging: RequestLogging,
+    /// Calls running longer than this are logged as slow (and counted, with
+    /// feature `metrics`); `None` disables the check.
+    #[serde(with = "serde_secs::option")]
+    pub slow_request_threshold: Option<Duration>,
+    /// Custom tower layers, applied in order around the built-in request
+    /// middleware and inside origin validation and CORS.
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    pub layers: Vec<RouterLayer>,
 }
 
 impl McpHttpConfig {
@@ -82,13 +1039,376 @@ impl McpHttpConfig {
             addr: format!("127.0.0.1:{}", port)
                 .parse()
                 .expect("Valid socket address"),
+            path: DEFAULT_MCP_PATH.to_string(),
+            #[cfg(feature = "mcp-http")]
+            routes: None,
             session_storage: SessionStorage::default(),
+            stateless: false,
+            legacy_sse: false,
+            sse_keep_alive: Some(DEFAULT_SSE_KEEP_ALIVE),
+            session_channel_capacity: DEFAULT_SESSION_CHANNEL_CAPACITY,
+            session_ttl: None,
+            ping_interval: None,
+            #[cfg(feature = "mcp-http")]
+            session_admin: None,
+            #[cfg(feature = "sqlite-sessions")]
+            sqlite_tuning: Default::default(),
             server_name: None,
             server_version: None,
+            instructions: None,
+            capabilities: Default::default(),
             api_key: None,
+            auth: None,
+            #[cfg(feature = "jwt-auth")]
+            jwt: None,
+            #[cfg(all(feature = "mcp-http", feature = "jwt-auth"))]
+            oauth: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+            authorization: None,
+            middleware: Vec::new(),
+            audit: None,
+            access_log: None,
+            observer: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            tool_filter: None,
+            tool_naming: Default::default(),
+            tool_timeouts: Default::default(),
+            partial_results: false,
+            max_result_bytes: None,
+            tools_page_size: None,
+            #[cfg(feature = "mcp-http")]
+            tool_list: None,
+            broadcaster: None,
+            #[cfg(feature = "mcp-http")]
+            schema_provider: None,
+            #[cfg(feature = "mcp-http")]
+            tool_metadata: None,
+            #[cfg(feature = "mcp-http")]
+            resources: None,
+            #[cfg(feature = "mcp-http")]
+            prompts: None,
+            #[cfg(feature = "mcp-http")]
+            completions: None,
+            #[cfg(feature = "mcp-http")]
+            error_mapper: None,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: None,
+            rate_limit: None,
+            ip_filter: None,
+            cors: None,
+            origin_check: OriginCheck::default(),
+            max_body_size: DEFAULT_MAX_PAYLOAD_SIZE,
+            redaction: Redaction::default(),
+            request_logging: RequestLogging::default(),
+            slow_request_threshold: None,
+            #[cfg(feature = "mcp-http")]
+            layers: Vec::new(),
         }
     }
 
+    /// Configuration listening on `addr`, e.g. `0.0.0.0:4445` or `[::]:4445`
+    pub fn from_addr(addr: SocketAddr) -> Self {
+        Self::new(addr.port()).with_addr(addr)
+    }
+
+    fn unbound() -> Self {
+        Self::new(0)
+    }
+
+    /// Listen on `addr` instead of `127.0.0.1:<port>`
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Listen on `host` (an IP such as `0.0.0.0`, `::` or `[::]`, or a hostname),
+    /// keeping the configured port
+    pub fn with_host(mut self, host: &str) -> std::io::Result<Self> {
+        self.addr = resolve_host(host, self.addr.port())?;
+        Ok(self)
+    }
+
+    /// Serve the MCP endpoint at `path` instead of `/mcp`
+    ///
+    /// A missing leading slash is added and a trailing slash removed.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = normalize_path(&path.into());
+        self
+    }
+
+    /// Serve `routes` on the same listener as the MCP endpoint
+    ///
+    /// Routes from repeated calls are merged; like [`axum::Router::merge`],
+    /// this panics if two routers (or the MCP endpoint, or `/debug`) claim
+    /// the same path, or if `routes` has a fallback. Apply the application's
+    /// own authentication to `routes` if they need it.
+    #[cfg(feature = "mcp-http")]
+    pub fn with_routes(mut self, routes: axum::Router) -> Self {
+        self.routes = Some(match self.routes.take() {
+            Some(existing) => existing.merge(routes),
+            None => routes,
+        });
+        self
+    }
+
+    /// Require API key authentication on all MCP HTTP requests
+    pub fn with_auth(mut self, auth: ApiKeyAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Require a valid JWT bearer token on all MCP HTTP requests
+    #[cfg(feature = "jwt-auth")]
+    pub fn with_jwt(mut self, jwt: JwtAuth) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    /// Check every tool call against an authorization policy; tools the caller
+    /// may not call are also hidden from `tools/list`
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every tool call, after the ones added before it
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Record every call in an audit log
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per call to an access log
+    pub fn with_access_log(mut self, log: Arc<AccessLog>) -> Self {
+        self.access_log = Some(log);
+        self
+    }
+
+    /// Report sessions being created and expiring to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn TransportObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Capture every tool call, its result and notifications, e.g. to replay them later
+    pub fn with_capture(mut self, capture: Arc<Capture>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Record every call in Prometheus metrics
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<TransportMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log calls still running after `threshold` as slow
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Only expose methods allowed by `filter` as MCP tools
+    pub fn with_tool_filter(mut self, filter: crate::mcp::filter::ToolFilter) -> Self {
+        self.tool_filter = Some(filter);
+        self
+    }
+
+    /// Name tools for MCP clients with `naming`, e.g. `namespace_method` for
+    /// clients that reject dots in tool names
+    pub fn with_tool_naming(mut self, naming: crate::mcp::naming::ToolNaming) -> Self {
+        self.tool_naming = naming;
+        self
+    }
+
+    /// Fail tool calls that run longer than their limit in `timeouts`
+    pub fn with_tool_timeouts(mut self, timeouts: crate::mcp::timeout::ToolTimeouts) -> Self {
+        self.tool_timeouts = timeouts;
+        self
+    }
+
+    /// Stream tool output to MCP clients as it is produced
+    pub fn with_partial_results(mut self) -> Self {
+        self.partial_results = true;
+        self
+    }
+
+    /// Return at most the last `bytes` of a tool call's output in its final result
+    pub fn with_max_result_bytes(mut self, bytes: usize) -> Self {
+        self.max_result_bytes = Some(bytes);
+        self
+    }
+
+    /// Paginate `tools/list`, `size` tools per page
+    pub fn with_tools_page_size(mut self, size: usize) -> Self {
+        self.tools_page_size = Some(size);
+        self
+    }
+
+    /// Tell MCP sessions to list tools again whenever `notifier` fires
+    #[cfg(feature = "mcp-http")]
+    pub fn with_tool_list_notifier(mut self, notifier: crate::mcp::tool_list::ToolListNotifier) -> Self {
+        self.tool_list = Some(notifier);
+        self
+    }
+
+    /// Send the notifications of `broadcaster` to every initialized session
+    pub fn with_broadcaster(mut self, broadcaster: Broadcaster) -> Self {
+        self.broadcaster = Some(broadcaster);
+        self
+    }
+
+    /// Advertise the tool input schemas of `provider` where it has one
+    #[cfg(feature = "mcp-http")]
+    pub fn with_schema_provider(mut self, provider: Arc<dyn crate::mcp::schema::SchemaProvider>) -> Self {
+        self.schema_provider = Some(provider);
+        self
+    }
+
+    /// Describe tools with the titles, descriptions and annotations of `provider`
+    #[cfg(feature = "mcp-http")]
+    pub fn with_tool_metadata_provider(
+        mut self,
+        provider: Arc<dyn crate::mcp::metadata::ToolMetadataProvider>,
+    ) -> Self {
+        self.tool_metadata = Some(provider);
+        self
+    }
+
+    /// Serve the resources of `provider`, e.g. the activation itself, to MCP clients
+    #[cfg(feature = "mcp-http")]
+    pub fn with_resource_provider(mut self, provider: Arc<dyn crate::mcp::resources::ResourceProvider>) -> Self {
+        self.resources = Some(provider);
+        self
+    }
+
+    /// Serve the prompt templates of `provider` to MCP clients
+    #[cfg(feature = "mcp-http")]
+    pub fn with_prompt_provider(mut self, provider: Arc<dyn crate::mcp::prompts::PromptProvider>) -> Self {
+        self.prompts = Some(provider);
+        self
+    }
+
+    /// Suggest prompt and resource template argument values with `provider`
+    #[cfg(feature = "mcp-http")]
+    pub fn with_completion_provider(mut self, provider: Arc<dyn crate::mcp::completion::CompletionProvider>) -> Self {
+        self.completions = Some(provider);
+        self
+    }
+
+    /// Translate activation errors for MCP clients with `mapper`
+    #[cfg(feature = "mcp-http")]
+    pub fn with_error_mapper(mut self, mapper: Arc<dyn crate::mcp::error_map::ErrorMapper>) -> Self {
+        self.error_mapper = Some(mapper);
+        self
+    }
+
+    /// Forward the tracing events `forwarder` collects to MCP clients that
+    /// send `logging/setLevel`, at the level they ask for
+    #[cfg(feature = "mcp-logging")]
+    pub fn with_log_forwarder(mut self, forwarder: Arc<crate::mcp::logging::McpLogForwarder>) -> Self {
+        self.log_forwarder = Some(forwarder);
+        self
+    }
+
+    /// Rate limit requests per client; requests over the limit get HTTP 429
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Only accept connections from networks allowed by `filter`
+    pub fn with_ip_filter(mut self, filter: IpFilter) -> Self {
+        self.ip_filter = Some(filter);
+        self
+    }
+
+    /// Answer CORS preflights and decorate responses per `cors`
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = Some(cors);
+        self
+    }
+
+    /// Accept browser requests from these origins in addition to localhost
+    pub fn with_allowed_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.origin_check = OriginCheck::Allow(origins.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set how the `Origin` header is validated
+    pub fn with_origin_check(mut self, check: OriginCheck) -> Self {
+        self.origin_check = check;
+        self
+    }
+
+    /// Reject request bodies larger than `bytes`
+    pub fn with_max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// Set which headers and JSON body fields are masked in request logs
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Set how much of each request is logged
+    pub fn with_request_logging(mut self, logging: RequestLogging) -> Self {
+        self.request_logging = logging;
+        self
+    }
+
+    /// Wrap the MCP router in a tower layer; later layers wrap earlier ones.
+    /// They run after origin validation and CORS, before authentication.
+    #[cfg(feature = "mcp-http")]
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<axum::routing::Route> + Clone + Send + Sync + 'static,
+        L::Service: tower::Service<axum::extract::Request> + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Response: axum::response::IntoResponse + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Error: Into<std::convert::Infallible> + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Future: Send + 'static,
+    {
+        self.layers.push(RouterLayer::new(layer));
+        self
+    }
+
+    /// Act as an OAuth 2.1 protected resource: publish resource metadata,
+    /// challenge unauthenticated clients, and validate access tokens
+    #[cfg(all(feature = "mcp-http", feature = "jwt-auth"))]
+    pub fn with_oauth(mut self, oauth: crate::mcp::oauth::OAuthResourceConfig) -> Self {
+        self.oauth = Some(oauth);
+        self
+    }
+
+    /// Serve the MCP endpoint over HTTPS using the PEM certificate chain and private key
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        self.tls = Some(TlsConfig::new(cert_path, key_path));
+        self
+    }
+
+    /// Serve TLS with a fully specified configuration (e.g. mutual TLS)
+    #[cfg(feature = "tls")]
+    pub fn with_tls_config(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     /// Override the server name reported in MCP server info
     pub fn with_server_name(mut self, name: String) -> Self {
         self.server_name = Some(name);
@@ -101,21 +1421,264 @@ impl McpHttpConfig {
         self
     }
 
+    /// Tell clients' LLMs how to use the server's tools, instead of sending
+    /// the activation's description
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Only declare and serve the MCP capabilities turned on in `capabilities`
+    pub fn with_capabilities(mut self, capabilities: crate::mcp::capabilities::McpCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     #[cfg(feature = "sqlite-sessions")]
     pub fn with_sqlite(mut self, path: PathBuf) -> Self {
         self.session_storage = SessionStorage::Sqlite { path };
         self
     }
+
+    /// Tune the SQLite session database (journal mode, busy timeout, pool
+    /// size, vacuum schedule)
+    #[cfg(feature = "sqlite-sessions")]
+    pub fn with_sqlite_tuning(mut self, tuning: crate::mcp::session::SqliteTuning) -> Self {
+        self.sqlite_tuning = tuning;
+        self
+    }
+
+    /// Serve MCP without sessions, e.g. behind a round-robin load balancer
+    ///
+    /// Each request runs against a fresh server, so there is no standalone SSE
+    /// stream for server-initiated notifications.
+    pub fn with_stateless(mut self) -> Self {
+        self.stateless = true;
+        self
+    }
+
+    /// Also serve older MCP clients over the HTTP+SSE transport
+    ///
+    /// Such clients open an SSE stream at `GET /sse` and POST their messages
+    /// to the `/messages?sessionId=...` URL it announces. Both routes sit next
+    /// to the Streamable HTTP endpoint, behind the same authentication and
+    /// rate limits; their sessions live in memory and are not covered by
+    /// session storage, TTL or administration.
+    pub fn with_legacy_sse(mut self) -> Self {
+        self.legacy_sse = true;
+        self
+    }
+
+    /// Send keep-alive comments on SSE streams every `interval`, or never
+    pub fn with_sse_keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.sse_keep_alive = interval;
+        self
+    }
+
+    /// Buffer up to `capacity` messages per session stream
+    pub fn with_session_channel_capacity(mut self, capacity: usize) -> Self {
+        self.session_channel_capacity = capacity;
+        self
+    }
+
+    /// Close sessions that have been idle for `ttl`
+    ///
+    /// Idle SQLite sessions are deleted from the database as well.
+    pub fn with_session_ttl(mut self, ttl: Duration) -> Self {
+        self.session_ttl = Some(ttl);
+        self
+    }
+
+    /// Ping sessions with an open SSE stream every `interval`, and close
+    /// those whose clients do not answer within it
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Let `admin` list and terminate this endpoint's sessions
+    #[cfg(feature = "mcp-http")]
+    pub fn with_session_admin(mut self, admin: Arc<crate::mcp::admin::SessionAdmin>) -> Self {
+        self.session_admin = Some(admin);
+        self
+    }
+
+    /// Keep sessions in Redis at `url`, with the default key prefix and TTL
+    #[cfg(feature = "redis-sessions")]
+    pub fn with_redis(mut self, url: impl Into<String>) -> Self {
+        self.session_storage = SessionStorage::redis(url);
+        self
+    }
+}
+
+/// CORS policy for an HTTP transport
+///
+/// Converted into a tower-http `CorsLayer`. The default allowed and exposed
+/// headers cover what MCP clients need (`Mcp-Session-Id`, `Mcp-Protocol-Version`,
+/// `Last-Event-ID`, `Authorization`, `X-Api-Key`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Allowed origins (e.g. `https://inspector.example.com`); empty allows any origin
+    pub allowed_origins: Vec<String>,
+    /// Request headers browsers may send
+    pub allowed_headers: Vec<String>,
+    /// Response headers browser scripts may read
+    pub exposed_headers: Vec<String>,
+    /// Whether cookies / `Authorization` may be sent cross-origin
+    pub allow_credentials: bool,
+    /// How long browsers may cache preflight responses (seconds when serialized)
+    #[serde(with = "serde_secs::option")]
+    pub max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_headers: [
+                "content-type",
+                "accept",
+                "authorization",
+                "x-api-key",
+                "mcp-session-id",
+                "mcp-protocol-version",
+                "last-event-id",
+            ]
+            .map(String::from)
+            .to_vec(),
+            exposed_headers: ["mcp-session-id", "www-authenticate"].map(String::from).to_vec(),
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Allow the given origins with the default MCP headers
+    pub fn new<I, S>(origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed_origins: origins.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Allow any origin (without credentials)
+    pub fn permissive() -> Self {
+        Self::default()
+    }
+
+    /// Allow additional request headers
+    pub fn with_allowed_headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_headers.extend(headers.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allow cookies and `Authorization` on cross-origin requests
+    ///
+    /// Browsers refuse credentials with a wildcard origin, so with an empty
+    /// origin list the request's own origin is echoed back instead.
+    pub fn with_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// Let browsers cache preflight responses for `max_age`
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Build the tower-http layer; invalid origins or header names are skipped with a warning
+    #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+    pub fn layer(&self) -> tower_http::cors::CorsLayer {
+        use tower_http::cors::{AllowOrigin, CorsLayer};
+
+        let origin = if !self.allowed_origins.is_empty() {
+            AllowOrigin::list(parse_all(&self.allowed_origins, "origin"))
+        } else if self.allow_credentials {
+            AllowOrigin::mirror_request()
+        } else {
+            AllowOrigin::any()
+        };
+
+        let mut layer = CorsLayer::new()
+            .allow_origin(origin)
+            .allow_methods([
+                http::Method::GET,
+                http::Method::POST,
+                http::Method::DELETE,
+                http::Method::OPTIONS,
+            ])
+            .allow_headers(parse_all::<http::HeaderName>(&self.allowed_headers, "header"))
+            .expose_headers(parse_all::<http::HeaderName>(&self.exposed_headers, "header"))
+            .allow_credentials(self.allow_credentials);
+        if let Some(max_age) = self.max_age {
+            layer = layer.max_age(max_age);
+        }
+        layer
+    }
+}
+
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+fn parse_all<T: std::str::FromStr>(values: &[String], what: &str) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|value| match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid CORS {} {:?}", what, value);
+                None
+            }
+        })
+        .collect()
 }
 
 /// Session storage backend for MCP
-#[derive(Debug, Clone)]
+///
+/// Serialized as `{"type": "in_memory"}`, `{"type": "sqlite", "path": "..."}` or
+/// `{"type": "redis", "url": "...", "key_prefix": "...", "ttl": 86400.0}`.
+/// `Custom` stores are set in code and cannot be serialized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum SessionStorage {
     /// In-memory sessions (lost on restart, simpler)
     InMemory,
     /// SQLite persistent sessions (survive restarts)
     #[cfg(feature = "sqlite-sessions")]
     Sqlite { path: PathBuf },
+    /// Redis sessions (shared between replicas, expire after `ttl` idle)
+    #[cfg(feature = "redis-sessions")]
+    Redis {
+        url: String,
+        key_prefix: String,
+        #[serde(with = "serde_secs")]
+        ttl: Duration,
+    },
+    /// Sessions kept in a store of your own
+    #[cfg(feature = "mcp-http")]
+    #[serde(skip)]
+    Custom(std::sync::Arc<dyn crate::mcp::store::SessionStore>),
+}
+
+impl SessionStorage {
+    /// Redis sessions at `url` with the default key prefix and TTL
+    #[cfg(feature = "redis-sessions")]
+    pub fn redis(url: impl Into<String>) -> Self {
+        Self::Redis {
+            url: url.into(),
+            key_prefix: crate::mcp::redis_session::DEFAULT_KEY_PREFIX.to_string(),
+            ttl: crate::mcp::redis_session::DEFAULT_SESSION_TTL,
+        }
+    }
 }
 
 impl Default for SessionStorage {
@@ -125,11 +1688,41 @@ impl Default for SessionStorage {
 }
 
 /// REST HTTP server configuration
-#[derive(Debug, Clone)]
+///
+/// Missing fields deserialize to the defaults of `RestHttpConfig::new(0)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default = "RestHttpConfig::unbound")]
 pub struct RestHttpConfig {
     pub addr: SocketAddr,
     pub server_name: String,
     pub server_version: String,
+    /// Optional per-method authorization policy; denied calls get HTTP 403.
+    #[serde(skip)]
+    pub authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every call the authorization policy lets through.
+    #[serde(skip)]
+    pub middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional audit log recording every call.
+    #[serde(skip)]
+    pub audit: Option<Arc<Auditor>>,
+    /// Optional access log with a JSON line per call.
+    #[serde(skip)]
+    pub access_log: Option<Arc<AccessLog>>,
+    /// Optional Prometheus metrics recording every call.
+    #[cfg(feature = "metrics")]
+    #[serde(skip)]
+    pub metrics: Option<Arc<TransportMetrics>>,
+    /// Optional per-client rate limit; requests over the limit get HTTP 429.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Optional CIDR allow/deny lists, enforced when connections are accepted.
+    pub ip_filter: Option<IpFilter>,
+    /// Calls running longer than this are logged as slow (and counted, with
+    /// feature `metrics`); `None` disables the check.
+    #[serde(with = "serde_secs::option")]
+    pub slow_request_threshold: Option<Duration>,
+    /// Time limits of calls, by method; calls running longer get HTTP 504.
+    /// None by default.
+    pub request_timeouts: ToolTimeouts,
 }
 
 impl RestHttpConfig {
@@ -140,9 +1733,96 @@ impl RestHttpConfig {
                 .expect("Valid socket address"),
             server_name: "plexus-rest".to_string(),
             server_version: env!("CARGO_PKG_VERSION").to_string(),
+            authorization: None,
+            middleware: Vec::new(),
+            audit: None,
+            access_log: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            rate_limit: None,
+            ip_filter: None,
+            slow_request_threshold: None,
+            request_timeouts: ToolTimeouts::default(),
         }
     }
 
+    /// Configuration listening on `addr`, e.g. `0.0.0.0:8888` or `[::]:8888`
+    pub fn from_addr(addr: SocketAddr) -> Self {
+        Self::new(addr.port()).with_addr(addr)
+    }
+
+    fn unbound() -> Self {
+        Self::new(0)
+    }
+
+    /// Listen on `addr` instead of `127.0.0.1:<port>`
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Listen on `host` (an IP such as `0.0.0.0`, `::` or `[::]`, or a hostname),
+    /// keeping the configured port
+    pub fn with_host(mut self, host: &str) -> std::io::Result<Self> {
+        self.addr = resolve_host(host, self.addr.port())?;
+        Ok(self)
+    }
+
+    /// Check every call against an authorization policy
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every call, after the ones added before it
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Record every call in an audit log
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per call to an access log
+    pub fn with_access_log(mut self, log: Arc<AccessLog>) -> Self {
+        self.access_log = Some(log);
+        self
+    }
+
+    /// Record every call in Prometheus metrics
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<TransportMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log calls still running after `threshold` as slow
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Fail calls that run longer than their limit in `timeouts`
+    pub fn with_request_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.request_timeouts = timeouts;
+        self
+    }
+
+    /// Rate limit requests per client; requests over the limit get HTTP 429
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Only accept connections from networks allowed by `filter`
+    pub fn with_ip_filter(mut self, filter: IpFilter) -> Self {
+        self.ip_filter = Some(filter);
+        self
+    }
+
     /// Override the server name
     pub fn with_server_name(mut self, name: String) -> Self {
         self.server_name = name;
@@ -155,3 +1835,75 @@ impl RestHttpConfig {
         self
     }
 }
+
+/// `path` with one leading slash and no trailing slash (`/` for the root)
+pub(crate) fn normalize_path(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+/// Serde representation of durations as (fractional) seconds
+pub(crate) mod serde_secs {
+    use std::time::Duration;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    pub(crate) mod map {
+        use std::collections::BTreeMap;
+        use std::time::Duration;
+
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub(crate) fn serialize<S: Serializer>(
+            durations: &BTreeMap<String, Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(durations.iter().map(|(key, duration)| (key, duration.as_secs_f64())))
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<BTreeMap<String, Duration>, D::Error> {
+            BTreeMap::<String, f64>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(key, secs)| {
+                    Duration::try_from_secs_f64(secs)
+                        .map(|duration| (key, duration))
+                        .map_err(serde::de::Error::custom)
+                })
+                .collect()
+        }
+    }
+
+    pub(crate) mod option {
+        use std::time::Duration;
+
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub(crate) fn serialize<S: Serializer>(
+            duration: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Option::<f64>::deserialize(deserializer)?
+                .map(|secs| Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom))
+                .transpose()
+        }
+    }
+}
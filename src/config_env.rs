@@ -0,0 +1,418 @@
+//! Overlaying a [`TransportConfig`] with `PLEXUS_*` environment variables
+//!
+//! Each transport is enabled by its port or address variable and can then be
+//! tuned further; variables for a transport that is neither enabled by the
+//! environment nor by the configuration underneath are an error. Empty
+//! variables are ignored.
+//!
+//! | Variable | Effect |
+//! |----------|--------|
+//! | `PLEXUS_API_KEY` | Bearer token required on every network transport |
+//! | `PLEXUS_SHUTDOWN_TIMEOUT_SECS` | Graceful shutdown timeout |
+//! | `PLEXUS_REQUEST_TIMEOUT_SECS` | Default time limit of calls on every transport |
+//! | `PLEXUS_WS_PORT`, `PLEXUS_WS_HOST`, `PLEXUS_WS_ADDR` | WebSocket listen address |
+//! | `PLEXUS_WS_API_KEY` | WebSocket bearer token |
+//! | `PLEXUS_WS_MAX_CONNECTIONS` | WebSocket connection limit |
+//! | `PLEXUS_WS_MAX_MESSAGE_SIZE` | WebSocket message size limit (bytes) |
+//! | `PLEXUS_STDIO` | `true` enables stdio, `false` disables it |
+//! | `PLEXUS_MCP_PORT`, `PLEXUS_MCP_HOST`, `PLEXUS_MCP_ADDR` | MCP HTTP listen address |
+//! | `PLEXUS_MCP_PATH` | Path of the MCP endpoint (default `/mcp`) |
+//! | `PLEXUS_MCP_API_KEY` | MCP HTTP bearer token |
+//! | `PLEXUS_MCP_SERVER_NAME` | Server name reported to MCP clients |
+//! | `PLEXUS_MCP_MAX_BODY_SIZE` | MCP HTTP body size limit (bytes) |
+//! | `PLEXUS_MCP_STATELESS` | `true` serves MCP without sessions |
+//! | `PLEXUS_MCP_LEGACY_SSE` | `true` also serves the HTTP+SSE transport |
+//! | `PLEXUS_MCP_SSE_KEEP_ALIVE_SECS` | SSE keep-alive interval; 0 disables keep-alives |
+//! | `PLEXUS_MCP_SESSION_TTL_SECS` | Close MCP sessions idle this long |
+//! | `PLEXUS_SESSIONS_SQLITE_PATH` | Persist MCP sessions in SQLite (feature `sqlite-sessions`) |
+//! | `PLEXUS_SESSIONS_REDIS_URL` | Share MCP sessions through Redis (feature `redis-sessions`) |
+//! | `PLEXUS_SESSIONS_REDIS_KEY_PREFIX`, `PLEXUS_SESSIONS_REDIS_TTL_SECS` | Redis session key prefix and TTL |
+//! | `PLEXUS_MCP_TLS_CERT_PATH`, `PLEXUS_MCP_TLS_KEY_PATH` | Serve MCP over HTTPS (feature `tls`) |
+//! | `PLEXUS_WS_TLS_CERT_PATH`, `PLEXUS_WS_TLS_KEY_PATH` | Serve `wss://` (feature `tls`) |
+//! | `PLEXUS_REST_PORT`, `PLEXUS_REST_HOST`, `PLEXUS_REST_ADDR` | REST HTTP listen address |
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::config::{
+    resolve_host, McpHttpConfig, RestHttpConfig, TransportConfig, WebSocketConfig,
+};
+
+/// Every variable [`TransportConfig::merge_env`] understands
+const KNOWN_VARS: &[&str] = &[
+    "PLEXUS_API_KEY",
+    "PLEXUS_SHUTDOWN_TIMEOUT_SECS",
+    "PLEXUS_REQUEST_TIMEOUT_SECS",
+    "PLEXUS_WS_PORT",
+    "PLEXUS_WS_HOST",
+    "PLEXUS_WS_ADDR",
+    "PLEXUS_WS_API_KEY",
+    "PLEXUS_WS_MAX_CONNECTIONS",
+    "PLEXUS_WS_MAX_MESSAGE_SIZE",
+    "PLEXUS_WS_TLS_CERT_PATH",
+    "PLEXUS_WS_TLS_KEY_PATH",
+    "PLEXUS_STDIO",
+    "PLEXUS_MCP_PORT",
+    "PLEXUS_MCP_HOST",
+    "PLEXUS_MCP_ADDR",
+    "PLEXUS_MCP_PATH",
+    "PLEXUS_MCP_API_KEY",
+    "PLEXUS_MCP_SERVER_NAME",
+    "PLEXUS_MCP_MAX_BODY_SIZE",
+    "PLEXUS_MCP_STATELESS",
+    "PLEXUS_MCP_LEGACY_SSE",
+    "PLEXUS_MCP_SSE_KEEP_ALIVE_SECS",
+    "PLEXUS_MCP_SESSION_TTL_SECS",
+    "PLEXUS_MCP_TLS_CERT_PATH",
+    "PLEXUS_MCP_TLS_KEY_PATH",
+    "PLEXUS_SESSIONS_SQLITE_PATH",
+    "PLEXUS_SESSIONS_REDIS_URL",
+    "PLEXUS_SESSIONS_REDIS_KEY_PREFIX",
+    "PLEXUS_SESSIONS_REDIS_TTL_SECS",
+    "PLEXUS_REST_PORT",
+    "PLEXUS_REST_HOST",
+    "PLEXUS_REST_ADDR",
+];
+
+/// An environment variable with an invalid value
+#[derive(Debug, Error)]
+#[error("{var}: {message}")]
+pub struct EnvConfigError {
+    /// The offending variable, e.g. `PLEXUS_WS_PORT`
+    pub var: String,
+    pub message: String,
+}
+
+impl EnvConfigError {
+    fn new(var: &str, message: impl std::fmt::Display) -> Self {
+        Self {
+            var: var.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl TransportConfig {
+    /// The default configuration overlaid with `PLEXUS_*` environment variables
+    pub fn from_env() -> Result<Self, EnvConfigError> {
+        Self::default().merge_env()
+    }
+
+    /// Overlay this configuration (e.g. one loaded from a file) with
+    /// `PLEXUS_*` environment variables
+    pub fn merge_env(self) -> Result<Self, EnvConfigError> {
+        self.merge_vars(std::env::vars())
+    }
+
+    /// Overlay this configuration with `PLEXUS_*` variables from `vars`
+    /// instead of the process environment
+    pub fn merge_vars<I, K, V>(mut self, vars: I) -> Result<Self, EnvConfigError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars: HashMap<String, String> = vars
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .filter(|(k, v)| k.starts_with("PLEXUS_") && !v.is_empty())
+            .collect();
+        for var in vars.keys() {
+            if !KNOWN_VARS.contains(&var.as_str()) {
+                tracing::warn!("Ignoring unknown environment variable {}", var);
+            }
+        }
+        let env = Env(vars);
+
+        if let Some(key) = env.get("PLEXUS_API_KEY") {
+            self.api_key = Some(key.to_string());
+        }
+        if let Some(secs) = env.parse("PLEXUS_SHUTDOWN_TIMEOUT_SECS")? {
+            self.shutdown_timeout = Duration::from_secs(secs);
+        }
+        // Per-method limits from the configuration underneath are kept
+        if let Some(secs) = env.parse("PLEXUS_REQUEST_TIMEOUT_SECS")? {
+            self.request_timeouts =
+                std::mem::take(&mut self.request_timeouts).with_default(Duration::from_secs(secs));
+        }
+
+        // WebSocket
+        let addr = env.listen_addr("PLEXUS_WS", self.websocket.as_ref().map(|c| c.addr))?;
+        if let Some(addr) = addr {
+            self.websocket = Some(match self.websocket.take() {
+                Some(config) => config.with_addr(addr),
+                None => WebSocketConfig::from_addr(addr),
+            });
+        }
+        env.require(
+            "PLEXUS_WS",
+            self.websocket.is_some(),
+            &[
+                "PLEXUS_WS_API_KEY",
+                "PLEXUS_WS_MAX_CONNECTIONS",
+                "PLEXUS_WS_MAX_MESSAGE_SIZE",
+                "PLEXUS_WS_TLS_CERT_PATH",
+                "PLEXUS_WS_TLS_KEY_PATH",
+            ],
+        )?;
+        if let Some(ref mut config) = self.websocket {
+            if let Some(key) = env.get("PLEXUS_WS_API_KEY") {
+                config.api_key = Some(key.to_string());
+            }
+            if let Some(max) = env.parse("PLEXUS_WS_MAX_CONNECTIONS")? {
+                config.max_connections = max;
+            }
+            if let Some(size) = env.parse("PLEXUS_WS_MAX_MESSAGE_SIZE")? {
+                config.max_message_size = size;
+            }
+            #[cfg(feature = "tls")]
+            if let Some(tls) = env.tls("PLEXUS_WS")? {
+                config.tls = Some(tls);
+            }
+        }
+        #[cfg(not(feature = "tls"))]
+        env.unsupported(
+            &["PLEXUS_WS_TLS_CERT_PATH", "PLEXUS_WS_TLS_KEY_PATH"],
+            "tls",
+        )?;
+
+        // Stdio
+        if let Some(enabled) = env.parse::<bool>("PLEXUS_STDIO")? {
+            self.stdio = match (enabled, self.stdio.take()) {
+                (true, existing) => Some(existing.unwrap_or_default()),
+                (false, _) => None,
+            };
+        }
+
+        // MCP HTTP
+        let addr = env.listen_addr("PLEXUS_MCP", self.mcp_http.as_ref().map(|c| c.addr))?;
+        if let Some(addr) = addr {
+            self.mcp_http = Some(match self.mcp_http.take() {
+                Some(config) => config.with_addr(addr),
+                None => McpHttpConfig::from_addr(addr),
+            });
+        }
+        env.require(
+            "PLEXUS_MCP",
+            self.mcp_http.is_some(),
+            &[
+                "PLEXUS_MCP_PATH",
+                "PLEXUS_MCP_API_KEY",
+                "PLEXUS_MCP_SERVER_NAME",
+                "PLEXUS_MCP_MAX_BODY_SIZE",
+                "PLEXUS_MCP_STATELESS",
+                "PLEXUS_MCP_LEGACY_SSE",
+                "PLEXUS_MCP_SSE_KEEP_ALIVE_SECS",
+                "PLEXUS_MCP_SESSION_TTL_SECS",
+                "PLEXUS_MCP_TLS_CERT_PATH",
+                "PLEXUS_MCP_TLS_KEY_PATH",
+                "PLEXUS_SESSIONS_SQLITE_PATH",
+                "PLEXUS_SESSIONS_REDIS_URL",
+                "PLEXUS_SESSIONS_REDIS_KEY_PREFIX",
+                "PLEXUS_SESSIONS_REDIS_TTL_SECS",
+            ],
+        )?;
+        if let Some(ref mut config) = self.mcp_http {
+            if let Some(path) = env.get("PLEXUS_MCP_PATH") {
+                config.path = crate::config::normalize_path(path);
+            }
+            if let Some(key) = env.get("PLEXUS_MCP_API_KEY") {
+                config.api_key = Some(key.to_string());
+            }
+            if let Some(name) = env.get("PLEXUS_MCP_SERVER_NAME") {
+                config.server_name = Some(name.to_string());
+            }
+            if let Some(size) = env.parse("PLEXUS_MCP_MAX_BODY_SIZE")? {
+                config.max_body_size = size;
+            }
+            if let Some(stateless) = env.parse::<bool>("PLEXUS_MCP_STATELESS")? {
+                config.stateless = stateless;
+            }
+            if let Some(legacy_sse) = env.parse::<bool>("PLEXUS_MCP_LEGACY_SSE")? {
+                config.legacy_sse = legacy_sse;
+            }
+            if let Some(secs) = env.parse::<u64>("PLEXUS_MCP_SSE_KEEP_ALIVE_SECS")? {
+                config.sse_keep_alive = (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            if let Some(secs) = env.parse("PLEXUS_MCP_SESSION_TTL_SECS")? {
+                config.session_ttl = Some(Duration::from_secs(secs));
+            }
+            #[cfg(feature = "tls")]
+            if let Some(tls) = env.tls("PLEXUS_MCP")? {
+                config.tls = Some(tls);
+            }
+            #[cfg(feature = "sqlite-sessions")]
+            if let Some(path) = env.get("PLEXUS_SESSIONS_SQLITE_PATH") {
+                config.session_storage =
+                    crate::config::SessionStorage::Sqlite { path: path.into() };
+            }
+            #[cfg(feature = "redis-sessions")]
+            if let Some(url) = env.get("PLEXUS_SESSIONS_REDIS_URL") {
+                config.session_storage = crate::config::SessionStorage::redis(url);
+            }
+            #[cfg(feature = "redis-sessions")]
+            {
+                let prefix = env.get("PLEXUS_SESSIONS_REDIS_KEY_PREFIX");
+                let ttl = env.parse::<u64>("PLEXUS_SESSIONS_REDIS_TTL_SECS")?;
+                match &mut config.session_storage {
+                    crate::config::SessionStorage::Redis { key_prefix, ttl: session_ttl, .. } => {
+                        if let Some(prefix) = prefix {
+                            *key_prefix = prefix.to_string();
+                        }
+                        if let Some(secs) = ttl {
+                            *session_ttl = Duration::from_secs(secs);
+                        }
+                    }
+                    _ if prefix.is_some() || ttl.is_some() => {
+                        let var = match prefix {
+                            Some(_) => "PLEXUS_SESSIONS_REDIS_KEY_PREFIX",
+                            None => "PLEXUS_SESSIONS_REDIS_TTL_SECS",
+                        };
+                        return Err(EnvConfigError::new(var, "set PLEXUS_SESSIONS_REDIS_URL to use Redis sessions"));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        #[cfg(not(feature = "tls"))]
+        env.unsupported(
+            &["PLEXUS_MCP_TLS_CERT_PATH", "PLEXUS_MCP_TLS_KEY_PATH"],
+            "tls",
+        )?;
+        #[cfg(not(feature = "sqlite-sessions"))]
+        env.unsupported(&["PLEXUS_SESSIONS_SQLITE_PATH"], "sqlite-sessions")?;
+        #[cfg(not(feature = "redis-sessions"))]
+        env.unsupported(
+            &[
+                "PLEXUS_SESSIONS_REDIS_URL",
+                "PLEXUS_SESSIONS_REDIS_KEY_PREFIX",
+                "PLEXUS_SESSIONS_REDIS_TTL_SECS",
+            ],
+            "redis-sessions",
+        )?;
+
+        // REST HTTP
+        let addr = env.listen_addr("PLEXUS_REST", self.rest_http.as_ref().map(|c| c.addr))?;
+        if let Some(addr) = addr {
+            self.rest_http = Some(match self.rest_http.take() {
+                Some(config) => config.with_addr(addr),
+                None => RestHttpConfig::from_addr(addr),
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+/// The non-empty `PLEXUS_*` variables
+struct Env(HashMap<String, String>);
+
+impl Env {
+    fn get(&self, var: &str) -> Option<&str> {
+        self.0.get(var).map(String::as_str)
+    }
+
+    fn parse<T>(&self, var: &str) -> Result<Option<T>, EnvConfigError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get(var)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e| EnvConfigError::new(var, e))
+            })
+            .transpose()
+    }
+
+    /// The listen address from `{prefix}_ADDR`, or `{prefix}_HOST` and
+    /// `{prefix}_PORT` over the currently configured address
+    fn listen_addr(
+        &self,
+        prefix: &str,
+        current: Option<SocketAddr>,
+    ) -> Result<Option<SocketAddr>, EnvConfigError> {
+        let addr_var = format!("{}_ADDR", prefix);
+        let host_var = format!("{}_HOST", prefix);
+        let port_var = format!("{}_PORT", prefix);
+
+        if let Some(addr) = self.parse::<SocketAddr>(&addr_var)? {
+            if self.get(&host_var).is_some() || self.get(&port_var).is_some() {
+                return Err(EnvConfigError::new(
+                    &addr_var,
+                    format!("cannot be combined with {} or {}", host_var, port_var),
+                ));
+            }
+            return Ok(Some(addr));
+        }
+
+        let port = match (self.parse::<u16>(&port_var)?, current) {
+            (Some(port), _) => port,
+            (None, Some(current)) if self.get(&host_var).is_some() => current.port(),
+            (None, _) if self.get(&host_var).is_some() => {
+                return Err(EnvConfigError::new(
+                    &host_var,
+                    format!("requires {}", port_var),
+                ));
+            }
+            (None, _) => return Ok(None),
+        };
+        match self.get(&host_var) {
+            Some(host) => resolve_host(host.trim(), port)
+                .map(Some)
+                .map_err(|e| EnvConfigError::new(&host_var, e)),
+            None => Ok(Some(SocketAddr::new(
+                current.map_or([127, 0, 0, 1].into(), |addr| addr.ip()),
+                port,
+            ))),
+        }
+    }
+
+    /// Reject settings for a transport that is not enabled
+    fn require(&self, prefix: &str, enabled: bool, vars: &[&str]) -> Result<(), EnvConfigError> {
+        match vars.iter().find(|var| self.get(var).is_some()) {
+            Some(var) if !enabled => Err(EnvConfigError::new(
+                var,
+                format!(
+                    "set {}_PORT or {}_ADDR to enable the transport",
+                    prefix, prefix
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject settings that need a feature the crate was built without
+    #[cfg(not(all(feature = "tls", feature = "sqlite-sessions", feature = "redis-sessions")))]
+    fn unsupported(&self, vars: &[&str], feature: &str) -> Result<(), EnvConfigError> {
+        match vars.iter().find(|var| self.get(var).is_some()) {
+            Some(var) => Err(EnvConfigError::new(
+                var,
+                format!("requires the `{}` feature", feature),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// TLS from `{prefix}_TLS_CERT_PATH` and `{prefix}_TLS_KEY_PATH`, which go together
+    #[cfg(feature = "tls")]
+    fn tls(&self, prefix: &str) -> Result<Option<crate::config::TlsConfig>, EnvConfigError> {
+        let cert_var = format!("{}_TLS_CERT_PATH", prefix);
+        let key_var = format!("{}_TLS_KEY_PATH", prefix);
+        match (self.get(&cert_var), self.get(&key_var)) {
+            (Some(cert), Some(key)) => Ok(Some(crate::config::TlsConfig::new(cert, key))),
+            (Some(_), None) => Err(EnvConfigError::new(
+                &key_var,
+                "required with the certificate path",
+            )),
+            (None, Some(_)) => Err(EnvConfigError::new(&cert_var, "required with the key path")),
+            (None, None) => Ok(None),
+        }
+    }
+}
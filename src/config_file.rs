@@ -0,0 +1,934 @@
+//! Loading a [`TransportConfig`] from a TOML or YAML file
+//!
+//! The file mirrors the builder API: one table per transport, each with its
+//! listen address, authentication, limits and filters. Anything the file
+//! cannot express (authorization policies, audit logs, custom validators) is
+//! added in code on the loaded configuration.
+//!
+//! ```toml
+//! api_key = "shared-secret"
+//! shutdown_timeout_secs = 10
+//! request_timeouts = { default_secs = 30, methods = { "reports.*" = 300 } }
+//!
+//! [websocket]
+//! port = 4444
+//!
+//! [mcp_http]
+//! addr = "0.0.0.0:4445"
+//! path = "/v1/mcp"
+//! slow_request_threshold_ms = 5000
+//! session_storage = { sqlite = "/var/lib/plexus/sessions.db" }
+//! session_ttl_secs = 86400
+//! sqlite_tuning = { busy_timeout_ms = 10000, max_connections = 4, vacuum_interval_secs = 86400 }
+//! # or, shared between replicas (feature `redis-sessions`):
+//! # session_storage = { redis = { url = "redis://redis:6379", ttl_secs = 3600 } }
+//! rate_limit = { requests_per_second = 5.0, burst = 20, key = "api_key" }
+//!
+//! [mcp_http.api_keys]
+//! ci = "key-for-ci"
+//!
+//! [mcp_http.tls]
+//! cert_path = "/etc/plexus/cert.pem"
+//! key_path = "/etc/plexus/key.pem"
+//!
+//! [metrics]  # feature `metrics`
+//! port = 9100
+//! ```
+//!
+//! Unknown keys are rejected, and every error names the offending field
+//! (e.g. `mcp_http.ip_filter.allow[1]: Invalid network "10.0.0.0/33"`).
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(not(all(feature = "jwt-auth", feature = "tls", feature = "sqlite-sessions", feature = "redis-sessions")))]
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::auth::ApiKeyAuth;
+use crate::config::{
+    resolve_host, CorsConfig, McpHttpConfig, NotificationOverflow, RequestLogging, RestHttpConfig,
+    SessionStorage, StdioConfig, StdioFraming, TransportConfig, WebSocketConfig,
+};
+use crate::ip_filter::IpFilter;
+use crate::mcp::capabilities::McpCapabilities;
+use crate::mcp::filter::ToolFilter;
+use crate::mcp::naming::ToolNaming;
+use crate::mcp::timeout::ToolTimeouts;
+use crate::origin_check::OriginCheck;
+use crate::rate_limit::{RateLimitConfig, RateLimitKey};
+use crate::resume::SubscriptionResume;
+use crate::supervisor::RestartPolicy;
+
+/// Error loading a configuration file
+#[derive(Debug, Error)]
+pub enum ConfigFileError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Unsupported config file {0} (expected .toml, .yaml or .yml)")]
+    UnsupportedFormat(PathBuf),
+    /// A missing, malformed or invalid field, e.g. `websocket.port`
+    #[error("{field}: {message}")]
+    Invalid { field: String, message: String },
+}
+
+impl ConfigFileError {
+    fn invalid(field: impl Into<String>, message: impl std::fmt::Display) -> Self {
+        Self::Invalid {
+            field: field.into(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Load a configuration from a `.toml`, `.yaml` or `.yml` file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&text),
+            Some("yaml" | "yml") => Self::from_yaml_str(&text),
+            _ => Err(ConfigFileError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Parse a TOML configuration
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigFileError> {
+        let file: FileConfig = serde_path_to_error::deserialize(toml::Deserializer::new(text))
+            .map_err(|e| ConfigFileError::invalid(e.path().to_string(), e.inner()))?;
+        file.into_config()
+    }
+
+    /// Parse a YAML configuration
+    pub fn from_yaml_str(text: &str) -> Result<Self, ConfigFileError> {
+        let file: FileConfig =
+            serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(text))
+                .map_err(|e| ConfigFileError::invalid(e.path().to_string(), e.inner()))?;
+        file.into_config()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// File layout
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    websocket: Option<WebSocketFile>,
+    stdio: Option<StdioFile>,
+    mcp_http: Option<McpHttpFile>,
+    rest_http: Option<RestHttpFile>,
+    api_key: Option<String>,
+    shutdown_timeout_secs: Option<u64>,
+    request_timeouts: Option<RequestTimeoutsFile>,
+    #[serde(default)]
+    signal_shutdown: bool,
+    restart: Option<RestartFile>,
+    metrics: Option<MetricsFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WebSocketFile {
+    port: Option<u16>,
+    host: Option<String>,
+    addr: Option<String>,
+    unix_socket: Option<PathBuf>,
+    api_key: Option<String>,
+    /// Accepted keys by the identity they authenticate as
+    api_keys: Option<BTreeMap<String, String>>,
+    jwt: Option<JwtFile>,
+    tls: Option<TlsFile>,
+    rate_limit: Option<RateLimitFile>,
+    ip_filter: Option<IpFilterFile>,
+    origin_check: Option<OriginCheckFile>,
+    allowed_hosts: Option<Vec<String>>,
+    max_message_size: Option<u32>,
+    max_response_size: Option<u32>,
+    max_connections: Option<u32>,
+    max_subscriptions_per_connection: Option<u32>,
+    max_request_batch_size: Option<u32>,
+    message_buffer_capacity: Option<u32>,
+    ping_interval_secs: Option<u64>,
+    inactive_limit_secs: Option<u64>,
+    max_missed_pings: Option<usize>,
+    resume_window_secs: Option<u64>,
+    resume_replay: Option<usize>,
+    drain_timeout_secs: Option<u64>,
+    slow_request_threshold_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StdioFile {
+    subscription_buffer_size: Option<usize>,
+    notification_overflow: Option<NotificationOverflowFile>,
+    framing: Option<StdioFramingFile>,
+    eof_grace_ms: Option<u64>,
+    request_timeout_ms: Option<u64>,
+    #[serde(default)]
+    exit_on_parent_death: bool,
+    #[serde(default)]
+    stdout_guard: bool,
+    #[serde(default)]
+    mcp: bool,
+    max_line_length: Option<usize>,
+    slow_request_threshold_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct McpHttpFile {
+    port: Option<u16>,
+    host: Option<String>,
+    addr: Option<String>,
+    path: Option<String>,
+    server_name: Option<String>,
+    server_version: Option<String>,
+    instructions: Option<String>,
+    capabilities: Option<CapabilitiesFile>,
+    session_storage: Option<SessionStorageFile>,
+    stateless: Option<bool>,
+    legacy_sse: Option<bool>,
+    sse_keep_alive_secs: Option<u64>,
+    session_channel_capacity: Option<usize>,
+    session_ttl_secs: Option<u64>,
+    ping_interval_secs: Option<u64>,
+    sqlite_tuning: Option<SqliteTuningFile>,
+    api_key: Option<String>,
+    api_keys: Option<BTreeMap<String, String>>,
+    jwt: Option<JwtFile>,
+    tls: Option<TlsFile>,
+    tool_filter: Option<ToolFilterFile>,
+    tool_naming: Option<ToolNamingFile>,
+    tool_timeouts: Option<ToolTimeoutsFile>,
+    tools_page_size: Option<usize>,
+    partial_results: Option<bool>,
+    max_result_bytes: Option<usize>,
+    rate_limit: Option<RateLimitFile>,
+    ip_filter: Option<IpFilterFile>,
+    cors: Option<CorsFile>,
+    origin_check: Option<OriginCheckFile>,
+    max_body_size: Option<usize>,
+    request_logging: Option<RequestLoggingFile>,
+    slow_request_threshold_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RestHttpFile {
+    port: Option<u16>,
+    host: Option<String>,
+    addr: Option<String>,
+    server_name: Option<String>,
+    server_version: Option<String>,
+    rate_limit: Option<RateLimitFile>,
+    ip_filter: Option<IpFilterFile>,
+    slow_request_threshold_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetricsFile {
+    port: Option<u16>,
+    host: Option<String>,
+    addr: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RestartFile {
+    initial_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    max_restarts: Option<u32>,
+}
+
+/// Either a shared `secret` (HS256) or a `jwks_url` (RS256)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg(feature = "jwt-auth")]
+struct JwtFile {
+    secret: Option<String>,
+    jwks_url: Option<String>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg(feature = "sqlite-sessions")]
+struct SqliteTuningFile {
+    wal: Option<bool>,
+    busy_timeout_ms: Option<u64>,
+    max_connections: Option<u32>,
+    vacuum_interval_secs: Option<u64>,
+    replay_events: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg(feature = "tls")]
+struct TlsFile {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    client_ca_path: Option<PathBuf>,
+}
+
+// Sections for features the crate was built without are only recognised, so
+// that loading them names the missing feature
+#[cfg(not(feature = "jwt-auth"))]
+type JwtFile = IgnoredAny;
+#[cfg(not(feature = "sqlite-sessions"))]
+type SqliteTuningFile = IgnoredAny;
+#[cfg(not(feature = "tls"))]
+type TlsFile = IgnoredAny;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitFile {
+    requests_per_second: f64,
+    burst: u32,
+    #[serde(default)]
+    key: RateLimitKeyFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RateLimitKeyFile {
+    #[default]
+    Ip,
+    ApiKey,
+    Session,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpFilterFile {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolFilterFile {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolTimeoutsFile {
+    default_secs: Option<u64>,
+    #[serde(default)]
+    tools: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RequestTimeoutsFile {
+    default_secs: Option<u64>,
+    /// Limits by method name or glob
+    #[serde(default)]
+    methods: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CapabilitiesFile {
+    tools: Option<bool>,
+    resources: Option<bool>,
+    prompts: Option<bool>,
+    logging: Option<bool>,
+    completions: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolNamingFile {
+    separator: Option<String>,
+    prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CorsFile {
+    #[serde(default)]
+    allowed_origins: Vec<String>,
+    /// Request headers allowed in addition to the MCP defaults
+    #[serde(default)]
+    allowed_headers: Vec<String>,
+    #[serde(default)]
+    allow_credentials: bool,
+    max_age_secs: Option<u64>,
+}
+
+/// `"auto"`, `"disabled"` or `{ allow = [...] }`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OriginCheckFile {
+    Auto,
+    Disabled,
+    Allow(Vec<String>),
+}
+
+/// `"memory"`, `{ sqlite = "path" }` or `{ redis = { url = "..." } }`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SessionStorageFile {
+    #[serde(alias = "in_memory")]
+    Memory,
+    Sqlite(SqliteSessionsFile),
+    Redis(RedisSessionsFile),
+}
+
+#[cfg(feature = "sqlite-sessions")]
+type SqliteSessionsFile = PathBuf;
+#[cfg(not(feature = "sqlite-sessions"))]
+type SqliteSessionsFile = IgnoredAny;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg(feature = "redis-sessions")]
+struct RedisSessionsFile {
+    url: String,
+    key_prefix: Option<String>,
+    ttl_secs: Option<u64>,
+}
+
+#[cfg(not(feature = "redis-sessions"))]
+type RedisSessionsFile = IgnoredAny;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum NotificationOverflowFile {
+    Block,
+    DropOldest,
+    DropNewest,
+    Close,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StdioFramingFile {
+    Lines,
+    ContentLength,
+    LengthPrefixed,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RequestLoggingFile {
+    Off,
+    Summary,
+    Full,
+}
+
+// ---------------------------------------------------------------------------
+// Conversion and validation
+// ---------------------------------------------------------------------------
+
+impl FileConfig {
+    fn into_config(self) -> Result<TransportConfig, ConfigFileError> {
+        let mut config = TransportConfig {
+            websocket: self
+                .websocket
+                .map(|ws| ws.into_config("websocket"))
+                .transpose()?,
+            stdio: self.stdio.map(StdioFile::into_config),
+            mcp_http: self
+                .mcp_http
+                .map(|mcp| mcp.into_config("mcp_http"))
+                .transpose()?,
+            rest_http: self
+                .rest_http
+                .map(|rest| rest.into_config("rest_http"))
+                .transpose()?,
+            api_key: self.api_key,
+            signal_shutdown: self.signal_shutdown,
+            restart_policy: self.restart.map(RestartFile::into_policy),
+            ..TransportConfig::default()
+        };
+        if let Some(secs) = self.shutdown_timeout_secs {
+            config.shutdown_timeout = Duration::from_secs(secs);
+        }
+        if let Some(timeouts) = self.request_timeouts {
+            config.request_timeouts = timeouts_from(timeouts.default_secs, timeouts.methods);
+        }
+        #[cfg(feature = "metrics")]
+        {
+            config.metrics_endpoint = self.metrics.map(|m| m.into_config("metrics")).transpose()?;
+        }
+        #[cfg(not(feature = "metrics"))]
+        if self.metrics.is_some() {
+            return Err(requires_feature("metrics", "metrics"));
+        }
+        Ok(config)
+    }
+}
+
+impl WebSocketFile {
+    fn into_config(self, field: &str) -> Result<WebSocketConfig, ConfigFileError> {
+        let addr = listen_addr(field, self.port, self.host.as_deref(), self.addr.as_deref())?;
+        let mut config = WebSocketConfig::from_addr(addr);
+        config.unix_socket = self.unix_socket;
+        config.api_key = self.api_key;
+        config.auth = self.api_keys.map(ApiKeyAuth::named_keys);
+        #[cfg(feature = "jwt-auth")]
+        if let Some(jwt) = self.jwt {
+            config.jwt = Some(jwt.into_auth(&format!("{}.jwt", field))?);
+        }
+        #[cfg(not(feature = "jwt-auth"))]
+        if self.jwt.is_some() {
+            return Err(requires_feature(format!("{}.jwt", field), "jwt-auth"));
+        }
+        #[cfg(feature = "tls")]
+        if let Some(tls) = self.tls {
+            config.tls = Some(tls.into_config());
+        }
+        #[cfg(not(feature = "tls"))]
+        if self.tls.is_some() {
+            return Err(requires_feature(format!("{}.tls", field), "tls"));
+        }
+        config.rate_limit = self.rate_limit.map(RateLimitFile::into_config);
+        config.ip_filter = self
+            .ip_filter
+            .map(|filter| filter.into_filter(&format!("{}.ip_filter", field)))
+            .transpose()?;
+        if let Some(check) = self.origin_check {
+            config.origin_check = check.into_check();
+        }
+        config.allowed_hosts = self.allowed_hosts;
+        if let Some(size) = self.max_message_size {
+            config.max_message_size = size;
+        }
+        if let Some(size) = self.max_response_size {
+            config.max_response_size = size;
+        }
+        if let Some(max) = self.max_connections {
+            config.max_connections = max;
+        }
+        if let Some(max) = self.max_subscriptions_per_connection {
+            config.max_subscriptions_per_connection = max;
+        }
+        if let Some(max) = self.max_request_batch_size {
+            config = config.with_max_request_batch_size(max);
+        }
+        if let Some(capacity) = self.message_buffer_capacity {
+            config.message_buffer_capacity = capacity;
+        }
+        config.ping_interval = self.ping_interval_secs.map(Duration::from_secs);
+        if let Some(limit) = self.inactive_limit_secs {
+            config.inactive_limit = Duration::from_secs(limit);
+        }
+        if let Some(max) = self.max_missed_pings {
+            config = config.with_max_missed_pings(max);
+        }
+        if self.resume_window_secs.is_some() || self.resume_replay.is_some() {
+            let defaults = SubscriptionResume::default();
+            config = config.with_subscription_resume(SubscriptionResume::new(
+                self.resume_window_secs.map_or(defaults.window, Duration::from_secs),
+                self.resume_replay.unwrap_or(defaults.replay),
+            ));
+        }
+        if let Some(secs) = self.drain_timeout_secs {
+            config.drain_timeout = Duration::from_secs(secs);
+        }
+        config.slow_request_threshold = self.slow_request_threshold_ms.map(Duration::from_millis);
+        Ok(config)
+    }
+}
+
+impl StdioFile {
+    fn into_config(self) -> StdioConfig {
+        let mut config = StdioConfig::default();
+        if let Some(size) = self.subscription_buffer_size {
+            config.subscription_buffer_size = size;
+        }
+        if let Some(length) = self.max_line_length {
+            config.max_line_length = length;
+        }
+        if let Some(overflow) = self.notification_overflow {
+            config.notification_overflow = match overflow {
+                NotificationOverflowFile::Block => NotificationOverflow::Block,
+                NotificationOverflowFile::DropOldest => NotificationOverflow::DropOldest,
+                NotificationOverflowFile::DropNewest => NotificationOverflow::DropNewest,
+                NotificationOverflowFile::Close => NotificationOverflow::Close,
+            };
+        }
+        if let Some(framing) = self.framing {
+            config.framing = match framing {
+                StdioFramingFile::Lines => StdioFraming::Lines,
+                StdioFramingFile::ContentLength => StdioFraming::ContentLength,
+                StdioFramingFile::LengthPrefixed => StdioFraming::LengthPrefixed,
+            };
+        }
+        config.slow_request_threshold = self.slow_request_threshold_ms.map(Duration::from_millis);
+        config.eof_grace = self.eof_grace_ms.map(Duration::from_millis);
+        config.request_timeout = self.request_timeout_ms.map(Duration::from_millis);
+        config.exit_on_parent_death = self.exit_on_parent_death;
+        config.stdout_guard = self.stdout_guard;
+        config.mcp = self.mcp;
+        config
+    }
+}
+
+impl McpHttpFile {
+    fn into_config(self, field: &str) -> Result<McpHttpConfig, ConfigFileError> {
+        let addr = listen_addr(field, self.port, self.host.as_deref(), self.addr.as_deref())?;
+        let mut config = McpHttpConfig::from_addr(addr);
+        if let Some(path) = self.path {
+            config = config.with_path(path);
+        }
+        config.server_name = self.server_name;
+        config.server_version = self.server_version;
+        config.instructions = self.instructions;
+        if let Some(capabilities) = self.capabilities {
+            let all = McpCapabilities::all();
+            config.capabilities = McpCapabilities {
+                tools: capabilities.tools.unwrap_or(all.tools),
+                resources: capabilities.resources.unwrap_or(all.resources),
+                prompts: capabilities.prompts.unwrap_or(all.prompts),
+                logging: capabilities.logging.unwrap_or(all.logging),
+                completions: capabilities.completions.unwrap_or(all.completions),
+            };
+        }
+        if let Some(storage) = self.session_storage {
+            config.session_storage = storage.into_storage(&format!("{}.session_storage", field))?;
+        }
+        config.stateless = self.stateless.unwrap_or(false);
+        config.legacy_sse = self.legacy_sse.unwrap_or(false);
+        if let Some(secs) = self.sse_keep_alive_secs {
+            // 0 turns keep-alives off
+            config.sse_keep_alive = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Some(capacity) = self.session_channel_capacity {
+            config.session_channel_capacity = capacity;
+        }
+        config.session_ttl = self.session_ttl_secs.map(Duration::from_secs);
+        config.ping_interval = self.ping_interval_secs.map(Duration::from_secs);
+        #[cfg(feature = "sqlite-sessions")]
+        if let Some(tuning) = self.sqlite_tuning {
+            config.sqlite_tuning = tuning.into_tuning();
+        }
+        #[cfg(not(feature = "sqlite-sessions"))]
+        if self.sqlite_tuning.is_some() {
+            return Err(requires_feature(format!("{}.sqlite_tuning", field), "sqlite-sessions"));
+        }
+        config.api_key = self.api_key;
+        config.auth = self.api_keys.map(ApiKeyAuth::named_keys);
+        #[cfg(feature = "jwt-auth")]
+        if let Some(jwt) = self.jwt {
+            config.jwt = Some(jwt.into_auth(&format!("{}.jwt", field))?);
+        }
+        #[cfg(not(feature = "jwt-auth"))]
+        if self.jwt.is_some() {
+            return Err(requires_feature(format!("{}.jwt", field), "jwt-auth"));
+        }
+        #[cfg(feature = "tls")]
+        if let Some(tls) = self.tls {
+            config.tls = Some(tls.into_config());
+        }
+        #[cfg(not(feature = "tls"))]
+        if self.tls.is_some() {
+            return Err(requires_feature(format!("{}.tls", field), "tls"));
+        }
+        config.tool_filter = self.tool_filter.map(|filter| {
+            ToolFilter::new()
+                .include(filter.include)
+                .exclude(filter.exclude)
+        });
+        if let Some(naming) = self.tool_naming {
+            let mut tool_naming = ToolNaming::new();
+            if let Some(separator) = naming.separator {
+                tool_naming = tool_naming.with_separator(separator);
+            }
+            if let Some(prefix) = naming.prefix {
+                tool_naming = tool_naming.with_prefix(prefix);
+            }
+            config.tool_naming = tool_naming;
+        }
+        if let Some(timeouts) = self.tool_timeouts {
+            config.tool_timeouts = timeouts_from(timeouts.default_secs, timeouts.tools);
+        }
+        config.tools_page_size = self.tools_page_size;
+        config.partial_results = self.partial_results.unwrap_or(false);
+        config.max_result_bytes = self.max_result_bytes;
+        config.rate_limit = self.rate_limit.map(RateLimitFile::into_config);
+        config.ip_filter = self
+            .ip_filter
+            .map(|filter| filter.into_filter(&format!("{}.ip_filter", field)))
+            .transpose()?;
+        config.cors = self.cors.map(CorsFile::into_config);
+        if let Some(check) = self.origin_check {
+            config.origin_check = check.into_check();
+        }
+        if let Some(size) = self.max_body_size {
+            config.max_body_size = size;
+        }
+        if let Some(logging) = self.request_logging {
+            config.request_logging = match logging {
+                RequestLoggingFile::Off => RequestLogging::Off,
+                RequestLoggingFile::Summary => RequestLogging::Summary,
+                RequestLoggingFile::Full => RequestLogging::Full,
+            };
+        }
+        config.slow_request_threshold = self.slow_request_threshold_ms.map(Duration::from_millis);
+        Ok(config)
+    }
+}
+
+impl RestHttpFile {
+    fn into_config(self, field: &str) -> Result<RestHttpConfig, ConfigFileError> {
+        let addr = listen_addr(field, self.port, self.host.as_deref(), self.addr.as_deref())?;
+        let mut config = RestHttpConfig::from_addr(addr);
+        if let Some(name) = self.server_name {
+            config.server_name = name;
+        }
+        if let Some(version) = self.server_version {
+            config.server_version = version;
+        }
+        config.rate_limit = self.rate_limit.map(RateLimitFile::into_config);
+        config.ip_filter = self
+            .ip_filter
+            .map(|filter| filter.into_filter(&format!("{}.ip_filter", field)))
+            .transpose()?;
+        config.slow_request_threshold = self.slow_request_threshold_ms.map(Duration::from_millis);
+        Ok(config)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsFile {
+    fn into_config(self, field: &str) -> Result<crate::metrics::MetricsConfig, ConfigFileError> {
+        let addr = listen_addr(field, self.port, self.host.as_deref(), self.addr.as_deref())?;
+        let config = crate::metrics::MetricsConfig::from_addr(addr);
+        Ok(match self.path {
+            Some(path) if !path.starts_with('/') => {
+                return Err(ConfigFileError::invalid(
+                    format!("{}.path", field),
+                    "must start with `/`",
+                ))
+            }
+            Some(path) => config.with_path(path),
+            None => config,
+        })
+    }
+}
+
+impl RestartFile {
+    fn into_policy(self) -> RestartPolicy {
+        let mut policy = RestartPolicy::default();
+        let initial = self
+            .initial_backoff_ms
+            .map_or(policy.initial_backoff, Duration::from_millis);
+        let max = self
+            .max_backoff_ms
+            .map_or(policy.max_backoff, Duration::from_millis);
+        policy = policy.with_backoff(initial, max);
+        if let Some(max_restarts) = self.max_restarts {
+            policy = policy.with_max_restarts(max_restarts);
+        }
+        policy
+    }
+}
+
+#[cfg(feature = "jwt-auth")]
+impl JwtFile {
+    fn into_auth(self, field: &str) -> Result<crate::auth::jwt::JwtAuth, ConfigFileError> {
+        use crate::auth::jwt::JwtAuth;
+
+        let mut jwt = match (self.secret, self.jwks_url) {
+            (Some(secret), None) => JwtAuth::with_secret(secret),
+            (None, Some(url)) => JwtAuth::with_jwks(url),
+            _ => {
+                return Err(ConfigFileError::invalid(
+                    field,
+                    "exactly one of `secret` and `jwks_url` is required",
+                ))
+            }
+        };
+        if let Some(issuer) = self.issuer {
+            jwt = jwt.with_issuer(issuer);
+        }
+        if let Some(audience) = self.audience {
+            jwt = jwt.with_audience(audience);
+        }
+        Ok(jwt)
+    }
+}
+
+#[cfg(feature = "sqlite-sessions")]
+impl SqliteTuningFile {
+    fn into_tuning(self) -> crate::mcp::session::SqliteTuning {
+        let mut tuning = crate::mcp::session::SqliteTuning::default();
+        if let Some(wal) = self.wal {
+            tuning.wal = wal;
+        }
+        if let Some(ms) = self.busy_timeout_ms {
+            tuning.busy_timeout = Duration::from_millis(ms);
+        }
+        if let Some(max) = self.max_connections {
+            tuning.max_connections = max;
+        }
+        tuning.vacuum_interval = self.vacuum_interval_secs.map(Duration::from_secs);
+        if let Some(events) = self.replay_events {
+            tuning.replay_events = events;
+        }
+        tuning
+    }
+}
+
+#[cfg(feature = "tls")]
+impl TlsFile {
+    fn into_config(self) -> crate::config::TlsConfig {
+        let tls = crate::config::TlsConfig::new(self.cert_path, self.key_path);
+        match self.client_ca_path {
+            Some(ca_path) => tls.with_client_ca(ca_path),
+            None => tls,
+        }
+    }
+}
+
+impl RateLimitFile {
+    fn into_config(self) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second: self.requests_per_second,
+            burst: self.burst,
+            key: match self.key {
+                RateLimitKeyFile::Ip => RateLimitKey::Ip,
+                RateLimitKeyFile::ApiKey => RateLimitKey::ApiKey,
+                RateLimitKeyFile::Session => RateLimitKey::Session,
+            },
+        }
+    }
+}
+
+impl IpFilterFile {
+    fn into_filter(self, field: &str) -> Result<IpFilter, ConfigFileError> {
+        let mut filter = IpFilter::new();
+        for (i, network) in self.allow.iter().enumerate() {
+            filter = filter
+                .allow([network])
+                .map_err(|e| ConfigFileError::invalid(format!("{}.allow[{}]", field, i), e))?;
+        }
+        for (i, network) in self.deny.iter().enumerate() {
+            filter = filter
+                .deny([network])
+                .map_err(|e| ConfigFileError::invalid(format!("{}.deny[{}]", field, i), e))?;
+        }
+        Ok(filter)
+    }
+}
+
+impl CorsFile {
+    fn into_config(self) -> CorsConfig {
+        let mut cors = CorsConfig::new(self.allowed_origins)
+            .with_allowed_headers(self.allowed_headers)
+            .with_credentials(self.allow_credentials);
+        if let Some(secs) = self.max_age_secs {
+            cors = cors.with_max_age(Duration::from_secs(secs));
+        }
+        cors
+    }
+}
+
+impl OriginCheckFile {
+    fn into_check(self) -> OriginCheck {
+        match self {
+            Self::Auto => OriginCheck::Auto,
+            Self::Disabled => OriginCheck::Disabled,
+            Self::Allow(origins) => OriginCheck::Allow(origins),
+        }
+    }
+}
+
+impl SessionStorageFile {
+    #[cfg_attr(all(feature = "sqlite-sessions", feature = "redis-sessions"), allow(unused_variables))]
+    fn into_storage(self, field: &str) -> Result<SessionStorage, ConfigFileError> {
+        match self {
+            Self::Memory => Ok(SessionStorage::InMemory),
+            #[cfg(feature = "sqlite-sessions")]
+            Self::Sqlite(path) => Ok(SessionStorage::Sqlite { path }),
+            #[cfg(not(feature = "sqlite-sessions"))]
+            Self::Sqlite(IgnoredAny) => Err(requires_feature(field, "sqlite-sessions")),
+            #[cfg(feature = "redis-sessions")]
+            Self::Redis(redis) => Ok(SessionStorage::Redis {
+                url: redis.url,
+                key_prefix: redis
+                    .key_prefix
+                    .unwrap_or_else(|| crate::mcp::redis_session::DEFAULT_KEY_PREFIX.to_string()),
+                ttl: redis
+                    .ttl_secs
+                    .map(Duration::from_secs)
+                    .unwrap_or(crate::mcp::redis_session::DEFAULT_SESSION_TTL),
+            }),
+            #[cfg(not(feature = "redis-sessions"))]
+            Self::Redis(IgnoredAny) => Err(requires_feature(field, "redis-sessions")),
+        }
+    }
+}
+
+/// A setting the crate was built without support for
+#[cfg(not(all(
+    feature = "metrics",
+    feature = "jwt-auth",
+    feature = "tls",
+    feature = "sqlite-sessions",
+    feature = "redis-sessions"
+)))]
+fn requires_feature(field: impl Into<String>, feature: &str) -> ConfigFileError {
+    ConfigFileError::invalid(field, format!("requires the `{}` feature", feature))
+}
+
+/// Resolve a transport's `addr`, or `host` and `port`, defaulting to localhost
+fn listen_addr(
+    field: &str,
+    port: Option<u16>,
+    host: Option<&str>,
+    addr: Option<&str>,
+) -> Result<SocketAddr, ConfigFileError> {
+    match (addr, port, host) {
+        (Some(addr), None, None) => addr
+            .parse()
+            .map_err(|e| ConfigFileError::invalid(format!("{}.addr", field), e)),
+        (Some(_), _, _) => Err(ConfigFileError::invalid(
+            format!("{}.addr", field),
+            "`addr` cannot be combined with `port` or `host`",
+        )),
+        (None, Some(port), None) => Ok(SocketAddr::from(([127, 0, 0, 1], port))),
+        (None, Some(port), Some(host)) => resolve_host(host, port)
+            .map_err(|e| ConfigFileError::invalid(format!("{}.host", field), e)),
+        (None, None, _) => Err(ConfigFileError::invalid(
+            format!("{}.port", field),
+            "either `port` or `addr` is required",
+        )),
+    }
+}
+
+/// Time limits from a default and limits by name or glob, all in seconds
+fn timeouts_from(default_secs: Option<u64>, limits: BTreeMap<String, u64>) -> ToolTimeouts {
+    let mut timeouts = ToolTimeouts::new();
+    if let Some(secs) = default_secs {
+        timeouts = timeouts.with_default(Duration::from_secs(secs));
+    }
+    for (pattern, secs) in limits {
+        timeouts = timeouts.with_tool(pattern, Duration::from_secs(secs));
+    }
+    timeouts
+}
@@ -0,0 +1,39 @@
+//! tokio-console integration
+//!
+//! With the `console` feature, and the application built with
+//! `RUSTFLAGS="--cfg tokio_unstable"`, [`layer`] adds the tokio-console
+//! instrumentation to the application's tracing subscriber; `tokio-console`
+//! then lists the transports' tasks by name (`plexus.websocket.connection`,
+//! `plexus.stdio.notifications`, ...). The console server listens on
+//! `127.0.0.1:6669` unless the `TOKIO_CONSOLE_*` environment variables say
+//! otherwise.
+
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// The tokio-console layer, to add to the application's subscriber
+///
+/// ```rust,ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(plexus_transport::console::layer())
+///     .with(tracing_subscriber::fmt::layer())
+///     .init();
+/// ```
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    console_subscriber::ConsoleLayer::builder().with_default_env().spawn()
+}
+
+/// Install a global subscriber with the tokio-console layer and
+/// `RUST_LOG`-filtered logging to stdout
+///
+/// For applications without a subscriber of their own; stdio servers, whose
+/// stdout carries the protocol, should build theirs with [`layer`] instead.
+pub fn init() {
+    console_subscriber::init();
+}
@@ -0,0 +1,207 @@
+//! Replacing the activation of a running server
+//!
+//! Plexus plugins are upgraded in place with [`ActivationSwap::swap`]: the new
+//! activation and its rebuilt RPC module are published to every transport of
+//! the server. New WebSocket connections, MCP sessions, REST requests and stdio
+//! requests are served by the new instance, while connections and sessions
+//! opened before the swap keep the old one until they close. No client is
+//! disconnected; the old instance is dropped once the last of them has drained.
+//!
+//! Draining is passive: there is no deadline, so a long-lived WebSocket
+//! connection or MCP session keeps calling the old instance for as long as it
+//! stays open. Restarting a transport after the swap (e.g.
+//! [`RunningServer::restart_websocket`](crate::RunningServer::restart_websocket))
+//! closes its connections once the shutdown timeout has passed, moving their
+//! clients to the new instance when they reconnect.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use jsonrpsee::{Methods, RpcModule};
+use plexus_core::plexus::{Activation, PluginSchema};
+use tokio::sync::watch;
+
+use crate::mount::{check_prefixes, merge_methods, Mount};
+use crate::server::{RouteFn, RpcConverter};
+
+/// Yields the RPC methods a new WebSocket connection or stdio request is served with
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+pub(crate) type MethodSource = Arc<dyn Fn() -> Methods + Send + Sync>;
+
+/// A method source that never changes
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+pub(crate) fn fixed_methods(methods: Methods) -> MethodSource {
+    Arc::new(move || methods.clone())
+}
+
+/// The activation transports serve, with everything derived from it
+pub(crate) struct Served<A: Activation> {
+    pub(crate) activation: Arc<A>,
+    /// RPC methods for WebSocket and stdio; `None` until the converter has run
+    pub(crate) methods: Option<Methods>,
+    /// Tool schemas and hub routing for MCP and REST
+    #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) flat_schemas: Option<Vec<PluginSchema>>,
+    #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) route_fn: Option<RouteFn>,
+    /// Activations served next to this one; not replaced by swaps
+    pub(crate) mounts: Arc<[Mount]>,
+}
+
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+impl<A: Activation> Served<A> {
+    /// A receiver that always sees `activation`, for transports started on their own
+    pub(crate) fn fixed(
+        activation: Arc<A>,
+        flat_schemas: Option<Vec<PluginSchema>>,
+        route_fn: Option<RouteFn>,
+    ) -> watch::Receiver<Self> {
+        watch::channel(Self {
+            activation,
+            methods: None,
+            flat_schemas,
+            route_fn,
+            mounts: Arc::default(),
+        })
+        .1
+    }
+}
+
+/// Replaces the activation served by a [`TransportServer`](crate::TransportServer)
+///
+/// Obtained from [`TransportServer::activation_swap`](crate::TransportServer::activation_swap)
+/// before serving; cloning it is cheap and every clone swaps the same server.
+pub struct ActivationSwap<A: Activation> {
+    current: Arc<watch::Sender<Served<A>>>,
+    /// The builder's converter, reused by [`ActivationSwap::replace`]
+    rpc_converter: RpcConverter<A>,
+}
+
+impl<A: Activation> Clone for ActivationSwap<A> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+            rpc_converter: self.rpc_converter.clone(),
+        }
+    }
+}
+
+impl<A: Activation> fmt::Debug for ActivationSwap<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActivationSwap")
+            .field("receivers", &self.current.receiver_count())
+            .finish()
+    }
+}
+
+impl<A: Activation> ActivationSwap<A> {
+    pub(crate) fn new(
+        activation: Arc<A>,
+        rpc_converter: RpcConverter<A>,
+        flat_schemas: Option<Vec<PluginSchema>>,
+        route_fn: Option<RouteFn>,
+        mounts: Vec<Mount>,
+    ) -> Self {
+        #[cfg(not(any(feature = "mcp-http", feature = "http-gateway")))]
+        let _ = (flat_schemas, route_fn);
+        let (current, _) = watch::channel(Served {
+            activation,
+            methods: None,
+            #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+            flat_schemas,
+            #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+            route_fn,
+            mounts: mounts.into(),
+        });
+        Self {
+            current: Arc::new(current),
+            rpc_converter,
+        }
+    }
+
+    /// Serve `activation` from now on
+    ///
+    /// `rpc_converter` rebuilds the RPC module for WebSocket and stdio, like the
+    /// converter given to the builder. Hub activations pass the new hub's flat
+    /// schemas and routing function (see
+    /// [`with_mcp_flat_schemas`](crate::TransportServerBuilder::with_mcp_flat_schemas)
+    /// and [`with_mcp_route_fn`](crate::TransportServerBuilder::with_mcp_route_fn));
+    /// `None` serves the activation's own schema.
+    ///
+    /// Mounted activations are kept as they are. When the converter fails, or
+    /// a mount prefix is a namespace of the new activation, the current
+    /// activation stays in place.
+    ///
+    /// Connections open at the time of the swap are not closed and keep the
+    /// old instance until they disconnect; see the [module docs](self).
+    pub fn swap<F>(
+        &self,
+        activation: Arc<A>,
+        rpc_converter: F,
+        flat_schemas: Option<Vec<PluginSchema>>,
+        route_fn: Option<RouteFn>,
+    ) -> Result<()>
+    where
+        F: FnOnce(Arc<A>) -> Result<RpcModule<()>>,
+    {
+        let mounts = self.current.borrow().mounts.clone();
+        check_prefixes(&*activation, flat_schemas.as_deref(), &mounts)?;
+        let methods = merge_methods(rpc_converter(activation.clone())?.into(), &mounts)?;
+        #[cfg(not(any(feature = "mcp-http", feature = "http-gateway")))]
+        let _ = (flat_schemas, route_fn);
+        self.current.send_replace(Served {
+            activation,
+            methods: Some(methods),
+            #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+            flat_schemas,
+            #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+            route_fn,
+            mounts,
+        });
+        tracing::info!("Activation replaced; open connections keep the previous instance until they close");
+        Ok(())
+    }
+
+    /// Serve `activation` from now on, converted with the builder's converter
+    ///
+    /// Like [`swap`](Self::swap), for upgrades of the same activation type that
+    /// need no conversion logic of their own.
+    pub fn replace(
+        &self,
+        activation: Arc<A>,
+        flat_schemas: Option<Vec<PluginSchema>>,
+        route_fn: Option<RouteFn>,
+    ) -> Result<()> {
+        let rpc_converter = self.rpc_converter.clone();
+        self.swap(activation, |activation| rpc_converter(activation), flat_schemas, route_fn)
+    }
+
+    /// The activation new connections are served with
+    pub fn current(&self) -> Arc<A> {
+        self.current.borrow().activation.clone()
+    }
+
+    /// Run the builder's converter unless a swap already provided a module
+    pub(crate) fn init_methods(&self) -> Result<()> {
+        if self.current.borrow().methods.is_some() {
+            return Ok(());
+        }
+        let mounts = self.current.borrow().mounts.clone();
+        let methods = merge_methods((self.rpc_converter)(self.current())?.into(), &mounts)?;
+        self.current.send_modify(|served| served.methods = Some(methods));
+        Ok(())
+    }
+
+    /// The current RPC methods; empty until [`init_methods`](Self::init_methods) has run
+    #[cfg(any(feature = "websocket", feature = "stdio"))]
+    pub(crate) fn method_source(&self) -> MethodSource {
+        let current = self.current.subscribe();
+        Arc::new(move || current.borrow().methods.clone().unwrap_or_default())
+    }
+
+    #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Served<A>> {
+        self.current.subscribe()
+    }
+}
@@ -19,7 +19,13 @@ use axum::{
 use plexus_core::plexus::{Activation, PlexusError, PlexusStream, PluginSchema, schema::HttpMethod};
 use serde_json::Value;
 
+use crate::audit::{AuditStatus, Auditor};
+use crate::auth::policy::{forbidden_message, FORBIDDEN_ERROR_CODE};
+use crate::auth::{AuthorizationPolicy, CallerIdentity};
+use crate::mcp::timeout::ToolTimeouts;
 use crate::http::handler::{handle_method_call, MethodInfo};
+use crate::middleware::{self, CallContext, CallRejection, TransportMiddleware};
+use crate::rate_limit::RATE_LIMITED_ERROR_CODE;
 
 /// A function that routes a namespaced method call (e.g., "loopback.permit") to the
 /// correct activation. Used by hub activations to dispatch child calls via `hub.route()`.
@@ -92,6 +98,11 @@ fn schemas_to_rest_routes<A>(
     activation: Arc<A>,
     schemas: Vec<PluginSchema>,
     route_fn: Option<RouteFn>,
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+    request_timeouts: ToolTimeouts,
+    audit: Option<Arc<Auditor>>,
+    prefix: Option<String>,
 ) -> Router
 where
     A: Activation + 'static,
@@ -101,6 +112,11 @@ where
         activation,
         route_fn,
         registry: registry.clone(),
+        authorization,
+        middleware,
+        request_timeouts,
+        audit,
+        prefix,
     });
 
     let mut router = Router::new();
@@ -133,6 +149,23 @@ struct RestBridgeState<A: Activation> {
     activation: Arc<A>,
     route_fn: Option<RouteFn>,
     registry: MethodRegistry,
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Time limits of calls, by `prefix.namespace.method` name
+    request_timeouts: ToolTimeouts,
+    audit: Option<Arc<Auditor>>,
+    /// Mount prefix; policy and audit see calls as `prefix.namespace.method`
+    prefix: Option<String>,
+}
+
+impl<A: Activation> RestBridgeState<A> {
+    /// The name a call is authorized and audited under
+    fn qualified_name(&self, namespace: &str, method: &str) -> String {
+        match self.prefix {
+            Some(ref prefix) => format!("{}.{}.{}", prefix, namespace, method),
+            None => format!("{}.{}", namespace, method),
+        }
+    }
 }
 
 // =============================================================================
@@ -140,11 +173,130 @@ struct RestBridgeState<A: Activation> {
 // =============================================================================
 
 /// Axum handler for REST method calls
+///
+/// With an audit log configured, every call is recorded; the error code of a
+/// failed call is its HTTP status.
 async fn rest_method_handler<A>(
     Path((namespace, method)): Path<(String, String)>,
     State(state): State<Arc<RestBridgeState<A>>>,
+    extensions: axum::http::Extensions,
     Json(params): Json<Value>,
 ) -> Response
+where
+    A: Activation + 'static,
+{
+    let Some(auditor) = state.audit.clone() else {
+        return dispatch_rest_call(namespace, method, &state, &extensions, params).await;
+    };
+
+    let identity = CallerIdentity::from_extensions(&extensions);
+    let full_method = state.qualified_name(&namespace, &method);
+    let params_json = params.to_string();
+    let started = std::time::Instant::now();
+
+    let response = dispatch_rest_call(namespace, method, &state, &extensions, params).await;
+    let status = response_status(&response);
+    auditor.record("rest", &identity, &full_method, Some(&params_json), status, started.elapsed());
+    response
+}
+
+/// Audit status of a REST response; the error code of a failed call is its HTTP status
+fn response_status(response: &Response) -> AuditStatus {
+    match response.status() {
+        s if s.is_success() => AuditStatus::Ok,
+        StatusCode::FORBIDDEN => AuditStatus::Denied,
+        s => AuditStatus::Error { code: s.as_u16() as i32 },
+    }
+}
+
+/// Check and dispatch a REST call
+async fn dispatch_rest_call<A>(
+    namespace: String,
+    method: String,
+    state: &RestBridgeState<A>,
+    extensions: &axum::http::Extensions,
+    params: Value,
+) -> Response
+where
+    A: Activation + 'static,
+{
+    // Check the caller against the authorization policy before anything else
+    if let Some(ref policy) = state.authorization {
+        let identity = CallerIdentity::from_extensions(extensions);
+        let full_method = state.qualified_name(&namespace, &method);
+        if !policy.allow_method(&identity, &full_method) {
+            tracing::warn!("REST call denied: {} (caller={:?})", full_method, identity.name());
+            return (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": forbidden_message(&full_method)
+                }))
+            ).into_response();
+        }
+    }
+
+    if state.middleware.is_empty() {
+        return call_rest_method_timed(namespace, method, state, params).await;
+    }
+
+    // Middleware sees the body as the call's params, and may replace it
+    let started = std::time::Instant::now();
+    let mut call = CallContext::new(
+        "rest",
+        state.qualified_name(&namespace, &method),
+        Some(params),
+        CallerIdentity::from_extensions(extensions),
+        middleware::peer_of(extensions),
+    );
+    if let Err(rejection) = middleware::before_call(&state.middleware, &mut call, started).await {
+        return rejection_response(rejection);
+    }
+    let params = call.params.clone().unwrap_or(Value::Null);
+    let response = call_rest_method_timed(namespace, method, state, params).await;
+    middleware::after_call(&state.middleware, &call, response_status(&response), started);
+    response
+}
+
+/// Dispatch a checked REST call, within its time limit
+///
+/// Calls running longer than their limit are dropped, which stops the
+/// activation's stream, and answered with HTTP 504. Streaming methods are
+/// limited until their event stream starts.
+async fn call_rest_method_timed<A>(
+    namespace: String,
+    method: String,
+    state: &RestBridgeState<A>,
+    params: Value,
+) -> Response
+where
+    A: Activation + 'static,
+{
+    let full_method = state.qualified_name(&namespace, &method);
+    let Some(timeout) = state.request_timeouts.timeout_for(&full_method) else {
+        return call_rest_method(namespace, method, state, params).await;
+    };
+    match tokio::time::timeout(timeout, call_rest_method(namespace, method, state, params)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("REST call timed out after {:?}: {}", timeout, full_method);
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(serde_json::json!({
+                    "error": format!("Request timed out after {}s: {}", timeout.as_secs_f64(), full_method),
+                    "data": { "timeout_ms": timeout.as_millis() as u64 },
+                }))
+            ).into_response()
+        }
+    }
+}
+
+/// Dispatch a checked REST call to the activation
+async fn call_rest_method<A>(
+    namespace: String,
+    method: String,
+    state: &RestBridgeState<A>,
+    params: Value,
+) -> Response
 where
     A: Activation + 'static,
 {
@@ -189,6 +341,23 @@ where
 // Error Mapping
 // =============================================================================
 
+/// Convert a call rejected by middleware to HTTP response
+///
+/// Rejections with `FORBIDDEN_ERROR_CODE` get 403, with `RATE_LIMITED_ERROR_CODE`
+/// 429, and any other code 400.
+fn rejection_response(rejection: CallRejection) -> Response {
+    let status = match rejection.code {
+        FORBIDDEN_ERROR_CODE => StatusCode::FORBIDDEN,
+        RATE_LIMITED_ERROR_CODE => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    let mut body = serde_json::json!({ "error": rejection.message });
+    if let Some(data) = rejection.data {
+        body["data"] = data;
+    }
+    (status, Json(body)).into_response()
+}
+
 /// Convert PlexusError to HTTP response
 fn plexus_error_to_response(e: PlexusError) -> Response {
     let (status, error_msg) = match e {
@@ -233,10 +402,11 @@ pub struct ActivationRestBridge<A: Activation> {
     activation: Arc<A>,
     schemas: Vec<PluginSchema>,
     route_fn: Option<RouteFn>,
-    #[allow(dead_code)]
-    server_name: Option<String>,
-    #[allow(dead_code)]
-    server_version: Option<String>,
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+    request_timeouts: ToolTimeouts,
+    audit: Option<Arc<Auditor>>,
+    prefix: Option<String>,
 }
 
 impl<A: Activation> ActivationRestBridge<A> {
@@ -247,19 +417,23 @@ impl<A: Activation> ActivationRestBridge<A> {
             activation,
             schemas,
             route_fn: None,
-            server_name: None,
-            server_version: None,
+            authorization: None,
+            middleware: Vec::new(),
+            request_timeouts: ToolTimeouts::new(),
+            audit: None,
+            prefix: None,
         }
     }
 
     /// Create a REST bridge with server info and custom schemas
     ///
     /// For hub activations, pass `hub.list_plugin_schemas()` as `flat_schemas`
-    /// to expose all child activation methods.
+    /// to expose all child activation methods. REST responses carry no server
+    /// info; the name and version are taken for symmetry with the MCP bridge.
     pub fn with_server_info_and_schemas(
         activation: Arc<A>,
-        server_name: Option<String>,
-        server_version: Option<String>,
+        _server_name: Option<String>,
+        _server_version: Option<String>,
         flat_schemas: Option<Vec<PluginSchema>>,
     ) -> Self {
         let schemas = flat_schemas.unwrap_or_else(|| vec![activation.plugin_schema()]);
@@ -267,8 +441,11 @@ impl<A: Activation> ActivationRestBridge<A> {
             activation,
             schemas,
             route_fn: None,
-            server_name,
-            server_version,
+            authorization: None,
+            middleware: Vec::new(),
+            request_timeouts: ToolTimeouts::new(),
+            audit: None,
+            prefix: None,
         }
     }
 
@@ -280,8 +457,57 @@ impl<A: Activation> ActivationRestBridge<A> {
         self
     }
 
+    /// Set the authorization policy consulted before every call
+    ///
+    /// Denied calls are rejected with HTTP 403.
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every call, after the ones added before it
+    ///
+    /// The middleware sees the JSON body as the call's params, and runs after
+    /// the authorization policy.
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Fail calls that run longer than their limit in `timeouts` with HTTP 504
+    ///
+    /// Limits are looked up by the name the authorization policy sees.
+    pub fn with_request_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.request_timeouts = timeouts;
+        self
+    }
+
+    /// Record every call and its outcome in an audit log
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Authorize and audit calls as `prefix.namespace.method`
+    ///
+    /// Used for activations mounted under a prefix; the routes themselves stay
+    /// `/{namespace}/{method}` and are nested under `/{prefix}` by the caller.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
     /// Convert this bridge into an Axum router
     pub fn into_router(self) -> Router {
-        schemas_to_rest_routes(self.activation, self.schemas, self.route_fn)
+        schemas_to_rest_routes(
+            self.activation,
+            self.schemas,
+            self.route_fn,
+            self.authorization,
+            self.middleware,
+            self.request_timeouts,
+            self.audit,
+            self.prefix,
+        )
     }
 }
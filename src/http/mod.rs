@@ -38,4 +38,4 @@ pub mod server;
 
 pub use bridge::ActivationRestBridge;
 pub use handler::{handle_method_call, MethodInfo};
-pub use server::serve_rest_http;
+pub use server::{serve_rest_http, serve_rest_http_with_shutdown};
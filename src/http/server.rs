@@ -9,11 +9,17 @@ use axum::{
     routing::any, Router,
 };
 use plexus_core::plexus::{Activation, PluginSchema};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
+use tower::ServiceExt;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::config::RestHttpConfig;
+use crate::hot_swap::Served;
 use crate::http::bridge::{ActivationRestBridge, RouteFn};
+use crate::slow::SlowRequests;
 
 /// Middleware to enforce `Authorization: Bearer <key>` on all REST HTTP requests.
 ///
@@ -71,7 +77,11 @@ async fn log_request_middleware(request: Request, next: Next) -> Response {
 /// Fallback handler for unmatched routes
 async fn fallback_handler(request: Request) -> impl IntoResponse {
     let method = request.method().clone();
-    let uri = request.uri().clone();
+    // Nested services see the path without their prefix
+    let uri = match request.extensions().get::<axum::extract::OriginalUri>() {
+        Some(original) => original.0.clone(),
+        None => request.uri().clone(),
+    };
 
     tracing::warn!(
         "REST HTTP: Unmatched route {} {} - Expected format: POST /rest/{{namespace}}/{{method}}",
@@ -98,6 +108,198 @@ async fn debug_handler() -> impl IntoResponse {
     }))
 }
 
+/// Holds the REST routes for the activation served at the time
+struct RouterSource<A: Activation> {
+    state: Mutex<(watch::Receiver<Served<A>>, Router)>,
+    config: RestHttpConfig,
+}
+
+impl<A: Activation> RouterSource<A> {
+    fn new(mut served: watch::Receiver<Served<A>>, config: RestHttpConfig) -> Self {
+        let router = build_router(&served.borrow_and_update(), &config);
+        Self {
+            state: Mutex::new((served, router)),
+            config,
+        }
+    }
+
+    /// The routes for a new request, rebuilt if the activation was swapped
+    fn router(&self) -> Router {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (served, router) = &mut *state;
+        if served.has_changed().unwrap_or(false) {
+            *router = build_router(&served.borrow_and_update(), &self.config);
+        }
+        router.clone()
+    }
+}
+
+/// Build the REST routes for an activation with the transport's settings
+fn build_router<A: Activation>(served: &Served<A>, config: &RestHttpConfig) -> Router {
+    // Create REST bridge
+    let bridge = ActivationRestBridge::with_server_info_and_schemas(
+        served.activation.clone(),
+        Some(config.server_name.clone()),
+        Some(config.server_version.clone()),
+        served.flat_schemas.clone(),
+    );
+
+    // Apply routing function if provided
+    let bridge = if let Some(rf) = served.route_fn.clone() {
+        bridge.with_router(rf)
+    } else {
+        bridge
+    };
+
+    // Apply authorization policy if provided
+    let bridge = if let Some(policy) = config.authorization.clone() {
+        bridge.with_authorization_policy(policy)
+    } else {
+        bridge
+    };
+
+    // Apply middleware, in the order it was added
+    let bridge = config
+        .middleware
+        .iter()
+        .fold(bridge, |bridge, middleware| bridge.with_middleware(middleware.clone()));
+
+    // Apply time limits, by method
+    let bridge = bridge.with_request_timeouts(config.request_timeouts.clone());
+
+    // Apply audit log if provided
+    let bridge = if let Some(auditor) = config.audit.clone() {
+        bridge.with_audit(auditor)
+    } else {
+        bridge
+    };
+
+    // Mounted activations live under their prefix
+    let mut router = bridge.into_router();
+    for mount in served.mounts.iter() {
+        let routes = (mount.rest)(
+            config.authorization.clone(),
+            config.middleware.clone(),
+            config.request_timeouts.clone(),
+            config.audit.clone(),
+        );
+        router = router.nest(&format!("/{}", mount.prefix), routes);
+    }
+
+    let router = router.fallback(fallback_handler);
+
+    let router = match config.slow_request_threshold {
+        Some(threshold) => {
+            let slow = SlowRequests::new(Some(threshold));
+            #[cfg(feature = "metrics")]
+            let slow = slow.with_metrics(config.metrics.clone());
+            router.layer(middleware::from_fn_with_state(slow, slow_request_middleware))
+        }
+        None => router,
+    };
+
+    let router = match config.access_log.clone() {
+        Some(log) => router.layer(middleware::from_fn_with_state(log, access_log_middleware)),
+        None => router,
+    };
+
+    #[cfg(feature = "metrics")]
+    let router = match config.metrics.clone() {
+        Some(metrics) => router.layer(middleware::from_fn_with_state(metrics, metrics_middleware)),
+        None => router,
+    };
+
+    router.layer(middleware::from_fn(trace_middleware))
+}
+
+/// The called method as `namespace.method` (with the mount prefix, if any),
+/// or `unknown` if no route matched
+fn route_method(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str().trim_start_matches('/').replace('/', "."))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Middleware warning about calls still running after the slow-request threshold
+///
+/// The params size is the request's `Content-Length`, if given.
+async fn slow_request_middleware(
+    axum::extract::State(slow): axum::extract::State<SlowRequests>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = route_method(&request);
+    let params_len = request
+        .headers()
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse().ok())
+        .unwrap_or(0);
+    slow.watch("rest", || (method, params_len), next.run(request)).await
+}
+
+/// Middleware handling every call in a `request` span
+///
+/// With feature `otel` the span continues the trace of the request's
+/// `traceparent` header.
+async fn trace_middleware(request: Request, next: Next) -> Response {
+    let span = crate::trace::request_span("rest", &route_method(&request));
+    #[cfg(feature = "otel")]
+    if let Some(parent) = crate::trace::from_headers(request.headers()) {
+        crate::trace::set_parent(&span, parent);
+    }
+    next.run(request).instrument(span).await
+}
+
+/// Middleware recording every call in the Prometheus metrics
+///
+/// The method is the matched route as `namespace.method` (with the mount
+/// prefix, if any), so only registered methods become label values; requests
+/// no route matched are recorded as `unknown`. The error code of a failed
+/// call is its HTTP status.
+#[cfg(feature = "metrics")]
+async fn metrics_middleware(
+    axum::extract::State(metrics): axum::extract::State<Arc<crate::metrics::TransportMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = route_method(&request);
+    let started = std::time::Instant::now();
+
+    let response = next.run(request).await;
+    let status = call_status(response.status());
+    metrics.record_call("rest", &method, &status, started.elapsed());
+    response
+}
+
+/// Middleware writing every call to the access log, with the peer address
+///
+/// The method is the matched route as `namespace.method`, or `unknown` when no
+/// route matched. The error code of a failed call is its HTTP status.
+async fn access_log_middleware(
+    axum::extract::State(log): axum::extract::State<Arc<crate::access_log::AccessLog>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = route_method(&request);
+    let client = crate::middleware::peer_of(request.extensions()).map(|addr| addr.to_string());
+    let started = std::time::Instant::now();
+
+    let response = next.run(request).await;
+    log.record("rest", &method, call_status(response.status()), started.elapsed(), client, None);
+    response
+}
+
+/// Outcome of a call answered with `status`
+fn call_status(status: StatusCode) -> crate::audit::AuditStatus {
+    match status {
+        s if s.is_success() => crate::audit::AuditStatus::Ok,
+        StatusCode::FORBIDDEN => crate::audit::AuditStatus::Denied,
+        s => crate::audit::AuditStatus::Error { code: s.as_u16() as i32 },
+    }
+}
+
 /// Start a standalone REST HTTP server
 ///
 /// This function creates an Axum HTTP server that exposes activation methods
@@ -143,46 +345,104 @@ pub async fn serve_rest_http<A: Activation>(
     config: RestHttpConfig,
     api_key: Option<String>,
 ) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    serve_rest_http_with_shutdown(
+        activation,
+        flat_schemas,
+        route_fn,
+        config,
+        api_key,
+        listener,
+        CancellationToken::new(),
+    )
+    .await
+}
+
+/// Start a REST HTTP server on an already bound listener that stops when
+/// `shutdown` is cancelled
+///
+/// Like [`serve_rest_http`], but `config.addr` is ignored in favour of
+/// `listener`, so a listener bound to port 0 serves on an OS-assigned port.
+/// Cancelling `shutdown` closes the listener and lets in-flight requests
+/// finish; the returned task completes once they have.
+pub async fn serve_rest_http_with_shutdown<A: Activation>(
+    activation: Arc<A>,
+    flat_schemas: Option<Vec<PluginSchema>>,
+    route_fn: Option<RouteFn>,
+    config: RestHttpConfig,
+    api_key: Option<String>,
+    listener: tokio::net::TcpListener,
+    shutdown: CancellationToken,
+) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
+    let served = Served::fixed(activation, flat_schemas, route_fn);
+    serve_rest_http_served(served, config, api_key, listener, shutdown).await
+}
+
+/// Serve REST HTTP, answering each request with the activation current when it arrives
+pub(crate) async fn serve_rest_http_served<A: Activation>(
+    served: watch::Receiver<Served<A>>,
+    config: RestHttpConfig,
+    api_key: Option<String>,
+    listener: tokio::net::TcpListener,
+    shutdown: CancellationToken,
+) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
+    let local_addr = listener.local_addr()?;
     tracing::info!(
         "Starting REST HTTP server at http://{} (server: {}, version: {})",
-        config.addr,
+        local_addr,
         config.server_name,
         config.server_version
     );
 
-    // Create REST bridge
-    let bridge = ActivationRestBridge::with_server_info_and_schemas(
-        activation,
-        Some(config.server_name.clone()),
-        Some(config.server_version.clone()),
-        flat_schemas,
-    );
-
-    // Apply routing function if provided
-    let bridge = if let Some(rf) = route_fn {
-        bridge.with_router(rf)
-    } else {
-        bridge
-    };
-
-    // Build router
-    let rest_router = bridge.into_router();
+    // The REST routes are rebuilt whenever the activation is swapped
+    let routes = Arc::new(RouterSource::new(served, config.clone()));
+    let rest_router = tower::service_fn(move |request: Request| {
+        let router = routes.router();
+        async move { router.oneshot(request).await }
+    });
 
     // Build main app with middleware
-    let app = Router::new()
-        .nest("/rest", rest_router)
+    let mut app = Router::new()
+        .nest_service("/rest", rest_router)
         .route("/debug", any(debug_handler))
         .fallback(fallback_handler)
-        .layer(middleware::from_fn(log_request_middleware))
-        .layer(middleware::from_fn_with_state(api_key.clone(), auth_middleware));
+        .layer(middleware::from_fn(log_request_middleware));
+
+    // Rate limiting runs inside the auth layer so it only counts authenticated requests
+    if let Some(rate_limit) = config.rate_limit.clone() {
+        let limiter = Arc::new(crate::rate_limit::RateLimiter::new(rate_limit));
+        app = app.layer(middleware::from_fn_with_state(
+            limiter,
+            crate::rate_limit::rate_limit_middleware,
+        ));
+    }
+
+    let app = app.layer(middleware::from_fn_with_state(api_key.clone(), auth_middleware));
+
+    // Requests failing authentication never reach the limit above; limit them by IP
+    let app = match config.rate_limit {
+        Some(ref rate_limit) => app.layer(middleware::from_fn_with_state(
+            crate::rate_limit::auth_failure_limiter(rate_limit),
+            crate::rate_limit::auth_failure_limit_middleware,
+        )),
+        None => app,
+    };
 
     // Start server
-    let listener = tokio::net::TcpListener::bind(config.addr).await?;
-    tracing::info!("REST HTTP server listening on {}", config.addr);
+    let listener = crate::ip_filter::FilteredListener::new(
+        listener,
+        config.ip_filter.clone(),
+        "REST HTTP",
+    );
+    tracing::info!("REST HTTP server listening on {}", local_addr);
 
-    let handle = tokio::spawn(async move {
-        axum::serve(listener, app)
-            .await
+    let handle = crate::task::spawn("plexus.rest.server", async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { shutdown.cancelled().await })
+        .await
     });
 
     Ok(handle)
@@ -0,0 +1,183 @@
+//! CIDR-based allow/deny filtering of incoming connections
+//!
+//! Listeners consult an [`IpFilter`] as soon as a TCP connection is accepted and
+//! drop peers from unauthorized networks before any TLS handshake or HTTP
+//! parsing happens. Rejected peers are logged at `warn`.
+
+use std::net::IpAddr;
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+/// Allow/deny lists of networks
+///
+/// A peer is accepted when it matches at least one allowed network (or no
+/// allowed networks are configured) and no denied network. Deny always wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpFilter {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl IpFilter {
+    /// A filter that accepts every peer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only accept peers in the given networks (`10.0.0.0/8`, `::1/128`, or a bare address)
+    pub fn allow<I, S>(mut self, networks: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for network in networks {
+            self.allow.push(parse_network(network.as_ref())?);
+        }
+        Ok(self)
+    }
+
+    /// Reject peers in the given networks
+    pub fn deny<I, S>(mut self, networks: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for network in networks {
+            self.deny.push(parse_network(network.as_ref())?);
+        }
+        Ok(self)
+    }
+
+    /// Whether a peer address is accepted
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        // IPv4 clients on a dual-stack socket show up as `::ffff:a.b.c.d`
+        let ip = ip.to_canonical();
+        let allowed = self.allow.is_empty() || self.allow.iter().any(|net| net.contains(&ip));
+        allowed && !self.deny.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Check a freshly accepted peer, logging rejections
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn check_peer(&self, peer: SocketAddr, transport: &str) -> bool {
+        let allowed = self.allows(peer.ip());
+        if !allowed {
+            tracing::warn!("{} connection from {} rejected by IP filter", transport, peer);
+        }
+        allowed
+    }
+}
+
+fn parse_network(network: &str) -> Result<IpNet> {
+    let network = network.trim();
+    if network.contains('/') {
+        network
+            .parse()
+            .with_context(|| format!("Invalid network {:?}", network))
+    } else {
+        let ip: IpAddr = network
+            .parse()
+            .with_context(|| format!("Invalid IP address {:?}", network))?;
+        Ok(IpNet::from(ip))
+    }
+}
+
+/// TCP listener for `axum::serve` that drops peers rejected by an [`IpFilter`]
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) struct FilteredListener {
+    inner: tokio::net::TcpListener,
+    filter: Option<IpFilter>,
+    transport: &'static str,
+}
+
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+impl FilteredListener {
+    pub(crate) fn new(
+        inner: tokio::net::TcpListener,
+        filter: Option<IpFilter>,
+        transport: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            filter,
+            transport,
+        }
+    }
+}
+
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+impl axum::serve::Listener for FilteredListener {
+    type Io = tokio::net::TcpStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (stream, peer) = axum::serve::Listener::accept(&mut self.inner).await;
+            match self.filter {
+                Some(ref filter) if !filter.check_peer(peer, self.transport) => continue,
+                _ => return (stream, peer),
+            }
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+/// axum-server acceptor that drops peers rejected by an [`IpFilter`] before
+/// handing the connection to the inner acceptor (e.g. the TLS handshake)
+#[cfg(all(feature = "tls", feature = "mcp-http"))]
+#[derive(Clone)]
+pub(crate) struct IpFilterAcceptor<A> {
+    inner: A,
+    filter: Option<IpFilter>,
+    transport: &'static str,
+}
+
+#[cfg(all(feature = "tls", feature = "mcp-http"))]
+impl<A> IpFilterAcceptor<A> {
+    pub(crate) fn new(inner: A, filter: Option<IpFilter>, transport: &'static str) -> Self {
+        Self {
+            inner,
+            filter,
+            transport,
+        }
+    }
+}
+
+#[cfg(all(feature = "tls", feature = "mcp-http"))]
+impl<A, S> axum_server::accept::Accept<tokio::net::TcpStream, S> for IpFilterAcceptor<A>
+where
+    A: axum_server::accept::Accept<tokio::net::TcpStream, S>,
+{
+    type Stream = A::Stream;
+    type Service = A::Service;
+    type Future = futures::future::Either<
+        A::Future,
+        std::future::Ready<std::io::Result<(Self::Stream, Self::Service)>>,
+    >;
+
+    fn accept(&self, stream: tokio::net::TcpStream, service: S) -> Self::Future {
+        if let Some(ref filter) = self.filter {
+            // A peer the filter cannot check is not on the allow-list
+            let allowed = match stream.peer_addr() {
+                Ok(peer) => filter.check_peer(peer, self.transport),
+                Err(e) => {
+                    tracing::warn!("{} connection rejected: no peer address for the IP filter ({})", self.transport, e);
+                    false
+                }
+            };
+            if !allowed {
+                return futures::future::Either::Right(std::future::ready(Err(
+                    std::io::Error::new(std::io::ErrorKind::PermissionDenied, "peer rejected by IP filter"),
+                )));
+            }
+        }
+        futures::future::Either::Left(self.inner.accept(stream, service))
+    }
+}
@@ -29,20 +29,62 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Each transport is a cargo feature (`websocket`, `stdio`, `mcp-http`, all on
+//! by default, and `http-gateway`), so a binary that serves only stdio can
+//! leave out the HTTP and WebSocket server stacks. The `metrics` feature adds
+//! Prometheus request, connection and session metrics, `otel` continues
+//! callers' W3C traces in the request spans, `console` names the spawned
+//! tasks for tokio-console, `mcp-logging` forwards tracing events to MCP
+//! clients, and `stdio-logging` logs stdio servers to stderr or a file.
 
 pub mod request;
 
+pub mod access_log;
+pub mod audit;
+pub mod auth;
+pub mod broadcast;
+pub mod capture;
+#[cfg(feature = "cli")]
+pub mod cli;
 #[cfg(feature = "mcp-gateway")]
 pub mod combined;
 pub mod config;
+pub mod config_env;
+#[cfg(feature = "config-file")]
+pub mod config_file;
+#[cfg(feature = "console")]
+pub mod console;
+pub mod hot_swap;
+pub mod ip_filter;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
+pub mod mount;
+pub mod observer;
+pub mod origin_check;
+pub mod rate_limit;
+pub mod redact;
+pub mod resume;
+#[cfg(feature = "websocket")]
+pub mod rpc_middleware;
 pub mod server;
+mod sink;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+mod slow;
+#[cfg(feature = "stdio")]
 pub mod stdio;
+#[cfg(feature = "stdio-logging")]
+pub mod stdio_logging;
+pub mod supervisor;
+mod task;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+mod trace;
+#[cfg(feature = "websocket")]
 pub mod websocket;
 
-#[cfg(feature = "sqlite-sessions")]
-pub mod mcp;
-
-#[cfg(not(feature = "sqlite-sessions"))]
 pub mod mcp;
 
 #[cfg(feature = "http-gateway")]
@@ -51,23 +93,97 @@ pub mod http;
 // Re-export main API
 #[cfg(feature = "mcp-gateway")]
 pub use combined::serve_combined;
-pub use config::{McpHttpConfig, SessionStorage, StdioConfig, TransportConfig, WebSocketConfig};
+pub use access_log::{AccessLog, AccessRecord};
+pub use audit::{AuditRecord, AuditSink, AuditStatus, Auditor, FileAuditSink};
+pub use auth::{ApiKeyAuth, ApiKeyIdentity, AuthorizationPolicy, CallerIdentity, MethodAllowList};
+pub use broadcast::Broadcaster;
+#[cfg(feature = "jwt-auth")]
+pub use auth::jwt::{JwtAuth, JwtClaims};
+pub use capture::{Capture, CaptureRecord, CaptureSink, FileCaptureSink, ReplayedCall, Replayer};
+pub use hot_swap::ActivationSwap;
+pub use config_env::EnvConfigError;
+pub use config::{
+    CorsConfig, McpHttpConfig, NotificationOverflow, RequestLogging, SessionStorage, StdioConfig,
+    StdioFraming, TransportConfig, WebSocketConfig,
+};
+#[cfg(feature = "mcp-http")]
+pub use config::RouterLayer;
+pub use ip_filter::IpFilter;
+pub use middleware::{CallContext, CallOutcome, CallRejection, TransportMiddleware};
+pub use mount::MountedActivation;
+pub use observer::{ConnectionInfo, SessionInfo, TransportObserver};
+pub use origin_check::OriginCheck;
+pub use rate_limit::{RateLimitConfig, RateLimitKey};
+pub use redact::Redaction;
+pub use resume::SubscriptionResume;
+#[cfg(feature = "websocket")]
+pub use rpc_middleware::RpcMiddleware;
+pub use supervisor::{RestartPolicy, TransportCrash};
 
 #[cfg(feature = "http-gateway")]
 pub use config::RestHttpConfig;
 
-pub use server::{TransportServer, TransportServerBuilder};
+#[cfg(feature = "config-file")]
+pub use config_file::ConfigFileError;
+
+#[cfg(feature = "cli")]
+pub use cli::TransportArgs;
+
+#[cfg(feature = "metrics")]
+pub use metrics::{MetricsConfig, TransportMetrics};
+
+#[cfg(feature = "tls")]
+pub use config::TlsConfig;
+#[cfg(feature = "tls")]
+pub use tls::PeerCertificate;
+
+pub use server::{BindError, BindFailure, RouteFn, RunningServer, ServerControl, TransportServer, TransportServerBuilder, TransportTask};
 pub use request::{ValidOrigin, init_allowed_origins};
 
 // Re-export MCP bridge for advanced usage
 #[cfg(feature = "sqlite-sessions")]
 pub use mcp::{bridge::ActivationMcpBridge, session::SqliteSessionManager};
 
-#[cfg(not(feature = "sqlite-sessions"))]
+#[cfg(all(feature = "mcp-http", not(feature = "sqlite-sessions")))]
 pub use mcp::bridge::ActivationMcpBridge;
 
-pub use mcp::bridge::RouteFn;
+#[cfg(feature = "redis-sessions")]
+pub use mcp::redis_session::RedisSessionManager;
+
+#[cfg(feature = "mcp-http")]
+pub use mcp::store::{SessionStore, SessionStoreError};
+#[cfg(feature = "mcp-http")]
+pub use mcp::admin::{session_admin_router, McpSessionInfo, SessionAdmin};
+#[cfg(feature = "mcp-http")]
+pub use mcp::client::{ClientRequestError, McpClient};
+#[cfg(feature = "mcp-logging")]
+pub use mcp::logging::{McpLogForwarder, McpLogLayer};
+#[cfg(feature = "mcp-http")]
+pub use mcp::progress::ProgressHandle;
+#[cfg(all(feature = "mcp-http", feature = "stdio"))]
+pub use mcp::stdio::{serve_mcp_io, serve_mcp_stdio};
+#[cfg(feature = "mcp-http")]
+pub use mcp::prompts::{PromptError, PromptProvider};
+#[cfg(feature = "mcp-http")]
+pub use mcp::completion::{CompletionError, CompletionProvider};
+#[cfg(feature = "mcp-http")]
+pub use mcp::context::SessionContext;
+#[cfg(feature = "mcp-http")]
+pub use mcp::error_map::{ErrorMapper, MappedError};
+#[cfg(feature = "mcp-http")]
+pub use mcp::metadata::{ToolMetadata, ToolMetadataProvider};
+#[cfg(feature = "mcp-http")]
+pub use mcp::resources::{ResourceError, ResourceProvider};
+#[cfg(feature = "mcp-http")]
+pub use mcp::schema::SchemaProvider;
+#[cfg(feature = "mcp-http")]
+pub use mcp::tool_list::ToolListNotifier;
+
+pub use mcp::capabilities::McpCapabilities;
+pub use mcp::filter::ToolFilter;
+pub use mcp::naming::ToolNaming;
+pub use mcp::timeout::{ToolTimeouts, TIMEOUT_ERROR_CODE};
 
 // Re-export REST HTTP bridge for advanced usage
 #[cfg(feature = "http-gateway")]
-pub use http::{ActivationRestBridge, serve_rest_http};
+pub use http::{ActivationRestBridge, serve_rest_http, serve_rest_http_with_shutdown};
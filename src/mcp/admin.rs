@@ -0,0 +1,272 @@
+//! MCP session administration
+//!
+//! A [`SessionAdmin`] lists the sessions of the MCP endpoints it is configured
+//! on and terminates them on request, e.g. to evict a stuck agent without
+//! restarting the server. Use it from Rust, or mount
+//! [`session_admin_router`] to expose it over HTTP:
+//!
+//! | Route | Method checked by the policy | Effect |
+//! |-------|------------------------------|--------|
+//! | `GET /sessions` | `sessions.list` | Every session, oldest first |
+//! | `GET /sessions/{id}` | `sessions.get` | One session, or 404 |
+//! | `DELETE /sessions/{id}` | `sessions.terminate` | Terminate a session: 204, or 404 if unknown |
+//!
+//! Every route needs an API key (401 otherwise) and, with a policy, the
+//! key's identity must be allowed the route's method (403 otherwise).
+//! Session ids let anyone holding them act as the session, so the routes
+//! answer with [`McpSessionInfo::masked_id`] in their place; `{id}` is either.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::auth::policy::forbidden_message;
+use crate::auth::{ApiKeyAuth, AuthorizationPolicy, CallerIdentity};
+
+/// A live MCP session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpSessionInfo {
+    /// The `Mcp-Session-Id` of the session
+    pub id: String,
+    /// When the session was created (milliseconds since the Unix epoch)
+    pub created_at_ms: u64,
+    /// When the session last handled a request or closed a stream
+    pub last_seen_ms: u64,
+    /// SSE streams currently open; the session is in use while any are
+    pub open_streams: usize,
+    /// `clientInfo.name` from the client's initialize request
+    pub client_name: Option<String>,
+    /// `clientInfo.version` from the client's initialize request
+    pub client_version: Option<String>,
+    /// The protocol version the client asked for
+    pub protocol_version: Option<String>,
+}
+
+impl McpSessionInfo {
+    /// Time since the session was created
+    pub fn age(&self) -> Duration {
+        since(self.created_at_ms)
+    }
+
+    /// Time since the session was last used
+    pub fn idle(&self) -> Duration {
+        since(self.last_seen_ms)
+    }
+
+    /// Stand-in for the id that does not reveal it: `sha256:` and the first
+    /// 16 hex digits of the id's SHA-256
+    pub fn masked_id(&self) -> String {
+        let digest = Sha256::digest(self.id.as_bytes());
+        let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sha256:{}", hex)
+    }
+
+    /// Whether `id` is the session's id or its [`masked_id`](Self::masked_id)
+    fn is(&self, id: &str) -> bool {
+        self.id == id || (id.starts_with("sha256:") && self.masked_id() == id)
+    }
+
+    /// The info with its id masked
+    fn masked(mut self) -> Self {
+        self.id = self.masked_id();
+        self
+    }
+}
+
+fn since(timestamp_ms: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    Duration::from_millis(now.saturating_sub(timestamp_ms))
+}
+
+/// The sessions of one MCP endpoint
+pub(crate) trait AdminSessions: Send + Sync {
+    fn list(&self) -> Vec<McpSessionInfo>;
+
+    /// One session, if the endpoint knows it
+    fn get(&self, id: &str) -> Option<McpSessionInfo> {
+        self.list().into_iter().find(|session| session.id == id)
+    }
+
+    /// Close a session; `false` if the endpoint does not know it
+    fn terminate<'a>(&'a self, id: &'a str) -> BoxFuture<'a, anyhow::Result<bool>>;
+}
+
+/// Lists and terminates the sessions of MCP endpoints
+///
+/// Configure one with `McpHttpConfig::with_session_admin`; the endpoint
+/// registers its sessions when it starts. One admin can be shared by several
+/// endpoints.
+///
+/// ```rust,ignore
+/// let admin = Arc::new(SessionAdmin::new());
+/// let mcp_config = McpHttpConfig::new(8889).with_session_admin(admin.clone());
+///
+/// for session in admin.sessions() {
+///     if session.idle() > Duration::from_secs(3600) {
+///         admin.terminate(&session.id).await?;
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct SessionAdmin {
+    endpoints: Mutex<Vec<Weak<dyn AdminSessions>>>,
+}
+
+impl std::fmt::Debug for SessionAdmin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionAdmin").finish_non_exhaustive()
+    }
+}
+
+impl SessionAdmin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the sessions of an endpoint for as long as it runs
+    pub(crate) fn register(&self, endpoint: Weak<dyn AdminSessions>) {
+        self.lock().push(endpoint);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Weak<dyn AdminSessions>>> {
+        self.endpoints.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The endpoints still running
+    fn endpoints(&self) -> Vec<Arc<dyn AdminSessions>> {
+        let mut endpoints = self.lock();
+        endpoints.retain(|endpoint| endpoint.strong_count() > 0);
+        endpoints.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// Every live session, oldest first
+    pub fn sessions(&self) -> Vec<McpSessionInfo> {
+        let mut sessions: Vec<_> = self.endpoints().iter().flat_map(|endpoint| endpoint.list()).collect();
+        sessions.sort_by(|a, b| a.created_at_ms.cmp(&b.created_at_ms).then_with(|| a.id.cmp(&b.id)));
+        sessions
+    }
+
+    /// A live session by id
+    pub fn session(&self, id: &str) -> Option<McpSessionInfo> {
+        self.endpoints()
+            .iter()
+            .find_map(|endpoint| endpoint.list().into_iter().find(|session| session.id == id))
+    }
+
+    /// Close a session as if its client had deleted it
+    ///
+    /// Returns `false` if no endpoint knows the session. The client's next
+    /// request gets a 404 and it starts a new session.
+    pub async fn terminate(&self, id: &str) -> anyhow::Result<bool> {
+        for endpoint in self.endpoints() {
+            if endpoint.terminate(id).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// A router serving `admin` under `/sessions`
+///
+/// Callers must present a key accepted by `auth` (e.g. the server's,
+/// `ApiKeyAuth::keys([key])`); with `policy`, their identity must also be
+/// allowed the method of the route, as listed in the [module docs](self).
+pub fn session_admin_router(
+    admin: Arc<SessionAdmin>,
+    auth: ApiKeyAuth,
+    policy: Option<Arc<dyn AuthorizationPolicy>>,
+) -> axum::Router {
+    axum::Router::new()
+        .route("/sessions", axum::routing::get(list_sessions))
+        .route(
+            "/sessions/{id}",
+            axum::routing::get(get_session).delete(terminate_session),
+        )
+        .with_state(AdminState { admin, auth, policy })
+}
+
+/// State of the [`session_admin_router`] routes
+#[derive(Clone)]
+struct AdminState {
+    admin: Arc<SessionAdmin>,
+    auth: ApiKeyAuth,
+    policy: Option<Arc<dyn AuthorizationPolicy>>,
+}
+
+impl AdminState {
+    /// Check the caller's key, then the policy for `full_method`
+    async fn authorize(&self, headers: &HeaderMap, full_method: &str) -> Result<(), Response> {
+        let Some(identity) = self.auth.authenticate(headers).await else {
+            tracing::warn!("Session admin auth rejected: missing or invalid API key ({})", full_method);
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                [(http::header::WWW_AUTHENTICATE, "Bearer realm=\"plexus\"")],
+                "Unauthorized",
+            )
+                .into_response());
+        };
+        if let Some(ref policy) = self.policy {
+            let caller = CallerIdentity::ApiKey(identity);
+            if !policy.allow_method(&caller, full_method) {
+                tracing::warn!("Session admin call denied: {} (caller={})", full_method, caller.label());
+                return Err((StatusCode::FORBIDDEN, forbidden_message(full_method)).into_response());
+            }
+        }
+        Ok(())
+    }
+
+    /// The admin's session known by its id or masked id
+    fn session(&self, id: &str) -> Option<McpSessionInfo> {
+        self.admin.sessions().into_iter().find(|session| session.is(id))
+    }
+}
+
+async fn list_sessions(State(state): State<AdminState>, headers: HeaderMap) -> Response {
+    if let Err(response) = state.authorize(&headers, "sessions.list").await {
+        return response;
+    }
+    let sessions: Vec<_> = state.admin.sessions().into_iter().map(McpSessionInfo::masked).collect();
+    Json(sessions).into_response()
+}
+
+async fn get_session(State(state): State<AdminState>, headers: HeaderMap, Path(id): Path<String>) -> Response {
+    if let Err(response) = state.authorize(&headers, "sessions.get").await {
+        return response;
+    }
+    match state.session(&id) {
+        Some(session) => Json(session.masked()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn terminate_session(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(response) = state.authorize(&headers, "sessions.terminate").await {
+        return response;
+    }
+    let Some(session) = state.session(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match state.admin.terminate(&session.id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("{}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
@@ -6,26 +6,52 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::StreamExt;
-use plexus_core::plexus::{types::PlexusStreamItem, Activation, PlexusError, PlexusStream, PluginSchema};
+use plexus_core::plexus::{types::PlexusStreamItem, Activation, PlexusError, PluginSchema};
 use rmcp::{
     model::*,
-    service::{RequestContext, RoleServer},
+    service::{NotificationContext, RequestContext, RoleServer},
     ErrorData as McpError, ServerHandler,
 };
 use serde_json::json;
 use form_urlencoded;
+use tracing::Instrument;
 
-/// A function that routes a namespaced method call (e.g., "loopback.permit") to the
-/// correct activation. Used by hub activations to dispatch child calls via `hub.route()`.
-///
-/// Signature: `fn(method: String, params: Value) -> Future<Output = Result<PlexusStream, PlexusError>>`
-pub type RouteFn = Arc<
-    dyn Fn(String, serde_json::Value) -> Pin<Box<dyn Future<Output = Result<PlexusStream, PlexusError>> + Send>>
-        + Send
-        + Sync,
->;
+use crate::access_log::AccessLog;
+use crate::audit::{AuditStatus, Auditor};
+use crate::auth::policy::{forbidden_message, FORBIDDEN_ERROR_CODE};
+use crate::auth::{AuthorizationPolicy, CallerIdentity};
+use crate::broadcast::Broadcaster;
+use crate::capture::{Capture, Direction};
+use crate::mcp::capabilities::McpCapabilities;
+use crate::mcp::client::McpClient;
+use crate::mcp::completion::{complete_result, CompletionProvider};
+use crate::mcp::context::SessionContext;
+use crate::mcp::error_map::ErrorMapper;
+use crate::mcp::filter::ToolFilter;
+#[cfg(feature = "mcp-logging")]
+use crate::mcp::logging::McpLogForwarder;
+use crate::mcp::logging::SessionLogging;
+use crate::mcp::metadata::ToolMetadataProvider;
+use crate::mcp::naming::ToolNaming;
+use crate::mcp::output::{chunk_text, OutputBuffer};
+use crate::mcp::pagination::paginate;
+use crate::mcp::ping::Pings;
+use crate::mcp::progress::ProgressHandle;
+use crate::mcp::prompts::PromptProvider;
+use crate::mcp::resources::{ResourceProvider, ResourceSubscriptions};
+use crate::mcp::schema::{input_schema, output_schema, structured_content, SchemaProvider};
+use crate::mcp::timeout::{ToolTimeouts, TIMEOUT_ERROR_CODE};
+use crate::mcp::tool_list::ToolListNotifier;
+use crate::middleware::{self, CallContext, CallRejection, TransportMiddleware};
+#[cfg(feature = "metrics")]
+use crate::metrics::{GaugeGuard, TransportMetrics};
+use crate::slow::SlowRequests;
+use crate::trace::request_span;
+
+pub use crate::server::RouteFn;
 
 // =============================================================================
 // Schema Transformation
@@ -44,27 +70,9 @@ fn schemas_to_rmcp_tools(schemas: Vec<PluginSchema>) -> Vec<Tool> {
                 let name = format!("{}.{}", namespace, method.name);
                 let description = method.description.clone();
 
-                // Convert schemars::Schema to JSON, ensure "type": "object" exists
-                let input_schema = method
-                    .params
-                    .and_then(|s| serde_json::to_value(s).ok())
-                    .and_then(|v| v.as_object().cloned())
-                    .map(|mut obj| {
-                        // MCP requires "type": "object" at schema root
-                        if !obj.contains_key("type") {
-                            obj.insert("type".to_string(), json!("object"));
-                        }
-                        Arc::new(obj)
-                    })
-                    .unwrap_or_else(|| {
-                        // Empty params = empty object schema
-                        Arc::new(serde_json::Map::from_iter([(
-                            "type".to_string(),
-                            json!("object"),
-                        )]))
-                    });
-
-                Tool::new(name, description, input_schema)
+                // Empty params = empty object schema
+                let params = method.params.and_then(|s| serde_json::to_value(s).ok());
+                Tool::new(name, description, input_schema(params))
             })
         })
         .collect()
@@ -120,6 +128,71 @@ pub struct ActivationMcpBridge<A: Activation> {
     /// When set, `call_tool` uses this to dispatch namespaced calls (e.g., "loopback.permit")
     /// via `hub.route()` instead of stripping the namespace and calling `activation.call()`.
     router: Option<RouteFn>,
+    /// Optional per-method authorization policy.
+    /// When set, `call_tool` rejects calls the caller may not make and
+    /// `list_tools` hides those tools.
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    /// Middleware run around every tool call the policy lets through.
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+    /// Optional include/exclude patterns restricting which methods are exposed as tools.
+    tool_filter: Option<ToolFilter>,
+    /// Optional audit log recording every tool call.
+    audit: Option<Arc<Auditor>>,
+    /// Optional access log with a JSON line per tool call.
+    access_log: Option<Arc<AccessLog>>,
+    /// Optional capture of every tool call, its result and notifications.
+    capture: Option<Arc<Capture>>,
+    /// Optional Prometheus metrics recording every tool call.
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<TransportMetrics>>,
+    /// Counts the session served by this bridge as open while any clone lives.
+    #[cfg(feature = "metrics")]
+    session: Option<Arc<GaugeGuard>>,
+    /// Tools of other activations, exposed as `prefix.namespace.method`.
+    mounts: Vec<(String, Arc<dyn ToolSource>)>,
+    /// Tool calls running longer than this are logged as slow.
+    slow_request_threshold: Option<Duration>,
+    /// Optional source of the resources served by `resources/list` and `resources/read`.
+    resources: Option<Arc<dyn ResourceProvider>>,
+    /// Optional source of the prompts served by `prompts/list` and `prompts/get`.
+    prompts: Option<Arc<dyn PromptProvider>>,
+    /// Optional source of argument suggestions for `completion/complete`.
+    completions: Option<Arc<dyn CompletionProvider>>,
+    /// Optional translation of activation errors into MCP errors and tool results.
+    error_mapper: Option<Arc<dyn ErrorMapper>>,
+    /// The log level the session asked for with `logging/setLevel`.
+    logging: Arc<SessionLogging>,
+    /// The resources the session subscribed to.
+    subscriptions: Arc<ResourceSubscriptions>,
+    /// Optional tool input and output schemas, overriding the activation's.
+    schema_provider: Option<Arc<dyn SchemaProvider>>,
+    /// Optional tool titles, descriptions and annotations.
+    tool_metadata: Option<Arc<dyn ToolMetadataProvider>>,
+    /// Tools per `tools/list` page; `None` lists every tool at once.
+    tools_page_size: Option<usize>,
+    /// Time limits of tool calls.
+    tool_timeouts: ToolTimeouts,
+    /// Send each data item as a progress message while the call runs.
+    partial_results: bool,
+    /// Keep only the most recent output, up to this many bytes, for the final result.
+    max_result_bytes: Option<usize>,
+    /// Optional notifier of tool list changes, and of the flat schemas that replace `flat_schemas`.
+    tool_list: Option<ToolListNotifier>,
+    /// Optional server-wide notifications, forwarded to initialized sessions.
+    broadcaster: Option<Broadcaster>,
+    /// The names clients see for `namespace.method` tools.
+    tool_naming: ToolNaming,
+    /// Usage instructions sent with the server info; the activation's description by default.
+    instructions: Option<String>,
+    /// The capabilities the bridge may declare and serve.
+    capabilities: McpCapabilities,
+    /// Optional pings of the session's client, closing the session when they go unanswered.
+    pings: Option<Pings>,
+    /// Callers are the local process on a stdio pipe rather than HTTP clients.
+    local: bool,
+    /// Optional forwarder of tracing events to sessions that set a log level.
+    #[cfg(feature = "mcp-logging")]
+    log_forwarder: Option<Arc<McpLogForwarder>>,
 }
 
 impl<A: Activation> ActivationMcpBridge<A> {
@@ -130,6 +203,39 @@ impl<A: Activation> ActivationMcpBridge<A> {
             server_name_override: None,
             server_version_override: None,
             router: None,
+            authorization: None,
+            middleware: Vec::new(),
+            tool_filter: None,
+            audit: None,
+            access_log: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            session: None,
+            mounts: Vec::new(),
+            slow_request_threshold: None,
+            resources: None,
+            prompts: None,
+            completions: None,
+            error_mapper: None,
+            logging: Default::default(),
+            subscriptions: Default::default(),
+            schema_provider: None,
+            tool_metadata: None,
+            tools_page_size: None,
+            tool_timeouts: ToolTimeouts::new(),
+            partial_results: false,
+            max_result_bytes: None,
+            tool_list: None,
+            broadcaster: None,
+            tool_naming: ToolNaming::new(),
+            instructions: None,
+            capabilities: McpCapabilities::all(),
+            pings: None,
+            local: false,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: None,
         }
     }
 
@@ -142,6 +248,39 @@ impl<A: Activation> ActivationMcpBridge<A> {
             server_name_override: None,
             server_version_override: None,
             router: None,
+            authorization: None,
+            middleware: Vec::new(),
+            tool_filter: None,
+            audit: None,
+            access_log: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            session: None,
+            mounts: Vec::new(),
+            slow_request_threshold: None,
+            resources: None,
+            prompts: None,
+            completions: None,
+            error_mapper: None,
+            logging: Default::default(),
+            subscriptions: Default::default(),
+            schema_provider: None,
+            tool_metadata: None,
+            tools_page_size: None,
+            tool_timeouts: ToolTimeouts::new(),
+            partial_results: false,
+            max_result_bytes: None,
+            tool_list: None,
+            broadcaster: None,
+            tool_naming: ToolNaming::new(),
+            instructions: None,
+            capabilities: McpCapabilities::all(),
+            pings: None,
+            local: false,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: None,
         }
     }
 
@@ -157,6 +296,39 @@ impl<A: Activation> ActivationMcpBridge<A> {
             server_name_override: name,
             server_version_override: version,
             router: None,
+            authorization: None,
+            middleware: Vec::new(),
+            tool_filter: None,
+            audit: None,
+            access_log: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            session: None,
+            mounts: Vec::new(),
+            slow_request_threshold: None,
+            resources: None,
+            prompts: None,
+            completions: None,
+            error_mapper: None,
+            logging: Default::default(),
+            subscriptions: Default::default(),
+            schema_provider: None,
+            tool_metadata: None,
+            tools_page_size: None,
+            tool_timeouts: ToolTimeouts::new(),
+            partial_results: false,
+            max_result_bytes: None,
+            tool_list: None,
+            broadcaster: None,
+            tool_naming: ToolNaming::new(),
+            instructions: None,
+            capabilities: McpCapabilities::all(),
+            pings: None,
+            local: false,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: None,
         }
     }
 
@@ -173,6 +345,39 @@ impl<A: Activation> ActivationMcpBridge<A> {
             server_name_override: name,
             server_version_override: version,
             router: None,
+            authorization: None,
+            middleware: Vec::new(),
+            tool_filter: None,
+            audit: None,
+            access_log: None,
+            capture: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            session: None,
+            mounts: Vec::new(),
+            slow_request_threshold: None,
+            resources: None,
+            prompts: None,
+            completions: None,
+            error_mapper: None,
+            logging: Default::default(),
+            subscriptions: Default::default(),
+            schema_provider: None,
+            tool_metadata: None,
+            tools_page_size: None,
+            tool_timeouts: ToolTimeouts::new(),
+            partial_results: false,
+            max_result_bytes: None,
+            tool_list: None,
+            broadcaster: None,
+            tool_naming: ToolNaming::new(),
+            instructions: None,
+            capabilities: McpCapabilities::all(),
+            pings: None,
+            local: false,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: None,
         }
     }
 
@@ -184,6 +389,396 @@ impl<A: Activation> ActivationMcpBridge<A> {
         self.router = Some(router);
         self
     }
+
+    /// Set the authorization policy consulted before every tool call.
+    ///
+    /// The caller is resolved from the HTTP request extensions populated by the
+    /// auth middleware (anonymous when there are none).
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every tool call, after the ones added before it.
+    ///
+    /// The middleware sees the tool arguments as the call's params, and runs
+    /// after the authorization policy.
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Only expose the methods allowed by `filter` as MCP tools.
+    ///
+    /// Filtered methods are omitted from `tools/list` and rejected by `call_tool`
+    /// as unknown, regardless of what other transports expose.
+    pub fn with_tool_filter(mut self, filter: ToolFilter) -> Self {
+        self.tool_filter = Some(filter);
+        self
+    }
+
+    /// Record every tool call and its outcome in an audit log.
+    pub fn with_audit(mut self, auditor: Arc<Auditor>) -> Self {
+        self.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per tool call to an access log.
+    pub fn with_access_log(mut self, log: Arc<AccessLog>) -> Self {
+        self.access_log = Some(log);
+        self
+    }
+
+    /// Capture every tool call, its result and the notifications it sends.
+    pub fn with_capture(mut self, capture: Arc<Capture>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Log tool calls still running after `threshold` as slow.
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Serve the resources of `provider` and advertise the `resources` capability.
+    pub fn with_resource_provider(mut self, provider: Arc<dyn ResourceProvider>) -> Self {
+        self.resources = Some(provider);
+        self
+    }
+
+    /// Serve the prompts of `provider` and advertise the `prompts` capability.
+    pub fn with_prompt_provider(mut self, provider: Arc<dyn PromptProvider>) -> Self {
+        self.prompts = Some(provider);
+        self
+    }
+
+    /// Suggest argument values with `provider` and advertise the `completions` capability.
+    pub fn with_completion_provider(mut self, provider: Arc<dyn CompletionProvider>) -> Self {
+        self.completions = Some(provider);
+        self
+    }
+
+    /// Answer failed calls as `mapper` says instead of with the default mapping.
+    pub fn with_error_mapper(mut self, mapper: Arc<dyn ErrorMapper>) -> Self {
+        self.error_mapper = Some(mapper);
+        self
+    }
+
+    /// Advertise the input schemas of `provider` instead of the activation's where it has
+    /// one, and the output schemas it declares.
+    pub fn with_schema_provider(mut self, provider: Arc<dyn SchemaProvider>) -> Self {
+        self.schema_provider = Some(provider);
+        self
+    }
+
+    /// Describe tools with the titles, descriptions and annotations of `provider`.
+    pub fn with_tool_metadata_provider(mut self, provider: Arc<dyn ToolMetadataProvider>) -> Self {
+        self.tool_metadata = Some(provider);
+        self
+    }
+
+    /// Fail tool calls that run longer than their limit in `timeouts`, dropping them.
+    pub fn with_tool_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.tool_timeouts = timeouts;
+        self
+    }
+
+    /// Stream each data item of a call to clients that passed a progress token,
+    /// as the message of a progress notification.
+    pub fn with_partial_results(mut self) -> Self {
+        self.partial_results = true;
+        self
+    }
+
+    /// Return at most the last `bytes` of a call's output in its final result.
+    pub fn with_max_result_bytes(mut self, bytes: usize) -> Self {
+        self.max_result_bytes = Some(bytes);
+        self
+    }
+
+    /// List at most `size` tools per `tools/list` response, with a cursor to the next page.
+    pub fn with_tools_page_size(mut self, size: usize) -> Self {
+        self.tools_page_size = Some(size);
+        self
+    }
+
+    /// Name tools for clients with `naming` instead of `namespace.method`.
+    pub fn with_tool_naming(mut self, naming: ToolNaming) -> Self {
+        self.tool_naming = naming;
+        self
+    }
+
+    /// Send `instructions` with the server info instead of the activation's description.
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Only declare and serve the capabilities turned on in `capabilities`.
+    pub fn with_capabilities(mut self, capabilities: McpCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Send `notifications/tools/list_changed` to initialized sessions whenever `notifier` fires.
+    pub fn with_tool_list_notifier(mut self, notifier: ToolListNotifier) -> Self {
+        self.tool_list = Some(notifier);
+        self
+    }
+
+    /// Send the notifications of `broadcaster` to initialized sessions.
+    pub fn with_broadcaster(mut self, broadcaster: Broadcaster) -> Self {
+        self.broadcaster = Some(broadcaster);
+        self
+    }
+
+    /// Forward tracing events to sessions once they send `logging/setLevel`.
+    #[cfg(feature = "mcp-logging")]
+    pub fn with_log_forwarder(mut self, forwarder: Arc<McpLogForwarder>) -> Self {
+        self.log_forwarder = Some(forwarder);
+        self
+    }
+
+    /// Record every tool call and its outcome in Prometheus metrics.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<TransportMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// A copy of this bridge for a new session; with metrics, the session
+    /// counts as open until the copy and its clones are dropped
+    pub(crate) fn for_session(&self) -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            session: self.metrics.as_ref().map(|metrics| Arc::new(metrics.mcp_session())),
+            logging: Default::default(),
+            subscriptions: Default::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Also expose the tools of `activation` as `prefix.namespace.method`
+    ///
+    /// Lets one MCP endpoint serve several activations, of the same or of
+    /// different types. The prefix must be non-empty and contain no `.` or
+    /// `/`; calls to its tools are checked against this bridge's filter,
+    /// authorization policy and audit log under their prefixed name.
+    ///
+    /// Fails if the prefix is invalid, already in use, or a namespace of this
+    /// bridge's own tools, which it would take over.
+    pub fn with_activation<B: Activation>(self, prefix: impl Into<String>, activation: Arc<B>) -> anyhow::Result<Self> {
+        self.with_bridge(prefix, ActivationMcpBridge::new(activation))
+    }
+
+    /// Also expose the tools of `bridge` as `prefix.<tool>`
+    ///
+    /// Like [`with_activation`](Self::with_activation), for activations that
+    /// need flat schemas, a router or a tool filter of their own.
+    pub fn with_bridge<B: Activation>(
+        self,
+        prefix: impl Into<String>,
+        bridge: ActivationMcpBridge<B>,
+    ) -> anyhow::Result<Self> {
+        let prefix = prefix.into();
+        anyhow::ensure!(
+            !prefix.is_empty() && !prefix.contains(['.', '/']),
+            "invalid MCP tool prefix {:?}: must be non-empty without '.' or '/'",
+            prefix
+        );
+        anyhow::ensure!(
+            !self.mounts.iter().any(|(mounted, _)| *mounted == prefix),
+            "MCP tool prefix {:?} is already in use",
+            prefix
+        );
+        anyhow::ensure!(
+            !self.schemas().iter().any(|schema| schema.namespace == prefix) && self.activation.namespace() != prefix,
+            "MCP tool prefix {:?} is also a namespace of the bridge's own tools",
+            prefix
+        );
+        Ok(self.with_mounted_tools(prefix, Arc::new(bridge)))
+    }
+
+    /// Ping the clients of initialized sessions, closing their sessions when they stop answering.
+    pub(crate) fn with_pings(mut self, pings: Pings) -> Self {
+        self.pings = Some(pings);
+        self
+    }
+
+    /// Replace the middleware run around tool calls.
+    pub(crate) fn with_middleware_stack(mut self, middleware: Vec<Arc<dyn TransportMiddleware>>) -> Self {
+        self.middleware = middleware;
+        self
+    }
+
+    /// Treat every caller as `CallerIdentity::Local`, for sessions over stdio.
+    pub(crate) fn with_local_caller(mut self) -> Self {
+        self.local = true;
+        self
+    }
+
+    /// Also expose the tools of `tools` as `prefix.<tool>`.
+    ///
+    /// Calls to prefixed tools are checked against this bridge's filter,
+    /// authorization policy and audit log under their prefixed name, then
+    /// dispatched to `tools` without the prefix.
+    pub(crate) fn with_mounted_tools(mut self, prefix: String, tools: Arc<dyn ToolSource>) -> Self {
+        self.mounts.push((prefix, tools));
+        self
+    }
+
+    /// The mounted tool set serving `name`, with the name it has there
+    fn mounted<'a>(&self, name: &'a str) -> Option<(&Arc<dyn ToolSource>, &'a str)> {
+        let (prefix, rest) = name.split_once('.')?;
+        self.mounts
+            .iter()
+            .find(|(mounted, _)| mounted == prefix)
+            .map(|(_, tools)| (tools, rest))
+    }
+
+    /// The schemas whose methods are exposed as tools
+    fn schemas(&self) -> Vec<PluginSchema> {
+        if let Some(published) = self.tool_list.as_ref().and_then(ToolListNotifier::flat_schemas) {
+            return published.as_ref().clone();
+        }
+        // Use pre-computed flat schemas if available (set for hub activations).
+        // Otherwise fall back to single activation schema.
+        match self.flat_schemas {
+            Some(ref flat) => flat.as_ref().clone(),
+            None => vec![self.activation.plugin_schema()],
+        }
+    }
+
+    /// Every tool passing the tool filter, own and mounted, by `namespace.method` name
+    fn exposed_tools(&self) -> Vec<Tool> {
+        let mut tools = schemas_to_rmcp_tools(self.schemas());
+        for (prefix, mounted) in &self.mounts {
+            tools.extend(mounted.tools().into_iter().map(|mut tool| {
+                tool.name = format!("{}.{}", prefix, tool.name).into();
+                tool
+            }));
+        }
+        tools.retain(|tool| self.exposes(&tool.name));
+        tools
+    }
+
+    /// The `namespace.method` name of the tool clients call `name`, if an
+    /// exposed tool has that name
+    ///
+    /// Without a naming strategy names are kept as they are, and unknown ones
+    /// fail when dispatched.
+    fn method_name(&self, name: &str) -> Option<String> {
+        if self.tool_naming.is_identity() {
+            return Some(name.to_string());
+        }
+        self.exposed_tools()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .find(|method| self.tool_naming.tool_name(method) == name)
+    }
+
+    /// The resource provider, unless the `resources` capability is off
+    fn resource_provider(&self) -> Option<&Arc<dyn ResourceProvider>> {
+        self.resources.as_ref().filter(|_| self.capabilities.resources)
+    }
+
+    /// The prompt provider, unless the `prompts` capability is off
+    fn prompt_provider(&self) -> Option<&Arc<dyn PromptProvider>> {
+        self.prompts.as_ref().filter(|_| self.capabilities.prompts)
+    }
+
+    /// The completion provider, unless the `completions` capability is off
+    fn completion_provider(&self) -> Option<&Arc<dyn CompletionProvider>> {
+        self.completions.as_ref().filter(|_| self.capabilities.completions)
+    }
+
+    /// Whether messages at `level` are sent to the session
+    fn logs(&self, level: LoggingLevel) -> bool {
+        self.capabilities.logging && self.logging.enabled(level)
+    }
+
+    /// The caller behind a message: `Local` over stdio, otherwise resolved
+    /// from the HTTP request that carried it
+    fn caller(&self, ctx: &RequestContext<RoleServer>) -> CallerIdentity {
+        if self.local {
+            return CallerIdentity::Local;
+        }
+        caller_identity(ctx)
+    }
+
+    /// Whether the tool `name` passes the configured tool filter
+    fn exposes(&self, name: &str) -> bool {
+        self.tool_filter.as_ref().map_or(true, |filter| filter.allows(name))
+    }
+}
+
+/// Audit status of a tool call's result; tool results flagged as errors count as internal errors
+fn tool_status(result: &Result<CallToolResult, McpError>) -> AuditStatus {
+    match result {
+        Ok(r) if r.is_error == Some(true) => AuditStatus::Error {
+            code: ErrorCode::INTERNAL_ERROR.0,
+        },
+        Ok(_) => AuditStatus::Ok,
+        Err(e) => AuditStatus::from_error_code(e.code.0),
+    }
+}
+
+/// The MCP error a tool call rejected by middleware is answered with
+fn rejection_error(rejection: CallRejection) -> McpError {
+    McpError::new(ErrorCode(rejection.code), rejection.message, rejection.data)
+}
+
+/// Resolve the MCP caller from the HTTP request that carried the message
+fn caller_identity(ctx: &RequestContext<RoleServer>) -> CallerIdentity {
+    ctx.extensions
+        .get::<http::request::Parts>()
+        .map(|parts| CallerIdentity::from_extensions(&parts.extensions))
+        .unwrap_or(CallerIdentity::Anonymous)
+}
+
+/// Peer address and MCP session id of the HTTP request that carried the message
+fn connection_of(ctx: &RequestContext<RoleServer>) -> (Option<String>, Option<String>) {
+    let Some(parts) = ctx.extensions.get::<http::request::Parts>() else {
+        return (None, None);
+    };
+    (
+        middleware::peer_of(&parts.extensions).map(|addr| addr.to_string()),
+        session_of(&ctx.extensions),
+    )
+}
+
+/// The `Mcp-Session-Id` of the HTTP request that carried a message
+fn session_of(extensions: &Extensions) -> Option<String> {
+    extensions
+        .get::<http::request::Parts>()?
+        .headers
+        .get(crate::rate_limit::MCP_SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Namespaces the client limited `tools/list` to, with `?namespace=` on the endpoint URL
+///
+/// The parameter can be repeated or hold a comma-separated list.
+fn requested_namespaces(ctx: &RequestContext<RoleServer>) -> Option<Vec<String>> {
+    let query = ctx.extensions.get::<http::request::Parts>()?.uri.query()?;
+    let namespaces: Vec<String> = form_urlencoded::parse(query.as_bytes())
+        .filter(|(key, _)| key == "namespace")
+        .flat_map(|(_, value)| value.split(',').map(|n| n.trim().to_string()).collect::<Vec<_>>())
+        .filter(|n| !n.is_empty())
+        .collect();
+    (!namespaces.is_empty()).then_some(namespaces)
+}
+
+/// The caller's trace context: the `_meta` of the call, else the HTTP headers
+#[cfg(feature = "otel")]
+fn trace_parent(ctx: &RequestContext<RoleServer>) -> Option<opentelemetry::Context> {
+    crate::trace::from_meta(&ctx.meta.0).or_else(|| {
+        ctx.extensions
+            .get::<http::request::Parts>()
+            .and_then(|parts| crate::trace::from_headers(&parts.headers))
+    })
 }
 
 impl<A: Activation> Clone for ActivationMcpBridge<A> {
@@ -194,6 +789,39 @@ impl<A: Activation> Clone for ActivationMcpBridge<A> {
             server_name_override: self.server_name_override.clone(),
             server_version_override: self.server_version_override.clone(),
             router: self.router.clone(),
+            authorization: self.authorization.clone(),
+            middleware: self.middleware.clone(),
+            tool_filter: self.tool_filter.clone(),
+            audit: self.audit.clone(),
+            access_log: self.access_log.clone(),
+            capture: self.capture.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+            #[cfg(feature = "metrics")]
+            session: self.session.clone(),
+            mounts: self.mounts.clone(),
+            slow_request_threshold: self.slow_request_threshold,
+            resources: self.resources.clone(),
+            prompts: self.prompts.clone(),
+            completions: self.completions.clone(),
+            error_mapper: self.error_mapper.clone(),
+            logging: self.logging.clone(),
+            subscriptions: self.subscriptions.clone(),
+            schema_provider: self.schema_provider.clone(),
+            tool_metadata: self.tool_metadata.clone(),
+            tools_page_size: self.tools_page_size,
+            tool_timeouts: self.tool_timeouts.clone(),
+            partial_results: self.partial_results,
+            max_result_bytes: self.max_result_bytes,
+            tool_list: self.tool_list.clone(),
+            broadcaster: self.broadcaster.clone(),
+            tool_naming: self.tool_naming.clone(),
+            instructions: self.instructions.clone(),
+            capabilities: self.capabilities,
+            pings: self.pings.clone(),
+            local: self.local,
+            #[cfg(feature = "mcp-logging")]
+            log_forwarder: self.log_forwarder.clone(),
         }
     }
 }
@@ -212,41 +840,412 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
             .clone()
             .unwrap_or_else(|| self.activation.version().to_string());
 
+        let mut capabilities = ServerCapabilities::default();
+        if self.capabilities.tools {
+            capabilities.tools = Some(ToolsCapability {
+                list_changed: self.tool_list.is_some().then_some(true),
+            });
+        }
+        if self.capabilities.logging {
+            capabilities.logging = Some(JsonObject::new());
+        }
+        if let Some(provider) = self.resource_provider() {
+            capabilities.resources = Some(ResourcesCapability {
+                subscribe: provider.supports_subscriptions().then_some(true),
+                ..Default::default()
+            });
+        }
+        if self.prompt_provider().is_some() {
+            capabilities.prompts = Some(PromptsCapability::default());
+        }
+        if self.completion_provider().is_some() {
+            capabilities.completions = Some(JsonObject::new());
+        }
+
+        let instructions = self
+            .instructions
+            .clone()
+            .unwrap_or_else(|| self.activation.description().to_string());
         ServerInfo {
             protocol_version: ProtocolVersion::LATEST,
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .enable_logging()
-                .build(),
+            capabilities,
             server_info,
-            instructions: Some(self.activation.description().to_string()),
+            instructions: Some(instructions),
         }
     }
 
     async fn list_tools(
         &self,
-        _request: Option<PaginatedRequestParam>,
-        _ctx: RequestContext<RoleServer>,
+        request: Option<PaginatedRequestParam>,
+        ctx: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
-        // Use pre-computed flat schemas if available (set for hub activations).
-        // Otherwise fall back to single activation schema.
-        let schemas = if let Some(ref flat) = self.flat_schemas {
-            flat.as_ref().clone()
-        } else {
-            vec![self.activation.plugin_schema()]
-        };
+        if !self.capabilities.tools {
+            return Ok(ListToolsResult::default());
+        }
+        let mut tools = self.exposed_tools();
+        if let Some(namespaces) = requested_namespaces(&ctx) {
+            tools.retain(|tool| {
+                let namespace = tool.name.split('.').next().unwrap_or_default();
+                namespaces.iter().any(|n| n == namespace)
+            });
+        }
+        if let Some(ref provider) = self.schema_provider {
+            for tool in &mut tools {
+                if let Some(schema) = provider.input_schema(&tool.name) {
+                    tool.input_schema = input_schema(Some(schema));
+                }
+                if let Some(schema) = provider.output_schema(&tool.name) {
+                    tool.output_schema = output_schema(schema);
+                }
+            }
+        }
+
+        if let Some(ref provider) = self.tool_metadata {
+            for tool in &mut tools {
+                if let Some(metadata) = provider.metadata(&tool.name) {
+                    metadata.apply(tool);
+                }
+            }
+        }
+
+        // Hide tools the caller is not allowed to call
+        if let Some(ref policy) = self.authorization {
+            let identity = self.caller(&ctx);
+            tools.retain(|tool| policy.allow_method(&identity, &tool.name));
+        }
+
+        if !self.tool_naming.is_identity() {
+            for tool in &mut tools {
+                tool.name = self.tool_naming.tool_name(&tool.name).into();
+            }
+        }
+
+        let cursor = request.and_then(|r| r.cursor);
+        let (tools, next_cursor) = paginate(tools, cursor.as_deref(), self.tools_page_size)?;
 
-        let tools = schemas_to_rmcp_tools(schemas);
         tracing::debug!("Listing {} tools", tools.len());
 
         Ok(ListToolsResult {
             tools,
-            next_cursor: None,
+            next_cursor,
             meta: None,
         })
     }
 
     async fn call_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.capabilities.tools {
+            return Err(McpError::method_not_found::<CallToolRequestMethod>());
+        }
+        let Some(method) = self.method_name(&request.name) else {
+            tracing::warn!("MCP tool call to unknown tool: {}", request.name);
+            return Err(McpError::invalid_params(format!("Unknown tool: {}", request.name), None));
+        };
+        let span = request_span("mcp", &method);
+        #[cfg(feature = "otel")]
+        if let Some(parent) = trace_parent(&ctx) {
+            crate::trace::set_parent(&span, parent);
+        }
+        // Only slow calls are described, but the request is gone by then
+        let described = self.slow_request_threshold.map(|_| {
+            let params_len = request
+                .arguments
+                .as_ref()
+                .map_or(0, |args| serde_json::Value::Object(args.clone()).to_string().len());
+            (method.clone(), params_len)
+        });
+        let captured = self.capture.clone().map(|capture| {
+            let (_, session) = connection_of(&ctx);
+            let id = serde_json::to_value(&ctx.id).unwrap_or_default();
+            let message = json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": &request });
+            capture.record("mcp", session.clone(), Direction::Request, None, message);
+            (capture, session, id)
+        });
+        request.name = method.into();
+        let slow = SlowRequests::new(self.slow_request_threshold);
+        #[cfg(feature = "metrics")]
+        let slow = slow.with_metrics(self.metrics.clone());
+        let result = slow
+            .watch("mcp", || described.unwrap_or_default(), self.record_tool(request, ctx))
+            .instrument(span)
+            .await;
+        if let Some((capture, session, id)) = captured {
+            let message = match result {
+                Ok(ref result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(ref error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+            };
+            capture.record("mcp", session, Direction::Response, None, message);
+        }
+        result
+    }
+
+    async fn on_initialized(&self, ctx: NotificationContext<RoleServer>) {
+        if let Some(ref notifier) = self.tool_list {
+            notifier.watch(ctx.peer.clone(), &self.logging);
+        }
+        if let Some(ref broadcaster) = self.broadcaster {
+            broadcaster.watch(ctx.peer.clone(), &self.logging);
+        }
+        if let Some(ref pings) = self.pings {
+            if let Some(session_id) = session_of(&ctx.extensions) {
+                pings.watch(ctx.peer, session_id, &self.logging);
+            }
+        }
+    }
+
+    async fn set_level(&self, request: SetLevelRequestParam, ctx: RequestContext<RoleServer>) -> Result<(), McpError> {
+        if !self.capabilities.logging {
+            return Err(McpError::method_not_found::<SetLevelRequestMethod>());
+        }
+        tracing::debug!("MCP session log level set to {:?}", request.level);
+        self.logging.set_level(request.level);
+        #[cfg(feature = "mcp-logging")]
+        if let Some(ref forwarder) = self.log_forwarder {
+            forwarder.forward(ctx.peer.clone(), &self.logging);
+        }
+        #[cfg(not(feature = "mcp-logging"))]
+        let _ = ctx;
+        Ok(())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let Some(provider) = self.resource_provider() else {
+            return Ok(ListResourcesResult::default());
+        };
+        let resources = provider.list_resources(&self.caller(&ctx)).await?;
+        tracing::debug!("Listing {} resources", resources.len());
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        let Some(provider) = self.resource_provider() else {
+            return Ok(ListResourceTemplatesResult::default());
+        };
+        let templates = provider.list_resource_templates(&self.caller(&ctx)).await?;
+        Ok(ListResourceTemplatesResult::with_all_items(templates))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let Some(provider) = self.resource_provider() else {
+            return Err(McpError::method_not_found::<ReadResourceRequestMethod>());
+        };
+        tracing::debug!("Reading resource: {}", request.uri);
+        let contents = provider
+            .read_resource(&request.uri, &self.caller(&ctx))
+            .instrument(request_span("mcp", "resources/read"))
+            .await?;
+        Ok(ReadResourceResult { contents })
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        let Some(provider) = self.completion_provider() else {
+            return Ok(CompleteResult::default());
+        };
+        let values = provider.complete(&request, &self.caller(&ctx)).await?;
+        Ok(complete_result(values))
+    }
+
+    async fn subscribe(&self, request: SubscribeRequestParam, ctx: RequestContext<RoleServer>) -> Result<(), McpError> {
+        let Some(provider) = self.resource_provider().filter(|p| p.supports_subscriptions()) else {
+            return Err(McpError::method_not_found::<SubscribeRequestMethod>());
+        };
+        let updates = provider.subscribe(&request.uri, &self.caller(&ctx)).await?;
+        tracing::debug!("MCP session subscribed to resource {}", request.uri);
+        self.subscriptions.insert(request.uri, ctx.peer.clone(), updates);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        if !self.resource_provider().is_some_and(|p| p.supports_subscriptions()) {
+            return Err(McpError::method_not_found::<UnsubscribeRequestMethod>());
+        }
+        self.subscriptions.remove(&request.uri);
+        Ok(())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let Some(provider) = self.prompt_provider() else {
+            return Ok(ListPromptsResult::default());
+        };
+        let prompts = provider.list_prompts(&self.caller(&ctx)).await?;
+        tracing::debug!("Listing {} prompts", prompts.len());
+        Ok(ListPromptsResult::with_all_items(prompts))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        let Some(provider) = self.prompt_provider() else {
+            return Err(McpError::method_not_found::<GetPromptRequestMethod>());
+        };
+        tracing::debug!("Getting prompt: {}", request.name);
+        let arguments = request.arguments.unwrap_or_default();
+        let result = provider
+            .get_prompt(&request.name, &arguments, &self.caller(&ctx))
+            .instrument(request_span("mcp", "prompts/get"))
+            .await?;
+        Ok(result)
+    }
+}
+
+impl<A: Activation> ActivationMcpBridge<A> {
+    /// Capture a notification sent while handling the call of `ctx`
+    fn capture_notification(&self, ctx: &RequestContext<RoleServer>, method: &str, params: &impl serde::Serialize) {
+        if let Some(ref capture) = self.capture {
+            let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+            let id = serde_json::to_value(&ctx.id).ok();
+            capture.record("mcp", connection_of(ctx).1, Direction::Notification, id, message);
+        }
+    }
+
+    /// Dispatch a tool call, recording it in the audit log, access log and metrics
+    async fn record_tool(
+        &self,
+        request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        #[cfg(feature = "metrics")]
+        let recording = self.audit.is_some() || self.access_log.is_some() || self.metrics.is_some();
+        #[cfg(not(feature = "metrics"))]
+        let recording = self.audit.is_some() || self.access_log.is_some();
+        if !recording {
+            return self.check_tool(request, ctx).await;
+        }
+
+        let identity = self.caller(&ctx);
+        let (client, session) = connection_of(&ctx);
+        let method = request.name.to_string();
+        let params = request
+            .arguments
+            .as_ref()
+            .map(|args| serde_json::Value::Object(args.clone()).to_string());
+        let started = std::time::Instant::now();
+
+        let result = self.check_tool(request, ctx).await;
+        let status = tool_status(&result);
+        #[cfg(feature = "metrics")]
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_call("mcp", &method, &status, started.elapsed());
+        }
+        if let Some(ref log) = self.access_log {
+            log.record("mcp", &method, status.clone(), started.elapsed(), client, session);
+        }
+        if let Some(ref auditor) = self.audit {
+            auditor.record("mcp", &identity, &method, params.as_deref(), status, started.elapsed());
+        }
+        result
+    }
+
+    /// Check a tool call against the tool filter, authorization policy and
+    /// middleware, then dispatch it
+    async fn check_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let method_name = &request.name;
+        if !self.exposes(method_name) {
+            tracing::warn!("MCP tool call rejected by tool filter: {}", method_name);
+            return Err(McpError::invalid_params(format!("Unknown tool: {}", method_name), None));
+        }
+
+        if let Some(ref policy) = self.authorization {
+            let identity = self.caller(&ctx);
+            if !policy.allow_method(&identity, method_name) {
+                tracing::warn!("MCP tool call denied: {} (caller={:?})", method_name, identity.name());
+                return Err(McpError::new(
+                    ErrorCode(FORBIDDEN_ERROR_CODE),
+                    forbidden_message(method_name),
+                    None,
+                ));
+            }
+        }
+
+        if self.middleware.is_empty() {
+            return self.dispatch_tool_timed(request, ctx).await;
+        }
+
+        // Middleware sees the arguments as the call's params, and may replace them
+        let started = std::time::Instant::now();
+        let peer = ctx
+            .extensions
+            .get::<http::request::Parts>()
+            .and_then(|parts| middleware::peer_of(&parts.extensions));
+        let params = request.arguments.take().map(serde_json::Value::Object);
+        let mut call = CallContext::new("mcp", method_name.to_string(), params, self.caller(&ctx), peer);
+        middleware::before_call(&self.middleware, &mut call, started)
+            .await
+            .map_err(rejection_error)?;
+        let result = match call.params.clone() {
+            None => Ok(None),
+            Some(serde_json::Value::Object(arguments)) => Ok(Some(arguments)),
+            Some(_) => Err(McpError::invalid_params("Tool arguments must be an object", None)),
+        };
+        let result = match result {
+            Ok(arguments) => {
+                request.arguments = arguments;
+                self.dispatch_tool_timed(request, ctx).await
+            }
+            Err(e) => Err(e),
+        };
+        middleware::after_call(&self.middleware, &call, tool_status(&result), started);
+        result
+    }
+
+    /// Dispatch a tool call, dropping it once it exceeds its time limit
+    async fn dispatch_tool_timed(
+        &self,
+        request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(timeout) = self.tool_timeouts.timeout_for(&request.name) else {
+            return self.dispatch_tool(request, ctx).await;
+        };
+        let method = request.name.to_string();
+        match tokio::time::timeout(timeout, self.dispatch_tool(request, ctx)).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!("MCP tool call timed out after {:?}: {}", timeout, method);
+                Err(McpError::new(
+                    ErrorCode(TIMEOUT_ERROR_CODE),
+                    format!("Tool call timed out after {}s: {}", timeout.as_secs_f64(), method),
+                    Some(json!({ "timeout_ms": timeout.as_millis() as u64 })),
+                ))
+            }
+        }
+    }
+
+    /// Dispatch a checked tool call, buffering its stream into the result
+    async fn dispatch_tool(
         &self,
         request: CallToolRequestParam,
         ctx: RequestContext<RoleServer>,
@@ -258,6 +1257,15 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
 
         tracing::debug!("Calling tool: {} with args: {:?}", method_name, arguments_map);
 
+        // Tools of mounted activations are dispatched there without their prefix
+        if let Some((mounted, name)) = self.mounted(method_name) {
+            let request = CallToolRequestParam {
+                name: name.to_string().into(),
+                arguments: Some(arguments_map),
+            };
+            return mounted.call(request, ctx).await;
+        }
+
         // Extract HTTP connection metadata from extensions and inject into arguments.
         // This makes the gateway transparent: all HTTP-level metadata (query params, headers)
         // is forwarded to the backend without the gateway needing to know what it means.
@@ -294,9 +1302,53 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
 
         let arguments_value = serde_json::Value::Object(arguments_map);
 
-        // Get progress token if provided
-        let progress_token = ctx.meta.get_progress_token();
+        // The activation can report progress, watch for cancellation, make
+        // requests to the client and see its session through task-local handles
+        let progress = self.progress_handle(&ctx);
+        let cancelled = ctx.ct.clone();
+        let client = McpClient::new(ctx.peer.clone());
+        let session = SessionContext::of(&ctx, connection_of(&ctx).1, self.caller(&ctx));
+        let call = self.run_tool(method_name, arguments_value, ctx, progress.clone());
+        let call = progress.scope(call);
+        let call = crate::mcp::cancellation::scope(cancelled.clone(), call);
+        let call = client.scope(call);
+        let call = session.scope(call);
+
+        // Dropping the call stops the activation's future or stream, even
+        // while it is waiting for its next item
+        tokio::select! {
+            result = call => result,
+            _ = cancelled.cancelled() => {
+                tracing::info!("MCP tool call cancelled: {}", method_name);
+                Err(McpError::internal_error("Cancelled", None))
+            }
+        }
+    }
+
+    /// Progress notifications for the call of `ctx`, if the client passed a progress token
+    fn progress_handle(&self, ctx: &RequestContext<RoleServer>) -> ProgressHandle {
+        let Some(token) = ctx.meta.get_progress_token() else {
+            return ProgressHandle::default();
+        };
+        let observer = self.capture.clone().map(|capture| {
+            let session = connection_of(ctx).1;
+            let id = serde_json::to_value(&ctx.id).ok();
+            Box::new(move |progress: &ProgressNotificationParam| {
+                let message = json!({ "jsonrpc": "2.0", "method": "notifications/progress", "params": progress });
+                capture.record("mcp", session.clone(), Direction::Notification, id.clone(), message);
+            }) as Box<dyn Fn(&ProgressNotificationParam) + Send + Sync>
+        });
+        ProgressHandle::new(ctx.peer.clone(), token, observer)
+    }
 
+    /// Call the activation, streaming its events as notifications and buffering them into the result
+    async fn run_tool(
+        &self,
+        method_name: &str,
+        arguments_value: serde_json::Value,
+        ctx: RequestContext<RoleServer>,
+        progress: ProgressHandle,
+    ) -> Result<CallToolResult, McpError> {
         // Logger name: namespace.method (e.g., bash.execute)
         let logger = method_name.to_string();
 
@@ -305,9 +1357,7 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
         // namespaced method name (e.g., "loopback.permit") to the correct child.
         // Otherwise strip the namespace prefix and call activation directly.
         let stream = if let Some(ref router) = self.router {
-            router(method_name.to_string(), arguments_value)
-                .await
-                .map_err(plexus_to_mcp_error)?
+            router(method_name.to_string(), arguments_value).await
         } else {
             let method = if method_name.contains('.') {
                 method_name.split('.').nth(1).unwrap_or(method_name)
@@ -317,39 +1367,41 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
             self.activation
                 .call(method, arguments_value, None, None)
                 .await
-                .map_err(plexus_to_mcp_error)?
+        };
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                let mapped = self.error_mapper.as_ref().and_then(|m| m.map_call_error(method_name, &e));
+                return match mapped {
+                    Some(mapped) => mapped.into_result(),
+                    None => Err(plexus_to_mcp_error(e)),
+                };
+            }
         };
 
         // Stream events via notifications AND buffer for final result
         let mut had_error = false;
-        let mut buffered_data: Vec<serde_json::Value> = Vec::new();
+        let mut output = OutputBuffer::new(self.max_result_bytes);
         let mut error_messages: Vec<String> = Vec::new();
+        // Progress items without a percentage, and partial results, count as one step each
+        let mut steps = 0.0;
 
         tokio::pin!(stream);
         while let Some(item) = stream.next().await {
-            // Check cancellation on each iteration
-            if ctx.ct.is_cancelled() {
-                return Err(McpError::internal_error("Cancelled", None));
-            }
-
             match &item {
                 PlexusStreamItem::Progress {
                     message,
                     percentage,
                     ..
                 } => {
-                    // Only send progress if client provided token
-                    if let Some(ref token) = progress_token {
-                        let _ = ctx
-                            .peer
-                            .notify_progress(ProgressNotificationParam {
-                                progress_token: token.clone(),
-                                progress: percentage.unwrap_or(0.0) as f64,
-                                total: None,
-                                message: Some(message.clone()),
-                            })
-                            .await;
-                    }
+                    let (value, total) = match percentage {
+                        Some(percentage) => (*percentage as f64, Some(100.0)),
+                        None => {
+                            steps += 1.0;
+                            (steps, None)
+                        }
+                    };
+                    progress.report(value, total, Some(message.clone())).await;
                 }
 
                 PlexusStreamItem::Data {
@@ -358,12 +1410,16 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                     ..
                 } => {
                     // Buffer data for final result
-                    buffered_data.push(content.clone());
+                    output.push(content.clone());
+
+                    if self.partial_results && progress.is_active() {
+                        steps += 1.0;
+                        progress.report(steps, None, Some(chunk_text(content))).await;
+                    }
 
                     // Also stream via notifications for real-time consumers
-                    let _ = ctx
-                        .peer
-                        .notify_logging_message(LoggingMessageNotificationParam {
+                    if self.logs(LoggingLevel::Info) {
+                        let log = LoggingMessageNotificationParam {
                             level: LoggingLevel::Info,
                             logger: Some(logger.clone()),
                             data: json!({
@@ -371,8 +1427,10 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                                 "content_type": content_type,
                                 "data": content,
                             }),
-                        })
-                        .await;
+                        };
+                        self.capture_notification(&ctx, "notifications/message", &log);
+                        let _ = ctx.peer.notify_logging_message(log).await;
+                    }
                 }
 
                 PlexusStreamItem::Error {
@@ -383,9 +1441,8 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                     // Buffer errors for final result
                     error_messages.push(message.clone());
 
-                    let _ = ctx
-                        .peer
-                        .notify_logging_message(LoggingMessageNotificationParam {
+                    if self.logs(LoggingLevel::Error) {
+                        let log = LoggingMessageNotificationParam {
                             level: LoggingLevel::Error,
                             logger: Some(logger.clone()),
                             data: json!({
@@ -393,8 +1450,10 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                                 "error": message,
                                 "recoverable": recoverable,
                             }),
-                        })
-                        .await;
+                        };
+                        self.capture_notification(&ctx, "notifications/message", &log);
+                        let _ = ctx.peer.notify_logging_message(log).await;
+                    }
 
                     if !recoverable {
                         had_error = true;
@@ -412,25 +1471,31 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                 } => {
                     // Send bidirectional request to client via logging notification
                     // Client should respond via _plexus_respond tool
-                    let _ = ctx
-                        .peer
-                        .notify_logging_message(LoggingMessageNotificationParam {
-                            level: LoggingLevel::Info,
-                            logger: Some(logger.clone()),
-                            data: json!({
-                                "type": "request",
-                                "request_id": request_id,
-                                "request_data": request_data,
-                                "timeout_ms": timeout_ms,
-                            }),
-                        })
-                        .await;
+                    let log = LoggingMessageNotificationParam {
+                        level: LoggingLevel::Info,
+                        logger: Some(logger.clone()),
+                        data: json!({
+                            "type": "request",
+                            "request_id": request_id,
+                            "request_data": request_data,
+                            "timeout_ms": timeout_ms,
+                        }),
+                    };
+                    self.capture_notification(&ctx, "notifications/message", &log);
+                    let _ = ctx.peer.notify_logging_message(log).await;
                 }
             }
         }
 
         // Return buffered data in the final result
         if had_error {
+            if let Some(mapped) = self
+                .error_mapper
+                .as_ref()
+                .and_then(|m| m.map_stream_errors(method_name, &error_messages))
+            {
+                return mapped.into_result();
+            }
             let error_content = if error_messages.is_empty() {
                 "Stream completed with errors".to_string()
             } else {
@@ -438,6 +1503,7 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
             };
             Ok(CallToolResult::error(vec![Content::text(error_content)]))
         } else {
+            let (buffered_data, omitted) = output.finish();
             // Convert buffered data to content
             let text_content = if buffered_data.is_empty() {
                 "(no output)".to_string()
@@ -461,9 +1527,50 @@ impl<A: Activation> ServerHandler for ActivationMcpBridge<A> {
                 }
             };
 
-            Ok(CallToolResult::success(vec![Content::text(
-                text_content,
-            )]))
+            if omitted > 0 {
+                let text_content = format!("[{} bytes of earlier output omitted]\n{}", omitted, text_content);
+                return Ok(CallToolResult::success(vec![Content::text(text_content)]));
+            }
+
+            // A single object is also returned as structured content
+            let mut result = CallToolResult::success(vec![Content::text(text_content)]);
+            result.structured_content = structured_content(&buffered_data);
+            Ok(result)
         }
     }
 }
+
+// =============================================================================
+// Mounted Tool Sets
+// =============================================================================
+
+/// A set of MCP tools another bridge can expose under a prefix
+///
+/// Object-safe so activations of different types can be mounted side by side.
+pub(crate) trait ToolSource: Send + Sync {
+    /// The tools, with their unprefixed names
+    fn tools(&self) -> Vec<Tool>;
+
+    /// Call a tool by its unprefixed name
+    fn call(
+        &self,
+        request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Pin<Box<dyn Future<Output = Result<CallToolResult, McpError>> + Send + '_>>;
+}
+
+impl<A: Activation> ToolSource for ActivationMcpBridge<A> {
+    fn tools(&self) -> Vec<Tool> {
+        let mut tools = schemas_to_rmcp_tools(self.schemas());
+        tools.retain(|tool| self.exposes(&tool.name));
+        tools
+    }
+
+    fn call(
+        &self,
+        request: CallToolRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Pin<Box<dyn Future<Output = Result<CallToolResult, McpError>> + Send + '_>> {
+        Box::pin(self.check_tool(request, ctx))
+    }
+}
@@ -0,0 +1,36 @@
+//! Cancellation of MCP tool calls
+//!
+//! A client abandons a tool call with `notifications/cancelled`, or by ending
+//! its session. The bridge then drops the call: the activation's `call`
+//! future, or the stream it returned, is never polled again. Work the call
+//! handed to other tasks keeps running unless it watches the call's token:
+//!
+//! ```rust,ignore
+//! let cancelled = plexus_transport::mcp::cancellation::current();
+//! tokio::spawn(async move {
+//!     tokio::select! {
+//!         _ = cancelled.cancelled() => {}
+//!         _ = build_report() => {}
+//!     }
+//! });
+//! ```
+
+use std::future::Future;
+
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static CURRENT: CancellationToken;
+}
+
+/// The token cancelled when the MCP tool call running on this task is
+///
+/// Outside an MCP call, a token that is never cancelled.
+pub fn current() -> CancellationToken {
+    CURRENT.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Run `future` with `token` as the current call's token
+pub(crate) fn scope<F: Future>(token: CancellationToken, future: F) -> impl Future<Output = F::Output> {
+    CURRENT.scope(token, future)
+}
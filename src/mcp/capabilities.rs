@@ -0,0 +1,55 @@
+//! MCP server capabilities
+//!
+//! By default the bridge declares `tools` and `logging`, and `resources`,
+//! `prompts` and `completions` when a provider for them is configured.
+//! [`McpCapabilities`] turns any of them off: a disabled capability is not
+//! declared in the `initialize` result, and its requests are answered as if
+//! nothing served it. Lists are empty, and calls, reads and
+//! `logging/setLevel` fail with "method not found". Without `logging`, the
+//! bridge also sends no `notifications/message`.
+//!
+//! ```rust,ignore
+//! let mcp_config = McpHttpConfig::new(8889)
+//!     .with_instructions("Call search.query before fetching documents.")
+//!     .with_capabilities(McpCapabilities { logging: false, ..Default::default() });
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Which MCP capabilities a server may declare
+///
+/// A capability that is on is still only declared when the bridge can serve
+/// it, e.g. `resources` needs a resource provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpCapabilities {
+    /// `tools/list` and `tools/call`
+    pub tools: bool,
+    /// `resources/*`
+    pub resources: bool,
+    /// `prompts/list` and `prompts/get`
+    pub prompts: bool,
+    /// `logging/setLevel` and `notifications/message`
+    pub logging: bool,
+    /// `completion/complete`
+    pub completions: bool,
+}
+
+impl Default for McpCapabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl McpCapabilities {
+    /// Every capability the bridge can serve
+    pub fn all() -> Self {
+        Self {
+            tools: true,
+            resources: true,
+            prompts: true,
+            logging: true,
+            completions: true,
+        }
+    }
+}
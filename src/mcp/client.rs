@@ -0,0 +1,165 @@
+//! Requests from the server to the MCP client
+//!
+//! During a tool call the activation can ask the connected client for help:
+//! have the host LLM summarize intermediate data with
+//! `sampling/createMessage`, or pause and ask the user for input with
+//! `elicitation/create`. [`McpClient::current`] returns the client of the MCP
+//! tool call running on this task:
+//!
+//! ```rust,ignore
+//! if let Some(client) = McpClient::current().filter(McpClient::supports_sampling) {
+//!     let summary = client.complete(format!("Summarize these rows:\n{}", rows), 500).await?;
+//! }
+//!
+//! let client = McpClient::current().ok_or_else(|| anyhow!("not an MCP call"))?;
+//! if !client.confirm(format!("Delete {} rows?", rows.len())).await? {
+//!     return Ok(Cancelled);
+//! }
+//! ```
+//!
+//! Like [`ProgressHandle`](super::progress::ProgressHandle), the handle is
+//! task-local: take it in the activation's `call` and move it into any task
+//! the call spawns.
+
+use std::future::Future;
+
+use rmcp::model::{
+    Content, CreateElicitationRequestParam, CreateElicitationResult, CreateMessageRequestParam, CreateMessageResult,
+    ElicitationAction, ElicitationSchema, Role, SamplingMessage,
+};
+use rmcp::service::{Peer, RoleServer};
+use thiserror::Error;
+
+tokio::task_local! {
+    static CURRENT: McpClient;
+}
+
+/// The MCP client of a tool call
+#[derive(Clone)]
+pub struct McpClient {
+    peer: Peer<RoleServer>,
+}
+
+impl std::fmt::Debug for McpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpClient").finish_non_exhaustive()
+    }
+}
+
+/// Why a request to the MCP client failed
+#[derive(Debug, Error)]
+pub enum ClientRequestError {
+    /// The client did not declare the capability when it initialized
+    #[error("The MCP client does not support {0}")]
+    Unsupported(&'static str),
+    /// The client answered with an error, or the session ended
+    #[error("MCP client request failed: {0}")]
+    Failed(#[from] rmcp::ServiceError),
+    /// The client's answer was not what was asked for
+    #[error("Unexpected answer from the MCP client: {0}")]
+    Unexpected(String),
+}
+
+impl McpClient {
+    pub(crate) fn new(peer: Peer<RoleServer>) -> Self {
+        Self { peer }
+    }
+
+    /// The client of the MCP tool call running on this task, if any
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Whether the client accepts `sampling/createMessage`
+    pub fn supports_sampling(&self) -> bool {
+        self.peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.sampling.is_some())
+    }
+
+    /// Ask the client's LLM for a message (`sampling/createMessage`)
+    ///
+    /// The client may show the request to its user first, so the answer can
+    /// take a while; wrap the call in a timeout if the tool cannot wait.
+    pub async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, ClientRequestError> {
+        if !self.supports_sampling() {
+            return Err(ClientRequestError::Unsupported("sampling"));
+        }
+        Ok(self.peer.create_message(params).await?)
+    }
+
+    /// Ask the client's LLM to answer `prompt` in at most `max_tokens` tokens
+    pub async fn complete(&self, prompt: impl Into<String>, max_tokens: u32) -> Result<String, ClientRequestError> {
+        let result = self
+            .create_message(CreateMessageRequestParam {
+                messages: vec![SamplingMessage {
+                    role: Role::User,
+                    content: Content::text(prompt.into()),
+                }],
+                model_preferences: None,
+                system_prompt: None,
+                include_context: None,
+                temperature: None,
+                max_tokens,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await?;
+        match result.message.content.as_text() {
+            Some(text) => Ok(text.text.clone()),
+            None => Err(ClientRequestError::Unexpected(format!(
+                "{} answered with non-text content",
+                result.model
+            ))),
+        }
+    }
+
+    /// Whether the client accepts `elicitation/create`
+    pub fn supports_elicitation(&self) -> bool {
+        self.peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.elicitation.is_some())
+    }
+
+    /// Ask the user for input matching `schema` (`elicitation/create`)
+    ///
+    /// The call waits until the user accepts, declines or dismisses the
+    /// request; the input is in `content` when the action is `Accept`.
+    pub async fn elicit(
+        &self,
+        message: impl Into<String>,
+        schema: ElicitationSchema,
+    ) -> Result<CreateElicitationResult, ClientRequestError> {
+        if !self.supports_elicitation() {
+            return Err(ClientRequestError::Unsupported("elicitation"));
+        }
+        let params = CreateElicitationRequestParam {
+            message: message.into(),
+            requested_schema: schema,
+        };
+        Ok(self.peer.create_elicitation(params).await?)
+    }
+
+    /// Ask the user to confirm `message`; `false` unless they accept
+    pub async fn confirm(&self, message: impl Into<String>) -> Result<bool, ClientRequestError> {
+        let schema = ElicitationSchema::builder()
+            .required_bool_property("confirm", |b| b.title("Confirm").with_default(true))
+            .build_unchecked();
+        let result = self.elicit(message, schema).await?;
+        Ok(result.action == ElicitationAction::Accept
+            && result
+                .content
+                .as_ref()
+                .and_then(|content| content.get("confirm"))
+                .and_then(|confirm| confirm.as_bool())
+                .unwrap_or(true))
+    }
+
+    /// Run `future` with this client as the current one
+    pub(crate) fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, future)
+    }
+}
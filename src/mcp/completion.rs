@@ -0,0 +1,97 @@
+//! MCP argument completion
+//!
+//! Clients such as the MCP Inspector and IDE integrations suggest argument
+//! values as the user types, with `completion/complete`. The protocol
+//! completes the arguments of prompts and of resource templates (tools have
+//! no completion in MCP). A [`CompletionProvider`] returns the suggestions;
+//! the bridge advertises the `completions` capability only when one is
+//! configured, and returns at most the 100 values the protocol allows.
+//!
+//! ```rust,ignore
+//! impl CompletionProvider for MyHub {
+//!     fn complete<'a>(
+//!         &'a self,
+//!         request: &'a CompleteRequestParam,
+//!         _caller: &'a CallerIdentity,
+//!     ) -> BoxFuture<'a, Result<Vec<String>, CompletionError>> {
+//!         Box::pin(async move {
+//!             match (&request.r#ref, request.argument.name.as_str()) {
+//!                 (Reference::Prompt(prompt), "plugin") if prompt.name == "summarize" => Ok(self
+//!                     .plugin_names()
+//!                     .filter(|name| name.starts_with(&request.argument.value))
+//!                     .collect()),
+//!                 _ => Ok(Vec::new()),
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! let mcp_config = McpHttpConfig::new(8889).with_completion_provider(hub.clone());
+//! ```
+
+use std::fmt;
+
+use futures::future::BoxFuture;
+use rmcp::model::{CompleteRequestParam, CompleteResult, CompletionInfo};
+use rmcp::ErrorData as McpError;
+use thiserror::Error;
+
+use crate::auth::CallerIdentity;
+
+/// Suggests values for prompt and resource template arguments
+///
+/// `request` names the prompt or resource template, the argument and what
+/// the user typed so far, and may carry the values of arguments already
+/// filled in. Suggestions come back best first.
+pub trait CompletionProvider: Send + Sync + 'static {
+    fn complete<'a>(
+        &'a self,
+        request: &'a CompleteRequestParam,
+        caller: &'a CallerIdentity,
+    ) -> BoxFuture<'a, Result<Vec<String>, CompletionError>>;
+}
+
+impl fmt::Debug for dyn CompletionProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompletionProvider")
+    }
+}
+
+/// Why no completions could be produced
+#[derive(Debug, Error)]
+pub enum CompletionError {
+    /// The prompt or resource template does not exist
+    #[error("Unknown reference: {0}")]
+    NotFound(String),
+    /// The provider failed
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl CompletionError {
+    pub fn failed(message: impl fmt::Display) -> Self {
+        Self::Failed(message.to_string())
+    }
+}
+
+impl From<CompletionError> for McpError {
+    fn from(e: CompletionError) -> Self {
+        match e {
+            CompletionError::NotFound(_) => McpError::invalid_params(e.to_string(), None),
+            CompletionError::Failed(message) => McpError::internal_error(message, None),
+        }
+    }
+}
+
+/// The result for `values`, cut to the number of values MCP allows
+pub(crate) fn complete_result(mut values: Vec<String>) -> CompleteResult {
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    CompleteResult {
+        completion: CompletionInfo {
+            has_more: Some(total > values.len()),
+            total: Some(total.min(u32::MAX as usize) as u32),
+            values,
+        },
+    }
+}
@@ -0,0 +1,79 @@
+//! The MCP session of a tool call
+//!
+//! Activations are called the same way from every transport, so they cannot
+//! tell MCP conversations apart on their own. During an MCP tool call,
+//! [`SessionContext::current`] describes the session the call belongs to:
+//! its id, to key per-conversation state, what the client said about itself
+//! when it initialized, the authenticated caller, and the extensions of the
+//! HTTP request that carried the call.
+//!
+//! ```rust,ignore
+//! let session = SessionContext::current().and_then(|s| s.session_id).unwrap_or_default();
+//! let history = self.conversations.entry(session).or_default();
+//! ```
+//!
+//! Like [`ProgressHandle`](super::progress::ProgressHandle), the context is
+//! task-local: take it in the activation's `call` and move it into any task
+//! the call spawns.
+
+use std::future::Future;
+
+use rmcp::model::{ClientCapabilities, ClientInfo, Implementation};
+use rmcp::service::{RequestContext, RoleServer};
+
+use crate::auth::CallerIdentity;
+
+tokio::task_local! {
+    static CURRENT: SessionContext;
+}
+
+/// The MCP session a tool call belongs to
+#[derive(Debug, Clone)]
+pub struct SessionContext {
+    /// The `Mcp-Session-Id` of the session; `None` in stateless mode
+    pub session_id: Option<String>,
+    /// What the client sent in `initialize`: its name and version, protocol
+    /// version and capabilities
+    pub client: Option<ClientInfo>,
+    /// The authenticated caller
+    pub caller: CallerIdentity,
+    /// Extensions of the HTTP request that carried the call
+    pub extensions: http::Extensions,
+}
+
+impl SessionContext {
+    /// The session of the request of `ctx`
+    pub(crate) fn of(ctx: &RequestContext<RoleServer>, session_id: Option<String>, caller: CallerIdentity) -> Self {
+        let extensions = ctx
+            .extensions
+            .get::<http::request::Parts>()
+            .map(|parts| parts.extensions.clone())
+            .unwrap_or_default();
+        Self {
+            session_id,
+            client: ctx.peer.peer_info().cloned(),
+            caller,
+            extensions,
+        }
+    }
+
+    /// The session of the MCP tool call running on this task, if any
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// The client's name and version
+    pub fn client_info(&self) -> Option<&Implementation> {
+        self.client.as_ref().map(|client| &client.client_info)
+    }
+
+    /// The capabilities the client declared
+    pub fn capabilities(&self) -> Option<&ClientCapabilities> {
+        self.client.as_ref().map(|client| &client.capabilities)
+    }
+
+    /// Run `future` with this context as the current one
+    pub(crate) fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, future)
+    }
+}
@@ -0,0 +1,84 @@
+//! Mapping activation errors to MCP errors
+//!
+//! By default, errors returned by the activation's `call` become generic
+//! JSON-RPC errors (`ExecutionError` is an internal error), and error items of
+//! its stream become a tool result with `isError: true` joining their
+//! messages. An [`ErrorMapper`] translates domain errors instead: into
+//! specific error codes and messages, or into tool results the model can read
+//! and act on.
+//!
+//! ```rust,ignore
+//! struct HubErrors;
+//!
+//! impl ErrorMapper for HubErrors {
+//!     fn map_call_error(&self, tool: &str, error: &PlexusError) -> Option<MappedError> {
+//!         match error {
+//!             PlexusError::ExecutionError(message) if message.starts_with("not found:") => {
+//!                 Some(MappedError::tool(format!("{} failed: {}. List the files first.", tool, message)))
+//!             }
+//!             PlexusError::ExecutionError(message) if message.contains("quota") => {
+//!                 Some(MappedError::protocol(-32010, "Quota exceeded", Some(json!({ "tool": tool }))))
+//!             }
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let mcp_config = McpHttpConfig::new(8889).with_error_mapper(Arc::new(HubErrors));
+//! ```
+
+use std::fmt;
+
+use plexus_core::plexus::PlexusError;
+use rmcp::model::{CallToolResult, Content, ErrorCode};
+use rmcp::ErrorData as McpError;
+use serde_json::Value;
+
+/// Translates activation errors for MCP clients; `None` keeps the default mapping
+pub trait ErrorMapper: Send + Sync + 'static {
+    /// An error returned by the activation's `call` for `tool`
+    fn map_call_error(&self, tool: &str, error: &PlexusError) -> Option<MappedError> {
+        let _ = (tool, error);
+        None
+    }
+
+    /// The messages of the error items in the stream of a failed call of `tool`
+    fn map_stream_errors(&self, tool: &str, messages: &[String]) -> Option<MappedError> {
+        let _ = (tool, messages);
+        None
+    }
+}
+
+impl fmt::Debug for dyn ErrorMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorMapper")
+    }
+}
+
+/// What a failed tool call is answered with
+#[derive(Debug, Clone)]
+pub enum MappedError {
+    /// A JSON-RPC error response
+    Protocol(McpError),
+    /// A tool result with `isError: true` and this message, which the model sees
+    Tool(String),
+}
+
+impl MappedError {
+    /// A JSON-RPC error with `code`, `message` and optional `data`
+    pub fn protocol(code: i32, message: impl Into<String>, data: Option<Value>) -> Self {
+        Self::Protocol(McpError::new(ErrorCode(code), message.into(), data))
+    }
+
+    /// A tool result with `isError: true`
+    pub fn tool(message: impl Into<String>) -> Self {
+        Self::Tool(message.into())
+    }
+
+    pub(crate) fn into_result(self) -> Result<CallToolResult, McpError> {
+        match self {
+            Self::Protocol(error) => Err(error),
+            Self::Tool(message) => Ok(CallToolResult::error(vec![Content::text(message)])),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Tool include/exclude filtering for the MCP bridge
+//!
+//! Activations often expose maintenance methods that are fine over an internal
+//! WebSocket but must never reach an LLM client. A [`ToolFilter`] restricts which
+//! `namespace.method` names the MCP bridge lists and accepts in `tools/call`;
+//! filtered tools behave exactly like tools that do not exist.
+
+use serde::{Deserialize, Serialize};
+
+/// Include/exclude patterns over MCP tool names (`namespace.method`)
+///
+/// Patterns are globs where `*` matches any run of characters, e.g. `query.*`,
+/// `*.delete_*`, or an exact name. A tool is exposed when it matches at least one
+/// include pattern (or no include patterns are set) and no exclude pattern.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ToolFilter {
+    /// A filter that exposes every tool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only expose tools matching at least one of `patterns`
+    pub fn include<I, P>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.include.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Never expose tools matching any of `patterns`
+    pub fn exclude<I, P>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.exclude.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether the tool `name` is exposed
+    pub fn allows(&self, name: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name));
+        included && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+/// Match `name` against a glob where `*` matches any (possibly empty) run of characters
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one item
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern: exact match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
@@ -0,0 +1,216 @@
+//! Legacy MCP HTTP+SSE transport (protocol version 2024-11-05)
+//!
+//! Clients that predate Streamable HTTP open an SSE stream with `GET /sse`.
+//! The first event, `endpoint`, names the URL to POST messages to
+//! (`/messages?sessionId=<id>`); every response and notification then arrives
+//! as a `message` event on the stream. Each stream is one MCP session, served
+//! by the same bridge as Streamable HTTP, and ends when the client disconnects
+//! or the server shuts down.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use rmcp::model::{ClientJsonRpcMessage, GetExtensions, ServerJsonRpcMessage};
+use rmcp::service::serve_server_with_ct;
+use rmcp::transport::common::server_side_http::session_id;
+use rmcp::transport::Transport;
+use rmcp::RoleServer;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+/// Path clients open the SSE stream at
+pub const LEGACY_SSE_PATH: &str = "/sse";
+
+/// Path clients POST their messages to
+pub const LEGACY_MESSAGES_PATH: &str = "/messages";
+
+/// Messages buffered per direction and session
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Where each session's incoming messages go
+type Sessions = Arc<Mutex<HashMap<String, mpsc::Sender<ClientJsonRpcMessage>>>>;
+
+struct LegacySse<S> {
+    factory: Box<dyn Fn() -> S + Send + Sync>,
+    sessions: Sessions,
+    keep_alive: Option<Duration>,
+    shutdown: CancellationToken,
+}
+
+/// Routes serving the legacy transport: `GET /sse` and `POST /messages`
+///
+/// `factory` creates the MCP service of each new session.
+pub(crate) fn legacy_sse_router<S, F>(factory: F, keep_alive: Option<Duration>, shutdown: &CancellationToken) -> axum::Router
+where
+    S: rmcp::Service<RoleServer>,
+    F: Fn() -> S + Send + Sync + 'static,
+{
+    let state = Arc::new(LegacySse {
+        factory: Box::new(factory),
+        sessions: Arc::new(Mutex::new(HashMap::new())),
+        keep_alive,
+        shutdown: shutdown.clone(),
+    });
+    axum::Router::new()
+        .route(LEGACY_SSE_PATH, axum::routing::get(open_stream::<S>))
+        .route(LEGACY_MESSAGES_PATH, axum::routing::post(post_message::<S>))
+        .with_state(state)
+}
+
+/// Start a session and stream its messages
+async fn open_stream<S: rmcp::Service<RoleServer>>(State(state): State<Arc<LegacySse<S>>>) -> Response {
+    let id = session_id().to_string();
+    let (client_tx, client_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let (server_tx, server_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    lock(&state.sessions).insert(id.clone(), client_tx);
+
+    let service = (state.factory)();
+    let transport = SseTransport {
+        outgoing: server_tx,
+        incoming: client_rx,
+    };
+    let ct = state.shutdown.child_token();
+    {
+        let id = id.clone();
+        crate::task::spawn("plexus.mcp.legacy_sse", async move {
+            match serve_server_with_ct(service, transport, ct).await {
+                Ok(running) => {
+                    let _ = running.waiting().await;
+                }
+                Err(e) => tracing::debug!(session_id = %id, "Legacy SSE session ended before initializing: {}", e),
+            }
+        });
+    }
+    tracing::info!(session_id = %id, "Opened legacy MCP SSE session");
+
+    // Dropping the stream (client gone) closes the session's inbox, which
+    // ends the service
+    let guard = SessionGuard {
+        sessions: state.sessions.clone(),
+        id: id.clone(),
+    };
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("{}?sessionId={}", LEGACY_MESSAGES_PATH, id));
+    let messages = ReceiverStream::new(server_rx).map(move |message| {
+        let _ = &guard;
+        Event::default()
+            .event("message")
+            .json_data(&message)
+            .unwrap_or_else(|e| Event::default().event("error").data(format!("Failed to serialize message: {}", e)))
+    });
+    let events = futures::stream::once(async move { endpoint })
+        .chain(messages)
+        .map(Ok::<_, Infallible>)
+        .take_until(state.shutdown.clone().cancelled_owned());
+
+    match state.keep_alive {
+        Some(interval) => Sse::new(events).keep_alive(KeepAlive::new().interval(interval)).into_response(),
+        None => Sse::new(events).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: String,
+}
+
+/// Hand a client message to its session; the answer arrives on the stream
+async fn post_message<S: rmcp::Service<RoleServer>>(
+    State(state): State<Arc<LegacySse<S>>>,
+    Query(query): Query<MessageQuery>,
+    request: Request,
+) -> Response {
+    let Some(inbox) = lock(&state.sessions).get(&query.session_id).cloned() else {
+        return (StatusCode::NOT_FOUND, "Session not found").into_response();
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let body: Bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Failed to read request body: {}", e)).into_response(),
+    };
+    let mut message: ClientJsonRpcMessage = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid JSON-RPC message: {}", e)).into_response(),
+    };
+
+    // Let the bridge see the caller and session, as on Streamable HTTP
+    if let Ok(value) = http::HeaderValue::from_str(&query.session_id) {
+        parts.headers.insert(crate::rate_limit::MCP_SESSION_HEADER, value);
+    }
+    match &mut message {
+        ClientJsonRpcMessage::Request(request) => {
+            request.request.extensions_mut().insert(parts);
+        }
+        ClientJsonRpcMessage::Notification(notification) => {
+            notification.notification.extensions_mut().insert(parts);
+        }
+        _ => {}
+    }
+
+    match inbox.send(message).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "Session closed").into_response(),
+    }
+}
+
+fn lock(sessions: &Sessions) -> std::sync::MutexGuard<'_, HashMap<String, mpsc::Sender<ClientJsonRpcMessage>>> {
+    sessions.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Forgets its session when the SSE stream is dropped
+struct SessionGuard {
+    sessions: Sessions,
+    id: String,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        lock(&self.sessions).remove(&self.id);
+        tracing::info!(session_id = %self.id, "Closed legacy MCP SSE session");
+    }
+}
+
+/// The service side of a session: messages in from POSTs, out to the stream
+struct SseTransport {
+    outgoing: mpsc::Sender<ServerJsonRpcMessage>,
+    incoming: mpsc::Receiver<ClientJsonRpcMessage>,
+}
+
+impl Transport<RoleServer> for SseTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: ServerJsonRpcMessage,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let outgoing = self.outgoing.clone();
+        async move {
+            outgoing
+                .send(item)
+                .await
+                .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "SSE stream closed"))
+        }
+    }
+
+    fn receive(&mut self) -> impl std::future::Future<Output = Option<ClientJsonRpcMessage>> + Send {
+        self.incoming.recv()
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.incoming.close();
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! MCP logging: `logging/setLevel` and forwarding of tracing events
+//!
+//! Each MCP session has a minimum log level, `debug` until the client sends
+//! `logging/setLevel`. The bridge's own `notifications/message` (tool output
+//! and errors) below that level are not sent.
+//!
+//! With the `mcp-logging` feature, an [`McpLogForwarder`] also forwards the
+//! application's tracing events to every session that has set a level, so
+//! remote servers can be debugged from the MCP client:
+//!
+//! ```rust,ignore
+//! use tracing_subscriber::prelude::*;
+//!
+//! let forwarder = Arc::new(McpLogForwarder::new());
+//! tracing_subscriber::registry()
+//!     .with(forwarder.layer())
+//!     .with(tracing_subscriber::fmt::layer())
+//!     .init();
+//!
+//! let mcp_config = McpHttpConfig::new(8889).with_log_forwarder(forwarder);
+//! ```
+//!
+//! Events of the transport's own stack (`rmcp`, `hyper`, `plexus_transport`,
+//! ...) are not forwarded: sending a notification logs, and would be
+//! forwarded in turn.
+
+use std::sync::Mutex;
+
+use rmcp::model::LoggingLevel;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+/// The log level of one MCP session
+#[derive(Debug)]
+pub(crate) struct SessionLogging {
+    level: Mutex<LoggingLevel>,
+    /// Whether tracing events are being forwarded to the session
+    #[cfg(feature = "mcp-logging")]
+    forwarding: std::sync::atomic::AtomicBool,
+    /// Cancelled when the session's state is dropped
+    closed: CancellationToken,
+}
+
+impl Default for SessionLogging {
+    fn default() -> Self {
+        Self {
+            level: Mutex::new(LoggingLevel::Debug),
+            #[cfg(feature = "mcp-logging")]
+            forwarding: Default::default(),
+            closed: CancellationToken::new(),
+        }
+    }
+}
+
+impl Drop for SessionLogging {
+    fn drop(&mut self) {
+        self.closed.cancel();
+    }
+}
+
+impl SessionLogging {
+    /// Whether messages at `level` reach the client
+    pub(crate) fn enabled(&self, level: LoggingLevel) -> bool {
+        level as u8 >= *self.lock() as u8
+    }
+
+    /// Only send messages at `level` and above from now on
+    pub(crate) fn set_level(&self, level: LoggingLevel) {
+        *self.lock() = level;
+    }
+
+    /// Completes when the session ends, for tasks that must not outlive it
+    pub(crate) fn closed(&self) -> WaitForCancellationFutureOwned {
+        self.closed.clone().cancelled_owned()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LoggingLevel> {
+        self.level.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(feature = "mcp-logging")]
+pub use forwarding::{McpLogForwarder, McpLogLayer};
+
+#[cfg(feature = "mcp-logging")]
+mod forwarding {
+    use std::sync::{Arc, Weak};
+
+    use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+    use rmcp::service::{Peer, RoleServer};
+    use serde_json::{json, Map, Value};
+    use tokio::sync::broadcast;
+    use tracing::field::{Field, Visit};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::Layer;
+
+    use super::SessionLogging;
+
+    /// Events buffered for sessions that fall behind; older ones are dropped
+    const BUFFERED_EVENTS: usize = 1024;
+
+    /// Targets whose events are never forwarded
+    const TRANSPORT_TARGETS: &[&str] = &[
+        "plexus_transport",
+        "rmcp",
+        "hyper",
+        "h2",
+        "tower",
+        "tower_http",
+        "axum",
+        "tokio",
+        "sqlx",
+        "redis",
+    ];
+
+    /// Forwards tracing events to the MCP sessions that asked for logs
+    ///
+    /// Add [`layer`](Self::layer) to the application's subscriber and pass
+    /// the forwarder to `McpHttpConfig::with_log_forwarder`. One forwarder
+    /// can serve several endpoints.
+    #[derive(Debug)]
+    pub struct McpLogForwarder {
+        events: broadcast::Sender<LoggingMessageNotificationParam>,
+        excluded: Vec<String>,
+    }
+
+    impl Default for McpLogForwarder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl McpLogForwarder {
+        pub fn new() -> Self {
+            Self {
+                events: broadcast::channel(BUFFERED_EVENTS).0,
+                excluded: TRANSPORT_TARGETS.iter().map(|t| t.to_string()).collect(),
+            }
+        }
+
+        /// Also keep events of `targets` (and their submodules) from clients
+        pub fn with_excluded_targets<I, T>(mut self, targets: I) -> Self
+        where
+            I: IntoIterator<Item = T>,
+            T: Into<String>,
+        {
+            self.excluded.extend(targets.into_iter().map(Into::into));
+            self
+        }
+
+        /// The tracing layer feeding this forwarder
+        pub fn layer(self: &Arc<Self>) -> McpLogLayer {
+            McpLogLayer(self.clone())
+        }
+
+        fn excludes(&self, target: &str) -> bool {
+            self.excluded.iter().any(|excluded| {
+                target
+                    .strip_prefix(excluded.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+        }
+
+        /// Forward events to `peer` at the session's level until the session ends
+        pub(crate) fn forward(&self, peer: Peer<RoleServer>, session: &Arc<SessionLogging>) {
+            if session.forwarding.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+            let mut events = self.events.subscribe();
+            let session: Weak<SessionLogging> = Arc::downgrade(session);
+            crate::task::spawn("plexus.mcp.log_forwarding", async move {
+                loop {
+                    let event = match events.recv().await {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    let Some(session) = session.upgrade() else {
+                        break;
+                    };
+                    if session.enabled(event.level) && peer.notify_logging_message(event).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    /// A tracing layer handing events to an [`McpLogForwarder`]
+    pub struct McpLogLayer(Arc<McpLogForwarder>);
+
+    impl<S: Subscriber> Layer<S> for McpLogLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let forwarder = &self.0;
+            let metadata = event.metadata();
+            if forwarder.events.receiver_count() == 0 || forwarder.excludes(metadata.target()) {
+                return;
+            }
+            let level = match *metadata.level() {
+                tracing::Level::ERROR => LoggingLevel::Error,
+                tracing::Level::WARN => LoggingLevel::Warning,
+                tracing::Level::INFO => LoggingLevel::Info,
+                _ => LoggingLevel::Debug,
+            };
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            let mut data = json!({ "message": fields.message.unwrap_or_default() });
+            if !fields.other.is_empty() {
+                data["fields"] = Value::Object(fields.other);
+            }
+            let _ = forwarder.events.send(LoggingMessageNotificationParam {
+                level,
+                logger: Some(metadata.target().to_string()),
+                data,
+            });
+        }
+    }
+
+    /// The message and other fields of an event
+    #[derive(Default)]
+    struct Fields {
+        message: Option<String>,
+        other: Map<String, Value>,
+    }
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.record(field, Value::from(value));
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            self.record(field, Value::from(value));
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.record(field, Value::from(value));
+        }
+
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            self.record(field, Value::from(value));
+        }
+
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            self.record(field, Value::from(value));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.record(field, Value::from(format!("{:?}", value)));
+        }
+    }
+
+    impl Fields {
+        fn record(&mut self, field: &Field, value: Value) {
+            if field.name() == "message" {
+                self.message = Some(match value {
+                    Value::String(message) => message,
+                    other => other.to_string(),
+                });
+            } else {
+                self.other.insert(field.name().to_string(), value);
+            }
+        }
+    }
+}
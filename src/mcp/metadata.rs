@@ -0,0 +1,91 @@
+//! Tool titles, descriptions and annotations
+//!
+//! `tools/list` describes each method with the description from the
+//! activation's schema. A [`ToolMetadataProvider`] can replace it with one
+//! written for LLMs, add a display title, and flag tools with MCP annotations
+//! such as `readOnlyHint` and `destructiveHint`:
+//!
+//! ```rust,ignore
+//! let metadata = |tool: &str| match tool {
+//!     "files.delete" => Some(
+//!         ToolMetadata::new()
+//!             .with_title("Delete file")
+//!             .with_description("Permanently delete the file at `path`. Cannot be undone.")
+//!             .with_annotations(ToolAnnotations::new().read_only(false).destructive(true)),
+//!     ),
+//!     "files.read" => Some(ToolMetadata::new().with_annotations(ToolAnnotations::new().read_only(true))),
+//!     _ => None,
+//! };
+//! let mcp_config = McpHttpConfig::new(8889).with_tool_metadata_provider(Arc::new(metadata));
+//! ```
+//!
+//! Annotations are hints for clients, not enforcement; use a `ToolFilter` or
+//! an authorization policy to keep tools from being called.
+
+use rmcp::model::{Tool, ToolAnnotations};
+
+/// What a [`ToolMetadataProvider`] says about one tool; unset fields keep their defaults
+#[derive(Debug, Clone, Default)]
+pub struct ToolMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub annotations: Option<ToolAnnotations>,
+}
+
+impl ToolMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A human-readable name for clients to display
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Replace the description from the activation's schema
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Hints about the tool's behavior (read-only, destructive, idempotent, open world)
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Apply the fields that are set to `tool`
+    pub(crate) fn apply(self, tool: &mut Tool) {
+        if let Some(title) = self.title {
+            tool.title = Some(title);
+        }
+        if let Some(description) = self.description {
+            tool.description = Some(description.into());
+        }
+        if let Some(annotations) = self.annotations {
+            tool.annotations = Some(annotations);
+        }
+    }
+}
+
+/// Supplies tool titles, descriptions and annotations
+pub trait ToolMetadataProvider: Send + Sync + 'static {
+    /// The metadata of `tool` (`namespace.method`), or `None` to keep the defaults
+    fn metadata(&self, tool: &str) -> Option<ToolMetadata>;
+}
+
+impl<F> ToolMetadataProvider for F
+where
+    F: Fn(&str) -> Option<ToolMetadata> + Send + Sync + 'static,
+{
+    fn metadata(&self, tool: &str) -> Option<ToolMetadata> {
+        self(tool)
+    }
+}
+
+impl std::fmt::Debug for dyn ToolMetadataProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ToolMetadataProvider")
+    }
+}
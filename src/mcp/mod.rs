@@ -1,15 +1,109 @@
 //! MCP (Model Context Protocol) transport
 //!
-//! Provides HTTP-based MCP server with SSE streaming support.
+//! Provides HTTP-based MCP server with SSE streaming support (feature
+//! `mcp-http`). Tool filtering, naming and timeouts, and the capabilities to
+//! declare, are configuration and always available.
 
+#[cfg(feature = "mcp-http")]
+pub mod admin;
+#[cfg(feature = "mcp-http")]
 pub mod bridge;
+#[cfg(feature = "mcp-http")]
+pub mod cancellation;
+pub mod capabilities;
+#[cfg(feature = "mcp-http")]
+pub mod client;
+#[cfg(feature = "mcp-http")]
+pub mod completion;
+#[cfg(feature = "mcp-http")]
+pub mod context;
+#[cfg(feature = "mcp-http")]
+pub mod error_map;
+pub mod filter;
+#[cfg(feature = "mcp-http")]
+mod legacy_sse;
+#[cfg(feature = "mcp-http")]
+pub mod logging;
+#[cfg(feature = "mcp-http")]
+pub mod metadata;
+#[cfg(all(feature = "mcp-http", feature = "jwt-auth"))]
+pub mod oauth;
+pub mod naming;
+#[cfg(feature = "mcp-http")]
+mod observed;
+#[cfg(feature = "mcp-http")]
+mod output;
+#[cfg(feature = "mcp-http")]
+mod pagination;
+#[cfg(feature = "mcp-http")]
+mod ping;
+#[cfg(feature = "mcp-http")]
+pub mod progress;
+#[cfg(feature = "mcp-http")]
+pub mod prompts;
+#[cfg(feature = "mcp-http")]
+pub mod resources;
+#[cfg(feature = "mcp-http")]
+pub mod schema;
+#[cfg(feature = "mcp-http")]
 pub mod server;
+#[cfg(all(feature = "mcp-http", feature = "stdio"))]
+pub mod stdio;
+#[cfg(feature = "mcp-http")]
+pub mod store;
+pub mod timeout;
+#[cfg(feature = "mcp-http")]
+pub mod tool_list;
+#[cfg(feature = "mcp-http")]
+mod tracked;
+
+#[cfg(feature = "redis-sessions")]
+pub mod redis_session;
 
 #[cfg(feature = "sqlite-sessions")]
 pub mod session;
 
+#[cfg(feature = "mcp-http")]
+pub use admin::{session_admin_router, McpSessionInfo, SessionAdmin};
+#[cfg(feature = "mcp-http")]
 pub use bridge::ActivationMcpBridge;
-pub use server::serve_mcp_http;
+#[cfg(feature = "mcp-http")]
+pub use client::{ClientRequestError, McpClient};
+#[cfg(feature = "mcp-http")]
+pub use completion::{CompletionError, CompletionProvider};
+#[cfg(feature = "mcp-http")]
+pub use context::SessionContext;
+#[cfg(feature = "mcp-http")]
+pub use error_map::{ErrorMapper, MappedError};
+pub use capabilities::McpCapabilities;
+pub use filter::ToolFilter;
+pub use naming::ToolNaming;
+pub use timeout::{ToolTimeouts, TIMEOUT_ERROR_CODE};
+#[cfg(all(feature = "mcp-http", feature = "jwt-auth"))]
+pub use oauth::OAuthResourceConfig;
+#[cfg(feature = "mcp-logging")]
+pub use logging::{McpLogForwarder, McpLogLayer};
+#[cfg(feature = "mcp-http")]
+pub use metadata::{ToolMetadata, ToolMetadataProvider};
+#[cfg(feature = "mcp-http")]
+pub use progress::ProgressHandle;
+#[cfg(feature = "mcp-http")]
+pub use prompts::{PromptError, PromptProvider};
+#[cfg(feature = "mcp-http")]
+pub use resources::{ResourceError, ResourceProvider};
+#[cfg(feature = "mcp-http")]
+pub use schema::SchemaProvider;
+#[cfg(feature = "mcp-http")]
+pub use server::{build_mcp_router, serve_mcp_http, serve_mcp_http_with_shutdown};
+#[cfg(all(feature = "mcp-http", feature = "stdio"))]
+pub use stdio::{serve_mcp_io, serve_mcp_stdio};
+#[cfg(feature = "mcp-http")]
+pub use store::{SessionStore, SessionStoreError, StoreSessionManager};
+#[cfg(feature = "mcp-http")]
+pub use tool_list::ToolListNotifier;
 
 #[cfg(feature = "sqlite-sessions")]
-pub use session::{SqliteSessionConfig, SqliteSessionManager};
+pub use session::{PersistedSession, SessionSnapshot, SqliteSessionConfig, SqliteSessionManager, SqliteTuning};
+
+#[cfg(feature = "redis-sessions")]
+pub use redis_session::{RedisSessionConfig, RedisSessionManager};
@@ -0,0 +1,92 @@
+//! MCP tool naming
+//!
+//! Methods are exposed as `namespace.method` by default, but several MCP
+//! clients reject tool names containing dots. A [`ToolNaming`] changes the
+//! names clients see, e.g. to `namespace_method` or `hub__namespace__method`,
+//! or maps them with any function. `tools/call` maps the name back by
+//! looking it up among the exposed tools, so mappings need not be reversible
+//! on their own, only distinct. Names that are not among them, including the
+//! `namespace.method` ones, are unknown tools.
+//!
+//! Tool filters, authorization policies, schema providers, audit and access
+//! logs keep seeing the `namespace.method` names.
+
+use std::fmt;
+use std::sync::Arc;
+
+type NameMap = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// How `namespace.method` names become the tool names clients see
+#[derive(Clone)]
+pub struct ToolNaming {
+    separator: String,
+    prefix: Option<String>,
+    map: Option<NameMap>,
+}
+
+impl Default for ToolNaming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ToolNaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolNaming")
+            .field("separator", &self.separator)
+            .field("prefix", &self.prefix)
+            .field("mapped", &self.map.is_some())
+            .finish()
+    }
+}
+
+impl ToolNaming {
+    /// `namespace.method`, unchanged
+    pub fn new() -> Self {
+        Self {
+            separator: ".".to_string(),
+            prefix: None,
+            map: None,
+        }
+    }
+
+    /// Join the parts of tool names with `separator` instead of `.`
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Start every tool name with `prefix` and the separator
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Name tools with `map`, given the `namespace.method` name
+    ///
+    /// Separator and prefix are not applied to mapped names.
+    pub fn with_mapping<F>(mut self, map: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.map = Some(Arc::new(map));
+        self
+    }
+
+    /// Whether tools keep their `namespace.method` names
+    pub fn is_identity(&self) -> bool {
+        self.separator == "." && self.prefix.is_none() && self.map.is_none()
+    }
+
+    /// The name clients see for the method `name` (`namespace.method`)
+    pub fn tool_name(&self, name: &str) -> String {
+        if let Some(ref map) = self.map {
+            return map(name);
+        }
+        let name = name.replace('.', &self.separator);
+        match self.prefix {
+            Some(ref prefix) => format!("{}{}{}", prefix, self.separator, name),
+            None => name,
+        }
+    }
+}
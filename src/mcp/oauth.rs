@@ -0,0 +1,112 @@
+//! OAuth 2.1 resource-server support per the MCP authorization spec
+//!
+//! The MCP server acts as an OAuth protected resource (RFC 9728):
+//! - `/.well-known/oauth-protected-resource` publishes which authorization
+//!   server(s) issue tokens for this endpoint
+//! - unauthenticated requests get `401` with a `WWW-Authenticate: Bearer
+//!   resource_metadata="..."` challenge so clients can discover the above
+//! - access tokens are JWTs validated against the authorization server's JWKS,
+//!   with `iss` and `aud` (the resource URL) enforced
+//!
+//! Only compiled when the `jwt-auth` feature is enabled.
+
+use axum::{http::StatusCode, response::IntoResponse, Json};
+use serde_json::json;
+
+use crate::auth::jwt::JwtAuth;
+
+/// Path of the protected resource metadata document (RFC 9728)
+pub const PROTECTED_RESOURCE_METADATA_PATH: &str = "/.well-known/oauth-protected-resource";
+
+/// OAuth protected-resource configuration for the MCP HTTP endpoint
+#[derive(Debug, Clone)]
+pub struct OAuthResourceConfig {
+    /// Canonical URL of this MCP endpoint (e.g. `https://mcp.example.com/mcp`);
+    /// access tokens must carry it as their audience
+    pub resource: String,
+    /// Issuer URLs of the authorization servers trusted for this resource
+    pub authorization_servers: Vec<String>,
+    /// Scopes advertised in the resource metadata
+    pub scopes_supported: Vec<String>,
+    /// Access token validator
+    pub validator: JwtAuth,
+}
+
+impl OAuthResourceConfig {
+    /// Trust tokens issued by `authorization_server`, verified with keys from `jwks_url`
+    pub fn new(
+        resource: impl Into<String>,
+        authorization_server: impl Into<String>,
+        jwks_url: impl Into<String>,
+    ) -> Self {
+        let resource = resource.into();
+        let authorization_server = authorization_server.into();
+        let validator = JwtAuth::with_jwks(jwks_url)
+            .with_issuer(authorization_server.clone())
+            .with_audience(resource.clone());
+
+        Self {
+            resource,
+            authorization_servers: vec![authorization_server],
+            scopes_supported: Vec::new(),
+            validator,
+        }
+    }
+
+    /// Advertise the scopes this resource understands
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes_supported = scopes;
+        self
+    }
+
+    /// Replace the access token validator (e.g. to accept additional algorithms)
+    pub fn with_validator(mut self, validator: JwtAuth) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Absolute URL of the metadata document for this resource
+    ///
+    /// Per RFC 9728 the well-known segment is inserted between the host and the
+    /// resource path: `https://host/mcp` → `https://host/.well-known/oauth-protected-resource/mcp`.
+    pub fn metadata_url(&self) -> String {
+        match self.resource.parse::<http::Uri>() {
+            Ok(uri) if uri.scheme().is_some() && uri.authority().is_some() => {
+                let path = uri.path().trim_end_matches('/');
+                format!(
+                    "{}://{}{}{}",
+                    uri.scheme_str().unwrap_or("https"),
+                    uri.authority().map(|a| a.as_str()).unwrap_or_default(),
+                    PROTECTED_RESOURCE_METADATA_PATH,
+                    path
+                )
+            }
+            _ => PROTECTED_RESOURCE_METADATA_PATH.to_string(),
+        }
+    }
+
+    /// `WWW-Authenticate` challenge pointing clients at the metadata document
+    pub fn challenge(&self) -> String {
+        format!("Bearer resource_metadata=\"{}\"", self.metadata_url())
+    }
+
+    /// The protected resource metadata document
+    pub fn metadata(&self) -> serde_json::Value {
+        let mut metadata = json!({
+            "resource": self.resource,
+            "authorization_servers": self.authorization_servers,
+            "bearer_methods_supported": ["header"],
+        });
+        if !self.scopes_supported.is_empty() {
+            metadata["scopes_supported"] = json!(self.scopes_supported);
+        }
+        metadata
+    }
+}
+
+/// Handler serving the protected resource metadata document
+pub(crate) async fn protected_resource_metadata_handler(
+    axum::extract::State(config): axum::extract::State<OAuthResourceConfig>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(config.metadata()))
+}
@@ -0,0 +1,125 @@
+//! MCP session lifecycle reporting
+//!
+//! [`ObservedSessions`] wraps the session manager behind the MCP endpoint and
+//! reports every session it creates to a [`TransportObserver`]. rmcp drops a
+//! session's transport when the session ends, whether the client deleted it or
+//! it expired, so the session is reported closed when its transport is dropped.
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::Stream;
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::{
+        common::server_side_http::{ServerSseMessage, SessionId},
+        streamable_http_server::session::SessionManager,
+        Transport,
+    },
+    RoleServer,
+};
+
+use crate::observer::{ObservedSession, TransportObserver};
+
+/// A session manager reporting session creation and expiry to an observer
+pub(crate) struct ObservedSessions<M> {
+    inner: Arc<M>,
+    observer: Arc<dyn TransportObserver>,
+}
+
+impl<M> ObservedSessions<M> {
+    pub(crate) fn new(inner: Arc<M>, observer: Arc<dyn TransportObserver>) -> Self {
+        Self { inner, observer }
+    }
+}
+
+impl<M: SessionManager> SessionManager for ObservedSessions<M> {
+    type Error = M::Error;
+    type Transport = ObservedTransport<M::Transport>;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        let (id, transport) = self.inner.create_session().await?;
+        let session = ObservedSession::new(self.observer.clone(), "mcp", id.to_string());
+        Ok((
+            id,
+            ObservedTransport {
+                inner: transport,
+                _session: session,
+            },
+        ))
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        self.inner.initialize_session(id, message).await
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        self.inner.has_session(id).await
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        self.inner.close_session(id).await
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.inner.create_stream(id, message).await
+    }
+
+    async fn accept_message(&self, id: &SessionId, message: ClientJsonRpcMessage) -> Result<(), Self::Error> {
+        self.inner.accept_message(id, message).await
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.inner.create_standalone_stream(id).await
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.inner.resume(id, last_event_id).await
+    }
+}
+
+/// A session's transport, reporting the session closed when dropped
+pub(crate) struct ObservedTransport<T> {
+    inner: T,
+    _session: ObservedSession,
+}
+
+impl<T: Transport<RoleServer>> Transport<RoleServer> for ObservedTransport<T> {
+    type Error = T::Error;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleServer>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.inner.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleServer>>> + Send {
+        self.inner.receive()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
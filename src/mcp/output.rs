@@ -0,0 +1,65 @@
+//! Output of MCP tool calls
+//!
+//! Tools that produce output for minutes, like log tailing or builds, can
+//! stream it: with partial results enabled, each `Data` item of the
+//! activation's stream is sent as it arrives, as the message of a
+//! `notifications/progress` for the call (clients opt in by passing a
+//! progress token). A byte limit keeps the final result, which still carries
+//! the whole output by default, to the most recent output.
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+/// The text of one output chunk: strings as they are, other values as JSON
+pub(crate) fn chunk_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The data items of a call, up to a byte limit
+///
+/// Over the limit, the oldest items are dropped; the last one is always kept.
+pub(crate) struct OutputBuffer {
+    items: VecDeque<(Value, usize)>,
+    bytes: usize,
+    limit: Option<usize>,
+    omitted: usize,
+}
+
+impl OutputBuffer {
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        Self {
+            items: VecDeque::new(),
+            bytes: 0,
+            limit,
+            omitted: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, content: Value) {
+        let Some(limit) = self.limit else {
+            self.items.push_back((content, 0));
+            return;
+        };
+        let size = match content {
+            Value::String(ref s) => s.len(),
+            ref other => other.to_string().len(),
+        };
+        self.items.push_back((content, size));
+        self.bytes += size;
+        while self.bytes > limit && self.items.len() > 1 {
+            if let Some((_, size)) = self.items.pop_front() {
+                self.bytes -= size;
+                self.omitted += size;
+            }
+        }
+    }
+
+    /// The items kept, and how many bytes of output were dropped
+    pub(crate) fn finish(self) -> (Vec<Value>, usize) {
+        (self.items.into_iter().map(|(content, _)| content).collect(), self.omitted)
+    }
+}
@@ -0,0 +1,33 @@
+//! Cursor-based pagination of MCP list results
+//!
+//! Cursors are opaque to clients; here they are the offset of the first item
+//! of the next page. Lists are rebuilt on every request, so a list that
+//! changes between pages may repeat or skip items, as the MCP specification
+//! allows.
+
+use rmcp::ErrorData as McpError;
+
+/// The page of `items` starting at `cursor`, and the cursor of the next page
+///
+/// Without a page size, every item is returned at once.
+pub(crate) fn paginate<T>(
+    mut items: Vec<T>,
+    cursor: Option<&str>,
+    page_size: Option<usize>,
+) -> Result<(Vec<T>, Option<String>), McpError> {
+    let start = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .ok()
+            .filter(|&start| start <= items.len())
+            .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor: {}", cursor), None))?,
+        None => 0,
+    };
+    let Some(page_size) = page_size.filter(|&size| size > 0) else {
+        return Ok((items.split_off(start), None));
+    };
+    let end = start.saturating_add(page_size).min(items.len());
+    let next_cursor = (end < items.len()).then(|| end.to_string());
+    items.truncate(end);
+    Ok((items.split_off(start), next_cursor))
+}
@@ -0,0 +1,91 @@
+//! Server-side pings of MCP sessions
+//!
+//! A session with an SSE stream open counts as in use, so the session TTL
+//! never closes it, even after the client behind the stream has hung or
+//! vanished without closing its connection. With a ping interval, the server
+//! sends every such session an MCP `ping` request that often, and closes the
+//! session when the client does not answer within the interval.
+//!
+//! Pings travel on the session's standalone SSE stream, so sessions without
+//! one open are not pinged; the TTL collects them once they are idle.
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use rmcp::model::{PingRequest, ServerRequest};
+use rmcp::service::{Peer, PeerRequestOptions, RoleServer};
+use rmcp::ServiceError;
+
+use crate::mcp::admin::AdminSessions;
+use crate::mcp::logging::SessionLogging;
+
+/// Pings the sessions of one MCP endpoint
+#[derive(Clone)]
+pub(crate) struct Pings {
+    interval: Duration,
+    sessions: Weak<dyn AdminSessions>,
+}
+
+impl Pings {
+    pub(crate) fn new(interval: Duration, sessions: Weak<dyn AdminSessions>) -> Self {
+        Self { interval, sessions }
+    }
+
+    /// Ping the client of the session `id` on `peer` until the session ends
+    ///
+    /// `session` is the bridge's state of the session, dropped when it ends.
+    pub(crate) fn watch(&self, peer: Peer<RoleServer>, id: String, session: &Arc<SessionLogging>) {
+        let Pings { interval, sessions } = self.clone();
+        let session: Weak<SessionLogging> = Arc::downgrade(session);
+        crate::task::spawn("plexus.mcp.ping", async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                let Some(endpoint) = sessions.upgrade() else {
+                    break;
+                };
+                if session.strong_count() == 0 {
+                    break;
+                }
+                match endpoint.get(&id) {
+                    None => break,
+                    Some(info) if info.open_streams == 0 => continue,
+                    Some(_) => {}
+                }
+                drop(endpoint);
+
+                match ping(&peer, interval).await {
+                    Ok(()) => {}
+                    Err(ServiceError::TransportClosed) => break,
+                    Err(ServiceError::Timeout { .. }) => {
+                        tracing::info!(session_id = %id, "MCP client did not answer ping; closing session");
+                        if let Some(endpoint) = sessions.upgrade() {
+                            if let Err(e) = endpoint.terminate(&id).await {
+                                tracing::warn!(session_id = %id, "{}", e);
+                            }
+                        }
+                        break;
+                    }
+                    // An error answer still shows the client is there
+                    Err(e) => tracing::debug!(session_id = %id, "MCP ping failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// Send a `ping` and wait up to `timeout` for the answer
+async fn ping(peer: &Peer<RoleServer>, timeout: Duration) -> Result<(), ServiceError> {
+    let options = PeerRequestOptions {
+        timeout: Some(timeout),
+        ..Default::default()
+    };
+    peer.send_request_with_option(ServerRequest::PingRequest(PingRequest::default()), options)
+        .await?
+        .await_response()
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Progress reporting for long-running MCP tool calls
+//!
+//! When a client passes a progress token with `tools/call`, the bridge sends
+//! `notifications/progress` for the call: one per `Progress` item in the
+//! activation's stream, and one per [`ProgressHandle::report`] from the
+//! activation itself. The handle of the running call is available to the
+//! activation's code through [`ProgressHandle::current`]:
+//!
+//! ```rust,ignore
+//! async fn reindex(&self) -> impl Stream<Item = Event> {
+//!     let progress = ProgressHandle::current();
+//!     for (i, table) in tables.iter().enumerate() {
+//!         progress.report(i as f64, Some(tables.len() as f64), Some(format!("Indexing {}", table))).await;
+//!         index(table).await;
+//!     }
+//!     ...
+//! }
+//! ```
+//!
+//! `current` reads a task-local value, so call it in the activation's `call`
+//! (or the stream it returns) and move the handle into any task the call
+//! spawns. Outside an MCP call, or without a progress token, the handle does
+//! nothing.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::{Peer, RoleServer};
+
+tokio::task_local! {
+    static CURRENT: ProgressHandle;
+}
+
+type Observer = Box<dyn Fn(&ProgressNotificationParam) + Send + Sync>;
+
+/// Sends progress notifications for one tool call
+#[derive(Clone, Default)]
+pub struct ProgressHandle(Option<Arc<Reporter>>);
+
+struct Reporter {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+    /// Progress must increase; lower values are raised to the last one sent
+    last: Mutex<f64>,
+    /// Sees every notification sent, e.g. to capture it
+    observer: Option<Observer>,
+}
+
+impl std::fmt::Debug for ProgressHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressHandle")
+            .field("active", &self.is_active())
+            .finish()
+    }
+}
+
+impl ProgressHandle {
+    /// A handle reporting to `peer` under the client's progress token
+    pub(crate) fn new(peer: Peer<RoleServer>, token: ProgressToken, observer: Option<Observer>) -> Self {
+        Self(Some(Arc::new(Reporter {
+            peer,
+            token,
+            last: Mutex::new(0.0),
+            observer,
+        })))
+    }
+
+    /// The handle of the MCP tool call running on this task
+    ///
+    /// Does nothing when reported to if there is none, or the client did not
+    /// ask for progress.
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Whether reports reach a client
+    pub fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Report `progress` out of `total` (if known), with an optional message
+    ///
+    /// Failures to notify the client are ignored; the call carries on.
+    pub async fn report(&self, progress: f64, total: Option<f64>, message: Option<String>) {
+        let Some(ref reporter) = self.0 else {
+            return;
+        };
+        let progress = {
+            let mut last = reporter.last.lock().unwrap_or_else(|e| e.into_inner());
+            *last = last.max(progress);
+            *last
+        };
+        let notification = ProgressNotificationParam {
+            progress_token: reporter.token.clone(),
+            progress,
+            total,
+            message,
+        };
+        if let Some(ref observer) = reporter.observer {
+            observer(&notification);
+        }
+        if let Err(e) = reporter.peer.notify_progress(notification).await {
+            tracing::debug!("Failed to send MCP progress notification: {}", e);
+        }
+    }
+
+    /// Run `future` with this handle as the current one
+    pub(crate) fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, future)
+    }
+}
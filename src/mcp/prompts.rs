@@ -0,0 +1,96 @@
+//! MCP prompts
+//!
+//! Activations can ship reusable prompt templates alongside their tools, which
+//! clients such as Claude Desktop offer to their users. A [`PromptProvider`]
+//! lists the prompts and renders one from the client's arguments. The bridge
+//! answers `prompts/list` and `prompts/get` from it, and advertises the
+//! `prompts` capability only when one is configured.
+//!
+//! ```rust,ignore
+//! impl PromptProvider for MyHub {
+//!     fn list_prompts<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Prompt>, PromptError>> {
+//!         let topic = PromptArgument { name: "topic".into(), title: None, description: None, required: Some(true) };
+//!         Box::pin(async move { Ok(vec![Prompt::new("summarize", Some("Summarize hub activity"), Some(vec![topic]))]) })
+//!     }
+//!
+//!     fn get_prompt<'a>(
+//!         &'a self,
+//!         name: &'a str,
+//!         arguments: &'a JsonObject,
+//!         _caller: &'a CallerIdentity,
+//!     ) -> BoxFuture<'a, Result<GetPromptResult, PromptError>> {
+//!         Box::pin(async move {
+//!             let topic = arguments.get("topic").and_then(|v| v.as_str())
+//!                 .ok_or_else(|| PromptError::InvalidArguments("topic is required".into()))?;
+//!             Ok(GetPromptResult {
+//!                 description: None,
+//!                 messages: vec![PromptMessage::new_text(PromptMessageRole::User, format!("Summarize {}", topic))],
+//!             })
+//!         })
+//!     }
+//! }
+//!
+//! let mcp_config = McpHttpConfig::new(8889).with_prompt_provider(hub.clone());
+//! ```
+
+use std::fmt;
+
+use futures::future::BoxFuture;
+use rmcp::model::{GetPromptResult, JsonObject, Prompt};
+use rmcp::ErrorData as McpError;
+use thiserror::Error;
+
+use crate::auth::CallerIdentity;
+
+/// Source of the prompt templates an MCP endpoint serves
+///
+/// Methods return boxed futures so providers can be shared as
+/// `Arc<dyn PromptProvider>`. `caller` is the authenticated client, so a
+/// provider can offer different prompts per caller.
+pub trait PromptProvider: Send + Sync + 'static {
+    /// Every prompt the caller may use
+    fn list_prompts<'a>(&'a self, caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Prompt>, PromptError>>;
+
+    /// The prompt `name` rendered with `arguments` (empty when the client sent none)
+    fn get_prompt<'a>(
+        &'a self,
+        name: &'a str,
+        arguments: &'a JsonObject,
+        caller: &'a CallerIdentity,
+    ) -> BoxFuture<'a, Result<GetPromptResult, PromptError>>;
+}
+
+impl fmt::Debug for dyn PromptProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PromptProvider")
+    }
+}
+
+/// Why a prompt could not be listed or rendered
+#[derive(Debug, Error)]
+pub enum PromptError {
+    /// No prompt has this name, or the caller may not use it
+    #[error("Unknown prompt: {0}")]
+    NotFound(String),
+    /// Arguments are missing or malformed
+    #[error("{0}")]
+    InvalidArguments(String),
+    /// The provider failed
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl PromptError {
+    pub fn failed(message: impl fmt::Display) -> Self {
+        Self::Failed(message.to_string())
+    }
+}
+
+impl From<PromptError> for McpError {
+    fn from(e: PromptError) -> Self {
+        match e {
+            PromptError::NotFound(_) | PromptError::InvalidArguments(_) => McpError::invalid_params(e.to_string(), None),
+            PromptError::Failed(message) => McpError::internal_error(message, None),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Redis-backed MCP session manager for sessions shared between replicas
+//!
+//! This module provides a SessionManager implementation that records session
+//! IDs in Redis, so every replica behind a load balancer sees the same set of
+//! live sessions and they expire alike, without a database file per replica.
+//!
+//! Each session is a single key, `<key_prefix><session id>`, holding the time
+//! it was last seen. Keys expire after the configured TTL without activity;
+//! there is no cleanup pass.
+//!
+//! Only the existence of sessions is shared. Their workers stay in the
+//! process that created them, so requests of a session must still reach that
+//! replica; another one can only take a session over by recreating its worker
+//! when the client resumes an SSE stream.
+
+use std::{sync::Arc, time::Duration};
+
+use futures::{future::BoxFuture, Stream};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use thiserror::Error;
+
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    transport::{
+        WorkerTransport,
+        common::server_side_http::{SessionId, ServerSseMessage},
+        streamable_http_server::session::{
+            SessionManager,
+            local::{LocalSessionWorker, SessionError, SessionConfig, EventIdParseError},
+        },
+    },
+};
+
+use super::store::{SessionStore, SessionStoreError, StoreSessionError, StoreSessionManager};
+
+/// Default prefix of the session keys
+pub const DEFAULT_KEY_PREFIX: &str = "plexus:mcp:session:";
+
+/// Default session TTL: 24 hours without activity
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Configuration for Redis session storage
+#[derive(Debug, Clone)]
+pub struct RedisSessionConfig {
+    /// Redis connection URL, e.g. `redis://127.0.0.1:6379/0`
+    pub url: String,
+    /// Prefix of the session keys, to share a Redis database between services
+    pub key_prefix: String,
+    /// How long a session lives without activity (default: 24 hours)
+    pub ttl: Duration,
+    /// Session worker configuration
+    pub session_config: SessionConfig,
+}
+
+impl Default for RedisSessionConfig {
+    fn default() -> Self {
+        Self {
+            url: "redis://127.0.0.1:6379".to_string(),
+            key_prefix: DEFAULT_KEY_PREFIX.to_string(),
+            ttl: DEFAULT_SESSION_TTL,
+            session_config: SessionConfig::default(),
+        }
+    }
+}
+
+/// Error types for Redis session manager
+#[derive(Debug, Error)]
+pub enum RedisSessionError {
+    #[error("Session not found: {0}")]
+    SessionNotFound(SessionId),
+    #[error("Session error: {0}")]
+    SessionError(#[from] SessionError),
+    #[error("Invalid event id: {0}")]
+    InvalidEventId(#[from] EventIdParseError),
+    #[error("Redis error: {0}")]
+    RedisError(String),
+}
+
+/// Redis-backed session manager
+///
+/// Records session IDs in Redis with a sliding TTL. Session workers live on
+/// the replica that created the session, so the load balancer must keep a
+/// session on one replica (e.g. hash on the `Mcp-Session-Id` header). A replica
+/// asked about a session it has no worker for answers "not found", and the
+/// client starts a new session; resuming an SSE stream recreates the worker
+/// for a session Redis still knows.
+pub struct RedisSessionManager {
+    /// Runs the session workers for sessions kept in Redis
+    manager: StoreSessionManager,
+}
+
+/// The session keys in Redis, as a [`SessionStore`]
+struct RedisStore {
+    conn: ConnectionManager,
+    key_prefix: String,
+    /// Session TTL, refreshed on every use
+    ttl: Duration,
+}
+
+impl From<StoreSessionError> for RedisSessionError {
+    fn from(e: StoreSessionError) -> Self {
+        match e {
+            StoreSessionError::SessionNotFound(id) => Self::SessionNotFound(id),
+            StoreSessionError::SessionError(e) => Self::SessionError(e),
+            StoreSessionError::InvalidEventId(e) => Self::InvalidEventId(e),
+            StoreSessionError::StoreError(e) => Self::RedisError(e.0),
+        }
+    }
+}
+
+impl RedisSessionManager {
+    /// Create a new Redis session manager
+    pub async fn new(config: RedisSessionConfig) -> Result<Self, RedisSessionError> {
+        let client = redis::Client::open(config.url.as_str())
+            .map_err(|e| RedisSessionError::RedisError(format!("Invalid Redis URL: {}", e)))?;
+        let conn = client
+            .get_connection_manager()
+            .await
+            .map_err(|e| RedisSessionError::RedisError(format!("Failed to connect: {}", e)))?;
+
+        let store = Arc::new(RedisStore {
+            conn,
+            key_prefix: config.key_prefix,
+            ttl: config.ttl,
+        });
+        Ok(Self {
+            manager: StoreSessionManager::with_session_config(store, config.session_config),
+        })
+    }
+
+    /// Refresh the TTL of every live session
+    ///
+    /// Sessions in use at shutdown get a full TTL for their clients to resume
+    /// them on another replica. Called when the MCP server stops.
+    pub async fn close(&self) -> Result<(), RedisSessionError> {
+        Ok(self.manager.close().await?)
+    }
+}
+
+impl RedisStore {
+    fn key(&self, id: &str) -> String {
+        format!("{}{}", self.key_prefix, id)
+    }
+
+    /// Record a session in Redis, or refresh its TTL
+    async fn touch_session(&self, id: &str) -> Result<(), SessionStoreError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.conn
+            .clone()
+            .set_ex::<_, _, ()>(self.key(id), now, self.ttl.as_secs().max(1))
+            .await
+            .map_err(|e| SessionStoreError::new(format!("Failed to touch session: {}", e)))
+    }
+}
+
+impl SessionStore for RedisStore {
+    fn create<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(self.touch_session(id))
+    }
+
+    fn touch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(self.touch_session(id))
+    }
+
+    fn exists<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<bool, SessionStoreError>> {
+        Box::pin(async move {
+            self.conn
+                .clone()
+                .exists(self.key(id))
+                .await
+                .map_err(|e| SessionStoreError::new(format!("Failed to check session: {}", e)))
+        })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move {
+            self.conn
+                .clone()
+                .del::<_, ()>(self.key(id))
+                .await
+                .map_err(|e| SessionStoreError::new(format!("Failed to remove session: {}", e)))
+        })
+    }
+
+    fn close<'a>(&'a self, live: &'a [String]) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move {
+            for id in live {
+                self.touch_session(id).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn stale<'a>(&'a self, _id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        // A session known to Redis may live on another replica, so unlike the
+        // SQLite store its key is left to expire rather than removed here
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl SessionManager for RedisSessionManager {
+    type Error = RedisSessionError;
+    type Transport = WorkerTransport<LocalSessionWorker>;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        Ok(self.manager.create_session().await?)
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        Ok(self.manager.initialize_session(id, message).await?)
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        Ok(self.manager.has_session(id).await?)
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        Ok(self.manager.close_session(id).await?)
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        Ok(self.manager.create_stream(id, message).await?)
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        Ok(self.manager.create_standalone_stream(id).await?)
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        Ok(self.manager.resume(id, last_event_id).await?)
+    }
+
+    async fn accept_message(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<(), Self::Error> {
+        Ok(self.manager.accept_message(id, message).await?)
+    }
+}
@@ -0,0 +1,184 @@
+//! MCP resources
+//!
+//! Tools let agents act on a hub; resources let them read its state as
+//! context. A [`ResourceProvider`] lists the resources it serves and reads
+//! them by URI. The bridge answers `resources/list`, `resources/templates/list`
+//! and `resources/read` from it, and advertises the `resources` capability
+//! only when one is configured.
+//!
+//! Activations usually implement the trait themselves and are passed as their
+//! own provider:
+//!
+//! ```rust,ignore
+//! impl ResourceProvider for MyHub {
+//!     fn list_resources<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Resource>, ResourceError>> {
+//!         Box::pin(async { Ok(vec![RawResource::new("hub://status", "status").no_annotation()]) })
+//!     }
+//!
+//!     fn read_resource<'a>(&'a self, uri: &'a str, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<ResourceContents>, ResourceError>> {
+//!         Box::pin(async move {
+//!             match uri {
+//!                 "hub://status" => Ok(vec![ResourceContents::text(self.status().await, uri)]),
+//!                 _ => Err(ResourceError::NotFound(uri.to_string())),
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! let mcp_config = McpHttpConfig::new(8889).with_resource_provider(hub.clone());
+//! ```
+//!
+//! Providers that can tell when a resource changes also serve
+//! `resources/subscribe`: [`subscribe`](ResourceProvider::subscribe) returns a
+//! stream yielding once per change, typically the activation's own
+//! subscription for that state, and each item becomes a
+//! `notifications/resources/updated` for the subscribed session:
+//!
+//! ```rust,ignore
+//! fn supports_subscriptions(&self) -> bool {
+//!     true
+//! }
+//!
+//! fn subscribe<'a>(&'a self, uri: &'a str, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<BoxStream<'static, ()>, ResourceError>> {
+//!     Box::pin(async move {
+//!         match uri {
+//!             "hub://status" => Ok(self.status_changes().map(|_| ()).boxed()),
+//!             _ => Err(ResourceError::NotFound(uri.to_string())),
+//!         }
+//!     })
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rmcp::model::{Resource, ResourceContents, ResourceTemplate, ResourceUpdatedNotificationParam};
+use rmcp::service::{Peer, RoleServer};
+use rmcp::ErrorData as McpError;
+use thiserror::Error;
+
+use crate::auth::CallerIdentity;
+
+/// Source of the resources an MCP endpoint serves
+///
+/// Methods return boxed futures so providers can be shared as
+/// `Arc<dyn ResourceProvider>`. `caller` is the authenticated client, so a
+/// provider can list and serve different resources per caller.
+pub trait ResourceProvider: Send + Sync + 'static {
+    /// Every resource the caller may read
+    fn list_resources<'a>(&'a self, caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Resource>, ResourceError>>;
+
+    /// URI templates for resources too numerous to list, e.g.
+    /// `hub://sessions/{id}`; none by default
+    fn list_resource_templates<'a>(
+        &'a self,
+        caller: &'a CallerIdentity,
+    ) -> BoxFuture<'a, Result<Vec<ResourceTemplate>, ResourceError>> {
+        let _ = caller;
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    /// The contents of the resource at `uri`
+    fn read_resource<'a>(
+        &'a self,
+        uri: &'a str,
+        caller: &'a CallerIdentity,
+    ) -> BoxFuture<'a, Result<Vec<ResourceContents>, ResourceError>>;
+
+    /// Whether clients can subscribe to resources; advertised to them as the
+    /// `subscribe` capability
+    fn supports_subscriptions(&self) -> bool {
+        false
+    }
+
+    /// A stream yielding once each time the resource at `uri` changes
+    ///
+    /// The subscription ends with the stream, when the client unsubscribes,
+    /// or when its session closes.
+    fn subscribe<'a>(
+        &'a self,
+        uri: &'a str,
+        caller: &'a CallerIdentity,
+    ) -> BoxFuture<'a, Result<BoxStream<'static, ()>, ResourceError>> {
+        let _ = (uri, caller);
+        Box::pin(async { Err(ResourceError::failed("Resource subscriptions are not supported")) })
+    }
+}
+
+impl fmt::Debug for dyn ResourceProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResourceProvider")
+    }
+}
+
+/// Why a resource could not be listed or read
+#[derive(Debug, Error)]
+pub enum ResourceError {
+    /// No resource has this URI, or the caller may not read it
+    #[error("Resource not found: {0}")]
+    NotFound(String),
+    /// The provider failed
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl ResourceError {
+    pub fn failed(message: impl fmt::Display) -> Self {
+        Self::Failed(message.to_string())
+    }
+}
+
+impl From<ResourceError> for McpError {
+    fn from(e: ResourceError) -> Self {
+        match e {
+            ResourceError::NotFound(ref uri) => McpError::resource_not_found(e.to_string(), Some(serde_json::json!({ "uri": uri }))),
+            ResourceError::Failed(message) => McpError::internal_error(message, None),
+        }
+    }
+}
+
+/// The resource subscriptions of one MCP session, ended when it is dropped
+#[derive(Debug, Default)]
+pub(crate) struct ResourceSubscriptions(Mutex<HashMap<String, tokio::task::AbortHandle>>);
+
+impl ResourceSubscriptions {
+    /// Notify `peer` of every update in `updates`, replacing any subscription to `uri`
+    pub(crate) fn insert(&self, uri: String, peer: Peer<RoleServer>, mut updates: BoxStream<'static, ()>) {
+        let notified = uri.clone();
+        let task = crate::task::spawn("plexus.mcp.resource_subscription", async move {
+            while updates.next().await.is_some() {
+                let update = ResourceUpdatedNotificationParam { uri: notified.clone() };
+                if let Err(e) = peer.notify_resource_updated(update).await {
+                    tracing::debug!("Failed to send MCP resource update: {}", e);
+                    break;
+                }
+            }
+        });
+        if let Some(previous) = self.lock().insert(uri, task.abort_handle()) {
+            previous.abort();
+        }
+    }
+
+    /// End the subscription to `uri`, if there is one
+    pub(crate) fn remove(&self, uri: &str) {
+        if let Some(task) = self.lock().remove(uri) {
+            task.abort();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, tokio::task::AbortHandle>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for ResourceSubscriptions {
+    fn drop(&mut self) {
+        for task in self.lock().values() {
+            task.abort();
+        }
+    }
+}
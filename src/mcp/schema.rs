@@ -0,0 +1,111 @@
+//! Tool input schemas
+//!
+//! `tools/list` advertises each method's parameter schema from the
+//! activation's `PluginSchema`. Methods without one, or whose schema is too
+//! loose for LLMs to call correctly, can be given a schema by a
+//! [`SchemaProvider`]:
+//!
+//! ```rust,ignore
+//! let schemas = |tool: &str| match tool {
+//!     "files.read" => Some(json!({
+//!         "type": "object",
+//!         "properties": { "path": { "type": "string" } },
+//!         "required": ["path"],
+//!     })),
+//!     _ => None,
+//! };
+//! let mcp_config = McpHttpConfig::new(8889).with_schema_provider(Arc::new(schemas));
+//! ```
+//!
+//! Every advertised schema is normalized the way MCP clients expect: an
+//! object at the root, without a `$schema` marker.
+//!
+//! A provider can also declare the `outputSchema` of a tool. Whether or not
+//! one is declared, a call whose output is a single JSON object returns it as
+//! `structuredContent`, next to the usual text block for older clients, so
+//! declared output schemas should describe that object.
+
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+/// Supplies tool input schemas, overriding the activation's
+pub trait SchemaProvider: Send + Sync + 'static {
+    /// The JSON Schema of the arguments of `tool` (`namespace.method`), or
+    /// `None` to keep the activation's
+    fn input_schema(&self, tool: &str) -> Option<Value>;
+
+    /// The JSON Schema of the structured output of `tool`, if it declares one
+    ///
+    /// Only object schemas can be declared; others are ignored.
+    fn output_schema(&self, _tool: &str) -> Option<Value> {
+        None
+    }
+}
+
+impl<F> SchemaProvider for F
+where
+    F: Fn(&str) -> Option<Value> + Send + Sync + 'static,
+{
+    fn input_schema(&self, tool: &str) -> Option<Value> {
+        self(tool)
+    }
+}
+
+impl std::fmt::Debug for dyn SchemaProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SchemaProvider")
+    }
+}
+
+/// A tool input schema as MCP requires it: an object schema at the root
+///
+/// Schemas of optional parameters (`"type": ["object", "null"]`) become plain
+/// object schemas, `$schema` is dropped, and anything that is not an object
+/// schema becomes an empty one.
+pub(crate) fn input_schema(schema: Option<Value>) -> Arc<Map<String, Value>> {
+    let mut schema = match schema {
+        Some(Value::Object(schema)) => schema,
+        _ => Map::new(),
+    };
+    schema.remove("$schema");
+    let is_object = match schema.get("type") {
+        None => true,
+        Some(Value::String(t)) => t == "object",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "object"),
+        Some(_) => false,
+    };
+    if !is_object {
+        tracing::warn!("Tool input schema is not an object schema: {}", Value::Object(schema));
+        schema = Map::new();
+    }
+    schema.insert("type".to_string(), json!("object"));
+    Arc::new(schema)
+}
+
+/// A tool output schema as MCP requires it, or `None` if it is not an object schema
+pub(crate) fn output_schema(schema: Value) -> Option<Arc<Map<String, Value>>> {
+    let Value::Object(mut schema) = schema else {
+        tracing::warn!("Tool output schema is not an object schema: {}", schema);
+        return None;
+    };
+    schema.remove("$schema");
+    match schema.get("type") {
+        None => {}
+        Some(Value::String(t)) if t == "object" => {}
+        _ => {
+            tracing::warn!("Tool output schema is not an object schema: {}", Value::Object(schema));
+            return None;
+        }
+    }
+    schema.insert("type".to_string(), json!("object"));
+    Some(Arc::new(schema))
+}
+
+/// The structured content of a call's output: the output if it is a single object
+pub(crate) fn structured_content(output: &[Value]) -> Option<Value> {
+    match output {
+        [value @ Value::Object(_)] => Some(value.clone()),
+        _ => None,
+    }
+}
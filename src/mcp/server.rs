@@ -10,145 +10,329 @@ use axum::{
 };
 use plexus_core::plexus::Activation;
 use rmcp::transport::streamable_http_server::{
-    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+    session::local::{LocalSessionManager, SessionConfig},
+    SessionManager, StreamableHttpServerConfig, StreamableHttpService,
 };
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
-use crate::config::McpHttpConfig;
+use crate::auth::{ApiKeyAuth, TransportAuth};
+use crate::config::{McpHttpConfig, RequestLogging};
+use crate::hot_swap::Served;
 use crate::mcp::bridge::{ActivationMcpBridge, RouteFn};
+use crate::mcp::admin::AdminSessions;
+use crate::mcp::observed::ObservedSessions;
+use crate::mcp::ping::Pings;
+use crate::mcp::store::StoreSessionManager;
+use crate::mcp::tracked::TrackedSessions;
+use crate::rate_limit::SessionCheck;
+#[cfg(feature = "sqlite-sessions")]
+use crate::mcp::tracked::{gc_interval, spawn_sweeper};
+use crate::redact::Redaction;
 
 #[cfg(feature = "sqlite-sessions")]
 use crate::mcp::session::{SqliteSessionConfig, SqliteSessionManager};
+#[cfg(feature = "redis-sessions")]
+use crate::mcp::redis_session::{RedisSessionConfig, RedisSessionManager};
+
+/// State for [`auth_middleware`]: the enabled methods plus the challenge sent on 401
+#[derive(Clone)]
+struct McpAuthState {
+    auth: TransportAuth,
+    /// `WWW-Authenticate` value; OAuth deployments point at the resource metadata
+    challenge: http::HeaderValue,
+}
 
-/// Middleware to enforce `Authorization: Bearer <key>` on all MCP HTTP requests.
+/// Middleware to enforce authentication on all MCP HTTP requests.
 ///
-/// When the `api_key` state is `Some(key)`, requests missing or supplying the
-/// wrong token are rejected with HTTP 401. When state is `None`, all requests
-/// pass through unchanged (preserving the no-auth default).
+/// When an API key policy is configured, requests must carry a valid key in
+/// `Authorization: Bearer <key>` or `X-Api-Key: <key>`; with a JWT validator
+/// (feature `jwt-auth`), a valid bearer JWT is accepted too. Accepted requests
+/// carry the caller's `ApiKeyIdentity`/`JwtClaims` in their extensions, others
+/// are rejected with HTTP 401. When nothing is configured, all requests pass
+/// through unchanged (preserving the no-auth default).
 async fn auth_middleware(
-    axum::extract::State(api_key): axum::extract::State<Option<String>>,
-    request: Request,
+    axum::extract::State(state): axum::extract::State<McpAuthState>,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    if let Some(ref key) = api_key {
-        let expected = format!("Bearer {}", key);
-        let ok = request
-            .headers()
-            .get(http::header::AUTHORIZATION)
-            .and_then(|v| v.to_str().ok())
-            .map(|v| v == expected)
-            .unwrap_or(false);
-
-        if !ok {
-            tracing::warn!(
-                "MCP HTTP auth rejected: missing or invalid Authorization header (uri={})",
-                request.uri()
-            );
-            return (
-                StatusCode::UNAUTHORIZED,
-                [(
-                    http::header::WWW_AUTHENTICATE,
-                    http::HeaderValue::from_static("Bearer realm=\"plexus\""),
-                )],
-                "Unauthorized",
-            )
-                .into_response();
-        }
+    if !state.auth.authenticate(&mut request).await {
+        tracing::warn!(
+            "MCP HTTP auth rejected: missing or invalid credentials (uri={})",
+            request.uri()
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(http::header::WWW_AUTHENTICATE, state.challenge)],
+            "Unauthorized",
+        )
+            .into_response();
     }
     next.run(request).await
 }
 
-/// Middleware to log all incoming HTTP requests
-async fn log_request_middleware(request: Request, next: Next) -> Response {
+/// State for [`log_request_middleware`]
+#[derive(Clone)]
+struct LogState {
+    redaction: Arc<Redaction>,
+    /// Whether headers and bodies are logged too
+    full: bool,
+    /// Bodies larger than this are rejected instead of buffered
+    max_body_size: usize,
+}
+
+/// Middleware to log incoming HTTP requests
+///
+/// Logs one info line per request (method, URI, status, latency). In
+/// [`RequestLogging::Full`] mode, headers and bodies are logged at debug
+/// level as well, with credentials and other sensitive values masked.
+async fn log_request_middleware(
+    axum::extract::State(logging): axum::extract::State<LogState>,
+    request: Request,
+    next: Next,
+) -> Response {
     let method = request.method().clone();
     let uri = request.uri().clone();
-    let headers = request.headers().clone();
+    let started = std::time::Instant::now();
 
-    tracing::info!("▶▶▶ MCP HTTP REQUEST ▶▶▶");
-    tracing::info!("  Method: {}", method);
-    tracing::info!("  URI: {}", uri);
-    tracing::info!("  Headers:");
-    for (name, value) in headers.iter() {
-        tracing::info!("    {}: {:?}", name, value);
-    }
+    let request = if logging.full {
+        tracing::debug!(
+            "MCP HTTP {} {} headers: {}",
+            method,
+            uri,
+            redacted_headers(&logging.redaction, request.headers())
+        );
+
+        // Buffer the body so it can be logged, then hand it on unchanged
+        let (parts, body) = request.into_parts();
+        let bytes = match axum::body::to_bytes(body, logging.max_body_size).await {
+            Ok(bytes) => bytes,
+            Err(e) if exceeds_limit(&e) => {
+                return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read MCP HTTP request body: {}", e);
+                return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+            }
+        };
+        if !bytes.is_empty() {
+            tracing::debug!("MCP HTTP {} {} body: {}", method, uri, logging.redaction.body(&bytes));
+        }
+        Request::from_parts(parts, axum::body::Body::from(bytes))
+    } else {
+        request
+    };
 
     let response = next.run(request).await;
 
-    let status = response.status();
-    tracing::info!("◀◀◀ MCP HTTP RESPONSE ◀◀◀");
-    tracing::info!("  Status: {}", status);
+    tracing::info!(
+        "MCP HTTP {} {} -> {} ({} ms)",
+        method,
+        uri,
+        response.status(),
+        started.elapsed().as_millis()
+    );
 
     response
 }
 
+/// Whether reading a body failed because it was larger than allowed
+///
+/// The limit is hit either here or in the `RequestBodyLimitLayer` outside,
+/// which only sees chunked bodies fail as they are read.
+fn exceeds_limit(error: &axum::Error) -> bool {
+    std::iter::successors(Some(error as &(dyn std::error::Error + 'static)), |e| e.source())
+        .any(|e| e.is::<http_body_util::LengthLimitError>())
+}
+
+/// `headers` as a JSON object, with sensitive values masked
+fn redacted_headers(redaction: &Redaction, headers: &http::HeaderMap) -> serde_json::Value {
+    headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), redaction.header_value(name, value).into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
 /// Fallback handler for unmatched routes - logs and returns debug info
-async fn fallback_handler(request: Request) -> impl IntoResponse {
+///
+/// Logs a single event with the request as fields, so log pipelines can parse
+/// it; headers are included at debug level, with sensitive values masked.
+async fn fallback_handler(mcp_path: Arc<str>, request: Request) -> impl IntoResponse {
     let method = request.method().clone();
     let uri = request.uri().clone();
-    let headers = request.headers().clone();
-
-    tracing::error!("╔══════════════════════════════════════════════════════════╗");
-    tracing::error!("║  UNMATCHED REQUEST - NO ROUTE FOUND                      ║");
-    tracing::error!("╚══════════════════════════════════════════════════════════╝");
-    tracing::error!("  Method: {}", method);
-    tracing::error!("  URI: {}", uri);
-    tracing::error!("  Path: {}", uri.path());
-    tracing::error!("  Query: {:?}", uri.query());
-    tracing::error!("  Headers:");
-    for (name, value) in headers.iter() {
-        tracing::error!("    {}: {:?}", name, value);
-    }
-
-    let body_hint = if method == axum::http::Method::POST {
-        "(body not captured in fallback - check /mcp endpoint)"
-    } else {
-        "(no body expected)"
-    };
-    tracing::error!("  Body: {}", body_hint);
-    tracing::error!("");
-    tracing::error!("  HINT: MCP endpoint is at /mcp");
-    tracing::error!("  HINT: Make sure to send 'initialize' request first!");
-
-    let debug_response = format!(
-        r#"{{
-  "error": "Route not found",
-  "received": {{
-    "method": "{}",
-    "uri": "{}",
-    "path": "{}"
-  }},
-  "hint": "MCP endpoint is at /mcp. Send 'initialize' request first.",
-  "available_endpoints": ["/mcp", "/debug"]
-}}"#,
-        method,
-        uri,
-        uri.path()
+
+    tracing::warn!(
+        http.method = %method,
+        http.path = uri.path(),
+        http.query = uri.query().unwrap_or_default(),
+        "Unmatched MCP HTTP request (the MCP endpoint is at {})",
+        mcp_path
     );
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        tracing::debug!(
+            http.method = %method,
+            http.path = uri.path(),
+            http.headers = %redacted_headers(&Redaction::default(), request.headers()),
+            "Unmatched MCP HTTP request headers"
+        );
+    }
+
+    let debug_response = serde_json::json!({
+        "error": "Route not found",
+        "received": {
+            "method": method.as_str(),
+            "uri": uri.to_string(),
+            "path": uri.path(),
+        },
+        "hint": format!("MCP endpoint is at {}. Send 'initialize' request first.", mcp_path),
+        "available_endpoints": [&*mcp_path, "/debug"],
+    });
 
     (
         StatusCode::NOT_FOUND,
         [("content-type", "application/json")],
-        debug_response,
+        serde_json::to_string_pretty(&debug_response).unwrap_or_default(),
     )
 }
 
 /// Debug endpoint that returns server info
-async fn debug_handler() -> impl IntoResponse {
+async fn debug_handler(mcp_path: Arc<str>) -> impl IntoResponse {
     tracing::info!("Debug endpoint hit");
 
-    let info = r#"{
-  "server": "plexus-transport",
-  "mcp_endpoint": "/mcp",
-  "mcp_protocol": "MCP Streamable HTTP (2025-03-26)",
-  "notes": [
-    "MCP requires 'initialize' request before 'tools/list'",
-    "Accept header must include 'application/json, text/event-stream'",
-    "Tool names use format: namespace.method (e.g., 'echo.echo')"
-  ]
-}"#;
+    let info = serde_json::json!({
+        "server": "plexus-transport",
+        "mcp_endpoint": &*mcp_path,
+        "mcp_protocol": "MCP Streamable HTTP (2025-03-26)",
+        "notes": [
+            "MCP requires 'initialize' request before 'tools/list'",
+            "Accept header must include 'application/json, text/event-stream'",
+            "Tool names use format: namespace.method (e.g., 'echo.echo')"
+        ]
+    });
+
+    (
+        StatusCode::OK,
+        [("content-type", "application/json")],
+        serde_json::to_string_pretty(&info).unwrap_or_default(),
+    )
+}
+
+/// Hands each new MCP session a bridge to the activation served at that time
+///
+/// Sessions keep the bridge they were created with, so after an activation
+/// swap existing sessions finish on the old instance.
+struct BridgeSource<A: Activation> {
+    state: Mutex<(watch::Receiver<Served<A>>, ActivationMcpBridge<A>)>,
+    config: McpHttpConfig,
+}
+
+impl<A: Activation> BridgeSource<A> {
+    fn new(mut served: watch::Receiver<Served<A>>, config: McpHttpConfig) -> Self {
+        let bridge = build_bridge(&served.borrow_and_update(), &config);
+        Self {
+            state: Mutex::new((served, bridge)),
+            config,
+        }
+    }
+
+    /// The bridge for a new session, rebuilt if the activation was swapped
+    fn bridge(&self) -> ActivationMcpBridge<A> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (served, bridge) = &mut *state;
+        if served.has_changed().unwrap_or(false) {
+            *bridge = build_bridge(&served.borrow_and_update(), &self.config);
+        }
+        bridge.for_session()
+    }
+}
 
-    (StatusCode::OK, [("content-type", "application/json")], info)
+/// Build the MCP bridge for an activation with the transport's settings
+pub(crate) fn build_bridge<A: Activation>(served: &Served<A>, config: &McpHttpConfig) -> ActivationMcpBridge<A> {
+    let mut bridge = ActivationMcpBridge::with_server_info_and_schemas(
+        served.activation.clone(),
+        config.server_name.clone(),
+        config.server_version.clone(),
+        served.flat_schemas.clone(),
+    );
+    if let Some(ref router) = served.route_fn {
+        bridge = bridge.with_router(router.clone());
+    }
+    if let Some(policy) = config.authorization.clone() {
+        bridge = bridge.with_authorization_policy(policy);
+    }
+    for middleware in &config.middleware {
+        bridge = bridge.with_middleware(middleware.clone());
+    }
+    if let Some(filter) = config.tool_filter.clone() {
+        bridge = bridge.with_tool_filter(filter);
+    }
+    if let Some(ref instructions) = config.instructions {
+        bridge = bridge.with_instructions(instructions.clone());
+    }
+    bridge = bridge.with_capabilities(config.capabilities);
+    bridge = bridge.with_tool_naming(config.tool_naming.clone());
+    bridge = bridge.with_tool_timeouts(config.tool_timeouts.clone());
+    if config.partial_results {
+        bridge = bridge.with_partial_results();
+    }
+    if let Some(bytes) = config.max_result_bytes {
+        bridge = bridge.with_max_result_bytes(bytes);
+    }
+    if let Some(size) = config.tools_page_size {
+        bridge = bridge.with_tools_page_size(size);
+    }
+    if let Some(notifier) = config.tool_list.clone() {
+        bridge = bridge.with_tool_list_notifier(notifier);
+    }
+    if let Some(broadcaster) = config.broadcaster.clone() {
+        bridge = bridge.with_broadcaster(broadcaster);
+    }
+    if let Some(provider) = config.schema_provider.clone() {
+        bridge = bridge.with_schema_provider(provider);
+    }
+    if let Some(provider) = config.tool_metadata.clone() {
+        bridge = bridge.with_tool_metadata_provider(provider);
+    }
+    if let Some(provider) = config.resources.clone() {
+        bridge = bridge.with_resource_provider(provider);
+    }
+    if let Some(provider) = config.prompts.clone() {
+        bridge = bridge.with_prompt_provider(provider);
+    }
+    if let Some(provider) = config.completions.clone() {
+        bridge = bridge.with_completion_provider(provider);
+    }
+    if let Some(mapper) = config.error_mapper.clone() {
+        bridge = bridge.with_error_mapper(mapper);
+    }
+    #[cfg(feature = "mcp-logging")]
+    if let Some(forwarder) = config.log_forwarder.clone() {
+        bridge = bridge.with_log_forwarder(forwarder);
+    }
+    if let Some(auditor) = config.audit.clone() {
+        bridge = bridge.with_audit(auditor);
+    }
+    if let Some(log) = config.access_log.clone() {
+        bridge = bridge.with_access_log(log);
+    }
+    if let Some(capture) = config.capture.clone() {
+        bridge = bridge.with_capture(capture);
+    }
+    if let Some(threshold) = config.slow_request_threshold {
+        bridge = bridge.with_slow_request_threshold(threshold);
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = config.metrics.clone() {
+        bridge = bridge.with_metrics(metrics);
+    }
+    for mount in served.mounts.iter() {
+        bridge = bridge.with_mounted_tools(mount.prefix.clone(), mount.tools.clone());
+    }
+    bridge
 }
 
 /// Serve MCP HTTP endpoint for any Activation
@@ -163,6 +347,10 @@ async fn debug_handler() -> impl IntoResponse {
 /// `route_fn` is an optional routing function for hub activations. When provided,
 /// `call_tool` uses it to dispatch namespaced method calls (e.g., "loopback.permit")
 /// to the correct child activation via `hub.route()`.
+///
+/// When `config.tls` is set (feature `tls`), the endpoint is served over HTTPS.
+/// With a client CA configured, the verified client certificate is attached to
+/// each request as a `PeerCertificate` extension.
 pub async fn serve_mcp_http<A: Activation>(
     activation: Arc<A>,
     flat_schemas: Option<Vec<plexus_core::plexus::PluginSchema>>,
@@ -170,72 +358,614 @@ pub async fn serve_mcp_http<A: Activation>(
     config: McpHttpConfig,
     api_key: Option<String>,
 ) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
-    tracing::info!("Starting MCP HTTP transport at http://{}/mcp", config.addr);
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    // Never shut down, so there is nothing to drain
+    let served = Served::fixed(activation, flat_schemas, route_fn);
+    serve_mcp_http_served(served, config, api_key, listener, CancellationToken::new(), None).await
+}
 
-    let mut bridge = ActivationMcpBridge::with_server_info_and_schemas(
-        activation,
-        config.server_name.clone(),
-        config.server_version.clone(),
-        flat_schemas,
+/// Serve MCP HTTP endpoint on an already bound listener until `shutdown` is cancelled
+///
+/// Like [`serve_mcp_http`], but `config.addr` is ignored in favour of
+/// `listener`, so a listener bound to port 0 serves on an OS-assigned port.
+///
+/// Cancelling `shutdown` closes the listener and lets in-flight requests
+/// finish; the returned task completes once they have. Standalone SSE streams
+/// (`GET` on the MCP path) carry no request and are closed right away. Request streams
+/// still open after `drain_timeout` are closed too.
+#[allow(clippy::too_many_arguments)]
+pub async fn serve_mcp_http_with_shutdown<A: Activation>(
+    activation: Arc<A>,
+    flat_schemas: Option<Vec<plexus_core::plexus::PluginSchema>>,
+    route_fn: Option<RouteFn>,
+    config: McpHttpConfig,
+    api_key: Option<String>,
+    listener: tokio::net::TcpListener,
+    shutdown: CancellationToken,
+    drain_timeout: Duration,
+) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
+    let served = Served::fixed(activation, flat_schemas, route_fn);
+    serve_mcp_http_served(served, config, api_key, listener, shutdown, Some(drain_timeout)).await
+}
+
+/// Serve MCP HTTP, giving each new session the activation current when it starts
+///
+/// Without a `drain_timeout`, `shutdown` is never expected to be cancelled:
+/// no drain task waits on it, and a cancellation ends request streams at once.
+pub(crate) async fn serve_mcp_http_served<A: Activation>(
+    served: watch::Receiver<Served<A>>,
+    config: McpHttpConfig,
+    api_key: Option<String>,
+    listener: tokio::net::TcpListener,
+    shutdown: CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> Result<JoinHandle<std::result::Result<(), std::io::Error>>> {
+    let local_addr = listener.local_addr()?;
+    #[cfg(feature = "tls")]
+    let scheme = if config.tls.is_some() { "https" } else { "http" };
+    #[cfg(not(feature = "tls"))]
+    let scheme = "http";
+    let mcp_path: Arc<str> = crate::config::normalize_path(&config.path).into();
+    tracing::info!("Starting MCP HTTP transport at {}://{}{}", scheme, local_addr, mcp_path);
+
+    let legacy_sse = config.legacy_sse.then(|| {
+        if config.stateless {
+            tracing::warn!("Legacy SSE sessions live in memory; pin their clients to one replica");
+        }
+        let bridge = BridgeSource::new(served.clone(), config.clone());
+        super::legacy_sse::legacy_sse_router(move || bridge.bridge(), config.sse_keep_alive, &shutdown)
+    });
+    let endpoint = mcp_endpoint(served, &config, &shutdown, drain_timeout).await?;
+    let on_stop = endpoint.on_stop;
+    let sessions = endpoint.sessions;
+
+    // Build axum router with the MCP endpoint, debug endpoint, request logging, and auth
+    let mcp_app = match &*mcp_path {
+        // axum cannot nest at the root
+        "/" => Router::new().route("/", endpoint.routes),
+        path => Router::new().nest_service(path, endpoint.routes),
+    };
+    let mcp_app = {
+        let debug_path = mcp_path.clone();
+        let fallback_path = mcp_path.clone();
+        mcp_app
+            .route("/debug", any(move || debug_handler(debug_path.clone())))
+            .fallback(move |request: Request| fallback_handler(fallback_path.clone(), request))
+    };
+    let mcp_app = match legacy_sse {
+        Some(routes) => mcp_app.merge(routes),
+        None => mcp_app,
+    };
+    let mcp_app = with_request_layers(mcp_app, &config, api_key, sessions)?;
+
+    // Extra routes skip authentication and rate limiting, like the metadata below
+    let mcp_app = match config.routes.clone() {
+        Some(routes) => mcp_app.merge(routes),
+        None => mcp_app,
+    };
+
+    // Protected resource metadata must be reachable without a token, so it is
+    // routed after (outside) the auth layer
+    #[cfg(feature = "jwt-auth")]
+    let mcp_app = match config.oauth {
+        Some(ref oauth) => {
+            let mut metadata_routes = Router::new().route(
+                crate::mcp::oauth::PROTECTED_RESOURCE_METADATA_PATH,
+                axum::routing::get(crate::mcp::oauth::protected_resource_metadata_handler),
+            );
+            // Also at the path-specific location (RFC 9728)
+            if &*mcp_path != "/" {
+                metadata_routes = metadata_routes.route(
+                    &format!("{}{}", crate::mcp::oauth::PROTECTED_RESOURCE_METADATA_PATH, mcp_path),
+                    axum::routing::get(crate::mcp::oauth::protected_resource_metadata_handler),
+                );
+            }
+            let metadata_routes = metadata_routes.with_state(oauth.clone());
+            mcp_app.merge(metadata_routes)
+        }
+        None => mcp_app,
+    };
+
+    let mcp_app = with_custom_layers(mcp_app, &config);
+    let mcp_app = with_browser_layers(mcp_app, &config, local_addr);
+
+    // Start MCP HTTPS server (TLS terminated in-process via axum-server/rustls)
+    #[cfg(feature = "tls")]
+    if let Some(ref tls) = config.tls {
+        let rustls_config = axum_server::tls_rustls::RustlsConfig::from_config(
+            crate::tls::server_config(tls, &[b"h2", b"http/1.1"])?,
+        );
+        let listener = listener.into_std()?;
+        let ip_filter = config.ip_filter.clone();
+        let server_handle = axum_server::Handle::new();
+        if drain_timeout.is_some() {
+            let server_handle = server_handle.clone();
+            crate::task::spawn("plexus.mcp.shutdown", async move {
+                shutdown.cancelled().await;
+                server_handle.graceful_shutdown(drain_timeout);
+            });
+        }
+        let handle = crate::task::spawn("plexus.mcp.server", async move {
+            let result = axum_server::from_tcp(listener)
+                .handle(server_handle)
+                .acceptor(crate::ip_filter::IpFilterAcceptor::new(
+                    crate::tls::PeerCertificateAcceptor::new(rustls_config),
+                    ip_filter,
+                    "MCP HTTP",
+                ))
+                .serve(mcp_app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+                .await;
+            if let Some(on_stop) = on_stop {
+                on_stop.await;
+            }
+            result
+        });
+        return Ok(handle);
+    }
+
+    // Start MCP HTTP server
+    let listener = crate::ip_filter::FilteredListener::new(
+        listener,
+        config.ip_filter.clone(),
+        "MCP HTTP",
     );
-    if let Some(router) = route_fn {
-        bridge = bridge.with_router(router);
+    let handle = crate::task::spawn("plexus.mcp.server", async move {
+        let result = axum::serve(
+            listener,
+            mcp_app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { shutdown.cancelled().await })
+        .await;
+        if let Some(on_stop) = on_stop {
+            on_stop.await;
+        }
+        result
+    });
+
+    Ok(handle)
+}
+
+/// Build the MCP endpoint as an axum router, for embedding in an existing application
+///
+/// The router answers MCP Streamable HTTP at its root, so
+/// `app.nest("/mcp", router)` serves it at `/mcp` next to the application's
+/// own routes and layers. `flat_schemas` and `route_fn` work as in
+/// [`serve_mcp_http`].
+///
+/// The middleware configured on `config` (authentication, rate limiting, body
+/// size limit, request logging, CORS) is applied; leave it unset to rely on
+/// the application's layers instead. `OriginCheck::Auto` cannot know the bound
+/// address and assumes a loopback bind, accepting only localhost and CORS
+/// origins; use `OriginCheck::Allow` or `OriginCheck::Disabled` when browsers
+/// on other origins call the application.
+/// Listener settings (`addr`, TLS, IP filter), `path` and `routes` are the
+/// application's business, and OAuth protected resource metadata must be
+/// served at the application root. `legacy_sse` is ignored: its endpoint URLs
+/// assume the server root.
+///
+/// Standalone SSE streams stay open until their clients disconnect, and SQLite
+/// and Redis sessions are not flushed when the application stops.
+pub async fn build_mcp_router<A: Activation>(
+    activation: Arc<A>,
+    flat_schemas: Option<Vec<plexus_core::plexus::PluginSchema>>,
+    route_fn: Option<RouteFn>,
+    config: McpHttpConfig,
+) -> Result<Router> {
+    let served = Served::fixed(activation, flat_schemas, route_fn);
+    // The application owns shutdown, so there is nothing to drain the routes on
+    let endpoint = mcp_endpoint(served, &config, &CancellationToken::new(), None).await?;
+    let router = Router::new().route("/", endpoint.routes);
+    let router = with_request_layers(router, &config, None, endpoint.sessions)?;
+    let router = with_custom_layers(router, &config);
+    // The bound address is unknown; assume a local server rather than drop the check
+    let assumed = std::net::SocketAddr::from(([127, 0, 0, 1], 0));
+    Ok(with_browser_layers(router, &config, assumed))
+}
+
+/// The MCP Streamable HTTP routes of an activation
+struct McpEndpoint {
+    routes: axum::routing::MethodRouter,
+    /// Tells session ids the server issued from made-up ones
+    sessions: Option<SessionCheck>,
+    /// Flushes the session store once the server has drained
+    on_stop: Option<futures::future::BoxFuture<'static, ()>>,
+}
+
+/// Create the MCP service and its session store as configured
+async fn mcp_endpoint<A: Activation>(
+    served: watch::Receiver<Served<A>>,
+    config: &McpHttpConfig,
+    shutdown: &CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> Result<McpEndpoint> {
+    let bridge = Arc::new(BridgeSource::new(served, config.clone()));
+
+    if config.stateless {
+        if !matches!(config.session_storage, crate::config::SessionStorage::InMemory)
+            || config.session_ttl.is_some()
+            || config.ping_interval.is_some()
+        {
+            tracing::warn!("MCP HTTP is stateless; session storage, TTL and pings are ignored");
+        }
+        return Ok(McpEndpoint {
+            routes: stateless_routes(bridge, config, shutdown, drain_timeout),
+            sessions: None,
+            on_stop: None,
+        });
     }
 
-    // Create session manager based on configuration
-    #[cfg(feature = "sqlite-sessions")]
-    let mcp_service = match config.session_storage {
+    let endpoint = match config.session_storage {
         crate::config::SessionStorage::InMemory => {
-            let session_manager = LocalSessionManager::new();
-            let server_config = StreamableHttpServerConfig::default();
-            let bridge_clone = bridge.clone();
-            StreamableHttpService::new(
-                move || Ok(bridge_clone.clone()),
-                session_manager.into(),
-                server_config,
-            )
+            let session_manager = LocalSessionManager {
+                sessions: Default::default(),
+                session_config: session_config(config),
+            };
+            let (routes, sessions) = session_routes(bridge, Arc::new(session_manager), config, shutdown, drain_timeout);
+            McpEndpoint {
+                routes,
+                sessions: Some(sessions),
+                on_stop: None,
+            }
         }
-        crate::config::SessionStorage::Sqlite { path } => {
-            let sqlite_config = SqliteSessionConfig {
-                db_path: path,
+        #[cfg(feature = "sqlite-sessions")]
+        crate::config::SessionStorage::Sqlite { ref path } => {
+            let mut sqlite_config = SqliteSessionConfig {
+                db_path: path.clone(),
+                session_config: session_config(config),
+                tuning: config.sqlite_tuning.clone(),
                 ..Default::default()
             };
-            let session_manager = SqliteSessionManager::new(sqlite_config)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to initialize SQLite session manager: {}", e))?;
-            let server_config = StreamableHttpServerConfig::default();
-            let bridge_clone = bridge.clone();
-            StreamableHttpService::new(
-                move || Ok(bridge_clone.clone()),
-                session_manager.into(),
-                server_config,
-            )
+            if let Some(ttl) = config.session_ttl {
+                sqlite_config.max_session_age = ttl;
+            }
+            let session_manager = Arc::new(
+                SqliteSessionManager::new(sqlite_config)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to initialize SQLite session manager: {}", e))?,
+            );
+            if config.session_ttl.is_some() {
+                spawn_sqlite_cleanup(&session_manager, config, shutdown.clone());
+            }
+            if let Some(interval) = config.sqlite_tuning.vacuum_interval {
+                spawn_sqlite_vacuum(&session_manager, interval, shutdown.clone());
+            }
+            let (routes, sessions) = session_routes(bridge, session_manager.clone(), config, shutdown, drain_timeout);
+            McpEndpoint {
+                routes,
+                sessions: Some(sessions),
+                on_stop: Some(Box::pin(async move {
+                    if let Err(e) = session_manager.close().await {
+                        tracing::error!("Failed to flush MCP sessions: {}", e);
+                    }
+                })),
+            }
+        }
+        crate::config::SessionStorage::Custom(ref store) => {
+            let session_manager = Arc::new(StoreSessionManager::with_session_config(store.clone(), session_config(config)));
+            let (routes, sessions) = session_routes(bridge, session_manager.clone(), config, shutdown, drain_timeout);
+            McpEndpoint {
+                routes,
+                sessions: Some(sessions),
+                on_stop: Some(Box::pin(async move {
+                    if let Err(e) = session_manager.close().await {
+                        tracing::error!("Failed to flush MCP sessions: {}", e);
+                    }
+                })),
+            }
+        }
+        #[cfg(feature = "redis-sessions")]
+        crate::config::SessionStorage::Redis { ref url, ref key_prefix, ttl } => {
+            let redis_config = RedisSessionConfig {
+                url: url.clone(),
+                key_prefix: key_prefix.clone(),
+                ttl,
+                session_config: session_config(config),
+            };
+            let session_manager = Arc::new(
+                RedisSessionManager::new(redis_config)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to initialize Redis session manager: {}", e))?,
+            );
+            let (routes, sessions) = session_routes(bridge, session_manager.clone(), config, shutdown, drain_timeout);
+            McpEndpoint {
+                routes,
+                sessions: Some(sessions),
+                on_stop: Some(Box::pin(async move {
+                    if let Err(e) = session_manager.close().await {
+                        tracing::error!("Failed to flush MCP sessions: {}", e);
+                    }
+                })),
+            }
+        }
+    };
+
+    Ok(endpoint)
+}
+
+/// Worker settings for every session of the endpoint
+fn session_config(config: &McpHttpConfig) -> SessionConfig {
+    SessionConfig {
+        channel_capacity: config.session_channel_capacity.max(1),
+        ..Default::default()
+    }
+}
+
+/// rmcp service settings: keep-alive interval and stateful mode
+fn server_config(config: &McpHttpConfig) -> StreamableHttpServerConfig {
+    StreamableHttpServerConfig {
+        sse_keep_alive: config.sse_keep_alive,
+        stateful_mode: !config.stateless,
+        ..Default::default()
+    }
+}
+
+/// Delete idle sessions left in the SQLite database, e.g. by earlier runs
+///
+/// Sessions with a worker are closed by the session GC; this catches the rows
+/// no worker is left for, which would otherwise accumulate.
+#[cfg(feature = "sqlite-sessions")]
+fn spawn_sqlite_cleanup(
+    session_manager: &Arc<SqliteSessionManager>,
+    config: &McpHttpConfig,
+    shutdown: CancellationToken,
+) {
+    let Some(ttl) = config.session_ttl else {
+        return;
+    };
+    #[cfg(feature = "metrics")]
+    let metrics = config.metrics.clone();
+    let session_manager = Arc::downgrade(session_manager);
+    spawn_sweeper("plexus.mcp.sqlite_cleanup", gc_interval(ttl), shutdown, move || {
+        let session_manager = session_manager.clone();
+        #[cfg(feature = "metrics")]
+        let metrics = metrics.clone();
+        async move {
+            let Some(session_manager) = session_manager.upgrade() else {
+                return;
+            };
+            match session_manager.cleanup_old_sessions().await {
+                Ok(0) => {}
+                Ok(deleted) => {
+                    tracing::info!(count = deleted, "Deleted expired MCP sessions from SQLite");
+                    #[cfg(feature = "metrics")]
+                    if let Some(metrics) = metrics {
+                        metrics.record_expired_sessions(deleted);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to clean up MCP sessions: {}", e),
+            }
+        }
+    });
+}
+
+/// Vacuum the SQLite session database every `interval`
+#[cfg(feature = "sqlite-sessions")]
+fn spawn_sqlite_vacuum(session_manager: &Arc<SqliteSessionManager>, interval: Duration, shutdown: CancellationToken) {
+    let session_manager = Arc::downgrade(session_manager);
+    spawn_sweeper("plexus.mcp.sqlite_vacuum", interval, shutdown, move || {
+        let session_manager = session_manager.clone();
+        async move {
+            let Some(session_manager) = session_manager.upgrade() else {
+                return;
+            };
+            match session_manager.vacuum().await {
+                Ok(()) => tracing::debug!("Vacuumed MCP session database"),
+                Err(e) => tracing::warn!("Failed to vacuum MCP session database: {}", e),
+            }
         }
+    });
+}
+
+/// Serve MCP sessions kept by `session_manager`
+///
+/// With `config.observer` set, the sessions are reported to it as they are
+/// created and expire. With `config.session_ttl` set, idle sessions are closed
+/// until `shutdown` is cancelled, with `config.ping_interval` set, sessions
+/// whose clients stop answering pings are closed, and with
+/// `config.session_admin` set they can be listed and terminated.
+///
+/// Also returns the check the rate limiter validates session ids with.
+fn session_routes<A, M>(
+    bridge: Arc<BridgeSource<A>>,
+    session_manager: Arc<M>,
+    config: &McpHttpConfig,
+    shutdown: &CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> (axum::routing::MethodRouter, SessionCheck)
+where
+    A: Activation,
+    M: SessionManager,
+{
+    let sessions: SessionCheck = {
+        let session_manager = session_manager.clone();
+        Arc::new(move |id: String| {
+            let session_manager = session_manager.clone();
+            Box::pin(async move { session_manager.has_session(&id.into()).await.unwrap_or(false) })
+        })
+    };
+    let session_manager = Arc::new(TrackedSessions::new(session_manager, config));
+    session_manager.spawn_gc(config, shutdown.clone());
+    let endpoint: Arc<dyn AdminSessions> = session_manager.clone();
+    if let Some(admin) = &config.session_admin {
+        admin.register(Arc::downgrade(&endpoint));
+    }
+    let pings = config
+        .ping_interval
+        .map(|interval| Pings::new(interval, Arc::downgrade(&endpoint)));
+
+    let server_config = server_config(config);
+    let factory = move || {
+        let session = bridge.bridge();
+        Ok(match pings {
+            Some(ref pings) => session.with_pings(pings.clone()),
+            None => session,
+        })
+    };
+    let routes = match config.observer.clone() {
+        Some(observer) => drain_routes(
+            StreamableHttpService::new(factory, Arc::new(ObservedSessions::new(session_manager, observer)), server_config),
+            shutdown,
+            drain_timeout,
+        ),
+        None => drain_routes(
+            StreamableHttpService::new(factory, session_manager, server_config),
+            shutdown,
+            drain_timeout,
+        ),
     };
+    (routes, sessions)
+}
 
-    #[cfg(not(feature = "sqlite-sessions"))]
-    let mcp_service = {
-        let session_manager = LocalSessionManager::default();
-        let server_config = StreamableHttpServerConfig::default();
-        let bridge_clone = bridge.clone();
+/// Serve every MCP request on its own, without sessions
+///
+/// rmcp answers GET and DELETE with 405, as there is no session to stream or
+/// delete; the session manager is never used.
+fn stateless_routes<A: Activation>(
+    bridge: Arc<BridgeSource<A>>,
+    config: &McpHttpConfig,
+    shutdown: &CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> axum::routing::MethodRouter {
+    drain_routes(
         StreamableHttpService::new(
-            move || Ok(bridge_clone.clone()),
-            session_manager.into(),
-            server_config,
-        )
+            move || Ok(bridge.bridge()),
+            Arc::new(LocalSessionManager::default()),
+            server_config(config),
+        ),
+        shutdown,
+        drain_timeout,
+    )
+}
+
+/// Route GET, POST and DELETE to the MCP service with shutdown-aware streams
+///
+/// Standalone SSE streams end as soon as shutdown starts; streams answering a
+/// request get until the drain deadline to deliver their response. Without a
+/// `drain_timeout` nobody drains the routes, and request streams are never cut.
+fn drain_routes<S, M>(
+    service: StreamableHttpService<S, M>,
+    shutdown: &CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> axum::routing::MethodRouter
+where
+    S: rmcp::Service<rmcp::RoleServer> + Send + 'static,
+    M: SessionManager,
+{
+    let mut stream_service = service.clone();
+    stream_service.config.cancellation_token = shutdown.child_token();
+    let mut request_service = service;
+    if let Some(drain_timeout) = drain_timeout {
+        let requests = CancellationToken::new();
+        request_service.config.cancellation_token = requests.clone();
+        let shutdown = shutdown.clone();
+        crate::task::spawn("plexus.mcp.drain", async move {
+            shutdown.cancelled().await;
+            tokio::time::sleep(drain_timeout).await;
+            requests.cancel();
+        });
+    }
+    axum::routing::get_service(stream_service)
+        .post_service(request_service.clone())
+        .delete_service(request_service)
+}
+
+/// Apply request logging, the body limit, rate limiting and authentication
+fn with_request_layers(
+    router: Router,
+    config: &McpHttpConfig,
+    api_key: Option<String>,
+    sessions: Option<SessionCheck>,
+) -> Result<Router> {
+    // `config.auth` takes precedence over the single legacy bearer token;
+    // an OAuth resource config supplies the JWT validator and the 401 challenge
+    let auth = TransportAuth {
+        api_key: config
+            .auth
+            .clone()
+            .or_else(|| config.api_key.clone().or(api_key).map(|key| ApiKeyAuth::keys([key]))),
+        #[cfg(feature = "jwt-auth")]
+        jwt: config
+            .oauth
+            .as_ref()
+            .map(|oauth| oauth.validator.clone())
+            .or_else(|| config.jwt.clone()),
     };
 
-    // Build axum router with MCP at /mcp, debug endpoint, request logging, and auth
-    let mcp_app = Router::new()
-        .nest_service("/mcp", mcp_service)
-        .route("/debug", any(debug_handler))
-        .fallback(fallback_handler)
-        .layer(middleware::from_fn(log_request_middleware))
-        .layer(middleware::from_fn_with_state(api_key, auth_middleware));
+    #[cfg(feature = "jwt-auth")]
+    let challenge = match config.oauth {
+        Some(ref oauth) => http::HeaderValue::from_str(&oauth.challenge())
+            .map_err(|e| anyhow::anyhow!("Invalid OAuth resource URL: {}", e))?,
+        None => http::HeaderValue::from_static("Bearer realm=\"plexus\""),
+    };
+    #[cfg(not(feature = "jwt-auth"))]
+    let challenge = http::HeaderValue::from_static("Bearer realm=\"plexus\"");
 
-    // Start MCP HTTP server
-    let listener = tokio::net::TcpListener::bind(config.addr).await?;
-    let handle = tokio::spawn(async move { axum::serve(listener, mcp_app).await });
+    let auth_state = McpAuthState { auth, challenge };
 
-    Ok(handle)
+    let mut router = router;
+    if config.request_logging != RequestLogging::Off {
+        router = router.layer(middleware::from_fn_with_state(
+            LogState {
+                redaction: Arc::new(config.redaction.clone()),
+                full: config.request_logging == RequestLogging::Full,
+                max_body_size: config.max_body_size,
+            },
+            log_request_middleware,
+        ));
+    }
+
+    // Oversized bodies get 413 before rmcp buffers them
+    router = router.layer(tower_http::limit::RequestBodyLimitLayer::new(config.max_body_size));
+
+    // Rate limiting runs inside the auth layer so it can key on the caller's identity
+    if let Some(rate_limit) = config.rate_limit.clone() {
+        let limiter = crate::rate_limit::RateLimiter::new(rate_limit);
+        let limiter = Arc::new(match sessions {
+            Some(sessions) => limiter.with_session_check(sessions),
+            None => limiter,
+        });
+        router = router.layer(middleware::from_fn_with_state(
+            limiter,
+            crate::rate_limit::rate_limit_middleware,
+        ));
+    }
+
+    let router = router.layer(middleware::from_fn_with_state(auth_state, auth_middleware));
+
+    // Requests failing authentication never reach the limit above; limit them by IP
+    Ok(match config.rate_limit {
+        Some(ref rate_limit) => router.layer(middleware::from_fn_with_state(
+            crate::rate_limit::auth_failure_limiter(rate_limit),
+            crate::rate_limit::auth_failure_limit_middleware,
+        )),
+        None => router,
+    })
+}
+
+/// Apply the layers registered with [`McpHttpConfig::with_layer`]
+fn with_custom_layers(router: Router, config: &McpHttpConfig) -> Router {
+    config.layers.iter().fold(router, |router, layer| layer.apply(router))
+}
+
+/// Apply origin validation and CORS, the outermost layers
+fn with_browser_layers(router: Router, config: &McpHttpConfig, local_addr: std::net::SocketAddr) -> Router {
+    // Reject browser requests from foreign origins (DNS rebinding) before auth;
+    // origins allowed by CORS are trusted too. Enabling CORS never turns the
+    // check off, only `OriginCheck::Disabled` does
+    let cors_origins = config.cors.as_ref().map_or(&[][..], |cors| &cors.allowed_origins[..]);
+    let origin_check = config.origin_check.resolve(local_addr, cors_origins);
+    let router = match origin_check {
+        Some(allowed) => router.layer(middleware::from_fn_with_state(
+            Arc::new(allowed),
+            crate::origin_check::origin_middleware,
+        )),
+        None => router,
+    };
+
+    // CORS wraps everything so preflights are answered before auth and even
+    // 401/429 responses carry the headers browsers need to read them
+    match config.cors {
+        Some(ref cors) => router.layer(cors.layer()),
+        None => router,
+    }
 }
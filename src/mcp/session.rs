@@ -4,40 +4,123 @@
 //! state to SQLite, allowing clients to reconnect after server restarts.
 //!
 //! Sessions older than 30 days (configurable) are automatically cleaned up on startup.
+//! [`SqliteTuning`] sets the journal mode, busy timeout and pool size for
+//! concurrent load, and how often the database is vacuumed.
+//!
+//! Events sent on SSE streams are journaled per session, so a client that
+//! reconnects with `Last-Event-ID` is sent what it missed, even once the
+//! worker's in-memory buffer has moved on or the request's stream has ended.
+//!
+//! [`SqliteSessionManager::export`] and [`SqliteSessionManager::import`] move
+//! the persisted sessions between databases as a [`SessionSnapshot`], e.g.
+//! when an upgrade moves the server to another host.
 
 use std::{
-    collections::HashMap,
     path::PathBuf,
+    sync::Arc,
     time::Duration,
 };
 
-use futures::Stream;
+use futures::{future::BoxFuture, Stream};
+use serde::{Deserialize, Serialize};
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqlitePool},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions},
     ConnectOptions,
 };
 use thiserror::Error;
-use tokio::sync::RwLock;
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::sync::{mpsc, oneshot};
 
 use rmcp::{
     model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
     transport::{
         WorkerTransport,
-        common::server_side_http::{SessionId, ServerSseMessage, session_id},
+        common::server_side_http::{SessionId, ServerSseMessage},
         streamable_http_server::session::{
             SessionManager,
-            local::{
-                LocalSessionWorker, LocalSessionHandle, SessionConfig,
-                SessionError, create_local_session, EventIdParseError,
-            },
+            local::{LocalSessionWorker, SessionConfig, SessionError, EventIdParseError},
         },
     },
 };
 
+use super::store::{parse_event_id, SessionStore, SessionStoreError, StoreSessionError, StoreSessionManager};
+
 /// Default session cleanup age: 30 days
 pub const DEFAULT_SESSION_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
+/// Default time a connection waits for a lock held by another: 5 seconds
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default number of pooled connections
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+
+/// Default number of stream events kept per session for resumption
+pub const DEFAULT_REPLAY_EVENTS: usize = 256;
+
+/// Connection and maintenance settings of the session database
+///
+/// The defaults suit concurrent agents: write-ahead logging lets readers
+/// proceed during writes, and a busy timeout makes a writer wait for the lock
+/// instead of failing with `database is locked`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqliteTuning {
+    /// Use write-ahead logging instead of a rollback journal (default: true)
+    pub wal: bool,
+    /// How long a connection waits for a lock before failing
+    #[serde(with = "crate::config::serde_secs")]
+    pub busy_timeout: Duration,
+    /// Maximum number of pooled connections
+    pub max_connections: u32,
+    /// Run `VACUUM` this often to return space freed by deleted sessions;
+    /// `None` (default) never vacuums
+    #[serde(with = "crate::config::serde_secs::option")]
+    pub vacuum_interval: Option<Duration>,
+    /// Stream events kept per session and replayed to clients resuming with
+    /// `Last-Event-ID`; 0 disables the event journal
+    pub replay_events: usize,
+}
+
+impl Default for SqliteTuning {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout: DEFAULT_BUSY_TIMEOUT,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            vacuum_interval: None,
+            replay_events: DEFAULT_REPLAY_EVENTS,
+        }
+    }
+}
+
+/// Current [`SessionSnapshot::version`]
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The persisted sessions of a database, for moving them to another one
+///
+/// Serializes to JSON:
+///
+/// ```json
+/// {"version": 1, "exported_at": 1760000000, "sessions": [{"id": "6f1c...", "created_at": 1759990000, "last_seen_at": 1759999000}]}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+    /// When the snapshot was taken (seconds since the Unix epoch)
+    pub exported_at: i64,
+    pub sessions: Vec<PersistedSession>,
+}
+
+/// A session as persisted in the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedSession {
+    pub id: String,
+    /// Seconds since the Unix epoch
+    pub created_at: i64,
+    /// Seconds since the Unix epoch
+    pub last_seen_at: i64,
+}
+
 /// Configuration for SQLite session storage
 #[derive(Debug, Clone)]
 pub struct SqliteSessionConfig {
@@ -47,6 +130,8 @@ pub struct SqliteSessionConfig {
     pub session_config: SessionConfig,
     /// Maximum age for sessions before cleanup (default: 30 days)
     pub max_session_age: Duration,
+    /// Journal mode, busy timeout, pool size and vacuum schedule
+    pub tuning: SqliteTuning,
 }
 
 impl Default for SqliteSessionConfig {
@@ -55,6 +140,7 @@ impl Default for SqliteSessionConfig {
             db_path: PathBuf::from("mcp_sessions.db"),
             session_config: SessionConfig::default(),
             max_session_age: DEFAULT_SESSION_MAX_AGE,
+            tuning: SqliteTuning::default(),
         }
     }
 }
@@ -77,12 +163,49 @@ pub enum SqliteSessionError {
 /// Persists session IDs to SQLite so clients can reconnect after server restart.
 /// The actual session workers are created on-demand, but session identity persists.
 pub struct SqliteSessionManager {
+    store: Arc<SqliteStore>,
+    /// Runs the session workers for sessions kept in `store`
+    manager: StoreSessionManager,
+}
+
+/// The session database, as a [`SessionStore`]
+struct SqliteStore {
     pool: SqlitePool,
-    /// In-memory session handles (runtime state)
-    sessions: RwLock<HashMap<SessionId, LocalSessionHandle>>,
-    session_config: SessionConfig,
     /// Maximum age for sessions before cleanup
     max_session_age: Duration,
+    /// Queue of stream events to journal; `None` when replay is disabled
+    journal: Option<mpsc::UnboundedSender<JournalEntry>>,
+}
+
+/// Work for the journal writer
+enum JournalEntry {
+    Event {
+        session_id: String,
+        event_id: String,
+        message: Arc<ServerJsonRpcMessage>,
+    },
+    /// Answered once every earlier event is written
+    Flush(oneshot::Sender<()>),
+}
+
+impl From<StoreSessionError> for SqliteSessionError {
+    fn from(e: StoreSessionError) -> Self {
+        match e {
+            StoreSessionError::SessionNotFound(id) => Self::SessionNotFound(id),
+            StoreSessionError::SessionError(e) => Self::SessionError(e),
+            StoreSessionError::InvalidEventId(e) => Self::InvalidEventId(e),
+            StoreSessionError::StoreError(e) => Self::DatabaseError(e.0),
+        }
+    }
+}
+
+impl From<SqliteSessionError> for SessionStoreError {
+    fn from(e: SqliteSessionError) -> Self {
+        match e {
+            SqliteSessionError::DatabaseError(message) => Self(message),
+            e => Self::new(e),
+        }
+    }
 }
 
 impl SqliteSessionManager {
@@ -93,28 +216,35 @@ impl SqliteSessionManager {
             .parse()
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to parse DB URL: {}", e)))?;
         connect_options.disable_statement_logging();
-
-        let pool = SqlitePool::connect_with(connect_options.clone())
+        let tuning = &config.tuning;
+        let connect_options = connect_options
+            .journal_mode(if tuning.wal { SqliteJournalMode::Wal } else { SqliteJournalMode::Delete })
+            .busy_timeout(tuning.busy_timeout);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(tuning.max_connections.max(1))
+            .connect_with(connect_options)
             .await
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to connect: {}", e)))?;
 
-        let manager = Self {
+        let journal = (tuning.replay_events > 0)
+            .then(|| spawn_journal_writer(pool.clone(), tuning.replay_events));
+
+        let store = Arc::new(SqliteStore {
             pool,
-            sessions: RwLock::new(HashMap::new()),
-            session_config: config.session_config,
             max_session_age: config.max_session_age,
-        };
-
-        manager.run_migrations().await?;
+            journal,
+        });
+        store.run_migrations().await?;
 
         // Clean up old sessions on startup
-        let cleaned = manager.cleanup_old_sessions().await?;
+        let cleaned = store.cleanup_old_sessions().await?;
         if cleaned > 0 {
             tracing::info!(count = cleaned, "Cleaned up old MCP sessions");
         }
 
         // Log persisted sessions (for debugging)
-        let persisted = manager.count_persisted_sessions().await?;
+        let persisted = store.count_persisted_sessions().await?;
         if persisted > 0 {
             tracing::info!(
                 count = persisted,
@@ -122,9 +252,118 @@ impl SqliteSessionManager {
             );
         }
 
-        Ok(manager)
+        let manager = StoreSessionManager::with_session_config(store.clone(), config.session_config);
+        Ok(Self { store, manager })
+    }
+
+    /// Clean up sessions older than max_session_age
+    ///
+    /// Sessions with a live worker are touched first, so only abandoned rows
+    /// are removed. Returns the number of sessions cleaned up
+    pub async fn cleanup_old_sessions(&self) -> Result<usize, SqliteSessionError> {
+        self.touch_live().await?;
+        self.store.cleanup_old_sessions().await
     }
 
+    /// Snapshot every persisted session
+    ///
+    /// Live sessions are touched first, so the snapshot shows them as seen now.
+    pub async fn export(&self) -> Result<SessionSnapshot, SqliteSessionError> {
+        self.touch_live().await?;
+
+        let rows = sqlx::query("SELECT id, created_at, last_seen_at FROM mcp_sessions ORDER BY created_at, id")
+            .fetch_all(&self.store.pool)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to export sessions: {}", e)))?;
+
+        let sessions = rows
+            .iter()
+            .map(|row| PersistedSession {
+                id: sqlx::Row::get(row, "id"),
+                created_at: sqlx::Row::get(row, "created_at"),
+                last_seen_at: sqlx::Row::get(row, "last_seen_at"),
+            })
+            .collect();
+
+        Ok(SessionSnapshot {
+            version: SNAPSHOT_VERSION,
+            exported_at: now(),
+            sessions,
+        })
+    }
+
+    /// Persist the sessions of a snapshot, returning how many were imported
+    ///
+    /// Sessions already in the database keep the later of the two
+    /// `last_seen_at` times. Imported sessions are in the state a restart
+    /// leaves them in: clients resuming their SSE stream get a new worker for
+    /// the session, others are asked to start a new one.
+    pub async fn import(&self, snapshot: &SessionSnapshot) -> Result<usize, SqliteSessionError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(SqliteSessionError::DatabaseError(format!(
+                "Unsupported session snapshot version {}",
+                snapshot.version
+            )));
+        }
+
+        let mut tx = self
+            .store
+            .pool
+            .begin()
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to import sessions: {}", e)))?;
+        for session in &snapshot.sessions {
+            sqlx::query(
+                "INSERT INTO mcp_sessions (id, created_at, last_seen_at) VALUES (?, ?, ?) \
+                 ON CONFLICT(id) DO UPDATE SET last_seen_at = MAX(last_seen_at, excluded.last_seen_at)",
+            )
+            .bind(&session.id)
+            .bind(session.created_at)
+            .bind(session.last_seen_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to import session: {}", e)))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to import sessions: {}", e)))?;
+
+        tracing::info!(count = snapshot.sessions.len(), "Imported MCP sessions");
+        Ok(snapshot.sessions.len())
+    }
+
+    /// Rebuild the database file, returning the space of deleted sessions
+    ///
+    /// Blocks other writers while it runs. Called every
+    /// [`SqliteTuning::vacuum_interval`] by the MCP server.
+    pub async fn vacuum(&self) -> Result<(), SqliteSessionError> {
+        sqlx::query("VACUUM")
+            .execute(&self.store.pool)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to vacuum: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Flush session state and close the database
+    ///
+    /// Records the current time as `last_seen_at` for every live session, so
+    /// sessions in use at shutdown are not aged out early, then closes the pool,
+    /// which checkpoints the write-ahead log. Called when the MCP server stops.
+    pub async fn close(&self) -> Result<(), SqliteSessionError> {
+        Ok(self.manager.close().await?)
+    }
+
+    /// Record the current time as `last_seen_at` for every live session
+    async fn touch_live(&self) -> Result<(), SqliteSessionError> {
+        for id in self.manager.live().await {
+            self.store.touch_session(&id).await?;
+        }
+        Ok(())
+    }
+}
+
+impl SqliteStore {
     /// Count persisted sessions in database
     async fn count_persisted_sessions(&self) -> Result<usize, SqliteSessionError> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM mcp_sessions")
@@ -136,22 +375,19 @@ impl SqliteSessionManager {
         Ok(count as usize)
     }
 
-
-    /// Clean up sessions older than max_session_age
-    ///
-    /// Returns the number of sessions cleaned up
-    pub async fn cleanup_old_sessions(&self) -> Result<usize, SqliteSessionError> {
-        let cutoff = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64
-            - self.max_session_age.as_secs() as i64;
+    /// Delete sessions not seen for max_session_age, and their events
+    async fn cleanup_old_sessions(&self) -> Result<usize, SqliteSessionError> {
+        let cutoff = now() - self.max_session_age.as_secs() as i64;
 
         let result = sqlx::query("DELETE FROM mcp_sessions WHERE last_seen_at < ?")
             .bind(cutoff)
             .execute(&self.pool)
             .await
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to cleanup sessions: {}", e)))?;
+        sqlx::query("DELETE FROM mcp_session_cache WHERE session_id NOT IN (SELECT id FROM mcp_sessions)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to cleanup events: {}", e)))?;
 
         Ok(result.rows_affected() as usize)
     }
@@ -177,6 +413,7 @@ impl SqliteSessionManager {
 
             CREATE INDEX IF NOT EXISTS idx_session_cache_session ON mcp_session_cache(session_id);
             CREATE INDEX IF NOT EXISTS idx_session_cache_event ON mcp_session_cache(session_id, event_id);
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_session_cache_event_unique ON mcp_session_cache(session_id, event_id);
             "#,
         )
         .execute(&self.pool)
@@ -187,16 +424,12 @@ impl SqliteSessionManager {
     }
 
     /// Record a session in the database
-    async fn persist_session(&self, id: &SessionId) -> Result<(), SqliteSessionError> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
+    async fn persist_session(&self, id: &str) -> Result<(), SqliteSessionError> {
+        let now = now();
         sqlx::query(
             "INSERT OR REPLACE INTO mcp_sessions (id, created_at, last_seen_at) VALUES (?, ?, ?)",
         )
-        .bind(id.as_ref())
+        .bind(id)
         .bind(now)
         .bind(now)
         .execute(&self.pool)
@@ -207,15 +440,10 @@ impl SqliteSessionManager {
     }
 
     /// Update last seen timestamp
-    async fn touch_session(&self, id: &SessionId) -> Result<(), SqliteSessionError> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
+    async fn touch_session(&self, id: &str) -> Result<(), SqliteSessionError> {
         sqlx::query("UPDATE mcp_sessions SET last_seen_at = ? WHERE id = ?")
-            .bind(now)
-            .bind(id.as_ref())
+            .bind(now())
+            .bind(id)
             .execute(&self.pool)
             .await
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to touch session: {}", e)))?;
@@ -224,9 +452,9 @@ impl SqliteSessionManager {
     }
 
     /// Check if a session exists in the database
-    async fn session_exists_in_db(&self, id: &SessionId) -> Result<bool, SqliteSessionError> {
+    async fn session_exists_in_db(&self, id: &str) -> Result<bool, SqliteSessionError> {
         let row = sqlx::query("SELECT 1 FROM mcp_sessions WHERE id = ?")
-            .bind(id.as_ref())
+            .bind(id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to check session: {}", e)))?;
@@ -234,10 +462,11 @@ impl SqliteSessionManager {
         Ok(row.is_some())
     }
 
-    /// Remove a session from the database
-    async fn remove_session_from_db(&self, id: &SessionId) -> Result<(), SqliteSessionError> {
+    /// Remove a session and its journaled events from the database
+    async fn remove_session_from_db(&self, id: &str) -> Result<(), SqliteSessionError> {
+        self.forget_session_events(id).await?;
         sqlx::query("DELETE FROM mcp_sessions WHERE id = ?")
-            .bind(id.as_ref())
+            .bind(id)
             .execute(&self.pool)
             .await
             .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to remove session: {}", e)))?;
@@ -245,34 +474,129 @@ impl SqliteSessionManager {
         Ok(())
     }
 
-    /// Recreate a session worker for a known session ID (for reconnection after restart)
-    async fn recreate_session(
-        &self,
-        id: SessionId,
-    ) -> Result<WorkerTransport<LocalSessionWorker>, SqliteSessionError> {
-        let (handle, worker) = create_local_session(id.clone(), self.session_config.clone());
-        self.sessions.write().await.insert(id.clone(), handle);
-        self.touch_session(&id).await?;
-        Ok(WorkerTransport::spawn(worker))
+    /// Delete the journaled events of a session
+    async fn forget_session_events(&self, id: &str) -> Result<(), SqliteSessionError> {
+        sqlx::query("DELETE FROM mcp_session_cache WHERE session_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to remove events: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Wait until the events queued so far are written
+    async fn flush_journal(&self) {
+        if let Some(journal) = &self.journal {
+            let (flushed, done) = oneshot::channel();
+            if journal.send(JournalEntry::Flush(flushed)).is_ok() {
+                done.await.ok();
+            }
+        }
+    }
+
+    /// Journaled events of the stream of `last_event_id` sent after it
+    async fn replay_events(&self, id: &str, last_event_id: &str) -> Result<Vec<ServerSseMessage>, SqliteSessionError> {
+        let Some((stream, last_index)) = parse_event_id(last_event_id) else {
+            return Ok(Vec::new());
+        };
+        self.flush_journal().await;
+
+        let rows = sqlx::query("SELECT event_id, message FROM mcp_session_cache WHERE session_id = ? ORDER BY id")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SqliteSessionError::DatabaseError(format!("Failed to load events: {}", e)))?;
+
+        let mut events: Vec<(usize, ServerSseMessage)> = rows
+            .iter()
+            .filter_map(|row| {
+                let event_id: String = sqlx::Row::get(row, "event_id");
+                let (event_stream, index) = parse_event_id(&event_id)?;
+                if event_stream != stream || index <= last_index {
+                    return None;
+                }
+                let message: String = sqlx::Row::get(row, "message");
+                let message = serde_json::from_str(&message)
+                    .inspect_err(|e| tracing::warn!(session_id = id, event_id, "Skipping unreadable event: {}", e))
+                    .ok()?;
+                Some((index, ServerSseMessage { event_id: Some(event_id), message: Arc::new(message) }))
+            })
+            .collect();
+        events.sort_by_key(|(index, _)| *index);
+        Ok(events.into_iter().map(|(_, event)| event).collect())
     }
 }
 
+impl SessionStore for SqliteStore {
+    fn create<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move { Ok(self.persist_session(id).await?) })
+    }
+
+    fn touch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move { Ok(self.touch_session(id).await?) })
+    }
+
+    fn exists<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<bool, SessionStoreError>> {
+        Box::pin(async move { Ok(self.session_exists_in_db(id).await?) })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move { Ok(self.remove_session_from_db(id).await?) })
+    }
+
+    fn close<'a>(&'a self, live: &'a [String]) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move {
+            for id in live {
+                self.touch_session(id).await?;
+            }
+            self.flush_journal().await;
+            self.pool.close().await;
+            Ok(())
+        })
+    }
+
+    fn journals(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    fn record_event(&self, id: &str, event_id: &str, message: &Arc<ServerJsonRpcMessage>) {
+        if let Some(journal) = &self.journal {
+            let _ = journal.send(JournalEntry::Event {
+                session_id: id.to_string(),
+                event_id: event_id.to_string(),
+                message: message.clone(),
+            });
+        }
+    }
+
+    fn replay<'a>(
+        &'a self,
+        id: &'a str,
+        last_event_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ServerSseMessage>, SessionStoreError>> {
+        Box::pin(async move { Ok(self.replay_events(id, last_event_id).await?) })
+    }
+
+    fn forget_events<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        Box::pin(async move { Ok(self.forget_session_events(id).await?) })
+    }
+}
+
+/// Seconds since the Unix epoch
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
 impl SessionManager for SqliteSessionManager {
     type Error = SqliteSessionError;
     type Transport = WorkerTransport<LocalSessionWorker>;
 
     async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
-        let id = session_id();
-        let (handle, worker) = create_local_session(id.clone(), self.session_config.clone());
-
-        // Persist to database
-        self.persist_session(&id).await?;
-
-        // Store in memory
-        self.sessions.write().await.insert(id.clone(), handle);
-
-        tracing::info!(session_id = ?id, "Created new persistent MCP session");
-        Ok((id, WorkerTransport::spawn(worker)))
+        Ok(self.manager.create_session().await?)
     }
 
     async fn initialize_session(
@@ -280,54 +604,15 @@ impl SessionManager for SqliteSessionManager {
         id: &SessionId,
         message: ClientJsonRpcMessage,
     ) -> Result<ServerJsonRpcMessage, Self::Error> {
-        // Check if session exists in memory
-        let sessions = self.sessions.read().await;
-        if let Some(handle) = sessions.get(id) {
-            let response = handle.initialize(message).await?;
-            return Ok(response);
-        }
-        drop(sessions);
-
-        // Check if session exists in database (reconnection case)
-        if self.session_exists_in_db(id).await? {
-            tracing::info!(session_id = ?id, "Reconnecting to persisted MCP session");
-            // Note: For reconnection, the transport would need to be re-established
-            // This is a limitation - we can track the session but can't fully restore state
-            return Err(SqliteSessionError::SessionNotFound(id.clone()));
-        }
-
-        Err(SqliteSessionError::SessionNotFound(id.clone()))
+        Ok(self.manager.initialize_session(id, message).await?)
     }
 
     async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
-        // Only return true if the session worker is active in memory
-        // Workers can't be restored without handler connection (rmcp limitation)
-        if self.sessions.read().await.contains_key(id) {
-            return Ok(true);
-        }
-
-        // Session in DB but no active worker - remove stale entry and return false
-        // Client will get 401 and should reconnect with fresh session
-        if self.session_exists_in_db(id).await? {
-            tracing::info!(session_id = ?id, "Removing stale session from DB (no active worker)");
-            self.remove_session_from_db(id).await.ok();
-        }
-
-        Ok(false)
+        Ok(self.manager.has_session(id).await?)
     }
 
     async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
-        // Remove from memory
-        let mut sessions = self.sessions.write().await;
-        if let Some(handle) = sessions.remove(id) {
-            handle.close().await?;
-        }
-
-        // Remove from database
-        self.remove_session_from_db(id).await?;
-
-        tracing::info!(session_id = ?id, "Closed MCP session");
-        Ok(())
+        Ok(self.manager.close_session(id).await?)
     }
 
     async fn create_stream(
@@ -335,32 +620,14 @@ impl SessionManager for SqliteSessionManager {
         id: &SessionId,
         message: ClientJsonRpcMessage,
     ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
-        let sessions = self.sessions.read().await;
-        let handle = sessions
-            .get(id)
-            .ok_or(SqliteSessionError::SessionNotFound(id.clone()))?;
-
-        let receiver = handle.establish_request_wise_channel().await?;
-        handle
-            .push_message(message, receiver.http_request_id)
-            .await?;
-
-        self.touch_session(id).await.ok(); // Best effort
-        Ok(ReceiverStream::new(receiver.inner))
+        Ok(self.manager.create_stream(id, message).await?)
     }
 
     async fn create_standalone_stream(
         &self,
         id: &SessionId,
     ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
-        let sessions = self.sessions.read().await;
-        let handle = sessions
-            .get(id)
-            .ok_or(SqliteSessionError::SessionNotFound(id.clone()))?;
-
-        let receiver = handle.establish_common_channel().await?;
-        self.touch_session(id).await.ok(); // Best effort
-        Ok(ReceiverStream::new(receiver.inner))
+        Ok(self.manager.create_standalone_stream(id).await?)
     }
 
     async fn resume(
@@ -368,31 +635,7 @@ impl SessionManager for SqliteSessionManager {
         id: &SessionId,
         last_event_id: String,
     ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
-        // Check memory first
-        {
-            let sessions = self.sessions.read().await;
-            if let Some(handle) = sessions.get(id) {
-                let receiver = handle.resume(last_event_id.parse()?).await?;
-                self.touch_session(id).await.ok();
-                return Ok(ReceiverStream::new(receiver.inner));
-            }
-        }
-
-        // Check if this is a reconnection after restart
-        if self.session_exists_in_db(id).await? {
-            tracing::info!(session_id = ?id, last_event_id, "Session reconnection attempt - recreating worker");
-            // Recreate the session worker
-            let _transport = self.recreate_session(id.clone()).await?;
-
-            // Now try to get the handle and resume
-            let sessions = self.sessions.read().await;
-            if let Some(handle) = sessions.get(id) {
-                let receiver = handle.resume(last_event_id.parse()?).await?;
-                return Ok(ReceiverStream::new(receiver.inner));
-            }
-        }
-
-        Err(SqliteSessionError::SessionNotFound(id.clone()))
+        Ok(self.manager.resume(id, last_event_id).await?)
     }
 
     async fn accept_message(
@@ -400,13 +643,52 @@ impl SessionManager for SqliteSessionManager {
         id: &SessionId,
         message: ClientJsonRpcMessage,
     ) -> Result<(), Self::Error> {
-        let sessions = self.sessions.read().await;
-        let handle = sessions
-            .get(id)
-            .ok_or(SqliteSessionError::SessionNotFound(id.clone()))?;
-
-        handle.push_message(message, None).await?;
-        self.touch_session(id).await.ok(); // Best effort
-        Ok(())
+        Ok(self.manager.accept_message(id, message).await?)
     }
 }
+
+/// Write journaled events in the background, keeping the latest `keep` per session
+fn spawn_journal_writer(pool: SqlitePool, keep: usize) -> mpsc::UnboundedSender<JournalEntry> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    crate::task::spawn("plexus.mcp.sqlite_journal", async move {
+        while let Some(entry) = rx.recv().await {
+            let (session_id, event_id, message) = match entry {
+                JournalEntry::Event { session_id, event_id, message } => (session_id, event_id, message),
+                JournalEntry::Flush(done) => {
+                    let _ = done.send(());
+                    continue;
+                }
+            };
+            let message = match serde_json::to_string(&*message) {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!(session_id = ?session_id, event_id, "Failed to serialize event: {}", e);
+                    continue;
+                }
+            };
+            let written = sqlx::query(
+                "INSERT OR IGNORE INTO mcp_session_cache (session_id, event_id, message, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(session_id.as_str())
+            .bind(&event_id)
+            .bind(message)
+            .bind(now())
+            .execute(&pool)
+            .await;
+            let pruned = sqlx::query(
+                "DELETE FROM mcp_session_cache WHERE session_id = ? AND id NOT IN \
+                 (SELECT id FROM mcp_session_cache WHERE session_id = ? ORDER BY id DESC LIMIT ?)",
+            )
+            .bind(session_id.as_str())
+            .bind(session_id.as_str())
+            .bind(keep as i64)
+            .execute(&pool)
+            .await;
+            // Fails once the session is gone, e.g. for events sent as it closes
+            if let Err(e) = written.and(pruned) {
+                tracing::debug!(session_id = ?session_id, event_id, "Failed to journal event: {}", e);
+            }
+        }
+    });
+    tx
+}
@@ -0,0 +1,146 @@
+//! MCP over stdio
+//!
+//! The stdio transport normally exposes the activation's RPC methods as
+//! line-delimited JSON-RPC, which MCP clients can call but not discover. With
+//! `StdioConfig::with_mcp`, it runs the [`ActivationMcpBridge`] instead, the
+//! same MCP server the HTTP transport serves: `initialize`, `tools/list`,
+//! `tools/call`, progress and logging notifications, and resources, prompts
+//! and completions when they are configured.
+//!
+//! ```rust,ignore
+//! TransportServer::builder(activation, rpc_converter)
+//!     .with_stdio_config(StdioConfig::default().with_mcp())
+//!     .build().await?
+//!     .serve().await?;
+//! ```
+//!
+//! The bridge takes its settings (tool filter, naming, timeouts, providers,
+//! ...) from the MCP HTTP configuration when there is one, without serving
+//! HTTP. Messages are always newline-delimited, as MCP requires; the stdio
+//! settings for framing, notification queues, EOF grace and request timeouts
+//! apply to plain JSON-RPC only. Every caller is `CallerIdentity::Local`.
+
+use anyhow::Result;
+use plexus_core::plexus::Activation;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::{McpHttpConfig, StdioConfig};
+use crate::hot_swap::Served;
+use crate::mcp::bridge::ActivationMcpBridge;
+use crate::mcp::server::build_bridge;
+use crate::observer::ObservedConnection;
+use crate::stdio::{protocol_stdout, until_parent_exit};
+
+/// Serve `bridge` as an MCP server over stdin and stdout
+///
+/// Returns when stdin is closed, or with `config.exit_on_parent_death` when
+/// the parent process exits. `config.stdout_guard` protects stdout as for
+/// plain JSON-RPC.
+pub async fn serve_mcp_stdio<A: Activation>(bridge: ActivationMcpBridge<A>, config: StdioConfig) -> Result<()> {
+    serve_mcp_stdio_until(bridge, config, CancellationToken::new()).await
+}
+
+/// Serve `bridge` over stdin and stdout until `shutdown` is cancelled
+async fn serve_mcp_stdio_until<A: Activation>(
+    bridge: ActivationMcpBridge<A>,
+    config: StdioConfig,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    tracing::info!("Starting stdio transport (MCP)");
+
+    let stdout = protocol_stdout(&config)?;
+    let exit_on_parent_death = config.exit_on_parent_death;
+    until_parent_exit(
+        exit_on_parent_death,
+        serve_mcp_io_until(bridge, tokio::io::stdin(), stdout, config, shutdown),
+    )
+    .await
+}
+
+/// Serve `bridge` as an MCP server over an arbitrary reader/writer pair
+///
+/// The authorization policy, audit log, access log, capture, metrics and
+/// slow request threshold of `config`, when set, replace the bridge's own,
+/// as does its middleware.
+/// `config.observer` sees the reader and writer as one connection.
+///
+/// This function will block until `reader` reaches EOF.
+pub async fn serve_mcp_io<A, R, W>(bridge: ActivationMcpBridge<A>, reader: R, writer: W, config: StdioConfig) -> Result<()>
+where
+    A: Activation,
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    serve_mcp_io_until(bridge, reader, writer, config, CancellationToken::new()).await
+}
+
+/// Serve `bridge` over a reader/writer pair; cancelling `shutdown` closes the
+/// session, dropping the tool calls still running
+async fn serve_mcp_io_until<A, R, W>(
+    bridge: ActivationMcpBridge<A>,
+    reader: R,
+    writer: W,
+    config: StdioConfig,
+    shutdown: CancellationToken,
+) -> Result<()>
+where
+    A: Activation,
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let mut bridge = bridge.with_local_caller();
+    if let Some(policy) = config.authorization {
+        bridge = bridge.with_authorization_policy(policy);
+    }
+    if !config.middleware.is_empty() {
+        bridge = bridge.with_middleware_stack(config.middleware);
+    }
+    if let Some(auditor) = config.audit {
+        bridge = bridge.with_audit(auditor);
+    }
+    if let Some(log) = config.access_log {
+        bridge = bridge.with_access_log(log);
+    }
+    if let Some(capture) = config.capture {
+        bridge = bridge.with_capture(capture);
+    }
+    if let Some(threshold) = config.slow_request_threshold {
+        bridge = bridge.with_slow_request_threshold(threshold);
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(metrics) = config.metrics {
+        bridge = bridge.with_metrics(metrics);
+    }
+    let _observed = config
+        .observer
+        .map(|observer| ObservedConnection::new(observer, "stdio", None));
+
+    let running = match rmcp::service::serve_server_with_ct(bridge, (reader, writer), shutdown.child_token()).await {
+        Ok(running) => running,
+        // The client hung up or broke off the handshake; there is no one to serve
+        Err(e) => {
+            tracing::warn!("MCP stdio session ended before initializing: {}", e);
+            return Ok(());
+        }
+    };
+    let reason = running.waiting().await?;
+    tracing::debug!("MCP stdio session ended: {:?}", reason);
+    Ok(())
+}
+
+/// Serve MCP over stdio with the activation current at startup
+///
+/// The bridge is built from `mcp`, the MCP HTTP configuration or the
+/// defaults. The session lasts as long as the process, so activation swaps do
+/// not reach it. It ends when `shutdown` is cancelled.
+pub(crate) async fn serve_mcp_stdio_served<A: Activation>(
+    served: watch::Receiver<Served<A>>,
+    mcp: McpHttpConfig,
+    config: StdioConfig,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    let bridge = build_bridge(&served.borrow(), &mcp);
+    serve_mcp_stdio_until(bridge, config, shutdown).await
+}
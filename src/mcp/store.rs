@@ -0,0 +1,407 @@
+//! Pluggable MCP session stores
+//!
+//! A [`SessionStore`] keeps track of which MCP session IDs exist, the part of
+//! session management that differs between backends. Session workers always
+//! run in memory; [`StoreSessionManager`] pairs them with any store, so a
+//! backend only has to record, refresh, look up and remove IDs. The SQLite
+//! and Redis backends are stores too. A store may also journal stream events,
+//! as the SQLite one does, so clients resuming with `Last-Event-ID` get what
+//! they missed.
+//!
+//! Plug a store in with `SessionStorage::Custom`:
+//!
+//! ```rust,ignore
+//! let mut mcp_config = McpHttpConfig::new(8889);
+//! mcp_config.session_storage = SessionStorage::Custom(Arc::new(MyStore::connect().await?));
+//! ```
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use futures::{future::BoxFuture, Stream, StreamExt};
+use thiserror::Error;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::ReceiverStream;
+
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    transport::{
+        WorkerTransport,
+        common::server_side_http::{SessionId, ServerSseMessage, session_id},
+        streamable_http_server::session::{
+            SessionManager,
+            local::{
+                LocalSessionWorker, LocalSessionHandle, SessionConfig,
+                SessionError, create_local_session, EventId, EventIdParseError,
+            },
+        },
+    },
+};
+
+/// Backend recording which MCP sessions exist
+///
+/// Methods return boxed futures so stores can be shared as
+/// `Arc<dyn SessionStore>`. Errors are reported to the client as a failed
+/// request, except from [`touch`](SessionStore::touch) after a request was
+/// accepted, which is logged and ignored.
+pub trait SessionStore: Send + Sync + 'static {
+    /// Record a newly created session
+    fn create<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>>;
+
+    /// Mark a session as used just now
+    fn touch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>>;
+
+    /// Whether the store knows a session
+    fn exists<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<bool, SessionStoreError>>;
+
+    /// Forget a session, on close or when no worker is left for it
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>>;
+
+    /// Flush and release the store when the MCP server stops
+    ///
+    /// `live` holds the sessions that still had a worker. Does nothing by
+    /// default.
+    fn close<'a>(&'a self, live: &'a [String]) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        let _ = live;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Handle a session the store knows but this process has no worker for
+    ///
+    /// Removes it by default, so the client starts a new session. A store
+    /// shared between processes may leave it to whichever process runs it.
+    fn stale<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        self.remove(id)
+    }
+
+    /// Whether the store journals stream events, see [`record_event`](SessionStore::record_event)
+    ///
+    /// False by default.
+    fn journals(&self) -> bool {
+        false
+    }
+
+    /// Journal an event sent on one of a session's streams
+    ///
+    /// Called in the order events are sent, only when [`journals`](SessionStore::journals)
+    /// is true, and must not block: queue the write.
+    fn record_event(&self, id: &str, event_id: &str, message: &Arc<ServerJsonRpcMessage>) {
+        let _ = (id, event_id, message);
+    }
+
+    /// Journaled events of the stream of `last_event_id` sent after it, in order
+    ///
+    /// Event IDs are `<index>` on the standalone stream and
+    /// `<index>/<http request id>` on streams answering a request. Returns
+    /// nothing by default.
+    fn replay<'a>(
+        &'a self,
+        id: &'a str,
+        last_event_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ServerSseMessage>, SessionStoreError>> {
+        let _ = (id, last_event_id);
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    /// Forget the journaled events of a session whose worker is recreated
+    ///
+    /// The new worker numbers its events from zero again. Does nothing by
+    /// default.
+    fn forget_events<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+        let _ = id;
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// The stream (`None` for the standalone stream) and index of an event ID
+pub(crate) fn parse_event_id(event_id: &str) -> Option<(Option<u64>, usize)> {
+    match event_id.split_once('/') {
+        Some((index, request)) => Some((Some(request.parse().ok()?), index.parse().ok()?)),
+        None => Some((None, event_id.parse().ok()?)),
+    }
+}
+
+impl fmt::Debug for dyn SessionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SessionStore")
+    }
+}
+
+/// A [`SessionStore`] failure, carrying the backend's message
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct SessionStoreError(pub String);
+
+impl SessionStoreError {
+    pub fn new(message: impl fmt::Display) -> Self {
+        Self(message.to_string())
+    }
+}
+
+/// Error types for the store-backed session manager
+#[derive(Debug, Error)]
+pub enum StoreSessionError {
+    #[error("Session not found: {0}")]
+    SessionNotFound(SessionId),
+    #[error("Session error: {0}")]
+    SessionError(#[from] SessionError),
+    #[error("Invalid event id: {0}")]
+    InvalidEventId(#[from] EventIdParseError),
+    #[error("Session store error: {0}")]
+    StoreError(#[from] SessionStoreError),
+}
+
+/// Session manager running in-memory workers for sessions kept in a [`SessionStore`]
+///
+/// A session the store knows but this process has no worker for is handed to
+/// [`SessionStore::stale`] and reported missing, so the client starts a new
+/// session; resuming an SSE stream for it recreates the worker.
+pub struct StoreSessionManager {
+    store: Arc<dyn SessionStore>,
+    /// In-memory session handles (runtime state)
+    sessions: RwLock<HashMap<SessionId, LocalSessionHandle>>,
+    session_config: SessionConfig,
+}
+
+impl StoreSessionManager {
+    pub fn new(store: Arc<dyn SessionStore>) -> Self {
+        Self::with_session_config(store, SessionConfig::default())
+    }
+
+    /// Create a manager whose workers use `session_config`
+    pub fn with_session_config(store: Arc<dyn SessionStore>, session_config: SessionConfig) -> Self {
+        Self {
+            store,
+            sessions: RwLock::new(HashMap::new()),
+            session_config,
+        }
+    }
+
+    /// Close the store, passing it the sessions that are still live
+    ///
+    /// Called when the MCP server stops.
+    pub async fn close(&self) -> Result<(), StoreSessionError> {
+        let live = self.live().await;
+        self.store.close(&live).await?;
+
+        tracing::info!(count = live.len(), "Flushed MCP sessions");
+        Ok(())
+    }
+
+    /// The sessions that have a worker in this process
+    pub(crate) async fn live(&self) -> Vec<String> {
+        self.sessions.read().await.keys().map(|id| id.to_string()).collect()
+    }
+
+    /// Refresh a session in the store, logging failures
+    async fn touch_session(&self, id: &SessionId) {
+        if let Err(e) = self.store.touch(id).await {
+            tracing::warn!(session_id = ?id, "Failed to touch MCP session: {}", e);
+        }
+    }
+
+    /// Journal the events of a worker channel as they are sent
+    ///
+    /// The channel is drained until the worker closes it, even once the client
+    /// has gone, so events sent after a connection drops are journaled too.
+    fn journaled(&self, id: &SessionId, mut receiver: mpsc::Receiver<ServerSseMessage>) -> ReceiverStream<ServerSseMessage> {
+        if !self.store.journals() {
+            return ReceiverStream::new(receiver);
+        }
+        let (tx, rx) = mpsc::channel(self.session_config.channel_capacity);
+        let store = self.store.clone();
+        let session_id = id.clone();
+        crate::task::spawn("plexus.mcp.session_journal", async move {
+            let mut client = Some(tx);
+            while let Some(event) = receiver.recv().await {
+                if let Some(event_id) = &event.event_id {
+                    store.record_event(&session_id, event_id, &event.message);
+                }
+                if let Some(ref tx) = client {
+                    if tx.send(event).await.is_err() {
+                        client = None;
+                    }
+                }
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+
+    /// Recreate a session worker for a known session ID (for reconnection after restart)
+    async fn recreate_session(
+        &self,
+        id: SessionId,
+    ) -> Result<WorkerTransport<LocalSessionWorker>, StoreSessionError> {
+        let (handle, worker) = create_local_session(id.clone(), self.session_config.clone());
+        self.sessions.write().await.insert(id.clone(), handle);
+        self.store.touch(&id).await?;
+        Ok(WorkerTransport::spawn(worker))
+    }
+}
+
+impl fmt::Debug for StoreSessionManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreSessionManager").finish_non_exhaustive()
+    }
+}
+
+impl SessionManager for StoreSessionManager {
+    type Error = StoreSessionError;
+    type Transport = WorkerTransport<LocalSessionWorker>;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        let id = session_id();
+        let (handle, worker) = create_local_session(id.clone(), self.session_config.clone());
+
+        self.store.create(&id).await?;
+        self.sessions.write().await.insert(id.clone(), handle);
+
+        tracing::info!(session_id = ?id, "Created new stored MCP session");
+        Ok((id, WorkerTransport::spawn(worker)))
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        let sessions = self.sessions.read().await;
+        let handle = sessions
+            .get(id)
+            .ok_or(StoreSessionError::SessionNotFound(id.clone()))?;
+
+        let response = handle.initialize(message).await?;
+        Ok(response)
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        if self.sessions.read().await.contains_key(id) {
+            return Ok(true);
+        }
+
+        // Known to the store but no worker here - the client starts over
+        // with a fresh session
+        if self.store.exists(id).await? {
+            tracing::info!(session_id = ?id, "MCP session has no active worker");
+            if let Err(e) = self.store.stale(id).await {
+                tracing::warn!(session_id = ?id, "Failed to forget stale MCP session: {}", e);
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        let mut sessions = self.sessions.write().await;
+        if let Some(handle) = sessions.remove(id) {
+            handle.close().await?;
+        }
+
+        self.store.remove(id).await?;
+
+        tracing::info!(session_id = ?id, "Closed MCP session");
+        Ok(())
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        let sessions = self.sessions.read().await;
+        let handle = sessions
+            .get(id)
+            .ok_or(StoreSessionError::SessionNotFound(id.clone()))?;
+
+        let receiver = handle.establish_request_wise_channel().await?;
+        handle
+            .push_message(message, receiver.http_request_id)
+            .await?;
+
+        self.touch_session(id).await;
+        Ok(self.journaled(id, receiver.inner))
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        let sessions = self.sessions.read().await;
+        let handle = sessions
+            .get(id)
+            .ok_or(StoreSessionError::SessionNotFound(id.clone()))?;
+
+        let receiver = handle.establish_common_channel().await?;
+        self.touch_session(id).await;
+        Ok(self.journaled(id, receiver.inner))
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + 'static, Self::Error> {
+        let last: EventId = last_event_id.parse()?;
+        let replayed = if self.store.journals() {
+            self.store.replay(id, &last_event_id).await?
+        } else {
+            Vec::new()
+        };
+
+        // Check if this is a reconnection after restart
+        let recreated = !self.sessions.read().await.contains_key(id);
+        if recreated {
+            if !self.store.exists(id).await? {
+                return Err(StoreSessionError::SessionNotFound(id.clone()));
+            }
+            tracing::info!(session_id = ?id, last_event_id, "Session reconnection attempt - recreating worker");
+            self.store.forget_events(id).await?;
+            let _transport = self.recreate_session(id.clone()).await?;
+        }
+
+        let live = {
+            let sessions = self.sessions.read().await;
+            let handle = sessions
+                .get(id)
+                .ok_or(StoreSessionError::SessionNotFound(id.clone()))?;
+            match handle.resume(last).await {
+                Ok(receiver) => Some(self.journaled(id, receiver.inner)),
+                // The request's stream has ended, but its events were journaled
+                Err(e) if !replayed.is_empty() => {
+                    tracing::debug!(session_id = ?id, last_event_id, "Replaying journaled events only: {}", e);
+                    None
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.touch_session(id).await;
+
+        // The worker re-sends the events it still buffers; skip those replayed
+        let replayed_up_to = match replayed.last() {
+            Some(event) if !recreated => event.event_id.as_deref().and_then(parse_event_id).map(|(_, index)| index),
+            _ => None,
+        };
+        let live = futures::stream::iter(live).flatten().filter(move |event| {
+            let index = event.event_id.as_deref().and_then(parse_event_id).map(|(_, index)| index);
+            futures::future::ready(match (replayed_up_to, index) {
+                (Some(up_to), Some(index)) => index > up_to,
+                _ => true,
+            })
+        });
+        Ok(futures::stream::iter(replayed).chain(live))
+    }
+
+    async fn accept_message(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<(), Self::Error> {
+        let sessions = self.sessions.read().await;
+        let handle = sessions
+            .get(id)
+            .ok_or(StoreSessionError::SessionNotFound(id.clone()))?;
+
+        handle.push_message(message, None).await?;
+        self.touch_session(id).await;
+        Ok(())
+    }
+}
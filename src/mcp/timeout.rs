@@ -0,0 +1,70 @@
+//! Time limits for MCP tool calls and RPC method calls
+//!
+//! A plugin method that never finishes would otherwise hold its call, and the
+//! client waiting on it, forever. With [`ToolTimeouts`], calls running longer
+//! than their limit are dropped, which stops the activation's future or
+//! stream, and the client gets an error with code [`TIMEOUT_ERROR_CODE`]
+//! (HTTP 504 on REST).
+//!
+//! Limits are given per tool, by `namespace.method` name or glob (`reports.*`),
+//! with a default for all other tools. When several patterns match, the
+//! longest one wins. The same limits apply to calls on every transport when
+//! set with
+//! [`TransportServerBuilder::with_request_timeouts`](crate::TransportServerBuilder::with_request_timeouts),
+//! so plugins with very different latencies each get a fitting limit.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mcp::filter::glob_match;
+
+/// JSON-RPC error code returned for calls that exceeded their time limit
+pub const TIMEOUT_ERROR_CODE: i32 = -32001;
+
+/// Time limits of MCP tool calls, or of method calls on any transport
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolTimeouts {
+    /// Limit of tools without one of their own; `None` lets them run
+    #[serde(with = "crate::config::serde_secs::option")]
+    default: Option<Duration>,
+    /// Limits by tool name or glob
+    #[serde(with = "crate::config::serde_secs::map")]
+    tools: BTreeMap<String, Duration>,
+}
+
+impl ToolTimeouts {
+    /// No time limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit every tool without a limit of its own to `timeout`
+    pub fn with_default(mut self, timeout: Duration) -> Self {
+        self.default = Some(timeout);
+        self
+    }
+
+    /// Limit the tools matching `pattern` to `timeout`
+    pub fn with_tool(mut self, pattern: impl Into<String>, timeout: Duration) -> Self {
+        self.tools.insert(pattern.into(), timeout);
+        self
+    }
+
+    /// Whether no call has a time limit
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.tools.is_empty()
+    }
+
+    /// The time limit of the tool `name`, if it has one
+    pub fn timeout_for(&self, name: &str) -> Option<Duration> {
+        self.tools
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, name))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, timeout)| *timeout)
+            .or(self.default)
+    }
+}
@@ -0,0 +1,89 @@
+//! `notifications/tools/list_changed` for hubs whose plugins change at runtime
+//!
+//! MCP clients cache `tools/list` until the server says it changed. A hub that
+//! loads or unloads plugins keeps a [`ToolListNotifier`], passes it to
+//! `McpHttpConfig::with_tool_list_notifier`, and calls it after every change:
+//!
+//! ```rust,ignore
+//! let tool_list = ToolListNotifier::new();
+//! let mcp_config = McpHttpConfig::new(8889).with_tool_list_notifier(tool_list.clone());
+//!
+//! // Later, after the hub has loaded a plugin:
+//! tool_list.set_flat_schemas(hub.list_plugin_schemas());
+//! ```
+//!
+//! Every initialized session is then told to list the tools again. Hubs
+//! listing their tools from `plugin_schema()` only need
+//! [`notify`](ToolListNotifier::notify); hubs that were given flat schemas
+//! publish the new ones with [`set_flat_schemas`](ToolListNotifier::set_flat_schemas),
+//! which replace the configured ones from then on.
+
+use std::fmt;
+use std::sync::{Arc, Weak};
+
+use plexus_core::plexus::PluginSchema;
+use rmcp::service::{Peer, RoleServer};
+use tokio::sync::watch;
+
+use crate::mcp::logging::SessionLogging;
+
+/// Tells MCP sessions that the tool list changed
+///
+/// Cloning is cheap; every clone notifies the same sessions.
+#[derive(Clone)]
+pub struct ToolListNotifier(Arc<watch::Sender<Option<Arc<Vec<PluginSchema>>>>>);
+
+impl Default for ToolListNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ToolListNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolListNotifier")
+            .field("sessions", &self.0.receiver_count())
+            .finish()
+    }
+}
+
+impl ToolListNotifier {
+    pub fn new() -> Self {
+        Self(Arc::new(watch::channel(None).0))
+    }
+
+    /// The activation's tools changed; sessions list them again
+    pub fn notify(&self) {
+        self.0.send_modify(|_| {});
+    }
+
+    /// Serve `schemas` as the flat schema list from now on, and notify sessions
+    pub fn set_flat_schemas(&self, schemas: Vec<PluginSchema>) {
+        self.0.send_replace(Some(Arc::new(schemas)));
+    }
+
+    /// The flat schemas last published, if any
+    pub(crate) fn flat_schemas(&self) -> Option<Arc<Vec<PluginSchema>>> {
+        self.0.borrow().clone()
+    }
+
+    /// Notify `peer` of changes until the session ends
+    ///
+    /// The session's logging state lives exactly as long as the session, so
+    /// the task stops at the first change after it is gone.
+    pub(crate) fn watch(&self, peer: Peer<RoleServer>, session: &Arc<SessionLogging>) {
+        let mut changes = self.0.subscribe();
+        let session: Weak<SessionLogging> = Arc::downgrade(session);
+        crate::task::spawn("plexus.mcp.tool_list_changed", async move {
+            while changes.changed().await.is_ok() {
+                if session.strong_count() == 0 {
+                    break;
+                }
+                if let Err(e) = peer.notify_tool_list_changed().await {
+                    tracing::debug!("Failed to send MCP tool list change: {}", e);
+                    break;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,362 @@
+//! MCP session tracking: time-to-live and administration
+//!
+//! [`TrackedSessions`] wraps the session manager behind the MCP endpoint and
+//! records when each session was created and last used: a request, or an SSE
+//! stream closing. A session with a stream still open is in use. With a TTL, a
+//! background task closes sessions idle for longer than the TTL through the
+//! inner manager, which removes them from its store as a client `DELETE`
+//! would. With a [`SessionAdmin`](super::admin::SessionAdmin), operators can
+//! list the tracked sessions and terminate them the same way, and with a ping
+//! interval, sessions whose clients stop answering pings are terminated too.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::{future::BoxFuture, Stream};
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    transport::{
+        common::server_side_http::{ServerSseMessage, SessionId},
+        streamable_http_server::session::SessionManager,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+use super::admin::{AdminSessions, McpSessionInfo};
+use crate::config::McpHttpConfig;
+
+/// How often expired sessions are collected for a TTL
+///
+/// A quarter of the TTL, between one second and one minute.
+pub(crate) fn gc_interval(ttl: Duration) -> Duration {
+    (ttl / 4).clamp(Duration::from_secs(1), Duration::from_secs(60))
+}
+
+/// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// What is known about a session
+struct Activity {
+    created_at_ms: u64,
+    last_seen: Instant,
+    /// SSE streams currently open
+    streams: usize,
+    /// `clientInfo` and `protocolVersion` from the initialize request
+    client_name: Option<String>,
+    client_version: Option<String>,
+    protocol_version: Option<String>,
+}
+
+impl Activity {
+    fn new() -> Self {
+        Self {
+            created_at_ms: now_ms(),
+            last_seen: Instant::now(),
+            streams: 0,
+            client_name: None,
+            client_version: None,
+            protocol_version: None,
+        }
+    }
+
+    fn info(&self, id: &SessionId) -> McpSessionInfo {
+        McpSessionInfo {
+            id: id.to_string(),
+            created_at_ms: self.created_at_ms,
+            last_seen_ms: now_ms().saturating_sub(self.last_seen.elapsed().as_millis() as u64),
+            open_streams: self.streams,
+            client_name: self.client_name.clone(),
+            client_version: self.client_version.clone(),
+            protocol_version: self.protocol_version.clone(),
+        }
+    }
+}
+
+type ActivityMap = Arc<Mutex<HashMap<SessionId, Activity>>>;
+
+/// A session manager recording session activity
+///
+/// Without a TTL or an admin, calls pass straight through and nothing is
+/// tracked.
+pub(crate) struct TrackedSessions<M> {
+    inner: Arc<M>,
+    ttl: Option<Duration>,
+    enabled: bool,
+    activity: ActivityMap,
+}
+
+impl<M> TrackedSessions<M> {
+    pub(crate) fn new(inner: Arc<M>, config: &McpHttpConfig) -> Self {
+        Self {
+            inner,
+            ttl: config.session_ttl,
+            enabled: config.session_ttl.is_some() || config.session_admin.is_some() || config.ping_interval.is_some(),
+            activity: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Activity>> {
+        self.activity.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Mark a session as used just now
+    fn touch(&self, id: &SessionId) {
+        if !self.enabled {
+            return;
+        }
+        self.lock()
+            .entry(id.clone())
+            .and_modify(|a| a.last_seen = Instant::now())
+            .or_insert_with(Activity::new);
+    }
+
+    /// Record the client named in an initialize request
+    fn initialized(&self, id: &SessionId, message: &ClientJsonRpcMessage) {
+        if !self.enabled {
+            return;
+        }
+        let Ok(message) = serde_json::to_value(message) else {
+            return;
+        };
+        let field = |pointer: &str| message.pointer(pointer).and_then(|v| v.as_str()).map(str::to_string);
+        let mut activity = self.lock();
+        let a = activity.entry(id.clone()).or_insert_with(Activity::new);
+        a.last_seen = Instant::now();
+        a.client_name = field("/params/clientInfo/name");
+        a.client_version = field("/params/clientInfo/version");
+        a.protocol_version = field("/params/protocolVersion");
+    }
+
+    /// Stop tracking a session
+    fn forget(&self, id: &SessionId) -> bool {
+        self.lock().remove(id).is_some()
+    }
+
+    /// Count `stream` as keeping the session in use until it is dropped
+    fn track<S>(&self, id: &SessionId, stream: S) -> TrackedStream<S> {
+        let guard = self.enabled.then(|| {
+            self.lock()
+                .entry(id.clone())
+                .or_insert_with(Activity::new)
+                .streams += 1;
+            StreamGuard {
+                activity: self.activity.clone(),
+                id: id.clone(),
+            }
+        });
+        TrackedStream {
+            inner: Box::pin(stream),
+            _guard: guard,
+        }
+    }
+
+    /// Sessions idle for longer than the TTL
+    fn expired(&self) -> Vec<SessionId> {
+        let Some(ttl) = self.ttl else {
+            return Vec::new();
+        };
+        self.lock()
+            .iter()
+            .filter(|(_, a)| a.streams == 0 && a.last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+impl<M: SessionManager> TrackedSessions<M> {
+    /// Start closing expired sessions until `shutdown` is cancelled
+    ///
+    /// Does nothing without a TTL. Closed sessions are counted in
+    /// `config.metrics`, if set.
+    pub(crate) fn spawn_gc(self: &Arc<Self>, config: &McpHttpConfig, shutdown: CancellationToken) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        #[cfg(feature = "metrics")]
+        let metrics = config.metrics.clone();
+        #[cfg(not(feature = "metrics"))]
+        let _ = config;
+
+        let sessions = Arc::downgrade(self);
+        spawn_sweeper("plexus.mcp.session_gc", gc_interval(ttl), shutdown, move || {
+            let sessions = sessions.clone();
+            #[cfg(feature = "metrics")]
+            let metrics = metrics.clone();
+            async move {
+                let Some(sessions) = sessions.upgrade() else {
+                    return;
+                };
+                #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+                let closed = sessions.collect().await;
+                #[cfg(feature = "metrics")]
+                if let Some(metrics) = metrics {
+                    metrics.record_expired_sessions(closed);
+                }
+            }
+        });
+    }
+
+    /// Close every expired session, returning how many were closed
+    async fn collect(&self) -> usize {
+        let expired = self.expired();
+        for id in &expired {
+            self.forget(id);
+            if let Err(e) = self.inner.close_session(id).await {
+                tracing::warn!(session_id = ?id, "Failed to close expired MCP session: {}", e);
+            }
+        }
+        if !expired.is_empty() {
+            tracing::info!(count = expired.len(), "Closed expired MCP sessions");
+        }
+        expired.len()
+    }
+}
+
+impl<M: SessionManager> AdminSessions for TrackedSessions<M> {
+    fn list(&self) -> Vec<McpSessionInfo> {
+        self.lock().iter().map(|(id, a)| a.info(id)).collect()
+    }
+
+    fn get(&self, id: &str) -> Option<McpSessionInfo> {
+        let id: SessionId = id.into();
+        self.lock().get(&id).map(|a| a.info(&id))
+    }
+
+    fn terminate<'a>(&'a self, id: &'a str) -> BoxFuture<'a, anyhow::Result<bool>> {
+        Box::pin(async move {
+            let id: SessionId = id.into();
+            if !self.forget(&id) {
+                return Ok(false);
+            }
+            self.inner
+                .close_session(&id)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to close MCP session {}: {}", id, e))?;
+            tracing::info!(session_id = ?id, "Terminated MCP session");
+            Ok(true)
+        })
+    }
+}
+
+/// Run `sweep` every `interval` until `shutdown` is cancelled
+pub(crate) fn spawn_sweeper<F, Fut>(name: &str, interval: Duration, shutdown: CancellationToken, sweep: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    crate::task::spawn(name, async move {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately
+        ticks.tick().await;
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticks.tick() => sweep().await,
+            }
+        }
+    });
+}
+
+impl<M: SessionManager> SessionManager for TrackedSessions<M> {
+    type Error = M::Error;
+    type Transport = M::Transport;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        let (id, transport) = self.inner.create_session().await?;
+        self.touch(&id);
+        Ok((id, transport))
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        self.initialized(id, &message);
+        self.inner.initialize_session(id, message).await
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        let exists = self.inner.has_session(id).await?;
+        if !exists {
+            self.forget(id);
+        }
+        Ok(exists)
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        self.forget(id);
+        self.inner.close_session(id).await
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let stream = self.inner.create_stream(id, message).await?;
+        Ok(self.track(id, stream))
+    }
+
+    async fn accept_message(&self, id: &SessionId, message: ClientJsonRpcMessage) -> Result<(), Self::Error> {
+        self.touch(id);
+        self.inner.accept_message(id, message).await
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let stream = self.inner.create_standalone_stream(id).await?;
+        Ok(self.track(id, stream))
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let stream = self.inner.resume(id, last_event_id).await?;
+        Ok(self.track(id, stream))
+    }
+}
+
+/// An SSE stream keeping its session in use while open
+pub(crate) struct TrackedStream<S> {
+    inner: Pin<Box<S>>,
+    _guard: Option<StreamGuard>,
+}
+
+impl<S: Stream> Stream for TrackedStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Marks the session used when its stream closes
+struct StreamGuard {
+    activity: ActivityMap,
+    id: SessionId,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(a) = activity.get_mut(&self.id) {
+            a.streams = a.streams.saturating_sub(1);
+            a.last_seen = Instant::now();
+        }
+    }
+}
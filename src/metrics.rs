@@ -0,0 +1,396 @@
+//! Prometheus metrics for the transports (feature `metrics`)
+//!
+//! A [`TransportMetrics`] holds the collectors every transport records into:
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `plexus_requests_total` | counter | `transport`, `method`, `status` |
+//! | `plexus_request_duration_seconds` | histogram | `transport`, `method` |
+//! | `plexus_active_connections` | gauge | `transport` |
+//! | `plexus_active_mcp_sessions` | gauge | |
+//! | `plexus_expired_mcp_sessions_total` | counter | |
+//! | `plexus_notification_queue_depth` | gauge | `transport` |
+//! | `plexus_slow_requests_total` | counter | `transport`, `method` |
+//!
+//! `status` is `ok`, `denied` or `error`. Calls to methods that do not exist
+//! are recorded as `unknown`, and once [`TransportMetrics::max_methods`]
+//! distinct methods have been seen further ones are recorded as `other`, so
+//! clients cannot grow the label set without bound. Metrics of transports
+//! the crate is built without are not registered.
+//!
+//! Configure [`TransportServerBuilder::with_metrics_endpoint`] to serve the
+//! registry in the Prometheus text format, or mount [`metrics_router`] in an
+//! existing application.
+//!
+//! [`TransportServerBuilder::with_metrics_endpoint`]: crate::TransportServerBuilder::with_metrics_endpoint
+
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::sync::Mutex;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::time::Duration;
+
+#[cfg(feature = "mcp-http")]
+use prometheus::IntCounter;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http"))]
+use prometheus::IntGauge;
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+use prometheus::IntGaugeVec;
+use prometheus::{Encoder, Registry, TextEncoder};
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts};
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use crate::audit::AuditStatus;
+
+/// Default path of the metrics endpoint
+pub const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+/// Default number of distinct `method` label values
+pub const DEFAULT_MAX_METHODS: usize = 1000;
+
+/// JSON-RPC "method not found"
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+const METHOD_NOT_FOUND_CODE: i32 = -32601;
+
+/// Request, connection, session and queue metrics shared by the transports
+pub struct TransportMetrics {
+    registry: Registry,
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    requests: IntCounterVec,
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    duration: HistogramVec,
+    #[cfg(feature = "websocket")]
+    connections: IntGaugeVec,
+    #[cfg(feature = "mcp-http")]
+    mcp_sessions: IntGauge,
+    #[cfg(feature = "mcp-http")]
+    expired_mcp_sessions: IntCounter,
+    #[cfg(feature = "stdio")]
+    queue_depth: IntGaugeVec,
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    slow_requests: IntCounterVec,
+    max_methods: usize,
+    /// Method label values handed out so far
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    methods: Mutex<HashSet<String>>,
+}
+
+impl std::fmt::Debug for TransportMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransportMetrics")
+            .field("max_methods", &self.max_methods)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TransportMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportMetrics {
+    /// Metrics in a registry of their own
+    pub fn new() -> Self {
+        Self::with_registry(Registry::new()).expect("a fresh registry has no conflicting metrics")
+    }
+
+    /// Metrics registered in `registry`, e.g. the application's own
+    ///
+    /// Fails if the registry already holds metrics with the same names.
+    pub fn with_registry(registry: Registry) -> prometheus::Result<Self> {
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        let requests = IntCounterVec::new(
+            Opts::new("plexus_requests_total", "Calls handled, by transport, method and outcome"),
+            &["transport", "method", "status"],
+        )?;
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        let duration = HistogramVec::new(
+            HistogramOpts::new("plexus_request_duration_seconds", "Time taken to handle a call"),
+            &["transport", "method"],
+        )?;
+        #[cfg(feature = "websocket")]
+        let connections = IntGaugeVec::new(
+            Opts::new("plexus_active_connections", "Open client connections"),
+            &["transport"],
+        )?;
+        #[cfg(feature = "mcp-http")]
+        let mcp_sessions = IntGauge::new("plexus_active_mcp_sessions", "Open MCP sessions")?;
+        #[cfg(feature = "mcp-http")]
+        let expired_mcp_sessions = IntCounter::new(
+            "plexus_expired_mcp_sessions_total",
+            "MCP sessions closed or deleted after exceeding the session TTL",
+        )?;
+        #[cfg(feature = "stdio")]
+        let queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "plexus_notification_queue_depth",
+                "Subscription notifications waiting to be written",
+            ),
+            &["transport"],
+        )?;
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        let slow_requests = IntCounterVec::new(
+            Opts::new(
+                "plexus_slow_requests_total",
+                "Calls still running after the transport's slow-request threshold",
+            ),
+            &["transport", "method"],
+        )?;
+
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        registry.register(Box::new(requests.clone()))?;
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        registry.register(Box::new(duration.clone()))?;
+        #[cfg(feature = "websocket")]
+        registry.register(Box::new(connections.clone()))?;
+        #[cfg(feature = "mcp-http")]
+        registry.register(Box::new(mcp_sessions.clone()))?;
+        #[cfg(feature = "mcp-http")]
+        registry.register(Box::new(expired_mcp_sessions.clone()))?;
+        #[cfg(feature = "stdio")]
+        registry.register(Box::new(queue_depth.clone()))?;
+        #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+        registry.register(Box::new(slow_requests.clone()))?;
+
+        Ok(Self {
+            registry,
+            #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+            requests,
+            #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+            duration,
+            #[cfg(feature = "websocket")]
+            connections,
+            #[cfg(feature = "mcp-http")]
+            mcp_sessions,
+            #[cfg(feature = "mcp-http")]
+            expired_mcp_sessions,
+            #[cfg(feature = "stdio")]
+            queue_depth,
+            #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+            slow_requests,
+            max_methods: DEFAULT_MAX_METHODS,
+            #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+            methods: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Record at most `max` distinct methods; further ones are labelled `other`
+    pub fn with_max_methods(mut self, max: usize) -> Self {
+        self.max_methods = max;
+        self
+    }
+
+    /// The maximum number of distinct `method` label values
+    pub fn max_methods(&self) -> usize {
+        self.max_methods
+    }
+
+    /// The registry holding the metrics
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// The registry's metrics in the Prometheus text format
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buf) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buf).unwrap_or_default()
+    }
+
+    /// Record a handled call
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn record_call(&self, transport: &str, method: &str, status: &AuditStatus, latency: Duration) {
+        let (method, status) = match status {
+            AuditStatus::Ok => (self.method_label(method), "ok"),
+            AuditStatus::Denied => (self.method_label(method), "denied"),
+            AuditStatus::Error { code } if *code == METHOD_NOT_FOUND_CODE => ("unknown".to_string(), "error"),
+            AuditStatus::Error { .. } => (self.method_label(method), "error"),
+        };
+        self.requests
+            .with_label_values(&[transport, &method, status])
+            .inc();
+        self.duration
+            .with_label_values(&[transport, &method])
+            .observe(latency.as_secs_f64());
+    }
+
+    /// Count a call that exceeded the slow-request threshold
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn record_slow(&self, transport: &str, method: &str) {
+        let method = self.method_label(method);
+        self.slow_requests.with_label_values(&[transport, &method]).inc();
+    }
+
+    /// Count a connection as open until the guard is dropped
+    #[cfg(feature = "websocket")]
+    pub(crate) fn connection(&self, transport: &str) -> GaugeGuard {
+        GaugeGuard::new(self.connections.with_label_values(&[transport]))
+    }
+
+    /// Count an MCP session as open until the guard is dropped
+    #[cfg(feature = "mcp-http")]
+    pub(crate) fn mcp_session(&self) -> GaugeGuard {
+        GaugeGuard::new(self.mcp_sessions.clone())
+    }
+
+    /// Count MCP sessions removed by session garbage collection
+    #[cfg(feature = "mcp-http")]
+    pub(crate) fn record_expired_sessions(&self, count: usize) {
+        self.expired_mcp_sessions.inc_by(count as u64);
+    }
+
+    /// Gauge of notifications queued on `transport`
+    #[cfg(feature = "stdio")]
+    pub(crate) fn queue_depth(&self, transport: &str) -> IntGauge {
+        self.queue_depth.with_label_values(&[transport])
+    }
+
+    /// `method` if it is already a label value or there is room for it, else `other`
+    #[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+    fn method_label(&self, method: &str) -> String {
+        let mut methods = self.methods.lock().unwrap_or_else(|e| e.into_inner());
+        if methods.contains(method) {
+            return method.to_string();
+        }
+        if methods.len() >= self.max_methods {
+            return "other".to_string();
+        }
+        methods.insert(method.to_string());
+        method.to_string()
+    }
+}
+
+/// Increments a gauge while alive
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+pub(crate) struct GaugeGuard(IntGauge);
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+impl GaugeGuard {
+    fn new(gauge: IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Tracks how much of a notification queue is filled
+///
+/// Several queues add up in one gauge, so each reports the change since its
+/// last observation; whatever is left is removed when the tracker is dropped.
+#[cfg(feature = "stdio")]
+pub(crate) struct QueueDepth {
+    gauge: IntGauge,
+    last: i64,
+}
+
+#[cfg(feature = "stdio")]
+impl QueueDepth {
+    pub(crate) fn new(gauge: IntGauge) -> Self {
+        Self { gauge, last: 0 }
+    }
+
+    /// Report the queue's current length
+    pub(crate) fn observe(&mut self, len: usize) {
+        let len = len as i64;
+        self.gauge.add(len - self.last);
+        self.last = len;
+    }
+}
+
+#[cfg(feature = "stdio")]
+impl Drop for QueueDepth {
+    fn drop(&mut self) {
+        self.gauge.sub(self.last);
+    }
+}
+
+/// Where the metrics endpoint listens
+///
+/// Missing fields deserialize to the defaults of `MetricsConfig::new(0)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default = "MetricsConfig::unbound")]
+pub struct MetricsConfig {
+    pub addr: SocketAddr,
+    /// Path the metrics are served at (default `/metrics`)
+    pub path: String,
+}
+
+impl MetricsConfig {
+    pub fn new(port: u16) -> Self {
+        Self {
+            addr: format!("127.0.0.1:{}", port)
+                .parse()
+                .expect("Valid socket address"),
+            path: DEFAULT_METRICS_PATH.to_string(),
+        }
+    }
+
+    /// Configuration listening on `addr`, e.g. `0.0.0.0:9100`
+    pub fn from_addr(addr: SocketAddr) -> Self {
+        Self::new(addr.port()).with_addr(addr)
+    }
+
+    fn unbound() -> Self {
+        Self::new(0)
+    }
+
+    /// Listen on `addr` instead of `127.0.0.1:<port>`
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Serve the metrics at `path` instead of `/metrics`
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+}
+
+/// A router answering `GET path` with `metrics` in the Prometheus text format
+///
+/// The endpoint is unauthenticated, as scrapers expect; keep it on a private
+/// address or put the application's own authentication in front of it.
+pub fn metrics_router(metrics: Arc<TransportMetrics>, path: &str) -> axum::Router {
+    axum::Router::new().route(
+        path,
+        axum::routing::get(move || async move {
+            (
+                [(http::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+                metrics.encode(),
+            )
+        }),
+    )
+}
+
+/// Serve the metrics endpoint on a bound listener until `shutdown` is cancelled
+pub(crate) fn serve_metrics(
+    metrics: Arc<TransportMetrics>,
+    config: &MetricsConfig,
+    listener: tokio::net::TcpListener,
+    shutdown: tokio_util::sync::CancellationToken,
+) -> crate::server::TransportTask {
+    let router = metrics_router(metrics, &config.path);
+    if let Ok(addr) = listener.local_addr() {
+        tracing::info!("Serving metrics at http://{}{}", addr, config.path);
+    }
+    crate::task::spawn("plexus.metrics.server", async move {
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async move { shutdown.cancelled().await })
+            .await
+    })
+}
@@ -0,0 +1,181 @@
+//! Middleware run around every call, whatever transport it arrives on
+//!
+//! A [`TransportMiddleware`] registered with
+//! [`TransportServerBuilder::with_middleware`](crate::TransportServerBuilder::with_middleware)
+//! sees each call on stdio, WebSocket, MCP HTTP and REST HTTP as a
+//! [`CallContext`]: the transport, the method (or tool) name, the params, the
+//! caller and its address. Cross-cutting concerns such as quota checks or
+//! request enrichment are written once instead of per transport.
+//!
+//! `before_call` runs after the transport's authorization policy and rate
+//! limit let the call through, and may reject it with a [`CallRejection`] or
+//! rewrite its params. `after_call` runs once the call is answered, with its
+//! [`CallOutcome`]. Several middleware run in registration order before the
+//! call and in reverse order after it.
+//!
+//! Unlike [`RpcMiddleware`](crate::RpcMiddleware), which wraps jsonrpsee's
+//! service on the WebSocket transport only, transport middleware works on
+//! plain JSON values and runs on every transport.
+
+use std::net::SocketAddr;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use crate::audit::AuditStatus;
+use crate::auth::CallerIdentity;
+
+/// Hooks run before and after every call on every transport
+///
+/// Both hooks have defaults, so middleware implements only the ones it needs.
+/// `after_call` is called inline on the transport's task and must not block.
+pub trait TransportMiddleware: Send + Sync {
+    /// Inspect a call before it is dispatched
+    ///
+    /// Return an error to answer the call with it instead of dispatching it.
+    /// Changes to `call.params` are what the method receives, and values put
+    /// in `call.extensions` are added to the call's extensions on WebSocket
+    /// and stdio. MCP and REST calls go to the activation, which has no
+    /// extensions; there they only reach `after_call`.
+    fn before_call<'a>(&'a self, _call: &'a mut CallContext) -> BoxFuture<'a, Result<(), CallRejection>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// A call this middleware let through was answered
+    ///
+    /// Also called when a middleware registered later rejected the call, with
+    /// the rejection as the outcome.
+    fn after_call(&self, _call: &CallContext, _outcome: &CallOutcome) {}
+}
+
+impl std::fmt::Debug for dyn TransportMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TransportMiddleware")
+    }
+}
+
+/// A call as middleware sees it
+#[derive(Debug)]
+pub struct CallContext {
+    /// Transport the call arrived on (`websocket`, `stdio`, `mcp`, `rest`)
+    pub transport: &'static str,
+    /// Full method or tool name, as the authorization policy sees it
+    pub method: String,
+    /// The call's params (tool arguments for MCP, the JSON body for REST)
+    pub params: Option<Value>,
+    /// Who is making the call
+    pub identity: CallerIdentity,
+    /// The client's address, for network transports
+    pub peer: Option<SocketAddr>,
+    /// Values for the method and for `after_call`; empty when the call arrives
+    pub extensions: http::Extensions,
+}
+
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+impl CallContext {
+    pub(crate) fn new(
+        transport: &'static str,
+        method: impl Into<String>,
+        params: Option<Value>,
+        identity: CallerIdentity,
+        peer: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            transport,
+            method: method.into(),
+            params,
+            identity,
+            peer,
+            extensions: http::Extensions::new(),
+        }
+    }
+}
+
+/// Why middleware refused a call, sent to the client as the call's error
+///
+/// The code is a JSON-RPC error code, e.g.
+/// [`RATE_LIMITED_ERROR_CODE`](crate::rate_limit::RATE_LIMITED_ERROR_CODE) for
+/// an exhausted quota. REST clients get HTTP 403 for
+/// [`FORBIDDEN_ERROR_CODE`](crate::auth::policy::FORBIDDEN_ERROR_CODE), 429
+/// for `RATE_LIMITED_ERROR_CODE` and 400 otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallRejection {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl CallRejection {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Attach structured details to the error
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// How a call ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallOutcome {
+    /// Success, or the error the call was answered with
+    pub status: AuditStatus,
+    /// Time from the first `before_call` to the answer
+    pub latency: Duration,
+}
+
+/// Run `before_call` of each middleware in order, stopping at the first rejection
+///
+/// Middleware that let the call through before it was rejected get its
+/// `after_call` with the rejection.
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) async fn before_call(
+    middleware: &[Arc<dyn TransportMiddleware>],
+    call: &mut CallContext,
+    started: Instant,
+) -> Result<(), CallRejection> {
+    for (i, m) in middleware.iter().enumerate() {
+        if let Err(rejection) = m.before_call(call).await {
+            tracing::debug!("{} call rejected by middleware: {} ({})", call.transport, call.method, rejection.code);
+            after_call(&middleware[..i], call, AuditStatus::from_error_code(rejection.code), started);
+            return Err(rejection);
+        }
+    }
+    Ok(())
+}
+
+/// Run `after_call` of each middleware in reverse order
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) fn after_call(
+    middleware: &[Arc<dyn TransportMiddleware>],
+    call: &CallContext,
+    status: AuditStatus,
+    started: Instant,
+) {
+    let outcome = CallOutcome {
+        status,
+        latency: started.elapsed(),
+    };
+    for m in middleware.iter().rev() {
+        m.after_call(call, &outcome);
+    }
+}
+
+/// The peer address the connection was accepted from, if known
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) fn peer_of(extensions: &http::Extensions) -> Option<SocketAddr> {
+    extensions
+        .get::<axum::extract::ConnectInfo<SocketAddr>>()
+        .map(|axum::extract::ConnectInfo(addr)| *addr)
+}
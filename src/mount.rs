@@ -0,0 +1,238 @@
+//! Additional activations served next to the main one
+//!
+//! A [`MountedActivation`] shares the main activation's listeners under a
+//! prefix: its RPC methods are called as `prefix.namespace.method` over
+//! WebSocket and stdio, its MCP tools are listed as `prefix.namespace.method`,
+//! and its REST endpoints live at `/rest/{prefix}/{namespace}/{method}`.
+//! Authorization policies, middleware, request time limits and audit logs see
+//! calls under the prefixed name, so the prefix acts as the namespace for
+//! `MethodAllowList` patterns.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use jsonrpsee::{Methods, RpcModule};
+use plexus_core::plexus::{Activation, PluginSchema};
+
+#[cfg(feature = "mcp-http")]
+use crate::mcp::bridge::{ActivationMcpBridge, ToolSource};
+use crate::server::RouteFn;
+
+#[cfg(feature = "http-gateway")]
+use crate::audit::Auditor;
+#[cfg(feature = "http-gateway")]
+use crate::auth::AuthorizationPolicy;
+#[cfg(feature = "http-gateway")]
+use crate::mcp::timeout::ToolTimeouts;
+#[cfg(feature = "http-gateway")]
+use crate::middleware::TransportMiddleware;
+
+/// Builds a mounted activation's REST routes with a transport's policy,
+/// middleware, time limits and audit log
+#[cfg(feature = "http-gateway")]
+pub(crate) type RestRoutesFn = Arc<
+    dyn Fn(
+            Option<Arc<dyn AuthorizationPolicy>>,
+            Vec<Arc<dyn TransportMiddleware>>,
+            ToolTimeouts,
+            Option<Arc<Auditor>>,
+        ) -> axum::Router
+        + Send
+        + Sync,
+>;
+
+/// An activation served under a prefix, registered with
+/// [`TransportServerBuilder::with_mount`](crate::TransportServerBuilder::with_mount)
+pub struct MountedActivation {
+    prefix: String,
+    flat_schemas: Option<Vec<PluginSchema>>,
+    route_fn: Option<RouteFn>,
+    build: Box<dyn FnOnce(&str, Option<Vec<PluginSchema>>, Option<RouteFn>) -> Result<Mount> + Send>,
+}
+
+impl fmt::Debug for MountedActivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MountedActivation")
+            .field("prefix", &self.prefix)
+            .field("flat_schemas", &self.flat_schemas.as_ref().map(Vec::len))
+            .field("route_fn", &self.route_fn.is_some())
+            .finish()
+    }
+}
+
+impl MountedActivation {
+    /// Serve `activation` under `prefix`
+    ///
+    /// `rpc_converter` builds its RPC module, like the converter given to the
+    /// builder. The prefix must be non-empty and contain no `.` or `/`.
+    pub fn new<A, F>(prefix: impl Into<String>, activation: Arc<A>, rpc_converter: F) -> Self
+    where
+        A: Activation,
+        F: FnOnce(Arc<A>) -> Result<RpcModule<()>> + Send + 'static,
+    {
+        Self {
+            prefix: prefix.into(),
+            flat_schemas: None,
+            route_fn: None,
+            build: Box::new(move |prefix, flat_schemas, route_fn| {
+                Mount::new(prefix, activation, rpc_converter, flat_schemas, route_fn)
+            }),
+        }
+    }
+
+    /// Expose these schemas as MCP tools and REST endpoints.
+    /// For hub activations, pass `hub.list_plugin_schemas()`.
+    pub fn with_flat_schemas(mut self, schemas: Vec<PluginSchema>) -> Self {
+        self.flat_schemas = Some(schemas);
+        self
+    }
+
+    /// Dispatch MCP and REST calls through `hub.route()`.
+    pub fn with_route_fn(mut self, route_fn: RouteFn) -> Self {
+        self.route_fn = Some(route_fn);
+        self
+    }
+
+    /// The prefix the activation is served under
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Convert the activation and prepare it for every transport
+    pub(crate) fn build(self) -> Result<Mount> {
+        let valid = !self.prefix.is_empty() && !self.prefix.contains(['.', '/']);
+        anyhow::ensure!(valid, "invalid mount prefix {:?}: must be non-empty without '.' or '/'", self.prefix);
+        (self.build)(&self.prefix, self.flat_schemas, self.route_fn)
+    }
+}
+
+/// A mounted activation, ready to be served
+#[derive(Clone)]
+pub(crate) struct Mount {
+    pub(crate) prefix: String,
+    /// RPC methods, renamed to `prefix.method`
+    pub(crate) methods: Methods,
+    /// MCP tools, without the prefix
+    #[cfg(feature = "mcp-http")]
+    pub(crate) tools: Arc<dyn ToolSource>,
+    #[cfg(feature = "http-gateway")]
+    pub(crate) rest: RestRoutesFn,
+}
+
+impl Mount {
+    fn new<A, F>(
+        prefix: &str,
+        activation: Arc<A>,
+        rpc_converter: F,
+        flat_schemas: Option<Vec<PluginSchema>>,
+        route_fn: Option<RouteFn>,
+    ) -> Result<Self>
+    where
+        A: Activation,
+        F: FnOnce(Arc<A>) -> Result<RpcModule<()>>,
+    {
+        let methods = prefixed_methods(prefix, rpc_converter(activation.clone())?)?;
+
+        #[cfg(feature = "mcp-http")]
+        let mut bridge =
+            ActivationMcpBridge::with_server_info_and_schemas(activation.clone(), None, None, flat_schemas.clone());
+        #[cfg(feature = "mcp-http")]
+        if let Some(ref route_fn) = route_fn {
+            bridge = bridge.with_router(route_fn.clone());
+        }
+
+        #[cfg(feature = "http-gateway")]
+        let rest: RestRoutesFn = {
+            let prefix = prefix.to_string();
+            Arc::new(move |authorization, middleware, request_timeouts, audit| {
+                let mut bridge = crate::http::ActivationRestBridge::with_server_info_and_schemas(
+                    activation.clone(),
+                    None,
+                    None,
+                    flat_schemas.clone(),
+                )
+                .with_prefix(prefix.clone());
+                if let Some(ref route_fn) = route_fn {
+                    bridge = bridge.with_router(route_fn.clone());
+                }
+                if let Some(policy) = authorization {
+                    bridge = bridge.with_authorization_policy(policy);
+                }
+                for middleware in middleware {
+                    bridge = bridge.with_middleware(middleware);
+                }
+                bridge = bridge.with_request_timeouts(request_timeouts);
+                if let Some(auditor) = audit {
+                    bridge = bridge.with_audit(auditor);
+                }
+                bridge.into_router()
+            })
+        };
+
+        tracing::info!("Mounted activation {} under prefix {:?}", activation.namespace(), prefix);
+        Ok(Self {
+            prefix: prefix.to_string(),
+            methods,
+            #[cfg(feature = "mcp-http")]
+            tools: Arc::new(bridge),
+            #[cfg(feature = "http-gateway")]
+            rest,
+        })
+    }
+}
+
+/// Rename every method of `module` to `prefix.method`
+///
+/// jsonrpsee needs `'static` method names, so the prefixed names are leaked;
+/// mounts are built once per server.
+fn prefixed_methods(prefix: &str, module: RpcModule<()>) -> Result<Methods> {
+    let source = Methods::from(module);
+    let mut methods = Methods::new();
+    for name in source.method_names() {
+        let callback = source.method(name).expect("listed method exists").clone();
+        let prefixed: &'static str = Box::leak(format!("{}.{}", prefix, name).into_boxed_str());
+        methods.verify_and_insert(prefixed, callback)?;
+    }
+    Ok(methods)
+}
+
+/// Fail if a mount prefix is also a namespace of the main activation
+///
+/// MCP hands `prefix.<rest>` tool names to the mount, so such a prefix would
+/// take over the main activation's tools in that namespace. `flat_schemas` are
+/// the main activation's tool schemas, if it is a hub.
+pub(crate) fn check_prefixes<A: Activation>(
+    activation: &A,
+    flat_schemas: Option<&[PluginSchema]>,
+    mounts: &[Mount],
+) -> Result<()> {
+    let own = own_namespaces(activation, flat_schemas);
+    for mount in mounts {
+        anyhow::ensure!(
+            !own.contains(&mount.prefix),
+            "mount prefix {:?} is also a namespace of the main activation",
+            mount.prefix
+        );
+    }
+    Ok(())
+}
+
+/// The namespaces of an activation's own methods
+fn own_namespaces<A: Activation>(activation: &A, flat_schemas: Option<&[PluginSchema]>) -> Vec<String> {
+    let mut namespaces = vec![activation.namespace().to_string()];
+    match flat_schemas {
+        Some(schemas) => namespaces.extend(schemas.iter().map(|schema| schema.namespace.to_string())),
+        None => namespaces.push(activation.plugin_schema().namespace.to_string()),
+    }
+    namespaces
+}
+
+/// Add the methods of every mount to the main activation's methods
+pub(crate) fn merge_methods(methods: Methods, mounts: &[Mount]) -> Result<Methods> {
+    let mut merged = methods;
+    for mount in mounts {
+        merged.merge(mount.methods.clone())?;
+    }
+    Ok(merged)
+}
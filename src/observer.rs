@@ -0,0 +1,135 @@
+//! Connection and session lifecycle hooks
+//!
+//! A [`TransportObserver`] registered with
+//! [`TransportServerBuilder::with_observer`](crate::TransportServerBuilder::with_observer)
+//! hears about every WebSocket and stdio connection and every MCP session as
+//! it opens and closes, e.g. to push connection counts to the application's
+//! own telemetry or to release per-agent state when an agent goes away.
+//!
+//! The HTTP transports are stateless per connection, so MCP HTTP reports its
+//! sessions instead; a session ends when the client deletes it or it expires.
+//!
+//! Every call on a WebSocket connection carries the connection's
+//! [`ConnectionInfo`] in its extensions, so methods can keep per-client state
+//! under its `id` and the observer can drop that state in `on_disconnect`.
+
+use std::net::SocketAddr;
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(any(feature = "websocket", feature = "stdio", feature = "mcp-http"))]
+use std::sync::Arc;
+
+/// Hooks called as connections and sessions open and close
+///
+/// Every hook has an empty default, so observers implement only the ones they
+/// need. Hooks are called inline on the transport's tasks and must not block;
+/// hand longer work to a task of your own.
+pub trait TransportObserver: Send + Sync {
+    /// A client connected
+    fn on_connect(&self, _connection: &ConnectionInfo) {}
+
+    /// A client that connected earlier went away
+    fn on_disconnect(&self, _connection: &ConnectionInfo) {}
+
+    /// A client started an MCP session
+    fn on_session_created(&self, _session: &SessionInfo) {}
+
+    /// An MCP session ended, closed by the client or expired
+    fn on_session_expired(&self, _session: &SessionInfo) {}
+}
+
+impl std::fmt::Debug for dyn TransportObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TransportObserver")
+    }
+}
+
+/// A client connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    /// Transport the client connected to (`websocket`, `stdio`)
+    pub transport: &'static str,
+    /// Identifies the connection in `on_connect` and `on_disconnect`; unique
+    /// within the process
+    pub id: u64,
+    /// The client's address, for network transports
+    pub peer: Option<SocketAddr>,
+}
+
+/// An MCP session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    /// Transport the session lives on (`mcp`)
+    pub transport: &'static str,
+    /// The `Mcp-Session-Id` the client sends with each request
+    pub session_id: String,
+}
+
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+impl ConnectionInfo {
+    /// A new connection, with the next id
+    pub(crate) fn new(transport: &'static str, peer: Option<SocketAddr>) -> Self {
+        Self {
+            transport,
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            peer,
+        }
+    }
+}
+
+/// Reports a connection as open until dropped
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+pub(crate) struct ObservedConnection {
+    observer: Arc<dyn TransportObserver>,
+    info: ConnectionInfo,
+}
+
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+impl ObservedConnection {
+    #[cfg(feature = "stdio")]
+    pub(crate) fn new(observer: Arc<dyn TransportObserver>, transport: &'static str, peer: Option<SocketAddr>) -> Self {
+        Self::open(observer, ConnectionInfo::new(transport, peer))
+    }
+
+    /// Report `info` as open until dropped
+    pub(crate) fn open(observer: Arc<dyn TransportObserver>, info: ConnectionInfo) -> Self {
+        observer.on_connect(&info);
+        Self { observer, info }
+    }
+}
+
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+impl Drop for ObservedConnection {
+    fn drop(&mut self) {
+        self.observer.on_disconnect(&self.info);
+    }
+}
+
+/// Reports a session as open until dropped
+#[cfg(feature = "mcp-http")]
+pub(crate) struct ObservedSession {
+    observer: Arc<dyn TransportObserver>,
+    info: SessionInfo,
+}
+
+#[cfg(feature = "mcp-http")]
+impl ObservedSession {
+    pub(crate) fn new(observer: Arc<dyn TransportObserver>, transport: &'static str, session_id: String) -> Self {
+        let info = SessionInfo {
+            transport,
+            session_id,
+        };
+        observer.on_session_created(&info);
+        Self { observer, info }
+    }
+}
+
+#[cfg(feature = "mcp-http")]
+impl Drop for ObservedSession {
+    fn drop(&mut self) {
+        self.observer.on_session_expired(&self.info);
+    }
+}
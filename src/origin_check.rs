@@ -0,0 +1,124 @@
+//! `Origin` header validation (DNS-rebinding protection)
+//!
+//! A page on `http://evil.example` can re-point its DNS name at `127.0.0.1` and
+//! then talk to a local MCP or WebSocket server from the victim's browser. The
+//! browser still sends the page's real `Origin`, so servers must check it, as the
+//! MCP Streamable HTTP transport requires.
+//!
+//! Requests without an `Origin` header (CLI tools, SDK clients) are never
+//! affected; browser requests from an origin that is not allowed get HTTP 403.
+//!
+//! The check builds on the application's allowlist: origins passed to
+//! [`init_allowed_origins`](crate::init_allowed_origins), which
+//! [`ValidOrigin`](crate::ValidOrigin) validates method calls against, are
+//! accepted as well as the ones configured on the transport.
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+use crate::request::origin::same_origin;
+
+/// How a transport validates the `Origin` header of incoming requests
+///
+/// Serialized as `"auto"`, `"disabled"` or `{"allow": [...]}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginCheck {
+    /// Only allow localhost origins when bound to a loopback or wildcard
+    /// (`0.0.0.0`, `::`) address, which localhost browsers reach; no check
+    /// when bound to a specific other address
+    #[default]
+    Auto,
+    /// Accept any origin
+    Disabled,
+    /// Accept only these origins (e.g. `https://app.example.com`), plus
+    /// localhost origins when bound to a loopback or wildcard address
+    Allow(Vec<String>),
+}
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+impl OriginCheck {
+    /// Resolve the check for a listener bound to `addr`; `None` disables it
+    ///
+    /// `extra` origins (e.g. the CORS allow list) and the application's
+    /// allowlist are accepted as well.
+    pub(crate) fn resolve(&self, addr: SocketAddr, extra: &[String]) -> Option<AllowedOrigins> {
+        let localhost = addr.ip().is_loopback() || addr.ip().is_unspecified();
+        let mut origins = extra.to_vec();
+        origins.extend(crate::request::origin::allowed_origins().iter().cloned());
+        match self {
+            Self::Disabled => return None,
+            Self::Auto if !localhost => return None,
+            Self::Auto => {}
+            Self::Allow(list) => origins.extend(list.iter().cloned()),
+        }
+        Some(AllowedOrigins { origins, localhost })
+    }
+}
+
+/// A resolved origin allow list
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+#[derive(Debug, Clone)]
+pub(crate) struct AllowedOrigins {
+    origins: Vec<String>,
+    /// Whether `http(s)://localhost|127.0.0.1|[::1][:port]` are accepted
+    localhost: bool,
+}
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+impl AllowedOrigins {
+    /// Whether a request with these headers passes the check
+    pub(crate) fn allows(&self, headers: &http::HeaderMap) -> bool {
+        let Some(origin) = headers.get(http::header::ORIGIN) else {
+            return true;
+        };
+        let Ok(origin) = origin.to_str() else {
+            return false;
+        };
+
+        if self.origins.iter().any(|allowed| same_origin(allowed, origin)) {
+            return true;
+        }
+        self.localhost && is_localhost_origin(origin)
+    }
+}
+
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+fn is_localhost_origin(origin: &str) -> bool {
+    let Ok(uri) = origin.parse::<http::Uri>() else {
+        return false;
+    };
+    matches!(uri.scheme_str(), Some("http") | Some("https"))
+        && matches!(uri.host(), Some("localhost") | Some("127.0.0.1") | Some("[::1]"))
+}
+
+/// HTTP 403 response for a rejected origin
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+pub(crate) fn forbidden_origin_response<B: From<&'static str>>() -> http::Response<B> {
+    http::Response::builder()
+        .status(http::StatusCode::FORBIDDEN)
+        .header(http::header::CONTENT_TYPE, "text/plain")
+        .body(B::from("Origin not allowed"))
+        .expect("static response is valid")
+}
+
+/// axum middleware rejecting requests whose `Origin` is not allowed
+#[cfg(feature = "mcp-http")]
+pub(crate) async fn origin_middleware(
+    axum::extract::State(allowed): axum::extract::State<std::sync::Arc<AllowedOrigins>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if !allowed.allows(request.headers()) {
+        tracing::warn!(
+            "Request rejected: origin {:?} not allowed (uri={})",
+            request.headers().get(http::header::ORIGIN),
+            request.uri()
+        );
+        return forbidden_origin_response();
+    }
+    next.run(request).await
+}
@@ -0,0 +1,303 @@
+//! Per-client rate limiting shared by the network transports
+//!
+//! A token bucket is kept per client key: each request takes one token, and
+//! tokens refill continuously at `requests_per_second` up to `burst`. The key is
+//! the client's IP address, its authenticated identity (API key / JWT subject),
+//! or its session (MCP session id, WebSocket connection), falling back to the IP
+//! when the preferred key is unavailable. MCP session ids come from the client,
+//! so they only key a bucket once the session manager knows the session; a
+//! client rotating made-up ids is limited by its IP.
+//!
+//! At most [`MAX_TRACKED_CLIENTS`] buckets are kept: refilled buckets are
+//! dropped first, then the ones idle the longest.
+//!
+//! Exceeding the limit yields HTTP 429 on MCP HTTP / REST and a JSON-RPC error
+//! (code [`RATE_LIMITED_ERROR_CODE`]) on WebSocket.
+//!
+//! Requests that fail authentication never reach the per-client limit, which
+//! runs inside the auth layer. On MCP HTTP and REST they are limited by IP
+//! instead, at the same rate: each 401 takes a token, and an IP out of tokens
+//! gets 429 before its credentials are checked.
+
+use std::collections::HashMap;
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+use axum::extract::ConnectInfo;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::CallerIdentity;
+
+/// JSON-RPC error code returned when a client exceeds its rate limit
+pub const RATE_LIMITED_ERROR_CODE: i32 = -32005;
+
+/// Header carrying the MCP session id (Streamable HTTP transport)
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) const MCP_SESSION_HEADER: &str = "mcp-session-id";
+
+/// Most clients tracked at once; idle buckets are evicted beyond it
+pub const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Whether a client-supplied session id names a live session
+pub(crate) type SessionCheck = Arc<dyn Fn(String) -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// What a rate limit bucket is keyed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// Client IP address
+    Ip,
+    /// Authenticated identity (API key identity, JWT subject, ...)
+    ApiKey,
+    /// MCP session id, or the connection for WebSocket clients
+    Session,
+}
+
+/// Token bucket rate limit configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Sustained request rate per client
+    pub requests_per_second: f64,
+    /// Maximum burst size (bucket capacity)
+    pub burst: u32,
+    /// What clients are distinguished by
+    pub key: RateLimitKey,
+}
+
+impl RateLimitConfig {
+    /// Limit each client IP to `requests_per_second`, allowing bursts of `burst`
+    pub fn per_ip(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+            key: RateLimitKey::Ip,
+        }
+    }
+
+    /// Limit each authenticated identity; unauthenticated clients are keyed by IP
+    pub fn per_api_key(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            key: RateLimitKey::ApiKey,
+            ..Self::per_ip(requests_per_second, burst)
+        }
+    }
+
+    /// Limit each session; requests without one are keyed by IP
+    pub fn per_session(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            key: RateLimitKey::Session,
+            ..Self::per_ip(requests_per_second, burst)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket rate limiter keyed per client
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    /// Validates MCP session ids before they key a bucket
+    sessions: Option<SessionCheck>,
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("config", &self.config)
+            .field("buckets", &self.buckets)
+            .field("sessions", &self.sessions.is_some())
+            .finish()
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            sessions: None,
+        }
+    }
+
+    /// Only key buckets on MCP session ids that `sessions` accepts
+    #[cfg(feature = "mcp-http")]
+    pub(crate) fn with_session_check(mut self, sessions: SessionCheck) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    /// Whether `key` has a token left, without taking it
+    pub fn has_token(&self, key: &str) -> bool {
+        let buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        match buckets.get(key) {
+            Some(bucket) => {
+                let elapsed = Instant::now().duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * self.config.requests_per_second >= 1.0
+            }
+            None => true,
+        }
+    }
+
+    /// Take a token for `key`; returns `false` if the client is over its limit
+    pub fn check(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let capacity = self.config.burst.max(1) as f64;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(key) {
+            self.evict(&mut buckets, now, capacity);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.config.requests_per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Make room for new clients
+    ///
+    /// Buckets that have refilled completely carry no state worth keeping; if
+    /// that is not enough, the buckets idle the longest go too. Evicting down
+    /// to nine tenths of the limit keeps the scan off most requests.
+    fn evict(&self, buckets: &mut HashMap<String, Bucket>, now: Instant, capacity: f64) {
+        let rate = self.config.requests_per_second;
+        buckets.retain(|_, b| b.tokens + now.duration_since(b.updated).as_secs_f64() * rate < capacity);
+
+        let keep = MAX_TRACKED_CLIENTS / 10 * 9;
+        if buckets.len() > keep {
+            let mut updated: Vec<Instant> = buckets.values().map(|b| b.updated).collect();
+            let evicted = buckets.len() - keep;
+            let (_, cutoff, _) = updated.select_nth_unstable(evicted);
+            let cutoff = *cutoff;
+            buckets.retain(|_, b| b.updated >= cutoff);
+        }
+    }
+
+    /// Time until a rejected client can expect a token, for `Retry-After`
+    pub fn retry_after(&self) -> Duration {
+        if self.config.requests_per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / self.config.requests_per_second)
+        } else {
+            Duration::from_secs(60)
+        }
+    }
+
+    /// Derive the bucket key for a request from its extensions
+    ///
+    /// `session` is the transport's notion of a session (MCP session id,
+    /// WebSocket connection id), if any.
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    pub fn key_for(&self, extensions: &http::Extensions, session: Option<String>) -> String {
+        let ip = || ip_key(extensions);
+
+        match self.config.key {
+            RateLimitKey::Ip => ip(),
+            RateLimitKey::ApiKey => CallerIdentity::from_extensions(extensions)
+                .name()
+                .map(|name| format!("id:{}", name))
+                .unwrap_or_else(ip),
+            RateLimitKey::Session => session
+                .map(|session| format!("session:{}", session))
+                .unwrap_or_else(ip),
+        }
+    }
+}
+
+/// The bucket key of the client IP in `extensions`
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+fn ip_key(extensions: &http::Extensions) -> String {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+        .unwrap_or_else(|| "ip:unknown".to_string())
+}
+
+/// 429 with a `Retry-After` from `limiter`
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+fn too_many_requests(limiter: &RateLimiter) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let retry_after = limiter.retry_after().as_secs().max(1).to_string();
+    (
+        http::StatusCode::TOO_MANY_REQUESTS,
+        [(http::header::RETRY_AFTER, retry_after)],
+        "Too Many Requests",
+    )
+        .into_response()
+}
+
+/// axum middleware enforcing a [`RateLimiter`] on HTTP requests
+///
+/// Must run inside the auth layer so per-identity keys can see the caller.
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) async fn rate_limit_middleware(
+    axum::extract::State(limiter): axum::extract::State<std::sync::Arc<RateLimiter>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    // A session id the server did not issue must not earn its own bucket
+    let session = match request.headers().get(MCP_SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(session) if limiter.config.key == RateLimitKey::Session => match limiter.sessions {
+            Some(ref sessions) if sessions(session.to_string()).await => Some(session.to_string()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let key = limiter.key_for(request.extensions(), session);
+
+    if !limiter.check(&key) {
+        tracing::warn!("Rate limit exceeded for {} (uri={})", key, request.uri());
+        return too_many_requests(&limiter);
+    }
+    next.run(request).await
+}
+
+/// The per-IP limiter of failed authentications for `config`
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) fn auth_failure_limiter(config: &RateLimitConfig) -> Arc<RateLimiter> {
+    Arc::new(RateLimiter::new(RateLimitConfig::per_ip(config.requests_per_second, config.burst)))
+}
+
+/// axum middleware limiting failed authentications per client IP
+///
+/// Must run outside the auth layer: each 401 takes a token from the client's
+/// bucket, and a client out of tokens is rejected before its credentials are
+/// checked, so API keys and tokens cannot be guessed faster than the limit.
+/// Authenticated requests take nothing.
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+pub(crate) async fn auth_failure_limit_middleware(
+    axum::extract::State(limiter): axum::extract::State<std::sync::Arc<RateLimiter>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let key = ip_key(request.extensions());
+    if !limiter.has_token(&key) {
+        tracing::warn!("Too many failed authentications from {} (uri={})", key, request.uri());
+        return too_many_requests(&limiter);
+    }
+
+    let response = next.run(request).await;
+    if response.status() == http::StatusCode::UNAUTHORIZED {
+        limiter.check(&key);
+    }
+    response
+}
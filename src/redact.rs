@@ -0,0 +1,121 @@
+//! Masking of sensitive data in request logs
+//!
+//! Request logging must never leak credentials into log aggregation. A
+//! [`Redaction`] lists the headers and JSON body fields whose values are
+//! replaced with `[REDACTED]` before anything is logged.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Replacement for masked values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Header names and JSON body field names whose values are masked in logs
+///
+/// Matching is case-insensitive. The default masks credentials (`Authorization`,
+/// `X-Api-Key`, cookies) and common secret fields (`password`, `token`, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Redaction {
+    headers: HashSet<String>,
+    body_fields: HashSet<String>,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self::none()
+            .with_headers([
+                "authorization",
+                "proxy-authorization",
+                "x-api-key",
+                "cookie",
+                "set-cookie",
+            ])
+            .with_body_fields([
+                "password",
+                "secret",
+                "token",
+                "access_token",
+                "refresh_token",
+                "api_key",
+                "apikey",
+            ])
+    }
+}
+
+impl Redaction {
+    /// Mask nothing
+    pub fn none() -> Self {
+        Self {
+            headers: HashSet::new(),
+            body_fields: HashSet::new(),
+        }
+    }
+
+    /// Also mask these headers
+    pub fn with_headers<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.headers
+            .extend(names.into_iter().map(|n| n.as_ref().to_ascii_lowercase()));
+        self
+    }
+
+    /// Also mask these JSON body fields, at any depth
+    pub fn with_body_fields<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.body_fields
+            .extend(names.into_iter().map(|n| n.as_ref().to_ascii_lowercase()));
+        self
+    }
+
+    /// Loggable form of a header value
+    pub fn header_value(&self, name: &http::HeaderName, value: &http::HeaderValue) -> String {
+        if self.headers.contains(name.as_str()) {
+            REDACTED.to_string()
+        } else {
+            format!("{:?}", value)
+        }
+    }
+
+    /// Mask matching fields in a JSON value, recursively
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if self.body_fields.contains(&key.to_ascii_lowercase()) {
+                        *field = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_json(item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Loggable form of a request body
+    ///
+    /// JSON bodies are logged with matching fields masked; anything else is
+    /// summarised by its size, since it cannot be masked reliably.
+    pub fn body(&self, body: &[u8]) -> String {
+        match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut value) => {
+                self.redact_json(&mut value);
+                value.to_string()
+            }
+            Err(_) => format!("<{} bytes>", body.len()),
+        }
+    }
+}
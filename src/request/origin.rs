@@ -20,6 +20,13 @@
 //! If [`init_allowed_origins`] has never been called, or was called with an empty list,
 //! **all** origins are permitted. This is the safe default for servers that do not need
 //! CORS enforcement.
+//!
+//! ## Transport-level check
+//!
+//! The MCP HTTP and WebSocket servers reject browser requests from foreign origins
+//! before any method runs (see [`OriginCheck`](crate::OriginCheck)). Origins passed to
+//! [`init_allowed_origins`] are accepted there too, so an origin allowed here is never
+//! turned away by the transport first.
 
 use std::sync::OnceLock;
 
@@ -42,6 +49,17 @@ pub fn init_allowed_origins(origins: Vec<String>) {
     let _ = ALLOWED_ORIGINS.set(origins);
 }
 
+/// The origins passed to [`init_allowed_origins`], if it was called
+#[cfg(any(feature = "websocket", feature = "mcp-http"))]
+pub(crate) fn allowed_origins() -> &'static [String] {
+    ALLOWED_ORIGINS.get().map_or(&[], Vec::as_slice)
+}
+
+/// Whether `origin` is the allowed origin `allowed`, ignoring a trailing slash
+pub(crate) fn same_origin(allowed: &str, origin: &str) -> bool {
+    allowed.trim_end_matches('/') == origin.trim_end_matches('/')
+}
+
 /// A validated `Origin` header value.
 ///
 /// - `ValidOrigin("")` — no `Origin` header was present (non-browser client).
@@ -75,7 +93,7 @@ impl PlexusRequestField for ValidOrigin {
             None => Ok(ValidOrigin(origin)),
             Some(list) if list.is_empty() => Ok(ValidOrigin(origin)),
             Some(list) => {
-                if list.iter().any(|allowed| allowed == &origin) {
+                if list.iter().any(|allowed| same_origin(allowed, &origin)) {
                     Ok(ValidOrigin(origin))
                 } else {
                     Err(PlexusError::Unauthenticated(format!(
@@ -0,0 +1,71 @@
+//! Resuming WebSocket subscriptions after a reconnect
+//!
+//! A WebSocket client that loses its connection normally loses its
+//! subscriptions with it, and must open them again and miss whatever was
+//! published in between. With [`WebSocketConfig::with_subscription_resume`](crate::config::WebSocketConfig::with_subscription_resume),
+//! subscriptions outlive their connection for a resume window:
+//!
+//! 1. The client asks for its connection's resume token with
+//!    `rpc.resume_token`. The token is a secret, bound to the caller identity
+//!    of the connection: only a connection authenticated as the same caller
+//!    can resume with it.
+//! 2. When the connection drops, its subscriptions keep running, and up to
+//!    `replay` of their notifications are kept.
+//! 3. On a new connection, the client calls `rpc.resume` with the token. The
+//!    answer lists the subscriptions resumed, then the kept notifications are
+//!    replayed, and notifications keep coming with the same subscription ids.
+//!
+//! ```json
+//! → {"jsonrpc":"2.0","id":1,"method":"rpc.resume","params":["3f0c..."]}
+//! ← {"jsonrpc":"2.0","id":1,"result":{"subscriptions":[8340125],"replayed":3,"dropped":0}}
+//! ← {"jsonrpc":"2.0","method":"ticker.update","params":{"subscription":8340125,"result":...}}
+//! ```
+//!
+//! Subscriptions not resumed within the window are closed. Resuming takes
+//! over subscriptions whose connection the server still considers open, as
+//! after a network change the old connection may only be noticed as dead later.
+//!
+//! Running subscriptions cannot be persisted, so resumable subscriptions are
+//! kept in memory: a client resumes on the same server process it subscribed
+//! to, and a restart ends them like any other connection state.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "websocket")]
+pub(crate) mod websocket;
+
+/// JSON-RPC error code returned when a resume token is unknown or expired
+pub const RESUME_FAILED_ERROR_CODE: i32 = -32004;
+
+/// Method returning the connection's resume token
+pub const RESUME_TOKEN_METHOD: &str = "rpc.resume_token";
+
+/// Method resuming the subscriptions of a resume token
+pub const RESUME_METHOD: &str = "rpc.resume";
+
+/// How long subscriptions wait for their client to come back, and what they keep meanwhile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionResume {
+    /// Time after a connection drops during which its subscriptions can be resumed
+    #[serde(with = "crate::config::serde_secs")]
+    pub window: Duration,
+    /// Notifications kept for replay while no connection is attached; older
+    /// ones are dropped first
+    pub replay: usize,
+}
+
+impl SubscriptionResume {
+    /// Keep subscriptions for `window` after their connection drops, with up
+    /// to `replay` missed notifications
+    pub fn new(window: Duration, replay: usize) -> Self {
+        Self { window, replay }
+    }
+}
+
+impl Default for SubscriptionResume {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30), 256)
+    }
+}
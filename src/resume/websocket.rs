@@ -0,0 +1,391 @@
+//! Resumable subscriptions on WebSocket connections
+//!
+//! jsonrpsee ends a subscription when its connection closes: the subscription
+//! notifies through the connection's sink, which closes with it. Each
+//! connection is therefore served copies of the methods whose subscriptions
+//! notify into a channel of ours instead. A task per subscription forwards
+//! the channel to the connection the subscription's session is attached to,
+//! and keeps notifications while there is none.
+//!
+//! A session records the caller identity that subscribed, and only resumes on
+//! connections of the same caller.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use futures::future::BoxFuture;
+use jsonrpsee::types::{ErrorCode, ErrorObject, Id, SubscriptionId};
+use jsonrpsee::{MethodCallback, MethodResponse, MethodSink, Methods, ResponsePayload};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::json;
+use tokio::sync::{mpsc, oneshot};
+
+use super::{SubscriptionResume, RESUME_FAILED_ERROR_CODE, RESUME_METHOD, RESUME_TOKEN_METHOD};
+use crate::auth::CallerIdentity;
+
+/// Resumable subscriptions of a WebSocket server, by resume token
+pub(crate) struct Resumption {
+    config: SubscriptionResume,
+    /// Notifications a subscription may queue before it waits for delivery
+    buffer: usize,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl Resumption {
+    pub(crate) fn new(config: SubscriptionResume, buffer: usize) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            buffer: buffer.max(1),
+            sessions: Mutex::default(),
+        })
+    }
+
+    /// `methods` as served to a new connection
+    ///
+    /// Subscriptions become resumable, and the resume methods are added unless
+    /// the activation has methods of the same names.
+    pub(crate) fn connection(self: &Arc<Self>, mut methods: Methods) -> Methods {
+        let connection = Arc::new(Connection {
+            resumption: self.clone(),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            session: Mutex::new(None),
+        });
+
+        let mut served = Methods::new();
+        *served.extensions_mut() = methods.extensions().clone();
+        for name in methods.method_names() {
+            let Some(callback) = methods.method(name) else {
+                continue;
+            };
+            let callback = match callback {
+                MethodCallback::Subscription(subscribe) => {
+                    let subscribe = subscribe.clone();
+                    let connection = connection.clone();
+                    let buffer = self.buffer;
+                    MethodCallback::Subscription(Arc::new(move |id, params, sink, state, extensions| {
+                        // The subscription answers and notifies into our channel,
+                        // which outlives the connection
+                        let (tx, notifications) = mpsc::channel(buffer);
+                        let own = MethodSink::new_with_limit(tx, sink.max_response_size());
+                        let caller = CallerIdentity::from_extensions(&extensions).label();
+                        let subscribed = subscribe(id, params, own, state, extensions);
+                        Box::pin(connection.clone().subscribed(subscribed, notifications, sink, caller))
+                    }))
+                }
+                MethodCallback::Unsubscription(unsubscribe) => {
+                    let unsubscribe = unsubscribe.clone();
+                    let connection = connection.clone();
+                    MethodCallback::Unsubscription(Arc::new(move |id, params, conn_id, max_response_size, extensions| {
+                        // Resumed subscriptions are unknown under this connection's id
+                        let subscription = params.one::<SubscriptionId>().map(SubscriptionId::into_owned);
+                        if subscription.is_ok_and(|subscription| connection.unsubscribe(&subscription)) {
+                            return MethodResponse::response(id, ResponsePayload::success(true), max_response_size)
+                                .with_extensions(extensions);
+                        }
+                        unsubscribe(id, params, conn_id, max_response_size, extensions)
+                    }))
+                }
+                callback => callback.clone(),
+            };
+            served
+                .verify_and_insert(name, callback)
+                .expect("method names are unique");
+        }
+
+        let token = {
+            let connection = connection.clone();
+            MethodCallback::Sync(Arc::new(move |id, _params, max_response_size, extensions| {
+                MethodResponse::response(id, ResponsePayload::success(connection.token()), max_response_size)
+                    .with_extensions(extensions)
+            }))
+        };
+        // Resuming needs the connection's sink, which only subscriptions get
+        let resume = MethodCallback::Subscription(Arc::new(move |id, params, sink, _state, extensions| {
+            let token = params.one::<String>().ok();
+            let caller = CallerIdentity::from_extensions(&extensions).label();
+            Box::pin(connection.clone().resume(id.into_owned(), token, sink, caller))
+        }));
+        for (name, callback) in [(RESUME_TOKEN_METHOD, token), (RESUME_METHOD, resume)] {
+            if served.verify_and_insert(name, callback).is_err() {
+                tracing::warn!("Subscription resume: the activation's {} method is served instead", name);
+            }
+        }
+        served
+    }
+
+    /// Start a session of `caller` for the subscriptions of `token`, attached to `sink`
+    fn open(self: &Arc<Self>, token: String, sink: MethodSink, caller: String) -> Arc<Session> {
+        let session = Arc::new(Session {
+            token: token.clone(),
+            caller,
+            resumption: Arc::downgrade(self),
+            config: self.config,
+            delivery: tokio::sync::Mutex::new(Delivery {
+                sink: Some(sink.clone()),
+                missed: VecDeque::new(),
+                dropped: 0,
+            }),
+            subscriptions: Mutex::default(),
+            attachment: AtomicU64::new(0),
+        });
+        self.sessions
+            .lock()
+            .expect("sessions lock poisoned")
+            .insert(token, session.clone());
+        session.watch(sink, 0);
+        session
+    }
+}
+
+/// One WebSocket connection's view of the resumable subscriptions
+struct Connection {
+    resumption: Arc<Resumption>,
+    /// Resume token of the session the connection would start
+    token: String,
+    /// The session the connection is attached to, once it subscribed or resumed
+    session: Mutex<Option<Arc<Session>>>,
+}
+
+impl Connection {
+    /// The token resuming this connection's subscriptions
+    fn token(&self) -> String {
+        match &*self.session.lock().expect("session lock poisoned") {
+            Some(session) => session.token.clone(),
+            None => self.token.clone(),
+        }
+    }
+
+    /// Finish a subscription call, making the subscription resumable
+    async fn subscribed(
+        self: Arc<Self>,
+        subscribed: BoxFuture<'static, MethodResponse>,
+        mut notifications: mpsc::Receiver<Box<RawValue>>,
+        sink: MethodSink,
+        caller: String,
+    ) -> MethodResponse {
+        let response = subscribed.await;
+        // Subscriptions send their answer (acceptance or rejection) through the
+        // sink, ahead of their notifications; other responses are the server's to send
+        if !response.is_subscription() {
+            return response;
+        }
+        let Ok(answer) = notifications.try_recv() else {
+            return response;
+        };
+        if sink.send(answer).await.is_err() || !response.is_success() {
+            return response;
+        }
+        if let Some(id) = subscription_id(&response) {
+            let session = {
+                let mut session = self.session.lock().expect("session lock poisoned");
+                session
+                    .get_or_insert_with(|| self.resumption.open(self.token.clone(), sink.clone(), caller))
+                    .clone()
+            };
+            session.add(id, notifications);
+        }
+        response
+    }
+
+    /// End the resumable subscription `id`, if the connection has it
+    fn unsubscribe(&self, id: &SubscriptionId<'static>) -> bool {
+        let session = self.session.lock().expect("session lock poisoned").clone();
+        session.is_some_and(|session| {
+            session
+                .subscriptions
+                .lock()
+                .expect("subscriptions lock poisoned")
+                .remove(id)
+                .is_some()
+        })
+    }
+
+    /// Attach the session of `token` to this connection, if `caller` owns it
+    async fn resume(
+        self: Arc<Self>,
+        id: Id<'static>,
+        token: Option<String>,
+        sink: MethodSink,
+        caller: String,
+    ) -> MethodResponse {
+        let Some(token) = token else {
+            return MethodResponse::error(id, ErrorObject::from(ErrorCode::InvalidParams));
+        };
+        let session = {
+            let mut current = self.session.lock().expect("session lock poisoned");
+            if current.is_some() {
+                return resume_failed(id, "Subscriptions must be resumed before subscribing");
+            }
+            let session = self
+                .resumption
+                .sessions
+                .lock()
+                .expect("sessions lock poisoned")
+                .get(&token)
+                .cloned();
+            let Some(session) = session else {
+                return resume_failed(id, "Unknown or expired resume token");
+            };
+            if session.caller != caller {
+                tracing::warn!("Subscription resume by {} refused: the session belongs to {}", caller, session.caller);
+                // Answered like an unknown token, so tokens cannot be probed
+                return resume_failed(id, "Unknown or expired resume token");
+            }
+            *current = Some(session.clone());
+            session
+        };
+        session.attach(id, sink).await
+    }
+}
+
+/// Subscriptions sharing a resume token
+struct Session {
+    token: String,
+    /// Label of the caller identity that subscribed
+    caller: String,
+    resumption: Weak<Resumption>,
+    config: SubscriptionResume,
+    delivery: tokio::sync::Mutex<Delivery>,
+    /// Running subscriptions; removing one stops forwarding it, which ends it
+    subscriptions: Mutex<HashMap<SubscriptionId<'static>, oneshot::Sender<()>>>,
+    /// Counts the connections the session was attached to
+    attachment: AtomicU64,
+}
+
+/// Where a session's notifications go
+struct Delivery {
+    /// The attached connection; `None` while the client is away
+    sink: Option<MethodSink>,
+    /// Notifications kept for the next connection, oldest first
+    missed: VecDeque<Box<RawValue>>,
+    /// Notifications dropped since the last attach
+    dropped: usize,
+}
+
+impl Session {
+    /// Forward the notifications of subscription `id` until it ends
+    fn add(self: &Arc<Self>, id: SubscriptionId<'static>, mut notifications: mpsc::Receiver<Box<RawValue>>) {
+        let (stop, mut stopped) = oneshot::channel();
+        self.subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned")
+            .insert(id.clone(), stop);
+        let session = self.clone();
+        crate::task::spawn("plexus.websocket.resume", async move {
+            loop {
+                tokio::select! {
+                    notification = notifications.recv() => match notification {
+                        Some(notification) => session.deliver(notification).await,
+                        None => break,
+                    },
+                    _ = &mut stopped => break,
+                }
+            }
+            session
+                .subscriptions
+                .lock()
+                .expect("subscriptions lock poisoned")
+                .remove(&id);
+        });
+    }
+
+    /// Send `notification` to the attached connection, or keep it for the next
+    async fn deliver(&self, notification: Box<RawValue>) {
+        let mut delivery = self.delivery.lock().await;
+        if let Some(ref sink) = delivery.sink {
+            if sink.send(notification.clone()).await.is_ok() {
+                return;
+            }
+            delivery.sink = None;
+        }
+        if delivery.missed.len() >= self.config.replay {
+            delivery.dropped += 1;
+            if delivery.missed.pop_front().is_none() {
+                return;
+            }
+        }
+        delivery.missed.push_back(notification);
+    }
+
+    /// Answer `rpc.resume` on `sink`, replay the missed notifications, and
+    /// deliver to `sink` from now on
+    async fn attach(self: &Arc<Self>, id: Id<'static>, sink: MethodSink) -> MethodResponse {
+        let mut delivery = self.delivery.lock().await;
+        // A connection still attached is taken over
+        let attachment = self.attachment.fetch_add(1, Ordering::SeqCst) + 1;
+        delivery.sink = None;
+        let missed = std::mem::take(&mut delivery.missed);
+        let dropped = std::mem::take(&mut delivery.dropped);
+        let subscriptions: Vec<_> = self
+            .subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        tracing::debug!(
+            "Resuming {} subscriptions, replaying {} notifications ({} dropped)",
+            subscriptions.len(),
+            missed.len(),
+            dropped
+        );
+
+        // Answered through the sink like a subscription, so the answer is
+        // sent before the replay and not again by the server
+        let result = json!({ "subscriptions": subscriptions, "replayed": missed.len(), "dropped": dropped });
+        let response =
+            MethodResponse::subscription_response(id, ResponsePayload::success(result), sink.max_response_size() as usize);
+        if sink.send(response.to_json()).await.is_ok() {
+            for notification in missed {
+                if sink.send(notification).await.is_err() {
+                    break;
+                }
+            }
+            delivery.sink = Some(sink.clone());
+        }
+        drop(delivery);
+        self.watch(sink, attachment);
+        response
+    }
+
+    /// End the session `window` after `sink` closes, unless it is resumed meanwhile
+    fn watch(self: &Arc<Self>, sink: MethodSink, attachment: u64) {
+        let session = self.clone();
+        crate::task::spawn("plexus.websocket.resume", async move {
+            sink.closed().await;
+            tokio::time::sleep(session.config.window).await;
+            if session.attachment.load(Ordering::SeqCst) == attachment {
+                session.expire();
+            }
+        });
+    }
+
+    /// Forget the resume token and end the subscriptions
+    fn expire(&self) {
+        if let Some(resumption) = self.resumption.upgrade() {
+            resumption
+                .sessions
+                .lock()
+                .expect("sessions lock poisoned")
+                .remove(&self.token);
+        }
+        let ended = std::mem::take(&mut *self.subscriptions.lock().expect("subscriptions lock poisoned"));
+        if !ended.is_empty() {
+            tracing::debug!("Resume window passed; closing {} subscriptions", ended.len());
+        }
+    }
+}
+
+/// The subscription id in the answer accepting a subscription
+fn subscription_id(response: &MethodResponse) -> Option<SubscriptionId<'static>> {
+    let answer: serde_json::Value = serde_json::from_str(response.as_json().get()).ok()?;
+    SubscriptionId::deserialize(&answer["result"])
+        .ok()
+        .map(SubscriptionId::into_owned)
+}
+
+fn resume_failed(id: Id<'static>, message: &str) -> MethodResponse {
+    MethodResponse::error(id, ErrorObject::owned(RESUME_FAILED_ERROR_CODE, message, None::<()>))
+}
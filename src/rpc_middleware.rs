@@ -0,0 +1,141 @@
+//! Custom jsonrpsee RPC middleware for the WebSocket transport
+//!
+//! An [`RpcMiddleware`] wraps a jsonrpsee [`RpcServiceBuilder`] so it can be
+//! stored in [`WebSocketConfig`](crate::WebSocketConfig), which is not generic
+//! over the middleware stack. The layers see every call that passed the
+//! transport's authorization policy and rate limit, with the same request
+//! extensions (caller identity, peer address) as the built-in middleware.
+
+use std::fmt;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use jsonrpsee::core::middleware::{Batch, Notification, RpcServiceBuilder, RpcServiceT};
+use jsonrpsee::types::Request;
+use jsonrpsee::MethodResponse;
+
+/// Object-safe form of a server-side [`RpcServiceT`]
+trait DynRpcService: Send + Sync {
+    fn call<'a>(&self, request: Request<'a>) -> BoxFuture<'a, MethodResponse>;
+    fn batch<'a>(&self, batch: Batch<'a>) -> BoxFuture<'a, MethodResponse>;
+    fn notification<'a>(&self, n: Notification<'a>) -> BoxFuture<'a, MethodResponse>;
+}
+
+impl<S> DynRpcService for S
+where
+    S: RpcServiceT<
+            MethodResponse = MethodResponse,
+            NotificationResponse = MethodResponse,
+            BatchResponse = MethodResponse,
+        > + Send
+        + Sync,
+{
+    fn call<'a>(&self, request: Request<'a>) -> BoxFuture<'a, MethodResponse> {
+        Box::pin(RpcServiceT::call(self, request))
+    }
+
+    fn batch<'a>(&self, batch: Batch<'a>) -> BoxFuture<'a, MethodResponse> {
+        Box::pin(RpcServiceT::batch(self, batch))
+    }
+
+    fn notification<'a>(&self, n: Notification<'a>) -> BoxFuture<'a, MethodResponse> {
+        Box::pin(RpcServiceT::notification(self, n))
+    }
+}
+
+/// The RPC service custom middleware wraps
+///
+/// Layers passed to [`RpcMiddleware::new`] are applied to this type; middleware
+/// generic over any `RpcServiceT` works unchanged.
+#[derive(Clone)]
+pub struct BoxedRpcService(Arc<dyn DynRpcService>);
+
+impl BoxedRpcService {
+    fn new<S>(service: S) -> Self
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + 'static,
+    {
+        Self(Arc::new(service))
+    }
+}
+
+impl fmt::Debug for BoxedRpcService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoxedRpcService")
+    }
+}
+
+impl RpcServiceT for BoxedRpcService {
+    type MethodResponse = MethodResponse;
+    type NotificationResponse = MethodResponse;
+    type BatchResponse = MethodResponse;
+
+    fn call<'a>(&self, request: Request<'a>) -> impl std::future::Future<Output = MethodResponse> + Send + 'a {
+        self.0.call(request)
+    }
+
+    fn batch<'a>(&self, batch: Batch<'a>) -> impl std::future::Future<Output = MethodResponse> + Send + 'a {
+        self.0.batch(batch)
+    }
+
+    fn notification<'a>(&self, n: Notification<'a>) -> impl std::future::Future<Output = MethodResponse> + Send + 'a {
+        self.0.notification(n)
+    }
+}
+
+/// Per-call jsonrpsee middleware for the WebSocket transport
+///
+/// ```rust,ignore
+/// use jsonrpsee::core::middleware::RpcServiceBuilder;
+///
+/// let config = WebSocketConfig::new(4444)
+///     .with_rpc_middleware(RpcServiceBuilder::new().rpc_logger(1024));
+/// ```
+#[derive(Clone)]
+pub struct RpcMiddleware(Arc<dyn Fn(BoxedRpcService) -> BoxedRpcService + Send + Sync>);
+
+impl fmt::Debug for RpcMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RpcMiddleware")
+    }
+}
+
+impl RpcMiddleware {
+    /// Type-erase the layers of `builder`
+    pub fn new<L>(builder: RpcServiceBuilder<L>) -> Self
+    where
+        L: tower::Layer<BoxedRpcService> + Send + Sync + 'static,
+        L::Service: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + 'static,
+    {
+        Self(Arc::new(move |service| BoxedRpcService::new(builder.service(service))))
+    }
+}
+
+impl<S> tower::Layer<S> for RpcMiddleware
+where
+    S: RpcServiceT<
+            MethodResponse = MethodResponse,
+            NotificationResponse = MethodResponse,
+            BatchResponse = MethodResponse,
+        > + Send
+        + Sync
+        + 'static,
+{
+    type Service = BoxedRpcService;
+
+    fn layer(&self, service: S) -> BoxedRpcService {
+        (self.0)(BoxedRpcService::new(service))
+    }
+}
@@ -1,23 +1,66 @@
 //! Transport server builder and orchestration
 
 use anyhow::Result;
-use plexus_core::plexus::{Activation, PluginSchema, SessionValidator};
+use futures::future::BoxFuture;
+use plexus_core::plexus::{Activation, PlexusError, PlexusStream, PluginSchema};
+#[cfg(feature = "websocket")]
+use plexus_core::plexus::SessionValidator;
+use crate::auth::AuthorizationPolicy;
+use crate::mcp::timeout::ToolTimeouts;
+use crate::middleware::TransportMiddleware;
+#[cfg(feature = "websocket")]
 use jsonrpsee::server::ServerHandle;
 use jsonrpsee::RpcModule;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
-use crate::config::{McpHttpConfig, StdioConfig, TransportConfig, WebSocketConfig};
-use crate::mcp::bridge::RouteFn;
-use crate::mcp::server::serve_mcp_http;
-use crate::stdio::serve_stdio;
-use crate::websocket::serve_websocket;
+use crate::config::TransportConfig;
+#[cfg(feature = "mcp-http")]
+use crate::config::McpHttpConfig;
+#[cfg(feature = "stdio")]
+use crate::config::StdioConfig;
+#[cfg(feature = "websocket")]
+use crate::config::WebSocketConfig;
+use crate::hot_swap::ActivationSwap;
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+use crate::hot_swap::MethodSource;
+#[cfg(feature = "mcp-http")]
+use crate::mcp::server::serve_mcp_http_served;
+#[cfg(all(feature = "mcp-http", feature = "stdio"))]
+use crate::mcp::stdio::serve_mcp_stdio_served;
+use crate::mount::{Mount, MountedActivation};
+#[cfg(feature = "stdio")]
+use crate::stdio::serve_stdio_with_source;
+use crate::supervisor::RestartPolicy;
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+use crate::supervisor::{supervise, StartFn};
+#[cfg(feature = "websocket")]
+use crate::websocket::serve_websocket_with_source;
 
 /// Function type for converting Arc<Activation> to RpcModule
 ///
 /// This allows each activation type to provide its own conversion logic,
 /// which is critical for preserving Arc lifecycle and Weak references.
-pub type RpcConverter<A> = Box<dyn FnOnce(Arc<A>) -> Result<RpcModule<()>> + Send>;
+/// It first runs when a WebSocket or stdio transport starts, so servers with
+/// only MCP or REST transports never call it. The converter may be called
+/// more than once, e.g. when the activation is replaced with
+/// [`ActivationSwap::replace`].
+pub type RpcConverter<A> = Arc<dyn Fn(Arc<A>) -> Result<RpcModule<()>> + Send + Sync>;
+
+/// A function that routes a namespaced method call (e.g., "loopback.permit") to the
+/// correct activation. Used by hub activations to dispatch child calls via `hub.route()`.
+///
+/// Signature: `fn(method: String, params: Value) -> Future<Output = Result<PlexusStream, PlexusError>>`
+pub type RouteFn = Arc<
+    dyn Fn(String, serde_json::Value) -> Pin<Box<dyn Future<Output = Result<PlexusStream, PlexusError>> + Send>>
+        + Send
+        + Sync,
+>;
 
 /// Transport server that can host any Activation
 ///
@@ -26,17 +69,13 @@ pub type RpcConverter<A> = Box<dyn FnOnce(Arc<A>) -> Result<RpcModule<()>> + Sen
 /// - Stdio (line-delimited JSON-RPC, MCP-compatible)
 /// - MCP HTTP (with SSE streaming)
 pub struct TransportServer<A: Activation> {
-    activation: Arc<A>,
     config: TransportConfig,
-    rpc_converter: Option<RpcConverter<A>>,
-    /// Pre-computed flat schema list for MCP tool exposure.
-    /// When set, the MCP bridge exposes all listed schemas as tools.
-    mcp_flat_schemas: Option<Vec<PluginSchema>>,
-    /// Optional routing function for hub activations.
-    /// When set, MCP call_tool uses it to dispatch namespaced calls via hub.route().
-    mcp_route_fn: Option<RouteFn>,
+    /// The served activation, with its pre-computed flat schema list (MCP
+    /// tools) and routing function (hub dispatch); replaceable while serving.
+    swap: ActivationSwap<A>,
     /// Optional session validator for cookie-based authentication.
     /// When set, validates cookies from HTTP upgrade requests.
+    #[cfg(feature = "websocket")]
     session_validator: Option<Arc<dyn SessionValidator>>,
 }
 
@@ -44,153 +83,1272 @@ impl<A: Activation> TransportServer<A> {
     /// Create a builder for configuring transports
     pub fn builder<F>(activation: Arc<A>, rpc_converter: F) -> TransportServerBuilder<A>
     where
-        F: FnOnce(Arc<A>) -> Result<RpcModule<()>> + Send + 'static,
+        F: Fn(Arc<A>) -> Result<RpcModule<()>> + Send + Sync + 'static,
     {
         TransportServerBuilder::new(activation, rpc_converter)
     }
 
+    /// A handle for replacing the activation while the server runs
+    ///
+    /// Take it before calling one of the `serve` methods; see [`ActivationSwap`].
+    pub fn activation_swap(&self) -> ActivationSwap<A> {
+        self.swap.clone()
+    }
+
+    /// The handle for notifying every client, if one was configured
+    ///
+    /// See [`TransportServerBuilder::with_broadcaster`].
+    pub fn broadcaster(&self) -> Option<crate::broadcast::Broadcaster> {
+        self.config.broadcaster.clone()
+    }
+
     /// Start all configured transports
     ///
     /// If stdio is configured, this will block on stdio (as it's the primary transport).
     /// Otherwise, it will start WebSocket/MCP servers and wait for them to complete.
-    pub async fn serve(mut self) -> Result<()> {
-        // Convert activation to RPC module for WebSocket/stdio
-        let needs_rpc = self.config.websocket.is_some() || self.config.stdio.is_some();
-        let module = if needs_rpc {
-            let converter = self
-                .rpc_converter
-                .take()
-                .ok_or_else(|| anyhow::anyhow!("RPC converter required for WebSocket/stdio"))?;
-            Some(converter(self.activation.clone())?)
-        } else {
-            None
+    ///
+    /// Every listener is bound before any transport starts. If some cannot be
+    /// bound, nothing is started and the error is a [`BindError`] listing each
+    /// failed transport.
+    pub async fn serve(self) -> Result<()> {
+        self.serve_with_shutdown(CancellationToken::new()).await
+    }
+
+    /// Start all configured transports and run until `shutdown` is cancelled
+    ///
+    /// On cancellation every listener is closed and in-flight requests are
+    /// given up to the configured shutdown timeout (see
+    /// [`TransportServerBuilder::with_shutdown_timeout`]) to finish before the
+    /// remaining transport tasks are aborted. If one transport stops on its
+    /// own, the others are shut down the same way.
+    ///
+    /// Stdio has no listener to close; it stops reading as soon as `shutdown`
+    /// is cancelled, then finishes like at EOF: open subscriptions get the
+    /// configured EOF grace and queued output is flushed, within the shutdown
+    /// timeout.
+    pub async fn serve_with_shutdown(self, shutdown: CancellationToken) -> Result<()> {
+        self.start(shutdown).await?.wait().await
+    }
+
+    /// Start all configured transports in the background
+    ///
+    /// Returns as soon as every transport is listening. The [`RunningServer`]
+    /// exposes each transport's task handle and address; call
+    /// [`RunningServer::wait`] to observe the transports ending, or
+    /// [`RunningServer::shutdown`] to stop them gracefully.
+    pub async fn serve_detached(self) -> Result<RunningServer> {
+        self.start(CancellationToken::new()).await
+    }
+
+    async fn start(mut self, shutdown: CancellationToken) -> Result<RunningServer> {
+        check_features(&self.config)?;
+        let shutdown_timeout = self.config.shutdown_timeout;
+        if self.config.signal_shutdown {
+            spawn_signal_handler(shutdown.clone())?;
+        }
+
+        // The endpoint alone is enough to collect metrics
+        #[cfg(feature = "metrics")]
+        if self.config.metrics.is_none() && self.config.metrics_endpoint.is_some() {
+            self.config.metrics = Some(Arc::new(crate::metrics::TransportMetrics::new()));
+        }
+
+        #[cfg(feature = "mcp-http")]
+        let mcp: McpStartFn = {
+            let (swap, api_key) = (self.swap.clone(), self.config.api_key.clone());
+            Arc::new(move |config, listener, token| {
+                Box::pin(serve_mcp_http_served(
+                    swap.subscribe(),
+                    config,
+                    api_key.clone(),
+                    listener,
+                    token,
+                    Some(shutdown_timeout),
+                ))
+            })
+        };
+        #[cfg(feature = "http-gateway")]
+        let rest: RestStartFn = {
+            let (swap, api_key) = (self.swap.clone(), self.config.api_key.clone());
+            Arc::new(move |config, listener, token| {
+                Box::pin(crate::http::server::serve_rest_http_served(
+                    swap.subscribe(),
+                    config,
+                    api_key.clone(),
+                    listener,
+                    token,
+                ))
+            })
         };
 
-        // Start stdio transport (blocking)
-        if let Some(stdio_config) = self.config.stdio {
-            let module = module.expect("RPC module should be created for stdio");
-            return serve_stdio(module, stdio_config).await;
+        let factory = Arc::new(TransportFactory {
+            #[cfg(any(feature = "websocket", feature = "stdio"))]
+            methods: self.swap.method_source(),
+            #[cfg(any(feature = "websocket", feature = "stdio"))]
+            init_methods: {
+                let swap = self.swap.clone();
+                Box::new(move || swap.init_methods())
+            },
+            #[cfg(feature = "websocket")]
+            session_validator: self.session_validator.clone(),
+            #[cfg(feature = "websocket")]
+            api_key: self.config.api_key.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+            authorization: self.config.authorization.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+            middleware: self.config.middleware.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+            request_timeouts: self.config.request_timeouts.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+            audit: self.config.audit.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+            access_log: self.config.access_log.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+            observer: self.config.observer.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+            broadcaster: self.config.broadcaster.clone(),
+            #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+            capture: self.config.capture.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.config.metrics.clone(),
+            #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+            restart_policy: self.config.restart_policy.clone(),
+            #[cfg(feature = "mcp-http")]
+            mcp,
+            #[cfg(feature = "http-gateway")]
+            rest,
+        });
+
+        let mut running = RunningServer {
+            stdio: None,
+            #[cfg(feature = "websocket")]
+            websocket: None,
+            websocket_addr: None,
+            mcp_http: None,
+            mcp_http_addr: None,
+            rest_http: None,
+            rest_http_addr: None,
+            metrics: None,
+            metrics_addr: None,
+            shutdown: shutdown.clone(),
+            shutdown_timeout,
+            factory: factory.clone(),
+            #[cfg(feature = "websocket")]
+            websocket_config: None,
+            #[cfg(feature = "websocket")]
+            websocket_abort: None,
+            #[cfg(feature = "mcp-http")]
+            mcp_http_config: None,
+            #[cfg(feature = "mcp-http")]
+            mcp_http_token: None,
+            #[cfg(feature = "http-gateway")]
+            rest_http_config: None,
+            #[cfg(feature = "http-gateway")]
+            rest_http_token: None,
+            commands: None,
+            control: None,
+        };
+
+        // The metrics endpoint runs alongside every transport, stdio included
+        let failures = &mut Vec::new();
+        #[cfg(feature = "metrics")]
+        let metrics_endpoint = self.config.metrics_endpoint.take();
+        #[cfg(feature = "metrics")]
+        let metrics_listener = bind_listener("Metrics", metrics_endpoint.as_ref().map(|c| c.addr), failures).await;
+
+        // Stdio is the primary transport; when configured, nothing else runs
+        #[cfg(feature = "stdio")]
+        if let Some(mut stdio_config) = self.config.stdio.take() {
+            if !failures.is_empty() {
+                return Err(BindError { failures: std::mem::take(failures) }.into());
+            }
+            #[cfg(feature = "metrics")]
+            if let (Some(endpoint), Some(listener)) = (metrics_endpoint, metrics_listener) {
+                running.start_metrics_on(&endpoint, listener)?;
+            }
+            if stdio_config.authorization.is_none() {
+                stdio_config.authorization = self.config.authorization.clone();
+            }
+            if stdio_config.middleware.is_empty() {
+                stdio_config.middleware = self.config.middleware.clone();
+            }
+            if stdio_config.request_timeouts.is_empty() && stdio_config.request_timeout.is_none() {
+                stdio_config.request_timeouts = self.config.request_timeouts.clone();
+            }
+            if stdio_config.audit.is_none() {
+                stdio_config.audit = self.config.audit.clone();
+            }
+            if stdio_config.access_log.is_none() {
+                stdio_config.access_log = self.config.access_log.clone();
+            }
+            if stdio_config.observer.is_none() {
+                stdio_config.observer = self.config.observer.clone();
+            }
+            if stdio_config.capture.is_none() {
+                stdio_config.capture = self.config.capture.clone();
+            }
+            #[cfg(feature = "metrics")]
+            if stdio_config.metrics.is_none() {
+                stdio_config.metrics = self.config.metrics.clone();
+            }
+            #[cfg(feature = "mcp-http")]
+            if stdio_config.mcp {
+                let mut mcp = self.config.mcp_http.clone().unwrap_or_else(|| McpHttpConfig::new(0));
+                if mcp.broadcaster.is_none() {
+                    mcp.broadcaster = self.config.broadcaster.clone();
+                }
+                if mcp.tool_timeouts.is_empty() {
+                    mcp.tool_timeouts = self.config.request_timeouts.clone();
+                }
+                let serve = serve_mcp_stdio_served(self.swap.subscribe(), mcp, stdio_config, shutdown.child_token());
+                running.stdio = Some(crate::task::spawn("plexus.stdio", serve));
+                return Ok(running);
+            }
+            (factory.init_methods)()?;
+            let methods = factory.methods.clone();
+            let serve = serve_stdio_with_source(methods, stdio_config, shutdown.child_token());
+            running.stdio = Some(crate::task::spawn("plexus.stdio", serve));
+            return Ok(running);
+        }
+
+        // Bind every listener before starting anything, so a port conflict
+        // fails startup as a whole instead of leaving other transports running
+        #[cfg(feature = "websocket")]
+        let ws_config = self.config.websocket.take();
+        #[cfg(feature = "mcp-http")]
+        let mcp_config = self.config.mcp_http.take();
+        #[cfg(feature = "http-gateway")]
+        let rest_config = self.config.rest_http.take();
+
+        #[cfg(feature = "websocket")]
+        let ws_listener = bind_listener("WebSocket", ws_config.as_ref().map(|c| c.addr), failures).await;
+        #[cfg(feature = "mcp-http")]
+        let mcp_listener = bind_listener("MCP HTTP", mcp_config.as_ref().map(|c| c.addr), failures).await;
+        #[cfg(feature = "http-gateway")]
+        let rest_listener = bind_listener("REST HTTP", rest_config.as_ref().map(|c| c.addr), failures).await;
+        if !failures.is_empty() {
+            return Err(BindError { failures: std::mem::take(failures) }.into());
+        }
+
+        let started: Result<()> = async {
+            #[cfg(feature = "metrics")]
+            if let (Some(endpoint), Some(listener)) = (metrics_endpoint, metrics_listener) {
+                running.start_metrics_on(&endpoint, listener)?;
+            }
+            #[cfg(feature = "websocket")]
+            if let (Some(config), Some(listener)) = (ws_config, ws_listener) {
+                running.start_websocket_on(config, listener).await?;
+            }
+            #[cfg(feature = "mcp-http")]
+            if let (Some(config), Some(listener)) = (mcp_config, mcp_listener) {
+                running.start_mcp_http_on(config, listener).await?;
+            }
+            #[cfg(feature = "http-gateway")]
+            if let (Some(config), Some(listener)) = (rest_config, rest_listener) {
+                running.start_rest_http_on(config, listener).await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        // Don't leave earlier transports running when a later one fails to start
+        if let Err(e) = started {
+            running.abort();
+            return Err(e);
         }
 
-        // Start WebSocket transport
-        let ws_handle: Option<ServerHandle> = if let Some(mut ws_config) = self.config.websocket {
-            // Propagate the global api_key to the WebSocket config if not already set.
-            if ws_config.api_key.is_none() {
-                ws_config.api_key = self.config.api_key.clone();
+        Ok(running)
+    }
+}
+
+/// Starts MCP HTTP on a bound listener until the token is cancelled
+#[cfg(feature = "mcp-http")]
+type McpStartFn = Arc<
+    dyn Fn(
+            McpHttpConfig,
+            tokio::net::TcpListener,
+            CancellationToken,
+        ) -> BoxFuture<'static, Result<TransportTask>>
+        + Send
+        + Sync,
+>;
+
+/// Starts REST HTTP on a bound listener until the token is cancelled
+#[cfg(feature = "http-gateway")]
+type RestStartFn = Arc<
+    dyn Fn(
+            crate::config::RestHttpConfig,
+            tokio::net::TcpListener,
+            CancellationToken,
+        ) -> BoxFuture<'static, Result<TransportTask>>
+        + Send
+        + Sync,
+>;
+
+/// What a [`RunningServer`] needs to start transports after startup
+///
+/// Holds the source of RPC methods, the server-wide settings each transport
+/// inherits, and the activation (captured by the start functions).
+struct TransportFactory {
+    #[cfg(any(feature = "websocket", feature = "stdio"))]
+    methods: MethodSource,
+    /// Converts the activation to RPC methods once a transport needs them,
+    /// unless a swap already has; MCP and REST never do
+    #[cfg(any(feature = "websocket", feature = "stdio"))]
+    init_methods: Box<dyn Fn() -> Result<()> + Send + Sync>,
+    #[cfg(feature = "websocket")]
+    session_validator: Option<Arc<dyn SessionValidator>>,
+    #[cfg(feature = "websocket")]
+    api_key: Option<String>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    authorization: Option<Arc<dyn AuthorizationPolicy>>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    middleware: Vec<Arc<dyn TransportMiddleware>>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    request_timeouts: ToolTimeouts,
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    audit: Option<Arc<crate::audit::Auditor>>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    access_log: Option<Arc<crate::access_log::AccessLog>>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+    observer: Option<Arc<dyn crate::observer::TransportObserver>>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+    broadcaster: Option<crate::broadcast::Broadcaster>,
+    #[cfg(any(feature = "websocket", feature = "mcp-http"))]
+    capture: Option<Arc<crate::capture::Capture>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<crate::metrics::TransportMetrics>>,
+    #[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+    restart_policy: Option<RestartPolicy>,
+    #[cfg(feature = "mcp-http")]
+    mcp: McpStartFn,
+    #[cfg(feature = "http-gateway")]
+    rest: RestStartFn,
+}
+
+#[cfg(any(feature = "mcp-http", feature = "http-gateway"))]
+impl TransportFactory {
+    /// Start a transport task, supervised when a restart policy is set
+    fn supervised<C>(
+        &self,
+        transport: &'static str,
+        addr: SocketAddr,
+        task: TransportTask,
+        token: CancellationToken,
+        config: C,
+        start: Arc<
+            dyn Fn(
+                    C,
+                    tokio::net::TcpListener,
+                    CancellationToken,
+                ) -> BoxFuture<'static, Result<TransportTask>>
+                + Send
+                + Sync,
+        >,
+    ) -> TransportTask
+    where
+        C: Clone + Send + Sync + 'static,
+    {
+        match self.restart_policy.clone() {
+            Some(policy) => {
+                let restart_token = token.clone();
+                let restart: StartFn = Box::new(move |listener| {
+                    start(config.clone(), listener, restart_token.clone())
+                });
+                supervise(transport, addr, task, policy, token, restart)
             }
-            let module = module.expect("RPC module should be created for WebSocket");
-            Some(serve_websocket(module, ws_config, self.session_validator.clone()).await?)
-        } else {
+            None => task,
+        }
+    }
+}
+
+/// Fail on transports that are configured but were not compiled in
+fn check_features(config: &TransportConfig) -> Result<()> {
+    let transports = [
+        ("WebSocket", "websocket", config.websocket.is_some(), cfg!(feature = "websocket")),
+        ("Stdio", "stdio", config.stdio.is_some(), cfg!(feature = "stdio")),
+        ("MCP HTTP", "mcp-http", config.mcp_http.is_some(), cfg!(feature = "mcp-http")),
+        ("REST HTTP", "http-gateway", config.rest_http.is_some(), cfg!(feature = "http-gateway")),
+    ];
+    for (transport, feature, configured, compiled) in transports {
+        anyhow::ensure!(
+            !configured || compiled,
+            "{} transport is configured but plexus-transport was built without the `{}` feature",
+            transport,
+            feature
+        );
+    }
+    anyhow::ensure!(
+        !config.stdio.as_ref().is_some_and(|stdio| stdio.mcp) || cfg!(feature = "mcp-http"),
+        "MCP over stdio is configured but plexus-transport was built without the `mcp-http` feature"
+    );
+    Ok(())
+}
+
+/// Bind a transport's listener, recording the failure if it cannot be bound
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway", feature = "metrics"))]
+async fn bind_listener(
+    transport: &'static str,
+    addr: Option<SocketAddr>,
+    failures: &mut Vec<BindFailure>,
+) -> Option<tokio::net::TcpListener> {
+    let addr = addr?;
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => Some(listener),
+        Err(error) => {
+            tracing::error!("{} transport cannot listen on {}: {}", transport, addr, error);
+            failures.push(BindFailure { transport, addr, error });
             None
-        };
+        }
+    }
+}
 
-        // Start MCP HTTP transport
-        let mcp_handle: Option<JoinHandle<std::result::Result<(), std::io::Error>>> =
-            if let Some(mcp_config) = self.config.mcp_http {
-                let api_key = self.config.api_key.clone();
-                Some(serve_mcp_http(self.activation.clone(), self.mcp_flat_schemas.clone(), self.mcp_route_fn.clone(), mcp_config, api_key).await?)
-            } else {
-                None
-            };
+/// Bind a listener for a transport started on a live server
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+async fn bind(transport: &'static str, addr: SocketAddr) -> Result<tokio::net::TcpListener> {
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|error| BindError { failures: vec![BindFailure { transport, addr, error }] }.into())
+}
 
-        // Start REST HTTP transport
-        #[cfg(feature = "http-gateway")]
-        let rest_handle: Option<JoinHandle<std::result::Result<(), std::io::Error>>> =
-            if let Some(rest_config) = self.config.rest_http {
-                let api_key = self.config.api_key.clone();
-                Some(crate::http::serve_rest_http(self.activation.clone(), self.mcp_flat_schemas.clone(), self.mcp_route_fn.clone(), rest_config, api_key).await?)
+/// Wait up to `timeout` for a stopping transport task, then abort it
+async fn drain(name: &str, task: TransportTask, timeout: Duration) {
+    let abort = task.abort_handle();
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => log_exit(name, result),
+        Err(_) => {
+            tracing::warn!("{} server still busy after {:?}; aborting remaining requests", name, timeout);
+            abort.abort();
+        }
+    }
+}
+
+/// A transport whose listener could not be bound
+#[derive(Debug)]
+pub struct BindFailure {
+    /// Transport name, e.g. `"MCP HTTP"`
+    pub transport: &'static str,
+    /// Configured listen address
+    pub addr: SocketAddr,
+    /// Why binding failed (address in use, permission denied, ...)
+    pub error: std::io::Error,
+}
+
+/// Startup error listing every transport that could not bind its listener
+///
+/// Returned (inside `anyhow::Error`) by [`TransportServer::serve`] and friends
+/// before any transport has started.
+#[derive(Debug)]
+pub struct BindError {
+    /// One entry per transport that failed to bind
+    pub failures: Vec<BindFailure>,
+}
+
+impl std::error::Error for BindError {}
+
+impl std::fmt::Display for BindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to bind transport listeners: ")?;
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{} on {}: {}", failure.transport, failure.addr, failure.error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Handle of a transport task: the server's result, or a join error if it panicked
+pub type TransportTask = JoinHandle<std::result::Result<(), std::io::Error>>;
+
+/// Transports started by [`TransportServer::serve_detached`]
+///
+/// Each configured transport has its task handle (`ServerHandle` for WebSocket)
+/// and the address it actually listens on, so a transport configured with
+/// port 0 reports its OS-assigned port. Fields for transports that are not
+/// configured are `None`. A handle taken out of the struct is no longer
+/// awaited or drained by [`RunningServer::wait`].
+pub struct RunningServer {
+    /// Stdio transport; ends at EOF on stdin
+    pub stdio: Option<JoinHandle<Result<()>>>,
+    /// WebSocket server handle
+    #[cfg(feature = "websocket")]
+    pub websocket: Option<ServerHandle>,
+    /// Address the WebSocket server listens on
+    pub websocket_addr: Option<SocketAddr>,
+    /// MCP HTTP server task
+    pub mcp_http: Option<TransportTask>,
+    /// Address the MCP HTTP server listens on
+    pub mcp_http_addr: Option<SocketAddr>,
+    /// REST HTTP server task
+    pub rest_http: Option<TransportTask>,
+    /// Address the REST HTTP server listens on
+    pub rest_http_addr: Option<SocketAddr>,
+    /// Metrics endpoint task (feature `metrics`)
+    pub metrics: Option<TransportTask>,
+    /// Address the metrics endpoint listens on
+    pub metrics_addr: Option<SocketAddr>,
+    shutdown: CancellationToken,
+    shutdown_timeout: Duration,
+    factory: Arc<TransportFactory>,
+    #[cfg(feature = "websocket")]
+    websocket_config: Option<WebSocketConfig>,
+    /// Drops the WebSocket connections still open after the drain timeout
+    #[cfg(feature = "websocket")]
+    websocket_abort: Option<CancellationToken>,
+    #[cfg(feature = "mcp-http")]
+    mcp_http_config: Option<McpHttpConfig>,
+    #[cfg(feature = "mcp-http")]
+    mcp_http_token: Option<CancellationToken>,
+    #[cfg(feature = "http-gateway")]
+    rest_http_config: Option<crate::config::RestHttpConfig>,
+    #[cfg(feature = "http-gateway")]
+    rest_http_token: Option<CancellationToken>,
+    /// Changes requested through [`ServerControl`]s, carried out by `wait`
+    commands: Option<tokio::sync::mpsc::Receiver<Command>>,
+    /// Cloned for each [`RunningServer::control`] call
+    control: Option<ServerControl>,
+}
+
+impl RunningServer {
+    /// The token that starts a graceful shutdown when cancelled
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Start the WebSocket transport on a live server
+    ///
+    /// Server-wide settings (API key, authorization policy, audit log, session
+    /// validator) apply as they do at startup. Returns the bound address.
+    #[cfg(feature = "websocket")]
+    pub async fn start_websocket(&mut self, config: WebSocketConfig) -> Result<SocketAddr> {
+        anyhow::ensure!(self.websocket.is_none(), "WebSocket transport is already running");
+        let listener = bind("WebSocket", config.addr).await?;
+        self.start_websocket_on(config, listener).await
+    }
+
+    /// Stop the WebSocket transport, letting open connections finish their
+    /// in-flight calls for up to the shutdown timeout, then dropping them;
+    /// other transports keep running
+    #[cfg(feature = "websocket")]
+    pub async fn stop_websocket(&mut self) {
+        if let Some(ws) = self.websocket.take() {
+            self.websocket_addr = None;
+            let abort = self.websocket_abort.take();
+            let _ = ws.stop();
+            if tokio::time::timeout(self.shutdown_timeout, ws.stopped()).await.is_err() {
+                tracing::warn!("WebSocket server still busy after {:?}; aborting remaining connections", self.shutdown_timeout);
+                if let Some(abort) = abort {
+                    abort.cancel();
+                }
             } else {
-                None
-            };
+                tracing::info!("WebSocket server stopped");
+            }
+        }
+    }
+
+    /// Stop and start the WebSocket transport again on the same address
+    #[cfg(feature = "websocket")]
+    pub async fn restart_websocket(&mut self) -> Result<SocketAddr> {
+        let mut config = self
+            .websocket_config
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("WebSocket transport was never started"))?;
+        if let Some(addr) = self.websocket_addr {
+            config.addr = addr;
+        }
+        self.stop_websocket().await;
+        self.start_websocket(config).await
+    }
+
+    /// Start the MCP HTTP transport on a live server
+    ///
+    /// Server-wide settings apply as they do at startup. Returns the bound address.
+    #[cfg(feature = "mcp-http")]
+    pub async fn start_mcp_http(&mut self, config: McpHttpConfig) -> Result<SocketAddr> {
+        anyhow::ensure!(self.mcp_http.is_none(), "MCP HTTP transport is already running");
+        let listener = bind("MCP HTTP", config.addr).await?;
+        self.start_mcp_http_on(config, listener).await
+    }
 
-        #[cfg(not(feature = "http-gateway"))]
-        let rest_handle: Option<JoinHandle<std::result::Result<(), std::io::Error>>> = None;
+    /// Stop the MCP HTTP transport, draining in-flight requests for up to the
+    /// shutdown timeout; other transports keep running
+    #[cfg(feature = "mcp-http")]
+    pub async fn stop_mcp_http(&mut self) {
+        if let Some(task) = self.mcp_http.take() {
+            self.mcp_http_addr = None;
+            if let Some(token) = self.mcp_http_token.take() {
+                token.cancel();
+            }
+            drain("MCP", task, self.shutdown_timeout).await;
+        }
+    }
 
-        // Wait for any server to complete
-        if ws_handle.is_none() && mcp_handle.is_none() && rest_handle.is_none() {
-            tracing::warn!("No transports configured, nothing to serve");
-            return Ok(());
+    /// Stop and start the MCP HTTP transport again on the same address
+    #[cfg(feature = "mcp-http")]
+    pub async fn restart_mcp_http(&mut self) -> Result<SocketAddr> {
+        let mut config = self
+            .mcp_http_config
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("MCP HTTP transport was never started"))?;
+        if let Some(addr) = self.mcp_http_addr {
+            config.addr = addr;
         }
+        self.stop_mcp_http().await;
+        self.start_mcp_http(config).await
+    }
 
-        // Wait for first server to stop
-        tokio::select! {
-            _ = async {
-                if let Some(ws) = ws_handle {
-                    ws.stopped().await;
-                    tracing::info!("WebSocket server stopped");
+    /// Start the REST HTTP transport on a live server
+    ///
+    /// Server-wide settings apply as they do at startup. Returns the bound address.
+    #[cfg(feature = "http-gateway")]
+    pub async fn start_rest_http(&mut self, config: crate::config::RestHttpConfig) -> Result<SocketAddr> {
+        anyhow::ensure!(self.rest_http.is_none(), "REST HTTP transport is already running");
+        let listener = bind("REST HTTP", config.addr).await?;
+        self.start_rest_http_on(config, listener).await
+    }
+
+    /// Stop the REST HTTP transport, draining in-flight requests for up to the
+    /// shutdown timeout; other transports keep running
+    #[cfg(feature = "http-gateway")]
+    pub async fn stop_rest_http(&mut self) {
+        if let Some(task) = self.rest_http.take() {
+            self.rest_http_addr = None;
+            if let Some(token) = self.rest_http_token.take() {
+                token.cancel();
+            }
+            drain("REST", task, self.shutdown_timeout).await;
+        }
+    }
+
+    /// Stop and start the REST HTTP transport again on the same address
+    #[cfg(feature = "http-gateway")]
+    pub async fn restart_rest_http(&mut self) -> Result<SocketAddr> {
+        let mut config = self
+            .rest_http_config
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("REST HTTP transport was never started"))?;
+        if let Some(addr) = self.rest_http_addr {
+            config.addr = addr;
+        }
+        self.stop_rest_http().await;
+        self.start_rest_http(config).await
+    }
+
+    #[cfg(feature = "websocket")]
+    async fn start_websocket_on(
+        &mut self,
+        config: WebSocketConfig,
+        listener: tokio::net::TcpListener,
+    ) -> Result<SocketAddr> {
+        let addr = listener.local_addr()?;
+        (self.factory.init_methods)()?;
+        let methods = self.factory.methods.clone();
+        self.websocket_config = Some(config.clone());
+
+        let mut config = config;
+        // Propagate the global api_key to the WebSocket config if not already set.
+        if config.api_key.is_none() {
+            config.api_key = self.factory.api_key.clone();
+        }
+        if config.authorization.is_none() {
+            config.authorization = self.factory.authorization.clone();
+        }
+        if config.middleware.is_empty() {
+            config.middleware = self.factory.middleware.clone();
+        }
+        if config.request_timeouts.is_empty() {
+            config.request_timeouts = self.factory.request_timeouts.clone();
+        }
+        if config.audit.is_none() {
+            config.audit = self.factory.audit.clone();
+        }
+        if config.access_log.is_none() {
+            config.access_log = self.factory.access_log.clone();
+        }
+        if config.observer.is_none() {
+            config.observer = self.factory.observer.clone();
+        }
+        if config.broadcaster.is_none() {
+            config.broadcaster = self.factory.broadcaster.clone();
+        }
+        if config.capture.is_none() {
+            config.capture = self.factory.capture.clone();
+        }
+        #[cfg(feature = "metrics")]
+        if config.metrics.is_none() {
+            config.metrics = self.factory.metrics.clone();
+        }
+        // Close busy connections before the server stops waiting for them
+        config.drain_timeout = config.drain_timeout.min(self.shutdown_timeout);
+        let abort = CancellationToken::new();
+        let handle = serve_websocket_with_source(
+            listener,
+            methods,
+            config,
+            self.factory.session_validator.clone(),
+            abort.clone(),
+        )
+        .await?;
+        self.websocket = Some(handle);
+        self.websocket_addr = Some(addr);
+        self.websocket_abort = Some(abort);
+        Ok(addr)
+    }
+
+    #[cfg(feature = "mcp-http")]
+    async fn start_mcp_http_on(
+        &mut self,
+        config: McpHttpConfig,
+        listener: tokio::net::TcpListener,
+    ) -> Result<SocketAddr> {
+        let addr = listener.local_addr()?;
+        self.mcp_http_config = Some(config.clone());
+
+        let mut config = config;
+        if config.authorization.is_none() {
+            config.authorization = self.factory.authorization.clone();
+        }
+        if config.middleware.is_empty() {
+            config.middleware = self.factory.middleware.clone();
+        }
+        if config.tool_timeouts.is_empty() {
+            config.tool_timeouts = self.factory.request_timeouts.clone();
+        }
+        if config.audit.is_none() {
+            config.audit = self.factory.audit.clone();
+        }
+        if config.access_log.is_none() {
+            config.access_log = self.factory.access_log.clone();
+        }
+        if config.observer.is_none() {
+            config.observer = self.factory.observer.clone();
+        }
+        if config.broadcaster.is_none() {
+            config.broadcaster = self.factory.broadcaster.clone();
+        }
+        if config.capture.is_none() {
+            config.capture = self.factory.capture.clone();
+        }
+        #[cfg(feature = "metrics")]
+        if config.metrics.is_none() {
+            config.metrics = self.factory.metrics.clone();
+        }
+        let token = self.shutdown.child_token();
+        let task = (self.factory.mcp)(config.clone(), listener, token.clone()).await?;
+        let task = self.factory.supervised(
+            "MCP HTTP",
+            addr,
+            task,
+            token.clone(),
+            config,
+            self.factory.mcp.clone(),
+        );
+        self.mcp_http = Some(task);
+        self.mcp_http_addr = Some(addr);
+        self.mcp_http_token = Some(token);
+        Ok(addr)
+    }
+
+    #[cfg(feature = "http-gateway")]
+    async fn start_rest_http_on(
+        &mut self,
+        config: crate::config::RestHttpConfig,
+        listener: tokio::net::TcpListener,
+    ) -> Result<SocketAddr> {
+        let addr = listener.local_addr()?;
+        self.rest_http_config = Some(config.clone());
+
+        let mut config = config;
+        if config.authorization.is_none() {
+            config.authorization = self.factory.authorization.clone();
+        }
+        if config.middleware.is_empty() {
+            config.middleware = self.factory.middleware.clone();
+        }
+        if config.request_timeouts.is_empty() {
+            config.request_timeouts = self.factory.request_timeouts.clone();
+        }
+        if config.audit.is_none() {
+            config.audit = self.factory.audit.clone();
+        }
+        if config.access_log.is_none() {
+            config.access_log = self.factory.access_log.clone();
+        }
+        #[cfg(feature = "metrics")]
+        if config.metrics.is_none() {
+            config.metrics = self.factory.metrics.clone();
+        }
+        let token = self.shutdown.child_token();
+        let task = (self.factory.rest)(config.clone(), listener, token.clone()).await?;
+        let task = self.factory.supervised(
+            "REST HTTP",
+            addr,
+            task,
+            token.clone(),
+            config,
+            self.factory.rest.clone(),
+        );
+        self.rest_http = Some(task);
+        self.rest_http_addr = Some(addr);
+        self.rest_http_token = Some(token);
+        Ok(addr)
+    }
+
+    #[cfg(feature = "metrics")]
+    fn start_metrics_on(
+        &mut self,
+        endpoint: &crate::metrics::MetricsConfig,
+        listener: tokio::net::TcpListener,
+    ) -> Result<SocketAddr> {
+        let addr = listener.local_addr()?;
+        let metrics = self
+            .factory
+            .metrics
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Metrics endpoint configured without metrics"))?;
+        let token = self.shutdown.child_token();
+        self.metrics = Some(crate::metrics::serve_metrics(metrics, endpoint, listener, token));
+        self.metrics_addr = Some(addr);
+        Ok(addr)
+    }
+
+    /// Stop every started transport immediately, without draining
+    fn abort(&self) {
+        self.shutdown.cancel();
+        #[cfg(feature = "websocket")]
+        if let Some(ref ws) = self.websocket {
+            let _ = ws.stop();
+        }
+        #[cfg(feature = "websocket")]
+        if let Some(ref abort) = self.websocket_abort {
+            abort.cancel();
+        }
+        for task in self.mcp_http.iter().chain(self.rest_http.iter()).chain(self.metrics.iter()) {
+            task.abort();
+        }
+    }
+
+    /// Stop all transports gracefully and wait for them to drain
+    pub async fn shutdown(self) -> Result<()> {
+        self.shutdown.cancel();
+        self.wait().await
+    }
+
+    /// Wait until shutdown is requested or a transport stops, then drain
+    ///
+    /// When one transport stops on its own, the others are shut down too.
+    /// Transports stopped through a [`ServerControl`] do not end the wait.
+    /// Returns the stdio transport's error, if it failed; failures of the
+    /// network transports are logged.
+    pub async fn wait(mut self) -> Result<()> {
+        // Only handles taken with `control` keep the channel open
+        let mut commands = self.commands.take();
+        self.control = None;
+
+        // Run until shutdown is requested or the first transport stops
+        let mut result = Ok(());
+        loop {
+            if commands.is_none() && self.stdio.is_none() && !self.serving() {
+                tracing::warn!("No transports running, nothing to serve");
+                break;
+            }
+
+            #[cfg(feature = "websocket")]
+            let ws_stopped = self.websocket.clone().map(ServerHandle::stopped);
+            #[cfg(not(feature = "websocket"))]
+            let ws_stopped: Option<std::future::Pending<()>> = None;
+            let stdio = self.stdio.as_mut();
+            let mcp = self.mcp_http.as_mut();
+            let rest = self.rest_http.as_mut();
+
+            tokio::select! {
+                _ = self.shutdown.cancelled() => {
+                    tracing::info!("Shutdown requested, draining transports");
+                    break;
                 }
-            }, if ws_handle.is_some() => {}
-
-            _ = async {
-                if let Some(mcp) = mcp_handle {
-                    match mcp.await {
-                        Ok(Ok(())) => tracing::info!("MCP server stopped"),
-                        Ok(Err(e)) => tracing::error!("MCP server error: {}", e),
-                        Err(e) => tracing::error!("MCP server task failed: {}", e),
-                    }
+
+                command = next_command(&mut commands) => match command {
+                    Some(command) => command(&mut self).await,
+                    None => commands = None,
+                },
+
+                joined = async { stdio.expect("checked").await }, if stdio.is_some() => {
+                    self.stdio = None;
+                    result = stdio_exit(joined);
+                    break;
                 }
-            }, if mcp_handle.is_some() => {}
-
-            _ = async {
-                if let Some(rest) = rest_handle {
-                    match rest.await {
-                        Ok(Ok(())) => tracing::info!("REST server stopped"),
-                        Ok(Err(e)) => tracing::error!("REST server error: {}", e),
-                        Err(e) => tracing::error!("REST server task failed: {}", e),
+
+                _ = async { ws_stopped.expect("checked").await }, if ws_stopped.is_some() => {
+                    tracing::info!("WebSocket server stopped");
+                    #[cfg(feature = "websocket")]
+                    {
+                        self.websocket = None;
                     }
+                    break;
+                }
+
+                joined = async { mcp.expect("checked").await }, if mcp.is_some() => {
+                    self.mcp_http = None;
+                    log_exit("MCP", joined);
+                    break;
+                }
+
+                joined = async { rest.expect("checked").await }, if rest.is_some() => {
+                    self.rest_http = None;
+                    log_exit("REST", joined);
+                    break;
                 }
-            }, if rest_handle.is_some() => {}
+            }
         }
 
-        Ok(())
+        // Stop the remaining transports and wait for their in-flight requests;
+        // stdio stops reading and drains its subscriptions and output
+        self.shutdown.cancel();
+        #[cfg(feature = "websocket")]
+        if let Some(ref ws) = self.websocket {
+            let _ = ws.stop();
+        }
+        let RunningServer {
+            stdio,
+            #[cfg(feature = "websocket")]
+            websocket,
+            #[cfg(feature = "websocket")]
+            websocket_abort,
+            mcp_http,
+            rest_http,
+            metrics,
+            shutdown_timeout,
+            ..
+        } = self;
+        let aborts: Vec<_> = mcp_http
+            .iter()
+            .chain(rest_http.iter())
+            .chain(metrics.iter())
+            .map(|handle| handle.abort_handle())
+            .chain(stdio.as_ref().map(|handle| handle.abort_handle()))
+            .collect();
+        let drain = async {
+            #[cfg(feature = "websocket")]
+            if let Some(ws) = websocket {
+                ws.stopped().await;
+            }
+            let stdio = match stdio {
+                Some(stdio) => stdio_exit(stdio.await),
+                None => Ok(()),
+            };
+            if let Some(mcp) = mcp_http {
+                log_exit("MCP", mcp.await);
+            }
+            if let Some(rest) = rest_http {
+                log_exit("REST", rest.await);
+            }
+            if let Some(metrics) = metrics {
+                log_exit("Metrics", metrics.await);
+            }
+            stdio
+        };
+        match tokio::time::timeout(shutdown_timeout, drain).await {
+            Ok(stdio) => result.and(stdio),
+            Err(_) => {
+                tracing::warn!(
+                    "Transports still busy after {:?}; aborting remaining requests",
+                    shutdown_timeout
+                );
+                for abort in aborts {
+                    abort.abort();
+                }
+                #[cfg(feature = "websocket")]
+                if let Some(abort) = websocket_abort {
+                    abort.cancel();
+                }
+                result
+            }
+        }
+    }
+
+    /// A handle for starting and stopping transports while another task waits
+    ///
+    /// [`wait`](Self::wait) takes the server, so transports cannot be started
+    /// on it directly once it is waited on; a `ServerControl` (cheap to clone)
+    /// hands the change to the waiting task instead. Changes requested before
+    /// `wait` is called are carried out when it is.
+    pub fn control(&mut self) -> ServerControl {
+        if let Some(ref control) = self.control {
+            return control.clone();
+        }
+        let (sender, receiver) = tokio::sync::mpsc::channel(CONTROL_QUEUE);
+        let control = ServerControl { commands: sender };
+        self.commands = Some(receiver);
+        self.control = Some(control.clone());
+        control
+    }
+
+    /// Whether a network transport is running
+    fn serving(&self) -> bool {
+        #[cfg(feature = "websocket")]
+        let ws_running = self.websocket.is_some();
+        #[cfg(not(feature = "websocket"))]
+        let ws_running = false;
+        ws_running || self.mcp_http.is_some() || self.rest_http.is_some()
+    }
+}
+
+/// Changes requested of a waiting [`RunningServer`] that are queued before
+/// senders wait for room
+const CONTROL_QUEUE: usize = 16;
+
+/// A change to a [`RunningServer`], carried out by the task waiting on it
+type Command = Box<dyn for<'a> FnOnce(&'a mut RunningServer) -> BoxFuture<'a, ()> + Send>;
+
+/// A [`Command`] carrying out `change`
+#[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+fn command<F>(change: F) -> Command
+where
+    F: for<'a> FnOnce(&'a mut RunningServer) -> BoxFuture<'a, ()> + Send + 'static,
+{
+    Box::new(change)
+}
+
+/// The next change requested through a [`ServerControl`]; never resolves
+/// without a control handle, and resolves to `None` once all are dropped
+async fn next_command(commands: &mut Option<tokio::sync::mpsc::Receiver<Command>>) -> Option<Command> {
+    match commands {
+        Some(commands) => commands.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// The stdio transport's result, from its task's
+fn stdio_exit(joined: std::result::Result<Result<()>, tokio::task::JoinError>) -> Result<()> {
+    joined.map_err(|e| anyhow::anyhow!("Stdio task failed: {}", e)).and_then(|r| r)
+}
+
+/// Starts and stops the transports of a [`RunningServer`] from other tasks
+///
+/// Obtained from [`RunningServer::control`]. Each method asks the task in
+/// [`RunningServer::wait`] to call the `RunningServer` method of the same
+/// name and returns its result. Once the server has stopped, they fail.
+///
+/// ```rust,ignore
+/// let mut running = server.serve_detached().await?;
+/// let control = running.control();
+/// tokio::spawn(async move {
+///     operator_approval().await;
+///     control.start_mcp_http(McpHttpConfig::new(4445)).await
+/// });
+/// running.wait().await?;
+/// ```
+#[derive(Clone)]
+pub struct ServerControl {
+    commands: tokio::sync::mpsc::Sender<Command>,
+}
+
+impl std::fmt::Debug for ServerControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerControl")
+            .field("stopped", &self.commands.is_closed())
+            .finish()
+    }
+}
+
+impl ServerControl {
+    /// Start the WebSocket transport; see [`RunningServer::start_websocket`]
+    #[cfg(feature = "websocket")]
+    pub async fn start_websocket(&self, config: WebSocketConfig) -> Result<SocketAddr> {
+        self.run(move |server| Box::pin(server.start_websocket(config))).await?
+    }
+
+    /// Stop the WebSocket transport; see [`RunningServer::stop_websocket`]
+    #[cfg(feature = "websocket")]
+    pub async fn stop_websocket(&self) -> Result<()> {
+        self.run(|server| Box::pin(server.stop_websocket())).await
+    }
+
+    /// Restart the WebSocket transport; see [`RunningServer::restart_websocket`]
+    #[cfg(feature = "websocket")]
+    pub async fn restart_websocket(&self) -> Result<SocketAddr> {
+        self.run(|server| Box::pin(server.restart_websocket())).await?
+    }
+
+    /// Start the MCP HTTP transport; see [`RunningServer::start_mcp_http`]
+    #[cfg(feature = "mcp-http")]
+    pub async fn start_mcp_http(&self, config: McpHttpConfig) -> Result<SocketAddr> {
+        self.run(move |server| Box::pin(server.start_mcp_http(config))).await?
+    }
+
+    /// Stop the MCP HTTP transport; see [`RunningServer::stop_mcp_http`]
+    #[cfg(feature = "mcp-http")]
+    pub async fn stop_mcp_http(&self) -> Result<()> {
+        self.run(|server| Box::pin(server.stop_mcp_http())).await
+    }
+
+    /// Restart the MCP HTTP transport; see [`RunningServer::restart_mcp_http`]
+    #[cfg(feature = "mcp-http")]
+    pub async fn restart_mcp_http(&self) -> Result<SocketAddr> {
+        self.run(|server| Box::pin(server.restart_mcp_http())).await?
+    }
+
+    /// Start the REST HTTP transport; see [`RunningServer::start_rest_http`]
+    #[cfg(feature = "http-gateway")]
+    pub async fn start_rest_http(&self, config: crate::config::RestHttpConfig) -> Result<SocketAddr> {
+        self.run(move |server| Box::pin(server.start_rest_http(config))).await?
+    }
+
+    /// Stop the REST HTTP transport; see [`RunningServer::stop_rest_http`]
+    #[cfg(feature = "http-gateway")]
+    pub async fn stop_rest_http(&self) -> Result<()> {
+        self.run(|server| Box::pin(server.stop_rest_http())).await
+    }
+
+    /// Restart the REST HTTP transport; see [`RunningServer::restart_rest_http`]
+    #[cfg(feature = "http-gateway")]
+    pub async fn restart_rest_http(&self) -> Result<SocketAddr> {
+        self.run(|server| Box::pin(server.restart_rest_http())).await?
     }
 
+    /// Run `change` on the waiting server and return its output
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    async fn run<T, F>(&self, change: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut RunningServer) -> BoxFuture<'a, T> + Send + 'static,
+    {
+        let (done, output) = tokio::sync::oneshot::channel();
+        let command = command(move |server| {
+            Box::pin(async move {
+                let _ = done.send(change(server).await);
+            })
+        });
+        let stopped = || anyhow::anyhow!("The server has stopped");
+        self.commands.send(command).await.map_err(|_| stopped())?;
+        output.await.map_err(|_| stopped())
+    }
+}
+
+/// Cancel `shutdown` on the first SIGTERM or SIGINT
+///
+/// The handlers are installed before returning so installation errors surface
+/// at startup.
+fn spawn_signal_handler(shutdown: CancellationToken) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        crate::task::spawn("plexus.signals", async move {
+            let name = tokio::select! {
+                _ = terminate.recv() => "SIGTERM",
+                _ = interrupt.recv() => "SIGINT",
+                _ = shutdown.cancelled() => return,
+            };
+            tracing::info!("Received {}, shutting down", name);
+            shutdown.cancel();
+        });
+    }
+
+    #[cfg(not(unix))]
+    crate::task::spawn("plexus.signals", async move {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                if let Err(e) = result {
+                    tracing::error!("Failed to listen for Ctrl-C: {}", e);
+                    return;
+                }
+            }
+            _ = shutdown.cancelled() => return,
+        }
+        tracing::info!("Received Ctrl-C, shutting down");
+        shutdown.cancel();
+    });
+
+    Ok(())
+}
+
+/// Log how an HTTP transport task ended
+fn log_exit(name: &str, result: std::result::Result<std::io::Result<()>, tokio::task::JoinError>) {
+    match result {
+        Ok(Ok(())) => tracing::info!("{} server stopped", name),
+        Ok(Err(e)) => tracing::error!("{} server error: {}", name, e),
+        Err(e) => tracing::error!("{} server task failed: {}", name, e),
+    }
 }
 
 /// Builder for configuring transport servers
 pub struct TransportServerBuilder<A: Activation> {
     activation: Arc<A>,
     config: TransportConfig,
-    rpc_converter: Option<RpcConverter<A>>,
+    rpc_converter: RpcConverter<A>,
     mcp_flat_schemas: Option<Vec<PluginSchema>>,
     mcp_route_fn: Option<RouteFn>,
+    #[cfg(feature = "websocket")]
     session_validator: Option<Arc<dyn SessionValidator>>,
+    mounts: Vec<MountedActivation>,
 }
 
 impl<A: Activation> TransportServerBuilder<A> {
     pub fn new<F>(activation: Arc<A>, rpc_converter: F) -> Self
     where
-        F: FnOnce(Arc<A>) -> Result<RpcModule<()>> + Send + 'static,
+        F: Fn(Arc<A>) -> Result<RpcModule<()>> + Send + Sync + 'static,
     {
         Self {
             activation,
             config: TransportConfig::default(),
-            rpc_converter: Some(Box::new(rpc_converter)),
+            rpc_converter: Arc::new(rpc_converter),
             mcp_flat_schemas: None,
             mcp_route_fn: None,
+            #[cfg(feature = "websocket")]
             session_validator: None,
+            mounts: Vec::new(),
         }
     }
 
     /// Enable WebSocket transport on the specified port
+    #[cfg(feature = "websocket")]
     pub fn with_websocket(mut self, port: u16) -> Self {
         self.config.websocket = Some(WebSocketConfig::new(port));
         self
     }
 
+    /// Enable WebSocket transport with custom configuration
+    #[cfg(feature = "websocket")]
+    pub fn with_websocket_config(mut self, config: WebSocketConfig) -> Self {
+        self.config.websocket = Some(config);
+        self
+    }
+
     /// Enable stdio transport (MCP-compatible)
+    #[cfg(feature = "stdio")]
     pub fn with_stdio(mut self) -> Self {
         self.config.stdio = Some(StdioConfig::default());
         self
     }
 
+    /// Enable stdio transport with custom configuration
+    #[cfg(feature = "stdio")]
+    pub fn with_stdio_config(mut self, config: StdioConfig) -> Self {
+        self.config.stdio = Some(config);
+        self
+    }
+
+    /// Enable stdio transport speaking MCP through the activation's MCP bridge
+    #[cfg(all(feature = "stdio", feature = "mcp-http"))]
+    pub fn with_mcp_stdio(mut self) -> Self {
+        self.config.stdio = Some(StdioConfig::default().with_mcp());
+        self
+    }
+
     /// Enable MCP HTTP transport on the specified port
+    #[cfg(feature = "mcp-http")]
     pub fn with_mcp_http(mut self, port: u16) -> Self {
         self.config.mcp_http = Some(McpHttpConfig::new(port));
         self
     }
 
     /// Enable MCP HTTP transport with custom configuration
+    #[cfg(feature = "mcp-http")]
     pub fn with_mcp_http_config(mut self, config: McpHttpConfig) -> Self {
         self.config.mcp_http = Some(config);
         self
@@ -210,6 +1368,13 @@ impl<A: Activation> TransportServerBuilder<A> {
         self
     }
 
+    /// Replace the whole transport configuration, e.g. one loaded with
+    /// `TransportConfig::from_file`; later `with_*` calls adjust it further
+    pub fn with_config(mut self, config: TransportConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Set pre-computed flat schemas for MCP tool exposure.
     /// For hub activations, pass `hub.list_plugin_schemas()` to expose all child schemas.
     pub fn with_mcp_flat_schemas(mut self, schemas: Vec<PluginSchema>) -> Self {
@@ -225,6 +1390,18 @@ impl<A: Activation> TransportServerBuilder<A> {
         self
     }
 
+    /// Serve another activation on the same listeners, under its prefix.
+    ///
+    /// Its methods are called as `prefix.namespace.method` over WebSocket and
+    /// stdio, its MCP tools carry the same names, and its REST endpoints live
+    /// at `/rest/{prefix}/{namespace}/{method}`. Server-wide and per-transport
+    /// settings (authentication, authorization, audit, limits) apply to it as
+    /// well; policies see the prefix as the namespace.
+    pub fn with_mount(mut self, mount: MountedActivation) -> Self {
+        self.mounts.push(mount);
+        self
+    }
+
     /// Require `Authorization: Bearer <key>` on all WebSocket and MCP HTTP connections.
     ///
     /// When set, connections missing or supplying the wrong token are rejected with
@@ -234,6 +1411,143 @@ impl<A: Activation> TransportServerBuilder<A> {
         self
     }
 
+    /// Check every call on every transport against an authorization policy.
+    ///
+    /// The policy sees the caller's `CallerIdentity` (API key identity, JWT
+    /// claims, client certificate, session, or `Local` for stdio) and the
+    /// `namespace`/`method` being called. Transports configured with their own
+    /// policy keep it.
+    pub fn with_authorization_policy(mut self, policy: Arc<dyn AuthorizationPolicy>) -> Self {
+        self.config.authorization = Some(policy);
+        self
+    }
+
+    /// Run `middleware` around every call on every transport.
+    ///
+    /// Middleware runs after the authorization policy, in the order it was
+    /// added. Transports configured with middleware of their own keep theirs.
+    pub fn with_middleware(mut self, middleware: Arc<dyn TransportMiddleware>) -> Self {
+        self.config.middleware.push(middleware);
+        self
+    }
+
+    /// Limit how long calls on every transport may run, by method.
+    ///
+    /// Calls running longer are dropped and answered with a
+    /// `TIMEOUT_ERROR_CODE` error (HTTP 504 on REST) instead of leaving the
+    /// client waiting. MCP tool calls use these limits unless the MCP config
+    /// has tool timeouts of its own; other transports configured with their
+    /// own limits keep theirs.
+    pub fn with_request_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.config.request_timeouts = timeouts;
+        self
+    }
+
+    /// Record every call on every transport in an audit log.
+    ///
+    /// All transports share the auditor, so their records form a single hash
+    /// chain. Transports configured with their own auditor keep it.
+    pub fn with_audit(mut self, auditor: Arc<crate::audit::Auditor>) -> Self {
+        self.config.audit = Some(auditor);
+        self
+    }
+
+    /// Write a JSON line per call on every transport to an access log.
+    ///
+    /// Transports configured with their own access log keep it.
+    pub fn with_access_log(mut self, log: Arc<crate::access_log::AccessLog>) -> Self {
+        self.config.access_log = Some(log);
+        self
+    }
+
+    /// Report WebSocket and stdio connections and MCP sessions opening and
+    /// closing to `observer`.
+    ///
+    /// Transports configured with their own observer keep it.
+    pub fn with_observer(mut self, observer: Arc<dyn crate::observer::TransportObserver>) -> Self {
+        self.config.observer = Some(observer);
+        self
+    }
+
+    /// Send the notifications of `broadcaster` to every WebSocket client and
+    /// MCP session.
+    ///
+    /// Transports configured with their own broadcaster keep it.
+    pub fn with_broadcaster(mut self, broadcaster: crate::broadcast::Broadcaster) -> Self {
+        self.config.broadcaster = Some(broadcaster);
+        self
+    }
+
+    /// Capture the traffic of the WebSocket, stdio and MCP HTTP transports,
+    /// e.g. to replay it with [`Replayer`](crate::capture::Replayer).
+    ///
+    /// REST HTTP traffic and WebSocket subscription notifications are not
+    /// captured. Transports configured with their own capture keep it.
+    pub fn with_capture(mut self, capture: Arc<crate::capture::Capture>) -> Self {
+        self.config.capture = Some(capture);
+        self
+    }
+
+    /// Record calls, connections, MCP sessions and notification queues on
+    /// every transport in Prometheus metrics.
+    ///
+    /// Transports configured with their own metrics keep them. Without an
+    /// endpoint, expose them with [`TransportMetrics::encode`](crate::metrics::TransportMetrics::encode)
+    /// or [`metrics_router`](crate::metrics::metrics_router).
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Arc<crate::metrics::TransportMetrics>) -> Self {
+        self.config.metrics = Some(metrics);
+        self
+    }
+
+    /// Serve the metrics at `http://127.0.0.1:<port>/metrics`.
+    ///
+    /// Collects metrics even when [`with_metrics`](Self::with_metrics) is not
+    /// called. The endpoint is unauthenticated.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_endpoint(mut self, port: u16) -> Self {
+        self.config.metrics_endpoint = Some(crate::metrics::MetricsConfig::new(port));
+        self
+    }
+
+    /// Serve the metrics with a custom address and path
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_endpoint_config(mut self, config: crate::metrics::MetricsConfig) -> Self {
+        self.config.metrics_endpoint = Some(config);
+        self
+    }
+
+    /// How long shutdown waits for in-flight requests to finish (default 30s).
+    ///
+    /// Requests still running when it expires are aborted.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.config.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Shut down gracefully on SIGTERM or SIGINT (Ctrl-C on non-Unix platforms).
+    ///
+    /// The signal cancels the same token as [`TransportServer::serve_with_shutdown`],
+    /// so listeners close, in-flight requests drain, and SQLite sessions are
+    /// flushed before `serve()` returns.
+    pub fn with_signal_shutdown(mut self) -> Self {
+        self.config.signal_shutdown = true;
+        self
+    }
+
+    /// Restart crashed MCP HTTP and REST HTTP transports with exponential backoff.
+    ///
+    /// Without a policy, a transport that stops on its own shuts the whole
+    /// server down. With one, a transport that fails (server error or panic)
+    /// is restarted on the same address and the policy's crash handler is
+    /// called, so long-running hubs survive transient socket errors. The
+    /// WebSocket transport retries failed accepts itself and is not
+    /// supervised.
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.config.restart_policy = Some(policy);
+        self
+    }
+
     /// Set session validator for cookie-based authentication.
     ///
     /// When set, the WebSocket transport will extract cookies from HTTP upgrade
@@ -242,19 +1556,39 @@ impl<A: Activation> TransportServerBuilder<A> {
     ///
     /// This is useful for browser-based authentication where cookies are preferred
     /// over Authorization headers.
+    #[cfg(feature = "websocket")]
     pub fn with_session_validator(mut self, validator: Arc<dyn SessionValidator>) -> Self {
         self.session_validator = Some(validator);
         self
     }
 
     /// Build the transport server
+    ///
+    /// Fails if a mounted activation has an invalid or duplicate prefix, a
+    /// prefix equal to a namespace of the main activation, or an RPC converter
+    /// that fails.
     pub async fn build(self) -> Result<TransportServer<A>> {
+        let mut mounts = Vec::with_capacity(self.mounts.len());
+        for mount in self.mounts {
+            anyhow::ensure!(
+                !mounts.iter().any(|m: &Mount| m.prefix == mount.prefix()),
+                "activation prefix {:?} is mounted twice",
+                mount.prefix()
+            );
+            mounts.push(mount.build()?);
+        }
+        crate::mount::check_prefixes(&*self.activation, self.mcp_flat_schemas.as_deref(), &mounts)?;
+
         Ok(TransportServer {
-            activation: self.activation,
             config: self.config,
-            rpc_converter: self.rpc_converter,
-            mcp_flat_schemas: self.mcp_flat_schemas,
-            mcp_route_fn: self.mcp_route_fn,
+            swap: ActivationSwap::new(
+                self.activation,
+                self.rpc_converter,
+                self.mcp_flat_schemas,
+                self.mcp_route_fn,
+                mounts,
+            ),
+            #[cfg(feature = "websocket")]
             session_validator: self.session_validator,
         })
     }
@@ -0,0 +1,79 @@
+//! JSON-lines files
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A file of one JSON record per line, opened for appending
+///
+/// Each record is written with a single `write` call and reaches the OS before
+/// [`append`](Self::append) returns.
+#[derive(Debug)]
+pub(crate) struct JsonLinesFile {
+    /// What the file holds (`audit log`, `capture file`), for errors
+    name: &'static str,
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonLinesFile {
+    /// Open (or create) the file at `path` for appending
+    pub(crate) fn open(name: &'static str, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {} {}", name, path.display()))?;
+
+        Ok(Self {
+            name,
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `record`; failures are logged
+    pub(crate) fn append(&self, record: &impl Serialize) {
+        let mut line = match serde_json::to_vec(record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize record for {} {}: {}", self.name, self.path.display(), e);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(&line) {
+            tracing::error!("Failed to write {} {}: {}", self.name, self.path.display(), e);
+        }
+    }
+
+    /// Read every record in the file at `path`, skipping blank lines
+    pub(crate) fn read<T: DeserializeOwned>(name: &str, path: impl AsRef<Path>) -> Result<Vec<T>> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("Failed to open {} {}", name, path.display()))?;
+        let mut records = Vec::new();
+        for (n, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Invalid record on line {} of {} {}", n + 1, name, path.display()))?,
+            );
+        }
+        Ok(records)
+    }
+}
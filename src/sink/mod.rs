@@ -0,0 +1,89 @@
+//! Plumbing shared by the audit and capture sinks
+//!
+//! Both logs take records on the request path and must never make it wait: a
+//! [`Writer`] queues records for a dedicated thread that hands them to the
+//! sink in order, and drops them with an error log once its bounded queue is
+//! full. The file and SQLite sinks of both logs share [`JsonLinesFile`] and
+//! the `sqlite` helpers.
+
+pub(crate) mod file;
+#[cfg(any(feature = "sqlite-audit", feature = "sqlite-capture"))]
+pub(crate) mod sqlite;
+
+pub(crate) use file::JsonLinesFile;
+
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+/// Work for the writer thread
+enum Queued<T> {
+    Record(T),
+    /// Answered once every record queued before it is written
+    Flush(mpsc::Sender<()>),
+}
+
+/// Bounded queue of records and the thread writing them
+pub(crate) struct Writer<T> {
+    name: &'static str,
+    /// Taken on drop to stop the thread
+    queue: Option<mpsc::SyncSender<Queued<T>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Writer<T> {
+    /// Start a thread called `name` passing queued records to `write`
+    pub(crate) fn spawn(name: &'static str, capacity: usize, write: impl Fn(T) + Send + 'static) -> Self {
+        let (queue, queued) = mpsc::sync_channel(capacity);
+        let thread = std::thread::Builder::new()
+            .name(name.into())
+            .spawn(move || {
+                for work in queued {
+                    match work {
+                        Queued::Record(record) => write(record),
+                        Queued::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .unwrap_or_else(|e| panic!("failed to spawn the {} thread: {}", name, e));
+        Self {
+            name,
+            queue: Some(queue),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue `record` without waiting; the error says why it was dropped
+    pub(crate) fn send(&self, record: T) -> Result<(), &'static str> {
+        let Some(ref queue) = self.queue else {
+            return Err("writer stopped");
+        };
+        queue.try_send(Queued::Record(record)).map_err(|e| match e {
+            mpsc::TrySendError::Full(_) => "queue full",
+            mpsc::TrySendError::Disconnected(_) => "writer stopped",
+        })
+    }
+
+    /// Wait until every record queued so far has been written
+    pub(crate) fn flush(&self) {
+        let (done, flushed) = mpsc::channel();
+        if let Some(ref queue) = self.queue {
+            if queue.send(Queued::Flush(done)).is_ok() {
+                let _ = flushed.recv();
+            }
+        }
+    }
+}
+
+impl<T> Drop for Writer<T> {
+    /// Write the queued records before the sink is dropped
+    fn drop(&mut self) {
+        drop(self.queue.take());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                tracing::error!("{} writer thread panicked", self.name);
+            }
+        }
+    }
+}
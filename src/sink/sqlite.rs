@@ -0,0 +1,64 @@
+//! SQLite tables
+
+use std::future::Future;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use sqlx::ConnectOptions;
+use tokio::sync::mpsc;
+
+/// Open (or create) the `name` database at `path` and run `schema` on it
+pub(crate) async fn connect(name: &str, path: &Path, schema: &str) -> Result<SqlitePool> {
+    let db_url = format!("sqlite:{}?mode=rwc", path.display());
+    let mut connect_options: SqliteConnectOptions = db_url
+        .parse()
+        .with_context(|| format!("Failed to parse {} DB URL", name))?;
+    connect_options.disable_statement_logging();
+    let pool = SqlitePool::connect_with(connect_options)
+        .await
+        .with_context(|| format!("Failed to open {} database", name))?;
+
+    sqlx::query(schema)
+        .execute(&pool)
+        .await
+        .with_context(|| format!("Failed to migrate the {} table", name))?;
+
+    Ok(pool)
+}
+
+/// Hands records to a task that inserts them in order
+///
+/// Sending waits while the task is `capacity` records behind, so a slow
+/// database fills the [`Writer`](super::Writer) queue in front of it instead
+/// of memory.
+#[derive(Debug)]
+pub(crate) struct SqliteWriter<T> {
+    tx: mpsc::Sender<T>,
+}
+
+impl<T: Send + 'static> SqliteWriter<T> {
+    /// Spawn the task called `task`, inserting each record with `insert`
+    pub(crate) fn spawn<F, Fut>(task: &'static str, capacity: usize, insert: F) -> Self
+    where
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), sqlx::Error>> + Send,
+    {
+        let (tx, mut rx) = mpsc::channel::<T>(capacity);
+        crate::task::spawn(task, async move {
+            while let Some(record) = rx.recv().await {
+                if let Err(e) = insert(record).await {
+                    tracing::error!("{} failed to insert a record: {}", task, e);
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queue `record`; false once the task stopped
+    ///
+    /// Blocks the calling thread, so call it from a writer thread, not async code.
+    pub(crate) fn send(&self, record: T) -> bool {
+        self.tx.blocking_send(record).is_ok()
+    }
+}
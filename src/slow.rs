@@ -0,0 +1,100 @@
+//! Slow-request detection
+//!
+//! With a transport's `slow_request_threshold` set, a call still running once
+//! the threshold has passed is logged as a warning with its method name and
+//! params size, while it is still in flight, so calls that never complete show
+//! up too. When it does complete, its total time is logged as well. With
+//! feature `metrics` each slow call increments `plexus_slow_requests_total`.
+
+use std::future::Future;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::TransportMetrics;
+
+/// Watches calls on one transport for exceeding its latency threshold
+#[derive(Clone, Default)]
+pub(crate) struct SlowRequests {
+    threshold: Option<Duration>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<TransportMetrics>>,
+}
+
+impl SlowRequests {
+    /// Watch for calls taking longer than `threshold`; `None` disables it
+    pub(crate) fn new(threshold: Option<Duration>) -> Self {
+        Self {
+            threshold,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Count slow calls in `metrics`
+    #[cfg(feature = "metrics")]
+    pub(crate) fn with_metrics(mut self, metrics: Option<Arc<TransportMetrics>>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Run `call`, warning if it is still running after the threshold
+    ///
+    /// `describe` gives the method name and params size in bytes; it is only
+    /// evaluated for slow calls.
+    pub(crate) async fn watch<F: Future>(
+        &self,
+        transport: &'static str,
+        describe: impl FnOnce() -> (String, usize),
+        call: F,
+    ) -> F::Output {
+        let Some(threshold) = self.threshold else {
+            return call.await;
+        };
+
+        let started = Instant::now();
+        tokio::pin!(call);
+        tokio::select! {
+            output = &mut call => return output,
+            _ = tokio::time::sleep(threshold) => {}
+        }
+
+        let (method, params_len) = describe();
+        tracing::warn!(
+            transport,
+            method = %method,
+            params_bytes = params_len,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Slow {} call {} still running after {:?}",
+            transport,
+            method,
+            threshold
+        );
+        #[cfg(feature = "metrics")]
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_slow(transport, &method);
+        }
+
+        let output = call.await;
+        tracing::warn!(
+            transport,
+            method = %method,
+            params_bytes = params_len,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Slow {} call {} completed after {:?}",
+            transport,
+            method,
+            started.elapsed()
+        );
+        output
+    }
+}
+
+impl std::fmt::Debug for SlowRequests {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlowRequests")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
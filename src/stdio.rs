@@ -1,70 +1,1017 @@
 //! Stdio transport - Line-delimited JSON-RPC over stdin/stdout
 //!
 //! This transport is MCP-compatible and is the standard way to integrate
-//! with Claude Desktop and other MCP clients.
+//! with Claude Desktop and other MCP clients. With
+//! [`StdioFraming::ContentLength`], messages are framed with LSP-style
+//! `Content-Length` headers instead, so they may contain newlines; with
+//! [`StdioFraming::LengthPrefixed`], behind a 4-byte big-endian length.
+//!
+//! The framing and subscription-forwarding logic lives in [`serve_io`], which
+//! works over any `AsyncRead`/`AsyncWrite` pair (serial ports, SSH channels,
+//! child-process pipes). [`serve_stdio`] is a thin wrapper binding it to the
+//! process's stdin and stdout.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
+use jsonrpsee::types::{ErrorCode, ErrorObject};
 use jsonrpsee::RpcModule;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+use crate::access_log::AccessLog;
+use crate::audit::{AuditStatus, Auditor};
+use crate::auth::policy::{forbidden_message, FORBIDDEN_ERROR_CODE};
+use crate::auth::{AuthorizationPolicy, CallerIdentity};
+use crate::capture::Direction;
+use crate::config::{NotificationOverflow, StdioConfig, StdioFraming};
+use crate::hot_swap::{fixed_methods, MethodSource};
+use crate::mcp::timeout::TIMEOUT_ERROR_CODE;
+#[cfg(feature = "metrics")]
+use crate::metrics::{QueueDepth, TransportMetrics};
+use crate::middleware::{self, CallContext};
+use crate::observer::ObservedConnection;
+use crate::slow::SlowRequests;
+
+/// How often the parent PID is checked with `config.exit_on_parent_death`
+const PARENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
-use crate::config::StdioConfig;
+/// JSON-RPC error code closing a subscription whose client fell too far behind
+pub const SUBSCRIPTION_OVERFLOW_ERROR_CODE: i32 = -32006;
 
 /// Serve RPC module over stdio (MCP-compatible transport)
 ///
 /// Reads line-delimited JSON-RPC requests from stdin and writes responses to stdout.
 /// Subscription notifications are forwarded to stdout as they arrive.
 ///
-/// This function will block until stdin is closed.
+/// This function will block until stdin is closed, or with
+/// `config.exit_on_parent_death` until the parent process exits.
+///
+/// With `config.stdout_guard`, the process's stdout is pointed at stderr for
+/// good, and the protocol is written to a duplicate of the original stdout.
 pub async fn serve_stdio(module: RpcModule<()>, config: StdioConfig) -> Result<()> {
+    serve_stdio_with_source(fixed_methods(module.into()), config, CancellationToken::new()).await
+}
+
+/// Serve stdio, dispatching each request to the methods current when it arrives
+///
+/// Cancelling `shutdown` stops reading stdin, as EOF would.
+pub(crate) async fn serve_stdio_with_source(
+    methods: MethodSource,
+    config: StdioConfig,
+    shutdown: CancellationToken,
+) -> Result<()> {
     tracing::info!("Starting stdio transport (MCP-compatible)");
 
-    let stdin = BufReader::new(tokio::io::stdin());
-    let mut stdout = tokio::io::stdout();
-    let mut lines = stdin.lines();
+    let stdout = protocol_stdout(&config)?;
+    let exit_on_parent_death = config.exit_on_parent_death;
+    until_parent_exit(
+        exit_on_parent_death,
+        serve_io_with_source(methods, tokio::io::stdin(), stdout, config, shutdown),
+    )
+    .await
+}
+
+/// The writer the protocol goes to: stdout, or with `config.stdout_guard`
+/// the original stdout after the process's stdout is pointed at stderr
+pub(crate) fn protocol_stdout(config: &StdioConfig) -> std::io::Result<Box<dyn AsyncWrite + Unpin + Send>> {
+    if config.stdout_guard {
+        guard_stdout()
+    } else {
+        Ok(Box::new(tokio::io::stdout()))
+    }
+}
+
+/// Run `serve`, stopping early when `enabled` and the parent process exits
+pub(crate) async fn until_parent_exit(enabled: bool, serve: impl Future<Output = Result<()>>) -> Result<()> {
+    if !enabled {
+        return serve.await;
+    }
+    // A crashed client may leave stdin open, orphaning the server
+    tokio::select! {
+        result = serve => result,
+        () = parent_exit() => {
+            tracing::info!("Parent process exited, stdio transport stopped");
+            Ok(())
+        }
+    }
+}
+
+/// Reserve stdout for the protocol: returns a writer to the original stdout,
+/// after pointing the process's stdout at stderr
+#[cfg(unix)]
+fn guard_stdout() -> std::io::Result<Box<dyn AsyncWrite + Unpin + Send>> {
+    use std::io::Write;
+    use std::os::fd::AsFd;
+
+    std::io::stdout().flush()?;
+    let protocol = std::io::stdout().as_fd().try_clone_to_owned()?;
+    // SAFETY: dup2 only takes file descriptors
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(Box::new(tokio::fs::File::from_std(std::fs::File::from(protocol))))
+}
+
+#[cfg(not(unix))]
+fn guard_stdout() -> std::io::Result<Box<dyn AsyncWrite + Unpin + Send>> {
+    tracing::warn!("The stdout guard is only supported on Unix; ignoring stdout_guard");
+    Ok(Box::new(tokio::io::stdout()))
+}
+
+/// Resolves once the process that started this one has exited
+///
+/// The parent PID is polled: an orphan is adopted by another process. On
+/// Linux the kernel also sends SIGTERM when the parent exits
+/// (`PR_SET_PDEATHSIG`), which ends the process at once unless it handles the
+/// signal, e.g. with `with_signal_shutdown`. Note that Linux sends it when the
+/// parent *thread* that spawned this process exits. On other platforms this
+/// never resolves.
+async fn parent_exit() {
+    #[cfg(unix)]
+    {
+        let parent = std::os::unix::process::parent_id();
+        #[cfg(target_os = "linux")]
+        set_parent_death_signal();
+        let mut ticks = tokio::time::interval(PARENT_POLL_INTERVAL);
+        loop {
+            ticks.tick().await;
+            if std::os::unix::process::parent_id() != parent {
+                return;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tracing::warn!("Parent death detection is only supported on Unix; ignoring exit_on_parent_death");
+        std::future::pending::<()>().await
+    }
+}
+
+/// Have the kernel send SIGTERM when the parent exits
+#[cfg(target_os = "linux")]
+fn set_parent_death_signal() {
+    // SAFETY: PR_SET_PDEATHSIG takes a signal number and touches no memory
+    if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM as libc::c_ulong) } != 0 {
+        tracing::warn!("Failed to set the parent death signal: {}", std::io::Error::last_os_error());
+    }
+}
+
+/// Serve RPC module over an arbitrary reader/writer pair
+///
+/// Reads line-delimited JSON-RPC requests from `reader` and writes responses to
+/// `writer`. Subscription notifications are forwarded to `writer` as they arrive.
+/// A single task owns `writer` and writes every message as a whole line, in the
+/// order they were queued; before returning, everything queued is written.
+///
+/// When `config.authorization` is set, each call is checked against the policy
+/// as `CallerIdentity::Local` before dispatch; denied calls get a JSON-RPC error.
+/// `config.middleware` then runs around each call; the extensions it adds are
+/// visible to the method.
+/// When `config.audit` is set, every call is recorded in the audit log, with
+/// `config.access_log` in the access log, and with `config.metrics` (feature
+/// `metrics`) in the Prometheus metrics.
+///
+/// Each call is dispatched in a `request` span; with feature `otel` it
+/// continues the trace in the `traceparent` of the call's `params._meta`.
+///
+/// Calls still running after `config.slow_request_threshold` are logged as slow.
+/// Calls still running after their limit in `config.request_timeouts`, or
+/// else `config.request_timeout`, are dropped and answered with a
+/// [`TIMEOUT_ERROR_CODE`] error, so the next request can be read.
+///
+/// `config.capture` records every request line, its response and the
+/// subscription notifications it causes.
+///
+/// `config.observer` sees the reader and writer as one connection, closed when
+/// this function returns.
+///
+/// Lines longer than `config.max_line_length` are skipped without being
+/// buffered and answered with a JSON-RPC "Request is too big" error.
+///
+/// `config.framing` selects newline-delimited messages, `Content-Length`
+/// framing or a binary length prefix, for both requests and output.
+///
+/// Notifications of each subscription queue up to
+/// `config.subscription_buffer_size` deep; `config.notification_overflow`
+/// decides whether a full queue holds back the publisher, drops notifications
+/// or closes the subscription with a [`SUBSCRIPTION_OVERFLOW_ERROR_CODE`] error.
+///
+/// Batches are dispatched call by call and answered with one array of the
+/// responses, leaving out notifications; calls in a batch that are not
+/// requests get an "Invalid request" error.
+///
+/// Input that is not JSON (or not UTF-8) is answered with a "Parse error",
+/// and JSON that is not a request with an "Invalid request" error; serving
+/// carries on with the next message. Only I/O errors, and `Content-Length`
+/// frames whose headers cannot be parsed, end it.
+///
+/// This function will block until `reader` reaches EOF. With
+/// `config.eof_grace`, it then waits up to that long for open subscriptions
+/// to end, forwarding their notifications, and closes the rest.
+pub async fn serve_io<R, W>(
+    module: RpcModule<()>,
+    reader: R,
+    writer: W,
+    config: StdioConfig,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    serve_io_with_source(fixed_methods(module.into()), reader, writer, config, CancellationToken::new()).await
+}
+
+async fn serve_io_with_source<R, W>(
+    methods: MethodSource,
+    reader: R,
+    writer: W,
+    config: StdioConfig,
+    shutdown: CancellationToken,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let mut reader = BufReader::new(reader);
+    let output = Output::spawn(writer, config.framing);
+    let mut buf = Vec::new();
+    let mut forwarders = Vec::new();
+    let slow = SlowRequests::new(config.slow_request_threshold);
+    #[cfg(feature = "metrics")]
+    let slow = slow.with_metrics(config.metrics.clone());
+    let _observed = config
+        .observer
+        .clone()
+        .map(|observer| ObservedConnection::new(observer, "stdio", None));
+
+    loop {
+        // A message read halfway when shutdown is requested is dropped
+        let fits = tokio::select! {
+            read = read_message(&mut reader, &mut buf, &config) => match read? {
+                Some(fits) => fits,
+                None => break,
+            },
+            _ = shutdown.cancelled() => {
+                tracing::info!("Shutdown requested, stdio transport stopped reading");
+                break;
+            }
+        };
+        if !fits {
+            tracing::warn!(
+                "Stdio request discarded: message exceeds {} bytes",
+                config.max_line_length
+            );
+            output.send(oversized_response(config.max_line_length)).await?;
+            continue;
+        }
 
-    while let Some(line) = lines.next_line().await? {
+        let Ok(line) = std::str::from_utf8(&buf) else {
+            tracing::warn!("Stdio request discarded: not UTF-8");
+            output.send(error_response(serde_json::Value::Null, ErrorCode::ParseError)).await?;
+            continue;
+        };
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
         }
 
         tracing::debug!("Received request: {}", trimmed);
+        if let Some(ref capture) = config.capture {
+            capture.record_raw("stdio", None, Direction::Request, None, trimmed);
+        }
 
-        // Call the RpcModule with the configured subscription buffer size
-        let (response, mut sub_receiver) = module
-            .raw_json_request(trimmed, config.subscription_buffer_size)
-            .await
-            .map_err(|e| anyhow::anyhow!("RPC error: {}", e))?;
-
-        // Write initial response to stdout
-        let response_str = response.get();
-        stdout.write_all(response_str.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
-        stdout.flush().await?;
-
-        tracing::debug!("Sent response: {}", response_str);
-
-        // Spawn task to forward subscription notifications (if any)
-        // The receiver will be empty for non-subscription responses
-        tokio::spawn(async move {
-            while let Some(notification) = sub_receiver.recv().await {
-                let notification_str = notification.get();
-                tracing::debug!("Forwarding notification: {}", notification_str);
-
-                // Get a new stdout handle for each notification
-                let mut out = tokio::io::stdout();
-                if out.write_all(notification_str.as_bytes()).await.is_err() {
-                    break;
+        // A batch is answered with one array holding the responses of its calls
+        let batch = match serde_json::from_str::<serde_json::Value>(trimmed) {
+            Ok(serde_json::Value::Array(calls)) => Some(calls),
+            _ => None,
+        };
+        let (response, subscriptions) = match batch {
+            None => match call(&methods, &config, &slow, trimmed).await {
+                Ok(called) => (called.response, Vec::from_iter(called.subscription)),
+                Err(e) => {
+                    tracing::warn!("Invalid stdio request: {}", e);
+                    (Some(malformed_response(trimmed)), Vec::new())
                 }
-                if out.write_all(b"\n").await.is_err() {
-                    break;
+            },
+            Some(calls) if calls.is_empty() => (
+                Some(error_response(serde_json::Value::Null, ErrorCode::InvalidRequest)),
+                Vec::new(),
+            ),
+            Some(calls) => {
+                let mut responses = Vec::with_capacity(calls.len());
+                let mut subscriptions = Vec::new();
+                for raw in calls.iter().map(serde_json::Value::to_string) {
+                    match call(&methods, &config, &slow, &raw).await {
+                        Ok(called) => {
+                            responses.extend(called.response);
+                            subscriptions.extend(called.subscription);
+                        }
+                        Err(e) => {
+                            tracing::debug!("Invalid call in stdio batch: {}", e);
+                            responses.push(malformed_response(&raw));
+                        }
+                    }
                 }
-                if out.flush().await.is_err() {
-                    break;
+                // A batch of notifications gets no response at all
+                let response = (!responses.is_empty()).then(|| format!("[{}]", responses.join(",")));
+                (response, subscriptions)
+            }
+        };
+
+        if let Some(response) = response {
+            tracing::debug!("Sending response: {}", response);
+            if let Some(ref capture) = config.capture {
+                capture.record_raw("stdio", None, Direction::Response, None, &response);
+            }
+            output.send(response).await?;
+        }
+
+        // Notifications of subscriptions follow the response that opened them
+        if !subscriptions.is_empty() {
+            forwarders.retain(|forwarder: &JoinHandle<()>| !forwarder.is_finished());
+        }
+        for subscription in subscriptions {
+            forwarders.push(forward_notifications(&output, &config, subscription));
+        }
+    }
+
+    if let Some(grace) = config.eof_grace {
+        drain(forwarders, grace).await;
+    }
+    output.flush().await?;
+    Ok(())
+}
+
+/// The subscription notifications of a call, and the id of the call
+type Subscription = (mpsc::Receiver<Box<serde_json::value::RawValue>>, Option<serde_json::Value>);
+
+/// The outcome of one call
+struct Called {
+    /// The response to send; `None` for notifications
+    response: Option<String>,
+    /// Notifications to forward; empty unless the call opened a subscription
+    subscription: Option<Subscription>,
+}
+
+/// Check, dispatch and record one JSON-RPC call
+///
+/// Notifications are dispatched like calls, and their response dropped. Fails
+/// when `raw` is not a JSON-RPC request.
+async fn call(methods: &MethodSource, config: &StdioConfig, slow: &SlowRequests, raw: &str) -> Result<Called> {
+    let started = Instant::now();
+
+    if let Some(ref policy) = config.authorization {
+        if let Some(denied) = check_authorization(policy.as_ref(), raw) {
+            record(config, raw, AuditStatus::Denied, started.elapsed());
+            return Ok(Called {
+                response: denied,
+                subscription: None,
+            });
+        }
+    }
+
+    // After an activation swap, subscriptions opened earlier keep the old module
+    let mut methods = methods();
+    let admitted = match admit(config, raw, &mut methods, started).await {
+        Ok(admitted) => admitted,
+        Err(rejected) => return Ok(rejected),
+    };
+    let finish = |status: &AuditStatus| {
+        if let Some((ref call, _)) = admitted {
+            middleware::after_call(&config.middleware, call, status.clone(), started);
+        }
+    };
+
+    // jsonrpsee only dispatches requests with an id
+    let request = admitted.as_ref().map_or(raw, |(_, request)| request.as_str());
+    let notification = notification_request(request);
+    let request = notification.as_deref().unwrap_or(request);
+
+    // Call the RpcModule with the configured subscription buffer size
+    let dispatch = methods
+        .raw_json_request(request, config.subscription_buffer_size)
+        .instrument(line_span(raw));
+    let dispatch = slow.watch("stdio", || describe_line(raw), dispatch);
+    // Per-method limits take precedence over the stdio-wide one
+    let timeout = if config.request_timeouts.is_empty() {
+        config.request_timeout
+    } else {
+        let (method, _) = describe_line(request);
+        config.request_timeouts.timeout_for(&method).or(config.request_timeout)
+    };
+    let dispatched = match timeout {
+        None => dispatch.await,
+        Some(timeout) => match tokio::time::timeout(timeout, dispatch).await {
+            Ok(dispatched) => dispatched,
+            Err(_) => {
+                let status = AuditStatus::Error { code: TIMEOUT_ERROR_CODE };
+                finish(&status);
+                record(config, raw, status, started.elapsed());
+                let response = timeout_response(raw, timeout);
+                return Ok(Called {
+                    response: notification.is_none().then_some(response),
+                    subscription: None,
+                });
+            }
+        },
+    };
+    let (response, notifications) = match dispatched {
+        Ok(dispatched) => dispatched,
+        Err(e) => {
+            finish(&AuditStatus::Error { code: ErrorCode::InvalidRequest.code() });
+            anyhow::bail!("RPC error: {}", e);
+        }
+    };
+    let response = response.get();
+
+    #[cfg(feature = "metrics")]
+    let recording = config.audit.is_some() || config.access_log.is_some() || config.metrics.is_some();
+    #[cfg(not(feature = "metrics"))]
+    let recording = config.audit.is_some() || config.access_log.is_some();
+    if recording || admitted.is_some() {
+        let status = serde_json::from_str(response)
+            .map(|response| AuditStatus::from_response(&response))
+            .unwrap_or(AuditStatus::Ok);
+        finish(&status);
+        record(config, raw, status, started.elapsed());
+    }
+
+    Ok(Called {
+        response: notification.is_none().then(|| response.to_string()),
+        subscription: Some((notifications, request_id(raw))),
+    })
+}
+
+/// Run a call through the configured middleware
+///
+/// Returns the call as the middleware left it, with the request to dispatch
+/// in its place, or `None` when there is no middleware or `raw` is not a
+/// single call. The extensions the middleware added are put in `methods`.
+/// A call the middleware rejected is answered and recorded here.
+async fn admit(
+    config: &StdioConfig,
+    raw: &str,
+    methods: &mut jsonrpsee::Methods,
+    started: Instant,
+) -> Result<Option<(CallContext, String)>, Called> {
+    if config.middleware.is_empty() {
+        return Ok(None);
+    }
+    let Ok(serde_json::Value::Object(mut request)) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return Ok(None);
+    };
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return Ok(None);
+    };
+
+    let mut call = CallContext::new("stdio", method, request.get("params").cloned(), CallerIdentity::Local, None);
+    if let Err(rejection) = middleware::before_call(&config.middleware, &mut call, started).await {
+        record(config, raw, AuditStatus::from_error_code(rejection.code), started.elapsed());
+        let response = request.get("id").map(|id| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": ErrorObject::owned(rejection.code, rejection.message, rejection.data),
+            })
+            .to_string()
+        });
+        return Err(Called {
+            response,
+            subscription: None,
+        });
+    }
+
+    match call.params {
+        Some(ref params) => request.insert("params".to_string(), params.clone()),
+        None => request.remove("params"),
+    };
+    methods.extensions_mut().extend(call.extensions.clone());
+    let request = serde_json::Value::Object(request).to_string();
+    Ok(Some((call, request)))
+}
+
+/// A notification as a request jsonrpsee dispatches, with a `null` id
+fn notification_request(raw: &str) -> Option<String> {
+    let mut request: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let fields = request.as_object_mut()?;
+    if fields.contains_key("id") {
+        return None;
+    }
+    fields.insert("id".to_string(), serde_json::Value::Null);
+    Some(request.to_string())
+}
+
+/// Forward the notifications of `subscription` until it ends or output fails
+///
+/// Up to `config.subscription_buffer_size` notifications wait for the output;
+/// `config.notification_overflow` decides what happens to more.
+fn forward_notifications(output: &Output, config: &StdioConfig, subscription: Subscription) -> JoinHandle<()> {
+    let (mut notifications, id) = subscription;
+    let output = output.clone();
+    let capture = config.capture.clone();
+    let capacity = config.subscription_buffer_size.max(1);
+    let overflow = config.notification_overflow;
+    #[cfg(feature = "metrics")]
+    let mut queue = config
+        .metrics
+        .as_ref()
+        .map(|metrics| QueueDepth::new(metrics.queue_depth("stdio")));
+    crate::task::spawn("plexus.stdio.notifications", async move {
+        let mut pending: VecDeque<String> = VecDeque::new();
+        let mut open = true;
+        while open || !pending.is_empty() {
+            // Blocking leaves notifications with the publisher until there is room
+            let accepting = open && (overflow != NotificationOverflow::Block || pending.len() < capacity);
+            tokio::select! {
+                received = notifications.recv(), if accepting => {
+                    let Some(notification) = received else {
+                        open = false;
+                        continue;
+                    };
+                    #[cfg(feature = "metrics")]
+                    if let Some(ref mut queue) = queue {
+                        queue.observe(pending.len() + notifications.len());
+                    }
+                    let notification = notification.get().to_string();
+                    if pending.len() < capacity {
+                        pending.push_back(notification);
+                        continue;
+                    }
+                    match overflow {
+                        NotificationOverflow::Block => unreachable!("not accepting while full"),
+                        NotificationOverflow::DropOldest => {
+                            pending.pop_front();
+                            pending.push_back(notification);
+                        }
+                        NotificationOverflow::DropNewest => {}
+                        NotificationOverflow::Close => {
+                            tracing::warn!("Stdio subscription closed: more than {} notifications queued", capacity);
+                            pending.push_back(overflow_notification(&notification, capacity));
+                            // The publisher's next send fails, ending the subscription
+                            notifications.close();
+                            open = false;
+                        }
+                    }
+                }
+                permit = output.reserve(), if !pending.is_empty() => {
+                    let Ok(permit) = permit else {
+                        break;
+                    };
+                    let notification = pending.pop_front().expect("pending is not empty");
+                    tracing::debug!("Forwarding notification: {}", notification);
+                    if let Some(ref capture) = capture {
+                        capture.record_raw("stdio", None, Direction::Notification, id.clone(), &notification);
+                    }
+                    permit.send(Outgoing::Line(notification));
+                }
+            }
+        }
+    })
+}
+
+/// Wait up to `grace` for `forwarders` to finish, then abort the rest
+async fn drain(mut forwarders: Vec<JoinHandle<()>>, grace: Duration) {
+    forwarders.retain(|forwarder| !forwarder.is_finished());
+    if forwarders.is_empty() {
+        return;
+    }
+    tracing::debug!("Stdio input closed; draining {} subscriptions", forwarders.len());
+    let aborts: Vec<_> = forwarders.iter().map(JoinHandle::abort_handle).collect();
+    if tokio::time::timeout(grace, futures::future::join_all(forwarders)).await.is_err() {
+        tracing::info!("Stdio subscriptions still open {:?} after EOF; closing them", grace);
+        for abort in aborts {
+            abort.abort();
+        }
+    }
+}
+
+/// The notification telling a client that its subscription was closed
+/// because `capacity` notifications were already waiting
+///
+/// Sent like the subscription's own notifications, with an `error` in place
+/// of the `result`, as jsonrpsee closes subscriptions with an error.
+fn overflow_notification(notification: &str, capacity: usize) -> String {
+    let notification: serde_json::Value = serde_json::from_str(notification).unwrap_or_default();
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": notification["method"],
+        "params": {
+            "subscription": notification["params"]["subscription"],
+            "error": {
+                "code": SUBSCRIPTION_OVERFLOW_ERROR_CODE,
+                "message": format!("Subscription closed: more than {} notifications queued", capacity),
+            },
+        },
+    })
+    .to_string()
+}
+
+/// Record a call in the metrics, access log and audit log, as configured
+fn record(config: &StdioConfig, raw: &str, status: AuditStatus, latency: std::time::Duration) {
+    #[cfg(feature = "metrics")]
+    if let Some(ref metrics) = config.metrics {
+        record_call(metrics, raw, &status, latency);
+    }
+    if let Some(ref log) = config.access_log {
+        log_access(log, raw, status.clone(), latency);
+    }
+    if let Some(ref auditor) = config.audit {
+        audit_call(auditor, raw, status, latency);
+    }
+}
+
+/// The span a request line is dispatched in, continuing the caller's trace
+/// from `params._meta` (feature `otel`)
+fn line_span(raw: &str) -> tracing::Span {
+    let request: Option<serde_json::Value> = serde_json::from_str(raw).ok();
+    let method = match request.as_ref() {
+        Some(serde_json::Value::Array(_)) => "batch",
+        Some(request) => request.get("method").and_then(|m| m.as_str()).unwrap_or("unknown"),
+        None => "unknown",
+    };
+    let span = crate::trace::request_span("stdio", method);
+    #[cfg(feature = "otel")]
+    if let Some(parent) = request.as_ref().and_then(|r| crate::trace::from_params(r.get("params"))) {
+        crate::trace::set_parent(&span, parent);
+    }
+    span
+}
+
+/// Id of a request line, if it is a single call
+fn request_id(raw: &str) -> Option<serde_json::Value> {
+    serde_json::from_str::<serde_json::Value>(raw).ok()?.get("id").cloned()
+}
+
+/// Method name (`batch` for batches) and params size of a request line
+fn describe_line(raw: &str) -> (String, usize) {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(serde_json::Value::Array(_)) => ("batch".to_string(), raw.len()),
+        Ok(request) => (
+            request.get("method").and_then(|m| m.as_str()).unwrap_or("unknown").to_string(),
+            request.get("params").map_or(0, |p| p.to_string().len()),
+        ),
+        Err(_) => ("unknown".to_string(), raw.len()),
+    }
+}
+
+/// Check a raw request against the authorization policy
+///
+/// Returns `None` when the call may proceed, or `Some(response)` when it is
+/// denied, where `response` is the error to send (`None` for notifications).
+/// Unparseable input is left for jsonrpsee to reject.
+fn check_authorization(policy: &dyn AuthorizationPolicy, raw: &str) -> Option<Option<String>> {
+    let request: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let method = request.get("method")?.as_str()?;
+
+    if policy.allow_method(&CallerIdentity::Local, method) {
+        return None;
+    }
+
+    tracing::warn!("Stdio call denied: {}", method);
+    let response = request.get("id").map(|id| {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": FORBIDDEN_ERROR_CODE,
+                "message": forbidden_message(method),
+            },
+        })
+        .to_string()
+    });
+    Some(response)
+}
+
+/// Record a call in the audit log; input that is not a single call is skipped
+fn audit_call(auditor: &Auditor, raw: &str, status: AuditStatus, latency: std::time::Duration) {
+    let Ok(request) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return;
+    };
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return;
+    };
+    let params = request.get("params").map(|p| p.to_string());
+    auditor.record("stdio", &CallerIdentity::Local, method, params.as_deref(), status, latency);
+}
+
+/// Record a call in the access log; input that is not a single call is skipped
+fn log_access(log: &AccessLog, raw: &str, status: AuditStatus, latency: std::time::Duration) {
+    let Ok(request) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return;
+    };
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return;
+    };
+    log.record("stdio", method, status, latency, None, None);
+}
+
+/// Record a call in the metrics; input that is not a single call is skipped
+#[cfg(feature = "metrics")]
+fn record_call(metrics: &TransportMetrics, raw: &str, status: &AuditStatus, latency: std::time::Duration) {
+    let Ok(request) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return;
+    };
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return;
+    };
+    metrics.record_call("stdio", method, status, latency);
+}
+
+/// Read one request into `buf`, framed as configured
+///
+/// Returns `None` at EOF, `Some(true)` when a request was read, and
+/// `Some(false)` when it was over `config.max_line_length` and skipped.
+async fn read_message<R>(reader: &mut R, buf: &mut Vec<u8>, config: &StdioConfig) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    match config.framing {
+        StdioFraming::Lines => read_line_bounded(reader, buf, config.max_line_length).await,
+        StdioFraming::ContentLength => read_frame(reader, buf, config.max_line_length).await,
+        StdioFraming::LengthPrefixed => read_length_prefixed(reader, buf, config.max_line_length).await,
+    }
+}
+
+/// Longest header line accepted in `Content-Length` framing
+const MAX_HEADER_LENGTH: usize = 1024;
+
+/// Read one `Content-Length` framed message into `buf`, holding at most `max` bytes
+///
+/// Headers other than `Content-Length` (e.g. `Content-Type`) are ignored, as
+/// are blank lines between messages. A message over `max` is skipped unread.
+async fn read_frame<R>(reader: &mut R, buf: &mut Vec<u8>, max: usize) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut header = Vec::new();
+    let mut headers = false;
+    let mut length = None;
+    loop {
+        match read_line_bounded(reader, &mut header, MAX_HEADER_LENGTH).await? {
+            None if !headers => return Ok(None),
+            None => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            Some(false) => return Err(invalid_frame("header line too long")),
+            Some(true) => {}
+        }
+        let line = std::str::from_utf8(&header).map_err(|_| invalid_frame("header is not UTF-8"))?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            if !headers {
+                continue;
+            }
+            break;
+        }
+        headers = true;
+        let (name, value) = line.split_once(':').ok_or_else(|| invalid_frame("malformed header"))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let value = value.trim().parse().map_err(|_| invalid_frame("invalid Content-Length"))?;
+            length = Some(value);
+        }
+    }
+    let length: usize = length.ok_or_else(|| invalid_frame("missing Content-Length"))?;
+    read_body(reader, buf, length, max).await
+}
+
+/// Read one message behind a 4-byte big-endian length into `buf`
+///
+/// Returns like [`read_frame`].
+async fn read_length_prefixed<R>(reader: &mut R, buf: &mut Vec<u8>, max: usize) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut prefix = [0u8; 4];
+    // EOF between messages ends the input; EOF inside a prefix is an error
+    if reader.fill_buf().await?.is_empty() {
+        return Ok(None);
+    }
+    reader.read_exact(&mut prefix).await?;
+    read_body(reader, buf, u32::from_be_bytes(prefix) as usize, max).await
+}
+
+/// Read a message body of `length` bytes into `buf`, or skip it if over `max`
+async fn read_body<R>(reader: &mut R, buf: &mut Vec<u8>, length: usize, max: usize) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    buf.clear();
+    if length > max {
+        let skipped = tokio::io::copy(&mut (&mut *reader).take(length as u64), &mut tokio::io::sink()).await?;
+        if skipped < length as u64 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        return Ok(Some(false));
+    }
+    buf.resize(length, 0);
+    reader.read_exact(&mut buf[..]).await?;
+    Ok(Some(true))
+}
+
+fn invalid_frame(reason: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid stdio frame: {}", reason))
+}
+
+/// Read one `\n`-terminated line into `buf`, holding at most `max` bytes
+///
+/// Returns `None` at EOF, `Some(true)` when a line was read, and `Some(false)`
+/// when the line was too long; its remainder is consumed and dropped.
+async fn read_line_bounded<R>(reader: &mut R, buf: &mut Vec<u8>, max: usize) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    buf.clear();
+    let mut read_any = false;
+    let mut overflow = false;
+
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(read_any.then_some(!overflow));
+        }
+        read_any = true;
+
+        let (chunk, used, done) = match available.iter().position(|&b| b == b'\n') {
+            Some(i) => (&available[..i], i + 1, true),
+            None => (available, available.len(), false),
+        };
+        if !overflow {
+            if buf.len() + chunk.len() > max {
+                overflow = true;
+                buf.clear();
+            } else {
+                buf.extend_from_slice(chunk);
+            }
+        }
+        reader.consume(used);
+
+        if done {
+            return Ok(Some(!overflow));
+        }
+    }
+}
+
+/// JSON-RPC error response with the standard message of `code`
+fn error_response(id: serde_json::Value, code: ErrorCode) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": ErrorObject::from(code),
+    })
+    .to_string()
+}
+
+/// Error response for input that is not a JSON-RPC request
+///
+/// Input that is not JSON gets a parse error; other input an invalid request
+/// error, with the request's id when it has a usable one.
+fn malformed_response(raw: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Err(_) => error_response(serde_json::Value::Null, ErrorCode::ParseError),
+        Ok(request) => {
+            let id = request
+                .get("id")
+                .filter(|id| id.is_string() || id.is_number())
+                .cloned()
+                .unwrap_or_default();
+            error_response(id, ErrorCode::InvalidRequest)
+        }
+    }
+}
+
+/// Error response for a call dropped after running for `timeout`
+fn timeout_response(raw: &str, timeout: Duration) -> String {
+    let (method, _) = describe_line(raw);
+    tracing::warn!("Stdio call timed out after {:?}: {}", timeout, method);
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": request_id(raw),
+        "error": {
+            "code": TIMEOUT_ERROR_CODE,
+            "message": format!("Request timed out after {}s: {}", timeout.as_secs_f64(), method),
+            "data": { "timeout_ms": timeout.as_millis() as u64 },
+        },
+    })
+    .to_string()
+}
+
+/// Error response for a request line over the size limit
+///
+/// The request was never parsed, so its id is unknown and reported as `null`.
+fn oversized_response(max: usize) -> String {
+    let error = jsonrpsee::types::error::reject_too_big_request(u32::try_from(max).unwrap_or(u32::MAX));
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": error,
+    })
+    .to_string()
+}
+
+/// Messages queued for the writer task
+const OUTPUT_QUEUE: usize = 1024;
+
+enum Outgoing {
+    Line(String),
+    /// Answered once every message queued before it is written
+    Flush(oneshot::Sender<()>),
+}
+
+/// The transport's output, written by a single task that owns the writer
+///
+/// Responses and subscription notifications are queued from any task; the
+/// writer task writes each as one line or frame, so messages never
+/// interleave. When writing fails, the task ends and every later `send`
+/// returns the error.
+#[derive(Clone)]
+struct Output {
+    queue: mpsc::Sender<Outgoing>,
+    error: Arc<Mutex<Option<std::io::Error>>>,
+}
+
+impl Output {
+    /// Start the task writing to `writer`
+    fn spawn<W>(mut writer: W, framing: StdioFraming) -> Self
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (queue, mut outgoing) = mpsc::channel(OUTPUT_QUEUE);
+        let error = Arc::new(Mutex::new(None));
+        let failed = error.clone();
+        crate::task::spawn("plexus.stdio.writer", async move {
+            while let Some(message) = outgoing.recv().await {
+                match message {
+                    Outgoing::Line(line) => {
+                        if let Err(e) = write_message(&mut writer, &line, framing).await {
+                            tracing::warn!("Stdio output failed: {}", e);
+                            *failed.lock().unwrap_or_else(|e| e.into_inner()) = Some(e);
+                            break;
+                        }
+                    }
+                    Outgoing::Flush(done) => {
+                        let _ = done.send(());
+                    }
                 }
             }
         });
+        Self { queue, error }
     }
 
-    Ok(())
+    /// Wait for room in the queue, e.g. to choose what to send once there is
+    async fn reserve(&self) -> std::io::Result<mpsc::Permit<'_, Outgoing>> {
+        self.queue.reserve().await.map_err(|_| self.closed())
+    }
+
+    /// Queue `message` to be written as one line or frame
+    async fn send(&self, message: String) -> std::io::Result<()> {
+        self.queue
+            .send(Outgoing::Line(message))
+            .await
+            .map_err(|_| self.closed())
+    }
+
+    /// Wait until every message queued so far is written
+    async fn flush(&self) -> std::io::Result<()> {
+        let (done, written) = oneshot::channel();
+        self.queue.send(Outgoing::Flush(done)).await.map_err(|_| self.closed())?;
+        written.await.map_err(|_| self.closed())
+    }
+
+    /// The error that stopped the writer task
+    fn closed(&self) -> std::io::Error {
+        match *self.error.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(ref e) => std::io::Error::new(e.kind(), e.to_string()),
+            None => std::io::Error::new(std::io::ErrorKind::BrokenPipe, "stdio output closed"),
+        }
+    }
+}
+
+/// Write a single message, newline-terminated or framed, and flush it
+async fn write_message<W>(out: &mut W, message: &str, framing: StdioFraming) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    match framing {
+        StdioFraming::Lines => {
+            out.write_all(message.as_bytes()).await?;
+            out.write_all(b"\n").await?;
+        }
+        StdioFraming::ContentLength => {
+            out.write_all(format!("Content-Length: {}\r\n\r\n", message.len()).as_bytes()).await?;
+            out.write_all(message.as_bytes()).await?;
+        }
+        StdioFraming::LengthPrefixed => {
+            let length = u32::try_from(message.len()).map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "Message too long for a 4-byte length prefix")
+            })?;
+            out.write_all(&length.to_be_bytes()).await?;
+            out.write_all(message.as_bytes()).await?;
+        }
+    }
+    out.flush().await
 }
@@ -0,0 +1,80 @@
+//! Logging for stdio servers
+//!
+//! A stdio server's stdout carries the protocol: one log line written there
+//! corrupts the stream, and the client drops the connection. With the
+//! `stdio-logging` feature, [`init`] installs a `RUST_LOG`-filtered subscriber
+//! writing to stderr, which MCP clients keep as the server's log, and
+//! [`init_file`] one writing to a file. Applications building their own
+//! subscriber add [`layer`] (or [`file_layer`]) to it in place of a stdout
+//! `fmt` layer.
+//!
+//! Output that reaches stdout some other way (`println!`, a library's own
+//! logger) is kept out of the protocol by `StdioConfig::with_stdout_guard`.
+
+use std::fs::OpenOptions;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing::Subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Level logged when `RUST_LOG` is unset or invalid
+const DEFAULT_FILTER: &str = "info";
+
+/// A `fmt` layer writing to stderr, to add to the application's subscriber
+///
+/// ```rust,ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(plexus_transport::stdio_logging::layer())
+///     .with(my_other_layer)
+///     .init();
+/// ```
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+}
+
+/// A `fmt` layer appending to the file at `path`, creating it if needed
+pub fn file_layer<S>(path: impl AsRef<Path>) -> std::io::Result<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(tracing_subscriber::fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false))
+}
+
+/// Install a global subscriber logging to stderr, filtered by `RUST_LOG`
+///
+/// Fails when the application already installed one.
+pub fn init() -> Result<(), TryInitError> {
+    tracing_subscriber::registry()
+        .with(env_filter())
+        .with(layer())
+        .try_init()
+}
+
+/// Install a global subscriber logging to the file at `path`, filtered by
+/// `RUST_LOG`
+pub fn init_file(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(env_filter())
+        .with(file_layer(path)?)
+        .try_init()?;
+    Ok(())
+}
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER))
+}
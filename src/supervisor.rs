@@ -0,0 +1,206 @@
+//! Automatic restart of crashed network transports
+//!
+//! With a [`RestartPolicy`], a transport task that ends with an error or panics
+//! while the server is not shutting down is restarted on the same address
+//! after an exponential backoff. Every crash is reported to an optional
+//! callback so it can be alerted on.
+//!
+//! The server supervises its MCP HTTP and REST HTTP transports. The WebSocket
+//! transport is not supervised: its accept loop retries failed accepts itself
+//! (backing off when out of file descriptors) and only ends when stopped, and
+//! a failing connection closes on its own. Stdio ends at EOF, which is not a
+//! crash. [`supervise`] restarts custom transports, e.g. an embedded MCP router
+//! served on a listener of your own.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use crate::server::TransportTask;
+
+/// A crashed transport, as reported to [`RestartPolicy::with_crash_handler`]
+#[derive(Debug, Clone)]
+pub struct TransportCrash {
+    /// Transport name, e.g. `"MCP HTTP"`
+    pub transport: &'static str,
+    /// Why the transport stopped (server error or panic message)
+    pub error: String,
+    /// Restarts of this transport so far, not counting the pending one
+    pub restarts: u32,
+    /// Delay before the next restart; `None` when the policy gives up
+    pub next_attempt: Option<Duration>,
+}
+
+/// How crashed transports are restarted
+///
+/// Durations serialize as seconds; the crash handler is not serialized.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    /// Delay before the first restart (default 1s)
+    #[serde(with = "crate::config::serde_secs")]
+    pub initial_backoff: Duration,
+    /// Upper bound for the doubling backoff (default 60s)
+    #[serde(with = "crate::config::serde_secs")]
+    pub max_backoff: Duration,
+    /// Consecutive restarts before giving up; `None` retries forever
+    pub max_restarts: Option<u32>,
+    #[serde(skip)]
+    crash_handler: Option<Arc<dyn Fn(&TransportCrash) + Send + Sync>>,
+}
+
+impl fmt::Debug for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestartPolicy")
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("max_restarts", &self.max_restarts)
+            .field("crash_handler", &self.crash_handler.is_some())
+            .finish()
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: None,
+            crash_handler: None,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Backoff starting at `initial` and doubling up to `max`
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Give up after `max_restarts` consecutive restarts
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// Call `handler` on every crash, e.g. to page someone
+    pub fn with_crash_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&TransportCrash) + Send + Sync + 'static,
+    {
+        self.crash_handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+/// Starts a transport on a bound listener
+pub type StartFn =
+    Box<dyn Fn(TcpListener) -> BoxFuture<'static, anyhow::Result<TransportTask>> + Send + Sync>;
+
+/// Run `first` and restart the transport on `addr` whenever it crashes
+///
+/// The returned task ends when the transport stops cleanly (shutdown), or
+/// with the last error once the policy gives up. Aborting it aborts the
+/// running transport too. `shutdown` tells a crash from a stop: a transport
+/// that ends after it is cancelled is not restarted.
+pub fn supervise(
+    transport: &'static str,
+    addr: SocketAddr,
+    first: TransportTask,
+    policy: RestartPolicy,
+    shutdown: CancellationToken,
+    start: StartFn,
+) -> TransportTask {
+    crate::task::spawn("plexus.supervisor", async move {
+        let mut task = AbortOnDrop(first);
+        let mut started = Instant::now();
+        let mut restarts = 0u32;
+        let mut backoff = policy.initial_backoff;
+
+        loop {
+            let mut error = match (&mut task.0).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(e)) => e.to_string(),
+                Err(e) => e.to_string(),
+            };
+            if shutdown.is_cancelled() {
+                return Ok(());
+            }
+
+            // A transport that ran for a while is not crash-looping
+            if started.elapsed() >= policy.max_backoff {
+                restarts = 0;
+                backoff = policy.initial_backoff;
+            }
+
+            loop {
+                let give_up = policy.max_restarts.is_some_and(|max| restarts >= max);
+                let crash = TransportCrash {
+                    transport,
+                    error: error.clone(),
+                    restarts,
+                    next_attempt: (!give_up).then_some(backoff),
+                };
+                if let Some(ref handler) = policy.crash_handler {
+                    handler(&crash);
+                }
+                if give_up {
+                    tracing::error!(
+                        "{} transport crashed ({}); giving up after {} restarts",
+                        transport,
+                        error,
+                        restarts
+                    );
+                    return Err(std::io::Error::other(error));
+                }
+                tracing::error!(
+                    "{} transport crashed ({}); restarting in {:?}",
+                    transport,
+                    error,
+                    backoff
+                );
+
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = shutdown.cancelled() => return Ok(()),
+                }
+                restarts += 1;
+                backoff = (backoff * 2).min(policy.max_backoff);
+
+                let restarted = match TcpListener::bind(addr).await {
+                    Ok(listener) => start(listener).await,
+                    Err(e) => Err(e.into()),
+                };
+                match restarted {
+                    Ok(next) => {
+                        tracing::info!("{} transport restarted on {}", transport, addr);
+                        task = AbortOnDrop(next);
+                        started = Instant::now();
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::error!("{} transport failed to restart: {}", transport, e);
+                        error = e.to_string();
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Aborts the transport task when the supervisor is aborted
+struct AbortOnDrop(TransportTask);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,33 @@
+//! Named task spawning
+//!
+//! Every task the transports spawn gets a name (`plexus.<transport>.<role>`)
+//! so tokio-console can tell them apart. Names need the `console` feature and
+//! a build with `RUSTFLAGS="--cfg tokio_unstable"`; otherwise tasks are
+//! spawned anonymously as before.
+
+use std::future::Future;
+
+use tokio::task::JoinHandle;
+
+/// Spawn `future` as a task called `name`
+#[cfg(all(tokio_unstable, feature = "console"))]
+pub(crate) fn spawn<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::task::Builder::new()
+        .name(name)
+        .spawn(future)
+        .expect("Failed to spawn task")
+}
+
+/// Spawn `future` as a task called `name`
+#[cfg(not(all(tokio_unstable, feature = "console")))]
+pub(crate) fn spawn<F>(_name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future)
+}
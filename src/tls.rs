@@ -0,0 +1,191 @@
+//! TLS termination shared by the network transports
+//!
+//! Loads PEM certificate chains and private keys from disk into a rustls
+//! `ServerConfig`. Only compiled when the `tls` feature is enabled.
+//!
+//! ## Mutual TLS
+//!
+//! When [`TlsConfig::client_ca_path`] is set, clients must present a certificate
+//! signed by one of the configured CAs or the handshake is rejected. The verified
+//! leaf certificate is attached to every HTTP request on that connection as a
+//! [`PeerCertificate`] extension, so downstream middleware (and MCP tools via
+//! `http::request::Parts`) can make decisions based on the client's identity.
+
+use std::fs::File;
+use std::future::Future;
+use std::io::BufReader;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::{ServerConnection, WebPkiClientVerifier};
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::TlsConfig;
+
+/// Build a rustls server configuration from the certificate/key in `config`
+///
+/// `alpn` lists the application protocols to advertise (e.g. `http/1.1`).
+pub fn server_config(config: &TlsConfig, alpn: &[&[u8]]) -> Result<Arc<ServerConfig>> {
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_key(config)?;
+
+    let builder = ServerConfig::builder();
+    let builder = match config.client_ca_path {
+        Some(ref ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for ca in load_certs(ca_path)? {
+                roots
+                    .add(ca)
+                    .with_context(|| format!("Invalid client CA in {}", ca_path.display()))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .context("Failed to build client certificate verifier")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .context("Invalid TLS certificate/key pair")?;
+    server_config.alpn_protocols = alpn.iter().map(|p| p.to_vec()).collect();
+
+    Ok(Arc::new(server_config))
+}
+
+/// Time a client gets to complete the TLS handshake, as axum-server allows
+///
+/// Connections count against the connection limit from the moment they are
+/// accepted, so clients that never send a ClientHello must not hold them.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build a tokio TLS acceptor for the certificate/key in `config`
+pub fn acceptor(config: &TlsConfig, alpn: &[&[u8]]) -> Result<TlsAcceptor> {
+    Ok(TlsAcceptor::from(server_config(config, alpn)?))
+}
+
+fn load_certs(path: &std::path::Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open TLS certificate {}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse TLS certificate {}", path.display()))?;
+
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+fn load_key(config: &TlsConfig) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(&config.key_path).with_context(|| {
+        format!("Failed to open TLS private key {}", config.key_path.display())
+    })?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to parse TLS private key {}", config.key_path.display()))?
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {}", config.key_path.display()))
+}
+
+// =============================================================================
+// Client certificate propagation
+// =============================================================================
+
+/// The verified client certificate of a mutual-TLS connection
+///
+/// Inserted into the extensions of every HTTP request received on the connection.
+#[derive(Debug, Clone)]
+pub struct PeerCertificate {
+    /// Subject distinguished name (e.g. `CN=agent-1, O=Example`)
+    pub subject: String,
+    /// DER-encoded leaf certificate
+    pub der: CertificateDer<'static>,
+}
+
+impl PeerCertificate {
+    /// Extract the client's leaf certificate from a completed handshake, if any
+    pub fn from_connection(conn: &ServerConnection) -> Option<Self> {
+        let der = conn.peer_certificates()?.first()?.clone().into_owned();
+        let subject = match x509_parser::parse_x509_certificate(der.as_ref()) {
+            Ok((_, cert)) => cert.subject().to_string(),
+            Err(e) => {
+                tracing::warn!("Failed to parse client certificate subject: {}", e);
+                String::new()
+            }
+        };
+        Some(Self { subject, der })
+    }
+}
+
+/// Tower service wrapper that attaches a connection's [`PeerCertificate`] to each request
+#[derive(Debug, Clone)]
+pub struct WithPeerCertificate<S> {
+    inner: S,
+    cert: Option<PeerCertificate>,
+}
+
+impl<S> WithPeerCertificate<S> {
+    pub fn new(inner: S, cert: Option<PeerCertificate>) -> Self {
+        Self { inner, cert }
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for WithPeerCertificate<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        if let Some(ref cert) = self.cert {
+            request.extensions_mut().insert(cert.clone());
+        }
+        self.inner.call(request)
+    }
+}
+
+/// axum-server acceptor: rustls handshake, then [`WithPeerCertificate`] on the service
+#[derive(Clone)]
+pub struct PeerCertificateAcceptor {
+    inner: axum_server::tls_rustls::RustlsAcceptor,
+}
+
+impl PeerCertificateAcceptor {
+    pub fn new(config: axum_server::tls_rustls::RustlsConfig) -> Self {
+        Self {
+            inner: axum_server::tls_rustls::RustlsAcceptor::new(config),
+        }
+    }
+}
+
+impl<I, S> axum_server::accept::Accept<I, S> for PeerCertificateAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = tokio_rustls::server::TlsStream<I>;
+    type Service = WithPeerCertificate<S>;
+    type Future =
+        Pin<Box<dyn Future<Output = std::io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let handshake = self.inner.accept(stream, service);
+        Box::pin(async move {
+            let (stream, service) = handshake.await?;
+            let cert = PeerCertificate::from_connection(stream.get_ref().1);
+            Ok((stream, WithPeerCertificate::new(service, cert)))
+        })
+    }
+}
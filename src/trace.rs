@@ -0,0 +1,110 @@
+//! Request spans and W3C trace context propagation
+//!
+//! Every transport handles a call inside a `request` span carrying the
+//! transport and method, so spans and events emitted by the activation nest
+//! under it. With the `otel` feature the span continues the caller's trace:
+//! its parent is read from the `traceparent`/`tracestate` headers of MCP HTTP
+//! and REST requests and of the WebSocket upgrade request, or from the `_meta`
+//! object of the params on stdio and MCP tool calls (where it takes precedence
+//! over the headers).
+//!
+//! Extraction uses the global text map propagator, so the application installs
+//! the W3C one together with its `tracing-opentelemetry` layer:
+//!
+//! ```rust,ignore
+//! opentelemetry::global::set_text_map_propagator(
+//!     opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+//! );
+//! ```
+//!
+//! Activations pass the trace on by injecting `Span::current().context()`
+//! into their own outgoing requests.
+
+use tracing::Span;
+
+/// The span a call on `transport` is handled in
+pub(crate) fn request_span(transport: &'static str, method: &str) -> Span {
+    tracing::info_span!(
+        "request",
+        otel.name = %method,
+        otel.kind = "server",
+        transport = transport,
+        method = %method,
+    )
+}
+
+#[cfg(feature = "otel")]
+pub(crate) use otel::*;
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::propagation::Extractor;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::Context;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    /// The caller's trace context, attached to a request's extensions
+    #[cfg(feature = "websocket")]
+    #[derive(Clone)]
+    pub(crate) struct RemoteContext(pub(crate) Context);
+
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    struct Headers<'a>(&'a http::HeaderMap);
+
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    impl Extractor for Headers<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|name| name.as_str()).collect()
+        }
+    }
+
+    #[cfg(any(feature = "stdio", feature = "mcp-http"))]
+    struct Meta<'a>(&'a serde_json::Map<String, serde_json::Value>);
+
+    #[cfg(any(feature = "stdio", feature = "mcp-http"))]
+    impl Extractor for Meta<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.as_str())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(String::as_str).collect()
+        }
+    }
+
+    /// The trace context in `headers`, if they carry a valid one
+    #[cfg(any(feature = "websocket", feature = "mcp-http", feature = "http-gateway"))]
+    pub(crate) fn from_headers(headers: &http::HeaderMap) -> Option<Context> {
+        valid(opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&Headers(headers))
+        }))
+    }
+
+    /// The trace context in a `_meta` object, if it carries a valid one
+    #[cfg(any(feature = "stdio", feature = "mcp-http"))]
+    pub(crate) fn from_meta(meta: &serde_json::Map<String, serde_json::Value>) -> Option<Context> {
+        valid(opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&Meta(meta))
+        }))
+    }
+
+    /// The trace context in the `_meta` object of JSON-RPC params
+    #[cfg(feature = "stdio")]
+    pub(crate) fn from_params(params: Option<&serde_json::Value>) -> Option<Context> {
+        params?.get("_meta")?.as_object().and_then(from_meta)
+    }
+
+    /// Make `span` continue the trace of `parent`
+    pub(crate) fn set_parent(span: &Span, parent: Context) {
+        span.set_parent(parent);
+    }
+
+    fn valid(cx: Context) -> Option<Context> {
+        cx.span().span_context().is_valid().then_some(cx)
+    }
+}
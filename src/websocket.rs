@@ -1,60 +1,670 @@
 //! WebSocket transport - JSON-RPC over WebSocket
+//!
+//! The same listener answers plain HTTP JSON-RPC POSTs, so one port serves
+//! both WebSocket clients and one-shot HTTP callers (curl, health checks).
+//! On Unix, the server can accept connections on a Unix socket as well.
 
 use anyhow::Result;
-use jsonrpsee::server::{Server, ServerHandle};
+use jsonrpsee::core::middleware::RpcServiceBuilder;
+use jsonrpsee::server::middleware::http::HostFilterLayer;
+use jsonrpsee::server::{
+    serve_with_graceful_shutdown, stop_channel, BatchRequestConfig, ConnectionGuard, PingConfig, Server,
+    ServerHandle,
+};
 use jsonrpsee::RpcModule;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
+use crate::auth::{ApiKeyAuth, TransportAuth};
+use crate::broadcast::websocket::{relay, Drain, UpgradeRequested};
+use crate::broadcast::Broadcaster;
 use crate::config::WebSocketConfig;
+use crate::hot_swap::{fixed_methods, MethodSource};
+use crate::observer::{ConnectionInfo, ObservedConnection};
+use crate::rate_limit::RateLimiter;
+use crate::request::RawRequestContext;
+use crate::resume::websocket::Resumption;
+use crate::slow::SlowRequests;
 
 /// Serve RPC module over WebSocket
 ///
-/// Starts a WebSocket server that accepts JSON-RPC requests.
-/// When `config.api_key` is set and the `mcp-gateway` feature is enabled,
-/// the HTTP upgrade request must carry `Authorization: Bearer <key>` or the
-/// connection is rejected with 401.
+/// Starts a WebSocket server that accepts JSON-RPC requests. Requests POSTed
+/// to the same address over plain HTTP are answered in the response body;
+/// subscriptions need a WebSocket connection. With `config.unix_socket`
+/// (Unix only), connections are also accepted on that Unix socket, for local
+/// clients that reach jsonrpsee's server through one.
+/// When `config.auth` (or the legacy `config.api_key`) is set, the HTTP upgrade
+/// request must carry a valid key in `Authorization: Bearer <key>` or
+/// `X-Api-Key: <key>`, or the connection is rejected with 401. The caller's
+/// `ApiKeyIdentity` is stored in request Extensions for use by RPC methods.
+/// With `config.jwt` (feature `jwt-auth`), a valid bearer JWT is also accepted
+/// and its `JwtClaims` are stored in request Extensions instead.
 ///
 /// When `session_validator` is provided, the server will:
 /// - Extract cookies from the HTTP upgrade request
 /// - Validate them using the SessionValidator
 /// - Store the resulting AuthContext in request Extensions for use by RPC methods
 ///
+/// When `config.tls` is set (feature `tls`), connections are TLS-terminated
+/// in-process and the server speaks `wss://`. With a client CA configured,
+/// clients without a valid certificate are rejected during the handshake and
+/// the certificate subject is available as a `PeerCertificate` extension.
+///
+/// When `config.authorization` is set, every call is checked against the policy
+/// using the caller's `CallerIdentity`; denied calls get a JSON-RPC error.
+/// When `config.rate_limit` is set, calls over the client's limit get a JSON-RPC
+/// error too. The peer address of TCP clients is available as an
+/// `axum::extract::ConnectInfo<SocketAddr>` extension. Peers rejected by
+/// `config.ip_filter` are dropped as soon as they are accepted.
+///
+/// When `config.audit` is set, every call and its outcome (including denied
+/// and rate-limited calls) is recorded in the audit log; `config.access_log`
+/// writes them to the access log and `config.metrics` (feature `metrics`)
+/// counts them and the open connections. `config.middleware`, then middleware
+/// installed with `config.rpc_middleware`, runs on the calls that were not rejected.
+///
+/// Calls still running after `config.slow_request_threshold` are logged as slow.
+/// Calls still running after their limit in `config.request_timeouts` are
+/// dropped and answered with a `TIMEOUT_ERROR_CODE` error.
+/// `config.capture` records every request and response (subscription
+/// notifications are not captured).
+/// `config.observer` is told about every accepted connection and its closing;
+/// each call carries the connection's `ConnectionInfo` as an extension.
+///
+/// Each call also carries a `RawRequestContext` with the headers, URI (and so
+/// query parameters) and peer address of the upgrade request, or of the POST
+/// for plain HTTP calls, so methods can tell clients apart by user agent or a
+/// client id parameter, or extract a `PlexusRequest` from it. Its `auth` is
+/// unset; the caller's credentials are the `ApiKeyIdentity`, `JwtClaims` and
+/// `Arc<AuthContext>` extensions described above.
+///
+/// Messages larger than `config.max_message_size` are rejected with a JSON-RPC
+/// "Request is too big" error instead of being buffered. At most
+/// `config.max_connections` sockets are open at once; `max_subscriptions_per_connection`,
+/// `max_request_batch_size` and `message_buffer_capacity` bound the per-connection state.
+/// With `config.ping_interval`, every connection is pinged that often and
+/// closed after `max_missed_pings` pings go unanswered for `inactive_limit`.
+/// With `config.broadcaster`, every WebSocket connection also receives the
+/// notifications sent through it.
+/// With `config.subscription_resume`, subscriptions outlive their connection
+/// for the resume window, and a reconnecting client picks them up with its
+/// resume token (see [`crate::resume`]).
+///
+/// Once the returned handle is stopped, no new connections are accepted and
+/// open ones stop reading calls. Each connection answers its in-flight calls
+/// and is then closed with a "going away" close frame (code 1001); connections
+/// still busy after `config.drain_timeout` are closed anyway.
+/// `ServerHandle::stopped` resolves once every connection is closed.
+///
+/// Upgrade requests carrying an `Origin` header are checked against
+/// `config.origin_check` (DNS-rebinding protection) and rejected with 403 when
+/// the origin is not allowed; by default only localhost origins may connect to
+/// a loopback-bound server. With `config.allowed_hosts`, requests whose `Host`
+/// header is not listed are rejected with 403 as well.
+///
 /// Returns a handle that can be used to stop the server.
 pub async fn serve_websocket(
     module: RpcModule<()>,
     config: WebSocketConfig,
     session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
 ) -> Result<ServerHandle> {
-    tracing::info!("Starting WebSocket transport at ws://{}", config.addr);
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    serve_websocket_with_listener(listener, module, config, session_validator).await
+}
+
+/// Serve RPC module over WebSocket on an already bound listener
+///
+/// Like [`serve_websocket`], but `config.addr` is ignored in favour of
+/// `listener`. Bind to port 0 and read `listener.local_addr()` first to serve on
+/// an OS-assigned port.
+pub async fn serve_websocket_with_listener(
+    listener: tokio::net::TcpListener,
+    module: RpcModule<()>,
+    config: WebSocketConfig,
+    session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
+) -> Result<ServerHandle> {
+    let methods = fixed_methods(jsonrpsee::Methods::from(module));
+    serve_websocket_with_source(listener, methods, config, session_validator, CancellationToken::new()).await
+}
+
+/// Serve RPC module over WebSocket on a bound Unix socket only
+///
+/// Like [`serve_websocket_with_listener`], for clients on the same host that
+/// reach jsonrpsee's server through a Unix socket, e.g. a jsonrpsee client
+/// with a Unix socket connector. `config.addr` and `config.unix_socket` are
+/// ignored in favour of `listener`, and the socket file is left in place.
+///
+/// Unix socket clients have no peer address: `config.ip_filter` does not
+/// apply to them (the socket file's permissions decide who may connect), and
+/// they share one bucket when rate limited by IP.
+#[cfg(unix)]
+pub async fn serve_websocket_with_unix_listener(
+    listener: tokio::net::UnixListener,
+    module: RpcModule<()>,
+    config: WebSocketConfig,
+    session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
+) -> Result<ServerHandle> {
+    let methods = fixed_methods(jsonrpsee::Methods::from(module));
+    let listeners = Listeners {
+        tcp: None,
+        unix: Some(listener),
+        bound: None,
+    };
+    serve_listeners(listeners, methods, config, session_validator, CancellationToken::new()).await
+}
+
+/// Serve WebSocket connections, each with the methods current when it was accepted
+///
+/// Connections are accepted on `listener`, and on `config.unix_socket` when set.
+/// Cancelling `abort` drops every connection at once, in-flight calls included,
+/// e.g. once a stopped server is past its drain timeout.
+pub(crate) async fn serve_websocket_with_source(
+    listener: tokio::net::TcpListener,
+    methods: MethodSource,
+    config: WebSocketConfig,
+    session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
+    abort: CancellationToken,
+) -> Result<ServerHandle> {
+    #[cfg(unix)]
+    let listeners = Listeners {
+        tcp: Some(listener),
+        unix: config.unix_socket.as_deref().map(listen::bind_unix).transpose()?,
+        bound: config.unix_socket.clone(),
+    };
+    #[cfg(not(unix))]
+    let listeners = {
+        anyhow::ensure!(
+            config.unix_socket.is_none(),
+            "WebSocket Unix sockets are only supported on Unix"
+        );
+        Listeners { tcp: Some(listener) }
+    };
+    serve_listeners(listeners, methods, config, session_validator, abort).await
+}
+
+/// Serve WebSocket connections accepted on `listeners` until `abort` is cancelled
+async fn serve_listeners(
+    listeners: Listeners,
+    methods: MethodSource,
+    config: WebSocketConfig,
+    session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
+    abort: CancellationToken,
+) -> Result<ServerHandle> {
+    let local_addr = listeners.tcp_addr()?;
+    #[cfg(feature = "tls")]
+    let acceptor = config
+        .tls
+        .as_ref()
+        .map(|tls| crate::tls::acceptor(tls, &[b"http/1.1"]))
+        .transpose()?;
+    #[cfg(feature = "tls")]
+    let scheme = if acceptor.is_some() { "wss" } else { "ws" };
+    #[cfg(not(feature = "tls"))]
+    let scheme = "ws";
 
-    let has_bearer = config.api_key.is_some();
-    let has_session = session_validator.is_some();
+    // The auth middleware passes requests through untouched when neither an
+    // authentication method nor a session validator is configured.
+    let auth = resolve_auth(&config);
+    // Unix socket clients are on this host, like loopback ones
+    let origins = config
+        .origin_check
+        .resolve(local_addr.unwrap_or(listen::LOOPBACK), &[])
+        .map(Arc::new);
+    // Checked before the origin, as a request for another host was not meant for us
+    let hosts = config
+        .allowed_hosts
+        .as_ref()
+        .map(|hosts| {
+            HostFilterLayer::new(hosts.iter().map(String::as_str))
+                .map_err(|e| anyhow::anyhow!("Invalid WebSocket allowed host in {:?}: {}", hosts, e))
+        })
+        .transpose()?;
+    let middleware = tower::ServiceBuilder::new()
+        .option_layer(hosts)
+        .layer_fn(move |service| CombinedAuthMiddleware {
+            service,
+            auth: auth.clone(),
+            session_validator: session_validator.clone(),
+            origins: origins.clone(),
+        });
 
-    if has_bearer || has_session {
-        let expected_bearer = config.api_key.map(|key| format!("Bearer {}", key));
-        let middleware = tower::ServiceBuilder::new().layer_fn(move |service| {
-            CombinedAuthMiddleware {
+    // Every call runs in a request span; the capture, slow-request, metrics,
+    // access log and audit layers and call guard are no-ops when not configured;
+    // transport and custom middleware run innermost, on calls the guard let
+    // through, and the time limits closest to the methods
+    let policy = config.authorization.clone();
+    let rate_limiter = config.rate_limit.clone().map(|c| Arc::new(RateLimiter::new(c)));
+    let auditor = config.audit.clone();
+    let hooks = (!config.middleware.is_empty())
+        .then(|| Arc::<[Arc<dyn crate::middleware::TransportMiddleware>]>::from(config.middleware.clone()));
+    let timeouts = (!config.request_timeouts.is_empty()).then(|| Arc::new(config.request_timeouts.clone()));
+    let max_response_size = config.max_response_size;
+    let slow = SlowRequests::new(config.slow_request_threshold);
+    #[cfg(feature = "metrics")]
+    let slow = slow.with_metrics(config.metrics.clone());
+    let rpc_middleware = RpcServiceBuilder::new()
+        .layer_fn(|service| SpanMiddleware { service })
+        .option_layer(config.capture.clone().map(|capture| {
+            tower::layer::layer_fn(move |service| CaptureMiddleware {
+                service,
+                capture: capture.clone(),
+            })
+        }))
+        .option_layer(config.slow_request_threshold.map(|_| {
+            tower::layer::layer_fn(move |service| SlowMiddleware {
+                service,
+                slow: slow.clone(),
+            })
+        }));
+    #[cfg(feature = "metrics")]
+    let rpc_middleware = rpc_middleware.option_layer(config.metrics.clone().map(|metrics| {
+        tower::layer::layer_fn(move |service| MetricsMiddleware {
+            service,
+            metrics: metrics.clone(),
+        })
+    }));
+    let rpc_middleware = rpc_middleware
+        .option_layer(config.access_log.clone().map(|log| {
+            tower::layer::layer_fn(move |service| AccessLogMiddleware {
                 service,
-                expected_bearer: expected_bearer.clone(),
-                session_validator: session_validator.clone(),
+                log: log.clone(),
+            })
+        }))
+        .layer_fn(move |service| AuditMiddleware {
+            service,
+            auditor: auditor.clone(),
+        })
+        .layer_fn(move |service| CallGuardMiddleware {
+            service,
+            policy: policy.clone(),
+            rate_limiter: rate_limiter.clone(),
+        })
+        .option_layer(hooks.map(|middleware| {
+            tower::layer::layer_fn(move |service| HooksMiddleware {
+                service,
+                middleware: middleware.clone(),
+            })
+        }))
+        .option_layer(config.rpc_middleware.clone())
+        .option_layer(timeouts.map(|timeouts| {
+            tower::layer::layer_fn(move |service| TimeoutMiddleware {
+                service,
+                timeouts: timeouts.clone(),
+                max_response_size,
+            })
+        }));
+
+    let mut server = Server::builder();
+    if let Some(interval) = config.ping_interval {
+        server = server.enable_ws_ping(
+            PingConfig::new()
+                .ping_interval(interval)
+                .inactive_limit(config.inactive_limit)
+                .max_failures(config.max_missed_pings.max(1)),
+        );
+    }
+    let batches = match config.max_request_batch_size {
+        None => BatchRequestConfig::Unlimited,
+        Some(0) => BatchRequestConfig::Disabled,
+        Some(max) => BatchRequestConfig::Limit(max),
+    };
+    let svc_builder = server
+        .max_request_body_size(config.max_message_size)
+        .max_response_body_size(config.max_response_size)
+        .max_connections(config.max_connections)
+        .max_subscriptions_per_connection(config.max_subscriptions_per_connection)
+        .set_batch_request_config(batches)
+        .set_message_buffer_capacity(config.message_buffer_capacity)
+        .set_http_middleware(middleware)
+        .set_rpc_middleware(rpc_middleware)
+        .to_service_builder();
+    let ip_filter = config.ip_filter.clone();
+    // jsonrpsee only enforces `max_connections` per request; this guard also
+    // bounds open sockets so idle clients cannot pile up connections
+    let conn_guard = ConnectionGuard::new(config.max_connections as usize);
+    #[cfg(feature = "metrics")]
+    let metrics = config.metrics.clone();
+    let observer = config.observer.clone();
+    let broadcaster = config.broadcaster.clone();
+    let drain_timeout = config.drain_timeout;
+    let resumption = config
+        .subscription_resume
+        .map(|resume| Resumption::new(resume, config.message_buffer_capacity as usize));
+
+    // jsonrpsee's `Server` owns its `TcpListener` and hides the peer address, so
+    // we run the accept loop ourselves and hand each connection (TLS-terminated
+    // when configured) to jsonrpsee's tower service.
+    let (stop_handle, server_handle) = stop_channel();
+    if let Some(addr) = local_addr {
+        tracing::info!("Starting WebSocket transport at {}://{}", scheme, addr);
+    }
+    #[cfg(unix)]
+    if let Some(path) = listeners.unix_path() {
+        tracing::info!("Starting WebSocket transport on Unix socket {}", path.display());
+    }
+
+    crate::task::spawn("plexus.websocket.accept", async move {
+        loop {
+            let accepted = tokio::select! {
+                res = listeners.accept() => res,
+                _ = stop_handle.clone().shutdown() => break,
+                _ = abort.cancelled() => break,
+            };
+            let (sock, peer) = match accepted {
+                Ok(x) => x,
+                Err(e) if listen::is_connection_error(&e) => {
+                    tracing::debug!("WebSocket accept: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    // Out of file descriptors or memory: retrying at once would
+                    // spin until open connections free some
+                    tracing::error!("WebSocket accept: {}", e);
+                    tokio::select! {
+                        _ = tokio::time::sleep(listen::ACCEPT_BACKOFF) => continue,
+                        _ = stop_handle.clone().shutdown() => break,
+                        _ = abort.cancelled() => break,
+                    }
+                }
+            };
+            let client = listen::describe(peer);
+
+            // Unix socket clients are checked by the socket file's permissions instead
+            if let (Some(filter), Some(peer)) = (&ip_filter, peer) {
+                if !filter.check_peer(peer, "WebSocket") {
+                    continue;
+                }
             }
-        });
-        let server = Server::builder()
-            .set_http_middleware(middleware)
-            .build(config.addr)
-            .await?;
-        let handle = server.start(module);
-        return Ok(handle);
+
+            let Some(permit) = conn_guard.try_acquire() else {
+                tracing::warn!(
+                    "WebSocket connection from {} rejected: connection limit ({}) reached",
+                    client,
+                    conn_guard.max_connections()
+                );
+                continue;
+            };
+
+            #[cfg(feature = "tls")]
+            let acceptor = acceptor.clone();
+            let connection = ConnectionInfo::new("websocket", peer);
+            let upgrade = UpgradeRequested::default();
+            let broadcasts = broadcaster.as_ref().map(Broadcaster::subscribe);
+            let mut served = methods();
+            if let Some(ref resumption) = resumption {
+                served = resumption.connection(served);
+            }
+            let svc = WithConnectInfo {
+                inner: svc_builder.build(served, stop_handle.clone()),
+                peer,
+                connection: connection.clone(),
+                upgrade: upgrade.clone(),
+            };
+            let stop = stop_handle.clone();
+            let drain = Drain {
+                stop: stop_handle.clone(),
+                timeout: drain_timeout,
+            };
+            #[cfg(feature = "metrics")]
+            let open = metrics.as_ref().map(|metrics| metrics.connection("websocket"));
+            let observed = observer
+                .clone()
+                .map(|observer| ObservedConnection::open(observer, connection));
+
+            let abort = abort.clone();
+            let name = format!("plexus.websocket.connection {}", client);
+            let serve = async move {
+                // Held for the lifetime of the connection
+                let _permit = permit;
+                #[cfg(feature = "metrics")]
+                let _open = open;
+                let _observed = observed;
+
+                #[cfg(feature = "tls")]
+                if let Some(acceptor) = acceptor {
+                    let handshake = tokio::time::timeout(crate::tls::HANDSHAKE_TIMEOUT, acceptor.accept(sock));
+                    let stream = match handshake.await {
+                        Ok(Ok(stream)) => stream,
+                        Ok(Err(e)) => {
+                            tracing::debug!("WebSocket TLS handshake with {} failed: {}", client, e);
+                            return;
+                        }
+                        Err(_) => {
+                            tracing::debug!("WebSocket TLS handshake with {} timed out", client);
+                            return;
+                        }
+                    };
+
+                    // Attach the verified client certificate (mutual TLS) to every request
+                    let cert = crate::tls::PeerCertificate::from_connection(stream.get_ref().1);
+                    if let Some(ref cert) = cert {
+                        tracing::debug!("WebSocket TLS client {} authenticated as {}", client, cert.subject);
+                    }
+                    let svc = crate::tls::WithPeerCertificate::new(svc, cert);
+                    let stream = relay(stream, broadcasts, upgrade, drain);
+                    if let Err(e) = serve_with_graceful_shutdown(stream, svc, stop.shutdown()).await {
+                        tracing::debug!("WebSocket TLS connection closed: {}", e);
+                    }
+                    return;
+                }
+
+                let sock = relay(sock, broadcasts, upgrade, drain);
+                if let Err(e) = serve_with_graceful_shutdown(sock, svc, stop.shutdown()).await {
+                    tracing::debug!("WebSocket connection closed: {}", e);
+                }
+            };
+            crate::task::spawn(&name, async move {
+                tokio::select! {
+                    _ = serve => {}
+                    _ = abort.cancelled() => tracing::debug!("WebSocket connection aborted"),
+                }
+            });
+        }
+    });
+
+    Ok(server_handle)
+}
+
+/// The effective authentication methods for a WebSocket config
+///
+/// The API key policy is `config.auth`, else the legacy single `api_key`.
+fn resolve_auth(config: &WebSocketConfig) -> TransportAuth {
+    TransportAuth {
+        api_key: config
+            .auth
+            .clone()
+            .or_else(|| config.api_key.clone().map(|key| ApiKeyAuth::keys([key]))),
+        #[cfg(feature = "jwt-auth")]
+        jwt: config.jwt.clone(),
     }
+}
 
-    let server = Server::builder().build(config.addr).await?;
-    let handle = server.start(module);
-    Ok(handle)
+/// Tower service wrapper that attaches the connection's peer address,
+/// [`ConnectionInfo`] and [`RawRequestContext`] to each request
+///
+/// It also notes upgrade requests, after which broadcasts may be relayed.
+///
+/// With feature `otel`, the trace context of the upgrade request's
+/// `traceparent` header is attached too, as the parent of every call's span.
+#[derive(Clone)]
+struct WithConnectInfo<S> {
+    inner: S,
+    /// `None` for Unix socket clients
+    peer: Option<SocketAddr>,
+    connection: ConnectionInfo,
+    upgrade: UpgradeRequested,
 }
 
+impl<S, B> tower::Service<http::Request<B>> for WithConnectInfo<S>
+where
+    S: tower::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        self.upgrade.observe(&request);
+        if let Some(peer) = self.peer {
+            request
+                .extensions_mut()
+                .insert(axum::extract::ConnectInfo(peer));
+        }
+        request.extensions_mut().insert(self.connection.clone());
+        let raw = RawRequestContext {
+            headers: request.headers().clone(),
+            uri: request.uri().clone(),
+            auth: None,
+            peer: self.peer,
+        };
+        request.extensions_mut().insert(raw);
+        #[cfg(feature = "otel")]
+        if let Some(parent) = crate::trace::from_headers(request.headers()) {
+            request.extensions_mut().insert(crate::trace::RemoteContext(parent));
+        }
+        self.inner.call(request)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Listening sockets: TCP, and Unix sockets on Unix
+// ---------------------------------------------------------------------------
+
+mod listen {
+    use std::io;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    #[cfg(unix)]
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use tokio::net::{TcpListener, TcpStream};
+    #[cfg(unix)]
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// Pause after an accept error that is not about a single connection
+    pub(super) const ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+    /// Whether an accept error only concerns the connection being accepted
+    ///
+    /// Anything else (`EMFILE`, `ENFILE`, `ENOMEM`, ...) will fail the next
+    /// accept too, so the loop backs off as hyper and axum do.
+    pub(super) fn is_connection_error(e: &io::Error) -> bool {
+        matches!(
+            e.kind(),
+            io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionAborted | io::ErrorKind::ConnectionReset
+        )
+    }
+
+    /// Stands in for the local address of a server on Unix sockets only
+    pub(super) const LOOPBACK: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+    /// An accepted connection
+    #[cfg(unix)]
+    pub(super) type Socket = tokio_util::either::Either<TcpStream, UnixStream>;
+    #[cfg(not(unix))]
+    pub(super) type Socket = TcpStream;
+
+    /// The sockets a WebSocket server accepts connections on
+    pub(super) struct Listeners {
+        pub(super) tcp: Option<TcpListener>,
+        #[cfg(unix)]
+        pub(super) unix: Option<UnixListener>,
+        /// Socket file the server bound, removed when it stops
+        #[cfg(unix)]
+        pub(super) bound: Option<PathBuf>,
+    }
+
+    impl Listeners {
+        pub(super) fn tcp_addr(&self) -> io::Result<Option<SocketAddr>> {
+            self.tcp.as_ref().map(TcpListener::local_addr).transpose()
+        }
+
+        #[cfg(unix)]
+        pub(super) fn unix_path(&self) -> Option<PathBuf> {
+            let addr = self.unix.as_ref()?.local_addr().ok()?;
+            addr.as_pathname().map(Path::to_path_buf)
+        }
+
+        /// Accept the next connection, with the peer address of TCP clients
+        #[cfg(unix)]
+        pub(super) async fn accept(&self) -> io::Result<(Socket, Option<SocketAddr>)> {
+            tokio::select! {
+                accepted = accept_tcp(self.tcp.as_ref()) => {
+                    accepted.map(|(sock, peer)| (Socket::Left(sock), Some(peer)))
+                }
+                accepted = accept_unix(self.unix.as_ref()) => {
+                    accepted.map(|sock| (Socket::Right(sock), None))
+                }
+            }
+        }
+
+        /// Accept the next connection, with its peer address
+        #[cfg(not(unix))]
+        pub(super) async fn accept(&self) -> io::Result<(Socket, Option<SocketAddr>)> {
+            accept_tcp(self.tcp.as_ref())
+                .await
+                .map(|(sock, peer)| (sock, Some(peer)))
+        }
+    }
+
+    #[cfg(unix)]
+    impl Drop for Listeners {
+        fn drop(&mut self) {
+            if let Some(ref path) = self.bound {
+                if let Err(e) = std::fs::remove_file(path) {
+                    tracing::debug!("Failed to remove WebSocket socket {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    async fn accept_tcp(listener: Option<&TcpListener>) -> io::Result<(TcpStream, SocketAddr)> {
+        match listener {
+            Some(listener) => listener.accept().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    #[cfg(unix)]
+    async fn accept_unix(listener: Option<&UnixListener>) -> io::Result<UnixStream> {
+        match listener {
+            Some(listener) => listener.accept().await.map(|(sock, _)| sock),
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Bind a Unix socket at `path`
+    ///
+    /// A socket file nobody listens on any more, left by a server that did not
+    /// stop cleanly, is replaced.
+    #[cfg(unix)]
+    pub(super) fn bind_unix(path: &Path) -> io::Result<UnixListener> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let stale = std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket())
+            && std::os::unix::net::UnixStream::connect(path).is_err();
+        if stale {
+            std::fs::remove_file(path)?;
+        }
+        UnixListener::bind(path)
+    }
+
+    /// A client's name in logs and task names
+    pub(super) fn describe(peer: Option<SocketAddr>) -> String {
+        peer.map_or_else(|| "unix socket".to_string(), |peer| peer.to_string())
+    }
+}
+
+use listen::Listeners;
+
 // ---------------------------------------------------------------------------
 // Combined auth middleware for jsonrpsee's HTTP upgrade path
-// Supports both Bearer tokens (for API keys) and Cookies (for session auth)
+// Supports both API keys (Bearer / X-Api-Key) and Cookies (for session auth)
 // (only compiled when the mcp-gateway feature is active)
 // ---------------------------------------------------------------------------
 
@@ -72,16 +682,18 @@ mod auth {
     type HttpRequest<B> = http::Request<B>;
     type HttpResponse = http::Response<jsonrpsee::server::HttpBody>;
 
-    /// Tower middleware layer that handles both Bearer token and Cookie authentication.
+    /// Tower middleware layer that handles both API key/JWT and Cookie authentication.
     ///
-    /// - If `expected_bearer` is set: validates Authorization header
+    /// - If `origins` is set: rejects requests from disallowed `Origin`s with 403 before authenticating
+    /// - If `auth` is enabled: validates `Authorization: Bearer` / `X-Api-Key` and stores ApiKeyIdentity (or JwtClaims) in Extensions
     /// - If `session_validator` is set: validates Cookie header and stores AuthContext in Extensions
-    /// - Both can be enabled simultaneously (Bearer for API access, Cookies for browser sessions)
+    /// - Both can be enabled simultaneously (API keys for programmatic access, Cookies for browser sessions)
     #[derive(Clone)]
     pub(super) struct CombinedAuthMiddleware<S> {
         pub(super) service: S,
-        pub(super) expected_bearer: Option<String>,
+        pub(super) auth: crate::auth::TransportAuth,
         pub(super) session_validator: Option<Arc<dyn plexus_core::plexus::SessionValidator>>,
+        pub(super) origins: Option<Arc<crate::origin_check::AllowedOrigins>>,
     }
 
     impl<S, B> Service<HttpRequest<B>> for CombinedAuthMiddleware<S>
@@ -103,45 +715,42 @@ mod auth {
         }
 
         fn call(&mut self, mut request: HttpRequest<B>) -> Self::Future {
-            let service = self.service.clone();
+            let mut service = self.service.clone();
+            let auth = self.auth.clone();
+            let session_validator = self.session_validator.clone();
+            let origins = self.origins.clone();
 
-            // Check Bearer token if configured
-            if let Some(ref expected) = self.expected_bearer {
-                let auth_ok = request
-                    .headers()
-                    .get(http::header::AUTHORIZATION)
-                    .and_then(|v| v.to_str().ok())
-                    .map(|v| v == expected)
-                    .unwrap_or(false);
+            Box::pin(async move {
+                // Reject cross-origin browser connections (DNS rebinding)
+                if let Some(origins) = origins {
+                    if !origins.allows(request.headers()) {
+                        tracing::warn!(
+                            "WebSocket connection rejected: origin {:?} not allowed",
+                            request.headers().get(http::header::ORIGIN)
+                        );
+                        return Ok(crate::origin_check::forbidden_origin_response());
+                    }
+                }
 
-                if !auth_ok {
+                // Check API key / JWT if configured
+                if !auth.authenticate(&mut request).await {
                     tracing::warn!(
-                        "WebSocket auth rejected: missing or invalid Authorization header (uri={})",
+                        "WebSocket auth rejected: missing or invalid credentials (uri={})",
                         request.uri()
                     );
-                    let resp = http::Response::builder()
-                        .status(http::StatusCode::UNAUTHORIZED)
-                        .header(http::header::WWW_AUTHENTICATE, "Bearer realm=\"plexus\"")
-                        .header(http::header::CONTENT_TYPE, "text/plain")
-                        .body(jsonrpsee::server::HttpBody::from("Unauthorized"))
-                        .expect("static response is valid");
-                    return Box::pin(async move { Ok(resp) });
+                    return Ok(crate::auth::unauthorized_response());
                 }
-            }
 
-            // Extract and validate session if validator is configured.
-            // Tokens MUST be carried in the Cookie header — query parameters are
-            // rejected because they leak to logs, browser history, and Referer headers.
-            let session_validator = self.session_validator.clone();
-            if let Some(validator) = session_validator {
-                let cookie_str = request.headers()
-                    .get(http::header::COOKIE)
-                    .and_then(|v| v.to_str().ok())
-                    .map(|s| s.to_string());
-
-                if let Some(cookies) = cookie_str {
-                    let mut service = service;
-                    return Box::pin(async move {
+                // Extract and validate session if validator is configured.
+                // Tokens MUST be carried in the Cookie header — query parameters are
+                // rejected because they leak to logs, browser history, and Referer headers.
+                if let Some(validator) = session_validator {
+                    let cookie_str = request.headers()
+                        .get(http::header::COOKIE)
+                        .and_then(|v| v.to_str().ok())
+                        .map(|s| s.to_string());
+
+                    if let Some(cookies) = cookie_str {
                         let auth_ctx = validator.validate(&cookies).await;
 
                         if let Some(ctx) = auth_ctx {
@@ -150,18 +759,1044 @@ mod auth {
                         } else {
                             tracing::debug!("Cookie present but validation failed, proceeding without auth");
                         }
+                    } else {
+                        tracing::debug!("No cookie present, proceeding without auth");
+                    }
+                }
+
+                service.call(request).await.map_err(Into::into)
+            })
+        }
+    }
+}
+
+use auth::CombinedAuthMiddleware;
+
+// ---------------------------------------------------------------------------
+// Request span middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod spans {
+    use std::future::Future;
+
+    use jsonrpsee::core::middleware::{Batch, Extensions, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::MethodResponse;
+    use tracing::{Instrument, Span};
+
+    use crate::trace::request_span;
+
+    /// RPC middleware running every call in a `request` span, so everything
+    /// below it (including the activation) is traced per call
+    ///
+    /// A batch is handled in a single span with method `batch`. With feature
+    /// `otel` the span continues the trace of the upgrade request.
+    #[derive(Clone)]
+    pub(super) struct SpanMiddleware<S> {
+        pub(super) service: S,
+    }
+
+    fn span(method: &str, extensions: &Extensions) -> Span {
+        let span = request_span("websocket", method);
+        #[cfg(feature = "otel")]
+        if let Some(remote) = extensions.get::<crate::trace::RemoteContext>() {
+            crate::trace::set_parent(&span, remote.0.clone());
+        }
+        #[cfg(not(feature = "otel"))]
+        let _ = extensions;
+        span
+    }
+
+    impl<S> RpcServiceT for SpanMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let span = span(request.method_name(), request.extensions());
+            let service = self.service.clone();
+
+            async move { service.call(request).await }.instrument(span)
+        }
+
+        fn batch<'a>(&self, mut batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let span = span("batch", batch.extensions());
+            let service = self.service.clone();
 
-                        service.call(request).await.map_err(Into::into)
-                    });
+            async move { service.batch(batch).await }.instrument(span)
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.service.notification(n)
+        }
+    }
+}
+
+use spans::SpanMiddleware;
+
+mod capture {
+    use std::future::Future;
+    use std::sync::Arc;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Extensions, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::{ConnectionId, MethodResponse};
+
+    use crate::capture::{Capture, Direction};
+
+    /// RPC middleware capturing every request and notification from the
+    /// client and every response, with the connection id
+    ///
+    /// Runs outside the call guard so the capture shows denied and
+    /// rate-limited calls as the client saw them. The calls of a batch and its
+    /// responses are captured one by one.
+    #[derive(Clone)]
+    pub(super) struct CaptureMiddleware<S> {
+        pub(super) service: S,
+        pub(super) capture: Arc<Capture>,
+    }
+
+    fn connection(extensions: &Extensions) -> Option<String> {
+        extensions.get::<ConnectionId>().map(|id| id.0.to_string())
+    }
+
+    fn to_value(message: &impl serde::Serialize) -> serde_json::Value {
+        serde_json::to_value(message).unwrap_or_default()
+    }
+
+    impl<S> RpcServiceT for CaptureMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let capture = self.capture.clone();
+            let connection = connection(request.extensions());
+            capture.record("websocket", connection.clone(), Direction::Request, None, to_value(&request));
+            let service = self.service.clone();
+
+            async move {
+                let response = service.call(request).await;
+                capture.record_raw("websocket", connection, Direction::Response, None, response.as_json().get());
+                response
+            }
+        }
+
+        fn batch<'a>(&self, mut batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let capture = self.capture.clone();
+            let connection = connection(batch.extensions());
+            for entry in batch.iter() {
+                let message = match entry {
+                    Ok(BatchEntry::Call(req)) => to_value(req),
+                    Ok(BatchEntry::Notification(n)) => to_value(n),
+                    Err(_) => continue,
+                };
+                capture.record("websocket", connection.clone(), Direction::Request, None, message);
+            }
+            let service = self.service.clone();
+
+            async move {
+                let response = service.batch(batch).await;
+                let responses = serde_json::from_str::<Vec<serde_json::Value>>(response.as_json().get())
+                    .unwrap_or_default();
+                for message in responses {
+                    capture.record("websocket", connection.clone(), Direction::Response, None, message);
                 }
-                tracing::debug!("No cookie present, proceeding without auth");
+                response
             }
+        }
 
-            // No auth configured - pass through
-            let mut service = service;
-            Box::pin(async move { service.call(request).await.map_err(Into::into) })
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.capture.record(
+                "websocket",
+                connection(n.extensions()),
+                Direction::Request,
+                None,
+                to_value(&n),
+            );
+            self.service.notification(n)
         }
     }
 }
 
-use auth::CombinedAuthMiddleware;
+use capture::CaptureMiddleware;
+
+// ---------------------------------------------------------------------------
+// Slow-request middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod slow {
+    use std::future::Future;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::MethodResponse;
+
+    use crate::slow::SlowRequests;
+
+    /// RPC middleware warning about calls still running after the slow-request
+    /// threshold
+    ///
+    /// A batch is watched as a whole, as method `batch` with the combined
+    /// size of its calls' params.
+    #[derive(Clone)]
+    pub(super) struct SlowMiddleware<S> {
+        pub(super) service: S,
+        pub(super) slow: SlowRequests,
+    }
+
+    impl<S> RpcServiceT for SlowMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let slow = self.slow.clone();
+            let method = request.method_name().to_string();
+            let params_len = request.params.as_ref().map_or(0, |p| p.get().len());
+            let service = self.service.clone();
+
+            async move {
+                slow.watch("websocket", || (method, params_len), service.call(request))
+                    .await
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let slow = self.slow.clone();
+            let params_len = batch
+                .iter()
+                .filter_map(|entry| match entry {
+                    Ok(BatchEntry::Call(req)) => req.params.as_ref().map(|p| p.get().len()),
+                    _ => None,
+                })
+                .sum();
+            let service = self.service.clone();
+
+            async move {
+                slow.watch("websocket", || ("batch".to_string(), params_len), service.batch(batch))
+                    .await
+            }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.service.notification(n)
+        }
+    }
+}
+
+use slow::SlowMiddleware;
+
+// ---------------------------------------------------------------------------
+// Per-call guard middleware (jsonrpsee RPC layer): authorization + rate limiting
+// ---------------------------------------------------------------------------
+
+mod call_guard {
+    use std::future::Future;
+    use std::sync::Arc;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, RpcServiceT};
+    use jsonrpsee::types::{ErrorObjectOwned, Request};
+    use jsonrpsee::{ConnectionId, MethodResponse};
+
+    use crate::auth::policy::{forbidden_message, FORBIDDEN_ERROR_CODE};
+    use crate::auth::{AuthorizationPolicy, CallerIdentity};
+    use crate::rate_limit::{RateLimiter, RATE_LIMITED_ERROR_CODE};
+
+    /// RPC middleware that checks each call against the client's rate limit and
+    /// an [`AuthorizationPolicy`] before dispatching it
+    ///
+    /// The caller is resolved from the extensions the HTTP upgrade request was
+    /// given by the auth middleware. Passes everything through when neither a
+    /// policy nor a rate limiter is set.
+    #[derive(Clone)]
+    pub(super) struct CallGuardMiddleware<S> {
+        pub(super) service: S,
+        pub(super) policy: Option<Arc<dyn AuthorizationPolicy>>,
+        pub(super) rate_limiter: Option<Arc<RateLimiter>>,
+    }
+
+    impl<S> CallGuardMiddleware<S> {
+        fn check(&self, extensions: &http::Extensions, method: &str) -> Result<(), ErrorObjectOwned> {
+            if let Some(ref limiter) = self.rate_limiter {
+                // Each WebSocket connection is its own session
+                let session = extensions.get::<ConnectionId>().map(|id| id.0.to_string());
+                let key = limiter.key_for(extensions, session);
+                if !limiter.check(&key) {
+                    tracing::warn!("WebSocket rate limit exceeded for {}: {}", key, method);
+                    return Err(ErrorObjectOwned::owned(
+                        RATE_LIMITED_ERROR_CODE,
+                        "Rate limit exceeded",
+                        None::<()>,
+                    ));
+                }
+            }
+
+            if let Some(ref policy) = self.policy {
+                let identity = CallerIdentity::from_extensions(extensions);
+                if !policy.allow_method(&identity, method) {
+                    tracing::warn!("WebSocket call denied: {} (caller={:?})", method, identity.name());
+                    return Err(ErrorObjectOwned::owned(
+                        FORBIDDEN_ERROR_CODE,
+                        forbidden_message(method),
+                        None::<()>,
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl<S> RpcServiceT for CallGuardMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let checked = self.check(request.extensions(), request.method_name());
+            let service = self.service.clone();
+
+            async move {
+                match checked {
+                    Ok(()) => service.call(request).await,
+                    Err(err) => MethodResponse::error(request.id(), err),
+                }
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            // Rejected calls become per-entry errors; rejected notifications are dropped
+            let entries = batch
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    Ok(BatchEntry::Call(req)) => match self.check(req.extensions(), req.method_name()) {
+                        Ok(()) => Some(Ok(BatchEntry::Call(req))),
+                        Err(err) => Some(Err(BatchEntryErr::new(req.id(), err))),
+                    },
+                    Ok(BatchEntry::Notification(n)) => self
+                        .check(n.extensions(), n.method_name())
+                        .ok()
+                        .map(|()| Ok(BatchEntry::Notification(n))),
+                    other => Some(other),
+                })
+                .collect();
+            let service = self.service.clone();
+
+            async move { service.batch(Batch::from(entries)).await }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            let checked = self.check(n.extensions(), n.method_name());
+            let service = self.service.clone();
+
+            async move {
+                match checked {
+                    Ok(()) => service.notification(n).await,
+                    Err(_) => MethodResponse::notification(),
+                }
+            }
+        }
+    }
+}
+
+use call_guard::CallGuardMiddleware;
+
+// ---------------------------------------------------------------------------
+// Transport middleware hooks (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod hooks {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, BatchEntryErr, Extensions, Notification, RpcServiceT};
+    use jsonrpsee::types::{ErrorObjectOwned, Request};
+    use jsonrpsee::MethodResponse;
+    use serde_json::value::RawValue;
+
+    use crate::audit::AuditStatus;
+    use crate::auth::CallerIdentity;
+    use crate::middleware::{self, peer_of, CallContext, CallRejection, TransportMiddleware};
+
+    /// RPC middleware running the configured [`TransportMiddleware`] around
+    /// every call (including calls inside batches) and notification
+    ///
+    /// Runs inside the call guard, so only calls the authorization policy and
+    /// rate limit let through reach it. The method sees the params as the
+    /// middleware left them, and the extensions it added.
+    #[derive(Clone)]
+    pub(super) struct HooksMiddleware<S> {
+        pub(super) service: S,
+        pub(super) middleware: Arc<[Arc<dyn TransportMiddleware>]>,
+    }
+
+    /// Run the middleware on a call, applying the params and extensions it left
+    async fn admit(
+        middleware: &[Arc<dyn TransportMiddleware>],
+        method: &str,
+        params: &mut Option<Cow<'_, RawValue>>,
+        extensions: &mut Extensions,
+        started: Instant,
+    ) -> Result<CallContext, CallRejection> {
+        let mut call = CallContext::new(
+            "websocket",
+            method,
+            params.as_ref().and_then(|p| serde_json::from_str(p.get()).ok()),
+            CallerIdentity::from_extensions(extensions),
+            peer_of(extensions),
+        );
+        middleware::before_call(middleware, &mut call, started).await?;
+        *params = call
+            .params
+            .as_ref()
+            .and_then(|p| serde_json::value::to_raw_value(p).ok())
+            .map(Cow::Owned);
+        extensions.extend(call.extensions.clone());
+        Ok(call)
+    }
+
+    fn rejection_error(rejection: CallRejection) -> ErrorObjectOwned {
+        ErrorObjectOwned::owned(rejection.code, rejection.message, rejection.data)
+    }
+
+    impl<S> RpcServiceT for HooksMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, mut request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let middleware = self.middleware.clone();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let admitted = admit(
+                    &middleware,
+                    &request.method,
+                    &mut request.params,
+                    &mut request.extensions,
+                    started,
+                )
+                .await;
+                let call = match admitted {
+                    Ok(call) => call,
+                    Err(rejection) => return MethodResponse::error(request.id(), rejection_error(rejection)),
+                };
+                let response = service.call(request).await;
+                let status = response
+                    .as_error_code()
+                    .map_or(AuditStatus::Ok, AuditStatus::from_error_code);
+                middleware::after_call(&middleware, &call, status, started);
+                response
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let middleware = self.middleware.clone();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                // Rejected calls become per-entry errors; rejected notifications are dropped
+                let mut entries = Vec::with_capacity(batch.len());
+                let mut calls = Vec::new();
+                let mut notifications = Vec::new();
+                for entry in batch.into_iter() {
+                    match entry {
+                        Ok(BatchEntry::Call(mut req)) => {
+                            match admit(&middleware, &req.method, &mut req.params, &mut req.extensions, started).await {
+                                Ok(call) => {
+                                    // Keyed by the serialized id, to match the call with the batch response
+                                    calls.push((serde_json::to_string(&req.id).unwrap_or_default(), call));
+                                    entries.push(Ok(BatchEntry::Call(req)));
+                                }
+                                Err(rejection) => {
+                                    entries.push(Err(BatchEntryErr::new(req.id(), rejection_error(rejection))))
+                                }
+                            }
+                        }
+                        Ok(BatchEntry::Notification(mut n)) => {
+                            if let Ok(call) = admit(&middleware, &n.method, &mut n.params, &mut n.extensions, started).await {
+                                notifications.push(call);
+                                entries.push(Ok(BatchEntry::Notification(n)));
+                            }
+                        }
+                        other => entries.push(other),
+                    }
+                }
+
+                let response = service.batch(Batch::from(entries)).await;
+                if !calls.is_empty() {
+                    let statuses: HashMap<String, AuditStatus> =
+                        serde_json::from_str::<Vec<serde_json::Value>>(response.as_json().get())
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|r| (r["id"].to_string(), AuditStatus::from_response(r)))
+                            .collect();
+                    for (id, call) in calls {
+                        let status = statuses.get(&id).cloned().unwrap_or(AuditStatus::Ok);
+                        middleware::after_call(&middleware, &call, status, started);
+                    }
+                }
+                for call in notifications {
+                    middleware::after_call(&middleware, &call, AuditStatus::Ok, started);
+                }
+                response
+            }
+        }
+
+        fn notification<'a>(&self, mut n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            let middleware = self.middleware.clone();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let Ok(call) = admit(&middleware, &n.method, &mut n.params, &mut n.extensions, started).await else {
+                    return MethodResponse::notification();
+                };
+                let response = service.notification(n).await;
+                middleware::after_call(&middleware, &call, AuditStatus::Ok, started);
+                response
+            }
+        }
+    }
+}
+
+use hooks::HooksMiddleware;
+
+// ---------------------------------------------------------------------------
+// Request timeout middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod timeout {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Notification, RpcServiceT};
+    use jsonrpsee::core::server::BatchResponseBuilder;
+    use jsonrpsee::types::{ErrorObjectOwned, Id, Request};
+    use jsonrpsee::MethodResponse;
+
+    use crate::mcp::timeout::{ToolTimeouts, TIMEOUT_ERROR_CODE};
+
+    /// RPC middleware dropping calls that run longer than their method's limit
+    ///
+    /// Timed-out calls are answered with a [`TIMEOUT_ERROR_CODE`] error, and
+    /// timed-out notifications are dropped. A batch with limited calls is
+    /// dispatched one entry at a time, as jsonrpsee does, so each call gets
+    /// its own limit; other batches pass through whole.
+    #[derive(Clone)]
+    pub(super) struct TimeoutMiddleware<S> {
+        pub(super) service: S,
+        pub(super) timeouts: Arc<ToolTimeouts>,
+        /// The server's response size limit, applied to the batches answered here
+        pub(super) max_response_size: u32,
+    }
+
+    /// Error response for a call dropped after running for `timeout`
+    fn timeout_response(id: Id<'_>, method: &str, timeout: Duration) -> MethodResponse {
+        tracing::warn!("WebSocket call timed out after {:?}: {}", timeout, method);
+        MethodResponse::error(
+            id,
+            ErrorObjectOwned::owned(
+                TIMEOUT_ERROR_CODE,
+                format!("Request timed out after {}s: {}", timeout.as_secs_f64(), method),
+                Some(serde_json::json!({ "timeout_ms": timeout.as_millis() as u64 })),
+            ),
+        )
+    }
+
+    impl<S> RpcServiceT for TimeoutMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let timeout = self.timeouts.timeout_for(request.method_name());
+            let service = self.service.clone();
+
+            async move {
+                let Some(timeout) = timeout else {
+                    return service.call(request).await;
+                };
+                let id = request.id();
+                let method = request.method_name().to_string();
+                tokio::time::timeout(timeout, service.call(request))
+                    .await
+                    .unwrap_or_else(|_| timeout_response(id, &method, timeout))
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let limited = batch.iter().any(|entry| match entry {
+                Ok(BatchEntry::Call(req)) => self.timeouts.timeout_for(req.method_name()).is_some(),
+                _ => false,
+            });
+            let this = self.clone();
+
+            async move {
+                if !limited {
+                    return this.service.batch(batch).await;
+                }
+                // Answered like jsonrpsee's own batches, within the server's response size limit
+                let mut responses = BatchResponseBuilder::new_with_limit(this.max_response_size as usize);
+                let mut notified = false;
+                for entry in batch.into_iter() {
+                    let response = match entry {
+                        Ok(BatchEntry::Call(req)) => this.call(req).await,
+                        Ok(BatchEntry::Notification(n)) => {
+                            notified = true;
+                            this.notification(n).await;
+                            continue;
+                        }
+                        Err(err) => {
+                            let (err, id) = err.into_parts();
+                            MethodResponse::error(id, err)
+                        }
+                    };
+                    if let Err(too_large) = responses.append(response) {
+                        return too_large;
+                    }
+                }
+                if responses.is_empty() && notified {
+                    MethodResponse::notification()
+                } else {
+                    MethodResponse::from_batch(responses.finish())
+                }
+            }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            let timeout = self.timeouts.timeout_for(n.method_name());
+            let service = self.service.clone();
+
+            async move {
+                let Some(timeout) = timeout else {
+                    return service.notification(n).await;
+                };
+                let method = n.method_name().to_string();
+                tokio::time::timeout(timeout, service.notification(n))
+                    .await
+                    .unwrap_or_else(|_| {
+                        tracing::warn!("WebSocket notification timed out after {:?}: {}", timeout, method);
+                        MethodResponse::notification()
+                    })
+            }
+        }
+    }
+}
+
+use timeout::TimeoutMiddleware;
+
+// ---------------------------------------------------------------------------
+// Audit middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod audit {
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::MethodResponse;
+
+    use crate::audit::{AuditStatus, Auditor};
+    use crate::auth::CallerIdentity;
+
+    /// RPC middleware recording every call (including calls inside batches)
+    /// and its outcome in the audit log
+    ///
+    /// Runs outside the call guard so denied and rate-limited calls are
+    /// recorded too. Notifications carry no outcome and are not recorded.
+    #[derive(Clone)]
+    pub(super) struct AuditMiddleware<S> {
+        pub(super) service: S,
+        pub(super) auditor: Option<Arc<Auditor>>,
+    }
+
+    /// What is known about a call before it is dispatched
+    struct PendingCall {
+        identity: CallerIdentity,
+        method: String,
+        params: Option<String>,
+    }
+
+    impl PendingCall {
+        fn new(request: &Request<'_>) -> Self {
+            Self {
+                identity: CallerIdentity::from_extensions(request.extensions()),
+                method: request.method_name().to_string(),
+                params: request.params.as_ref().map(|p| p.get().to_string()),
+            }
+        }
+
+        fn record(self, auditor: &Auditor, status: AuditStatus, started: Instant) {
+            auditor.record(
+                "websocket",
+                &self.identity,
+                &self.method,
+                self.params.as_deref(),
+                status,
+                started.elapsed(),
+            );
+        }
+    }
+
+    impl<S> RpcServiceT for AuditMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let auditor = self.auditor.clone();
+            let pending = auditor.as_ref().map(|_| PendingCall::new(&request));
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.call(request).await;
+                if let (Some(auditor), Some(pending)) = (auditor, pending) {
+                    let status = response
+                        .as_error_code()
+                        .map_or(AuditStatus::Ok, AuditStatus::from_error_code);
+                    pending.record(&auditor, status, started);
+                }
+                response
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let auditor = self.auditor.clone();
+            // Calls keyed by their serialized id, to match them with the batch response
+            let pending: Vec<(String, PendingCall)> = match auditor {
+                Some(_) => batch
+                    .iter()
+                    .filter_map(|entry| match entry {
+                        Ok(BatchEntry::Call(req)) => {
+                            Some((serde_json::to_string(&req.id).ok()?, PendingCall::new(req)))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.batch(batch).await;
+                if let Some(auditor) = auditor {
+                    let statuses: HashMap<String, AuditStatus> =
+                        serde_json::from_str::<Vec<serde_json::Value>>(response.as_json().get())
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|r| (r["id"].to_string(), AuditStatus::from_response(r)))
+                            .collect();
+                    for (id, call) in pending {
+                        let status = statuses.get(&id).cloned().unwrap_or(AuditStatus::Ok);
+                        call.record(&auditor, status, started);
+                    }
+                }
+                response
+            }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.service.notification(n)
+        }
+    }
+}
+
+use audit::AuditMiddleware;
+
+// ---------------------------------------------------------------------------
+// Access log middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+mod access {
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Extensions, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::{ConnectionId, MethodResponse};
+
+    use crate::access_log::AccessLog;
+    use crate::audit::AuditStatus;
+    use crate::middleware::peer_of;
+
+    /// RPC middleware writing every call (including calls inside batches) to
+    /// the access log, with the peer address and connection id
+    ///
+    /// Runs outside the call guard so denied and rate-limited calls are
+    /// logged too.
+    #[derive(Clone)]
+    pub(super) struct AccessLogMiddleware<S> {
+        pub(super) service: S,
+        pub(super) log: Arc<AccessLog>,
+    }
+
+    /// Peer address and connection id of the connection a call arrived on
+    fn origin(extensions: &Extensions) -> (Option<String>, Option<String>) {
+        (
+            peer_of(extensions).map(|addr| addr.to_string()),
+            extensions.get::<ConnectionId>().map(|id| id.0.to_string()),
+        )
+    }
+
+    impl<S> RpcServiceT for AccessLogMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let log = self.log.clone();
+            let method = request.method_name().to_string();
+            let (client, session) = origin(request.extensions());
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.call(request).await;
+                let status = response
+                    .as_error_code()
+                    .map_or(AuditStatus::Ok, AuditStatus::from_error_code);
+                log.record("websocket", &method, status, started.elapsed(), client, session);
+                response
+            }
+        }
+
+        fn batch<'a>(&self, mut batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let log = self.log.clone();
+            let (client, session) = origin(batch.extensions());
+            // Methods keyed by their call's serialized id, to match them with the batch response
+            let methods: Vec<(String, String)> = batch
+                .iter()
+                .filter_map(|entry| match entry {
+                    Ok(BatchEntry::Call(req)) => {
+                        Some((serde_json::to_string(&req.id).ok()?, req.method_name().to_string()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.batch(batch).await;
+                let statuses: HashMap<String, AuditStatus> =
+                    serde_json::from_str::<Vec<serde_json::Value>>(response.as_json().get())
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|r| (r["id"].to_string(), AuditStatus::from_response(r)))
+                        .collect();
+                for (id, method) in methods {
+                    let status = statuses.get(&id).cloned().unwrap_or(AuditStatus::Ok);
+                    log.record(
+                        "websocket",
+                        &method,
+                        status,
+                        started.elapsed(),
+                        client.clone(),
+                        session.clone(),
+                    );
+                }
+                response
+            }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.service.notification(n)
+        }
+    }
+}
+
+use access::AccessLogMiddleware;
+
+// ---------------------------------------------------------------------------
+// Metrics middleware (jsonrpsee RPC layer)
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "metrics")]
+mod metrics {
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use jsonrpsee::core::middleware::{Batch, BatchEntry, Notification, RpcServiceT};
+    use jsonrpsee::types::Request;
+    use jsonrpsee::MethodResponse;
+
+    use crate::audit::AuditStatus;
+    use crate::metrics::TransportMetrics;
+
+    /// RPC middleware counting every call (including calls inside batches) and
+    /// its latency by method and outcome
+    ///
+    /// Runs outermost so denied and rate-limited calls are counted too.
+    #[derive(Clone)]
+    pub(super) struct MetricsMiddleware<S> {
+        pub(super) service: S,
+        pub(super) metrics: Arc<TransportMetrics>,
+    }
+
+    impl<S> RpcServiceT for MetricsMiddleware<S>
+    where
+        S: RpcServiceT<
+                MethodResponse = MethodResponse,
+                NotificationResponse = MethodResponse,
+                BatchResponse = MethodResponse,
+            > + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        type MethodResponse = S::MethodResponse;
+        type NotificationResponse = S::NotificationResponse;
+        type BatchResponse = S::BatchResponse;
+
+        fn call<'a>(&self, request: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+            let metrics = self.metrics.clone();
+            let method = request.method_name().to_string();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.call(request).await;
+                let status = response
+                    .as_error_code()
+                    .map_or(AuditStatus::Ok, AuditStatus::from_error_code);
+                metrics.record_call("websocket", &method, &status, started.elapsed());
+                response
+            }
+        }
+
+        fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+            let metrics = self.metrics.clone();
+            // Methods keyed by their call's serialized id, to match them with the batch response
+            let methods: Vec<(String, String)> = batch
+                .iter()
+                .filter_map(|entry| match entry {
+                    Ok(BatchEntry::Call(req)) => {
+                        Some((serde_json::to_string(&req.id).ok()?, req.method_name().to_string()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let service = self.service.clone();
+
+            async move {
+                let started = Instant::now();
+                let response = service.batch(batch).await;
+                let statuses: HashMap<String, AuditStatus> =
+                    serde_json::from_str::<Vec<serde_json::Value>>(response.as_json().get())
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|r| (r["id"].to_string(), AuditStatus::from_response(r)))
+                        .collect();
+                for (id, method) in methods {
+                    let status = statuses.get(&id).cloned().unwrap_or(AuditStatus::Ok);
+                    metrics.record_call("websocket", &method, &status, started.elapsed());
+                }
+                response
+            }
+        }
+
+        fn notification<'a>(&self, n: Notification<'a>) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+            self.service.notification(n)
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+use metrics::MetricsMiddleware;
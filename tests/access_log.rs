@@ -0,0 +1,100 @@
+//! Tests for the structured access log.
+//!
+//! Run with: cargo test --test access_log
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::StdioConfig;
+use plexus_transport::stdio::serve_io;
+use plexus_transport::{AccessLog, AccessRecord, AuditStatus, AuthorizationPolicy, CallerIdentity};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// A writer whose output the test can read back
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuf {
+    fn records(&self) -> Vec<AccessRecord> {
+        let text = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+}
+
+fn module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("echo", |params, _, _| params.one::<String>().unwrap_or_default())
+        .unwrap();
+    module.register_method("admin_reset", |_, _, _| "reset").unwrap();
+    module
+}
+
+#[tokio::test]
+async fn stdio_writes_a_line_per_call() {
+    let buf = SharedBuf::default();
+    let policy: Arc<dyn AuthorizationPolicy> =
+        Arc::new(|_: &CallerIdentity, _: &str, method: &str| !method.starts_with("admin"));
+    let config = StdioConfig::default()
+        .with_access_log(Arc::new(AccessLog::new(buf.clone())))
+        .with_authorization_policy(policy);
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module(), server_in, server_out, config));
+
+    let mut lines = BufReader::new(client_out).lines();
+    for request in [
+        r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":["a"]}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"admin_reset"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"missing"}"#,
+    ] {
+        client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+        lines.next_line().await.unwrap().expect("response line");
+    }
+    drop(client_in);
+    server.await.unwrap().unwrap();
+
+    let records = buf.records();
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|r| r.transport == "stdio" && r.client.is_none() && r.session_id.is_none()));
+    assert_eq!((records[0].method.as_str(), &records[0].status), ("echo", &AuditStatus::Ok));
+    assert_eq!(records[1].status, AuditStatus::Denied);
+    assert_eq!(records[2].status, AuditStatus::Error { code: -32601 });
+}
+
+#[test]
+fn lines_are_flat_json_objects() {
+    let record = AccessRecord {
+        timestamp_ms: 1,
+        transport: "mcp".into(),
+        method: "echo.echo".into(),
+        status: AuditStatus::Error { code: -32602 },
+        duration_us: 812,
+        client: Some("10.0.0.7:51234".into()),
+        session_id: Some("abc".into()),
+    };
+    let buf = SharedBuf::default();
+    AccessLog::new(buf.clone()).write(&record);
+
+    let text = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    assert!(text.ends_with('\n'));
+    assert_eq!(text.lines().count(), 1);
+    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(value["status"], "error");
+    assert_eq!(value["code"], -32602);
+    assert_eq!(value["session_id"], "abc");
+    assert_eq!(buf.records(), vec![record]);
+}
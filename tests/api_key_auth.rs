@@ -0,0 +1,82 @@
+//! Tests for `ApiKeyAuth` key extraction and validation.
+//!
+//! Run with: cargo test --test api_key_auth
+
+use plexus_transport::auth::{ApiKeyAuth, ApiKeyIdentity};
+
+fn headers(pairs: &[(&str, &str)]) -> http::HeaderMap {
+    let mut h = http::HeaderMap::new();
+    for &(k, v) in pairs {
+        h.insert(
+            http::header::HeaderName::from_bytes(k.as_bytes()).unwrap(),
+            v.parse().unwrap(),
+        );
+    }
+    h
+}
+
+#[test]
+fn extracts_bearer_token() {
+    let h = headers(&[("authorization", "Bearer secret")]);
+    assert_eq!(ApiKeyAuth::extract_key(&h), Some("secret"));
+}
+
+#[test]
+fn extracts_x_api_key_header() {
+    let h = headers(&[("x-api-key", "secret")]);
+    assert_eq!(ApiKeyAuth::extract_key(&h), Some("secret"));
+}
+
+#[test]
+fn non_bearer_authorization_is_ignored() {
+    let h = headers(&[("authorization", "Basic dXNlcjpwYXNz")]);
+    assert_eq!(ApiKeyAuth::extract_key(&h), None);
+}
+
+#[tokio::test]
+async fn key_set_accepts_known_keys_only() {
+    let auth = ApiKeyAuth::named_keys([("reader", "k1"), ("admin", "k2")]);
+
+    let ok = auth.authenticate(&headers(&[("x-api-key", "k2")])).await;
+    assert_eq!(ok, Some(ApiKeyIdentity("admin".into())));
+
+    let bad = auth.authenticate(&headers(&[("x-api-key", "nope")])).await;
+    assert_eq!(bad, None);
+
+    let missing = auth.authenticate(&headers(&[])).await;
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn validator_callback_decides() {
+    let auth = ApiKeyAuth::validator(|key: String| async move {
+        key.starts_with("ok-").then(|| ApiKeyIdentity(key))
+    });
+
+    let ok = auth.authenticate(&headers(&[("authorization", "Bearer ok-1")])).await;
+    assert_eq!(ok, Some(ApiKeyIdentity("ok-1".into())));
+
+    let bad = auth.authenticate(&headers(&[("authorization", "Bearer no")])).await;
+    assert_eq!(bad, None);
+}
+
+#[tokio::test]
+async fn key_sets_store_digests_only() {
+    let auth = ApiKeyAuth::named_keys([("admin", "super-secret")]);
+    let ApiKeyAuth::Keys(ref keys) = auth else {
+        panic!("expected a key set");
+    };
+    assert_eq!(keys.get(&ApiKeyAuth::digest("super-secret")), Some(&ApiKeyIdentity("admin".into())));
+    assert_eq!(keys.len(), 1);
+
+    // Prefixes and extensions of a key are different keys
+    for key in ["super-secre", "super-secret!", "SUPER-SECRET"] {
+        assert_eq!(auth.authenticate(&headers(&[("x-api-key", key)])).await, None);
+    }
+}
+
+#[test]
+fn debug_does_not_leak_keys() {
+    let auth = ApiKeyAuth::keys(["super-secret"]);
+    assert!(!format!("{:?}", auth).contains("super-secret"));
+}
@@ -0,0 +1,206 @@
+//! Tests for the audit log.
+//!
+//! Run with: cargo test --test audit
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::audit::{verify_chain, verify_chain_from};
+use plexus_transport::config::StdioConfig;
+use plexus_transport::stdio::serve_io;
+use plexus_transport::{
+    AuditRecord, AuditStatus, Auditor, AuthorizationPolicy, CallerIdentity, FileAuditSink,
+};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+const KEY: &[u8] = b"audit-test-key";
+
+/// An auditor collecting its records in memory
+fn memory_auditor() -> (Arc<Auditor>, Arc<Mutex<Vec<AuditRecord>>>) {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = records.clone();
+    let auditor = Auditor::new(move |record: &AuditRecord| sink.lock().unwrap().push(record.clone()), KEY);
+    (Arc::new(auditor), records)
+}
+
+#[test]
+fn records_form_a_verifiable_chain() {
+    let (auditor, records) = memory_auditor();
+    for method in ["query.list", "query.get", "admin.reset"] {
+        auditor.record(
+            "websocket",
+            &CallerIdentity::Local,
+            method,
+            Some(r#"{"id":1}"#),
+            AuditStatus::Ok,
+            Duration::from_millis(3),
+        );
+    }
+    auditor.flush();
+
+    let mut records = records.lock().unwrap().clone();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].identity, "local");
+    assert_eq!(records[1].prev_hash, records[0].hash);
+    assert!(verify_chain(&records, KEY));
+
+    // Editing a record breaks its hash
+    records[1].method = "query.other".into();
+    assert!(!verify_chain(&records, KEY));
+
+    // Dropping a record breaks the link
+    let (auditor, fresh) = memory_auditor();
+    for _ in 0..3 {
+        auditor.record("stdio", &CallerIdentity::Anonymous, "a.b", None, AuditStatus::Ok, Duration::ZERO);
+    }
+    auditor.flush();
+    let mut fresh = fresh.lock().unwrap().clone();
+    let head = fresh.remove(0);
+    assert!(!verify_chain(&fresh, KEY), "a log cut at its head no longer starts the chain");
+    assert!(verify_chain_from(&fresh, KEY, &head.hash));
+    fresh.remove(0);
+    assert!(!verify_chain_from(&fresh, KEY, &head.hash));
+}
+
+#[test]
+fn rewritten_chains_fail_without_the_key() {
+    let (auditor, records) = memory_auditor();
+    for method in ["query.list", "admin.reset"] {
+        auditor.record("mcp", &CallerIdentity::Anonymous, method, None, AuditStatus::Ok, Duration::ZERO);
+    }
+    auditor.flush();
+    let mut records = records.lock().unwrap().clone();
+    assert_eq!(auditor.last_hash(), records[1].hash);
+
+    // Whoever rewrites the log cannot recompute the hashes without the key
+    records[1].status = AuditStatus::Denied;
+    records[1].hash = records[1].compute_hash(b"guessed-key");
+    assert!(!verify_chain(&records, KEY));
+}
+
+#[test]
+fn params_are_stored_as_digests() {
+    let (auditor, records) = memory_auditor();
+    auditor.record(
+        "mcp",
+        &CallerIdentity::Anonymous,
+        "secrets.put",
+        Some(r#"{"password":"hunter2"}"#),
+        AuditStatus::Denied,
+        Duration::ZERO,
+    );
+    auditor.flush();
+
+    let record = records.lock().unwrap()[0].clone();
+    assert_eq!(record.params_digest.len(), 64);
+    assert!(!serde_json::to_string(&record).unwrap().contains("hunter2"));
+    assert_eq!(record.status, AuditStatus::Denied);
+}
+
+#[test]
+fn file_sink_continues_the_chain_after_reopening() {
+    let path = std::env::temp_dir().join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+
+    let auditor = Auditor::new(FileAuditSink::open(&path).unwrap(), KEY);
+    auditor.record("stdio", &CallerIdentity::Local, "a.one", None, AuditStatus::Ok, Duration::ZERO);
+    drop(auditor);
+
+    let auditor = Auditor::new(FileAuditSink::open(&path).unwrap(), KEY);
+    auditor.record(
+        "stdio",
+        &CallerIdentity::Local,
+        "a.two",
+        None,
+        AuditStatus::Error { code: -32601 },
+        Duration::ZERO,
+    );
+    // Dropping the auditor writes out its queue
+    drop(auditor);
+
+    let records = FileAuditSink::open(&path).unwrap().records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].prev_hash, records[0].hash);
+    assert!(verify_chain(&records, KEY));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn stdio_calls_are_audited() {
+    let mut module = RpcModule::new(());
+    module.register_method("query.ping", |_, _, _| "pong").unwrap();
+    module.register_method("admin.reset", |_, _, _| "done").unwrap();
+
+    let (auditor, records) = memory_auditor();
+    let policy: Arc<dyn AuthorizationPolicy> =
+        Arc::new(|_: &CallerIdentity, namespace: &str, _: &str| namespace == "query");
+    let config = StdioConfig::default()
+        .with_authorization_policy(policy)
+        .with_audit(auditor.clone());
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+
+    for request in [
+        r#"{"jsonrpc":"2.0","id":1,"method":"query.ping"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"admin.reset"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"query.missing"}"#,
+    ] {
+        client_in.write_all(request.as_bytes()).await.unwrap();
+        client_in.write_all(b"\n").await.unwrap();
+    }
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    for _ in 0..3 {
+        lines.next_line().await.unwrap().expect("response line");
+    }
+    server.await.unwrap().unwrap();
+    auditor.flush();
+
+    let records = records.lock().unwrap();
+    let summary: Vec<_> = records
+        .iter()
+        .map(|r| (r.transport.as_str(), r.method.as_str(), r.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("stdio", "query.ping", AuditStatus::Ok),
+            ("stdio", "admin.reset", AuditStatus::Denied),
+            ("stdio", "query.missing", AuditStatus::Error { code: -32601 }),
+        ]
+    );
+    assert!(verify_chain(records.iter(), KEY));
+}
+
+#[test]
+fn slow_sinks_do_not_hold_up_callers() {
+    let (release, released) = std::sync::mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    let written = Arc::new(Mutex::new(Vec::new()));
+    let sink = written.clone();
+    let auditor = Auditor::new(
+        move |record: &AuditRecord| {
+            // The first write blocks until the test lets it through
+            if sink.lock().unwrap().is_empty() {
+                released.lock().unwrap().recv().unwrap();
+            }
+            sink.lock().unwrap().push(record.method.clone());
+        },
+        KEY,
+    );
+
+    let started = std::time::Instant::now();
+    for method in ["a.one", "a.two", "a.three"] {
+        auditor.record("stdio", &CallerIdentity::Local, method, None, AuditStatus::Ok, Duration::ZERO);
+    }
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(written.lock().unwrap().is_empty());
+
+    release.send(()).unwrap();
+    auditor.flush();
+    assert_eq!(*written.lock().unwrap(), ["a.one", "a.two", "a.three"]);
+}
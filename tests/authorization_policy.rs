@@ -0,0 +1,131 @@
+//! Tests for per-method authorization policies.
+//!
+//! Run with: cargo test --test authorization_policy
+
+use std::sync::Arc;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::auth::policy::FORBIDDEN_ERROR_CODE;
+use plexus_transport::config::StdioConfig;
+use plexus_transport::stdio::serve_io;
+use plexus_transport::{ApiKeyIdentity, AuthorizationPolicy, CallerIdentity, MethodAllowList};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+fn api_key(name: &str) -> CallerIdentity {
+    CallerIdentity::ApiKey(ApiKeyIdentity(name.into()))
+}
+
+#[test]
+fn allow_list_limits_read_only_keys() {
+    let policy = MethodAllowList::new()
+        .grant("reader", ["query.*"])
+        .grant("admin", ["*"]);
+
+    assert!(policy.allow(&api_key("reader"), "query", "list"));
+    assert!(!policy.allow(&api_key("reader"), "admin", "reset"));
+    assert!(policy.allow(&api_key("admin"), "admin", "reset"));
+}
+
+#[test]
+fn allow_list_exact_patterns_and_fallback() {
+    let policy = MethodAllowList::new()
+        .grant("ops", ["admin.status"])
+        .with_fallback(["health.*"]);
+
+    assert!(policy.allow_method(&api_key("ops"), "admin.status"));
+    assert!(!policy.allow_method(&api_key("ops"), "admin.reset"));
+    // Unknown identities and anonymous callers use the fallback
+    assert!(policy.allow_method(&api_key("someone"), "health.ping"));
+    assert!(policy.allow_method(&CallerIdentity::Anonymous, "health.ping"));
+    assert!(!policy.allow_method(&CallerIdentity::Anonymous, "query.list"));
+}
+
+#[test]
+fn grants_are_keyed_by_identity_kind() {
+    let policy = MethodAllowList::new()
+        .grant("api_key:admin", ["*"])
+        .grant("ops", ["admin.status"])
+        .with_fallback(["health.*"]);
+
+    assert!(policy.allow_method(&api_key("admin"), "admin.reset"));
+    // Bare names are API key identities
+    assert!(policy.allow_method(&api_key("ops"), "admin.status"));
+    assert_eq!(api_key("admin").label(), "api_key:admin");
+    assert_eq!(CallerIdentity::Local.label(), "local");
+}
+
+#[cfg(feature = "jwt-auth")]
+#[test]
+fn jwt_subjects_do_not_match_api_key_grants() {
+    use plexus_transport::auth::jwt::JwtClaims;
+
+    let policy = MethodAllowList::new()
+        .grant("admin", ["*"])
+        .grant("jwt:alice", ["query.*"])
+        .with_fallback(["health.*"]);
+    let jwt = |sub: &str| CallerIdentity::Jwt(JwtClaims(serde_json::json!({ "sub": sub })));
+
+    // A token for `admin` is not the API key named `admin`
+    assert_eq!(jwt("admin").label(), "jwt:admin");
+    assert!(!policy.allow_method(&jwt("admin"), "admin.reset"));
+    assert!(policy.allow_method(&jwt("admin"), "health.ping"));
+    assert!(policy.allow_method(&jwt("alice"), "query.list"));
+    assert!(!policy.allow_method(&api_key("alice"), "query.list"));
+}
+
+#[test]
+fn identity_resolved_from_extensions() {
+    let mut ext = http::Extensions::new();
+    assert!(matches!(
+        CallerIdentity::from_extensions(&ext),
+        CallerIdentity::Anonymous
+    ));
+
+    ext.insert(ApiKeyIdentity("reader".into()));
+    assert_eq!(CallerIdentity::from_extensions(&ext).name(), Some("reader"));
+}
+
+#[test]
+fn closures_are_policies() {
+    let policy = |_: &CallerIdentity, namespace: &str, _: &str| namespace != "admin";
+    assert!(policy.allow_method(&CallerIdentity::Local, "query.list"));
+    assert!(!policy.allow_method(&CallerIdentity::Local, "admin.reset"));
+}
+
+#[tokio::test]
+async fn stdio_rejects_denied_calls() {
+    let mut module = RpcModule::new(());
+    module.register_method("query.ping", |_, _, _| "pong").unwrap();
+    module.register_method("admin.reset", |_, _, _| "done").unwrap();
+
+    let policy: Arc<dyn AuthorizationPolicy> =
+        Arc::new(|_: &CallerIdentity, namespace: &str, _: &str| namespace == "query");
+    let config = StdioConfig::default().with_authorization_policy(policy);
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"admin.reset\"}\n")
+        .await
+        .unwrap();
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"query.ping\"}\n")
+        .await
+        .unwrap();
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let denied: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(denied["id"], 1);
+    assert_eq!(denied["error"]["code"], FORBIDDEN_ERROR_CODE);
+
+    let allowed: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(allowed["id"], 2);
+    assert_eq!(allowed["result"], "pong");
+
+    server.await.unwrap().unwrap();
+}
@@ -0,0 +1,43 @@
+//! Tests for configuring the listen address of network transports.
+//!
+//! Run with: cargo test --test bind_address
+
+use std::net::SocketAddr;
+
+use plexus_transport::config::{McpHttpConfig, WebSocketConfig};
+
+#[test]
+fn port_constructors_default_to_loopback() {
+    assert_eq!(WebSocketConfig::new(4444).addr, "127.0.0.1:4444".parse::<SocketAddr>().unwrap());
+    assert_eq!(McpHttpConfig::new(4445).addr, "127.0.0.1:4445".parse::<SocketAddr>().unwrap());
+}
+
+#[test]
+fn full_socket_addresses_are_accepted() {
+    let addr: SocketAddr = "[::]:4445".parse().unwrap();
+    assert_eq!(McpHttpConfig::from_addr(addr).addr, addr);
+
+    let addr: SocketAddr = "0.0.0.0:4444".parse().unwrap();
+    assert_eq!(WebSocketConfig::new(1).with_addr(addr).addr, addr);
+}
+
+#[test]
+fn host_strings_keep_the_port() {
+    let config = WebSocketConfig::new(4444).with_host("0.0.0.0").unwrap();
+    assert_eq!(config.addr, "0.0.0.0:4444".parse::<SocketAddr>().unwrap());
+
+    let config = McpHttpConfig::new(4445).with_host("[::]").unwrap();
+    assert_eq!(config.addr, "[::]:4445".parse::<SocketAddr>().unwrap());
+
+    let config = McpHttpConfig::new(4445).with_host("::1").unwrap();
+    assert_eq!(config.addr, "[::1]:4445".parse::<SocketAddr>().unwrap());
+
+    let config = WebSocketConfig::new(4444).with_host("localhost").unwrap();
+    assert!(config.addr.ip().is_loopback());
+    assert_eq!(config.addr.port(), 4444);
+}
+
+#[test]
+fn unresolvable_hosts_are_rejected() {
+    assert!(WebSocketConfig::new(4444).with_host("no such host").is_err());
+}
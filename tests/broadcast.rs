@@ -0,0 +1,169 @@
+//! Tests for server-wide broadcasts.
+//!
+//! Run with: cargo test --test broadcast
+
+use std::time::Duration;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::Broadcaster;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+mod common;
+use common::free_port;
+
+const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+/// Open a WebSocket connection, returning once the upgrade is accepted
+async fn connect(port: u16) -> TcpStream {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(UPGRADE.as_bytes()).await.unwrap();
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    assert!(head.starts_with(b"HTTP/1.1 101"));
+    stream
+}
+
+/// Send `message` as a client text frame (masked with a zero key)
+async fn send_text(stream: &mut TcpStream, message: &Value) {
+    let payload = message.to_string();
+    assert!(payload.len() < 126);
+    let mut frame = vec![0x81, 0x80 | payload.len() as u8, 0, 0, 0, 0];
+    frame.extend_from_slice(payload.as_bytes());
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Read the next server text frame as JSON
+async fn read_text(stream: &mut TcpStream) -> Value {
+    let opcode = stream.read_u8().await.unwrap();
+    assert_eq!(opcode, 0x81);
+    let len = match stream.read_u8().await.unwrap() {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    serde_json::from_slice(&payload).unwrap()
+}
+
+#[tokio::test]
+async fn broadcasts_reach_every_websocket_connection() {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    let broadcaster = Broadcaster::new();
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_broadcaster(broadcaster.clone());
+    let _server = serve_websocket(module, config, None).await.unwrap();
+
+    let mut first = connect(port).await;
+    let mut second = connect(port).await;
+    assert_eq!(broadcaster.send("hub.reloading", json!({ "plugins": ["search"] })), 2);
+
+    for stream in [&mut first, &mut second] {
+        let notification = read_text(stream).await;
+        assert_eq!(notification["method"], "hub.reloading");
+        assert_eq!(notification["params"]["plugins"][0], "search");
+        assert!(notification.get("id").is_none());
+    }
+
+    // Calls are still answered around broadcasts
+    send_text(&mut first, &json!({ "jsonrpc": "2.0", "id": 1, "method": "test.ping" })).await;
+    assert_eq!(read_text(&mut first).await["result"], "pong");
+    broadcaster.send("hub.shutdown", json!(null));
+    assert_eq!(read_text(&mut first).await["method"], "hub.shutdown");
+}
+
+/// A module whose `test.blob` answers a string of the given length
+fn blobs() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("test.blob", |params, _, _| "x".repeat(params.one::<usize>().unwrap()))
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn broadcasts_never_split_responses() {
+    let broadcaster = Broadcaster::new();
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_broadcaster(broadcaster.clone());
+    let _server = serve_websocket(blobs(), config, None).await.unwrap();
+    let mut stream = connect(port).await;
+
+    // Broadcast continuously while responses with 7-, 16- and 64-bit payload
+    // lengths are written, many of them spanning several relay reads
+    let flood = tokio::spawn({
+        let broadcaster = broadcaster.clone();
+        async move {
+            loop {
+                broadcaster.send("hub.tick", json!({ "padding": "y".repeat(300) }));
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+    });
+
+    let mut broadcasts = 0;
+    let mut id = 0;
+    for _ in 0..5 {
+        for len in [10, 1_000, 70_000, 300_000] {
+            id += 1;
+            send_text(&mut stream, &json!({ "jsonrpc": "2.0", "id": id, "method": "test.blob", "params": [len] })).await;
+            // Every frame must parse as a whole message
+            let response = loop {
+                let message = read_text(&mut stream).await;
+                if message["method"] == "hub.tick" {
+                    broadcasts += 1;
+                    continue;
+                }
+                break message;
+            };
+            assert_eq!(response["id"], id);
+            assert_eq!(response["result"].as_str().unwrap().len(), len);
+        }
+    }
+    flood.abort();
+    assert!(broadcasts > 0);
+}
+
+#[tokio::test]
+async fn broadcasts_skip_connections_whose_upgrade_failed() {
+    let broadcaster = Broadcaster::new();
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_broadcaster(broadcaster.clone());
+    let _server = serve_websocket(blobs(), config, None).await.unwrap();
+
+    // An unsupported WebSocket version is answered without switching protocols
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let upgrade = UPGRADE.replace("Sec-WebSocket-Version: 13", "Sec-WebSocket-Version: 8");
+    stream.write_all(upgrade.as_bytes()).await.unwrap();
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    assert!(!head.starts_with(b"HTTP/1.1 101"));
+    let head = String::from_utf8(head).unwrap().to_ascii_lowercase();
+    let length: usize = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .map(|len| len.trim().parse().unwrap())
+        .unwrap_or(0);
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).await.unwrap();
+
+    // The connection still carries HTTP, where a frame would corrupt the next response
+    broadcaster.send("hub.reloading", json!({}));
+    let mut byte = [0u8; 1];
+    let written = tokio::time::timeout(Duration::from_millis(300), stream.read(&mut byte)).await;
+    assert!(written.is_err(), "nothing may be written to an HTTP connection unasked");
+}
+
+#[test]
+fn broadcasts_without_clients_reach_no_one() {
+    assert_eq!(Broadcaster::new().send("hub.reloading", json!({})), 0);
+}
@@ -0,0 +1,154 @@
+//! Tests for traffic capture and replay.
+//!
+//! Run with: cargo test --test capture
+
+use std::sync::{Arc, Mutex};
+
+use jsonrpsee::RpcModule;
+use plexus_transport::capture::Direction;
+use plexus_transport::config::StdioConfig;
+use plexus_transport::stdio::serve_io;
+use plexus_transport::{Capture, CaptureRecord, CaptureSink, FileCaptureSink, Replayer};
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+fn module(greeting: &'static str) -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("greet", move |params, _, _| {
+            format!("{}, {}", greeting, params.one::<String>().unwrap_or_default())
+        })
+        .unwrap();
+    module
+}
+
+/// Capture a stdio session sending `requests` to `module`
+async fn capture_stdio(module: RpcModule<()>, requests: &[&str]) -> Vec<CaptureRecord> {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let records = records.clone();
+        move |record: &CaptureRecord| records.lock().unwrap().push(record.clone())
+    };
+    let capture = Arc::new(Capture::new(sink));
+    let config = StdioConfig::default().with_capture(capture.clone());
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+
+    let mut lines = BufReader::new(client_out).lines();
+    for request in requests {
+        client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+        lines.next_line().await.unwrap().expect("response line");
+    }
+    drop(client_in);
+    server.await.unwrap().unwrap();
+    capture.flush();
+
+    let records = records.lock().unwrap().clone();
+    records
+}
+
+#[tokio::test]
+async fn stdio_captures_requests_and_responses() {
+    let records = capture_stdio(
+        module("hello"),
+        &[r#"{"jsonrpc":"2.0","id":1,"method":"greet","params":["ann"]}"#],
+    )
+    .await;
+
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|r| r.transport == "stdio" && r.connection.is_none()));
+    assert_eq!(records[0].direction, Direction::Request);
+    assert_eq!(records[0].request_id, Some(json!(1)));
+    assert_eq!(records[0].message["params"], json!(["ann"]));
+    assert_eq!(records[1].direction, Direction::Response);
+    assert_eq!(records[1].request_id, Some(json!(1)));
+    assert_eq!(records[1].message["result"], "hello, ann");
+}
+
+#[tokio::test]
+async fn captures_mask_credentials() {
+    let records = capture_stdio(
+        module("hello"),
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"greet","params":{"name":"ann","token":"s3cret"}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"greet","params":{"password":"#,
+        ],
+    )
+    .await;
+
+    assert_eq!(records[0].message["params"], json!({ "name": "ann", "token": "[REDACTED]" }));
+    let unparsed = records.iter().find(|r| r.direction == Direction::Request && r.request_id.is_none());
+    assert_eq!(unparsed.expect("malformed request captured").message, json!("<62 bytes>"));
+    assert!(records.iter().all(|r| !r.message.to_string().contains("s3cret")));
+}
+
+#[tokio::test]
+async fn replay_compares_with_the_recording() {
+    let records = capture_stdio(
+        module("hello"),
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"greet","params":["ann"]}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"missing"}"#,
+        ],
+    )
+    .await;
+
+    let calls = Replayer::new(module("hello")).replay(&records).await;
+    assert_eq!(calls.len(), 2);
+    assert!(calls.iter().all(|call| call.matches() == Some(true)));
+    assert_eq!(calls[1].response["error"]["code"], -32601);
+
+    // A changed module answers differently
+    let calls = Replayer::new(module("goodbye")).replay(&records).await;
+    assert_eq!(calls[0].matches(), Some(false));
+    assert_eq!(calls[0].recorded_response.as_ref().unwrap()["result"], "hello, ann");
+    assert_eq!(calls[0].response["result"], "goodbye, ann");
+}
+
+#[tokio::test]
+async fn mcp_tool_calls_replay_as_method_calls() {
+    let record = |direction, message: serde_json::Value| CaptureRecord {
+        timestamp_ms: 1,
+        transport: "mcp".into(),
+        connection: Some("session-1".into()),
+        direction,
+        request_id: Some(json!(7)),
+        message,
+    };
+    let records = vec![
+        record(
+            Direction::Request,
+            json!({"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"greet","arguments":["bob"]}}),
+        ),
+        record(Direction::Response, json!({"jsonrpc":"2.0","id":7,"result":{"content":[]}})),
+    ];
+
+    let calls = Replayer::new(module("hi")).replay(&records).await;
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].request["method"], "greet");
+    assert_eq!(calls[0].response["result"], "hi, bob");
+    assert_eq!(calls[0].connection.as_deref(), Some("session-1"));
+    assert_eq!(calls[0].matches(), None);
+}
+
+#[test]
+fn file_sink_round_trips_records() {
+    let path = std::env::temp_dir().join(format!("capture-{}.jsonl", uuid::Uuid::new_v4()));
+    let sink = FileCaptureSink::open(&path).unwrap();
+    let record = CaptureRecord {
+        timestamp_ms: 1,
+        transport: "websocket".into(),
+        connection: Some("3".into()),
+        direction: Direction::Notification,
+        request_id: Some(json!("a")),
+        message: json!({"jsonrpc":"2.0","method":"tick","params":{"subscription":1,"result":2}}),
+    };
+    sink.write(&record);
+    sink.write(&record);
+
+    assert_eq!(sink.records().unwrap(), vec![record.clone(), record]);
+    assert_eq!(FileCaptureSink::read(&path).unwrap().len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,118 @@
+//! Tests for the clap transport flags.
+//!
+//! Run with: cargo test --features cli --test cli
+
+#[cfg(feature = "cli")]
+mod cli_tests {
+    use std::net::SocketAddr;
+
+    use clap::Parser;
+    use plexus_transport::{SessionStorage, TransportArgs, TransportConfig, WebSocketConfig};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        transport: TransportArgs,
+    }
+
+    fn parse(args: &[&str]) -> Result<TransportArgs, clap::Error> {
+        Cli::try_parse_from(std::iter::once("server").chain(args.iter().copied())).map(|cli| cli.transport)
+    }
+
+    #[test]
+    fn flags_enable_transports() {
+        let config: TransportConfig = parse(&["--ws-port", "4444", "--mcp-port", "4445", "--stdio"])
+            .unwrap()
+            .into();
+
+        assert_eq!(config.websocket.unwrap().addr, "127.0.0.1:4444".parse::<SocketAddr>().unwrap());
+        let mcp = config.mcp_http.unwrap();
+        assert_eq!(mcp.addr, "127.0.0.1:4445".parse::<SocketAddr>().unwrap());
+        assert!(matches!(mcp.session_storage, SessionStorage::InMemory));
+        assert!(config.stdio.is_some());
+    }
+
+    #[test]
+    fn no_flags_enable_nothing() {
+        let config: TransportConfig = parse(&[]).unwrap().into();
+        assert!(config.websocket.is_none());
+        assert!(config.mcp_http.is_none());
+        assert!(config.stdio.is_none());
+    }
+
+    #[test]
+    fn host_and_api_key_apply_to_every_network_transport() {
+        let config: TransportConfig = parse(&["--ws-port", "4444", "--mcp-port", "4445", "--host", "0.0.0.0", "--api-key", "k"])
+            .unwrap()
+            .into();
+
+        assert_eq!(config.websocket.unwrap().addr, "0.0.0.0:4444".parse::<SocketAddr>().unwrap());
+        assert_eq!(config.mcp_http.unwrap().addr, "0.0.0.0:4445".parse::<SocketAddr>().unwrap());
+        assert_eq!(config.api_key.as_deref(), Some("k"));
+    }
+
+    #[test]
+    fn apply_keeps_existing_settings() {
+        let mut base = TransportConfig::default();
+        base.websocket = Some(WebSocketConfig::new(4444).with_max_connections(3));
+
+        let config = parse(&["--host", "::1"]).unwrap().apply(base);
+        let ws = config.websocket.unwrap();
+        assert_eq!(ws.addr, "[::1]:4444".parse::<SocketAddr>().unwrap());
+        assert_eq!(ws.max_connections, 3);
+    }
+
+    #[test]
+    fn sessions_require_the_mcp_transport() {
+        assert!(parse(&["--sessions", "memory"]).is_err());
+        assert!(parse(&["--mcp-port", "4445", "--sessions", "memory"]).is_ok());
+    }
+
+    #[test]
+    fn invalid_session_storage_is_rejected() {
+        let err = parse(&["--mcp-port", "4445", "--sessions", "postgres://localhost"]).unwrap_err();
+        assert!(err.to_string().contains("sqlite:<path>"));
+        assert!(parse(&["--mcp-port", "4445", "--sessions", "sqlite:"]).is_err());
+    }
+
+    #[cfg(feature = "sqlite-sessions")]
+    #[test]
+    fn sqlite_sessions_take_a_path() {
+        let config: TransportConfig = parse(&["--mcp-port", "4445", "--sessions", "sqlite:sessions.db"])
+            .unwrap()
+            .into();
+        match config.mcp_http.unwrap().session_storage {
+            SessionStorage::Sqlite { path } => assert_eq!(path, std::path::Path::new("sessions.db")),
+            other => panic!("expected sqlite storage, got {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "sqlite-sessions"))]
+    #[test]
+    fn sqlite_sessions_need_the_feature() {
+        let err = parse(&["--mcp-port", "4445", "--sessions", "sqlite:sessions.db"]).unwrap_err();
+        assert!(err.to_string().contains("sqlite-sessions"));
+    }
+
+    #[cfg(feature = "redis-sessions")]
+    #[test]
+    fn redis_sessions_take_a_url() {
+        let config: TransportConfig = parse(&["--mcp-port", "4445", "--sessions", "redis://cache:6379/2"])
+            .unwrap()
+            .into();
+        match config.mcp_http.unwrap().session_storage {
+            SessionStorage::Redis { url, key_prefix, .. } => {
+                assert_eq!(url, "redis://cache:6379/2");
+                assert_eq!(key_prefix, "plexus:mcp:session:");
+            }
+            other => panic!("expected redis storage, got {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "redis-sessions"))]
+    #[test]
+    fn redis_sessions_need_the_feature() {
+        let err = parse(&["--mcp-port", "4445", "--sessions", "redis://localhost"]).unwrap_err();
+        assert!(err.to_string().contains("redis-sessions"));
+    }
+}
@@ -0,0 +1,196 @@
+//! A macro-generated activation for tests that serve a real transport.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use jsonrpsee::RpcModule;
+
+/// How many `sleep` calls started, and how many were dropped before they finished
+#[derive(Debug, Default)]
+pub struct Sleeps {
+    pub started: AtomicU32,
+    pub dropped: AtomicU32,
+}
+
+/// Counts a `sleep` call as dropped unless it finished
+struct Sleeping {
+    sleeps: Arc<Sleeps>,
+    finished: bool,
+}
+
+impl Drop for Sleeping {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.sleeps.dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// The `test` namespace; `generation` tells instances apart across a swap
+#[derive(Debug, Default)]
+pub struct TestHub {
+    pub generation: u32,
+    pub sleeps: Arc<Sleeps>,
+}
+
+impl TestHub {
+    pub fn new(generation: u32) -> Arc<Self> {
+        Arc::new(Self {
+            generation,
+            ..Self::default()
+        })
+    }
+}
+
+#[plexus_macros::activation(
+    namespace = "test",
+    version = "1.0.0",
+    description = "Methods for transport tests",
+    crate_path = "plexus_core"
+)]
+impl TestHub {
+    #[plexus_macros::method(description = "Echo a message back")]
+    async fn echo(&self, message: String) -> impl Stream<Item = String> + Send + 'static {
+        async_stream::stream! {
+            yield message;
+        }
+    }
+
+    #[plexus_macros::method(description = "The generation of this instance")]
+    async fn generation(&self) -> impl Stream<Item = u32> + Send + 'static {
+        let generation = self.generation;
+        async_stream::stream! {
+            yield generation;
+        }
+    }
+
+    #[plexus_macros::method(description = "The status of this instance, as one object")]
+    async fn status(&self) -> impl Stream<Item = serde_json::Value> + Send + 'static {
+        let status = serde_json::json!({ "generation": self.generation, "healthy": true });
+        async_stream::stream! {
+            yield status;
+        }
+    }
+
+    #[plexus_macros::method(description = "Answer after a delay")]
+    async fn sleep(&self, millis: u64) -> impl Stream<Item = String> + Send + 'static {
+        let sleeps = self.sleeps.clone();
+        async_stream::stream! {
+            sleeps.started.fetch_add(1, Ordering::SeqCst);
+            let mut sleeping = Sleeping { sleeps, finished: false };
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            sleeping.finished = true;
+            yield "done".to_string();
+        }
+    }
+
+    #[plexus_macros::method(description = "Output `count` lines, one item each")]
+    async fn lines(&self, count: u32) -> impl Stream<Item = String> + Send + 'static {
+        async_stream::stream! {
+            for line in 1..=count {
+                yield format!("line {}\n", line);
+            }
+        }
+    }
+
+    #[plexus_macros::method(description = "Report progress for each step")]
+    async fn progress(&self, steps: u32) -> impl Stream<Item = String> + Send + 'static {
+        async_stream::stream! {
+            for step in 1..=steps {
+                #[cfg(feature = "mcp-http")]
+                plexus_transport::ProgressHandle::current()
+                    .report(step as f64, Some(steps as f64), Some(format!("step {}", step)))
+                    .await;
+                #[cfg(not(feature = "mcp-http"))]
+                let _ = step;
+            }
+            yield format!("{} steps", steps);
+        }
+    }
+
+    #[plexus_macros::method(description = "The MCP session of the call")]
+    async fn session(&self) -> impl Stream<Item = String> + Send + 'static {
+        #[cfg(feature = "mcp-http")]
+        let session = plexus_transport::SessionContext::current().map(|session| {
+            serde_json::json!({
+                "session_id": session.session_id,
+                "client": session.client_info().map(|client| client.name.clone()),
+            })
+        });
+        #[cfg(not(feature = "mcp-http"))]
+        let session: Option<serde_json::Value> = None;
+        async_stream::stream! {
+            yield session.unwrap_or_default().to_string();
+        }
+    }
+
+    #[plexus_macros::method(description = "Ask the MCP client's LLM")]
+    async fn ask(&self, prompt: String) -> impl Stream<Item = String> + Send + 'static {
+        async_stream::stream! {
+            #[cfg(feature = "mcp-http")]
+            let answer = match plexus_transport::McpClient::current() {
+                Some(client) => client.complete(prompt, 100).await.unwrap_or_else(|e| e.to_string()),
+                None => "no client".to_string(),
+            };
+            #[cfg(not(feature = "mcp-http"))]
+            let answer = prompt;
+            yield answer;
+        }
+    }
+
+    #[plexus_macros::method(description = "Ask the MCP client's user to confirm")]
+    async fn confirm(&self, message: String) -> impl Stream<Item = String> + Send + 'static {
+        async_stream::stream! {
+            #[cfg(feature = "mcp-http")]
+            let answer = match plexus_transport::McpClient::current() {
+                Some(client) => match client.confirm(message).await {
+                    Ok(true) => "confirmed".to_string(),
+                    Ok(false) => "declined".to_string(),
+                    Err(e) => e.to_string(),
+                },
+                None => "no client".to_string(),
+            };
+            #[cfg(not(feature = "mcp-http"))]
+            let answer = message;
+            yield answer;
+        }
+    }
+
+    #[plexus_macros::method(description = "Log a message")]
+    async fn log(&self, message: String) -> impl Stream<Item = String> + Send + 'static {
+        tracing::info!(target: "test_hub", "{}", message);
+        async_stream::stream! {
+            yield message;
+        }
+    }
+}
+
+/// The RPC methods of `hub`, for the WebSocket and stdio transports
+///
+/// Besides the calls, `test.subscribe_generation` sends the hub's generation
+/// every 20ms until the client unsubscribes.
+pub fn rpc_module(hub: Arc<TestHub>) -> anyhow::Result<RpcModule<()>> {
+    let mut module = RpcModule::new(hub);
+    module.register_method("test.generation", |_, hub, _| hub.generation)?;
+    module.register_method("test.echo", |params, _, _| params.one::<String>())?;
+    module.register_subscription(
+        "test.subscribe_generation",
+        "test.generation_tick",
+        "test.unsubscribe_generation",
+        |_, pending, hub, _| async move {
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+            loop {
+                let tick = serde_json::value::to_raw_value(&hub.generation).unwrap();
+                if sink.send(tick).await.is_err() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        },
+    )?;
+    Ok(module.remove_context())
+}
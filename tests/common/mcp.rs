@@ -0,0 +1,184 @@
+//! An MCP client for tests that serve the bridge over HTTP.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use plexus_transport::mcp::server::serve_mcp_http_with_shutdown;
+use plexus_transport::McpHttpConfig;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, ClientCapabilities, ClientInfo, Content, CreateElicitationRequestParam,
+    CreateElicitationResult, CreateMessageRequestParam, CreateMessageResult, ElicitationAction,
+    ElicitationCapability, Implementation, JsonObject, LoggingMessageNotificationParam, ProgressNotificationParam,
+    ResourceUpdatedNotificationParam, Role, SamplingMessage,
+};
+use rmcp::service::{NotificationContext, RequestContext, RunningService};
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::{ClientHandler, ErrorData, RoleClient, ServiceError, ServiceExt};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use super::hub::TestHub;
+
+/// The name the client gives in `initialize`
+pub const CLIENT_NAME: &str = "transport-tests";
+
+/// The model the client claims to sample with
+pub const MODEL: &str = "test-model";
+
+/// Records the notifications the server sends the client, answers
+/// sampling requests with "Answer to: <prompt>", accepts elicitations if
+/// `confirms` is set, and leaves pings unanswered if `hangs` is set
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    pub progress: Arc<Mutex<Vec<ProgressNotificationParam>>>,
+    pub logs: Arc<Mutex<Vec<LoggingMessageNotificationParam>>>,
+    pub elicitations: Arc<Mutex<Vec<String>>>,
+    pub tool_list_changes: Arc<AtomicU32>,
+    pub resource_updates: Arc<Mutex<Vec<String>>>,
+    pub pings: Arc<AtomicU32>,
+    pub confirms: bool,
+    pub hangs: bool,
+}
+
+impl ClientHandler for Recorder {
+    fn ping(&self, _context: RequestContext<RoleClient>) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        self.pings.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if self.hangs {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+    }
+
+    fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<CreateMessageResult, ErrorData>> + Send + '_ {
+        let prompt = params.messages[0].content.as_text().map(|text| text.text.clone()).unwrap_or_default();
+        std::future::ready(Ok(CreateMessageResult {
+            model: MODEL.to_string(),
+            stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            message: SamplingMessage {
+                role: Role::Assistant,
+                content: Content::text(format!("Answer to: {}", prompt)),
+            },
+        }))
+    }
+
+    fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<CreateElicitationResult, ErrorData>> + Send + '_ {
+        self.elicitations.lock().unwrap().push(request.message);
+        let result = if self.confirms {
+            CreateElicitationResult {
+                action: ElicitationAction::Accept,
+                content: Some(serde_json::json!({ "confirm": true })),
+            }
+        } else {
+            CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            }
+        };
+        std::future::ready(Ok(result))
+    }
+
+    fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.progress.lock().unwrap().push(params);
+        std::future::ready(())
+    }
+
+    fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.logs.lock().unwrap().push(params);
+        std::future::ready(())
+    }
+
+    fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.resource_updates.lock().unwrap().push(params.uri);
+        std::future::ready(())
+    }
+
+    fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) -> impl Future<Output = ()> + Send + '_ {
+        self.tool_list_changes.fetch_add(1, Ordering::SeqCst);
+        std::future::ready(())
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            capabilities: ClientCapabilities {
+                sampling: Some(JsonObject::new()),
+                elicitation: Some(ElicitationCapability::default()),
+                ..Default::default()
+            },
+            client_info: Implementation {
+                name: CLIENT_NAME.to_string(),
+                version: "1.0.0".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+pub type Client = RunningService<RoleClient, Recorder>;
+
+/// Serve `hub` over MCP HTTP on a free port until `shutdown` is cancelled,
+/// returning the endpoint URL
+pub async fn serve(hub: Arc<TestHub>, config: McpHttpConfig, shutdown: CancellationToken) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), config.path);
+    serve_mcp_http_with_shutdown(hub, None, None, config, None, listener, shutdown, Duration::from_secs(1))
+        .await
+        .unwrap();
+    url
+}
+
+/// Initialize a session with the endpoint at `url`
+pub async fn connect(url: &str) -> (Client, Recorder) {
+    connect_with(url, Recorder::default()).await
+}
+
+/// Initialize a session with the endpoint at `url`, handled by `recorder`
+pub async fn connect_with(url: &str, recorder: Recorder) -> (Client, Recorder) {
+    let client = recorder
+        .clone()
+        .serve(StreamableHttpClientTransport::from_uri(url.to_string()))
+        .await
+        .unwrap();
+    (client, recorder)
+}
+
+/// Call `tool` with the arguments object `args`
+pub async fn call(client: &Client, tool: &str, args: Value) -> Result<CallToolResult, ServiceError> {
+    client
+        .call_tool(CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: args.as_object().cloned(),
+        })
+        .await
+}
+
+/// The text of a successful call's single content item
+pub fn text(result: &CallToolResult) -> &str {
+    assert_eq!(result.content.len(), 1, "{:?}", result);
+    &result.content[0].as_text().expect("text content").text
+}
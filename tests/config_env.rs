@@ -0,0 +1,123 @@
+//! Tests for the `PLEXUS_*` environment variable overlay.
+//!
+//! Run with: cargo test --test config_env
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use plexus_transport::{McpHttpConfig, ToolTimeouts, TransportConfig};
+
+fn addr(s: &str) -> SocketAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn ports_enable_transports_on_localhost() {
+    let config = TransportConfig::default()
+        .merge_vars([("PLEXUS_WS_PORT", "4444"), ("PLEXUS_MCP_PORT", "4445")])
+        .unwrap();
+
+    assert_eq!(config.websocket.unwrap().addr, addr("127.0.0.1:4444"));
+    assert_eq!(config.mcp_http.unwrap().addr, addr("127.0.0.1:4445"));
+    assert!(config.rest_http.is_none());
+    assert!(config.stdio.is_none());
+}
+
+#[test]
+fn variables_override_an_existing_configuration() {
+    let base = TransportConfig {
+        mcp_http: Some(McpHttpConfig::new(4445).with_server_name("from-file".into())),
+        request_timeouts: ToolTimeouts::new().with_tool("reports.*", Duration::from_secs(300)),
+        ..TransportConfig::default()
+    };
+    let config = base
+        .merge_vars([
+            ("PLEXUS_MCP_HOST", "0.0.0.0"),
+            ("PLEXUS_MCP_API_KEY", "secret"),
+            ("PLEXUS_MCP_SESSION_TTL_SECS", "600"),
+            ("PLEXUS_MCP_STATELESS", "true"),
+            ("PLEXUS_MCP_LEGACY_SSE", "true"),
+            ("PLEXUS_MCP_SSE_KEEP_ALIVE_SECS", "0"),
+            ("PLEXUS_SHUTDOWN_TIMEOUT_SECS", "3"),
+            ("PLEXUS_REQUEST_TIMEOUT_SECS", "30"),
+            ("PLEXUS_STDIO", "true"),
+        ])
+        .unwrap();
+
+    let mcp = config.mcp_http.unwrap();
+    assert_eq!(mcp.addr, addr("0.0.0.0:4445"));
+    assert_eq!(mcp.api_key.as_deref(), Some("secret"));
+    assert_eq!(mcp.server_name.as_deref(), Some("from-file"));
+    assert_eq!(mcp.session_ttl, Some(Duration::from_secs(600)));
+    assert!(mcp.stateless);
+    assert!(mcp.legacy_sse);
+    assert_eq!(mcp.sse_keep_alive, None);
+    assert_eq!(config.shutdown_timeout, Duration::from_secs(3));
+    assert_eq!(config.request_timeouts.timeout_for("files.read"), Some(Duration::from_secs(30)));
+    assert_eq!(config.request_timeouts.timeout_for("reports.export"), Some(Duration::from_secs(300)));
+    assert!(config.stdio.is_some());
+}
+
+#[test]
+fn addr_sets_the_full_listen_address() {
+    let config = TransportConfig::default()
+        .merge_vars([("PLEXUS_REST_ADDR", "[::]:8888")])
+        .unwrap();
+    assert_eq!(config.rest_http.unwrap().addr, addr("[::]:8888"));
+}
+
+#[test]
+fn empty_and_unrelated_variables_are_ignored() {
+    let config = TransportConfig::default()
+        .merge_vars([("PLEXUS_WS_PORT", ""), ("HOME", "/root")])
+        .unwrap();
+    assert!(config.websocket.is_none());
+}
+
+#[test]
+fn invalid_values_name_the_variable() {
+    let err = TransportConfig::default()
+        .merge_vars([("PLEXUS_WS_PORT", "http")])
+        .unwrap_err();
+    assert_eq!(err.var, "PLEXUS_WS_PORT");
+}
+
+#[test]
+fn settings_for_a_disabled_transport_are_rejected() {
+    let err = TransportConfig::default()
+        .merge_vars([("PLEXUS_MCP_API_KEY", "secret")])
+        .unwrap_err();
+    assert_eq!(err.var, "PLEXUS_MCP_API_KEY");
+}
+
+#[test]
+fn addr_cannot_be_combined_with_port() {
+    let err = TransportConfig::default()
+        .merge_vars([("PLEXUS_WS_ADDR", "0.0.0.0:1"), ("PLEXUS_WS_PORT", "2")])
+        .unwrap_err();
+    assert_eq!(err.var, "PLEXUS_WS_ADDR");
+}
+
+#[cfg(feature = "redis-sessions")]
+#[test]
+fn redis_variables_select_redis_sessions() {
+    let config = TransportConfig::default()
+        .merge_vars([
+            ("PLEXUS_MCP_PORT", "4445"),
+            ("PLEXUS_SESSIONS_REDIS_URL", "redis://cache:6379"),
+            ("PLEXUS_SESSIONS_REDIS_TTL_SECS", "600"),
+        ])
+        .unwrap();
+    match config.mcp_http.unwrap().session_storage {
+        plexus_transport::SessionStorage::Redis { url, ttl, .. } => {
+            assert_eq!(url, "redis://cache:6379");
+            assert_eq!(ttl, Duration::from_secs(600));
+        }
+        other => panic!("expected redis storage, got {:?}", other),
+    }
+
+    let err = TransportConfig::default()
+        .merge_vars([("PLEXUS_MCP_PORT", "4445"), ("PLEXUS_SESSIONS_REDIS_KEY_PREFIX", "files:")])
+        .unwrap_err();
+    assert_eq!(err.var, "PLEXUS_SESSIONS_REDIS_KEY_PREFIX");
+}
@@ -0,0 +1,238 @@
+//! Tests for loading transport configuration from TOML and YAML.
+//!
+//! Run with: cargo test --features config-file --test config_file
+
+#[cfg(feature = "config-file")]
+mod config_file_tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use plexus_transport::{
+        ConfigFileError, NotificationOverflow, RateLimitKey, RequestLogging, StdioFraming, SubscriptionResume,
+        TransportConfig,
+    };
+
+    fn invalid_field(err: ConfigFileError) -> String {
+        match err {
+            ConfigFileError::Invalid { field, .. } => field,
+            other => panic!("expected a field error, got {}", other),
+        }
+    }
+
+    #[test]
+    fn toml_configures_every_transport() {
+        let config = TransportConfig::from_toml_str(
+            r#"
+            api_key = "shared"
+            shutdown_timeout_secs = 5
+            signal_shutdown = true
+            request_timeouts = { default_secs = 60, methods = { "search.query" = 2 } }
+
+            [websocket]
+            port = 4444
+            max_connections = 7
+            max_request_batch_size = 20
+            allowed_hosts = ["localhost:*"]
+            ping_interval_secs = 20
+            inactive_limit_secs = 30
+            max_missed_pings = 2
+            resume_window_secs = 60
+            drain_timeout_secs = 5
+
+            [stdio]
+            max_line_length = 4096
+            framing = "content_length"
+            notification_overflow = "drop_oldest"
+            eof_grace_ms = 2500
+            request_timeout_ms = 30000
+            exit_on_parent_death = true
+            stdout_guard = true
+            mcp = true
+
+            [mcp_http]
+            addr = "0.0.0.0:4445"
+            server_name = "files"
+            instructions = "Read files before editing them."
+            capabilities = { logging = false }
+            path = "/v1/mcp"
+            request_logging = "full"
+            slow_request_threshold_ms = 2500
+            session_ttl_secs = 3600
+            ping_interval_secs = 30
+            sse_keep_alive_secs = 30
+            session_channel_capacity = 64
+            tools_page_size = 50
+            rate_limit = { requests_per_second = 2.5, burst = 10, key = "api_key" }
+            tool_timeouts = { default_secs = 30, tools = { "reports.*" = 300 } }
+
+            [rest_http]
+            host = "::1"
+            port = 8888
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.api_key.as_deref(), Some("shared"));
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(5));
+        assert!(config.signal_shutdown);
+        assert_eq!(config.request_timeouts.timeout_for("search.query"), Some(Duration::from_secs(2)));
+        assert_eq!(config.request_timeouts.timeout_for("reports.export"), Some(Duration::from_secs(60)));
+
+        let ws = config.websocket.unwrap();
+        assert_eq!(ws.addr, "127.0.0.1:4444".parse::<SocketAddr>().unwrap());
+        assert_eq!(ws.max_connections, 7);
+        assert_eq!(ws.max_request_batch_size, Some(20));
+        assert_eq!(ws.allowed_hosts, Some(vec!["localhost:*".to_string()]));
+        assert_eq!(ws.ping_interval, Some(Duration::from_secs(20)));
+        assert_eq!(ws.inactive_limit, Duration::from_secs(30));
+        assert_eq!(ws.max_missed_pings, 2);
+        let resume = ws.subscription_resume.unwrap();
+        assert_eq!(resume.window, Duration::from_secs(60));
+        assert_eq!(resume.replay, SubscriptionResume::default().replay);
+        assert_eq!(ws.drain_timeout, Duration::from_secs(5));
+
+        let stdio = config.stdio.unwrap();
+        assert_eq!(stdio.max_line_length, 4096);
+        assert_eq!(stdio.framing, StdioFraming::ContentLength);
+        assert_eq!(stdio.notification_overflow, NotificationOverflow::DropOldest);
+        assert_eq!(stdio.eof_grace, Some(Duration::from_millis(2500)));
+        assert!(stdio.exit_on_parent_death);
+        assert!(stdio.stdout_guard);
+        assert!(stdio.mcp);
+        assert_eq!(stdio.request_timeout, Some(Duration::from_secs(30)));
+
+        let mcp = config.mcp_http.unwrap();
+        assert_eq!(mcp.addr, "0.0.0.0:4445".parse::<SocketAddr>().unwrap());
+        assert_eq!(mcp.server_name.as_deref(), Some("files"));
+        assert_eq!(mcp.instructions.as_deref(), Some("Read files before editing them."));
+        assert!(!mcp.capabilities.logging);
+        assert!(mcp.capabilities.tools);
+        assert_eq!(mcp.path, "/v1/mcp");
+        assert_eq!(mcp.request_logging, RequestLogging::Full);
+        assert_eq!(mcp.slow_request_threshold, Some(Duration::from_millis(2500)));
+        assert_eq!(mcp.session_ttl, Some(Duration::from_secs(3600)));
+        assert_eq!(mcp.ping_interval, Some(Duration::from_secs(30)));
+        assert_eq!(mcp.sse_keep_alive, Some(Duration::from_secs(30)));
+        assert_eq!(mcp.session_channel_capacity, 64);
+        assert_eq!(mcp.tools_page_size, Some(50));
+        assert_eq!(mcp.tool_timeouts.timeout_for("reports.export"), Some(Duration::from_secs(300)));
+        assert_eq!(mcp.tool_timeouts.timeout_for("files.read"), Some(Duration::from_secs(30)));
+        let rate_limit = mcp.rate_limit.unwrap();
+        assert_eq!(rate_limit.burst, 10);
+        assert_eq!(rate_limit.key, RateLimitKey::ApiKey);
+
+        let rest = config.rest_http.unwrap();
+        assert_eq!(rest.addr, "[::1]:8888".parse::<SocketAddr>().unwrap());
+    }
+
+    #[test]
+    fn yaml_matches_toml() {
+        let config = TransportConfig::from_yaml_str(
+            "
+websocket:
+  port: 4444
+mcp_http:
+  port: 4445
+  stateless: true
+  legacy_sse: true
+  partial_results: true
+  max_result_bytes: 65536
+  tool_naming:
+    separator: _
+    prefix: hub
+  ip_filter:
+    allow: [10.0.0.0/8]
+",
+        )
+        .unwrap();
+
+        assert_eq!(config.websocket.unwrap().addr.port(), 4444);
+        let mcp = config.mcp_http.unwrap();
+        assert!(mcp.stateless);
+        assert!(mcp.legacy_sse);
+        assert_eq!(mcp.tool_naming.tool_name("files.read"), "hub_files_read");
+        assert!(mcp.partial_results);
+        assert_eq!(mcp.max_result_bytes, Some(65536));
+        let filter = mcp.ip_filter.unwrap();
+        assert!(filter.allows("10.1.2.3".parse().unwrap()));
+        assert!(!filter.allows("192.168.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_their_path() {
+        let err = TransportConfig::from_toml_str("[mcp_http]\nport = 4445\nmax_bodysize = 10\n").unwrap_err();
+        assert!(err.to_string().contains("max_bodysize"));
+        assert!(invalid_field(err).starts_with("mcp_http"));
+    }
+
+    #[test]
+    fn type_errors_are_reported_with_their_path() {
+        let err = TransportConfig::from_toml_str("[websocket]\nport = \"not a port\"\n").unwrap_err();
+        assert_eq!(invalid_field(err), "websocket.port");
+    }
+
+    #[test]
+    fn invalid_networks_are_reported_with_their_index() {
+        let err = TransportConfig::from_toml_str(
+            "[mcp_http]\nport = 4445\nip_filter = { allow = [\"10.0.0.0/8\", \"10.0.0.0/33\"] }\n",
+        )
+        .unwrap_err();
+        assert_eq!(invalid_field(err), "mcp_http.ip_filter.allow[1]");
+    }
+
+    #[test]
+    fn a_listen_address_is_required() {
+        let err = TransportConfig::from_toml_str("[websocket]\nmax_connections = 3\n").unwrap_err();
+        assert_eq!(invalid_field(err), "websocket.port");
+    }
+
+    #[test]
+    fn unsupported_extensions_are_rejected() {
+        let path = std::env::temp_dir().join(format!("plexus-transport-{}.ini", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let err = TransportConfig::from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(err, ConfigFileError::UnsupportedFormat(_)));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn metrics_endpoint_is_configured() {
+        let config = TransportConfig::from_toml_str("[metrics]\nport = 9100\npath = \"/stats\"\n").unwrap();
+        let endpoint = config.metrics_endpoint.unwrap();
+        assert_eq!(endpoint.addr, "127.0.0.1:9100".parse::<SocketAddr>().unwrap());
+        assert_eq!(endpoint.path, "/stats");
+
+        let err = TransportConfig::from_toml_str("[metrics]\nport = 9100\npath = \"stats\"\n").unwrap_err();
+        assert_eq!(invalid_field(err), "metrics.path");
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    #[test]
+    fn metrics_endpoint_needs_the_feature() {
+        let err = TransportConfig::from_toml_str("[metrics]\nport = 9100\n").unwrap_err();
+        assert_eq!(invalid_field(err), "metrics");
+    }
+
+    #[cfg(feature = "sqlite-sessions")]
+    #[test]
+    fn sqlite_tuning_is_configured() {
+        let config = TransportConfig::from_toml_str(
+            "[mcp_http]\nport = 4445\nsqlite_tuning = { busy_timeout_ms = 10000, max_connections = 4, vacuum_interval_secs = 3600, replay_events = 64 }\n",
+        )
+        .unwrap();
+        let tuning = config.mcp_http.unwrap().sqlite_tuning;
+        assert!(tuning.wal);
+        assert_eq!(tuning.busy_timeout, Duration::from_secs(10));
+        assert_eq!(tuning.max_connections, 4);
+        assert_eq!(tuning.vacuum_interval, Some(Duration::from_secs(3600)));
+        assert_eq!(tuning.replay_events, 64);
+    }
+
+    #[cfg(not(feature = "sqlite-sessions"))]
+    #[test]
+    fn sqlite_tuning_needs_the_feature() {
+        let err = TransportConfig::from_toml_str("[mcp_http]\nport = 4445\nsqlite_tuning = { wal = false }\n").unwrap_err();
+        assert_eq!(invalid_field(err), "mcp_http.sqlite_tuning");
+    }
+}
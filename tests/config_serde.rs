@@ -0,0 +1,100 @@
+//! Tests for serializing and deserializing transport configuration.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use plexus_transport::{
+    McpHttpConfig, OriginCheck, RateLimitConfig, RequestLogging, SessionStorage, StdioConfig,
+    TransportConfig, WebSocketConfig,
+};
+
+#[test]
+fn transport_config_round_trips_through_json() {
+    let mut config = TransportConfig::default();
+    config.websocket = Some(
+        WebSocketConfig::new(4444)
+            .with_max_connections(7)
+            .with_rate_limit(RateLimitConfig::per_ip(2.0, 10)),
+    );
+    config.stdio = Some(StdioConfig::default());
+    config.mcp_http = Some(
+        McpHttpConfig::new(4445)
+            .with_server_name("files".to_string())
+            .with_path("v1/mcp/")
+            .with_request_logging(RequestLogging::Full)
+            .with_origin_check(OriginCheck::Disabled)
+            .with_partial_results()
+            .with_max_result_bytes(65536),
+    );
+    config.shutdown_timeout = Duration::from_millis(2500);
+
+    let json = serde_json::to_string(&config).unwrap();
+    let back: TransportConfig = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back.shutdown_timeout, Duration::from_millis(2500));
+    let ws = back.websocket.unwrap();
+    assert_eq!(ws.addr.port(), 4444);
+    assert_eq!(ws.max_connections, 7);
+    assert!(ws.rate_limit.is_some());
+    assert!(back.stdio.is_some());
+    let mcp = back.mcp_http.unwrap();
+    assert_eq!(mcp.server_name.as_deref(), Some("files"));
+    assert_eq!(mcp.path, "/v1/mcp");
+    assert_eq!(mcp.request_logging, RequestLogging::Full);
+    assert!(matches!(mcp.origin_check, OriginCheck::Disabled));
+    assert!(mcp.partial_results);
+    assert_eq!(mcp.max_result_bytes, Some(65536));
+    assert!(back.rest_http.is_none());
+}
+
+#[test]
+fn session_storage_is_internally_tagged() {
+    let json = serde_json::to_value(SessionStorage::InMemory).unwrap();
+    assert_eq!(json, serde_json::json!({ "type": "in_memory" }));
+
+    let storage: SessionStorage = serde_json::from_value(json).unwrap();
+    assert!(matches!(storage, SessionStorage::InMemory));
+}
+
+#[test]
+fn missing_fields_take_their_defaults() {
+    let mcp: McpHttpConfig =
+        serde_json::from_str(r#"{ "addr": "0.0.0.0:4445", "server_name": "files" }"#).unwrap();
+    let defaults = McpHttpConfig::new(4445);
+
+    assert_eq!(mcp.addr, "0.0.0.0:4445".parse::<SocketAddr>().unwrap());
+    assert_eq!(mcp.server_name.as_deref(), Some("files"));
+    assert_eq!(mcp.max_body_size, defaults.max_body_size);
+    assert_eq!(mcp.path, "/mcp");
+    assert!(matches!(mcp.session_storage, SessionStorage::InMemory));
+
+    let config: TransportConfig = serde_json::from_str("{}").unwrap();
+    assert!(config.websocket.is_none());
+    assert_eq!(config.shutdown_timeout, TransportConfig::default().shutdown_timeout);
+}
+
+#[cfg(feature = "sqlite-sessions")]
+#[test]
+fn sqlite_session_storage_keeps_its_path() {
+    let json = r#"{ "type": "sqlite", "path": "/var/lib/plexus/sessions.db" }"#;
+    let storage: SessionStorage = serde_json::from_str(json).unwrap();
+    match storage {
+        SessionStorage::Sqlite { path } => assert_eq!(path, std::path::Path::new("/var/lib/plexus/sessions.db")),
+        other => panic!("expected sqlite storage, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "redis-sessions")]
+#[test]
+fn redis_session_storage_keeps_its_ttl() {
+    let json = r#"{ "type": "redis", "url": "redis://cache:6379", "key_prefix": "files:", "ttl": 3600.0 }"#;
+    let storage: SessionStorage = serde_json::from_str(json).unwrap();
+    match storage {
+        SessionStorage::Redis { url, key_prefix, ttl } => {
+            assert_eq!(url, "redis://cache:6379");
+            assert_eq!(key_prefix, "files:");
+            assert_eq!(ttl, Duration::from_secs(3600));
+        }
+        other => panic!("expected redis storage, got {:?}", other),
+    }
+}
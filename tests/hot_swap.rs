@@ -0,0 +1,136 @@
+//! Tests for replacing the activation of a running server.
+//!
+//! Run with: cargo test --test hot_swap
+
+mod common;
+
+#[cfg(feature = "websocket")]
+mod websocket_tests {
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use plexus_transport::TransportServer;
+
+    use super::common::free_port;
+    use super::common::hub::{rpc_module, TestHub};
+
+    const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+    /// Open a WebSocket connection, returning once the upgrade is accepted
+    async fn connect(port: u16) -> TcpStream {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream.write_all(UPGRADE.as_bytes()).await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        assert!(head.starts_with(b"HTTP/1.1 101"));
+        stream
+    }
+
+    /// Send `message` as a client text frame (masked with a zero key)
+    async fn send_text(stream: &mut TcpStream, message: &Value) {
+        let payload = message.to_string();
+        assert!(payload.len() < 126);
+        let mut frame = vec![0x81, 0x80 | payload.len() as u8, 0, 0, 0, 0];
+        frame.extend_from_slice(payload.as_bytes());
+        stream.write_all(&frame).await.unwrap();
+    }
+
+    /// Read the next server text frame as JSON
+    async fn read_text(stream: &mut TcpStream) -> Value {
+        let opcode = stream.read_u8().await.unwrap();
+        assert_eq!(opcode, 0x81);
+        let len = match stream.read_u8().await.unwrap() {
+            126 => stream.read_u16().await.unwrap() as usize,
+            127 => stream.read_u64().await.unwrap() as usize,
+            len => len as usize,
+        };
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).await.unwrap();
+        serde_json::from_slice(&payload).unwrap()
+    }
+
+    /// Call `method` and return its result, collecting the generation ticks
+    /// that arrive before it
+    async fn call(stream: &mut TcpStream, id: u64, method: &str, ticks: &mut Vec<Value>) -> Value {
+        send_text(stream, &json!({ "jsonrpc": "2.0", "id": id, "method": method })).await;
+        loop {
+            let message = read_text(stream).await;
+            if message["id"] == id {
+                return message["result"].clone();
+            }
+            assert_eq!(message["method"], "test.generation_tick");
+            ticks.push(message["params"]["result"].clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn open_subscriptions_keep_the_old_instance_after_a_swap() {
+        let port = free_port();
+        let server = TransportServer::builder(TestHub::new(1), rpc_module)
+            .with_websocket(port)
+            .build()
+            .await
+            .unwrap();
+        let swap = server.activation_swap();
+        let running = server.serve_detached().await.unwrap();
+
+        let mut old = connect(port).await;
+        let mut ticks = Vec::new();
+        call(&mut old, 1, "test.subscribe_generation", &mut ticks).await;
+        assert_eq!(read_text(&mut old).await["params"]["result"], 1);
+
+        swap.replace(TestHub::new(2), None, None).unwrap();
+        assert_eq!(swap.current().generation, 2);
+
+        // New connections call the new instance
+        let mut new = connect(port).await;
+        assert_eq!(call(&mut new, 1, "test.generation", &mut Vec::new()).await, 2);
+
+        // The open connection keeps its subscription and methods on the old one
+        for _ in 0..5 {
+            assert_eq!(read_text(&mut old).await["params"]["result"], 1);
+        }
+        assert_eq!(call(&mut old, 2, "test.generation", &mut ticks).await, 1);
+        assert!(ticks.iter().all(|tick| *tick == 1), "{:?}", ticks);
+
+        running.shutdown().await.unwrap();
+    }
+}
+
+#[cfg(feature = "mcp-http")]
+mod mcp_tests {
+    use serde_json::json;
+
+    use plexus_transport::{McpHttpConfig, TransportServer};
+
+    use super::common::hub::{rpc_module, TestHub};
+    use super::common::mcp::{call, connect, text};
+
+    #[tokio::test]
+    async fn open_sessions_keep_the_old_instance_after_a_swap() {
+        let server = TransportServer::builder(TestHub::new(1), rpc_module)
+            .with_mcp_http_config(McpHttpConfig::new(0))
+            .build()
+            .await
+            .unwrap();
+        let swap = server.activation_swap();
+        let running = server.serve_detached().await.unwrap();
+        let url = format!("http://{}/mcp", running.mcp_http_addr.unwrap());
+
+        let (old, _) = connect(&url).await;
+        assert_eq!(text(&call(&old, "test.generation", json!({})).await.unwrap()), "1");
+
+        swap.replace(TestHub::new(2), None, None).unwrap();
+
+        let (new, _) = connect(&url).await;
+        assert_eq!(text(&call(&new, "test.generation", json!({})).await.unwrap()), "2");
+        assert_eq!(text(&call(&old, "test.generation", json!({})).await.unwrap()), "1");
+
+        old.cancel().await.unwrap();
+        new.cancel().await.unwrap();
+        running.shutdown().await.unwrap();
+    }
+}
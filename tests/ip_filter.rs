@@ -0,0 +1,49 @@
+//! Tests for CIDR allow/deny filtering.
+//!
+//! Run with: cargo test --test ip_filter
+
+use std::net::IpAddr;
+
+use plexus_transport::IpFilter;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn empty_filter_accepts_everyone() {
+    let filter = IpFilter::new();
+    assert!(filter.allows(ip("203.0.113.9")));
+    assert!(filter.allows(ip("::1")));
+}
+
+#[test]
+fn allow_list_restricts_peers() {
+    let filter = IpFilter::new().allow(["10.0.0.0/8", "::1"]).unwrap();
+    assert!(filter.allows(ip("10.1.2.3")));
+    assert!(filter.allows(ip("::1")));
+    assert!(!filter.allows(ip("192.168.1.1")));
+}
+
+#[test]
+fn deny_wins_over_allow() {
+    let filter = IpFilter::new()
+        .allow(["10.0.0.0/8"])
+        .unwrap()
+        .deny(["10.66.0.0/16"])
+        .unwrap();
+    assert!(filter.allows(ip("10.1.0.1")));
+    assert!(!filter.allows(ip("10.66.3.4")));
+}
+
+#[test]
+fn ipv4_mapped_addresses_match_ipv4_networks() {
+    let filter = IpFilter::new().allow(["127.0.0.0/8"]).unwrap();
+    assert!(filter.allows(ip("::ffff:127.0.0.1")));
+}
+
+#[test]
+fn invalid_networks_are_rejected() {
+    assert!(IpFilter::new().allow(["10.0.0.0/33"]).is_err());
+    assert!(IpFilter::new().deny(["not-an-ip"]).is_err());
+}
@@ -0,0 +1,163 @@
+//! Tests for JWT bearer validation with a static secret or a JWKS URL.
+//!
+//! Run with: cargo test --features jwt-auth --test jwt_auth
+
+#[cfg(feature = "jwt-auth")]
+mod jwt_tests {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use plexus_transport::auth::jwt::JwtAuth;
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn token(claims: serde_json::Value, secret: &[u8]) -> String {
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn bearer(token: &str) -> http::HeaderMap {
+        let mut h = http::HeaderMap::new();
+        h.insert(
+            http::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        h
+    }
+
+    #[tokio::test]
+    async fn valid_token_yields_claims() {
+        let auth = JwtAuth::with_secret(SECRET);
+        let t = token(json!({"sub": "agent-1", "exp": now() + 60}), SECRET);
+
+        let claims = auth.authenticate(&bearer(&t)).await.expect("valid token");
+        assert_eq!(claims.subject(), Some("agent-1"));
+    }
+
+    #[tokio::test]
+    async fn wrong_secret_is_rejected() {
+        let auth = JwtAuth::with_secret(SECRET);
+        let t = token(json!({"sub": "agent-1", "exp": now() + 60}), b"other");
+
+        assert!(auth.authenticate(&bearer(&t)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_rejected() {
+        let auth = JwtAuth::with_secret(SECRET);
+        let t = token(json!({"sub": "agent-1", "exp": now() - 3600}), SECRET);
+
+        assert!(auth.validate(&t).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn issuer_is_enforced() {
+        let auth = JwtAuth::with_secret(SECRET).with_issuer("https://idp.example.com");
+        let good = token(json!({"iss": "https://idp.example.com", "exp": now() + 60}), SECRET);
+        let bad = token(json!({"iss": "https://evil.example.com", "exp": now() + 60}), SECRET);
+
+        assert!(auth.validate(&good).await.is_ok());
+        assert!(auth.validate(&bad).await.is_err());
+    }
+}
+
+#[cfg(feature = "jwt-auth")]
+mod jwks_tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use plexus_transport::auth::jwt::{JwtAuth, JwtError};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    /// An HS256 key set holding `SECRET` under kid `k1`
+    const JWKS: &str = r#"{"keys":[{"kty":"oct","kid":"k1","alg":"HS256","k":"dGVzdC1zZWNyZXQ"}]}"#;
+
+    fn token(kid: &str) -> String {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(kid.to_string());
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        encode(&header, &json!({"sub": "agent-1", "exp": exp}), &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn auth(url: String) -> JwtAuth {
+        JwtAuth::with_jwks(url).with_algorithms(vec![Algorithm::HS256])
+    }
+
+    /// Serves `JWKS` slowly, counting fetches
+    async fn jwks_server(fetches: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/jwks.json", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut sock, _) = listener.accept().await.unwrap();
+                fetches.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut request = [0u8; 4096];
+                    let _ = sock.read(&mut request).await;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        JWKS.len(),
+                        JWKS
+                    );
+                    let _ = sock.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn concurrent_cache_misses_fetch_the_jwks_once() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let auth = auth(jwks_server(fetches.clone()).await);
+
+        let validations = (0..10).map(|_| {
+            let auth = auth.clone();
+            tokio::spawn(async move { auth.validate(&token("k1")).await })
+        });
+        for validation in futures::future::join_all(validations).await {
+            let claims = validation.unwrap().expect("valid token");
+            assert_eq!(claims.subject(), Some("agent-1"));
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Unknown key ids right after a fetch are not refetched
+        assert!(matches!(auth.validate(&token("k2")).await, Err(JwtError::UnknownKey(_))));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stalled_jwks_endpoints_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/jwks.json", listener.local_addr().unwrap());
+        // Accepts connections and never answers
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((sock, _)) = listener.accept().await {
+                open.push(sock);
+            }
+        });
+
+        let auth = auth(url).with_jwks_timeout(Duration::from_millis(200));
+        let result = tokio::time::timeout(Duration::from_secs(5), auth.validate(&token("k1")))
+            .await
+            .expect("JWKS fetch timed out");
+        assert!(matches!(result, Err(JwtError::JwksFetch(_))), "{:?}", result);
+    }
+}
@@ -0,0 +1,36 @@
+//! Tests for serving on OS-assigned ports.
+//!
+//! Run with: cargo test --test local_addr
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::websocket::serve_websocket_with_listener;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const REQUEST: &str = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: 45\r\nConnection: close\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.ping\"}";
+
+#[tokio::test]
+async fn websocket_serves_on_an_ephemeral_port() {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_ne!(addr.port(), 0);
+
+    // The configured port is ignored in favour of the listener
+    let server = serve_websocket_with_listener(listener, module, WebSocketConfig::new(0), None)
+        .await
+        .unwrap();
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(REQUEST.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("\"result\":\"pong\""));
+
+    server.stop().unwrap();
+    server.stopped().await;
+}
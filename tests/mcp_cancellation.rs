@@ -0,0 +1,49 @@
+//! Tests for cancelling MCP tool calls.
+//!
+//! Run with: cargo test --test mcp_cancellation
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_cancellation_tests {
+    use std::sync::atomic::Ordering;
+
+    use plexus_transport::McpHttpConfig;
+    use rmcp::model::{CallToolRequest, CallToolRequestParam};
+    use rmcp::service::PeerRequestOptions;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+    use super::common::until;
+
+    #[tokio::test]
+    async fn cancelled_notifications_stop_the_tool_call() {
+        let hub = TestHub::new(1);
+        let sleeps = hub.sleeps.clone();
+        let shutdown = CancellationToken::new();
+        let url = serve(hub, McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let request = CallToolRequest::new(CallToolRequestParam {
+            name: "test.sleep".into(),
+            arguments: json!({ "millis": 60_000 }).as_object().cloned(),
+        });
+        let handle = client
+            .send_cancellable_request(request.into(), PeerRequestOptions::no_options())
+            .await
+            .unwrap();
+        until(|| sleeps.started.load(Ordering::SeqCst) == 1).await;
+
+        handle.cancel(Some("no longer needed".into())).await.unwrap();
+        until(|| sleeps.dropped.load(Ordering::SeqCst) == 1).await;
+
+        // The session carries on
+        let result = call(&client, "test.echo", json!({ "message": "still here" })).await.unwrap();
+        assert_eq!(text(&result), "still here");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
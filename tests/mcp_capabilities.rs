@@ -0,0 +1,72 @@
+//! Tests for the MCP server instructions and declared capabilities.
+//!
+//! Run with: cargo test --test mcp_capabilities
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_capabilities_tests {
+    use plexus_transport::{McpCapabilities, McpHttpConfig};
+    use rmcp::model::{ErrorCode, LoggingLevel, SetLevelRequestParam};
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve};
+
+    #[tokio::test]
+    async fn sessions_are_initialized_with_the_activation_description_and_served_capabilities() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let info = client.peer_info().unwrap();
+        assert_eq!(info.instructions.as_deref(), Some("Methods for transport tests"));
+        assert!(info.capabilities.tools.is_some());
+        assert!(info.capabilities.logging.is_some());
+        // Nothing serves these
+        assert!(info.capabilities.resources.is_none());
+        assert!(info.capabilities.prompts.is_none());
+        assert!(info.capabilities.completions.is_none());
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn disabled_capabilities_are_not_declared_or_served() {
+        let capabilities = McpCapabilities {
+            tools: false,
+            logging: false,
+            ..McpCapabilities::all()
+        };
+        let config = McpHttpConfig::new(0)
+            .with_instructions("Call test.echo first.")
+            .with_capabilities(capabilities);
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let info = client.peer_info().unwrap();
+        assert_eq!(info.instructions.as_deref(), Some("Call test.echo first."));
+        assert!(info.capabilities.tools.is_none());
+        assert!(info.capabilities.logging.is_none());
+
+        assert!(client.list_all_tools().await.unwrap().is_empty());
+        let Err(ServiceError::McpError(error)) = call(&client, "test.echo", json!({ "message": "hi" })).await else {
+            panic!("tool was called without the tools capability");
+        };
+        assert_eq!(error.code, ErrorCode::METHOD_NOT_FOUND);
+        let Err(ServiceError::McpError(error)) = client
+            .set_level(SetLevelRequestParam { level: LoggingLevel::Info })
+            .await
+        else {
+            panic!("log level was set without the logging capability");
+        };
+        assert_eq!(error.code, ErrorCode::METHOD_NOT_FOUND);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,66 @@
+//! Tests for requests from the server to the MCP client.
+//!
+//! Run with: cargo test --test mcp_client
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_client_tests {
+    use plexus_transport::{ClientRequestError, McpClient, McpHttpConfig};
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, connect_with, serve, text, Recorder};
+
+    #[tokio::test]
+    async fn outside_a_tool_call_there_is_no_client() {
+        assert!(McpClient::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn tools_sample_from_the_calling_client() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let result = call(&client, "test.ask", json!({ "prompt": "Summarize the rows" })).await.unwrap();
+        assert_eq!(text(&result), "Answer to: Summarize the rows");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn tools_ask_the_calling_client_to_confirm() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (declining, declined) = connect(&url).await;
+        let confirming = Recorder {
+            confirms: true,
+            ..Recorder::default()
+        };
+        let (confirming, confirmed) = connect_with(&url, confirming).await;
+
+        let result = call(&confirming, "test.confirm", json!({ "message": "Delete 3 rows?" })).await.unwrap();
+        assert_eq!(text(&result), "confirmed");
+        let result = call(&declining, "test.confirm", json!({ "message": "Drop the table?" })).await.unwrap();
+        assert_eq!(text(&result), "declined");
+
+        // Each question went to the session that made the call
+        assert_eq!(*confirmed.elicitations.lock().unwrap(), ["Delete 3 rows?"]);
+        assert_eq!(*declined.elicitations.lock().unwrap(), ["Drop the table?"]);
+
+        confirming.cancel().await.unwrap();
+        declining.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[test]
+    fn unsupported_capabilities_are_named() {
+        let error = ClientRequestError::Unsupported("sampling");
+        assert_eq!(error.to_string(), "The MCP client does not support sampling");
+        let error = ClientRequestError::Unsupported("elicitation");
+        assert_eq!(error.to_string(), "The MCP client does not support elicitation");
+    }
+}
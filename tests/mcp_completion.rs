@@ -0,0 +1,100 @@
+//! Tests for MCP argument completion providers.
+//!
+//! Run with: cargo test --test mcp_completion
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_completion_tests {
+    use std::sync::Arc;
+
+    use futures::future::BoxFuture;
+    use plexus_transport::{CallerIdentity, CompletionError, CompletionProvider, McpHttpConfig};
+    use rmcp::model::{ArgumentInfo, CompleteRequestParam, ErrorCode, Reference};
+    use rmcp::{ErrorData, ServiceError};
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{connect, serve};
+
+    const PLUGINS: &[&str] = &["bash", "files", "filesystem", "loopback"];
+
+    /// Completes the `plugin` argument of the `summarize` prompt
+    struct Plugins;
+
+    impl CompletionProvider for Plugins {
+        fn complete<'a>(
+            &'a self,
+            request: &'a CompleteRequestParam,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<Vec<String>, CompletionError>> {
+            Box::pin(async move {
+                match request.r#ref {
+                    Reference::Prompt(ref prompt) if prompt.name == "summarize" => Ok(PLUGINS
+                        .iter()
+                        .filter(|name| name.starts_with(&request.argument.value))
+                        .map(|name| name.to_string())
+                        .collect()),
+                    Reference::Prompt(ref prompt) => Err(CompletionError::NotFound(prompt.name.clone())),
+                    Reference::Resource(_) => Ok(Vec::new()),
+                }
+            })
+        }
+    }
+
+    fn request(prompt: &str, value: &str) -> CompleteRequestParam {
+        CompleteRequestParam {
+            r#ref: Reference::for_prompt(prompt),
+            argument: ArgumentInfo {
+                name: "plugin".into(),
+                value: value.into(),
+            },
+            context: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn providers_suggest_matching_values() {
+        let caller = CallerIdentity::Anonymous;
+        let values = Plugins.complete(&request("summarize", "fil"), &caller).await.unwrap();
+        assert_eq!(values, vec!["files", "filesystem"]);
+        assert!(Plugins.complete(&request("summarize", "zzz"), &caller).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn served_completions_come_from_the_provider() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_completion_provider(Arc::new(Plugins));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+        assert!(client.peer_info().unwrap().capabilities.completions.is_some());
+
+        let result = client.complete(request("summarize", "fil")).await.unwrap();
+        assert_eq!(result.completion.values, ["files", "filesystem"]);
+        assert_eq!(result.completion.total, Some(2));
+        assert_eq!(result.completion.has_more, Some(false));
+
+        let Err(ServiceError::McpError(error)) = client.complete(request("farewell", "")).await else {
+            panic!("unknown prompt was completed");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown reference: farewell");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn unknown_references_map_to_invalid_params() {
+        let error = Plugins
+            .complete(&request("farewell", ""), &CallerIdentity::Anonymous)
+            .await
+            .unwrap_err();
+        let error = ErrorData::from(error);
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown reference: farewell");
+
+        let error = ErrorData::from(CompletionError::failed("index unavailable"));
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+}
@@ -0,0 +1,77 @@
+//! Tests for the CORS configuration used by MCP HTTP.
+//!
+//! Run with: cargo test --test mcp_cors
+
+use axum::{routing::post, Router};
+use plexus_transport::CorsConfig;
+use tower::ServiceExt;
+
+fn app(cors: CorsConfig) -> Router {
+    Router::new()
+        .route("/mcp", post(|| async { "ok" }))
+        .layer(cors.layer())
+}
+
+fn preflight(origin: &str) -> http::Request<axum::body::Body> {
+    http::Request::builder()
+        .method(http::Method::OPTIONS)
+        .uri("/mcp")
+        .header("origin", origin)
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "mcp-session-id,content-type")
+        .body(axum::body::Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn preflight_from_allowed_origin() {
+    let response = app(CorsConfig::new(["https://inspector.example.com"]))
+        .oneshot(preflight("https://inspector.example.com"))
+        .await
+        .unwrap();
+
+    let headers = response.headers();
+    assert_eq!(
+        headers["access-control-allow-origin"],
+        "https://inspector.example.com"
+    );
+    let allowed = headers["access-control-allow-headers"].to_str().unwrap();
+    assert!(allowed.contains("mcp-session-id"));
+}
+
+#[tokio::test]
+async fn disallowed_origin_gets_no_cors_headers() {
+    let response = app(CorsConfig::new(["https://inspector.example.com"]))
+        .oneshot(preflight("https://evil.example.com"))
+        .await
+        .unwrap();
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn credentials_echo_origin_instead_of_wildcard() {
+    let response = app(CorsConfig::permissive().with_credentials(true))
+        .oneshot(preflight("https://app.example.com"))
+        .await
+        .unwrap();
+
+    let headers = response.headers();
+    assert_eq!(headers["access-control-allow-origin"], "https://app.example.com");
+    assert_eq!(headers["access-control-allow-credentials"], "true");
+}
+
+#[tokio::test]
+async fn session_id_is_exposed_to_scripts() {
+    let request = http::Request::builder()
+        .method(http::Method::POST)
+        .uri("/mcp")
+        .header("origin", "https://app.example.com")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = app(CorsConfig::permissive()).oneshot(request).await.unwrap();
+
+    let exposed = response.headers()["access-control-expose-headers"]
+        .to_str()
+        .unwrap();
+    assert!(exposed.contains("mcp-session-id"));
+}
@@ -0,0 +1,120 @@
+//! Tests for mapping activation errors to MCP errors.
+//!
+//! Run with: cargo test --test mcp_error_map
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_error_map_tests {
+    use std::sync::Arc;
+
+    use plexus_core::plexus::PlexusError;
+    use plexus_transport::{ErrorMapper, MappedError, McpHttpConfig};
+    use rmcp::model::ErrorCode;
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+
+    /// Turns missing files into tool results and quota errors into -32010
+    struct FileErrors;
+
+    impl ErrorMapper for FileErrors {
+        fn map_call_error(&self, tool: &str, error: &PlexusError) -> Option<MappedError> {
+            match error {
+                PlexusError::ExecutionError(message) if message.starts_with("not found") => {
+                    Some(MappedError::tool(format!("{}: {}", tool, message)))
+                }
+                PlexusError::ExecutionError(message) if message.contains("quota") => {
+                    Some(MappedError::protocol(-32010, "Quota exceeded", Some(json!({ "tool": tool }))))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn call_errors_map_to_tool_results_or_protocol_errors() {
+        let error = PlexusError::ExecutionError("not found: notes.txt".into());
+        match FileErrors.map_call_error("files.read", &error) {
+            Some(MappedError::Tool(message)) => assert_eq!(message, "files.read: not found: notes.txt"),
+            other => panic!("expected a tool result, got {:?}", other),
+        }
+
+        let error = PlexusError::ExecutionError("disk quota reached".into());
+        match FileErrors.map_call_error("files.write", &error) {
+            Some(MappedError::Protocol(error)) => {
+                assert_eq!(error.code, ErrorCode(-32010));
+                assert_eq!(error.message, "Quota exceeded");
+                assert_eq!(error.data.unwrap()["tool"], "files.write");
+            }
+            other => panic!("expected a protocol error, got {:?}", other),
+        }
+
+        let error = PlexusError::InvalidParams("path is required".into());
+        assert!(FileErrors.map_call_error("files.read", &error).is_none());
+    }
+
+    /// Points callers of missing methods at `tools/list`, except for the retired one
+    struct MissingMethods;
+
+    impl ErrorMapper for MissingMethods {
+        fn map_call_error(&self, tool: &str, error: &PlexusError) -> Option<MappedError> {
+            match error {
+                PlexusError::MethodNotFound { .. } if tool == "test.retired" => {
+                    Some(MappedError::protocol(-32010, "Tool retired", Some(json!({ "tool": tool }))))
+                }
+                PlexusError::MethodNotFound { .. } => Some(MappedError::tool(format!("{} does not exist; list the tools first", tool))),
+                _ => None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn served_call_errors_are_mapped() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_error_mapper(Arc::new(MissingMethods));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        // Mapped to a result the model can read
+        let result = call(&client, "test.missing", json!({})).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(text(&result), "test.missing does not exist; list the tools first");
+
+        // Mapped to a specific protocol error
+        let Err(ServiceError::McpError(error)) = call(&client, "test.retired", json!({})).await else {
+            panic!("retired tool was answered");
+        };
+        assert_eq!(error.code, ErrorCode(-32010));
+        assert_eq!(error.message, "Tool retired");
+        assert_eq!(error.data, Some(json!({ "tool": "test.retired" })));
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn served_call_errors_keep_the_default_mapping_without_a_mapper() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let Err(ServiceError::McpError(error)) = call(&client, "test.missing", json!({})).await else {
+            panic!("missing tool was answered");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown method: test.missing");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[test]
+    fn stream_errors_keep_the_default_mapping_unless_overridden() {
+        let messages = vec!["disk full".to_string()];
+        assert!(FileErrors.map_stream_errors("files.write", &messages).is_none());
+    }
+}
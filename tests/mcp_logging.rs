@@ -0,0 +1,78 @@
+//! Tests for forwarding tracing events to MCP clients.
+//!
+//! Run with: cargo test --features mcp-logging --test mcp_logging
+
+mod common;
+
+#[cfg(feature = "mcp-logging")]
+mod mcp_logging_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use plexus_transport::{McpHttpConfig, McpLogForwarder};
+    use rmcp::model::{LoggingLevel, SetLevelRequestParam};
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+    use tracing_subscriber::prelude::*;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, Recorder};
+    use super::common::until;
+
+    /// Messages `recorder` got from the test hub's logger
+    fn hub_messages(recorder: &Recorder) -> Vec<String> {
+        recorder
+            .logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|log| log.logger.as_deref() == Some("test_hub"))
+            .map(|log| log.data["message"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn events_logged_during_a_call_reach_sessions_that_set_a_level() {
+        let forwarder = Arc::new(McpLogForwarder::new());
+        let subscriber = tracing_subscriber::registry().with(forwarder.layer());
+        tracing::subscriber::set_global_default(subscriber).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_log_forwarder(forwarder);
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (listening, listened) = connect(&url).await;
+        listening
+            .set_level(SetLevelRequestParam { level: LoggingLevel::Info })
+            .await
+            .unwrap();
+        let (quiet, quieted) = connect(&url).await;
+        quiet
+            .set_level(SetLevelRequestParam { level: LoggingLevel::Error })
+            .await
+            .unwrap();
+
+        call(&quiet, "test.log", json!({ "message": "Reindexed tables" })).await.unwrap();
+        until(|| !hub_messages(&listened).is_empty()).await;
+        assert_eq!(hub_messages(&listened), ["Reindexed tables"]);
+
+        // Below its level, the other session hears nothing
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(hub_messages(&quieted).is_empty());
+
+        listening.cancel().await.unwrap();
+        quiet.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[test]
+    fn events_without_sessions_are_dropped() {
+        let forwarder = Arc::new(McpLogForwarder::new().with_excluded_targets(["secrets"]));
+        let subscriber = tracing_subscriber::registry().with(forwarder.layer());
+
+        // Nothing is listening; recording must neither fail nor block
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "hub", count = 3, "Reindexed tables");
+            tracing::warn!(target: "secrets::vault", "Rotated key");
+        });
+    }
+}
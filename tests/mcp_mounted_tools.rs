@@ -0,0 +1,59 @@
+//! Tests for exposing the tools of several activations through one MCP endpoint.
+//!
+//! Run with: cargo test --test mcp_mounted_tools
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_mounted_tools_tests {
+    use plexus_transport::{McpHttpConfig, MountedActivation, ToolFilter, TransportServer};
+    use rmcp::model::ErrorCode;
+    use rmcp::ServiceError;
+    use serde_json::json;
+
+    use super::common::hub::{rpc_module, TestHub};
+    use super::common::mcp::{call, connect, text};
+
+    #[tokio::test]
+    async fn mounted_tools_are_listed_and_called_under_their_prefix() {
+        // The filter sees prefixed names
+        let filter = ToolFilter::new().exclude(["second.test.sleep"]);
+        let running = TransportServer::builder(TestHub::new(1), rpc_module)
+            .with_mount(MountedActivation::new("second", TestHub::new(2), rpc_module))
+            .with_mcp_http_config(McpHttpConfig::new(0).with_tool_filter(filter))
+            .build()
+            .await
+            .unwrap()
+            .serve_detached()
+            .await
+            .unwrap();
+        let url = format!("http://{}/mcp", running.mcp_http_addr.unwrap());
+        let (client, _) = connect(&url).await;
+
+        let names: Vec<_> = client
+            .list_all_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        assert!(names.iter().any(|name| name == "test.sleep"), "{:?}", names);
+        assert!(names.iter().any(|name| name == "second.test.generation"), "{:?}", names);
+        assert!(!names.iter().any(|name| name == "second.test.sleep"), "{:?}", names);
+
+        // Each name reaches its own activation
+        assert_eq!(text(&call(&client, "test.generation", json!({})).await.unwrap()), "1");
+        assert_eq!(text(&call(&client, "second.test.generation", json!({})).await.unwrap()), "2");
+        let result = call(&client, "second.test.echo", json!({ "message": "mounted" })).await.unwrap();
+        assert_eq!(text(&result), "mounted");
+
+        let Err(ServiceError::McpError(error)) = call(&client, "second.test.sleep", json!({ "millis": 1 })).await
+        else {
+            panic!("filtered mounted tool was called");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+
+        client.cancel().await.unwrap();
+        running.shutdown().await.unwrap();
+    }
+}
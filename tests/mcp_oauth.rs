@@ -0,0 +1,42 @@
+//! Tests for OAuth protected-resource metadata (MCP authorization spec).
+//!
+//! Run with: cargo test --features jwt-auth --test mcp_oauth
+
+#[cfg(feature = "jwt-auth")]
+mod oauth_tests {
+    use plexus_transport::mcp::oauth::OAuthResourceConfig;
+
+    fn config() -> OAuthResourceConfig {
+        OAuthResourceConfig::new(
+            "https://mcp.example.com/mcp",
+            "https://auth.example.com",
+            "https://auth.example.com/.well-known/jwks.json",
+        )
+    }
+
+    #[test]
+    fn metadata_url_inserts_well_known_before_path() {
+        assert_eq!(
+            config().metadata_url(),
+            "https://mcp.example.com/.well-known/oauth-protected-resource/mcp"
+        );
+    }
+
+    #[test]
+    fn challenge_points_at_metadata() {
+        assert_eq!(
+            config().challenge(),
+            "Bearer resource_metadata=\"https://mcp.example.com/.well-known/oauth-protected-resource/mcp\""
+        );
+    }
+
+    #[test]
+    fn metadata_lists_resource_and_authorization_servers() {
+        let metadata = config()
+            .with_scopes(vec!["tools:read".into()])
+            .metadata();
+        assert_eq!(metadata["resource"], "https://mcp.example.com/mcp");
+        assert_eq!(metadata["authorization_servers"][0], "https://auth.example.com");
+        assert_eq!(metadata["scopes_supported"][0], "tools:read");
+    }
+}
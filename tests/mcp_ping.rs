@@ -0,0 +1,61 @@
+//! Tests for pinging MCP sessions.
+//!
+//! Run with: cargo test --test mcp_ping
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_ping_tests {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use plexus_transport::McpHttpConfig;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, connect_with, serve, text, Recorder};
+    use super::common::until;
+
+    fn pinging() -> McpHttpConfig {
+        McpHttpConfig::new(0).with_ping_interval(Duration::from_millis(100))
+    }
+
+    #[tokio::test]
+    async fn answering_sessions_stay_open() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), pinging(), shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+
+        until(|| recorder.pings.load(Ordering::SeqCst) >= 3).await;
+        let result = call(&client, "test.echo", json!({ "message": "still here" })).await.unwrap();
+        assert_eq!(text(&result), "still here");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn sessions_that_stop_answering_are_closed() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), pinging(), shutdown.clone()).await;
+        let hanging = Recorder {
+            hangs: true,
+            ..Recorder::default()
+        };
+        let (client, recorder) = connect_with(&url, hanging).await;
+        until(|| recorder.pings.load(Ordering::SeqCst) >= 1).await;
+
+        // Closed within an interval of the unanswered ping
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while call(&client, "test.echo", json!({ "message": "anyone?" })).await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("session closed");
+
+        let _ = client.cancel().await;
+        shutdown.cancel();
+    }
+}
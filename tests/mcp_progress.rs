@@ -0,0 +1,56 @@
+//! Tests for progress notifications of MCP tool calls.
+//!
+//! Run with: cargo test --test mcp_progress
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_progress_tests {
+    use std::time::Duration;
+
+    use plexus_transport::McpHttpConfig;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+
+    #[tokio::test]
+    async fn progress_reported_by_a_tool_reaches_the_client() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+
+        let result = call(&client, "test.progress", json!({ "steps": 3 })).await.unwrap();
+        assert_eq!(text(&result), "3 steps");
+
+        let progress = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let progress = recorder.progress.lock().unwrap().clone();
+                if progress.len() >= 3 {
+                    return progress;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("three progress notifications");
+        let steps: Vec<_> = progress
+            .iter()
+            .map(|p| (p.progress, p.total, p.message.clone().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                (1.0, Some(3.0), "step 1".to_string()),
+                (2.0, Some(3.0), "step 2".to_string()),
+                (3.0, Some(3.0), "step 3".to_string()),
+            ]
+        );
+        // All for the one call
+        assert!(progress.iter().all(|p| p.progress_token == progress[0].progress_token));
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
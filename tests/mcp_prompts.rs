@@ -0,0 +1,112 @@
+//! Tests for MCP prompt providers.
+//!
+//! Run with: cargo test --test mcp_prompts
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_prompts_tests {
+    use std::sync::Arc;
+
+    use futures::future::BoxFuture;
+    use plexus_transport::{CallerIdentity, McpHttpConfig, PromptError, PromptProvider};
+    use rmcp::model::{
+        ErrorCode, GetPromptRequestParam, GetPromptResult, JsonObject, Prompt, PromptMessage, PromptMessageRole,
+    };
+    use rmcp::{ErrorData, ServiceError};
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{connect, serve};
+
+    /// Greets whoever is named in `name`
+    struct Greeting;
+
+    impl PromptProvider for Greeting {
+        fn list_prompts<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Prompt>, PromptError>> {
+            Box::pin(async { Ok(vec![Prompt::new("greet", Some("Say hello"), None)]) })
+        }
+
+        fn get_prompt<'a>(
+            &'a self,
+            name: &'a str,
+            arguments: &'a JsonObject,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<GetPromptResult, PromptError>> {
+            Box::pin(async move {
+                if name != "greet" {
+                    return Err(PromptError::NotFound(name.to_string()));
+                }
+                let who = arguments
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| PromptError::InvalidArguments("name is required".into()))?;
+                Ok(GetPromptResult {
+                    description: None,
+                    messages: vec![PromptMessage::new_text(PromptMessageRole::User, format!("Say hello to {}", who))],
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn providers_render_prompts_from_arguments() {
+        let caller = CallerIdentity::Anonymous;
+        let prompts = Greeting.list_prompts(&caller).await.unwrap();
+        assert_eq!(prompts[0].name, "greet");
+
+        let arguments = json!({ "name": "Ada" }).as_object().unwrap().clone();
+        let result = Greeting.get_prompt("greet", &arguments, &caller).await.unwrap();
+        let json = serde_json::to_value(&result.messages[0]).unwrap();
+        assert_eq!(json["content"]["text"], "Say hello to Ada");
+    }
+
+    #[tokio::test]
+    async fn served_prompts_are_listed_and_rendered() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_prompt_provider(Arc::new(Greeting));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let prompts = client.list_all_prompts().await.unwrap();
+        let names: Vec<_> = prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(names, ["greet"]);
+
+        let result = client
+            .get_prompt(GetPromptRequestParam {
+                name: "greet".into(),
+                arguments: json!({ "name": "Ada" }).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&result.messages[0]).unwrap();
+        assert_eq!(json["content"]["text"], "Say hello to Ada");
+
+        let Err(ServiceError::McpError(error)) = client
+            .get_prompt(GetPromptRequestParam { name: "greet".into(), arguments: None })
+            .await
+        else {
+            panic!("prompt rendered without its argument");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn prompt_errors_map_to_mcp_errors() {
+        let caller = CallerIdentity::Anonymous;
+        let error = Greeting.get_prompt("greet", &JsonObject::new(), &caller).await.unwrap_err();
+        assert_eq!(ErrorData::from(error).code, ErrorCode::INVALID_PARAMS);
+
+        let error = Greeting.get_prompt("farewell", &JsonObject::new(), &caller).await.unwrap_err();
+        let error = ErrorData::from(error);
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown prompt: farewell");
+
+        let error = ErrorData::from(PromptError::failed("template store is down"));
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+}
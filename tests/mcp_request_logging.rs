@@ -0,0 +1,62 @@
+//! Tests for logging MCP HTTP request bodies.
+//!
+//! Run with: cargo test --test mcp_request_logging
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_request_logging_tests {
+    use axum::body::{Body, Bytes};
+    use axum::http::{Request, StatusCode};
+    use plexus_transport::mcp::build_mcp_router;
+    use plexus_transport::{McpHttpConfig, RequestLogging};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::common::hub::TestHub;
+
+    /// An `initialize` request padded with whitespace to `chunks` chunks of
+    /// 600 bytes, sent without a length
+    fn chunked_post(chunks: usize) -> Request<Body> {
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0.0" },
+            },
+        });
+        let mut body = initialize.to_string().into_bytes();
+        body.resize(600 * chunks, b' ');
+        let chunks: Vec<_> = body
+            .chunks(600)
+            .map(|chunk| Ok::<_, std::io::Error>(Bytes::copy_from_slice(chunk)))
+            .collect();
+        Request::post("/")
+            .header("content-type", "application/json")
+            .header("accept", "application/json, text/event-stream")
+            .body(Body::from_stream(futures::stream::iter(chunks)))
+            .unwrap()
+    }
+
+    async fn logged_router() -> axum::Router {
+        let config = McpHttpConfig::new(0)
+            .with_max_body_size(1024)
+            .with_request_logging(RequestLogging::Full);
+        build_mcp_router(TestHub::new(1), None, None, config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn oversized_chunked_bodies_are_rejected_as_too_large() {
+        let response = logged_router().await.oneshot(chunked_post(2)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn bodies_within_the_limit_are_passed_on() {
+        let response = logged_router().await.oneshot(chunked_post(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,235 @@
+//! Tests for MCP resource providers.
+//!
+//! Run with: cargo test --test mcp_resources
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_resources_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::future::BoxFuture;
+    use futures::stream::{self, BoxStream};
+    use futures::StreamExt;
+    use plexus_transport::{CallerIdentity, McpHttpConfig, ResourceError, ResourceProvider};
+    use rmcp::model::{
+        AnnotateAble, ErrorCode, RawResource, ReadResourceRequestParam, Resource, ResourceContents,
+        SubscribeRequestParam, UnsubscribeRequestParam,
+    };
+    use rmcp::{ErrorData, ServiceError};
+    use tokio::sync::watch;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{connect, serve};
+    use super::common::until;
+
+    /// Serves one status document
+    struct Status;
+
+    impl ResourceProvider for Status {
+        fn list_resources<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Resource>, ResourceError>> {
+            Box::pin(async { Ok(vec![RawResource::new("hub://status", "status").no_annotation()]) })
+        }
+
+        fn read_resource<'a>(
+            &'a self,
+            uri: &'a str,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<Vec<ResourceContents>, ResourceError>> {
+            Box::pin(async move {
+                match uri {
+                    "hub://status" => Ok(vec![ResourceContents::text("ok", uri)]),
+                    _ => Err(ResourceError::NotFound(uri.to_string())),
+                }
+            })
+        }
+    }
+
+    /// Serves a counter that changes twice
+    struct Counter;
+
+    impl ResourceProvider for Counter {
+        fn list_resources<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Resource>, ResourceError>> {
+            Box::pin(async { Ok(vec![RawResource::new("hub://counter", "counter").no_annotation()]) })
+        }
+
+        fn read_resource<'a>(
+            &'a self,
+            uri: &'a str,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<Vec<ResourceContents>, ResourceError>> {
+            Box::pin(async move { Ok(vec![ResourceContents::text("2", uri)]) })
+        }
+
+        fn supports_subscriptions(&self) -> bool {
+            true
+        }
+
+        fn subscribe<'a>(
+            &'a self,
+            uri: &'a str,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<BoxStream<'static, ()>, ResourceError>> {
+            Box::pin(async move {
+                match uri {
+                    "hub://counter" => Ok(stream::iter([(), ()]).boxed()),
+                    _ => Err(ResourceError::NotFound(uri.to_string())),
+                }
+            })
+        }
+    }
+
+    /// Serves a feed that changes whenever the test sends to it
+    struct Feed(watch::Sender<u32>);
+
+    impl ResourceProvider for Feed {
+        fn list_resources<'a>(&'a self, _caller: &'a CallerIdentity) -> BoxFuture<'a, Result<Vec<Resource>, ResourceError>> {
+            Box::pin(async { Ok(vec![RawResource::new("hub://feed", "feed").no_annotation()]) })
+        }
+
+        fn read_resource<'a>(
+            &'a self,
+            uri: &'a str,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<Vec<ResourceContents>, ResourceError>> {
+            let value = *self.0.borrow();
+            Box::pin(async move { Ok(vec![ResourceContents::text(value.to_string(), uri)]) })
+        }
+
+        fn supports_subscriptions(&self) -> bool {
+            true
+        }
+
+        fn subscribe<'a>(
+            &'a self,
+            uri: &'a str,
+            _caller: &'a CallerIdentity,
+        ) -> BoxFuture<'a, Result<BoxStream<'static, ()>, ResourceError>> {
+            Box::pin(async move {
+                if uri != "hub://feed" {
+                    return Err(ResourceError::NotFound(uri.to_string()));
+                }
+                let changes = stream::unfold(self.0.subscribe(), |mut changes| async move {
+                    changes.changed().await.ok()?;
+                    Some(((), changes))
+                });
+                Ok(changes.boxed())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn providers_list_and_read_resources() {
+        let caller = CallerIdentity::Anonymous;
+
+        let resources = Status.list_resources(&caller).await.unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "hub://status");
+        assert!(Status.list_resource_templates(&caller).await.unwrap().is_empty());
+
+        let contents = Status.read_resource("hub://status", &caller).await.unwrap();
+        let json = serde_json::to_value(&contents[0]).unwrap();
+        assert_eq!(json["text"], "ok");
+    }
+
+    #[tokio::test]
+    async fn served_resources_are_listed_and_read() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_resource_provider(Arc::new(Status));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let resources = client.list_all_resources().await.unwrap();
+        let uris: Vec<_> = resources.iter().map(|resource| resource.uri.as_str()).collect();
+        assert_eq!(uris, ["hub://status"]);
+
+        let read = client
+            .read_resource(ReadResourceRequestParam { uri: "hub://status".into() })
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&read.contents).unwrap();
+        assert_eq!(json[0]["text"], "ok");
+
+        let Err(ServiceError::McpError(error)) = client
+            .read_resource(ReadResourceRequestParam { uri: "hub://missing".into() })
+            .await
+        else {
+            panic!("unknown resource was read");
+        };
+        assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn subscriptions_yield_once_per_change() {
+        let caller = CallerIdentity::Anonymous;
+        assert!(Counter.supports_subscriptions());
+
+        let updates = Counter.subscribe("hub://counter", &caller).await.unwrap();
+        assert_eq!(updates.count().await, 2);
+
+        let error = Counter.subscribe("hub://missing", &caller).await.err().unwrap();
+        assert!(matches!(error, ResourceError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn served_subscriptions_notify_the_session_until_it_unsubscribes() {
+        let feed = Arc::new(Feed(watch::channel(0).0));
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_resource_provider(feed.clone());
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+        let resources = client.peer_info().unwrap().capabilities.resources.clone().unwrap();
+        assert_eq!(resources.subscribe, Some(true));
+
+        client
+            .subscribe(SubscribeRequestParam { uri: "hub://feed".into() })
+            .await
+            .unwrap();
+        feed.0.send_replace(1);
+        until(|| recorder.resource_updates.lock().unwrap().len() == 1).await;
+        assert_eq!(*recorder.resource_updates.lock().unwrap(), ["hub://feed"]);
+
+        client
+            .unsubscribe(UnsubscribeRequestParam { uri: "hub://feed".into() })
+            .await
+            .unwrap();
+        feed.0.send_replace(2);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(recorder.resource_updates.lock().unwrap().len(), 1);
+
+        let Err(ServiceError::McpError(error)) = client
+            .subscribe(SubscribeRequestParam { uri: "hub://missing".into() })
+            .await
+        else {
+            panic!("subscribed to an unknown resource");
+        };
+        assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn subscriptions_are_unsupported_by_default() {
+        assert!(!Status.supports_subscriptions());
+        let error = Status.subscribe("hub://status", &CallerIdentity::Anonymous).await.err().unwrap();
+        assert_eq!(ErrorData::from(error).code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn unknown_resources_map_to_resource_not_found() {
+        let error = Status.read_resource("hub://missing", &CallerIdentity::Anonymous).await.unwrap_err();
+        let error = ErrorData::from(error);
+        assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+        assert_eq!(error.data.unwrap()["uri"], "hub://missing");
+
+        let error = ErrorData::from(ResourceError::failed("database is down"));
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(error.message, "database is down");
+    }
+}
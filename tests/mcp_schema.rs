@@ -0,0 +1,141 @@
+//! Tests for MCP tool input schema providers.
+//!
+//! Run with: cargo test --test mcp_schema
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_schema_tests {
+    use std::sync::Arc;
+
+    use plexus_transport::{McpHttpConfig, SchemaProvider};
+    use serde_json::{json, Value};
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+
+    #[test]
+    fn closures_provide_schemas_by_tool_name() {
+        let schemas = |tool: &str| (tool == "files.read").then(|| json!({ "type": "object", "required": ["path"] }));
+        let provider: Arc<dyn SchemaProvider> = Arc::new(schemas);
+
+        let schema: Value = provider.input_schema("files.read").unwrap();
+        assert_eq!(schema["required"][0], "path");
+        assert!(provider.input_schema("files.write").is_none());
+
+        let config = McpHttpConfig::new(0).with_schema_provider(provider);
+        assert!(config.schema_provider.is_some());
+    }
+
+    #[tokio::test]
+    async fn served_tools_advertise_normalized_input_schemas() {
+        let sleep = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": ["object", "null"],
+            "properties": { "millis": { "type": "integer", "minimum": 1 } },
+            "required": ["millis"],
+        });
+        let schemas = move |tool: &str| (tool == "test.sleep").then(|| sleep.clone());
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_schema_provider(Arc::new(schemas));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let tools = client.list_all_tools().await.unwrap();
+        let schema_of = |name: &str| {
+            let tool = tools.iter().find(|tool| tool.name == name).expect(name);
+            Value::Object((*tool.input_schema).clone())
+        };
+
+        // The provider's schema replaces the activation's
+        assert_eq!(
+            schema_of("test.sleep"),
+            json!({
+                "type": "object",
+                "properties": { "millis": { "type": "integer", "minimum": 1 } },
+                "required": ["millis"],
+            })
+        );
+
+        // Other tools keep the schema derived from the activation
+        let echo = schema_of("test.echo");
+        assert_eq!(echo["type"], "object");
+        assert!(echo["properties"]["message"].is_object(), "{}", echo);
+        assert!(echo.get("$schema").is_none());
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    /// Declares the output of `hub.status`
+    struct StatusSchemas;
+
+    impl SchemaProvider for StatusSchemas {
+        fn input_schema(&self, _tool: &str) -> Option<Value> {
+            None
+        }
+
+        fn output_schema(&self, tool: &str) -> Option<Value> {
+            (tool == "hub.status").then(|| json!({ "type": "object", "properties": { "uptime_secs": { "type": "integer" } } }))
+        }
+    }
+
+    /// Declares the output of `test.status`
+    struct TestSchemas;
+
+    impl SchemaProvider for TestSchemas {
+        fn input_schema(&self, _tool: &str) -> Option<Value> {
+            None
+        }
+
+        fn output_schema(&self, tool: &str) -> Option<Value> {
+            (tool == "test.status").then(|| {
+                json!({
+                    "type": "object",
+                    "properties": { "generation": { "type": "integer" }, "healthy": { "type": "boolean" } },
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn served_object_output_is_structured_content() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_schema_provider(Arc::new(TestSchemas));
+        let url = serve(TestHub::new(2), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let tools = client.list_all_tools().await.unwrap();
+        let output_schema_of = |name: &str| {
+            let tool = tools.iter().find(|tool| tool.name == name).expect(name);
+            tool.output_schema.as_ref().map(|schema| Value::Object((**schema).clone()))
+        };
+        assert_eq!(output_schema_of("test.status").unwrap()["properties"]["healthy"]["type"], "boolean");
+        assert!(output_schema_of("test.echo").is_none());
+
+        // The object comes back structured, and as text for older clients
+        let status = json!({ "generation": 2, "healthy": true });
+        let result = call(&client, "test.status", json!({})).await.unwrap();
+        assert_eq!(result.structured_content, Some(status.clone()));
+        assert_eq!(serde_json::from_str::<Value>(text(&result)).unwrap(), status);
+
+        // Strings stay unstructured
+        let result = call(&client, "test.echo", json!({ "message": "hi" })).await.unwrap();
+        assert_eq!(result.structured_content, None);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[test]
+    fn output_schemas_are_declared_per_tool() {
+        let schema = StatusSchemas.output_schema("hub.status").unwrap();
+        assert_eq!(schema["properties"]["uptime_secs"]["type"], "integer");
+        assert!(StatusSchemas.output_schema("hub.restart").is_none());
+
+        // Closures only provide input schemas
+        let schemas = |_: &str| Some(json!({ "type": "object" }));
+        assert!(schemas.output_schema("hub.status").is_none());
+    }
+}
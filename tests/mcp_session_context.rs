@@ -0,0 +1,48 @@
+//! Tests for the MCP session context of tool calls.
+//!
+//! Run with: cargo test --test mcp_session_context
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_session_context_tests {
+    use plexus_transport::{McpHttpConfig, SessionContext};
+    use serde_json::{json, Value};
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text, Client, CLIENT_NAME};
+
+    /// The session context `test.session` saw when `client` called it
+    async fn seen_session(client: &Client) -> Value {
+        let result = call(client, "test.session", json!({})).await.unwrap();
+        serde_json::from_str(text(&result)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn there_is_no_session_outside_mcp_calls() {
+        assert!(SessionContext::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn tool_calls_see_the_session_that_made_them() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (first, _) = connect(&url).await;
+        let (second, _) = connect(&url).await;
+
+        let seen = seen_session(&first).await;
+        assert_eq!(seen["client"], CLIENT_NAME);
+        let session_id = seen["session_id"].as_str().expect("a session id").to_string();
+
+        // The same session on every call, another for another client
+        assert_eq!(seen_session(&first).await["session_id"], session_id.as_str());
+        let other = seen_session(&second).await;
+        assert_eq!(other["client"], CLIENT_NAME);
+        assert_ne!(other["session_id"], session_id.as_str());
+
+        first.cancel().await.unwrap();
+        second.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,46 @@
+//! Tests for serving the MCP bridge over a reader/writer pair.
+//!
+//! Run with: cargo test --test mcp_stdio
+
+mod common;
+
+#[cfg(all(feature = "mcp-http", feature = "stdio"))]
+mod mcp_stdio_tests {
+    use plexus_transport::{serve_mcp_io, ActivationMcpBridge, StdioConfig};
+    use rmcp::ServiceExt;
+    use serde_json::{json, Value};
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, text, Recorder, CLIENT_NAME};
+    use super::common::until;
+
+    #[tokio::test]
+    async fn mcp_clients_list_and_call_tools_over_io() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let bridge = ActivationMcpBridge::new(TestHub::new(1));
+        let server = tokio::spawn(serve_mcp_io(bridge, server_read, server_write, StdioConfig::default()));
+
+        let recorder = Recorder::default();
+        let client = recorder.clone().serve(tokio::io::split(client_io)).await.unwrap();
+
+        let tools = client.list_all_tools().await.unwrap();
+        assert!(tools.iter().any(|tool| tool.name == "test.echo"));
+        let result = call(&client, "test.echo", json!({ "message": "over stdio" })).await.unwrap();
+        assert_eq!(text(&result), "over stdio");
+
+        // Notifications share the pipe with the responses
+        let result = call(&client, "test.progress", json!({ "steps": 2 })).await.unwrap();
+        assert_eq!(text(&result), "2 steps");
+        until(|| recorder.progress.lock().unwrap().len() == 2).await;
+
+        // The one session has no HTTP session id
+        let result = call(&client, "test.session", json!({})).await.unwrap();
+        let session: Value = serde_json::from_str(text(&result)).unwrap();
+        assert_eq!(session, json!({ "session_id": null, "client": CLIENT_NAME }));
+
+        // Hanging up ends the server
+        client.cancel().await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
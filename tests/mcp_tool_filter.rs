@@ -0,0 +1,89 @@
+//! Tests for MCP tool include/exclude filtering.
+//!
+//! Run with: cargo test --test mcp_tool_filter
+
+mod common;
+
+use plexus_transport::ToolFilter;
+
+#[test]
+fn empty_filter_exposes_everything() {
+    let filter = ToolFilter::new();
+    assert!(filter.allows("query.list"));
+    assert!(filter.allows("admin.reset"));
+}
+
+#[test]
+fn include_patterns_restrict_tools() {
+    let filter = ToolFilter::new().include(["query.*", "health.ping"]);
+    assert!(filter.allows("query.list"));
+    assert!(filter.allows("health.ping"));
+    assert!(!filter.allows("health.pingall"));
+    assert!(!filter.allows("admin.reset"));
+}
+
+#[test]
+fn exclude_wins_over_include() {
+    let filter = ToolFilter::new()
+        .include(["*"])
+        .exclude(["admin.*", "*.delete_*"]);
+    assert!(filter.allows("query.list"));
+    assert!(!filter.allows("admin.reset"));
+    assert!(!filter.allows("files.delete_all"));
+    assert!(filter.allows("files.undelete"));
+}
+
+#[test]
+fn globs_match_inner_wildcards() {
+    let filter = ToolFilter::new().include(["db.*_read*"]);
+    assert!(filter.allows("db.table_read"));
+    assert!(filter.allows("db.table_readonly"));
+    assert!(!filter.allows("db.table_write"));
+    assert!(!filter.allows("cache.table_read"));
+}
+
+#[cfg(feature = "mcp-http")]
+mod served_tests {
+    use plexus_transport::{McpHttpConfig, ToolFilter};
+    use rmcp::model::{ErrorCode, PaginatedRequestParam};
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve};
+
+    #[tokio::test]
+    async fn served_tools_are_filtered_and_paged() {
+        let filter = ToolFilter::new()
+            .include(["test.echo", "test.generation", "test.s*"])
+            .exclude(["test.sleep", "test.session"]);
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_tool_filter(filter).with_tools_page_size(2);
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let first = client.list_tools(None).await.unwrap();
+        assert_eq!(first.tools.len(), 2);
+        let cursor = first.next_cursor.clone().expect("a second page");
+        let second = client
+            .list_tools(Some(PaginatedRequestParam { cursor: Some(cursor) }))
+            .await
+            .unwrap();
+        assert_eq!(second.next_cursor, None);
+
+        let mut names: Vec<_> = first.tools.iter().chain(&second.tools).map(|tool| tool.name.to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["test.echo", "test.generation", "test.status"]);
+
+        // Filtered tools cannot be called either
+        let Err(ServiceError::McpError(error)) = call(&client, "test.sleep", json!({ "millis": 1 })).await else {
+            panic!("filtered tool was called");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown tool: test.sleep");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,87 @@
+//! Tests for MCP tool list change notifications.
+//!
+//! Run with: cargo test --test mcp_tool_list
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_tool_list_tests {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use plexus_core::plexus::Activation;
+    use plexus_transport::{McpHttpConfig, ToolListNotifier};
+    use serde_json::Value;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{connect, serve, Client};
+
+    /// The names of the tools `client` is offered, sorted
+    async fn tool_names(client: &Client) -> Vec<String> {
+        let mut names: Vec<_> = client
+            .list_all_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn served_tools_are_listed_with_their_schemas() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let names = tool_names(&client).await;
+        for tool in ["test.echo", "test.generation", "test.progress", "test.sleep"] {
+            assert!(names.iter().any(|name| name == tool), "{} missing from {:?}", tool, names);
+        }
+
+        let tools = client.list_all_tools().await.unwrap();
+        let echo = tools.iter().find(|tool| tool.name == "test.echo").unwrap();
+        assert_eq!(echo.description.as_deref(), Some("Echo a message back"));
+        let schema = Value::Object((*echo.input_schema).clone());
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["message"]["type"], "string");
+        assert_eq!(schema["required"], serde_json::json!(["message"]));
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn published_schemas_are_listed_after_a_change_notification() {
+        let hub = TestHub::new(1);
+        let mut echo_only = hub.plugin_schema();
+        echo_only.methods.retain(|method| method.name == "echo");
+
+        let notifier = ToolListNotifier::new();
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_tool_list_notifier(notifier.clone());
+        let url = serve(hub, config, shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+        let tools = client.peer_info().unwrap().capabilities.tools.clone().unwrap();
+        assert_eq!(tools.list_changed, Some(true));
+        assert!(tool_names(&client).await.len() > 1);
+
+        notifier.set_flat_schemas(vec![echo_only]);
+        // The session watches for changes once it is initialized, which may
+        // be just after the client's first requests
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while recorder.tool_list_changes.load(Ordering::SeqCst) == 0 {
+                notifier.notify();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("tools/list_changed");
+        assert_eq!(tool_names(&client).await, ["test.echo"]);
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
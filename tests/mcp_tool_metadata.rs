@@ -0,0 +1,95 @@
+//! Tests for MCP tool metadata providers.
+//!
+//! Run with: cargo test --test mcp_tool_metadata
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_tool_metadata_tests {
+    use std::sync::Arc;
+
+    use plexus_transport::{McpHttpConfig, ToolMetadata, ToolMetadataProvider};
+    use rmcp::model::ToolAnnotations;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{connect, serve};
+
+    fn metadata(tool: &str) -> Option<ToolMetadata> {
+        match tool {
+            "files.delete" => Some(
+                ToolMetadata::new()
+                    .with_title("Delete file")
+                    .with_annotations(ToolAnnotations::new().read_only(false).destructive(true)),
+            ),
+            "files.read" => Some(ToolMetadata::new().with_description("Read the file at `path`")),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn providers_describe_tools_by_name() {
+        let provider: Arc<dyn ToolMetadataProvider> = Arc::new(metadata);
+
+        let delete = provider.metadata("files.delete").unwrap();
+        assert_eq!(delete.title.as_deref(), Some("Delete file"));
+        assert!(delete.description.is_none());
+        let annotations = delete.annotations.unwrap();
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.read_only_hint, Some(false));
+
+        let read = provider.metadata("files.read").unwrap();
+        assert_eq!(read.description.as_deref(), Some("Read the file at `path`"));
+        assert!(read.annotations.is_none());
+
+        assert!(provider.metadata("files.list").is_none());
+    }
+
+    #[test]
+    fn annotations_serialize_as_mcp_hints() {
+        let annotations = ToolAnnotations::new().read_only(true).idempotent(true);
+        let json = serde_json::to_value(annotations).unwrap();
+        assert_eq!(json["readOnlyHint"], true);
+        assert_eq!(json["idempotentHint"], true);
+        assert!(json.get("destructiveHint").is_none());
+    }
+
+    #[tokio::test]
+    async fn served_tools_are_listed_with_provided_metadata() {
+        let described = |tool: &str| match tool {
+            "test.sleep" => Some(
+                ToolMetadata::new()
+                    .with_title("Sleep")
+                    .with_annotations(ToolAnnotations::new().read_only(true).idempotent(true)),
+            ),
+            "test.echo" => Some(ToolMetadata::new().with_description("Repeat `message` verbatim")),
+            _ => None,
+        };
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_tool_metadata_provider(Arc::new(described));
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let tools = client.list_all_tools().await.unwrap();
+        let tool = |name: &str| tools.iter().find(|tool| tool.name == name).expect(name);
+
+        let sleep = tool("test.sleep");
+        assert_eq!(sleep.title.as_deref(), Some("Sleep"));
+        assert_eq!(sleep.description.as_deref(), Some("Answer after a delay"));
+        let annotations = sleep.annotations.as_ref().unwrap();
+        assert_eq!(annotations.read_only_hint, Some(true));
+        assert_eq!(annotations.idempotent_hint, Some(true));
+
+        let echo = tool("test.echo");
+        assert_eq!(echo.description.as_deref(), Some("Repeat `message` verbatim"));
+        assert!(echo.title.is_none());
+        assert!(echo.annotations.is_none());
+
+        // Tools the provider knows nothing about keep their defaults
+        let generation = tool("test.generation");
+        assert_eq!(generation.description.as_deref(), Some("The generation of this instance"));
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,88 @@
+//! Tests for MCP tool naming strategies.
+//!
+//! Run with: cargo test --test mcp_tool_naming
+
+mod common;
+
+use plexus_transport::ToolNaming;
+
+#[test]
+fn default_naming_keeps_dotted_names() {
+    let naming = ToolNaming::new();
+    assert!(naming.is_identity());
+    assert_eq!(naming.tool_name("files.read"), "files.read");
+}
+
+#[test]
+fn separators_replace_every_dot() {
+    let naming = ToolNaming::new().with_separator("_");
+    assert!(!naming.is_identity());
+    assert_eq!(naming.tool_name("files.read"), "files_read");
+    assert_eq!(naming.tool_name("hub.files.read"), "hub_files_read");
+}
+
+#[test]
+fn prefixes_are_joined_with_the_separator() {
+    let naming = ToolNaming::new().with_separator("__").with_prefix("plexus");
+    assert_eq!(naming.tool_name("files.read"), "plexus__files__read");
+
+    let naming = ToolNaming::new().with_prefix("plexus");
+    assert_eq!(naming.tool_name("files.read"), "plexus.files.read");
+}
+
+#[test]
+fn mappings_replace_separator_and_prefix() {
+    let naming = ToolNaming::new()
+        .with_prefix("ignored")
+        .with_mapping(|name| name.replace('.', "-").to_uppercase());
+    assert_eq!(naming.tool_name("files.read"), "FILES-READ");
+}
+
+#[cfg(feature = "mcp-http")]
+mod served_tests {
+    use plexus_transport::{McpHttpConfig, ToolFilter, ToolNaming};
+    use rmcp::model::ErrorCode;
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+
+    #[tokio::test]
+    async fn served_tools_are_listed_and_called_by_their_mapped_names() {
+        let naming = ToolNaming::new().with_separator("__").with_prefix("plexus");
+        // Filters keep seeing the dotted names
+        let filter = ToolFilter::new().include(["test.echo", "test.generation"]);
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_tool_naming(naming).with_tool_filter(filter);
+        let url = serve(TestHub::new(3), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let mut names: Vec<_> = client
+            .list_all_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["plexus__test__echo", "plexus__test__generation"]);
+
+        let result = call(&client, "plexus__test__echo", json!({ "message": "mapped" })).await.unwrap();
+        assert_eq!(text(&result), "mapped");
+        let result = call(&client, "plexus__test__generation", json!({})).await.unwrap();
+        assert_eq!(text(&result), "3");
+
+        // The dotted names are no longer tools
+        let Err(ServiceError::McpError(error)) = call(&client, "test.echo", json!({ "message": "dotted" })).await
+        else {
+            panic!("dotted name was called");
+        };
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown tool: test.echo");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,64 @@
+//! Tests for streaming and capping MCP tool output.
+//!
+//! Run with: cargo test --test mcp_tool_output
+
+mod common;
+
+#[cfg(feature = "mcp-http")]
+mod mcp_tool_output_tests {
+    use plexus_transport::McpHttpConfig;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+    use super::common::until;
+
+    #[tokio::test]
+    async fn partial_results_stream_each_item_as_progress() {
+        let shutdown = CancellationToken::new();
+        let config = McpHttpConfig::new(0).with_partial_results().with_max_result_bytes(14);
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+
+        let result = call(&client, "test.lines", json!({ "count": 4 })).await.unwrap();
+        // Only the most recent output fits in the result
+        assert_eq!(text(&result), "[14 bytes of earlier output omitted]\nline 3\nline 4\n");
+
+        // All of it was streamed while the call ran
+        until(|| recorder.progress.lock().unwrap().len() == 4).await;
+        let streamed: Vec<_> = recorder
+            .progress
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| (p.progress, p.total, p.message.clone().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            streamed,
+            [
+                (1.0, None, "line 1\n".to_string()),
+                (2.0, None, "line 2\n".to_string()),
+                (3.0, None, "line 3\n".to_string()),
+                (4.0, None, "line 4\n".to_string()),
+            ]
+        );
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn without_partial_results_output_arrives_whole() {
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), McpHttpConfig::new(0), shutdown.clone()).await;
+        let (client, recorder) = connect(&url).await;
+
+        let result = call(&client, "test.lines", json!({ "count": 3 })).await.unwrap();
+        assert_eq!(text(&result), "line 1\nline 2\nline 3\n");
+        assert!(recorder.progress.lock().unwrap().is_empty());
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
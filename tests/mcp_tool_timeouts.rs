@@ -0,0 +1,94 @@
+//! Tests for MCP tool call time limits.
+//!
+//! Run with: cargo test --test mcp_tool_timeouts
+
+mod common;
+
+use std::time::Duration;
+
+use plexus_transport::ToolTimeouts;
+
+#[test]
+fn no_limits_by_default() {
+    assert_eq!(ToolTimeouts::new().timeout_for("files.read"), None);
+}
+
+#[test]
+fn tools_without_a_limit_get_the_default() {
+    let timeouts = ToolTimeouts::new()
+        .with_default(Duration::from_secs(30))
+        .with_tool("reports.generate", Duration::from_secs(300));
+    assert_eq!(timeouts.timeout_for("reports.generate"), Some(Duration::from_secs(300)));
+    assert_eq!(timeouts.timeout_for("files.read"), Some(Duration::from_secs(30)));
+}
+
+#[test]
+fn the_longest_matching_pattern_wins() {
+    let timeouts = ToolTimeouts::new()
+        .with_tool("reports.*", Duration::from_secs(120))
+        .with_tool("reports.export_*", Duration::from_secs(600))
+        .with_tool("*", Duration::from_secs(10));
+    assert_eq!(timeouts.timeout_for("reports.list"), Some(Duration::from_secs(120)));
+    assert_eq!(timeouts.timeout_for("reports.export_pdf"), Some(Duration::from_secs(600)));
+    assert_eq!(timeouts.timeout_for("files.read"), Some(Duration::from_secs(10)));
+}
+
+#[test]
+fn limits_round_trip_as_seconds() {
+    let timeouts = ToolTimeouts::new()
+        .with_default(Duration::from_millis(1500))
+        .with_tool("reports.*", Duration::from_secs(120));
+    let json = serde_json::to_value(&timeouts).unwrap();
+    assert_eq!(json["default"], 1.5);
+    assert_eq!(json["tools"]["reports.*"], 120.0);
+
+    let back: ToolTimeouts = serde_json::from_value(json).unwrap();
+    assert_eq!(back.timeout_for("reports.list"), Some(Duration::from_secs(120)));
+    assert_eq!(back.timeout_for("files.read"), Some(Duration::from_millis(1500)));
+}
+
+#[cfg(feature = "mcp-http")]
+mod served_tests {
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
+
+    use plexus_transport::{McpHttpConfig, ToolTimeouts, TIMEOUT_ERROR_CODE};
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+
+    #[tokio::test]
+    async fn slow_tool_calls_fail_with_a_timeout_error_at_their_limit() {
+        let hub = TestHub::new(1);
+        let sleeps = hub.sleeps.clone();
+        let timeouts = ToolTimeouts::new()
+            .with_default(Duration::from_secs(30))
+            .with_tool("test.sleep", Duration::from_millis(200));
+        let shutdown = CancellationToken::new();
+        let url = serve(hub, McpHttpConfig::new(0).with_tool_timeouts(timeouts), shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let started = Instant::now();
+        let Err(ServiceError::McpError(error)) = call(&client, "test.sleep", json!({ "millis": 60_000 })).await else {
+            panic!("slow call was answered");
+        };
+        assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+        assert_eq!(error.code.0, TIMEOUT_ERROR_CODE);
+        assert_eq!(error.message, "Tool call timed out after 0.2s: test.sleep");
+        assert_eq!(error.data, Some(json!({ "timeout_ms": 200 })));
+
+        // The call was dropped, not left running
+        assert_eq!(sleeps.started.load(Ordering::SeqCst), 1);
+        assert_eq!(sleeps.dropped.load(Ordering::SeqCst), 1);
+
+        // Calls within the limit are answered
+        let result = call(&client, "test.sleep", json!({ "millis": 10 })).await.unwrap();
+        assert_eq!(text(&result), "done");
+
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,157 @@
+//! Tests for the Prometheus metrics.
+//!
+//! Run with: cargo test --features metrics --test metrics
+
+#[cfg(feature = "metrics")]
+mod metrics_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use jsonrpsee::RpcModule;
+    use plexus_transport::config::StdioConfig;
+    use plexus_transport::metrics::{metrics_router, MetricsConfig, TransportMetrics};
+    use plexus_transport::stdio::serve_io;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tower::ServiceExt;
+
+    fn module() -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("echo", |params, _, _| params.one::<String>().unwrap_or_default())
+            .unwrap();
+        module.register_method("ping", |_, _, _| "pong").unwrap();
+        module
+    }
+
+    /// Send `requests` over stdio with `metrics` attached, reading one response each
+    async fn call_stdio(metrics: Arc<TransportMetrics>, requests: &[&str]) {
+        let (mut client_in, server_in) = tokio::io::duplex(4096);
+        let (server_out, client_out) = tokio::io::duplex(4096);
+        let config = StdioConfig::default().with_metrics(metrics);
+        let server = tokio::spawn(serve_io(module(), server_in, server_out, config));
+
+        let mut lines = BufReader::new(client_out).lines();
+        for request in requests {
+            client_in.write_all(request.as_bytes()).await.unwrap();
+            client_in.write_all(b"\n").await.unwrap();
+            lines.next_line().await.unwrap().expect("response line");
+        }
+        drop(client_in);
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn stdio_calls_are_counted_by_method_and_status() {
+        let metrics = Arc::new(TransportMetrics::new());
+        call_stdio(
+            metrics.clone(),
+            &[
+                r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":["a"]}"#,
+                r#"{"jsonrpc":"2.0","id":2,"method":"echo","params":["b"]}"#,
+                r#"{"jsonrpc":"2.0","id":3,"method":"no_such_method"}"#,
+            ],
+        )
+        .await;
+
+        let text = metrics.encode();
+        assert!(text.contains(r#"plexus_requests_total{method="echo",status="ok",transport="stdio"} 2"#));
+        assert!(text.contains(r#"plexus_requests_total{method="unknown",status="error",transport="stdio"} 1"#));
+        assert!(!text.contains("no_such_method"));
+        assert!(text.contains(r#"plexus_request_duration_seconds_count{method="echo",transport="stdio"} 2"#));
+    }
+
+    #[tokio::test]
+    async fn methods_beyond_the_limit_are_grouped() {
+        let metrics = Arc::new(TransportMetrics::new().with_max_methods(1));
+        call_stdio(
+            metrics.clone(),
+            &[
+                r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":["a"]}"#,
+                r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#,
+                r#"{"jsonrpc":"2.0","id":3,"method":"echo","params":["b"]}"#,
+            ],
+        )
+        .await;
+
+        let text = metrics.encode();
+        assert!(text.contains(r#"plexus_requests_total{method="echo",status="ok",transport="stdio"} 2"#));
+        assert!(text.contains(r#"plexus_requests_total{method="other",status="ok",transport="stdio"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn slow_calls_are_counted() {
+        let metrics = Arc::new(TransportMetrics::new());
+        let mut module = module();
+        module
+            .register_async_method("sleep", |_, _, _| async {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                "done"
+            })
+            .unwrap();
+        let config = StdioConfig::default()
+            .with_metrics(metrics.clone())
+            .with_slow_request_threshold(Duration::from_millis(10));
+
+        let (mut client_in, server_in) = tokio::io::duplex(4096);
+        let (server_out, client_out) = tokio::io::duplex(4096);
+        let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+        let mut lines = BufReader::new(client_out).lines();
+        for request in [
+            r#"{"jsonrpc":"2.0","id":1,"method":"sleep"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#,
+        ] {
+            client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+            lines.next_line().await.unwrap().expect("response line");
+        }
+        drop(client_in);
+        server.await.unwrap().unwrap();
+
+        let text = metrics.encode();
+        assert!(text.contains(r#"plexus_slow_requests_total{method="sleep",transport="stdio"} 1"#));
+        assert!(!text.contains(r#"plexus_slow_requests_total{method="ping""#));
+    }
+
+    #[test]
+    fn metrics_can_share_an_application_registry() {
+        let registry = prometheus::Registry::new();
+        let metrics = TransportMetrics::with_registry(registry.clone()).unwrap();
+        assert!(metrics.encode().contains("plexus_active_mcp_sessions 0"));
+        assert!(!registry.gather().is_empty());
+
+        // The names are taken now
+        assert!(TransportMetrics::with_registry(registry).is_err());
+    }
+
+    #[tokio::test]
+    async fn router_serves_the_text_format() {
+        let metrics = Arc::new(TransportMetrics::new());
+        let router = metrics_router(metrics, "/custom-metrics");
+
+        let response = router
+            .clone()
+            .oneshot(http::Request::get("/custom-metrics").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(response.headers()[http::header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains("plexus_active_mcp_sessions"));
+
+        let response = router
+            .oneshot(http::Request::get("/metrics").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn endpoint_config_defaults() {
+        let config: MetricsConfig = serde_json::from_str(r#"{ "addr": "0.0.0.0:9100" }"#).unwrap();
+        assert_eq!(config.addr.port(), 9100);
+        assert_eq!(config.path, "/metrics");
+        assert_eq!(MetricsConfig::new(9100).with_path("/m").path, "/m");
+    }
+}
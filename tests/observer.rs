@@ -0,0 +1,128 @@
+//! Tests for the connection lifecycle hooks.
+//!
+//! Run with: cargo test --test observer
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::{StdioConfig, WebSocketConfig};
+use plexus_transport::stdio::serve_io;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::{ConnectionInfo, TransportObserver};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+mod common;
+use common::{free_port, post};
+
+/// Records the hooks it is called with
+#[derive(Default)]
+struct Recorder(Mutex<Vec<(&'static str, ConnectionInfo)>>);
+
+impl TransportObserver for Recorder {
+    fn on_connect(&self, connection: &ConnectionInfo) {
+        self.0.lock().unwrap().push(("connect", connection.clone()));
+    }
+
+    fn on_disconnect(&self, connection: &ConnectionInfo) {
+        self.0.lock().unwrap().push(("disconnect", connection.clone()));
+    }
+}
+
+impl Recorder {
+    fn events(&self) -> Vec<(&'static str, ConnectionInfo)> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    module
+        .register_method("test.connection", |_, _, ext| ext.get::<ConnectionInfo>().map(|c| c.id))
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn stdio_is_one_connection_until_eof() {
+    let recorder = Arc::new(Recorder::default());
+    let config = StdioConfig::default().with_observer(recorder.clone());
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module(), server_in, server_out, config));
+
+    let mut lines = BufReader::new(client_out).lines();
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.ping\"}\n")
+        .await
+        .unwrap();
+    lines.next_line().await.unwrap().expect("response line");
+
+    let events = recorder.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "connect");
+    assert_eq!((events[0].1.transport, events[0].1.peer), ("stdio", None));
+
+    drop(client_in);
+    server.await.unwrap().unwrap();
+
+    let events = recorder.events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1], ("disconnect", events[0].1.clone()));
+}
+
+const REQUEST: &str = "POST / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: 45\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.ping\"}";
+
+#[tokio::test]
+async fn websocket_reports_each_connection_with_its_peer() {
+    let recorder = Arc::new(Recorder::default());
+    let port = free_port();
+    let _server = serve_websocket(module(), WebSocketConfig::new(port).with_observer(recorder.clone()), None)
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let local = stream.local_addr().unwrap();
+        stream.write_all(REQUEST.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 200"));
+
+        // The server notices the close on its own task
+        let mut events = recorder.events();
+        for _ in 0..50 {
+            if events.len() == ids.len() * 2 + 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            events = recorder.events();
+        }
+        let (connect, disconnect) = (&events[events.len() - 2], &events[events.len() - 1]);
+        assert_eq!(connect.0, "connect");
+        assert_eq!((connect.1.transport, connect.1.peer), ("websocket", Some(local)));
+        assert_eq!(disconnect, &("disconnect", connect.1.clone()));
+        ids.push(connect.1.id);
+    }
+    assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn websocket_calls_carry_their_connection() {
+    let recorder = Arc::new(Recorder::default());
+    let port = free_port();
+    let _server = serve_websocket(module(), WebSocketConfig::new(port).with_observer(recorder.clone()), None)
+        .await
+        .unwrap();
+
+    let call = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "test.connection" });
+    let response = post(port, &call).await;
+
+    let events = recorder.events();
+    assert_eq!(events[0].0, "connect");
+    assert_eq!(response["result"], events[0].1.id);
+}
@@ -0,0 +1,107 @@
+//! Tests for Origin and Host validation (DNS-rebinding protection) on the WebSocket server.
+//!
+//! Run with: cargo test --test origin_check
+
+use jsonrpsee::server::ServerHandle;
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::OriginCheck;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+mod common;
+use common::free_port;
+
+/// Start a server; it stops when the returned handle is dropped
+async fn start(config: WebSocketConfig) -> ServerHandle {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    serve_websocket(module, config, None).await.unwrap()
+}
+
+/// POST a JSON-RPC call and return the HTTP status line
+async fn post(port: u16, origin: Option<&str>) -> String {
+    post_to(port, &format!("127.0.0.1:{port}"), origin).await
+}
+
+/// POST a JSON-RPC call with `host` as the `Host` header and return the HTTP status line
+async fn post_to(port: u16, host: &str, origin: Option<&str>) -> String {
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"test.ping"}"#;
+    let origin = origin
+        .map(|o| format!("Origin: {}\r\n", o))
+        .unwrap_or_default();
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {host}\r\n{origin}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[tokio::test]
+async fn loopback_bind_only_accepts_localhost_origins() {
+    let port = free_port();
+    let _server = start(WebSocketConfig::new(port)).await;
+
+    assert!(post(port, None).await.contains("200"));
+    assert!(post(port, Some("http://localhost:3000")).await.contains("200"));
+    assert!(post(port, Some("http://127.0.0.1:5173")).await.contains("200"));
+    assert!(post(port, Some("http://evil.example")).await.contains("403"));
+}
+
+#[tokio::test]
+async fn wildcard_bind_only_accepts_localhost_origins() {
+    let port = free_port();
+    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+    let _server = start(WebSocketConfig::new(port).with_addr(addr)).await;
+
+    // Localhost browsers reach a wildcard bind too
+    assert!(post(port, None).await.contains("200"));
+    assert!(post(port, Some("http://localhost:3000")).await.contains("200"));
+    assert!(post(port, Some("http://evil.example")).await.contains("403"));
+}
+
+#[tokio::test]
+async fn application_allowlist_is_accepted() {
+    plexus_transport::init_allowed_origins(vec!["https://console.example.com".to_string()]);
+    let port = free_port();
+    let _server = start(WebSocketConfig::new(port)).await;
+
+    assert!(post(port, Some("https://console.example.com")).await.contains("200"));
+    assert!(post(port, Some("https://console.example.com/")).await.contains("200"));
+    assert!(post(port, Some("http://evil.example")).await.contains("403"));
+}
+
+#[tokio::test]
+async fn allowed_origins_extend_localhost() {
+    let port = free_port();
+    let _server = start(WebSocketConfig::new(port).with_allowed_origins(["https://app.example.com"])).await;
+
+    assert!(post(port, Some("https://app.example.com")).await.contains("200"));
+    assert!(post(port, Some("http://localhost")).await.contains("200"));
+    assert!(post(port, Some("https://other.example.com")).await.contains("403"));
+}
+
+#[tokio::test]
+async fn disabled_check_accepts_any_origin() {
+    let port = free_port();
+    let _server = start(WebSocketConfig::new(port).with_origin_check(OriginCheck::Disabled)).await;
+
+    assert!(post(port, Some("http://evil.example")).await.contains("200"));
+}
+
+#[tokio::test]
+async fn allowed_hosts_reject_other_hosts() {
+    let port = free_port();
+    let _server = start(WebSocketConfig::new(port).with_allowed_hosts(["localhost:*", "127.0.0.1:*"])).await;
+
+    assert!(post_to(port, &format!("127.0.0.1:{port}"), None).await.contains("200"));
+    assert!(post_to(port, &format!("localhost:{port}"), None).await.contains("200"));
+    // A rebinding page's requests carry its own host name, even without an Origin
+    assert!(post_to(port, &format!("evil.example:{port}"), None).await.contains("403"));
+    assert!(post_to(port, "evil.example", Some("http://localhost")).await.contains("403"));
+}
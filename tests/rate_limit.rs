@@ -0,0 +1,152 @@
+//! Tests for the per-client token bucket rate limiter.
+//!
+//! Run with: cargo test --test rate_limit
+
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
+use plexus_transport::rate_limit::{RateLimiter, MAX_TRACKED_CLIENTS};
+use plexus_transport::{ApiKeyIdentity, RateLimitConfig};
+
+mod common;
+
+#[test]
+fn burst_then_reject() {
+    let limiter = RateLimiter::new(RateLimitConfig::per_ip(0.001, 3));
+    assert!(limiter.check("ip:10.0.0.1"));
+    assert!(limiter.check("ip:10.0.0.1"));
+    assert!(limiter.check("ip:10.0.0.1"));
+    assert!(!limiter.check("ip:10.0.0.1"));
+
+    // Other clients have their own bucket
+    assert!(limiter.check("ip:10.0.0.2"));
+}
+
+#[test]
+fn tokens_refill_over_time() {
+    let limiter = RateLimiter::new(RateLimitConfig::per_ip(50.0, 1));
+    assert!(limiter.check("k"));
+    assert!(!limiter.check("k"));
+    std::thread::sleep(std::time::Duration::from_millis(40));
+    assert!(limiter.check("k"));
+}
+
+fn extensions() -> http::Extensions {
+    let mut ext = http::Extensions::new();
+    let addr: SocketAddr = "192.0.2.7:5555".parse().unwrap();
+    ext.insert(ConnectInfo(addr));
+    ext
+}
+
+#[test]
+fn keys_by_ip_identity_or_session() {
+    let mut ext = extensions();
+
+    let per_ip = RateLimiter::new(RateLimitConfig::per_ip(1.0, 1));
+    assert_eq!(per_ip.key_for(&ext, None), "ip:192.0.2.7");
+
+    // Unauthenticated callers fall back to their IP
+    let per_key = RateLimiter::new(RateLimitConfig::per_api_key(1.0, 1));
+    assert_eq!(per_key.key_for(&ext, None), "ip:192.0.2.7");
+    ext.insert(ApiKeyIdentity("reader".into()));
+    assert_eq!(per_key.key_for(&ext, None), "id:reader");
+
+    let per_session = RateLimiter::new(RateLimitConfig::per_session(1.0, 1));
+    assert_eq!(per_session.key_for(&ext, Some("abc".into())), "session:abc");
+    assert_eq!(per_session.key_for(&ext, None), "ip:192.0.2.7");
+}
+
+#[test]
+fn eviction_keeps_recently_limited_clients() {
+    let limiter = RateLimiter::new(RateLimitConfig::per_ip(0.001, 1));
+    for i in 0..MAX_TRACKED_CLIENTS {
+        assert!(limiter.check(&format!("ip:old-{}", i)));
+    }
+    assert!(limiter.check("ip:recent"));
+    assert!(!limiter.check("ip:recent"));
+
+    // New clients push the idlest buckets out, not the one just used
+    for i in 0..MAX_TRACKED_CLIENTS / 2 {
+        assert!(limiter.check(&format!("ip:new-{}", i)));
+    }
+    assert!(!limiter.check("ip:recent"));
+}
+
+#[test]
+fn peeking_takes_no_token() {
+    let limiter = RateLimiter::new(RateLimitConfig::per_ip(0.001, 1));
+    assert!(limiter.has_token("ip:10.0.0.1"));
+    assert!(limiter.has_token("ip:10.0.0.1"));
+    assert!(limiter.check("ip:10.0.0.1"));
+    assert!(!limiter.has_token("ip:10.0.0.1"));
+}
+
+#[cfg(feature = "mcp-http")]
+mod failed_authentication_tests {
+    use std::time::Duration;
+
+    use plexus_transport::mcp::server::serve_mcp_http_with_shutdown;
+    use plexus_transport::{McpHttpConfig, RateLimitConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+
+    /// The status of an MCP `ping` sent with `key`
+    async fn status(addr: std::net::SocketAddr, key: &str) -> u16 {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nAccept: application/json, text/event-stream\r\nContent-Length: {}\r\n\r\n{}",
+            key,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response[9..12].parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn guessed_keys_are_limited_by_ip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Authenticated callers get their own bucket
+        let config = McpHttpConfig::new(0).with_rate_limit(RateLimitConfig::per_api_key(0.001, 2));
+        let shutdown = CancellationToken::new();
+        let key = Some("secret".to_string());
+        serve_mcp_http_with_shutdown(TestHub::new(1), None, None, config, key, listener, shutdown.clone(), Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert_eq!(status(addr, "guess-1").await, 401);
+        assert_eq!(status(addr, "guess-2").await, 401);
+        assert_eq!(status(addr, "guess-3").await, 429);
+        // Out of tokens, the IP is turned away before its key is checked
+        assert_eq!(status(addr, "secret").await, 429);
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn authenticated_requests_do_not_count_as_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = McpHttpConfig::new(0).with_rate_limit(RateLimitConfig::per_api_key(0.001, 2));
+        let shutdown = CancellationToken::new();
+        let key = Some("secret".to_string());
+        serve_mcp_http_with_shutdown(TestHub::new(1), None, None, config, key, listener, shutdown.clone(), Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert_eq!(status(addr, "guess-1").await, 401);
+        for _ in 0..2 {
+            let status = status(addr, "secret").await;
+            assert!(status != 401 && status != 429, "{}", status);
+        }
+        assert_eq!(status(addr, "guess-2").await, 401);
+        assert_eq!(status(addr, "guess-3").await, 429);
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests for masking sensitive data in request logs.
+//!
+//! Run with: cargo test --test redaction
+
+use http::{HeaderName, HeaderValue};
+use plexus_transport::redact::REDACTED;
+use plexus_transport::Redaction;
+
+#[test]
+fn credentials_headers_are_masked_by_default() {
+    let redaction = Redaction::default();
+    let secret = HeaderValue::from_static("Bearer sk-live-123");
+
+    assert_eq!(redaction.header_value(&http::header::AUTHORIZATION, &secret), REDACTED);
+    assert_eq!(
+        redaction.header_value(&HeaderName::from_static("x-api-key"), &secret),
+        REDACTED
+    );
+    assert_eq!(
+        redaction.header_value(&http::header::ACCEPT, &HeaderValue::from_static("text/event-stream")),
+        "\"text/event-stream\""
+    );
+}
+
+#[test]
+fn custom_headers_match_case_insensitively() {
+    let redaction = Redaction::none().with_headers(["X-Internal-Token"]);
+    let value = HeaderValue::from_static("abc");
+
+    assert_eq!(
+        redaction.header_value(&HeaderName::from_static("x-internal-token"), &value),
+        REDACTED
+    );
+    assert_ne!(redaction.header_value(&http::header::AUTHORIZATION, &value), REDACTED);
+}
+
+#[test]
+fn nested_body_fields_are_masked() {
+    let redaction = Redaction::default().with_body_fields(["ssn"]);
+    let body = br#"{"jsonrpc":"2.0","method":"tools/call","params":{"arguments":{"user":"bob","Password":"hunter2","items":[{"ssn":"123"}]}}}"#;
+
+    let logged = redaction.body(body);
+    assert!(!logged.contains("hunter2"));
+    assert!(!logged.contains("123"));
+    assert!(logged.contains("bob"));
+}
+
+#[test]
+fn non_json_bodies_are_summarised() {
+    assert_eq!(Redaction::default().body(b"password=hunter2"), "<16 bytes>");
+}
@@ -0,0 +1,71 @@
+//! Tests for Redis-backed MCP sessions.
+//!
+//! These need a Redis server, at `PLEXUS_TEST_REDIS_URL` or else
+//! `redis://127.0.0.1:6379`.
+//!
+//! Run with: cargo test --features redis-sessions --test redis_sessions -- --ignored
+
+#[cfg(feature = "redis-sessions")]
+mod redis_session_tests {
+    use std::time::Duration;
+
+    use plexus_transport::mcp::{RedisSessionConfig, RedisSessionManager};
+    use redis::AsyncCommands;
+    use rmcp::transport::streamable_http_server::session::SessionManager;
+
+    fn url() -> String {
+        std::env::var("PLEXUS_TEST_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string())
+    }
+
+    /// A replica's session manager; replicas sharing `key_prefix` share sessions
+    async fn replica(key_prefix: &str, ttl: Duration) -> RedisSessionManager {
+        RedisSessionManager::new(RedisSessionConfig {
+            url: url(),
+            key_prefix: key_prefix.to_string(),
+            ttl,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn key_exists(key: &str) -> bool {
+        let client = redis::Client::open(url()).unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        conn.exists(key).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn sessions_are_shared_but_served_by_their_replica() {
+        let prefix = format!("plexus:test:{}:", uuid::Uuid::new_v4());
+        let first = replica(&prefix, Duration::from_secs(60)).await;
+        let second = replica(&prefix, Duration::from_secs(60)).await;
+
+        let (id, _transport) = first.create_session().await.unwrap();
+        let key = format!("{}{}", prefix, id);
+        assert!(key_exists(&key).await);
+        assert!(first.has_session(&id).await.unwrap());
+
+        // The other replica has no worker for it, but leaves the key alone
+        assert!(!second.has_session(&id).await.unwrap());
+        assert!(key_exists(&key).await);
+
+        first.close_session(&id).await.unwrap();
+        assert!(!key_exists(&key).await);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server"]
+    async fn idle_sessions_expire() {
+        let prefix = format!("plexus:test:{}:", uuid::Uuid::new_v4());
+        let manager = replica(&prefix, Duration::from_secs(1)).await;
+
+        let (id, _transport) = manager.create_session().await.unwrap();
+        let key = format!("{}{}", prefix, id);
+        assert!(key_exists(&key).await);
+
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert!(!key_exists(&key).await);
+    }
+}
@@ -0,0 +1,112 @@
+//! Tests for per-method request time limits.
+//!
+//! Run with: cargo test --test request_timeouts
+
+use std::time::Duration;
+
+use jsonrpsee::types::error::TOO_BIG_BATCH_RESPONSE_CODE;
+use jsonrpsee::RpcModule;
+use plexus_transport::config::{StdioConfig, WebSocketConfig};
+use plexus_transport::stdio::serve_io;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::{ToolTimeouts, TIMEOUT_ERROR_CODE};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+mod common;
+use common::{free_port, post};
+
+/// `test.sleep` and `test.wait` both sleep for the given milliseconds
+fn sleeper() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    for name in ["test.sleep", "test.wait"] {
+        module
+            .register_async_method(name, |params, _, _| async move {
+                let millis: u64 = params.one().unwrap();
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                "done"
+            })
+            .unwrap();
+    }
+    module
+}
+
+/// Only `test.sleep` is limited
+fn timeouts() -> ToolTimeouts {
+    ToolTimeouts::new().with_tool("test.sleep", Duration::from_millis(100))
+}
+
+fn call(id: u32, method: &str, millis: u64) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": [millis] })
+}
+
+#[tokio::test]
+async fn websocket_calls_over_their_method_limit_time_out() {
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_request_timeouts(timeouts());
+    let _server = serve_websocket(sleeper(), config, None).await.unwrap();
+
+    let timed_out = post(port, &call(1, "test.sleep", 5000)).await;
+    assert_eq!(timed_out["id"], 1);
+    assert_eq!(timed_out["error"]["code"], TIMEOUT_ERROR_CODE);
+    assert_eq!(timed_out["error"]["data"], json!({ "timeout_ms": 100 }));
+
+    assert_eq!(post(port, &call(2, "test.wait", 300)).await["result"], "done");
+}
+
+#[tokio::test]
+async fn websocket_batches_limit_each_call() {
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_request_timeouts(timeouts());
+    let _server = serve_websocket(sleeper(), config, None).await.unwrap();
+
+    let batch = post(
+        port,
+        &json!([call(1, "test.sleep", 5000), call(2, "test.wait", 300), call(3, "test.sleep", 10)]),
+    )
+    .await;
+    let batch = batch.as_array().expect("batch response");
+    let by_id = |id: u32| batch.iter().find(|r| r["id"] == id).expect("response for every call");
+    assert_eq!(by_id(1)["error"]["code"], TIMEOUT_ERROR_CODE);
+    assert_eq!(by_id(2)["result"], "done");
+    assert_eq!(by_id(3)["result"], "done");
+}
+
+#[tokio::test]
+async fn websocket_limited_batches_keep_the_response_size_limit() {
+    let port = free_port();
+    let config = WebSocketConfig::new(port)
+        .with_request_timeouts(timeouts())
+        .with_max_response_size(64);
+    let _server = serve_websocket(sleeper(), config, None).await.unwrap();
+
+    let batch = post(port, &json!([call(1, "test.sleep", 10), call(2, "test.wait", 10)])).await;
+    assert_eq!(batch["error"]["code"], TOO_BIG_BATCH_RESPONSE_CODE);
+}
+
+#[tokio::test]
+async fn stdio_method_limits_take_precedence_over_the_request_timeout() {
+    let config = StdioConfig::default()
+        .with_request_timeout(Duration::from_secs(10))
+        .with_request_timeouts(timeouts());
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(sleeper(), server_in, server_out, config));
+
+    for request in [call(1, "test.sleep", 5000), call(2, "test.wait", 300)] {
+        client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    }
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let timed_out: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(timed_out["id"], 1);
+    assert_eq!(timed_out["error"]["code"], TIMEOUT_ERROR_CODE);
+    assert_eq!(timed_out["error"]["data"], json!({ "timeout_ms": 100 }));
+
+    let answered: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(answered, json!({ "jsonrpc": "2.0", "id": 2, "result": "done" }));
+
+    server.await.unwrap().unwrap();
+}
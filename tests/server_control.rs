@@ -0,0 +1,56 @@
+//! Tests for changing transports while the server is waited on.
+//!
+//! Run with: cargo test --test server_control
+
+mod common;
+
+#[cfg(all(feature = "websocket", feature = "mcp-http"))]
+mod server_control_tests {
+    use std::time::Duration;
+
+    use plexus_transport::{McpHttpConfig, TransportServer};
+    use tokio::net::TcpStream;
+
+    use super::common::free_port;
+    use super::common::hub::{rpc_module, TestHub};
+
+    #[tokio::test]
+    async fn transports_start_and_stop_while_the_server_waits() {
+        let mut running = TransportServer::builder(TestHub::new(1), rpc_module)
+            .with_websocket(free_port())
+            .with_shutdown_timeout(Duration::from_secs(1))
+            .build()
+            .await
+            .unwrap()
+            .serve_detached()
+            .await
+            .unwrap();
+        let control = running.control();
+        let shutdown = running.shutdown_token();
+        let waiting = tokio::spawn(running.wait());
+
+        let addr = control.start_mcp_http(McpHttpConfig::new(0)).await.unwrap();
+        assert!(TcpStream::connect(addr).await.is_ok());
+        let error = control.start_mcp_http(McpHttpConfig::new(0)).await.unwrap_err();
+        assert_eq!(error.to_string(), "MCP HTTP transport is already running");
+
+        control.stop_mcp_http().await.unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+        assert!(!waiting.is_finished());
+
+        // A clone reaches the same server
+        let addr = control.clone().restart_mcp_http().await.unwrap();
+        assert!(TcpStream::connect(addr).await.is_ok());
+
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("server stopped")
+            .unwrap()
+            .unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+
+        let error = control.stop_mcp_http().await.unwrap_err();
+        assert_eq!(error.to_string(), "The server has stopped");
+    }
+}
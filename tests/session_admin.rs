@@ -0,0 +1,133 @@
+//! Tests for the MCP session administration routes.
+//!
+//! Run with: cargo test --test session_admin
+
+#[cfg(feature = "mcp-http")]
+mod session_admin_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use http::{Method, Request, StatusCode};
+    use plexus_transport::{
+        session_admin_router, ApiKeyAuth, AuthorizationPolicy, CallerIdentity, McpSessionInfo, SessionAdmin,
+    };
+    use tower::ServiceExt;
+
+    fn request(method: Method, uri: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("x-api-key", "admin-key")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn auth() -> ApiKeyAuth {
+        ApiKeyAuth::named_keys([("operator", "admin-key"), ("viewer", "view-key")])
+    }
+
+    #[tokio::test]
+    async fn an_admin_without_endpoints_lists_no_sessions() {
+        let router = session_admin_router(Arc::new(SessionAdmin::new()), auth(), None);
+
+        let response = router.clone().oneshot(request(Method::GET, "/sessions")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"[]");
+
+        let response = router.clone().oneshot(request(Method::GET, "/sessions/abc")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = router.oneshot(request(Method::DELETE, "/sessions/abc")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn routes_require_an_api_key() {
+        let router = session_admin_router(Arc::new(SessionAdmin::new()), auth(), None);
+
+        for key in [None, Some("wrong")] {
+            let mut builder = Request::builder().uri("/sessions");
+            if let Some(key) = key {
+                builder = builder.header("x-api-key", key);
+            }
+            let response = router.clone().oneshot(builder.body(Body::empty()).unwrap()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let unauthenticated = Request::builder()
+            .method(Method::DELETE)
+            .uri("/sessions/abc")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(unauthenticated).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn the_policy_decides_which_routes_a_key_may_use() {
+        let policy = |identity: &CallerIdentity, namespace: &str, method: &str| {
+            namespace == "sessions" && (method == "list" || identity.name() == Some("operator"))
+        };
+        let policy: Arc<dyn AuthorizationPolicy> = Arc::new(policy);
+        let router = session_admin_router(Arc::new(SessionAdmin::new()), auth(), Some(policy));
+
+        let as_viewer = |method: Method, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("authorization", "Bearer view-key")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = router.clone().oneshot(as_viewer(Method::GET, "/sessions")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router.clone().oneshot(as_viewer(Method::DELETE, "/sessions/abc")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // The operator may terminate; the session just does not exist
+        let response = router.oneshot(request(Method::DELETE, "/sessions/abc")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn masked_ids_do_not_reveal_the_session_id() {
+        let info = |id: &str| McpSessionInfo {
+            id: id.into(),
+            created_at_ms: 0,
+            last_seen_ms: 0,
+            open_streams: 0,
+            client_name: None,
+            client_version: None,
+            protocol_version: None,
+        };
+        let masked = info("6f1c2b9e-4d1a-4c55-9a0e-2f7d3c1b8a90").masked_id();
+        assert!(masked.starts_with("sha256:") && masked.len() == "sha256:".len() + 16);
+        assert!(!masked.contains("6f1c2b9e"));
+        assert_eq!(masked, info("6f1c2b9e-4d1a-4c55-9a0e-2f7d3c1b8a90").masked_id());
+        assert_ne!(masked, info("another-session").masked_id());
+    }
+
+    #[test]
+    fn session_info_reports_age_and_idle_time() {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let info = McpSessionInfo {
+            id: "abc".into(),
+            created_at_ms: now_ms - 60_000,
+            last_seen_ms: now_ms - 5_000,
+            open_streams: 0,
+            client_name: Some("inspector".into()),
+            client_version: None,
+            protocol_version: None,
+        };
+        assert!(info.age() >= Duration::from_secs(60));
+        assert!(info.idle() >= Duration::from_secs(5) && info.idle() < Duration::from_secs(60));
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["client_name"], "inspector");
+    }
+}
@@ -0,0 +1,63 @@
+//! Tests for exporting and importing SQLite MCP sessions.
+//!
+//! Run with: cargo test --features sqlite-sessions --test session_export
+
+#[cfg(feature = "sqlite-sessions")]
+mod session_export_tests {
+    use std::path::PathBuf;
+
+    use plexus_transport::mcp::{PersistedSession, SessionSnapshot, SqliteSessionConfig, SqliteSessionManager};
+    use rmcp::transport::streamable_http_server::session::SessionManager;
+
+    fn temp_db() -> PathBuf {
+        std::env::temp_dir().join(format!("plexus-sessions-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    async fn manager(db_path: PathBuf) -> SqliteSessionManager {
+        SqliteSessionManager::new(SqliteSessionConfig { db_path, ..Default::default() })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn sessions_move_between_databases() {
+        let old = manager(temp_db()).await;
+        let (id, _transport) = old.create_session().await.unwrap();
+
+        let snapshot = old.export().await.unwrap();
+        assert_eq!(snapshot.sessions.len(), 1);
+        assert_eq!(snapshot.sessions[0].id, id.to_string());
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: SessionSnapshot = serde_json::from_str(&json).unwrap();
+
+        let new = manager(temp_db()).await;
+        assert_eq!(new.import(&snapshot).await.unwrap(), 1);
+        assert_eq!(new.export().await.unwrap().sessions, snapshot.sessions);
+    }
+
+    #[tokio::test]
+    async fn importing_twice_keeps_the_latest_activity() {
+        let new = manager(temp_db()).await;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let snapshot = |last_seen_at| SessionSnapshot {
+            version: 1,
+            exported_at: now,
+            sessions: vec![PersistedSession { id: "abc".into(), created_at: now - 60, last_seen_at }],
+        };
+
+        new.import(&snapshot(now)).await.unwrap();
+        new.import(&snapshot(now - 30)).await.unwrap();
+        assert_eq!(new.export().await.unwrap().sessions[0].last_seen_at, now);
+    }
+
+    #[tokio::test]
+    async fn unknown_snapshot_versions_are_rejected() {
+        let new = manager(temp_db()).await;
+        let snapshot = SessionSnapshot { version: 99, exported_at: 0, sessions: Vec::new() };
+        assert!(new.import(&snapshot).await.is_err());
+    }
+}
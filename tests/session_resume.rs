@@ -0,0 +1,130 @@
+//! Tests for resuming SSE streams of SQLite MCP sessions with Last-Event-ID.
+//!
+//! Run with: cargo test --features sqlite-sessions --test session_resume
+
+#[cfg(feature = "sqlite-sessions")]
+mod session_resume_tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use plexus_transport::mcp::{SqliteSessionConfig, SqliteSessionManager, SqliteTuning};
+    use rmcp::model::{
+        CallToolRequestParam, CallToolResult, ClientJsonRpcMessage, Content, ProgressNotificationParam,
+    };
+    use rmcp::service::RequestContext;
+    use rmcp::transport::common::server_side_http::SessionId;
+    use rmcp::transport::streamable_http_server::session::SessionManager;
+    use rmcp::{ErrorData, RoleServer, ServerHandler, ServiceExt};
+    use serde_json::json;
+
+    /// Reports progress once, then answers
+    struct Progressing;
+
+    impl ServerHandler for Progressing {
+        async fn call_tool(
+            &self,
+            _request: CallToolRequestParam,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, ErrorData> {
+            let progress_token = context.meta.get_progress_token().expect("progress token");
+            context
+                .peer
+                .notify_progress(ProgressNotificationParam {
+                    progress_token,
+                    progress: 1.0,
+                    total: None,
+                    message: None,
+                })
+                .await
+                .ok();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(CallToolResult::success(vec![Content::text("done")]))
+        }
+    }
+
+    fn message(value: serde_json::Value) -> ClientJsonRpcMessage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    async fn manager(replay_events: usize) -> SqliteSessionManager {
+        let db_path = std::env::temp_dir().join(format!("plexus-resume-{}.db", uuid::Uuid::new_v4()));
+        SqliteSessionManager::new(SqliteSessionConfig {
+            db_path,
+            tuning: SqliteTuning { replay_events, ..Default::default() },
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Start a session and call the tool, dropping the stream after the progress event
+    async fn call_and_disconnect(manager: &SqliteSessionManager) -> (SessionId, String) {
+        let (id, transport) = manager.create_session().await.unwrap();
+        tokio::spawn(async move {
+            if let Ok(service) = Progressing.serve(transport).await {
+                let _ = service.waiting().await;
+            }
+        });
+        manager
+            .initialize_session(
+                &id,
+                message(json!({
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "method": "initialize",
+                    "params": {
+                        "protocolVersion": "2025-03-26",
+                        "capabilities": {},
+                        "clientInfo": { "name": "test", "version": "0" }
+                    }
+                })),
+            )
+            .await
+            .unwrap();
+        manager
+            .accept_message(&id, message(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })))
+            .await
+            .unwrap();
+
+        let mut stream = Box::pin(
+            manager
+                .create_stream(
+                    &id,
+                    message(json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "method": "tools/call",
+                        "params": { "name": "slow", "arguments": {}, "_meta": { "progressToken": "p" } }
+                    })),
+                )
+                .await
+                .unwrap(),
+        );
+        let progress = stream.next().await.unwrap();
+        drop(stream);
+
+        // Let the call finish while the client is away
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        (id, progress.event_id.unwrap())
+    }
+
+    #[tokio::test]
+    async fn events_missed_while_disconnected_are_replayed() {
+        let manager = manager(16).await;
+        let (id, last_event_id) = call_and_disconnect(&manager).await;
+
+        let resumed: Vec<_> = manager.resume(&id, last_event_id).await.unwrap().collect().await;
+        assert_eq!(resumed.len(), 1);
+        let response = serde_json::to_value(&*resumed[0].message).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["content"][0]["text"], "done");
+    }
+
+    #[tokio::test]
+    async fn without_a_journal_ended_streams_cannot_be_resumed() {
+        let manager = manager(0).await;
+        let (id, last_event_id) = call_and_disconnect(&manager).await;
+
+        assert!(manager.resume(&id, last_event_id).await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Tests for pluggable MCP session stores.
+//!
+//! Run with: cargo test --test session_store
+
+#[cfg(feature = "mcp-http")]
+mod session_store_tests {
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+    use plexus_transport::mcp::StoreSessionManager;
+    use plexus_transport::{SessionStorage, SessionStore, SessionStoreError};
+    use rmcp::transport::streamable_http_server::session::SessionManager;
+
+    /// Keeps session IDs in a set
+    #[derive(Default)]
+    struct SetStore {
+        ids: Mutex<HashSet<String>>,
+        closed: Mutex<Option<Vec<String>>>,
+    }
+
+    impl SetStore {
+        fn contains(&self, id: &str) -> bool {
+            self.ids.lock().unwrap().contains(id)
+        }
+    }
+
+    impl SessionStore for SetStore {
+        fn create<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            self.ids.lock().unwrap().insert(id.to_string());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn touch<'a>(&'a self, _id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn exists<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<bool, SessionStoreError>> {
+            let exists = self.contains(id);
+            Box::pin(async move { Ok(exists) })
+        }
+
+        fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            self.ids.lock().unwrap().remove(id);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn close<'a>(&'a self, live: &'a [String]) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            *self.closed.lock().unwrap() = Some(live.to_vec());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn sessions_are_recorded_in_the_store() {
+        let store = Arc::new(SetStore::default());
+        let manager = StoreSessionManager::new(store.clone());
+
+        let (id, _transport) = manager.create_session().await.unwrap();
+        assert!(store.contains(&id));
+        assert!(manager.has_session(&id).await.unwrap());
+
+        manager.close().await.unwrap();
+        assert_eq!(store.closed.lock().unwrap().as_deref(), Some(&[id.to_string()][..]));
+
+        manager.close_session(&id).await.unwrap();
+        assert!(!store.contains(&id));
+        assert!(!manager.has_session(&id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn stored_sessions_without_a_worker_are_forgotten() {
+        let store = Arc::new(SetStore::default());
+        store.ids.lock().unwrap().insert("from-before-restart".to_string());
+        let manager = StoreSessionManager::new(store.clone());
+
+        assert!(!manager.has_session(&"from-before-restart".into()).await.unwrap());
+        assert!(!store.contains("from-before-restart"));
+    }
+
+    /// Shares its sessions with other processes, so leaves stale ones alone
+    #[derive(Default)]
+    struct SharedStore(SetStore);
+
+    impl SessionStore for SharedStore {
+        fn create<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            self.0.create(id)
+        }
+
+        fn touch<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            self.0.touch(id)
+        }
+
+        fn exists<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<bool, SessionStoreError>> {
+            self.0.exists(id)
+        }
+
+        fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            self.0.remove(id)
+        }
+
+        fn stale<'a>(&'a self, _id: &'a str) -> BoxFuture<'a, Result<(), SessionStoreError>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_can_keep_sessions_without_a_worker() {
+        let store = Arc::new(SharedStore::default());
+        store.0.ids.lock().unwrap().insert("on-another-replica".to_string());
+        let manager = StoreSessionManager::new(store.clone());
+
+        assert!(!manager.has_session(&"on-another-replica".into()).await.unwrap());
+        assert!(store.0.contains("on-another-replica"));
+    }
+
+    #[test]
+    fn custom_storage_is_not_serialized() {
+        let storage = SessionStorage::Custom(Arc::new(SetStore::default()));
+        assert!(format!("{:?}", storage).contains("Custom"));
+        assert!(serde_json::to_string(&storage).is_err());
+    }
+}
@@ -0,0 +1,550 @@
+//! Tests for the generic `serve_io` transport core.
+//!
+//! Drives the line-delimited JSON-RPC loop over in-memory duplex pipes instead
+//! of the process's stdin/stdout.
+//!
+//! Run with: cargo test --test stdio_io
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::{NotificationOverflow, StdioConfig, StdioFraming};
+use plexus_transport::stdio::{serve_io, SUBSCRIPTION_OVERFLOW_ERROR_CODE};
+use plexus_transport::TIMEOUT_ERROR_CODE;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+fn echo_module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("echo", |params, _, _| {
+            params.one::<String>().unwrap_or_default()
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn responds_on_writer_and_returns_at_eof() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let server = tokio::spawn(serve_io(
+        echo_module(),
+        server_in,
+        server_out,
+        StdioConfig::default(),
+    ));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"echo\",\"params\":[\"hi\"]}\n")
+        .await
+        .unwrap();
+
+    let mut lines = BufReader::new(client_out).lines();
+    let line = lines.next_line().await.unwrap().expect("response line");
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["id"], 1);
+    assert_eq!(value["result"], "hi");
+
+    // Closing the input side ends the serve loop
+    drop(client_in);
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn blank_lines_are_ignored() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let server = tokio::spawn(serve_io(
+        echo_module(),
+        server_in,
+        server_out,
+        StdioConfig::default(),
+    ));
+
+    client_in
+        .write_all(b"\n   \n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"echo\",\"params\":[\"x\"]}\n")
+        .await
+        .unwrap();
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let line = lines.next_line().await.unwrap().expect("response line");
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["id"], 7);
+
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn oversized_lines_are_rejected_and_skipped() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let server = tokio::spawn(serve_io(
+        echo_module(),
+        server_in,
+        server_out,
+        StdioConfig::default().with_max_line_length(64),
+    ));
+
+    let writer = tokio::spawn(async move {
+        let huge = format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"echo\",\"params\":[\"{}\"]}}\n",
+            "x".repeat(100_000)
+        );
+        client_in.write_all(huge.as_bytes()).await.unwrap();
+        client_in
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"echo\",\"params\":[\"ok\"]}\n")
+            .await
+            .unwrap();
+    });
+
+    let mut lines = BufReader::new(client_out).lines();
+    let rejected: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(rejected["id"], serde_json::Value::Null);
+    assert_eq!(rejected["error"]["code"], -32007);
+
+    // The stream resynchronises on the next line
+    let next: serde_json::Value =
+        serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(next["id"], 2);
+    assert_eq!(next["result"], "ok");
+
+    writer.await.unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn trace_context_in_meta_reaches_the_method() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let mut module = RpcModule::new(());
+    module
+        .register_method("params", |params, _, _| {
+            params.parse::<serde_json::Value>().unwrap_or_default()
+        })
+        .unwrap();
+    let server = tokio::spawn(serve_io(module, server_in, server_out, StdioConfig::default()));
+
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "params",
+        "params": { "value": 1, "_meta": { "traceparent": traceparent } },
+    });
+    client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    drop(client_in);
+
+    // The call is handled as usual, `_meta` included
+    let mut lines = BufReader::new(client_out).lines();
+    let line = lines.next_line().await.unwrap().expect("response line");
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["result"]["value"], 1);
+    assert_eq!(value["result"]["_meta"]["traceparent"], traceparent);
+
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn notifications_and_responses_are_written_as_whole_lines() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let mut module = echo_module();
+    module
+        .register_subscription("subscribe_ticks", "tick", "unsubscribe_ticks", |_, pending, _, _| async move {
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+            for i in 0..100 {
+                let tick = serde_json::value::to_raw_value(&format!("{}-{}", i, "t".repeat(1000))).unwrap();
+                if sink.send(tick).await.is_err() {
+                    return;
+                }
+            }
+        })
+        .unwrap();
+    let server = tokio::spawn(serve_io(module, server_in, server_out, StdioConfig::default()));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"subscribe_ticks\"}\n")
+        .await
+        .unwrap();
+    for id in 1..=50 {
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": "echo", "params": ["e".repeat(1000)] });
+        client_in.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    }
+
+    // Every line parses on its own, whichever task produced it
+    let mut lines = BufReader::new(client_out).lines();
+    let (mut ticks, mut responses) = (0, 0);
+    while ticks < 100 || responses < 51 {
+        let line = lines.next_line().await.unwrap().expect("output line");
+        let value: serde_json::Value = serde_json::from_str(&line).expect("whole JSON-RPC message");
+        if value["method"] == "tick" {
+            ticks += 1;
+        } else {
+            responses += 1;
+        }
+    }
+
+    drop(client_in);
+    server.await.unwrap().unwrap();
+}
+
+/// Read one `Content-Length` framed message
+async fn read_frame<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> serde_json::Value {
+    use tokio::io::AsyncReadExt;
+
+    let mut header = String::new();
+    reader.read_line(&mut header).await.unwrap();
+    let length: usize = header
+        .trim()
+        .strip_prefix("Content-Length: ")
+        .expect("Content-Length header")
+        .parse()
+        .unwrap();
+    let mut blank = String::new();
+    reader.read_line(&mut blank).await.unwrap();
+    assert_eq!(blank, "\r\n");
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn content_length_framing_carries_newlines() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let config = StdioConfig::default().with_framing(StdioFraming::ContentLength);
+    let server = tokio::spawn(serve_io(echo_module(), server_in, server_out, config));
+
+    // Pretty-printed JSON spans several lines
+    let request = serde_json::to_string_pretty(&serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "echo",
+        "params": ["two\nlines"],
+    }))
+    .unwrap();
+    let frame = format!("Content-Length: {}\r\nContent-Type: application/json\r\n\r\n{}", request.len(), request);
+    client_in.write_all(frame.as_bytes()).await.unwrap();
+
+    let mut out = BufReader::new(client_out);
+    let response = read_frame(&mut out).await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"], "two\nlines");
+
+    drop(client_in);
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn oversized_frames_are_rejected_and_skipped() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let config = StdioConfig::default()
+        .with_framing(StdioFraming::ContentLength)
+        .with_max_line_length(64);
+    let server = tokio::spawn(serve_io(echo_module(), server_in, server_out, config));
+
+    let writer = tokio::spawn(async move {
+        for word in ["x".repeat(10_000), "ok".to_string()] {
+            let request = serde_json::json!({ "jsonrpc": "2.0", "id": word.len(), "method": "echo", "params": [word] });
+            let body = request.to_string();
+            let frame = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+            client_in.write_all(frame.as_bytes()).await.unwrap();
+        }
+    });
+
+    let mut out = BufReader::new(client_out);
+    let rejected = read_frame(&mut out).await;
+    assert_eq!(rejected["error"]["code"], -32007);
+    let next = read_frame(&mut out).await;
+    assert_eq!(next["result"], "ok");
+
+    writer.await.unwrap();
+    server.await.unwrap().unwrap();
+}
+
+/// Read one length-prefixed message
+async fn read_prefixed<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> serde_json::Value {
+    use tokio::io::AsyncReadExt;
+
+    let length = reader.read_u32().await.unwrap() as usize;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn length_prefixed_framing_carries_large_messages() {
+    let (mut client_in, server_in) = tokio::io::duplex(64 * 1024);
+    let (server_out, mut client_out) = tokio::io::duplex(64 * 1024);
+
+    let config = StdioConfig::default()
+        .with_framing(StdioFraming::LengthPrefixed)
+        .with_max_line_length(1024 * 1024);
+    let server = tokio::spawn(serve_io(echo_module(), server_in, server_out, config));
+
+    let writer = tokio::spawn(async move {
+        for word in ["x".repeat(2 * 1024 * 1024), "y\n".repeat(256 * 1024)] {
+            let request = serde_json::json!({ "jsonrpc": "2.0", "id": word.len(), "method": "echo", "params": [word] });
+            let body = request.to_string();
+            client_in.write_all(&(body.len() as u32).to_be_bytes()).await.unwrap();
+            client_in.write_all(body.as_bytes()).await.unwrap();
+        }
+    });
+
+    // Over the limit: rejected and skipped; the next message still parses
+    let rejected = read_prefixed(&mut client_out).await;
+    assert_eq!(rejected["error"]["code"], -32007);
+    let echoed = read_prefixed(&mut client_out).await;
+    assert_eq!(echoed["id"], 512 * 1024);
+    assert_eq!(echoed["result"], "y\n".repeat(256 * 1024));
+
+    writer.await.unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn batches_get_one_array_response() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let server = tokio::spawn(serve_io(echo_module(), server_in, server_out, StdioConfig::default()));
+
+    let batch = serde_json::json!([
+        { "jsonrpc": "2.0", "id": 1, "method": "echo", "params": ["a"] },
+        { "jsonrpc": "2.0", "method": "echo", "params": ["notified"] },
+        { "jsonrpc": "2.0", "id": 2, "method": "missing" },
+        42,
+    ]);
+    client_in.write_all(format!("{}\n[]\n", batch).as_bytes()).await.unwrap();
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let response: serde_json::Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    let responses = response.as_array().expect("batch response");
+    // The notification gets no response
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["result"], "a");
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["error"]["code"], -32601);
+    assert_eq!(responses[2]["error"]["code"], -32600);
+
+    // An empty batch is an invalid request
+    let empty: serde_json::Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(empty["error"]["code"], -32600);
+
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn overflowing_subscriptions_are_closed_with_an_error() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(256);
+
+    let mut module = echo_module();
+    module
+        .register_subscription("subscribe_ticks", "tick", "unsubscribe_ticks", |_, pending, _, _| async move {
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+            for i in 0..5000 {
+                if sink.send(serde_json::value::to_raw_value(&i).unwrap()).await.is_err() {
+                    return;
+                }
+            }
+        })
+        .unwrap();
+    let config = StdioConfig {
+        subscription_buffer_size: 4,
+        ..StdioConfig::default()
+    }
+    .with_notification_overflow(NotificationOverflow::Close);
+    let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"subscribe_ticks\"}\n")
+        .await
+        .unwrap();
+    // Reading nothing while the ticks are published fills every queue
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let mut lines = BufReader::new(client_out).lines();
+    let mut ticks = 0;
+    let error = loop {
+        let line = lines.next_line().await.unwrap().expect("output line");
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        if value["method"] != "tick" {
+            continue;
+        }
+        if let Some(error) = value["params"].get("error") {
+            break error.clone();
+        }
+        ticks += 1;
+    };
+    assert_eq!(error["code"], SUBSCRIPTION_OVERFLOW_ERROR_CODE);
+    assert!(ticks < 5000);
+
+    // Nothing follows the error
+    drop(client_in);
+    while let Some(line) = lines.next_line().await.unwrap() {
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_ne!(value["method"], "tick");
+    }
+    server.await.unwrap().unwrap();
+}
+
+/// A module whose `subscribe_ticks` sends `count` ticks 20 ms apart
+fn ticking_module(count: u64) -> RpcModule<()> {
+    let mut module = echo_module();
+    module
+        .register_subscription("subscribe_ticks", "tick", "unsubscribe_ticks", move |_, pending, _, _| async move {
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+            for i in 0..count {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                if sink.send(serde_json::value::to_raw_value(&i).unwrap()).await.is_err() {
+                    return;
+                }
+            }
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn subscriptions_drain_after_eof_within_the_grace_period() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let config = StdioConfig::default().with_eof_grace(std::time::Duration::from_secs(5));
+    let server = tokio::spawn(serve_io(ticking_module(5), server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"subscribe_ticks\"}\n")
+        .await
+        .unwrap();
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let mut ticks = 0;
+    while let Some(line) = lines.next_line().await.unwrap() {
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        if value["method"] == "tick" {
+            ticks += 1;
+        }
+    }
+    assert_eq!(ticks, 5);
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn subscriptions_outliving_the_grace_period_are_closed() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let config = StdioConfig::default().with_eof_grace(std::time::Duration::from_millis(100));
+    let server = tokio::spawn(serve_io(ticking_module(u64::MAX), server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":0,\"method\":\"subscribe_ticks\"}\n")
+        .await
+        .unwrap();
+    drop(client_in);
+
+    let started = std::time::Instant::now();
+    let mut lines = BufReader::new(client_out).lines();
+    while lines.next_line().await.unwrap().is_some() {}
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn hung_calls_time_out_and_later_requests_are_served() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let mut module = echo_module();
+    module
+        .register_async_method("hang", |_, _, _| async {
+            std::future::pending::<()>().await;
+            "never"
+        })
+        .unwrap();
+    let config = StdioConfig::default().with_request_timeout(std::time::Duration::from_millis(100));
+    let server = tokio::spawn(serve_io(module, server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"hang\"}\n")
+        .await
+        .unwrap();
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"echo\",\"params\":[\"hi\"]}\n")
+        .await
+        .unwrap();
+
+    let mut lines = BufReader::new(client_out).lines();
+    let timed_out: serde_json::Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(timed_out["id"], 1);
+    assert_eq!(timed_out["error"]["code"], TIMEOUT_ERROR_CODE);
+    assert_eq!(timed_out["error"]["data"]["timeout_ms"], 100);
+
+    let echoed: serde_json::Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(echoed["id"], 2);
+    assert_eq!(echoed["result"], "hi");
+
+    drop(client_in);
+    server.await.unwrap().unwrap();
+}
+
+/// Read one output line as JSON
+async fn next_json<R: tokio::io::AsyncBufRead + Unpin>(lines: &mut tokio::io::Lines<R>) -> serde_json::Value {
+    let line = lines.next_line().await.unwrap().expect("output line");
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn malformed_input_is_answered_and_serving_continues() {
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+
+    let server = tokio::spawn(serve_io(
+        echo_module(),
+        server_in,
+        server_out,
+        StdioConfig::default(),
+    ));
+
+    client_in.write_all(b"{not json\n").await.unwrap();
+    client_in.write_all(b"\xff\xfe\n").await.unwrap();
+    client_in.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7}\n").await.unwrap();
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":8,\"method\":\"echo\",\"params\":[\"still here\"]}\n")
+        .await
+        .unwrap();
+
+    let mut lines = BufReader::new(client_out).lines();
+    let not_json = next_json(&mut lines).await;
+    assert_eq!(not_json["error"]["code"], -32700);
+    assert_eq!(not_json["id"], serde_json::Value::Null);
+    let not_utf8 = next_json(&mut lines).await;
+    assert_eq!(not_utf8["error"]["code"], -32700);
+    let not_request = next_json(&mut lines).await;
+    assert_eq!(not_request["error"]["code"], -32600);
+    assert_eq!(not_request["id"], 7);
+    let echoed = next_json(&mut lines).await;
+    assert_eq!(echoed["id"], 8);
+    assert_eq!(echoed["result"], "still here");
+
+    drop(client_in);
+    server.await.unwrap().unwrap();
+}
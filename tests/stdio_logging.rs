@@ -0,0 +1,30 @@
+//! Tests for logging from stdio servers.
+//!
+//! Run with: cargo test --features stdio-logging --test stdio_logging
+
+#[cfg(feature = "stdio-logging")]
+mod stdio_logging_tests {
+    use plexus_transport::stdio_logging;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn file_layer_appends_events_to_the_file() {
+        let path = std::env::temp_dir().join(format!("stdio-log-{}.log", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "earlier line\n").unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(stdio_logging::file_layer(&path).unwrap());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(tool = "search", "Plugin started");
+        });
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let mut lines = log.lines();
+        assert_eq!(lines.next(), Some("earlier line"));
+        let event = lines.next().expect("logged event");
+        assert!(event.contains("INFO"));
+        assert!(event.contains("Plugin started"));
+        assert!(event.contains("tool=\"search\""));
+        assert!(!event.contains('\u{1b}'), "no ANSI colours in files");
+    }
+}
@@ -0,0 +1,178 @@
+//! Tests for resuming WebSocket subscriptions after a reconnect.
+//!
+//! Run with: cargo test --test subscription_resume
+
+use std::time::Duration;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::resume::RESUME_FAILED_ERROR_CODE;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::{ApiKeyAuth, SubscriptionResume};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+
+mod common;
+use common::free_port;
+
+const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+/// Open a WebSocket connection, returning once the upgrade is accepted
+async fn connect(port: u16) -> TcpStream {
+    upgrade(port, UPGRADE).await
+}
+
+/// Open a WebSocket connection authenticated with the API key `key`
+async fn connect_as(port: u16, key: &str) -> TcpStream {
+    let request = UPGRADE.replacen("\r\n\r\n", &format!("\r\nAuthorization: Bearer {}\r\n\r\n", key), 1);
+    upgrade(port, &request).await
+}
+
+async fn upgrade(port: u16, request: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    assert!(head.starts_with(b"HTTP/1.1 101"));
+    stream
+}
+
+/// Send `message` as a client text frame (masked with a zero key)
+async fn send_text(stream: &mut TcpStream, message: &Value) {
+    let payload = message.to_string();
+    assert!(payload.len() < 126);
+    let mut frame = vec![0x81, 0x80 | payload.len() as u8, 0, 0, 0, 0];
+    frame.extend_from_slice(payload.as_bytes());
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Read the next server text frame as JSON
+async fn read_text(stream: &mut TcpStream) -> Value {
+    let opcode = stream.read_u8().await.unwrap();
+    assert_eq!(opcode, 0x81);
+    let len = match stream.read_u8().await.unwrap() {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    serde_json::from_slice(&payload).unwrap()
+}
+
+/// Call `method` and return its response
+async fn call(stream: &mut TcpStream, id: u64, method: &str, params: Value) -> Value {
+    send_text(stream, &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await;
+    let response = read_text(stream).await;
+    assert_eq!(response["id"], id);
+    response
+}
+
+/// A module whose `test.ticks` subscription forwards every published tick
+fn ticker(ticks: broadcast::Sender<u32>) -> RpcModule<broadcast::Sender<u32>> {
+    let mut module = RpcModule::new(ticks);
+    module
+        .register_subscription("test.ticks", "test.tick", "test.unticks", |_, pending, ticks, _| async move {
+            let mut ticks = ticks.subscribe();
+            let Ok(sink) = pending.accept().await else {
+                return;
+            };
+            while let Ok(tick) = ticks.recv().await {
+                if sink.send(serde_json::value::to_raw_value(&tick).unwrap()).await.is_err() {
+                    return;
+                }
+            }
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn subscriptions_resume_on_a_new_connection() {
+    let (ticks, _) = broadcast::channel(16);
+    let port = free_port();
+    let config = WebSocketConfig::new(port)
+        .with_subscription_resume(SubscriptionResume::new(Duration::from_secs(30), 2));
+    let _server = serve_websocket(ticker(ticks.clone()).remove_context(), config, None)
+        .await
+        .unwrap();
+
+    let mut first = connect(port).await;
+    let token = call(&mut first, 1, "rpc.resume_token", json!([])).await["result"].clone();
+    assert!(token.is_string());
+    let subscription = call(&mut first, 2, "test.ticks", json!([])).await["result"].clone();
+    ticks.send(1).unwrap();
+    let tick = read_text(&mut first).await;
+    assert_eq!(tick["method"], "test.tick");
+    assert_eq!(tick["params"]["subscription"], subscription);
+    assert_eq!(tick["params"]["result"], 1);
+
+    // Ticks published while the client is away are kept, up to the replay limit
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for tick in 2..=4 {
+        ticks.send(tick).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut second = connect(port).await;
+    let resumed = call(&mut second, 1, "rpc.resume", json!([token])).await;
+    assert_eq!(
+        resumed["result"],
+        json!({ "subscriptions": [subscription], "replayed": 2, "dropped": 1 })
+    );
+    for expected in [3, 4] {
+        let tick = read_text(&mut second).await;
+        assert_eq!(tick["params"]["subscription"], subscription);
+        assert_eq!(tick["params"]["result"], expected);
+    }
+    ticks.send(5).unwrap();
+    assert_eq!(read_text(&mut second).await["params"]["result"], 5);
+
+    // The resumed subscription can be ended from its new connection
+    let unsubscribed = call(&mut second, 2, "test.unticks", json!([subscription])).await;
+    assert_eq!(unsubscribed["result"], true);
+}
+
+#[tokio::test]
+async fn unknown_resume_tokens_are_rejected() {
+    let (ticks, _) = broadcast::channel(16);
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_subscription_resume(SubscriptionResume::default());
+    let _server = serve_websocket(ticker(ticks).remove_context(), config, None).await.unwrap();
+
+    let mut stream = connect(port).await;
+    let response = call(&mut stream, 1, "rpc.resume", json!(["not-a-token"])).await;
+    assert_eq!(response["error"]["code"], RESUME_FAILED_ERROR_CODE);
+}
+
+#[tokio::test]
+async fn resume_tokens_only_resume_for_the_same_caller() {
+    let (ticks, _) = broadcast::channel(16);
+    let port = free_port();
+    let config = WebSocketConfig::new(port)
+        .with_auth(ApiKeyAuth::named_keys([("reader", "reader-key"), ("admin", "admin-key")]))
+        .with_subscription_resume(SubscriptionResume::new(Duration::from_secs(30), 8));
+    let _server = serve_websocket(ticker(ticks.clone()).remove_context(), config, None)
+        .await
+        .unwrap();
+
+    let mut admin = connect_as(port, "admin-key").await;
+    let token = call(&mut admin, 1, "rpc.resume_token", json!([])).await["result"].clone();
+    let subscription = call(&mut admin, 2, "test.ticks", json!([])).await["result"].clone();
+    drop(admin);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut reader = connect_as(port, "reader-key").await;
+    let refused = call(&mut reader, 1, "rpc.resume", json!([token])).await;
+    assert_eq!(refused["error"]["code"], RESUME_FAILED_ERROR_CODE);
+
+    // The refused attempt leaves the session to its own caller
+    let mut admin = connect_as(port, "admin-key").await;
+    let resumed = call(&mut admin, 1, "rpc.resume", json!([token])).await;
+    assert_eq!(resumed["result"]["subscriptions"], json!([subscription]));
+}
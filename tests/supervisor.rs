@@ -0,0 +1,142 @@
+//! Tests for restarting crashed transports.
+//!
+//! Run with: cargo test --test supervisor
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::FutureExt;
+use plexus_transport::supervisor::{supervise, StartFn};
+use plexus_transport::{RestartPolicy, TransportCrash, TransportTask};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+/// Fast backoff, recording every crash
+fn policy(crashes: &Arc<Mutex<Vec<TransportCrash>>>) -> RestartPolicy {
+    let crashes = crashes.clone();
+    RestartPolicy::default()
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .with_crash_handler(move |crash| crashes.lock().unwrap().push(crash.clone()))
+}
+
+/// Answers every connection with "ok" until `shutdown` is cancelled
+fn greeter(listener: TcpListener, shutdown: CancellationToken) -> TransportTask {
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                _ = shutdown.cancelled() => return Ok(()),
+            };
+            // A client that hung up is not a transport crash
+            let _ = sock.write_all(b"ok").await;
+        }
+    })
+}
+
+/// Restarts [`greeter`] on the listener it is given
+fn restart_greeter(shutdown: CancellationToken) -> StartFn {
+    Box::new(move |listener| {
+        let task = greeter(listener, shutdown.clone());
+        async move { Ok::<_, anyhow::Error>(task) }.boxed()
+    })
+}
+
+/// The greeting once the transport listens on `addr` again
+async fn greeting(addr: std::net::SocketAddr) -> String {
+    loop {
+        if let Ok(mut sock) = TcpStream::connect(addr).await {
+            let mut reply = String::new();
+            sock.read_to_string(&mut reply).await.unwrap();
+            return reply;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn crashed_transports_restart_on_the_same_address() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let shutdown = CancellationToken::new();
+    let crashes = Arc::new(Mutex::new(Vec::new()));
+
+    // The first transport panics; its listener is dropped with the task
+    let first: TransportTask = tokio::spawn(async move {
+        let _listener = listener;
+        panic!("listener broke");
+    });
+    let start = restart_greeter(shutdown.clone());
+    let supervisor = supervise("Test", addr, first, policy(&crashes), shutdown.clone(), start);
+
+    let reply = tokio::time::timeout(Duration::from_secs(5), greeting(addr))
+        .await
+        .expect("transport restarted");
+    assert_eq!(reply, "ok");
+
+    {
+        let crashes = crashes.lock().unwrap();
+        assert_eq!(crashes.len(), 1);
+        assert_eq!(crashes[0].transport, "Test");
+        assert!(crashes[0].error.contains("panicked"), "{}", crashes[0].error);
+        assert_eq!(crashes[0].restarts, 0);
+        assert_eq!(crashes[0].next_attempt, Some(Duration::from_millis(10)));
+    }
+
+    // A transport that ends after shutdown is not restarted
+    shutdown.cancel();
+    supervisor.await.unwrap().unwrap();
+    assert_eq!(crashes.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn supervisor_gives_up_after_max_restarts() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let crashes = Arc::new(Mutex::new(Vec::new()));
+
+    let failing = |listener: TcpListener| -> TransportTask {
+        tokio::spawn(async move {
+            drop(listener);
+            Err(std::io::Error::other("socket error"))
+        })
+    };
+    let start: StartFn = Box::new(move |listener| {
+        let task = failing(listener);
+        async move { Ok::<_, anyhow::Error>(task) }.boxed()
+    });
+    let policy = policy(&crashes).with_max_restarts(2);
+    let supervisor = supervise("Test", addr, failing(listener), policy, CancellationToken::new(), start);
+
+    let error = tokio::time::timeout(Duration::from_secs(5), supervisor)
+        .await
+        .expect("supervisor gave up")
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "socket error");
+
+    let crashes = crashes.lock().unwrap();
+    let restarts: Vec<_> = crashes.iter().map(|crash| (crash.restarts, crash.next_attempt)).collect();
+    assert_eq!(
+        restarts,
+        [
+            (0, Some(Duration::from_millis(10))),
+            (1, Some(Duration::from_millis(20))),
+            (2, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn transports_that_stop_cleanly_are_not_restarted() {
+    let crashes = Arc::new(Mutex::new(Vec::new()));
+    let first: TransportTask = tokio::spawn(async { Ok(()) });
+    let start = restart_greeter(CancellationToken::new());
+    let addr = "127.0.0.1:0".parse().unwrap();
+
+    supervise("Test", addr, first, policy(&crashes), CancellationToken::new(), start)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(crashes.lock().unwrap().is_empty());
+}
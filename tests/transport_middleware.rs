@@ -0,0 +1,267 @@
+//! Tests for middleware run around calls on every transport.
+//!
+//! Run with: cargo test --test transport_middleware
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use jsonrpsee::RpcModule;
+use plexus_transport::config::{StdioConfig, WebSocketConfig};
+use plexus_transport::rate_limit::RATE_LIMITED_ERROR_CODE;
+use plexus_transport::stdio::serve_io;
+use plexus_transport::websocket::serve_websocket;
+use plexus_transport::{AuditStatus, CallContext, CallOutcome, CallRejection, TransportMiddleware};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+mod common;
+use common::{free_port, post};
+
+/// Who a call was made for, as seen by the method
+#[derive(Debug, Clone)]
+struct Tenant(String);
+
+/// Admits `limit` calls, telling each method its tenant, and keeps the outcomes
+///
+/// The tenant replaces the params as `tenant` for the RPC module below and as
+/// `message` for the test hub's `echo`.
+struct Quota {
+    remaining: AtomicU32,
+    outcomes: Mutex<Vec<(String, AuditStatus)>>,
+}
+
+impl Quota {
+    fn new(limit: u32) -> Arc<Self> {
+        Arc::new(Self {
+            remaining: AtomicU32::new(limit),
+            outcomes: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn outcomes(&self) -> Vec<(String, AuditStatus)> {
+        self.outcomes.lock().unwrap().clone()
+    }
+}
+
+impl TransportMiddleware for Quota {
+    fn before_call<'a>(&'a self, call: &'a mut CallContext) -> BoxFuture<'a, Result<(), CallRejection>> {
+        Box::pin(async move {
+            let admitted = self
+                .remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if !admitted {
+                return Err(CallRejection::new(RATE_LIMITED_ERROR_CODE, "Quota exhausted")
+                    .with_data(json!({ "transport": call.transport })));
+            }
+            let tenant = format!("{}:{}", call.transport, call.peer.is_some());
+            call.params = Some(json!({ "tenant": tenant, "message": tenant }));
+            call.extensions.insert(Tenant(tenant));
+            Ok(())
+        })
+    }
+
+    fn after_call(&self, call: &CallContext, outcome: &CallOutcome) {
+        self.outcomes.lock().unwrap().push((call.method.clone(), outcome.status.clone()));
+    }
+}
+
+/// Rejects every call
+struct Closed;
+
+impl TransportMiddleware for Closed {
+    fn before_call<'a>(&'a self, _call: &'a mut CallContext) -> BoxFuture<'a, Result<(), CallRejection>> {
+        Box::pin(async { Err(CallRejection::new(-32050, "Closed for maintenance")) })
+    }
+}
+
+/// `test.tenant` returns the tenant in its params and in its extensions
+fn module() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("test.tenant", |params, _, ext| {
+            let params: Value = params.parse().unwrap_or(Value::Null);
+            json!({
+                "params": params["tenant"],
+                "extension": ext.get::<Tenant>().map(|t| t.0.clone()),
+            })
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn stdio_calls_are_enriched_and_rejected_by_middleware() {
+    let quota = Quota::new(1);
+    let config = StdioConfig::default().with_middleware(quota.clone());
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module(), server_in, server_out, config));
+
+    for id in 1..=2 {
+        let call = json!({ "jsonrpc": "2.0", "id": id, "method": "test.tenant", "params": { "tenant": "forged" } });
+        client_in.write_all(format!("{}\n", call).as_bytes()).await.unwrap();
+    }
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let admitted: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(
+        admitted["result"],
+        json!({ "params": "stdio:false", "extension": "stdio:false" })
+    );
+
+    let rejected: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(rejected["id"], 2);
+    assert_eq!(rejected["error"]["code"], RATE_LIMITED_ERROR_CODE);
+    assert_eq!(rejected["error"]["message"], "Quota exhausted");
+    assert_eq!(rejected["error"]["data"], json!({ "transport": "stdio" }));
+
+    server.await.unwrap().unwrap();
+    // A middleware hears only about the calls it let through
+    assert_eq!(quota.outcomes(), vec![("test.tenant".to_string(), AuditStatus::Ok)]);
+}
+
+#[tokio::test]
+async fn websocket_calls_and_batches_go_through_middleware() {
+    let quota = Quota::new(2);
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_middleware(quota.clone());
+    let _server = serve_websocket(module(), config, None).await.unwrap();
+
+    let call = |id: u32| json!({ "jsonrpc": "2.0", "id": id, "method": "test.tenant", "params": {} });
+    let single = post(port, &call(1)).await;
+    assert_eq!(
+        single["result"],
+        json!({ "params": "websocket:true", "extension": "websocket:true" })
+    );
+
+    let batch = post(port, &json!([call(2), call(3)])).await;
+    let batch = batch.as_array().expect("batch response");
+    let by_id = |id: u32| batch.iter().find(|r| r["id"] == id).expect("response for every call");
+    assert_eq!(by_id(2)["result"]["params"], "websocket:true");
+    assert_eq!(by_id(3)["error"]["code"], RATE_LIMITED_ERROR_CODE);
+
+    assert_eq!(quota.outcomes().len(), 2);
+}
+
+#[tokio::test]
+async fn middleware_that_let_a_call_through_sees_a_later_rejection() {
+    let quota = Quota::new(10);
+    let config = StdioConfig::default()
+        .with_middleware(quota.clone())
+        .with_middleware(Arc::new(Closed));
+
+    let (mut client_in, server_in) = tokio::io::duplex(4096);
+    let (server_out, client_out) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_io(module(), server_in, server_out, config));
+
+    client_in
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.tenant\"}\n")
+        .await
+        .unwrap();
+    drop(client_in);
+
+    let mut lines = BufReader::new(client_out).lines();
+    let rejected: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(rejected["error"]["code"], -32050);
+    assert!(rejected["error"].get("data").is_none());
+
+    server.await.unwrap().unwrap();
+    assert_eq!(
+        quota.outcomes(),
+        vec![("test.tenant".to_string(), AuditStatus::Error { code: -32050 })]
+    );
+}
+
+#[cfg(feature = "mcp-http")]
+mod mcp_tests {
+    use plexus_transport::rate_limit::RATE_LIMITED_ERROR_CODE;
+    use plexus_transport::{AuditStatus, McpHttpConfig};
+    use rmcp::ServiceError;
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::common::mcp::{call, connect, serve, text};
+    use super::Quota;
+
+    #[tokio::test]
+    async fn mcp_tool_calls_are_enriched_and_rejected_by_middleware() {
+        let quota = Quota::new(1);
+        let config = McpHttpConfig::new(0).with_middleware(quota.clone());
+        let shutdown = CancellationToken::new();
+        let url = serve(TestHub::new(1), config, shutdown.clone()).await;
+        let (client, _) = connect(&url).await;
+
+        let admitted = call(&client, "test.echo", json!({ "message": "forged" })).await.unwrap();
+        assert_eq!(text(&admitted), "mcp:true");
+
+        let Err(ServiceError::McpError(rejected)) = call(&client, "test.echo", json!({ "message": "forged" })).await
+        else {
+            panic!("call over the quota was answered");
+        };
+        assert_eq!(rejected.code.0, RATE_LIMITED_ERROR_CODE);
+        assert_eq!(rejected.message, "Quota exhausted");
+        assert_eq!(rejected.data, Some(json!({ "transport": "mcp" })));
+
+        assert_eq!(quota.outcomes(), vec![("test.echo".to_string(), AuditStatus::Ok)]);
+        client.cancel().await.unwrap();
+        shutdown.cancel();
+    }
+}
+
+#[cfg(feature = "http-gateway")]
+mod rest_tests {
+    use plexus_transport::{serve_rest_http_with_shutdown, AuditStatus, RestHttpConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::sync::CancellationToken;
+
+    use super::common::hub::TestHub;
+    use super::Quota;
+
+    /// POST `body` to `path` and return the status code and response body
+    async fn post(addr: std::net::SocketAddr, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").expect("response body");
+        (head[9..12].parse().unwrap(), body.to_string())
+    }
+
+    #[tokio::test]
+    async fn rest_calls_are_enriched_and_rejected_by_middleware() {
+        let quota = Quota::new(1);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = RestHttpConfig::new(0).with_middleware(quota.clone());
+        let shutdown = CancellationToken::new();
+        serve_rest_http_with_shutdown(TestHub::new(1), None, None, config, None, listener, shutdown.clone())
+            .await
+            .unwrap();
+
+        let (status, body) = post(addr, "/rest/test/echo", r#"{"message":"forged"}"#).await;
+        assert_eq!(status, 200, "{}", body);
+        assert!(body.contains("rest:true"), "{}", body);
+        assert!(!body.contains("forged"), "{}", body);
+
+        let (status, body) = post(addr, "/rest/test/echo", r#"{"message":"forged"}"#).await;
+        assert_eq!(status, 429);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"], "Quota exhausted");
+        assert_eq!(body["data"]["transport"], "rest");
+
+        assert_eq!(quota.outcomes(), vec![("test.echo".to_string(), AuditStatus::Ok)]);
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,76 @@
+//! Tests for plain HTTP JSON-RPC and request metadata on the WebSocket port.
+//!
+//! Run with: cargo test --test websocket_http
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::request::RawRequestContext;
+use plexus_transport::websocket::serve_websocket;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+mod common;
+use common::free_port;
+
+const REQUEST: &str = "POST / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: 45\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.ping\"}";
+
+const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+#[tokio::test]
+async fn one_port_serves_http_posts_and_websocket_upgrades() {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    let port = free_port();
+    let _server = serve_websocket(module, WebSocketConfig::new(port), None).await.unwrap();
+
+    let mut http = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    http.write_all(REQUEST.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    http.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+    let (_, body) = response.split_once("\r\n\r\n").expect("response body");
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["result"], "pong");
+
+    let mut ws = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    ws.write_all(UPGRADE.as_bytes()).await.unwrap();
+    let mut buf = [0u8; 64];
+    let n = ws.read(&mut buf).await.unwrap();
+    assert!(String::from_utf8_lossy(&buf[..n]).starts_with("HTTP/1.1 101"));
+}
+
+#[tokio::test]
+async fn calls_see_the_request_headers_and_query() {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("test.client", |_, _, ext| {
+            let raw = ext.get::<RawRequestContext>().expect("request context");
+            let user_agent = raw.headers.get("user-agent").and_then(|v| v.to_str().ok());
+            serde_json::json!({
+                "user_agent": user_agent,
+                "query": raw.uri.query(),
+                "peer": raw.peer.map(|peer| peer.to_string()),
+            })
+        })
+        .unwrap();
+    let port = free_port();
+    let _server = serve_websocket(module, WebSocketConfig::new(port), None).await.unwrap();
+
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"test.client"}"#;
+    let request = format!(
+        "POST /?client_id=agent-7 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nUser-Agent: probe/1.0\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let local = stream.local_addr().unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").expect("response body");
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+
+    assert_eq!(body["result"]["user_agent"], "probe/1.0");
+    assert_eq!(body["result"]["query"], "client_id=agent-7");
+    assert_eq!(body["result"]["peer"], local.to_string());
+}
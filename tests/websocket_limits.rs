@@ -0,0 +1,79 @@
+//! Tests for WebSocket server connection limits.
+//!
+//! Run with: cargo test --test websocket_limits
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::websocket::serve_websocket;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+mod common;
+use common::{free_port, post};
+
+const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+const REQUEST: &str = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: 45\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.ping\"}";
+
+#[tokio::test]
+async fn connections_over_the_limit_are_dropped() {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    let port = free_port();
+    let _server = serve_websocket(module, WebSocketConfig::new(port).with_max_connections(1), None)
+        .await
+        .unwrap();
+
+    // The first connection is served and kept open
+    let mut first = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    first.write_all(REQUEST.as_bytes()).await.unwrap();
+    let mut buf = [0u8; 64];
+    let n = first.read(&mut buf).await.unwrap();
+    assert!(String::from_utf8_lossy(&buf[..n]).starts_with("HTTP/1.1 200"));
+
+    // The second is closed without a response
+    let mut second = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let _ = second.write_all(REQUEST.as_bytes()).await;
+    let mut response = Vec::new();
+    let _ = second.read_to_end(&mut response).await;
+    assert!(response.is_empty());
+}
+
+#[tokio::test]
+async fn clients_that_stop_answering_pings_are_disconnected() {
+    let port = free_port();
+    let config = WebSocketConfig::new(port)
+        .with_ping_interval(Duration::from_millis(100))
+        .with_inactive_limit(Duration::from_millis(50));
+    let _server = serve_websocket(RpcModule::new(()), config, None).await.unwrap();
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(UPGRADE.as_bytes()).await.unwrap();
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).await.unwrap();
+    assert!(String::from_utf8_lossy(&buf[..n]).starts_with("HTTP/1.1 101"));
+
+    // This client never answers the server's pings, so the server hangs up
+    let mut rest = Vec::new();
+    let closed = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut rest)).await;
+    assert!(closed.is_ok(), "connection still open after the missed ping");
+}
+
+#[tokio::test]
+async fn batches_over_the_limit_are_rejected() {
+    let mut module = RpcModule::new(());
+    module.register_method("test.ping", |_, _, _| "pong").unwrap();
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_max_request_batch_size(2);
+    let _server = serve_websocket(module, config, None).await.unwrap();
+
+    let call = |id: u32| serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": "test.ping" });
+    let allowed = post(port, &serde_json::json!([call(1), call(2)])).await;
+    assert_eq!(allowed.as_array().map(Vec::len), Some(2));
+
+    // None of an oversized batch's calls run
+    let rejected = post(port, &serde_json::json!([call(1), call(2), call(3)])).await;
+    assert_eq!(rejected["error"]["code"], -32010);
+    assert!(rejected["id"].is_null());
+}
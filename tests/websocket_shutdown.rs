@@ -0,0 +1,112 @@
+//! Tests for draining WebSocket connections when the server stops.
+//!
+//! Run with: cargo test --test websocket_shutdown
+
+use std::time::Duration;
+
+use jsonrpsee::RpcModule;
+use plexus_transport::config::WebSocketConfig;
+use plexus_transport::websocket::serve_websocket;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+mod common;
+use common::free_port;
+
+const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+/// Open a WebSocket connection, returning once the upgrade is accepted
+async fn connect(port: u16) -> TcpStream {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream.write_all(UPGRADE.as_bytes()).await.unwrap();
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    assert!(head.starts_with(b"HTTP/1.1 101"));
+    stream
+}
+
+/// Send `message` as a client text frame (masked with a zero key)
+async fn send_text(stream: &mut TcpStream, message: &Value) {
+    let payload = message.to_string();
+    assert!(payload.len() < 126);
+    let mut frame = vec![0x81, 0x80 | payload.len() as u8, 0, 0, 0, 0];
+    frame.extend_from_slice(payload.as_bytes());
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Read the next server frame's opcode and payload
+async fn read_frame(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+    let opcode = stream.read_u8().await.unwrap() & 0x0f;
+    let len = match stream.read_u8().await.unwrap() {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    (opcode, payload)
+}
+
+/// Read a close frame and return its status code
+async fn read_close(stream: &mut TcpStream) -> u16 {
+    let (opcode, payload) = read_frame(stream).await;
+    assert_eq!(opcode, 0x8);
+    u16::from_be_bytes([payload[0], payload[1]])
+}
+
+fn sleeper() -> RpcModule<()> {
+    let mut module = RpcModule::new(());
+    module
+        .register_async_method("test.sleep", |params, _, _| async move {
+            let millis: u64 = params.one().unwrap();
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            "done"
+        })
+        .unwrap();
+    module
+}
+
+#[tokio::test]
+async fn in_flight_calls_are_answered_before_the_connection_closes() {
+    let port = free_port();
+    let server = serve_websocket(sleeper(), WebSocketConfig::new(port), None).await.unwrap();
+
+    let mut stream = connect(port).await;
+    send_text(&mut stream, &json!({ "jsonrpc": "2.0", "id": 1, "method": "test.sleep", "params": [300] })).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    server.stop().unwrap();
+
+    let (opcode, payload) = read_frame(&mut stream).await;
+    assert_eq!(opcode, 0x1);
+    assert_eq!(serde_json::from_slice::<Value>(&payload).unwrap()["result"], "done");
+    assert_eq!(read_close(&mut stream).await, 1001);
+
+    tokio::time::timeout(Duration::from_secs(5), server.stopped())
+        .await
+        .expect("server stopped once its connection closed");
+    assert!(TcpStream::connect(("127.0.0.1", port)).await.is_err());
+}
+
+#[tokio::test]
+async fn connections_still_busy_after_the_drain_timeout_are_closed() {
+    let port = free_port();
+    let config = WebSocketConfig::new(port).with_drain_timeout(Duration::from_millis(200));
+    let server = serve_websocket(sleeper(), config, None).await.unwrap();
+
+    let mut stream = connect(port).await;
+    send_text(&mut stream, &json!({ "jsonrpc": "2.0", "id": 1, "method": "test.sleep", "params": [60000] })).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    server.stop().unwrap();
+
+    assert_eq!(read_close(&mut stream).await, 1001);
+    let mut rest = Vec::new();
+    stream.read_to_end(&mut rest).await.unwrap();
+    assert!(rest.is_empty());
+
+    tokio::time::timeout(Duration::from_secs(5), server.stopped())
+        .await
+        .expect("server stopped without waiting for the call");
+}
@@ -0,0 +1,91 @@
+//! Tests for serving the WebSocket transport on a Unix socket.
+//!
+//! Run with: cargo test --test websocket_unix
+
+mod common;
+
+#[cfg(unix)]
+mod websocket_unix_tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use jsonrpsee::RpcModule;
+    use plexus_transport::config::WebSocketConfig;
+    use plexus_transport::request::RawRequestContext;
+    use plexus_transport::websocket::{serve_websocket, serve_websocket_with_unix_listener};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpStream, UnixListener, UnixStream};
+
+    use crate::common::free_port;
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("plexus-{}.sock", uuid::Uuid::new_v4().simple()))
+    }
+
+    fn module() -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("test.peer", |_, _, ext| {
+                ext.get::<RawRequestContext>().map(|raw| raw.peer.is_some())
+            })
+            .unwrap();
+        module
+    }
+
+    const REQUEST: &str = "POST / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: 45\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"test.peer\"}";
+
+    const UPGRADE: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+
+    /// POST a `test.peer` call and return its result
+    async fn post<S: AsyncReadExt + AsyncWriteExt + Unpin>(mut stream: S) -> serde_json::Value {
+        stream.write_all(REQUEST.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        let (_, body) = response.split_once("\r\n\r\n").expect("response body");
+        serde_json::from_str::<serde_json::Value>(body).unwrap()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn the_unix_socket_is_served_next_to_the_port() {
+        let port = free_port();
+        let path = socket_path();
+        // Left behind by a server that did not stop cleanly
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let config = WebSocketConfig::new(port).with_unix_socket(&path);
+        let server = serve_websocket(module(), config, None).await.unwrap();
+
+        // Unix socket clients have no peer address; TCP clients do
+        assert_eq!(post(UnixStream::connect(&path).await.unwrap()).await, false);
+        assert_eq!(post(TcpStream::connect(("127.0.0.1", port)).await.unwrap()).await, true);
+
+        let mut ws = UnixStream::connect(&path).await.unwrap();
+        ws.write_all(UPGRADE.as_bytes()).await.unwrap();
+        let mut buf = [0u8; 64];
+        let n = ws.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..n]).starts_with("HTTP/1.1 101"));
+
+        server.stop().unwrap();
+        server.stopped().await;
+        for _ in 0..50 {
+            if !path.exists() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!path.exists(), "socket file removed on stop");
+    }
+
+    #[tokio::test]
+    async fn a_bound_unix_listener_is_served_alone() {
+        let path = socket_path();
+        let listener = UnixListener::bind(&path).unwrap();
+        let _server = serve_websocket_with_unix_listener(listener, module(), WebSocketConfig::new(0), None)
+            .await
+            .unwrap();
+
+        assert_eq!(post(UnixStream::connect(&path).await.unwrap()).await, false);
+        std::fs::remove_file(&path).unwrap();
+    }
+}